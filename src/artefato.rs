@@ -0,0 +1,464 @@
+//! Cabeçalho, integridade e assinatura dos artefatos compilados (`.pbl` e `.pbc`).
+//!
+//! Todo artefato começa com um bloco de metadados terminado por uma linha em branco:
+//!
+//! ```text
+//! [PBC]
+//! formato=1
+//! compilador=0.1.4
+//! sha256=9f86d081884c7d65…
+//!
+//! <corpo>
+//! ```
+//!
+//! O `sha256=` cobre o arquivo inteiro menos a própria linha `sha256=`: as demais linhas do
+//! cabeçalho (`nome=`, `versao=`, …), a linha em branco e o corpo, com quebras de linha
+//! normalizadas para `\n`. É conferido ao carregar, junto com o formato: um artefato de formato
+//! mais novo que o suportado é recusado em vez de mal interpretado. Um cabeçalho `[PBL]`/`[PBC]`
+//! sem `formato=` é recusado; só arquivos sem cabeçalho nenhum, gerados antes dele, continuam
+//! aceitos sem verificação.
+//!
+//! A assinatura é opcional e fica ao lado do artefato, em `<arquivo>.sig`: Ed25519 sobre o
+//! arquivo inteiro. Se existir, precisa conferir. Com `PORDOSOL_CHAVES` apontando para uma lista
+//! de chaves públicas confiáveis, toda biblioteca carregada precisa estar assinada por uma delas.
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use sha2::{Digest, Sha256};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Versão do formato gerado por este compilador; os carregadores aceitam até ela.
+pub const FORMATO: u32 = 1;
+
+pub const VERSAO_COMPILADOR: &str = env!("CARGO_PKG_VERSION");
+
+/// Variável com o caminho do arquivo de chaves públicas confiáveis (uma por linha, em hex).
+pub const VARIAVEL_CHAVES: &str = "PORDOSOL_CHAVES";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tipo {
+    /// `.pbl`: manifesto e bytecode de uma biblioteca
+    Biblioteca,
+    /// `.pbc`: programa em bytecode
+    Bytecode,
+}
+
+impl Tipo {
+    fn marcador(self) -> &'static str {
+        match self {
+            Tipo::Biblioteca => "[PBL]",
+            Tipo::Bytecode => "[PBC]",
+        }
+    }
+}
+
+/// Metadados do cabeçalho. `formato` é 0 nos artefatos sem cabeçalho, anteriores a ele.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cabecalho {
+    pub formato: u32,
+    pub compilador: Option<String>,
+    pub sha256: Option<String>,
+    /// Demais `chave=valor`, na ordem do arquivo (`nome`, `versao`, …)
+    pub metadados: Vec<(String, String)>,
+}
+
+impl Cabecalho {
+    pub fn valor(&self, chave: &str) -> Option<&str> {
+        self.metadados
+            .iter()
+            .find(|(k, _)| k == chave)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Artefato lido e verificado.
+#[derive(Debug)]
+pub struct Artefato {
+    pub cabecalho: Cabecalho,
+    pub conteudo: String,
+    inicio_corpo: usize,
+    /// Chave pública (hex) que assinou o artefato, se havia `.sig`
+    pub assinado_por: Option<String>,
+}
+
+impl Artefato {
+    pub fn corpo(&self) -> &str {
+        &self.conteudo[self.inicio_corpo..]
+    }
+}
+
+/// Monta o artefato: cabeçalho com formato, versão do compilador, `metadados` e o hash de tudo.
+pub fn selar(tipo: Tipo, metadados: &[(&str, &str)], corpo: &str) -> String {
+    let mut saida = format!(
+        "{}\nformato={}\ncompilador={}\n",
+        tipo.marcador(),
+        FORMATO,
+        VERSAO_COMPILADOR
+    );
+    for (chave, valor) in metadados {
+        saida.push_str(&format!("{}={}\n", chave, valor));
+    }
+    let hash = sha256_hex(&format!("{}\n{}", saida, corpo));
+    saida.push_str(&format!("sha256={}\n\n{}", hash, corpo));
+    saida
+}
+
+/// Grava o artefato e apaga a assinatura de uma versão anterior, que não valeria mais.
+pub fn gravar(caminho: &Path, conteudo: &str) -> std::io::Result<()> {
+    fs::write(caminho, conteudo)?;
+    match fs::remove_file(caminho_assinatura(caminho)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/// Copia o artefato junto com a assinatura, se houver.
+pub fn copiar(origem: &Path, destino: &Path) -> std::io::Result<()> {
+    fs::copy(origem, destino)?;
+    let assinatura = caminho_assinatura(origem);
+    if assinatura.is_file() {
+        fs::copy(assinatura, caminho_assinatura(destino))?;
+    } else {
+        let _ = fs::remove_file(caminho_assinatura(destino));
+    }
+    Ok(())
+}
+
+/// Separa cabeçalho e corpo e confere formato e hash. O erro não inclui o caminho do arquivo.
+pub fn abrir(conteudo: &str) -> Result<(Cabecalho, usize), String> {
+    let legado = (
+        Cabecalho {
+            formato: 0,
+            compilador: None,
+            sha256: None,
+            metadados: Vec::new(),
+        },
+        0,
+    );
+    let primeira = conteudo.lines().next().unwrap_or("").trim();
+    if primeira != "[PBL]" && primeira != "[PBC]" {
+        return Ok(legado);
+    }
+
+    let mut cabecalho = legado.0;
+    let mut inicio_corpo = conteudo.len();
+    let mut posicao = 0;
+    // O que o hash cobre: o cabeçalho sem a linha sha256=, a linha em branco e o corpo
+    let mut coberto = String::new();
+    for linha in conteudo.split_inclusive('\n') {
+        posicao += linha.len();
+        let linha = linha.trim();
+        if linha.is_empty() {
+            inicio_corpo = posicao;
+            break;
+        }
+        if !linha.starts_with("sha256=") {
+            coberto.push_str(linha);
+            coberto.push('\n');
+        }
+        let Some((chave, valor)) = linha.split_once('=') else {
+            continue;
+        };
+        match chave {
+            "formato" => {
+                cabecalho.formato = valor
+                    .parse()
+                    .map_err(|_| format!("formato inválido no cabeçalho: '{}'", valor))?
+            }
+            "compilador" => cabecalho.compilador = Some(valor.to_string()),
+            "sha256" => cabecalho.sha256 = Some(valor.to_string()),
+            _ => cabecalho
+                .metadados
+                .push((chave.to_string(), valor.to_string())),
+        }
+    }
+
+    // Sem formato=, o hash não seria conferido: apagar a linha não pode desligar a verificação
+    if cabecalho.formato == 0 {
+        return Err(format!("cabeçalho {} sem formato=", primeira));
+    }
+    let gerado_por = cabecalho
+        .compilador
+        .as_deref()
+        .map(|v| format!(" (gerado pelo compilador {})", v))
+        .unwrap_or_default();
+    if cabecalho.formato > FORMATO {
+        return Err(format!(
+            "formato {}{} é mais novo que o suportado por esta versão ({}, formato {}); atualize o Por do Sol",
+            cabecalho.formato, gerado_por, VERSAO_COMPILADOR, FORMATO
+        ));
+    }
+    let esperado = cabecalho
+        .sha256
+        .as_deref()
+        .filter(|h| !h.is_empty())
+        .ok_or_else(|| "cabeçalho sem sha256=".to_string())?;
+    coberto.push('\n');
+    coberto.push_str(&conteudo[inicio_corpo..]);
+    let calculado = sha256_hex(&coberto);
+    if !esperado.eq_ignore_ascii_case(&calculado) {
+        return Err(format!(
+            "conteúdo não confere com o hash do cabeçalho{}: arquivo corrompido ou alterado (esperado {}, calculado {})",
+            gerado_por, esperado, calculado
+        ));
+    }
+    Ok((cabecalho, inicio_corpo))
+}
+
+/// Lê e verifica um artefato: formato, hash e, se houver `.sig`, a assinatura. Bibliotecas
+/// precisam ainda ser assinadas por uma chave de `PORDOSOL_CHAVES`, quando definida.
+pub fn ler(caminho: &Path) -> Result<Artefato, String> {
+    let eh_biblioteca = caminho.extension().is_some_and(|e| e == "pbl");
+    let confiaveis = if eh_biblioteca { chaves_confiaveis()? } else { None };
+    verificar(caminho, confiaveis.as_deref())
+}
+
+/// Como [`ler`], exigindo assinatura de uma das chaves `confiaveis`, se dadas.
+pub fn verificar(caminho: &Path, confiaveis: Option<&[String]>) -> Result<Artefato, String> {
+    let conteudo = fs::read_to_string(caminho)
+        .map_err(|e| format!("não foi possível ler '{}': {}", caminho.display(), e))?;
+    let (cabecalho, inicio_corpo) =
+        abrir(&conteudo).map_err(|e| format!("'{}': {}", caminho.display(), e))?;
+    let assinado_por = verificar_assinatura(caminho, &conteudo, confiaveis)
+        .map_err(|e| format!("'{}': {}", caminho.display(), e))?;
+    Ok(Artefato {
+        cabecalho,
+        conteudo,
+        inicio_corpo,
+        assinado_por,
+    })
+}
+
+pub fn sha256_hex(texto: &str) -> String {
+    hex(&Sha256::digest(normalizar(texto).as_bytes()))
+}
+
+/// Quebras de linha `\r\n` (checkout no Windows) não alteram o hash nem a assinatura.
+fn normalizar(texto: &str) -> std::borrow::Cow<'_, str> {
+    if texto.contains('\r') {
+        texto.replace("\r\n", "\n").into()
+    } else {
+        texto.into()
+    }
+}
+
+// ============================================================================
+// Assinatura destacada
+// ============================================================================
+
+pub fn caminho_assinatura(artefato: &Path) -> PathBuf {
+    let mut nome = artefato.as_os_str().to_owned();
+    nome.push(".sig");
+    PathBuf::from(nome)
+}
+
+/// Par de chaves Ed25519 guardado em texto (`privada=` e `publica=`, em hex).
+pub struct Chave {
+    assinatura: SigningKey,
+}
+
+impl Chave {
+    pub fn gerar() -> Result<Self, String> {
+        let mut semente = [0u8; 32];
+        getrandom::getrandom(&mut semente)
+            .map_err(|e| format!("sem fonte de aleatoriedade para gerar a chave: {}", e))?;
+        Ok(Self {
+            assinatura: SigningKey::from_bytes(&semente),
+        })
+    }
+
+    pub fn ler(caminho: &Path) -> Result<Self, String> {
+        let texto = fs::read_to_string(caminho)
+            .map_err(|e| format!("não foi possível ler a chave '{}': {}", caminho.display(), e))?;
+        let privada = texto
+            .lines()
+            .find_map(|l| l.trim().strip_prefix("privada="))
+            .ok_or_else(|| format!("'{}' não contém uma chave privada", caminho.display()))?;
+        let semente: [u8; 32] = de_hex(privada)
+            .and_then(|b| b.try_into().ok())
+            .ok_or_else(|| format!("chave privada inválida em '{}'", caminho.display()))?;
+        Ok(Self {
+            assinatura: SigningKey::from_bytes(&semente),
+        })
+    }
+
+    pub fn publica(&self) -> String {
+        hex(self.assinatura.verifying_key().as_bytes())
+    }
+
+    pub fn para_texto(&self) -> String {
+        format!(
+            "# Chave de assinatura Por do Sol (Ed25519). Não compartilhe a linha privada=.\nprivada={}\npublica={}\n",
+            hex(self.assinatura.as_bytes()),
+            self.publica()
+        )
+    }
+
+    /// Confere o hash do artefato, assina-o e grava `<artefato>.sig` (substituindo o anterior).
+    pub fn assinar(&self, caminho: &Path) -> Result<PathBuf, String> {
+        let conteudo = fs::read_to_string(caminho)
+            .map_err(|e| format!("não foi possível ler '{}': {}", caminho.display(), e))?;
+        match abrir(&conteudo) {
+            Ok((cabecalho, _)) if cabecalho.formato == 0 => {
+                return Err(format!(
+                    "'{}' não tem cabeçalho com hash; gere-o de novo com este compilador",
+                    caminho.display()
+                ))
+            }
+            Ok(_) => {}
+            Err(e) => return Err(format!("'{}': {}", caminho.display(), e)),
+        }
+        let assinatura = self.assinatura.sign(normalizar(&conteudo).as_bytes());
+        let destino = caminho_assinatura(caminho);
+        fs::write(
+            &destino,
+            format!(
+                "[ASSINATURA]\nchave={}\nassinatura={}\n",
+                self.publica(),
+                hex(&assinatura.to_bytes())
+            ),
+        )
+        .map_err(|e| format!("Erro ao gravar '{}': {}", destino.display(), e))?;
+        Ok(destino)
+    }
+}
+
+/// Chaves públicas de `PORDOSOL_CHAVES`, se a variável estiver definida.
+pub fn chaves_confiaveis() -> Result<Option<Vec<String>>, String> {
+    match env::var_os(VARIAVEL_CHAVES) {
+        Some(caminho) if !caminho.is_empty() => ler_chaves(Path::new(&caminho)).map(Some),
+        _ => Ok(None),
+    }
+}
+
+/// Lista de chaves públicas: uma por linha (ou `publica=<hex>`), `#` inicia comentário.
+pub fn ler_chaves(caminho: &Path) -> Result<Vec<String>, String> {
+    let texto = fs::read_to_string(caminho).map_err(|e| {
+        format!("não foi possível ler as chaves confiáveis '{}': {}", caminho.display(), e)
+    })?;
+    Ok(texto
+        .lines()
+        .map(|l| l.split('#').next().unwrap_or("").trim())
+        .filter(|l| !l.is_empty() && !l.starts_with("privada="))
+        .map(|l| l.strip_prefix("publica=").unwrap_or(l).to_lowercase())
+        .collect())
+}
+
+/// Confere `<artefato>.sig`. Devolve a chave que assinou; sem `.sig`, `None` — a menos que
+/// haja chaves confiáveis, caso em que a assinatura é obrigatória.
+pub fn verificar_assinatura(
+    caminho: &Path,
+    conteudo: &str,
+    confiaveis: Option<&[String]>,
+) -> Result<Option<String>, String> {
+    let arquivo_sig = caminho_assinatura(caminho);
+    let texto = match fs::read_to_string(&arquivo_sig) {
+        Ok(texto) => texto,
+        Err(_) if confiaveis.is_none() => return Ok(None),
+        Err(_) => {
+            return Err(format!(
+                "sem assinatura ({} não encontrado), exigida pelas chaves confiáveis",
+                arquivo_sig.display()
+            ))
+        }
+    };
+    let campo = |nome: &str| {
+        texto
+            .lines()
+            .find_map(|l| l.trim().strip_prefix(nome).and_then(|r| r.strip_prefix('=')))
+            .map(str::to_lowercase)
+    };
+    let invalida = || format!("assinatura malformada em {}", arquivo_sig.display());
+    let chave_hex = campo("chave").ok_or_else(invalida)?;
+    let chave = de_hex(&chave_hex)
+        .and_then(|b| <[u8; 32]>::try_from(b).ok())
+        .and_then(|b| VerifyingKey::from_bytes(&b).ok())
+        .ok_or_else(invalida)?;
+    let assinatura = campo("assinatura")
+        .and_then(|s| de_hex(&s))
+        .and_then(|b| <[u8; 64]>::try_from(b).ok())
+        .map(|b| Signature::from_bytes(&b))
+        .ok_or_else(invalida)?;
+
+    if chave
+        .verify(normalizar(conteudo).as_bytes(), &assinatura)
+        .is_err()
+    {
+        return Err(format!(
+            "a assinatura em {} não confere com o arquivo (alterado depois de assinado?)",
+            arquivo_sig.display()
+        ));
+    }
+    if let Some(confiaveis) = confiaveis {
+        if !confiaveis.contains(&chave_hex) {
+            return Err(format!(
+                "assinado pela chave {}, que não está entre as confiáveis",
+                chave_hex
+            ));
+        }
+    }
+    Ok(Some(chave_hex))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn de_hex(texto: &str) -> Option<Vec<u8>> {
+    let texto = texto.trim();
+    if !texto.len().is_multiple_of(2) || !texto.is_ascii() {
+        return None;
+    }
+    (0..texto.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&texto[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selar_e_abrir_conferem_o_hash() {
+        let selado = selar(Tipo::Bytecode, &[("nome", "app")], "LOAD_CONST_INT 1\nPRINT\n");
+        let (cabecalho, inicio) = abrir(&selado).unwrap();
+        assert_eq!(cabecalho.formato, FORMATO);
+        assert_eq!(cabecalho.valor("nome"), Some("app"));
+        assert_eq!(&selado[inicio..], "LOAD_CONST_INT 1\nPRINT\n");
+
+        // Quebras de linha do Windows não invalidam o artefato
+        assert!(abrir(&selado.replace('\n', "\r\n")).is_ok());
+
+        let alterado = selado.replace("INT 1", "INT 2");
+        assert!(abrir(&alterado).unwrap_err().contains("não confere com o hash"));
+        let renomeado = selado.replace("nome=app", "nome=outro");
+        assert!(abrir(&renomeado).unwrap_err().contains("não confere com o hash"));
+
+        let futuro = selado.replace("formato=1", "formato=99");
+        assert!(abrir(&futuro).unwrap_err().contains("formato 99"));
+    }
+
+    #[test]
+    fn artefatos_sem_cabecalho_sao_legados() {
+        let (cabecalho, inicio) = abrir("LOAD_CONST_INT 1\nPRINT").unwrap();
+        assert_eq!((cabecalho.formato, inicio), (0, 0));
+    }
+
+    #[test]
+    fn cabecalho_sem_formato_e_recusado() {
+        assert!(abrir("[PBL]\nnome=geo\n\n[MANIFESTO]\n")
+            .unwrap_err()
+            .contains("sem formato="));
+        let selado = selar(Tipo::Bytecode, &[], "PRINT\n");
+        let sem_formato = selado.replace("formato=1\n", "");
+        assert!(abrir(&sem_formato).unwrap_err().contains("[PBC] sem formato="));
+    }
+
+    #[test]
+    fn hex_ida_e_volta() {
+        assert_eq!(de_hex(&hex(&[0, 15, 255])), Some(vec![0, 15, 255]));
+        assert_eq!(de_hex("abc"), None);
+    }
+}
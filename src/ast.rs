@@ -341,6 +341,48 @@ pub enum Comando {
     CriarObjeto(String, String, Vec<Expressao>),
     ChamarMetodo(Box<Expressao>, String, Vec<Expressao>),
     AcessarCampo(String, String),
+    // Marcador de posição no fonte; emitido pelo parser apenas quando há tabela de linhas
+    Posicao(Posicao),
+}
+
+/* ========================================================================== */
+/* POSIÇÕES NO FONTE                                                          */
+/* ========================================================================== */
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Posicao {
+    pub arquivo: String,
+    pub linha: usize,
+    pub coluna: usize,
+}
+
+/// Tabela de linhas de um arquivo fonte: converte offsets de bytes em linha/coluna (base 1).
+#[derive(Debug, Clone)]
+pub struct MapaLinhas {
+    pub arquivo: String,
+    inicios: Vec<usize>,
+}
+
+impl MapaLinhas {
+    pub fn novo(arquivo: impl Into<String>, fonte: &str) -> Self {
+        let mut inicios = vec![0];
+        inicios.extend(fonte.match_indices('\n').map(|(i, _)| i + 1));
+        Self {
+            arquivo: arquivo.into(),
+            inicios,
+        }
+    }
+
+    pub fn posicao(&self, offset: usize) -> Posicao {
+        let idx = match self.inicios.binary_search(&offset) {
+            Ok(i) => i,
+            Err(i) => i - 1,
+        };
+        Posicao {
+            arquivo: self.arquivo.clone(),
+            linha: idx + 1,
+            coluna: offset - self.inicios[idx] + 1,
+        }
+    }
 }
 
 /* ========================================================================== */
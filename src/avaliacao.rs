@@ -0,0 +1,374 @@
+// src/avaliacao.rs
+
+//! Modo de avaliação automática (`interpretador <programa.pbc> --avaliar casos/`).
+//!
+//! Cada subdiretório de `casos/` que contém um `saida.txt` é um caso de teste. O programa
+//! é executado uma vez por caso, em um processo separado, com `entrada.txt` (se existir)
+//! ligado à entrada padrão; a saída padrão capturada é comparada com `saida.txt`.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+pub const ARQUIVO_ENTRADA: &str = "entrada.txt";
+pub const ARQUIVO_SAIDA: &str = "saida.txt";
+pub const TEMPO_LIMITE_PADRAO: Duration = Duration::from_secs(5);
+
+/// Como a saída obtida é comparada com a esperada.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModoComparacao {
+    /// Byte a byte (apenas `\r\n` é normalizado para `\n`).
+    Exata,
+    /// Ignora espaços no fim de cada linha e linhas vazias no fim da saída (padrão).
+    Linhas,
+    /// Compara apenas a sequência de palavras: qualquer espaçamento é equivalente.
+    Espacos,
+}
+
+impl ModoComparacao {
+    pub fn de_nome(nome: &str) -> Option<Self> {
+        match nome.to_ascii_lowercase().as_str() {
+            "exata" | "exato" => Some(Self::Exata),
+            "linhas" => Some(Self::Linhas),
+            "espacos" | "espaços" => Some(Self::Espacos),
+            _ => None,
+        }
+    }
+
+    fn normalizar(self, texto: &str) -> Vec<String> {
+        let texto = texto.replace("\r\n", "\n");
+        match self {
+            Self::Exata => texto.split('\n').map(String::from).collect(),
+            Self::Linhas => {
+                let mut linhas: Vec<String> =
+                    texto.lines().map(|l| l.trim_end().to_string()).collect();
+                while linhas.last().is_some_and(|l| l.is_empty()) {
+                    linhas.pop();
+                }
+                linhas
+            }
+            Self::Espacos => texto.split_whitespace().map(String::from).collect(),
+        }
+    }
+
+    /// `None` quando as saídas são equivalentes; caso contrário, descreve a primeira diferença.
+    pub fn comparar(self, esperado: &str, obtido: &str) -> Option<String> {
+        let (esp, obt) = (self.normalizar(esperado), self.normalizar(obtido));
+        if esp == obt {
+            return None;
+        }
+        let unidade = if self == Self::Espacos {
+            "palavra"
+        } else {
+            "linha"
+        };
+        let i = esp
+            .iter()
+            .zip(&obt)
+            .position(|(e, o)| e != o)
+            .unwrap_or(esp.len().min(obt.len()));
+        let mostrar = |v: Option<&String>| match v {
+            Some(s) => format!("{:?}", s),
+            None => "(fim da saída)".to_string(),
+        };
+        Some(format!(
+            "{} {}: esperado {}, obtido {}",
+            unidade,
+            i + 1,
+            mostrar(esp.get(i)),
+            mostrar(obt.get(i))
+        ))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Caso {
+    pub nome: String,
+    pub entrada: Option<PathBuf>,
+    pub saida: PathBuf,
+}
+
+/// Lista os casos de `diretorio` em ordem alfabética (subdiretórios sem `saida.txt` são ignorados).
+pub fn descobrir_casos(diretorio: &Path) -> io::Result<Vec<Caso>> {
+    let mut casos = Vec::new();
+    for entrada in fs::read_dir(diretorio)? {
+        let caminho = entrada?.path();
+        let saida = caminho.join(ARQUIVO_SAIDA);
+        if !caminho.is_dir() || !saida.is_file() {
+            continue;
+        }
+        let arquivo_entrada = caminho.join(ARQUIVO_ENTRADA);
+        casos.push(Caso {
+            nome: caminho
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            entrada: arquivo_entrada.is_file().then_some(arquivo_entrada),
+            saida,
+        });
+    }
+    casos.sort_by(|a, b| a.nome.cmp(&b.nome));
+    Ok(casos)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusCaso {
+    Aprovado,
+    SaidaIncorreta,
+    ErroExecucao,
+    TempoEsgotado,
+}
+
+impl StatusCaso {
+    pub fn descricao(self) -> &'static str {
+        match self {
+            Self::Aprovado => "ok",
+            Self::SaidaIncorreta => "SAÍDA INCORRETA",
+            Self::ErroExecucao => "ERRO DE EXECUÇÃO",
+            Self::TempoEsgotado => "TEMPO ESGOTADO",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ResultadoCaso {
+    pub nome: String,
+    pub status: StatusCaso,
+    pub duracao_ms: u128,
+    /// Primeira diferença encontrada, ou a mensagem de erro do programa.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detalhe: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RelatorioAvaliacao {
+    pub programa: String,
+    pub comparacao: ModoComparacao,
+    pub tempo_limite_ms: u128,
+    pub aprovados: usize,
+    pub total: usize,
+    /// Percentual de casos aprovados (0 a 100).
+    pub nota: f64,
+    pub casos: Vec<ResultadoCaso>,
+}
+
+impl RelatorioAvaliacao {
+    pub fn new(
+        programa: impl Into<String>,
+        comparacao: ModoComparacao,
+        tempo_limite: Duration,
+    ) -> Self {
+        Self {
+            programa: programa.into(),
+            comparacao,
+            tempo_limite_ms: tempo_limite.as_millis(),
+            aprovados: 0,
+            total: 0,
+            nota: 0.0,
+            casos: Vec::new(),
+        }
+    }
+
+    pub fn adicionar(&mut self, resultado: ResultadoCaso) {
+        if resultado.status == StatusCaso::Aprovado {
+            self.aprovados += 1;
+        }
+        self.total += 1;
+        self.nota = self.aprovados as f64 * 100.0 / self.total as f64;
+        self.casos.push(resultado);
+    }
+
+    pub fn formatar_resultado(r: &ResultadoCaso) -> String {
+        let mut linha = format!(
+            "caso {} ... {} ({} ms)",
+            r.nome,
+            r.status.descricao(),
+            r.duracao_ms
+        );
+        if let Some(detalhe) = &r.detalhe {
+            for l in detalhe.lines() {
+                let _ = write!(linha, "\n    {}", l);
+            }
+        }
+        linha
+    }
+
+    pub fn resumo(&self) -> String {
+        format!(
+            "\nnota: {}/{} caso(s) aprovado(s) ({:.1}%)\n",
+            self.aprovados, self.total, self.nota
+        )
+    }
+
+    pub fn json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+}
+
+/// Executa `comando` uma vez por caso e compara a saída padrão com o esperado, usando o
+/// modo de comparação e o tempo limite do relatório (programas que excedem o limite são encerrados).
+pub fn avaliar(
+    comando: impl Fn() -> Command,
+    casos: &[Caso],
+    relatorio: &mut RelatorioAvaliacao,
+    mut ao_concluir: impl FnMut(&ResultadoCaso),
+) -> io::Result<()> {
+    let comparacao = relatorio.comparacao;
+    let tempo_limite = Duration::from_millis(relatorio.tempo_limite_ms as u64);
+    for caso in casos {
+        let esperado = fs::read_to_string(&caso.saida)?;
+        let stdin = match &caso.entrada {
+            Some(caminho) => Stdio::from(fs::File::open(caminho)?),
+            None => Stdio::null(),
+        };
+        let inicio = Instant::now();
+        let execucao = executar_com_limite(comando().stdin(stdin), tempo_limite)?;
+        let duracao_ms = inicio.elapsed().as_millis();
+
+        let (status, detalhe) = match execucao {
+            Execucao::TempoEsgotado => (
+                StatusCaso::TempoEsgotado,
+                Some(format!("excedeu {} ms", tempo_limite.as_millis())),
+            ),
+            Execucao::Concluida {
+                sucesso: false,
+                stderr,
+                ..
+            } => {
+                let msg = stderr.trim();
+                (
+                    StatusCaso::ErroExecucao,
+                    Some(if msg.is_empty() {
+                        "programa terminou com erro".to_string()
+                    } else {
+                        msg.to_string()
+                    }),
+                )
+            }
+            Execucao::Concluida { stdout, .. } => match comparacao.comparar(&esperado, &stdout) {
+                None => (StatusCaso::Aprovado, None),
+                Some(diferenca) => (StatusCaso::SaidaIncorreta, Some(diferenca)),
+            },
+        };
+        let resultado = ResultadoCaso {
+            nome: caso.nome.clone(),
+            status,
+            duracao_ms,
+            detalhe,
+        };
+        ao_concluir(&resultado);
+        relatorio.adicionar(resultado);
+    }
+    Ok(())
+}
+
+enum Execucao {
+    Concluida {
+        sucesso: bool,
+        stdout: String,
+        stderr: String,
+    },
+    TempoEsgotado,
+}
+
+fn executar_com_limite(comando: &mut Command, limite: Duration) -> io::Result<Execucao> {
+    let mut filho = comando
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Os pipes são lidos em threads para o programa não travar com o buffer cheio
+    let ler = |fluxo: Option<Box<dyn Read + Send>>| {
+        thread::spawn(move || {
+            let mut texto = String::new();
+            if let Some(mut f) = fluxo {
+                let mut bytes = Vec::new();
+                let _ = f.read_to_end(&mut bytes);
+                texto = String::from_utf8_lossy(&bytes).into_owned();
+            }
+            texto
+        })
+    };
+    let leitor_stdout = ler(filho.stdout.take().map(|s| Box::new(s) as _));
+    let leitor_stderr = ler(filho.stderr.take().map(|s| Box::new(s) as _));
+
+    let inicio = Instant::now();
+    let status = loop {
+        if let Some(status) = filho.try_wait()? {
+            break Some(status);
+        }
+        if inicio.elapsed() >= limite {
+            let _ = filho.kill();
+            let _ = filho.wait();
+            break None;
+        }
+        thread::sleep(Duration::from_millis(5));
+    };
+
+    let stdout = leitor_stdout.join().unwrap_or_default();
+    let stderr = leitor_stderr.join().unwrap_or_default();
+    Ok(match status {
+        Some(status) => Execucao::Concluida {
+            sucesso: status.success(),
+            stdout,
+            stderr,
+        },
+        None => Execucao::TempoEsgotado,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comparacao_tolerante_a_espacos() {
+        let esperado = "Olá, Ana\nfim\n";
+        assert_eq!(
+            ModoComparacao::Linhas.comparar(esperado, "Olá, Ana  \r\nfim\n\n"),
+            None
+        );
+        assert!(ModoComparacao::Exata
+            .comparar(esperado, "Olá, Ana  \nfim\n")
+            .is_some());
+        assert_eq!(
+            ModoComparacao::Espacos.comparar(esperado, "Olá,   Ana fim"),
+            None
+        );
+        assert_eq!(
+            ModoComparacao::Linhas
+                .comparar(esperado, "Olá, Ana\n")
+                .as_deref(),
+            Some("linha 2: esperado \"fim\", obtido (fim da saída)")
+        );
+    }
+
+    #[test]
+    fn relatorio_calcula_nota() {
+        let mut rel = RelatorioAvaliacao::new("p.pbc", ModoComparacao::Linhas, TEMPO_LIMITE_PADRAO);
+        for (nome, status) in [
+            ("a", StatusCaso::Aprovado),
+            ("b", StatusCaso::TempoEsgotado),
+        ] {
+            rel.adicionar(ResultadoCaso {
+                nome: nome.into(),
+                status,
+                duracao_ms: 1,
+                detalhe: None,
+            });
+        }
+        assert_eq!((rel.aprovados, rel.total), (1, 2));
+        assert!(rel
+            .resumo()
+            .contains("nota: 1/2 caso(s) aprovado(s) (50.0%)"));
+        assert!(rel.json().contains(r#""status": "tempo_esgotado""#));
+    }
+}
@@ -3,7 +3,7 @@ use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fmt;
 use std::fs;
-use std::io::{self, Read, Write};
+use std::io::{self, Write};
 use std::path::PathBuf;
 use std::rc::Rc;
 use std::sync::{Arc, Mutex};
@@ -12,9 +12,13 @@ use std::time::Duration;
 
 use rust_decimal::Decimal;
 
-// Disponibiliza o JIT da crate de biblioteca quando a feature estiver ativa
-#[cfg(feature = "jit")]
-use compilador_portugues::jit::CraneliftJit;
+// JIT em níveis da crate de biblioteca (sem a feature `jit`, JitNivelado::new sempre falha)
+use compilador_portugues::jit::{self, FuncaoBytecode, JitNivelado, ValorJit};
+use compilador_portugues::artefato;
+use compilador_portugues::library_loader;
+use compilador_portugues::avaliacao::{self, ModoComparacao, RelatorioAvaliacao};
+use compilador_portugues::teste_de_mesa::{FormatoTabela, TesteDeMesa};
+use compilador_portugues::testes::{self, RelatorioTestes, ResultadoTeste};
 
 //cargo run --bin compilador -- teste.pr --target=bytecode
 //cargo run --bin interpretador -- teste.pbc
@@ -156,11 +160,15 @@ struct VM {
     // Debugging support
     debug: Option<Rc<RefCell<DebugState>>>,
     code_id: String,
+    // Teste de mesa (rastreamento de variáveis por linha), compartilhado entre VMs
+    teste_de_mesa: Option<Rc<RefCell<TesteDeMesa>>>,
     // Gerenciador de tasks
     task_counter: Arc<Mutex<usize>>,
     tasks: Arc<Mutex<HashMap<usize, Task>>>,
     // Call stack para debugging
     call_stack: Vec<StackFrame>,
+    // JIT em níveis (--jit), compartilhado entre VMs
+    jit: Option<Rc<RefCell<JitNivelado>>>,
 }
 
 // Estrutura para representar uma task assíncrona.
@@ -232,10 +240,12 @@ impl VM {
             loaded_modules: self.loaded_modules.clone(),
             base_dir: self.base_dir.clone(),
             debug: self.debug.clone(),
+            teste_de_mesa: self.teste_de_mesa.clone(),
             code_id: format!("func:{}", func.nome),
             task_counter: self.task_counter.clone(),
             tasks: self.tasks.clone(),
             call_stack: self.call_stack.clone(),
+            jit: self.jit.clone(),
         };
 
         // Mapear parâmetros
@@ -281,10 +291,46 @@ impl VM {
             base_dir,
             debug: None,
             code_id: "global".to_string(),
+            teste_de_mesa: None,
             // Inicializa o gerenciador de tasks compartilhado
             task_counter: Arc::new(Mutex::new(0)),
             tasks: Arc::new(Mutex::new(HashMap::new())),
             call_stack: Vec::new(),
+            jit: None,
+        }
+    }
+
+    /// Conta a chamada no JIT e, se a função já tiver código nativo para estes tipos de
+    /// argumento, executa-o. `Ok(None)` significa que a chamada deve ser interpretada.
+    fn chamar_jit(&self, nome: &str, args: &[Valor]) -> Result<Option<Valor>, String> {
+        let Some(jit) = &self.jit else {
+            return Ok(None);
+        };
+        let mut valores = Vec::with_capacity(args.len());
+        for arg in args {
+            valores.push(match arg {
+                Valor::Inteiro(n) => ValorJit::Inteiro(*n),
+                Valor::Duplo(d) => ValorJit::Duplo(*d),
+                Valor::Booleano(b) => ValorJit::Booleano(*b),
+                _ => return Ok(None),
+            });
+        }
+        let buscar = |nome: &str| {
+            self.functions.get(nome).map(|f| FuncaoBytecode {
+                parametros: &f.parametros,
+                corpo: &f.corpo,
+            })
+        };
+        let resultado = jit.borrow_mut().chamar(nome, &valores, &buscar);
+        match resultado {
+            None => Ok(None),
+            Some(Err(e)) => Err(e),
+            Some(Ok(valor)) => Ok(Some(match valor {
+                Some(ValorJit::Inteiro(n)) => Valor::Inteiro(n),
+                Some(ValorJit::Duplo(d)) => Valor::Duplo(d),
+                Some(ValorJit::Booleano(b)) => Valor::Booleano(b),
+                None => Valor::Nulo,
+            })),
         }
     }
 
@@ -380,10 +426,12 @@ impl VM {
                 loaded_modules: self.loaded_modules.clone(),
                 base_dir: self.base_dir.clone(),
                 debug: self.debug.clone(),
+                teste_de_mesa: self.teste_de_mesa.clone(),
                 code_id: format!("ctor:{}", nome_classe),
                 task_counter: self.task_counter.clone(),
                 tasks: self.tasks.clone(),
                 call_stack: Vec::new(),
+                jit: self.jit.clone(),
             };
 
             // Adiciona 'este' e os argumentos ao escopo do construtor.
@@ -592,10 +640,12 @@ impl VM {
                     loaded_modules: self.loaded_modules.clone(),
                     base_dir: self.base_dir.clone(),
                     debug: self.debug.clone(),
+                    teste_de_mesa: self.teste_de_mesa.clone(),
                     code_id: format!("method:{}::{}", nome_classe, nome_metodo),
                     task_counter: self.task_counter.clone(),
                     tasks: self.tasks.clone(),
                     call_stack: Vec::new(),
+                    jit: self.jit.clone(),
                 };
 
                 Box::pin(vm_metodo.run()).await?;
@@ -680,10 +730,12 @@ impl VM {
                     loaded_modules: self.loaded_modules.clone(),
                     base_dir: self.base_dir.clone(),
                     debug: self.debug.clone(),
+                    teste_de_mesa: self.teste_de_mesa.clone(),
                     code_id: format!("static:{}::{}", nome_classe, nome_metodo),
                     task_counter: self.task_counter.clone(),
                     tasks: self.tasks.clone(),
                     call_stack: Vec::new(),
+                    jit: self.jit.clone(),
                 };
 
                 Box::pin(vm_metodo.run()).await?;
@@ -742,6 +794,33 @@ impl VM {
         Ok((func_info, i - start_index))
     }
 
+    /// Carrega uma única vez as classes e funções da seção `[BYTECODE]` de um `.pbl`, resolvido
+    /// a partir do diretório do programa. Definições do programa com o mesmo nome prevalecem.
+    fn carregar_biblioteca(&mut self, arquivo: &str) -> Result<(), String> {
+        let caminho = self.base_dir.join(arquivo);
+        let chave = caminho
+            .canonicalize()
+            .unwrap_or_else(|_| caminho.clone())
+            .display()
+            .to_string();
+        if !self.loaded_modules.insert(chave) {
+            return Ok(());
+        }
+        let bytecode = library_loader::ler_bytecode_pbl(&caminho)
+            .map_err(|e| format!("Biblioteca não carregada: {}", e))?;
+        let mut vm_biblioteca = VM::new(bytecode, self.base_dir.clone());
+        vm_biblioteca.loaded_modules = std::mem::take(&mut self.loaded_modules);
+        vm_biblioteca.carregar_definicoes()?;
+        self.loaded_modules = vm_biblioteca.loaded_modules;
+        for (nome, classe) in vm_biblioteca.classes {
+            self.classes.entry(nome).or_insert(classe);
+        }
+        for (nome, funcao) in vm_biblioteca.functions {
+            self.functions.entry(nome).or_insert(funcao);
+        }
+        Ok(())
+    }
+
     fn carregar_definicoes(&mut self) -> Result<(), String> {
         fn limpa_parametro(raw: &str) -> String {
             let mut clean = raw.split(':').last().unwrap_or(raw);
@@ -756,6 +835,11 @@ impl VM {
             let op = partes.get(0).unwrap_or(&"");
 
             match *op {
+                "LOAD_LIBRARY" => {
+                    let arquivo = instrucao["LOAD_LIBRARY".len()..].trim();
+                    self.carregar_biblioteca(arquivo)?;
+                    i += 1;
+                }
                 "DEFINE_STATIC_CLASS" => {
                     let nome_classe = partes
                         .get(1)
@@ -985,8 +1069,39 @@ impl VM {
         Ok(())
     }
 
-    // O laço principal de execução da VM.
+    // Executa o frame atual; com teste de mesa ativo, registra a última linha ao sair.
     async fn run(&mut self) -> Result<(), String> {
+        if let Some(t) = &self.teste_de_mesa {
+            t.borrow_mut().entrar_frame();
+        }
+        let resultado = self.executar_instrucoes().await;
+        if let Some(t) = self.teste_de_mesa.clone() {
+            let valores = self.valores_teste_de_mesa();
+            t.borrow_mut().sair_frame(valores);
+        }
+        resultado
+    }
+
+    // Variáveis visíveis no frame, formatadas para a tabela do teste de mesa.
+    fn valores_teste_de_mesa(&self) -> Vec<(String, String)> {
+        let mut valores: Vec<(String, String)> = self
+            .variaveis
+            .iter()
+            .filter(|(nome, _)| nome.as_str() != "este")
+            .map(|(nome, valor)| {
+                let texto = match valor {
+                    Valor::Texto(s) => format!("\"{}\"", s),
+                    outro => outro.to_string(),
+                };
+                (nome.clone(), texto)
+            })
+            .collect();
+        valores.sort();
+        valores
+    }
+
+    // O laço principal de execução da VM.
+    async fn executar_instrucoes(&mut self) -> Result<(), String> {
         while self.ip < self.bytecode.len() {
             let instrucao_str = self.bytecode[self.ip].clone();
             // Divide a instrução em partes (ex: "LOAD_CONST_INT", "42")
@@ -1777,6 +1892,7 @@ impl VM {
                         let mut temp_vm =
                             VM::new(vec![default_expr_bytecode_str], self.base_dir.clone());
                         temp_vm.debug = self.debug.clone();
+                        temp_vm.teste_de_mesa = self.teste_de_mesa.clone();
                         temp_vm.code_id = format!("expr-default:{}", nome_var);
                         Box::pin(temp_vm.run()).await?;
                         let valor = temp_vm.pilha.pop().unwrap_or(Valor::Nulo);
@@ -1802,7 +1918,13 @@ impl VM {
                         .get("este")
                         .cloned()
                         .ok_or("CALL_BASE_CONSTRUCTOR requer 'este' no escopo")?;
-                    if let Valor::Objeto { nome_classe, .. } = &este_obj {
+                    // Bytecode antigo não traz a classe do construtor; usa a classe de `este`
+                    let classe_atual = match (partes.get(2), &este_obj) {
+                        (Some(classe), _) => Some(classe.to_string()),
+                        (None, Valor::Objeto { nome_classe, .. }) => Some(nome_classe.clone()),
+                        _ => None,
+                    };
+                    if let Some(nome_classe) = &classe_atual {
                         if let Some(classe_info) = self.classes.get(nome_classe).cloned() {
                             if let Some(parent_name) = &classe_info.nome_classe_pai {
                                 if let Some(parent_info) = self.classes.get(parent_name).cloned() {
@@ -1819,10 +1941,12 @@ impl VM {
                                             loaded_modules: self.loaded_modules.clone(),
                                             base_dir: self.base_dir.clone(),
                                             debug: self.debug.clone(),
+                                            teste_de_mesa: self.teste_de_mesa.clone(),
                                             code_id: format!("base_ctor:{}", parent_name),
                                             task_counter: self.task_counter.clone(),
                                             tasks: self.tasks.clone(),
                                             call_stack: Vec::new(),
+                                            jit: self.jit.clone(),
                                         };
                                         constructor_vm
                                             .variaveis
@@ -1898,6 +2022,11 @@ impl VM {
                         }
                         _ => {}
                     }
+                    // Funções quentes com argumentos numéricos/booleanos podem ir para o código nativo
+                    if let Some(valor) = self.chamar_jit(nome, &args)? {
+                        self.pilha.push(valor);
+                        continue;
+                    }
                     // procura função
                     let func = self
                         .functions
@@ -1923,10 +2052,12 @@ impl VM {
                         loaded_modules: self.loaded_modules.clone(),
                         base_dir: self.base_dir.clone(),
                         debug: self.debug.clone(),
+                        teste_de_mesa: self.teste_de_mesa.clone(),
                         code_id: format!("func:{}", func.nome),
                         task_counter: self.task_counter.clone(),
                         tasks: self.tasks.clone(),
                         call_stack: Vec::new(),
+                        jit: self.jit.clone(),
                     };
                     Box::pin(vm.run()).await?;
                     self.pilha.push(vm.pilha.pop().unwrap_or(Valor::Nulo));
@@ -1945,6 +2076,12 @@ impl VM {
                     } else {
                         Vec::new()
                     };
+                    // Afirmar.Lanca precisa executar código da VM, então não passa pelo despacho comum
+                    if chave == "Afirmar::Lanca" {
+                        self.afirmar_lanca(args).await?;
+                        self.pilha.push(Valor::Nulo);
+                        continue;
+                    }
                     let resultado = despachar_nativo_estatico(&chave, args)?;
                     self.pilha.push(resultado);
                 }
@@ -2127,6 +2264,22 @@ impl VM {
                     }
                 }
 
+                "LINE" => {
+                    if let Some(t) = self.teste_de_mesa.clone() {
+                        if t.borrow().aceita(&self.code_id) {
+                            // `LINE <linha> <arquivo>`: o arquivo é o resto da instrução
+                            let mut campos = instrucao_str.splitn(3, ' ').skip(1);
+                            let linha = campos
+                                .next()
+                                .and_then(|l| l.parse::<usize>().ok())
+                                .ok_or("LINE requer o número da linha")?;
+                            let arquivo = campos.next().unwrap_or_default();
+                            let valores = self.valores_teste_de_mesa();
+                            t.borrow_mut().linha(&self.code_id, arquivo, linha, valores);
+                        }
+                    }
+                }
+
                 // Ignora comentários ou linhas vazias
                 op if op.starts_with(';') || op.is_empty() => {}
                 _ => {
@@ -2151,8 +2304,11 @@ impl VM {
                     i += 1;
                 }
                 i += 1; // Pula o END_CLASS
-            } else if instrucao.starts_with("DEFINE_STATIC_CLASS") {
-                // A classe estática não possui END_CLASS
+            } else if instrucao.starts_with("DEFINE_STATIC_CLASS")
+                || instrucao.starts_with("DEFINE_TEST")
+                || instrucao.starts_with("LOAD_LIBRARY")
+            {
+                // A classe estática não possui END_CLASS; DEFINE_TEST e LOAD_LIBRARY são metadados
                 i += 1;
             } else if instrucao.starts_with("DEFINE_FUNCTION")
                 || instrucao.starts_with("DEFINE_METHOD")
@@ -2193,8 +2349,10 @@ impl VM {
             loaded_modules: self.loaded_modules.clone(),
             base_dir: self.base_dir.clone(),
             debug: self.debug.clone(),
+            teste_de_mesa: self.teste_de_mesa.clone(),
             code_id: "global:init".to_string(),
             call_stack: self.call_stack.clone(),
+            jit: self.jit.clone(),
             task_counter: self.task_counter.clone(),
             tasks: self.tasks.clone(),
         };
@@ -2312,6 +2470,83 @@ impl VM {
         Ok(())
     }
 
+    // Afirmar.Lanca("Funcao" | "Classe.Metodo"): executa o alvo (sem argumentos) e exige que ele
+    // falhe. Métodos são executados como os testes: os de instância em um objeto novo.
+    async fn afirmar_lanca(&mut self, args: Vec<Valor>) -> Result<(), String> {
+        let nome = match args.first() {
+            Some(Valor::Texto(nome)) => nome.clone(),
+            _ => return Err("Afirmar.Lanca requer o nome de uma função ou método".into()),
+        };
+        let alvo = self.alvo_lanca(&nome).ok_or_else(|| {
+            format!(
+                "Afirmar.Lanca: \"{}\" não é uma função nem um método no formato \"Classe.Metodo\"",
+                nome
+            )
+        })?;
+        match self.executar_teste(&alvo).await {
+            Err(_) => Ok(()),
+            Ok(_) => Err(format!(
+                "Afirmar.Lanca falhou: esperava um erro ao executar '{}', mas nenhum erro ocorreu",
+                nome
+            )),
+        }
+    }
+
+    // Resolve o nome dado a Afirmar.Lanca no mesmo formato dos alvos `DEFINE_TEST`.
+    fn alvo_lanca(&self, nome: &str) -> Option<Vec<String>> {
+        let funcao = self.functions.keys().find(|k| *k == nome).or_else(|| {
+            self.functions
+                .keys()
+                .find(|k| k.rsplit('.').next() == Some(nome))
+        });
+        if let Some(fqn) = funcao {
+            return Some(vec!["funcao".to_string(), fqn.clone()]);
+        }
+        let (classe, metodo) = nome.rsplit_once('.')?;
+        let (fqn, info) = self.classes.get_key_value(classe).or_else(|| {
+            self.classes
+                .iter()
+                .find(|(k, _)| k.rsplit('.').next() == Some(classe))
+        })?;
+        let tipo = if info.metodos_estaticos.contains_key(metodo) {
+            "estatico"
+        } else {
+            let mut atual = Some(info);
+            while let Some(info) = atual.filter(|i| !i.metodos.contains_key(metodo)) {
+                atual = info
+                    .nome_classe_pai
+                    .as_deref()
+                    .and_then(|pai| self.classes.get(pai));
+            }
+            atual?;
+            "metodo"
+        };
+        Some(vec![tipo.to_string(), fqn.clone(), metodo.to_string()])
+    }
+
+    // Executa um alvo `DEFINE_TEST` (`funcao Nome` | `estatico Classe Metodo` | `metodo Classe Metodo`).
+    async fn executar_teste(&mut self, alvo: &[String]) -> Result<(), String> {
+        match alvo {
+            [tipo, nome] if tipo == "funcao" => {
+                let func = self
+                    .functions
+                    .get(nome)
+                    .cloned()
+                    .ok_or_else(|| format!("Função de teste \"{}\" não encontrada", nome))?;
+                self.executar_funcao(&func, Vec::new(), None).await?;
+            }
+            [tipo, classe, metodo] if tipo == "estatico" => {
+                self.chamar_metodo_estatico(classe, metodo, Vec::new()).await?;
+            }
+            [tipo, classe, metodo] if tipo == "metodo" => {
+                let mut objeto = self.criar_objeto(classe, Vec::new()).await?;
+                self.chamar_metodo(&mut objeto, metodo, Vec::new()).await?;
+            }
+            _ => return Err(format!("DEFINE_TEST inválido: {}", alvo.join(" "))),
+        }
+        Ok(())
+    }
+
     fn debug_pause_if_needed(&mut self, instr: &str) -> Result<(), String> {
         let Some(dbg_rc) = self.debug.clone() else {
             return Ok(());
@@ -2640,6 +2875,34 @@ async fn despachar_nativo_assincrono(chave: &str, args: Vec<Valor>) -> Result<Va
 /// Equivalente ao InternalCall do .NET CLR — sem acoplamento de nome entre .pr e Rust.
 fn despachar_nativo_estatico(chave: &str, args: Vec<Valor>) -> Result<Valor, String> {
     match chave {
+        // ============ Afirmar (framework de testes) ============
+        "Afirmar::Igual" => {
+            let mut it = args.into_iter();
+            let esperado = it.next().unwrap_or(Valor::Nulo);
+            let obtido = it.next().unwrap_or(Valor::Nulo);
+            if esperado == obtido {
+                return Ok(Valor::Nulo);
+            }
+            let (mut esp, mut obt) = (esperado.to_string(), obtido.to_string());
+            if esp == obt {
+                // Mesma representação, tipos diferentes (ex.: 1 e "1")
+                esp = format!("{:?}", esperado);
+                obt = format!("{:?}", obtido);
+            }
+            Err(format!(
+                "Afirmar.Igual falhou\n{}",
+                testes::formatar_diferenca(&esp, &obt)
+            ))
+        }
+        "Afirmar::Verdadeiro" => match args.first() {
+            Some(Valor::Booleano(true)) => Ok(Valor::Nulo),
+            Some(outro) => Err(format!(
+                "Afirmar.Verdadeiro falhou: condição avaliou para {}",
+                outro
+            )),
+            None => Err("Afirmar.Verdadeiro requer uma condição".into()),
+        },
+
         // ============ Sistema.Console ============
         "Console::EscreverLinha" => {
             let msg = args
@@ -2978,22 +3241,9 @@ fn despachar_nativo_instancia(chave: &str, este: Valor, args: Vec<Valor>) -> Res
 #[tokio::main(flavor = "multi_thread", worker_threads = 4)]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = env::args().collect();
-    let usar_jit = args.iter().any(|a| a == "--jit");
-
-    // Quando --jit for passado, faça um autoteste simples do JIT para confirmar que está funcional.
-    #[cfg(feature = "jit")]
-    if usar_jit {
-        if let Ok(mut jit) = CraneliftJit::new() {
-            if let Ok(handle) = jit.compilar_soma_i32() {
-                let r = unsafe { jit.chamar_soma_i32(&handle, 2, 40) };
-                eprintln!("[JIT] autoteste soma_i32(2,40) = {}", r);
-            }
-        }
-    }
-
     if args.len() < 2 {
         eprintln!(
-            "Uso: {} <arquivo.pbc> [--executar-funcao <nome_da_funcao_completo>]",
+            "Uso: {} <arquivo.pbc> [--executar-funcao <nome_da_funcao_completo>] [--debug] [--teste-de-mesa] [--jit [--jit-limite=<n>] [--jit-estatisticas]] [--testar [--filtro=<nome>] [--junit=<arquivo.xml>]] [--avaliar <casos/> [--comparacao=linhas|exata|espacos] [--tempo-limite=<ms>] [--relatorio=<arquivo.json>]]",
             args[0]
         );
         return Err("Argumento inválido".into());
@@ -3002,6 +3252,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let caminho_arquivo = &args[1];
     let mut function_to_execute: Option<String> = None;
     let mut usar_debug = false;
+    let mut usar_teste_de_mesa = false;
+    let mut mesa_funcao: Option<String> = None;
+    let mut mesa_limite: Option<usize> = None;
+    let mut mesa_formato: Option<FormatoTabela> = None;
+    let mut mesa_saida: Option<PathBuf> = None;
+    let mut usar_testes = false;
+    let mut filtro_testes: Option<String> = None;
+    let mut junit_saida: Option<PathBuf> = None;
+    let mut casos_avaliacao: Option<PathBuf> = None;
+    let mut comparacao = ModoComparacao::Linhas;
+    let mut tempo_limite = avaliacao::TEMPO_LIMITE_PADRAO;
+    let mut relatorio_avaliacao: Option<PathBuf> = None;
+    let mut usar_jit = false;
+    let mut jit_limite = jit::LIMITE_PADRAO;
+    let mut jit_estatisticas = false;
 
     let mut i = 2;
     while i < args.len() {
@@ -3015,11 +3280,115 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         } else if args[i] == "--debug" {
             usar_debug = true;
             i += 1;
+        } else if args[i] == "--testar" {
+            usar_testes = true;
+            i += 1;
+        } else if let Some(filtro) = args[i].strip_prefix("--filtro=") {
+            filtro_testes = Some(filtro.to_string());
+            i += 1;
+        } else if let Some(caminho) = args[i].strip_prefix("--junit=") {
+            junit_saida = Some(PathBuf::from(caminho));
+            i += 1;
+        } else if args[i] == "--avaliar" {
+            if i + 1 < args.len() {
+                casos_avaliacao = Some(PathBuf::from(&args[i + 1]));
+                i += 2;
+            } else {
+                return Err("Argumento --avaliar requer o diretório de casos".into());
+            }
+        } else if let Some(modo) = args[i].strip_prefix("--comparacao=") {
+            comparacao = ModoComparacao::de_nome(modo).ok_or_else(|| {
+                format!("Modo inválido para --comparacao: {} (use linhas, exata ou espacos)", modo)
+            })?;
+            i += 1;
+        } else if let Some(ms) = args[i].strip_prefix("--tempo-limite=") {
+            tempo_limite = Duration::from_millis(
+                ms.parse()
+                    .map_err(|_| format!("Valor inválido para --tempo-limite: {}", ms))?,
+            );
+            i += 1;
+        } else if let Some(caminho) = args[i].strip_prefix("--relatorio=") {
+            relatorio_avaliacao = Some(PathBuf::from(caminho));
+            i += 1;
+        } else if args[i] == "--jit" {
+            usar_jit = true;
+            i += 1;
+        } else if let Some(n) = args[i].strip_prefix("--jit-limite=") {
+            usar_jit = true;
+            jit_limite = n
+                .parse()
+                .map_err(|_| format!("Valor inválido para --jit-limite: {}", n))?;
+            i += 1;
+        } else if args[i] == "--jit-estatisticas" {
+            usar_jit = true;
+            jit_estatisticas = true;
+            i += 1;
+        } else if args[i] == "--teste-de-mesa" {
+            usar_teste_de_mesa = true;
+            i += 1;
+        } else if let Some(nome) = args[i].strip_prefix("--teste-de-mesa-funcao=") {
+            usar_teste_de_mesa = true;
+            mesa_funcao = Some(nome.to_string());
+            i += 1;
+        } else if let Some(n) = args[i].strip_prefix("--teste-de-mesa-limite=") {
+            usar_teste_de_mesa = true;
+            mesa_limite = Some(
+                n.parse()
+                    .map_err(|_| format!("Limite inválido para --teste-de-mesa-limite: {}", n))?,
+            );
+            i += 1;
+        } else if let Some(f) = args[i].strip_prefix("--teste-de-mesa-formato=") {
+            usar_teste_de_mesa = true;
+            mesa_formato = Some(FormatoTabela::de_nome(f).ok_or_else(|| {
+                format!("Formato inválido para --teste-de-mesa-formato: {} (use texto, csv ou md)", f)
+            })?);
+            i += 1;
+        } else if let Some(caminho) = args[i].strip_prefix("--teste-de-mesa-saida=") {
+            usar_teste_de_mesa = true;
+            mesa_saida = Some(PathBuf::from(caminho));
+            i += 1;
         } else {
             i += 1;
         }
     }
-    let bytecode = ler_bytecode(caminho_arquivo)?;
+    if let Some(diretorio) = casos_avaliacao {
+        let casos = avaliacao::descobrir_casos(&diretorio)
+            .map_err(|e| format!("Erro ao ler casos em {}: {}", diretorio.display(), e))?;
+        if casos.is_empty() {
+            return Err(format!("Nenhum caso (subdiretório com saida.txt) em {}", diretorio.display()).into());
+        }
+        // Cada caso roda em um processo novo deste mesmo interpretador, com stdin redirecionado
+        let executavel = env::current_exe()?;
+        println!("avaliando {} em {} caso(s)", caminho_arquivo, casos.len());
+        let mut relatorio = RelatorioAvaliacao::new(caminho_arquivo.as_str(), comparacao, tempo_limite);
+        avaliacao::avaliar(
+            || {
+                let mut cmd = std::process::Command::new(&executavel);
+                cmd.arg(caminho_arquivo);
+                cmd
+            },
+            &casos,
+            &mut relatorio,
+            |r| println!("{}", RelatorioAvaliacao::formatar_resultado(r)),
+        )?;
+        print!("{}", relatorio.resumo());
+        if let Some(caminho) = relatorio_avaliacao {
+            fs::write(&caminho, relatorio.json())?;
+            println!("Relatório JSON gravado em {}", caminho.display());
+        }
+        if relatorio.aprovados < relatorio.total {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let bytecode = match ler_bytecode(caminho_arquivo) {
+        Ok(bytecode) => bytecode,
+        Err(e) => {
+            eprintln!("Erro ao carregar o bytecode: {}", e);
+            return Err(e.into());
+        }
+    };
     if bytecode.is_empty() {
         return Err("Arquivo de bytecode vazio".into());
     }
@@ -3033,6 +3402,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         path
     };
 
+    if usar_testes {
+        let suite = std::path::Path::new(caminho_arquivo)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("testes")
+            .to_string();
+        let relatorio =
+            executar_testes(&bytecode, &base_dir, &suite, filtro_testes.as_deref()).await;
+        print!("{}", relatorio.resumo());
+        if let Some(caminho) = junit_saida {
+            fs::write(&caminho, relatorio.junit_xml())?;
+            println!("Relatório JUnit gravado em {}", caminho.display());
+        }
+        if relatorio.falhas() > 0 {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     let mut vm = VM::new(bytecode, base_dir);
     if usar_debug {
         let dbg = DebugState {
@@ -3044,6 +3432,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         };
         vm.debug = Some(Rc::new(RefCell::new(dbg)));
     }
+    if usar_teste_de_mesa {
+        vm.teste_de_mesa = Some(Rc::new(RefCell::new(TesteDeMesa::new(
+            mesa_funcao,
+            mesa_limite,
+        ))));
+    }
+
+    // O código nativo não passa pelo depurador nem pelo teste de mesa
+    if usar_jit && !usar_debug && !usar_teste_de_mesa {
+        match JitNivelado::new(jit_limite) {
+            Ok(nivelado) => vm.jit = Some(Rc::new(RefCell::new(nivelado))),
+            Err(e) => eprintln!("[JIT] indisponível: {}", e),
+        }
+    }
 
     // Carregar definições (classes, funções)
     if let Err(e) = vm.carregar_definicoes() {
@@ -3060,6 +3462,10 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Fase 3: Executar código global (funções main, etc.)
     if let Err(e) = vm.executar_codigo_global().await {
         eprintln!("Erro ao executar código de inicialização: {}", e);
+        emitir_teste_de_mesa(&vm, mesa_formato, mesa_saida.as_deref())?;
+        if jit_estatisticas {
+            emitir_estatisticas_jit(&vm);
+        }
         return Err(e.into());
     }
 
@@ -3094,28 +3500,120 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             loaded_modules: vm.loaded_modules.clone(),
             base_dir: vm.base_dir.clone(),
             debug: vm.debug.clone(),
+            teste_de_mesa: vm.teste_de_mesa.clone(),
             code_id: format!("main:{}", nome_funcao),
             // A VM principal herda o gerenciador de tasks compartilhado
             task_counter: vm.task_counter.clone(),
             tasks: vm.tasks.clone(),
             call_stack: Vec::new(),
+            jit: vm.jit.clone(),
         };
 
         if let Err(e) = main_vm.run().await {
             eprintln!("❌ Erro na execução da função {}: {}", nome_funcao, e);
+            emitir_teste_de_mesa(&vm, mesa_formato, mesa_saida.as_deref())?;
+            if jit_estatisticas {
+                emitir_estatisticas_jit(&vm);
+            }
             return Err(e.into());
         }
     }
 
+    emitir_teste_de_mesa(&vm, mesa_formato, mesa_saida.as_deref())?;
+    if jit_estatisticas {
+        emitir_estatisticas_jit(&vm);
+    }
+    Ok(())
+}
+
+// Resumo do JIT em stderr, para não misturar com a saída do programa
+fn emitir_estatisticas_jit(vm: &VM) {
+    if let Some(jit) = &vm.jit {
+        let resumo = jit.borrow().resumo();
+        if resumo.is_empty() {
+            eprintln!("[JIT] nenhuma função atingiu o limite de chamadas");
+        } else {
+            eprint!("{}", resumo);
+        }
+    }
+}
+
+// Executa cada alvo `DEFINE_TEST` em uma VM nova (definições, inicializadores e código
+// global recarregados), para que um teste não enxergue o estado deixado por outro.
+async fn executar_testes(
+    bytecode: &[String],
+    base_dir: &std::path::Path,
+    suite: &str,
+    filtro: Option<&str>,
+) -> RelatorioTestes {
+    let alvos: Vec<Vec<String>> = bytecode
+        .iter()
+        .filter_map(|l| l.strip_prefix("DEFINE_TEST "))
+        .map(|resto| resto.split_whitespace().map(String::from).collect::<Vec<_>>())
+        .filter(|alvo| filtro.is_none_or(|f| alvo[1..].join(".").contains(f)))
+        .collect();
+
+    println!("executando {} teste(s) de {}", alvos.len(), suite);
+    let mut relatorio = RelatorioTestes::new(suite);
+    for alvo in alvos {
+        let inicio = std::time::Instant::now();
+        let mut vm = VM::new(bytecode.to_vec(), base_dir.to_path_buf());
+        let mut resultado = vm.carregar_definicoes();
+        if resultado.is_ok() {
+            resultado = vm.run_apenas_inicializadores();
+        }
+        if resultado.is_ok() {
+            resultado = vm.executar_codigo_global().await;
+        }
+        if resultado.is_ok() {
+            resultado = vm.executar_teste(&alvo).await;
+        }
+        let teste = ResultadoTeste {
+            nome: alvo[1..].join("."),
+            duracao: inicio.elapsed(),
+            falha: resultado.err(),
+        };
+        println!("{}", RelatorioTestes::formatar_resultado(&teste));
+        relatorio.resultados.push(teste);
+    }
+    relatorio
+}
+
+// Imprime (ou grava em arquivo) a tabela do teste de mesa, se o modo estiver ativo.
+// Sem formato explícito, usa a extensão do arquivo de saída ou texto no terminal.
+fn emitir_teste_de_mesa(
+    vm: &VM,
+    formato: Option<FormatoTabela>,
+    saida: Option<&std::path::Path>,
+) -> io::Result<()> {
+    let Some(t) = &vm.teste_de_mesa else {
+        return Ok(());
+    };
+    let t = t.borrow();
+    match saida {
+        Some(caminho) => {
+            let formato = formato.unwrap_or_else(|| FormatoTabela::de_caminho(caminho));
+            fs::write(caminho, t.renderizar(formato))?;
+            eprintln!(
+                "Teste de mesa ({} passos) gravado em {}",
+                t.passos().len(),
+                caminho.display()
+            );
+        }
+        None => {
+            let formato = formato.unwrap_or(FormatoTabela::Texto);
+            println!("\n=== Teste de mesa ===");
+            print!("{}", t.renderizar(formato));
+        }
+    }
     Ok(())
 }
 
-//Função auxiliar para ler o bytecode do arquivo.
-fn ler_bytecode(caminho_arquivo: &str) -> Result<Vec<String>, std::io::Error> {
-    let mut arquivo = fs::File::open(caminho_arquivo)?;
-    let mut conteudo = String::new();
-    arquivo.read_to_string(&mut conteudo)?;
-    let bytecode_linhas: Vec<String> = conteudo
+//Função auxiliar para ler o bytecode do arquivo, conferindo formato, hash e assinatura.
+fn ler_bytecode(caminho_arquivo: &str) -> Result<Vec<String>, String> {
+    let artefato = artefato::ler(std::path::Path::new(caminho_arquivo))?;
+    let bytecode_linhas: Vec<String> = artefato
+        .corpo()
         .lines()
         .filter(|l| !l.trim().is_empty())
         .map(String::from)
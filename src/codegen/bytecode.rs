@@ -1,6 +1,8 @@
 use crate::ast;
 use crate::library_loader::LibSimbolo;
-use std::collections::{HashMap, HashSet};
+use crate::paralelo;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fmt;
 
 impl fmt::Display for ast::Expressao {
@@ -27,11 +29,99 @@ pub struct BytecodeGenerator<'a> {
     type_checker: &'a crate::type_checker::VerificadorTipos<'a>,
     namespace_path: String,
     bytecode_instructions: Vec<String>,
-    props_por_classe: HashMap<String, Vec<String>>,
-    construtor_params_por_classe: HashMap<String, Vec<String>>,
     current_class_name: Option<String>,
     // Parâmetros locais do método/construtor atual (para desambiguar nome igual a propriedade)
     current_params: Option<HashSet<String>>,
+    // Corpo de método estático: não há `este` para chamadas de métodos de instância sem alvo
+    metodo_estatico: bool,
+    // Índices dos saltos do código global e quantas instruções emitidas são definições
+    saltos: Vec<usize>,
+    definicoes: usize,
+}
+
+/// Prefixa o corpo de um método com instruções (SET_DEFAULT, chamada ao construtor base). Os
+/// destinos dos saltos são índices dentro do corpo e são deslocados pelos saltos registrados.
+fn prefixar_corpo(prefixo: Vec<String>, corpo: Trecho) -> Vec<String> {
+    let mut trecho = Trecho {
+        globais: prefixo.len(),
+        instrucoes: prefixo,
+        saltos: Vec::new(),
+    };
+    trecho.anexar(corpo);
+    trecho.instrucoes
+}
+
+/// Bytecode de uma sequência de declarações. A VM executa o código global à parte, sem as
+/// definições (classes, funções e seus corpos), então o destino de um salto global é um índice
+/// nessa sequência filtrada; `saltos` guarda onde estão esses saltos para que possam ser
+/// deslocados quando o trecho é juntado a outros.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Trecho {
+    pub instrucoes: Vec<String>,
+    /// Índices, em `instrucoes`, dos JUMP/JUMP_IF_FALSE do código global
+    pub saltos: Vec<usize>,
+    /// Quantas das `instrucoes` são código global
+    pub globais: usize,
+}
+
+impl Trecho {
+    /// Acrescenta `outro` ao fim deste trecho: os saltos globais dele passam a contar a partir
+    /// do código global que já está aqui.
+    fn anexar(&mut self, outro: Trecho) {
+        let inicio = self.instrucoes.len();
+        self.instrucoes.extend(outro.instrucoes);
+        for salto in outro.saltos {
+            let posicao = inicio + salto;
+            self.instrucoes[posicao] = deslocar_salto(&self.instrucoes[posicao], self.globais);
+            self.saltos.push(posicao);
+        }
+        self.globais += outro.globais;
+    }
+}
+
+/// `JUMP n` / `JUMP_IF_FALSE n` com o destino somado a `deslocamento`.
+fn deslocar_salto(instrucao: &str, deslocamento: usize) -> String {
+    match instrucao.split_once(' ') {
+        Some((op, destino)) => match destino.parse::<usize>() {
+            Ok(destino) => format!("{} {}", op, destino + deslocamento),
+            Err(_) => instrucao.to_string(),
+        },
+        None => instrucao.to_string(),
+    }
+}
+
+/// Bytecode de um programa (ou de um arquivo dele): o das declarações de nível superior e o de
+/// cada namespace, na ordem em que aparecem.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Trechos {
+    pub topo: Trecho,
+    pub namespaces: Vec<(String, Trecho)>,
+}
+
+/// Junta os trechos de vários arquivos na ordem em que o programa combinado seria gerado:
+/// primeiro o nível superior de todos, depois cada namespace (na ordem da primeira aparição)
+/// com as partes de cada arquivo.
+pub fn juntar_trechos(unidades: &[Trechos]) -> Vec<String> {
+    let mut bytecode = Trecho::default();
+    for unidade in unidades {
+        bytecode.anexar(unidade.topo.clone());
+    }
+    let mut ordem: Vec<&str> = Vec::new();
+    for (nome, _) in unidades.iter().flat_map(|t| &t.namespaces) {
+        if !ordem.contains(&nome.as_str()) {
+            ordem.push(nome);
+        }
+    }
+    for nome in ordem {
+        for (_, trecho) in unidades
+            .iter()
+            .flat_map(|t| &t.namespaces)
+            .filter(|(n, _)| n == nome)
+        {
+            bytecode.anexar(trecho.clone());
+        }
+    }
+    bytecode.instrucoes
 }
 
 impl<'a> BytecodeGenerator<'a> {
@@ -41,16 +131,67 @@ impl<'a> BytecodeGenerator<'a> {
             type_checker: self.type_checker,
             namespace_path: self.namespace_path.clone(),
             bytecode_instructions: Vec::new(),
-            props_por_classe: self.props_por_classe.clone(),
-            construtor_params_por_classe: self.construtor_params_por_classe.clone(),
             current_class_name: self.current_class_name.clone(),
             current_params: self.current_params.clone(),
+            metodo_estatico: self.metodo_estatico,
+            saltos: Vec::new(),
+            definicoes: 0,
+        }
+    }
+    /// Posição da próxima instrução no código global, que é o destino dos saltos dele.
+    fn posicao_global(&self) -> usize {
+        self.bytecode_instructions.len() - self.definicoes
+    }
+
+    fn trecho(self) -> Trecho {
+        Trecho {
+            globais: self.posicao_global(),
+            instrucoes: self.bytecode_instructions,
+            saltos: self.saltos,
         }
     }
+
+    fn absorver(&mut self, trecho: Trecho) {
+        let mut atual = Trecho {
+            globais: self.posicao_global(),
+            instrucoes: std::mem::take(&mut self.bytecode_instructions),
+            saltos: std::mem::take(&mut self.saltos),
+        };
+        atual.anexar(trecho);
+        self.definicoes = atual.instrucoes.len() - atual.globais;
+        self.bytecode_instructions = atual.instrucoes;
+        self.saltos = atual.saltos;
+    }
+
     fn get_class_declaration(&self, class_name: &str) -> Option<&'a ast::DeclaracaoClasse> {
         self.type_checker.classes.get(class_name).copied()
     }
 
+    /// Procura `nome` entre os métodos da classe atual e de suas ancestrais, para chamadas
+    /// sem `este.` dentro de métodos. Retorna a classe que declara o método e se é estático.
+    fn metodo_da_classe_atual(&self, nome: &str) -> Option<(String, bool)> {
+        let class_name = self.current_class_name.as_ref()?;
+        let mut atual = Some(class_name.clone());
+        while let Some(fqn) = atual {
+            let class_decl = self.type_checker.classes.get(&fqn).copied()?;
+            if let Some(metodo) = class_decl.metodos.iter().find(|m| m.nome == nome) {
+                return Some((fqn, metodo.eh_estatica));
+            }
+            atual = class_decl.classe_pai.as_ref().and_then(|parent_tipo| {
+                let base = match parent_tipo {
+                    ast::Tipo::Classe(n) => n.as_str(),
+                    ast::Tipo::Aplicado { nome, .. } => nome.as_str(),
+                    _ => return None,
+                };
+                Some(
+                    self.type_checker
+                        .resolver_nome_classe(base, &self.namespace_path),
+                )
+            });
+        }
+        None
+    }
+
     fn qual(&self, local: &str) -> String {
         if self.namespace_path.is_empty() {
             local.to_owned()
@@ -68,10 +209,11 @@ impl<'a> BytecodeGenerator<'a> {
             type_checker,
             namespace_path: String::new(),
             bytecode_instructions: Vec::new(),
-            props_por_classe: HashMap::new(),
-            construtor_params_por_classe: HashMap::new(),
             current_class_name: None,
             current_params: None,
+            metodo_estatico: false,
+            saltos: Vec::new(),
+            definicoes: 0,
         }
     }
 
@@ -92,13 +234,11 @@ impl<'a> BytecodeGenerator<'a> {
                 ctor.corpo.clone(),
             ))],
         };
-        let mut sub = BytecodeGenerator {
+        let sub = BytecodeGenerator {
             programa: &sub_prog,
             type_checker: self.type_checker,
             namespace_path: self.namespace_path.clone(),
             bytecode_instructions: Vec::new(),
-            props_por_classe: self.props_por_classe.clone(),
-            construtor_params_por_classe: self.construtor_params_por_classe.clone(),
             current_class_name: Some(nome_classe.to_string()),
             current_params: Some(
                 ctor.parametros
@@ -106,8 +246,11 @@ impl<'a> BytecodeGenerator<'a> {
                     .map(|p| p.nome.clone())
                     .collect::<HashSet<String>>(),
             ),
+            metodo_estatico: false,
+            saltos: Vec::new(),
+            definicoes: 0,
         };
-        let corpo = sub.generate();
+        let corpo = sub.gerar_trechos().topo;
         let mut corpo_com_defaults = Vec::new();
         if let Some(base_args) = &ctor.chamada_pai {
             let mut temp_gen = self.spawn_child();
@@ -115,7 +258,12 @@ impl<'a> BytecodeGenerator<'a> {
                 temp_gen.generate_expressao(arg);
             }
             corpo_com_defaults.extend(temp_gen.bytecode_instructions);
-            corpo_com_defaults.push(format!("CALL_BASE_CONSTRUCTOR {}", base_args.len()));
+            // A classe do construtor vai junto: em cadeias A <- B <- C, `este` é sempre C
+            corpo_com_defaults.push(format!(
+                "CALL_BASE_CONSTRUCTOR {} {}",
+                base_args.len(),
+                nome_classe
+            ));
         }
 
         for p in &ctor.parametros {
@@ -129,8 +277,7 @@ impl<'a> BytecodeGenerator<'a> {
                 ));
             }
         }
-        corpo_com_defaults.extend(corpo);
-        let corpo = corpo_com_defaults;
+        let corpo = prefixar_corpo(corpo_com_defaults, corpo);
         let params: Vec<String> = ctor
             .parametros
             .iter()
@@ -163,22 +310,8 @@ impl<'a> BytecodeGenerator<'a> {
                 } else {
                     format!("{}.{}", self.namespace_path, ns.nome)
                 };
-                let sub_prog = ast::Programa {
-                    usings: vec![],
-                    namespaces: vec![],
-                    declaracoes: ns.declaracoes.clone(),
-                };
-                let mut sub = BytecodeGenerator {
-                    programa: &sub_prog,
-                    type_checker: self.type_checker,
-                    namespace_path: new_path,
-                    bytecode_instructions: Vec::new(),
-                    props_por_classe: self.props_por_classe.clone(),
-                    construtor_params_por_classe: self.construtor_params_por_classe.clone(),
-                    current_class_name: None,
-                    current_params: None,
-                };
-                self.bytecode_instructions.extend(sub.generate());
+                let trecho = self.gerar_trecho(&new_path, &ns.declaracoes);
+                self.absorver(trecho);
             }
 
             // Reconhece e processa a declaração de classe
@@ -198,15 +331,9 @@ impl<'a> BytecodeGenerator<'a> {
                                 .resolver_nome_classe(base, &self.namespace_path)
                         });
 
-                let mut all_props = self
-                    .props_por_classe
-                    .get(&parent_class_name)
-                    .cloned()
-                    .unwrap_or_default();
+                let mut all_props = self.propriedades_herdadas(&parent_class_name);
                 all_props.extend(classe_def.propriedades.iter().map(|p| p.nome.clone()));
                 all_props.extend(classe_def.campos.iter().map(|c| c.nome.clone()));
-                self.props_por_classe
-                    .insert(full_class_name.clone(), all_props.clone());
 
                 // Utilize vírgula como separador para evitar que "split_whitespace" quebre o token na carga do interpretador
                 let props_str = all_props.join(",");
@@ -230,6 +357,7 @@ impl<'a> BytecodeGenerator<'a> {
                 let meta_str = format!("{}|{}|{}|", props_str, params_str, base_args_str);
 
                 // For static classes, we still need to register them but with a special marker
+                let inicio = self.bytecode_instructions.len();
                 if classe_def.eh_estatica {
                     self.bytecode_instructions
                         .push(format!("DEFINE_STATIC_CLASS {}", full_class_name));
@@ -248,6 +376,15 @@ impl<'a> BytecodeGenerator<'a> {
                     if metodo.eh_abstrato {
                         continue; // não gera corpo nem entrada para métodos abstratos
                     }
+                    // Marca métodos [Teste] para o executor de testes do interpretador
+                    if crate::testes::eh_teste(&metodo.attributes) {
+                        self.bytecode_instructions.push(format!(
+                            "DEFINE_TEST {} {} {}",
+                            if metodo.eh_estatica { "estatico" } else { "metodo" },
+                            full_class_name,
+                            metodo.nome
+                        ));
+                    }
                     if metodo.eh_estatica {
                         self.gerar_metodo_estatico(metodo, &full_class_name);
                     } else {
@@ -259,6 +396,7 @@ impl<'a> BytecodeGenerator<'a> {
                 if !classe_def.eh_estatica {
                     self.bytecode_instructions.push("END_CLASS".to_string());
                 }
+                self.definicoes += self.bytecode_instructions.len() - inicio;
 
                 // ===== Inicializadores de propriedades/campos estáticos =====
                 for campo in &classe_def.campos {
@@ -309,10 +447,11 @@ impl<'a> BytecodeGenerator<'a> {
                     type_checker: self.type_checker,
                     namespace_path: self.namespace_path.clone(),
                     bytecode_instructions: Vec::new(),
-                    props_por_classe: self.props_por_classe.clone(),
-                    construtor_params_por_classe: self.construtor_params_por_classe.clone(),
                     current_class_name: None,
                     current_params: None,
+                    metodo_estatico: false,
+                    saltos: Vec::new(),
+                    definicoes: 0,
                 };
                 let mut corpo = sub.generate(); // inclui HALT
                 if !matches!(corpo.last(), Some(op) if op == "RETURN") {
@@ -325,6 +464,11 @@ impl<'a> BytecodeGenerator<'a> {
                     func_def.parametros.iter().map(|p| p.nome.clone()).collect();
                 // let full_fn = self.type_checker.resolver_nome_funcao(&func_def.nome, &self.namespace_path);
                 let full_fn = self.qual(&func_def.nome);
+                let inicio = self.bytecode_instructions.len();
+                if crate::testes::eh_teste(&func_def.attributes) {
+                    self.bytecode_instructions
+                        .push(format!("DEFINE_TEST funcao {}", full_fn));
+                }
                 self.bytecode_instructions.push(format!(
                     "DEFINE_FUNCTION {} {} {}",
                     full_fn,
@@ -333,6 +477,7 @@ impl<'a> BytecodeGenerator<'a> {
                 ));
 
                 self.bytecode_instructions.extend(corpo);
+                self.definicoes += self.bytecode_instructions.len() - inicio;
             }
 
             // Mantém o comportamento para comandos
@@ -357,13 +502,11 @@ impl<'a> BytecodeGenerator<'a> {
             ))],
         };
 
-        let mut sub = BytecodeGenerator {
+        let sub = BytecodeGenerator {
             programa: &sub_prog,
             type_checker: self.type_checker,
             namespace_path: self.namespace_path.clone(),
             bytecode_instructions: Vec::new(),
-            props_por_classe: self.props_por_classe.clone(),
-            construtor_params_por_classe: self.construtor_params_por_classe.clone(),
             current_class_name: Some(nome_classe.to_string()),
             current_params: Some(
                 metodo
@@ -372,12 +515,15 @@ impl<'a> BytecodeGenerator<'a> {
                     .map(|p| p.nome.clone())
                     .collect::<HashSet<String>>(),
             ),
+            metodo_estatico: false,
+            saltos: Vec::new(),
+            definicoes: 0,
         };
-        let mut corpo = sub.generate();
+        let mut corpo = sub.gerar_trechos().topo;
 
-        if !matches!(corpo.last(), Some(op) if op == "RETURN") {
-            corpo.push("LOAD_CONST_NULL".to_string());
-            corpo.push("RETURN".to_string());
+        if !matches!(corpo.instrucoes.last(), Some(op) if op == "RETURN") {
+            corpo.instrucoes.push("LOAD_CONST_NULL".to_string());
+            corpo.instrucoes.push("RETURN".to_string());
         }
 
         let mut corpo_com_defaults = Vec::new();
@@ -392,8 +538,7 @@ impl<'a> BytecodeGenerator<'a> {
                 ));
             }
         }
-        corpo_com_defaults.extend(corpo);
-        let corpo = corpo_com_defaults;
+        let corpo = prefixar_corpo(corpo_com_defaults, corpo);
 
         let tipo_retorno_str = metodo
             .tipo_retorno
@@ -425,13 +570,11 @@ impl<'a> BytecodeGenerator<'a> {
             ))],
         };
 
-        let mut sub = BytecodeGenerator {
+        let sub = BytecodeGenerator {
             programa: &sub_prog,
             type_checker: self.type_checker,
             namespace_path: self.namespace_path.clone(),
             bytecode_instructions: Vec::new(),
-            props_por_classe: self.props_por_classe.clone(),
-            construtor_params_por_classe: self.construtor_params_por_classe.clone(),
             current_class_name: Some(nome_classe.to_string()),
             current_params: Some(
                 metodo
@@ -440,12 +583,15 @@ impl<'a> BytecodeGenerator<'a> {
                     .map(|p| p.nome.clone())
                     .collect::<HashSet<String>>(),
             ),
+            metodo_estatico: true,
+            saltos: Vec::new(),
+            definicoes: 0,
         };
-        let mut corpo = sub.generate();
+        let mut corpo = sub.gerar_trechos().topo;
 
-        if !matches!(corpo.last(), Some(op) if op == "RETURN") {
-            corpo.push("LOAD_CONST_NULL".to_string());
-            corpo.push("RETURN".to_string());
+        if !matches!(corpo.instrucoes.last(), Some(op) if op == "RETURN") {
+            corpo.instrucoes.push("LOAD_CONST_NULL".to_string());
+            corpo.instrucoes.push("RETURN".to_string());
         }
 
         let mut corpo_com_defaults = Vec::new();
@@ -460,8 +606,7 @@ impl<'a> BytecodeGenerator<'a> {
                 ));
             }
         }
-        corpo_com_defaults.extend(corpo);
-        let corpo = corpo_com_defaults;
+        let corpo = prefixar_corpo(corpo_com_defaults, corpo);
 
         let tipo_retorno_str = metodo
             .tipo_retorno
@@ -486,59 +631,77 @@ impl<'a> BytecodeGenerator<'a> {
     }
 
     pub fn generate(&mut self) -> Vec<String> {
-        // Itera sobre as declarações no nível raiz do programa
-        for declaracao in &self.programa.declaracoes {
-            self.generate_declaracao(declaracao);
-        }
-
-        // Também processa namespaces de primeiro nível
-        for namespace in &self.programa.namespaces {
-            // Cria gerador dedicado com o caminho do namespace
-            let mut sub = BytecodeGenerator {
-                programa: &ast::Programa {
-                    usings: vec![],
-                    namespaces: vec![],
-                    declaracoes: namespace.declaracoes.clone(),
-                },
-                type_checker: self.type_checker,
-                namespace_path: namespace.nome.clone(),
-                bytecode_instructions: Vec::new(),
-                props_por_classe: self.props_por_classe.clone(),
-                construtor_params_por_classe: self.construtor_params_por_classe.clone(),
-                current_class_name: None,
-                current_params: None,
-            };
-            self.bytecode_instructions.extend(sub.generate());
-        }
-
-        std::mem::take(&mut self.bytecode_instructions)
+        juntar_trechos(&[self.gerar_trechos()])
     }
 
     pub fn generate_for_library(&mut self) -> Vec<String> {
-        for declaracao in &self.programa.declaracoes {
-            self.generate_declaracao(declaracao);
+        self.generate()
+    }
+
+    /// Bytecode do programa separado por namespace. Cada trecho depende só das próprias
+    /// declarações e do verificador de tipos, então o de um arquivo pode ser gerado (ou
+    /// reaproveitado) sozinho e juntado aos demais com [`juntar_trechos`].
+    pub fn gerar_trechos(&self) -> Trechos {
+        Trechos {
+            topo: self.gerar_trecho(&self.namespace_path, &self.programa.declaracoes),
+            namespaces: self
+                .programa
+                .namespaces
+                .iter()
+                .map(|ns| (ns.nome.clone(), self.gerar_trecho(&ns.nome, &ns.declaracoes)))
+                .collect(),
         }
+    }
 
-        for namespace in &self.programa.namespaces {
+    /// O bytecode de cada declaração também não depende das outras: elas são geradas em
+    /// paralelo e concatenadas na ordem do fonte.
+    fn gerar_trecho(&self, namespace: &str, declaracoes: &[ast::Declaracao]) -> Trecho {
+        let gerados = paralelo::mapear(declaracoes, |declaracao| {
             let mut sub = BytecodeGenerator {
-                programa: &ast::Programa {
-                    usings: vec![],
-                    namespaces: vec![],
-                    declaracoes: namespace.declaracoes.clone(),
-                },
-                type_checker: self.type_checker,
-                namespace_path: namespace.nome.clone(),
-                bytecode_instructions: Vec::new(),
-                props_por_classe: self.props_por_classe.clone(),
-                construtor_params_por_classe: self.construtor_params_por_classe.clone(),
-                current_class_name: None,
-                current_params: None,
+                namespace_path: namespace.to_string(),
+                ..self.spawn_child()
             };
-            self.bytecode_instructions
-                .extend(sub.generate_for_library());
+            sub.generate_declaracao(declaracao);
+            sub.trecho()
+        });
+        let mut trecho = Trecho::default();
+        for gerado in gerados {
+            trecho.anexar(gerado);
         }
+        trecho
+    }
 
-        std::mem::take(&mut self.bytecode_instructions)
+    /// Propriedades e campos de `fqn` e das suas ancestrais no programa, das mais antigas para
+    /// as mais novas (a lista do `DEFINE_CLASS` de uma subclasse começa por elas).
+    fn propriedades_herdadas(&self, fqn: &str) -> Vec<String> {
+        let mut cadeia = Vec::new();
+        let mut atual = fqn.to_string();
+        while let Some(classe) = self.get_class_declaration(&atual) {
+            if cadeia.iter().any(|(nome, _)| *nome == atual) {
+                break; // herança circular, já apontada pelo verificador
+            }
+            let namespace = atual.rsplit_once('.').map_or("", |(ns, _)| ns).to_string();
+            let pai = classe.classe_pai.as_ref().and_then(|p| match p {
+                ast::Tipo::Classe(n) | ast::Tipo::Aplicado { nome: n, .. } => Some(n.clone()),
+                _ => None,
+            });
+            cadeia.push((atual, classe));
+            match pai {
+                Some(pai) => atual = self.type_checker.resolver_nome_classe(&pai, &namespace),
+                None => break,
+            }
+        }
+        cadeia
+            .iter()
+            .rev()
+            .flat_map(|(_, classe)| {
+                classe
+                    .propriedades
+                    .iter()
+                    .map(|p| p.nome.clone())
+                    .chain(classe.campos.iter().map(|c| c.nome.clone()))
+            })
+            .collect()
     }
 
     // Altera a assinatura para `&mut self` e remove o retorno Vec<String>
@@ -609,19 +772,28 @@ impl<'a> BytecodeGenerator<'a> {
 
             // Adicionado: Comando 'enquanto'
             ast::Comando::Enquanto(condicao, corpo) => {
-                let loop_start_ip = self.bytecode_instructions.len(); // Ponto de início do loop
+                // O `LINE` do próprio `enquanto` fica dentro do laço: cada avaliação da condição
+                // é um passo do teste de mesa
+                let linha_do_laco = self
+                    .bytecode_instructions
+                    .last()
+                    .is_some_and(|i| i.starts_with("LINE "));
+                // Ponto de início do loop
+                let loop_start_ip = self.posicao_global() - usize::from(linha_do_laco);
 
                 self.generate_expressao(condicao); // Gera código para a condição
                 let jump_if_false_placeholder_ip = self.bytecode_instructions.len();
+                self.saltos.push(jump_if_false_placeholder_ip);
                 self.bytecode_instructions
                     .push("JUMP_IF_FALSE 0".to_string()); // Placeholder para o salto para o final do loop
 
                 self.generate_comando(corpo); // Gera código para o corpo do loop
 
+                self.saltos.push(self.bytecode_instructions.len());
                 self.bytecode_instructions
                     .push(format!("JUMP {}", loop_start_ip)); // Salta de volta para o início da condição
 
-                let loop_end_ip = self.bytecode_instructions.len(); // Ponto final do loop
+                let loop_end_ip = self.posicao_global(); // Ponto final do loop
                                                                     // Patching: Atualiza a instrução JUMP_IF_FALSE com o endereço real
                 self.bytecode_instructions[jump_if_false_placeholder_ip] =
                     format!("JUMP_IF_FALSE {}", loop_end_ip);
@@ -631,6 +803,7 @@ impl<'a> BytecodeGenerator<'a> {
             ast::Comando::Se(condicao, bloco_if, bloco_else) => {
                 self.generate_expressao(condicao);
                 let jump_if_false_placeholder = self.bytecode_instructions.len();
+                self.saltos.push(jump_if_false_placeholder);
                 self.bytecode_instructions
                     .push("JUMP_IF_FALSE 0".to_string());
 
@@ -638,19 +811,20 @@ impl<'a> BytecodeGenerator<'a> {
 
                 if let Some(else_bloco) = bloco_else {
                     let jump_to_end_placeholder = self.bytecode_instructions.len();
+                    self.saltos.push(jump_to_end_placeholder);
                     self.bytecode_instructions.push("JUMP 0".to_string());
 
-                    let else_start_pos = self.bytecode_instructions.len();
+                    let else_start_pos = self.posicao_global();
                     self.bytecode_instructions[jump_if_false_placeholder] =
                         format!("JUMP_IF_FALSE {}", else_start_pos);
 
                     self.generate_comando(else_bloco);
 
-                    let end_pos = self.bytecode_instructions.len();
+                    let end_pos = self.posicao_global();
                     self.bytecode_instructions[jump_to_end_placeholder] =
                         format!("JUMP {}", end_pos);
                 } else {
-                    let end_pos = self.bytecode_instructions.len();
+                    let end_pos = self.posicao_global();
                     self.bytecode_instructions[jump_if_false_placeholder] =
                         format!("JUMP_IF_FALSE {}", end_pos);
                 }
@@ -710,7 +884,12 @@ impl<'a> BytecodeGenerator<'a> {
                     if let Some(classe_info) =
                         self.type_checker.resolved_classes.get(&full_class_name)
                     {
-                        if classe_info.eh_estatica {
+                        if classe_info.eh_estatica
+                            || classe_info
+                                .methods
+                                .get(metodo)
+                                .is_some_and(|m| m.eh_estatica)
+                        {
                             is_static_call = true;
                             class_fqn_opt = Some(full_class_name);
                         }
@@ -720,7 +899,12 @@ impl<'a> BytecodeGenerator<'a> {
                             if let Some(LibSimbolo::Classe(lib_classe)) =
                                 bib.simbolos.get(&full_class_name)
                             {
-                                if lib_classe.eh_estatica {
+                                if lib_classe.eh_estatica
+                                    || lib_classe
+                                        .metodos
+                                        .get(metodo)
+                                        .is_some_and(|m| m.eh_estatica)
+                                {
                                     is_static_call = true;
                                     class_fqn_opt = Some(full_class_name);
                                 }
@@ -771,7 +955,8 @@ impl<'a> BytecodeGenerator<'a> {
                                 if let Some(chave_nativa) = &lib_metodo.chave_nativa {
                                     // É uma chamada nativa da biblioteca externa
                                     // Determinar se é estática baseando-se na classe
-                                    let eh_estatica = lib_classe.eh_estatica;
+                                    let eh_estatica =
+                                        lib_classe.eh_estatica || lib_metodo.eh_estatica;
                                     if eh_estatica {
                                         for arg in argumentos {
                                             self.generate_expressao(arg);
@@ -882,6 +1067,13 @@ impl<'a> BytecodeGenerator<'a> {
                 self.generate_expressao(e);
             }
 
+            // Marca a linha do fonte (consumida pelo teste de mesa do interpretador); o arquivo
+            // vai no resto da instrução, que pode ter espaços
+            ast::Comando::Posicao(pos) => {
+                self.bytecode_instructions
+                    .push(format!("LINE {} {}", pos.linha, pos.arquivo));
+            }
+
             // Para outros comandos não implementados, remova a linha de comentário e implemente se necessário
             _ => { /* Fazer nada ou adicionar tratamento para outros comandos */ }
         }
@@ -972,7 +1164,11 @@ impl<'a> BytecodeGenerator<'a> {
                         .type_checker
                         .resolver_nome_classe(class_name, &self.namespace_path);
                     // if self.type_checker.is_static_class(&full_class_name) {
-                    if self.type_checker.is_static_class(&full_class_name) {
+                    if self.type_checker.is_static_class(&full_class_name)
+                        || self
+                            .type_checker
+                            .eh_membro_estatico_externo(&full_class_name, membro)
+                    {
                         // Acesso a membro estático
                         self.bytecode_instructions.push(format!(
                             "GET_STATIC_PROPERTY {} {}",
@@ -984,12 +1180,22 @@ impl<'a> BytecodeGenerator<'a> {
                     let fqn_enum = self
                         .type_checker
                         .resolver_nome_enum(class_name, &self.namespace_path);
-                    if let Some(en) = self.type_checker.enums.get(&fqn_enum) {
-                        if let Some(idx) = en.valores.iter().position(|v| v == membro) {
-                            self.bytecode_instructions
-                                .push(format!("LOAD_CONST_INT {}", idx));
-                            return;
-                        }
+                    let valores = match self.type_checker.enums.get(&fqn_enum) {
+                        Some(en) => Some(&en.valores),
+                        None => match self
+                            .type_checker
+                            .biblioteca_externa
+                            .as_ref()
+                            .and_then(|bib| bib.simbolos.get(&fqn_enum))
+                        {
+                            Some(LibSimbolo::Enum(en)) => Some(&en.valores),
+                            _ => None,
+                        },
+                    };
+                    if let Some(idx) = valores.and_then(|v| v.iter().position(|v| v == membro)) {
+                        self.bytecode_instructions
+                            .push(format!("LOAD_CONST_INT {}", idx));
+                        return;
                     }
                 }
 
@@ -1157,6 +1363,31 @@ impl<'a> BytecodeGenerator<'a> {
             }
 
             ast::Expressao::Chamada(nome_funcao, argumentos) => {
+                // Dentro de uma classe, `Metodo()` é `este.Metodo()` (ou a chamada estática); em
+                // método estático não há `este`, então só os estáticos são chamados assim
+                if let Some((classe, estatico)) = self
+                    .metodo_da_classe_atual(nome_funcao)
+                    .filter(|(_, estatico)| *estatico || !self.metodo_estatico)
+                {
+                    if !estatico {
+                        self.bytecode_instructions
+                            .push("LOAD_VAR este".to_string());
+                    }
+                    for arg in argumentos {
+                        self.generate_expressao(arg);
+                    }
+                    self.bytecode_instructions.push(if estatico {
+                        format!(
+                            "CALL_STATIC_METHOD {} {} {}",
+                            classe,
+                            nome_funcao,
+                            argumentos.len()
+                        )
+                    } else {
+                        format!("CALL_METHOD {} {}", nome_funcao, argumentos.len())
+                    });
+                    return;
+                }
                 for arg in argumentos {
                     self.generate_expressao(arg);
                 }
@@ -1195,7 +1426,12 @@ impl<'a> BytecodeGenerator<'a> {
                     if let Some(classe_info) =
                         self.type_checker.resolved_classes.get(&full_class_name)
                     {
-                        if classe_info.eh_estatica {
+                        if classe_info.eh_estatica
+                            || classe_info
+                                .methods
+                                .get(nome_metodo)
+                                .is_some_and(|m| m.eh_estatica)
+                        {
                             is_static_call = true;
                             class_fqn_opt = Some(full_class_name);
                         }
@@ -1205,7 +1441,12 @@ impl<'a> BytecodeGenerator<'a> {
                             if let Some(LibSimbolo::Classe(lib_classe)) =
                                 bib.simbolos.get(&full_class_name)
                             {
-                                if lib_classe.eh_estatica {
+                                if lib_classe.eh_estatica
+                                    || lib_classe
+                                        .metodos
+                                        .get(nome_metodo)
+                                        .is_some_and(|m| m.eh_estatica)
+                                {
                                     is_static_call = true;
                                     class_fqn_opt = Some(full_class_name);
                                 }
@@ -1254,7 +1495,8 @@ impl<'a> BytecodeGenerator<'a> {
                             if let Some(lib_metodo) = lib_classe.metodos.get(nome_metodo) {
                                 if let Some(chave_nativa) = &lib_metodo.chave_nativa {
                                     // É uma chamada nativa da biblioteca externa
-                                    let eh_estatica = lib_classe.eh_estatica;
+                                    let eh_estatica =
+                                        lib_classe.eh_estatica || lib_metodo.eh_estatica;
                                     if eh_estatica {
                                         for arg in argumentos {
                                             self.generate_expressao(arg);
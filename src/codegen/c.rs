@@ -0,0 +1,3376 @@
+//! Gerador de código C (C99) para o alvo `--target=c`.
+//!
+//! Alternativa portátil ao LLVM IR: o programa vira um único arquivo `.c` legível, compilado
+//! com qualquer compilador C99 (gcc, clang, tcc) junto com o runtime em `src/runtime`
+//! (`c_runtime.c`, `gc_runtime.c` e `async_runtime.c`).
+//!
+//! - Classes viram structs cujo primeiro membro é a struct da classe pai (`base`), ou o
+//!   cabeçalho `PdsObjeto` nas classes raiz. Campos e propriedades automáticas são membros.
+//! - Cada classe tem uma vtable (`<Classe>_vtable`) que começa com a vtable da classe pai, como
+//!   as do alvo LLVM (`build_all_vtables`): métodos redefiníveis, abstratos ou redefinidos em
+//!   alguma subclasse ganham uma entrada; os demais são chamados diretamente.
+//! - Chamadas em valores do tipo interface ou `objeto` passam por funções de despacho pelo nome
+//!   do método, que comparam o descritor da classe do objeto.
+//! - `inteiro` é `int64_t`, `decimal` é `double`, `texto` é `const char *` (NULL é `nulo`) e
+//!   arrays são `PdsArray *`. Toda a memória vem do coletor de lixo do runtime.
+//! - `aguarde` em funções `assíncrona` executa a função no pool de threads de `async_runtime.c`.
+//!
+//! A semântica acompanha o interpretador de AST (a referência do teste diferencial): ordem de
+//! avaliação da esquerda para a direita, exibição dos valores e mensagens dos erros de execução.
+
+use super::tabelas::{
+    chave_nativa, erro_metodo_texto, nome_do_tipo, AlvoEstatico, Escopo, Membro, Tabelas,
+    TipoNomeado, Variavel,
+};
+use crate::ast;
+use crate::type_checker;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Tipo de um valor no código C gerado (os tipos da linguagem já resolvidos).
+#[derive(Debug, Clone, PartialEq)]
+enum TipoC {
+    Inteiro,
+    Booleano,
+    Flutuante,
+    Duplo,
+    Texto,
+    Vazio,
+    Array(Box<TipoC>),
+    /// FQN de uma classe do programa.
+    Classe(String),
+    /// FQN de uma interface; o valor é um `PdsObjeto *`.
+    Interface(String),
+    /// `objeto`, parâmetros genéricos e classes desconhecidas.
+    Objeto,
+    /// Tipo do literal `nulo`.
+    Nulo,
+}
+
+impl TipoC {
+    fn numerico(&self) -> bool {
+        matches!(self, TipoC::Inteiro | TipoC::Flutuante | TipoC::Duplo)
+    }
+
+    fn referencia(&self) -> bool {
+        matches!(
+            self,
+            TipoC::Array(_) | TipoC::Classe(_) | TipoC::Interface(_) | TipoC::Objeto | TipoC::Nulo
+        )
+    }
+
+    /// Guardado como `PdsObjeto *` (pode conter caixas de valores primitivos).
+    fn dinamico(&self) -> bool {
+        matches!(self, TipoC::Interface(_) | TipoC::Objeto)
+    }
+}
+
+/// Efeito de avaliar uma expressão, usado para preservar a ordem da esquerda para a direita
+/// (o C não define a ordem de avaliação dos operandos).
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+enum Ordem {
+    /// Literais e variáveis locais: nenhuma chamada pode alterá-los.
+    Estavel,
+    /// Lê campos, estáticos ou itens de arrays.
+    Leitura,
+    /// Chama funções ou pode terminar o programa com um erro.
+    Efeito,
+}
+
+/// Expressão C já gerada.
+#[derive(Debug, Clone)]
+struct Expr {
+    codigo: String,
+    tipo: TipoC,
+    ordem: Ordem,
+    /// Não precisa de parênteses como operando (nomes, literais, chamadas, acessos).
+    atomico: bool,
+    /// Referência que nunca é nula (`este`, objetos recém-criados).
+    nao_nulo: bool,
+    /// Partes de uma concatenação de textos, para juntar `a + b + c` em uma chamada.
+    partes: Vec<String>,
+}
+
+impl Expr {
+    fn novo(codigo: impl Into<String>, tipo: TipoC, ordem: Ordem, atomico: bool) -> Self {
+        Self {
+            codigo: codigo.into(),
+            tipo,
+            ordem,
+            atomico,
+            nao_nulo: false,
+            partes: Vec::new(),
+        }
+    }
+
+    fn atomo(&self) -> String {
+        if self.atomico {
+            self.codigo.clone()
+        } else {
+            format!("({})", self.codigo)
+        }
+    }
+}
+
+/// Função de despacho pelo nome do método, para receptores do tipo interface ou `objeto`.
+struct Despacho {
+    nome: String,
+    parametros: Vec<TipoC>,
+    retorno: TipoC,
+}
+
+/// Palavras reservadas do C e nomes dos cabeçalhos incluídos pelo código gerado.
+const RESERVADAS: &[&str] = &[
+    "auto",
+    "break",
+    "case",
+    "char",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extern",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "inline",
+    "int",
+    "long",
+    "register",
+    "restrict",
+    "return",
+    "short",
+    "signed",
+    "sizeof",
+    "static",
+    "struct",
+    "switch",
+    "typedef",
+    "union",
+    "unsigned",
+    "void",
+    "volatile",
+    "while",
+    "bool",
+    "true",
+    "false",
+    "NULL",
+    "main",
+    "int8_t",
+    "int16_t",
+    "int32_t",
+    "int64_t",
+    "uint8_t",
+    "uint16_t",
+    "uint32_t",
+    "uint64_t",
+    "size_t",
+    "ptrdiff_t",
+    "wchar_t",
+    "offsetof",
+    "Task",
+    "TaskFunction",
+    "TaskStatus",
+    "PdsObjeto",
+    "PdsClasse",
+    "PdsArray",
+    "PdsElemento",
+    "este",
+    "objeto",
+    "errno",
+];
+
+/// Prefixos usados pelo runtime e pelos nomes internos do código gerado.
+const PREFIXOS_RESERVADOS: &[&str] = &["pds_", "PDS_", "task_", "TASK_", "thread_pool_"];
+
+/// Identificador C para um nome da linguagem: letras acentuadas perdem o acento e os demais
+/// caracteres não ASCII viram `_uXXXX`.
+fn identificador(nome: &str) -> String {
+    let mut c = String::with_capacity(nome.len());
+    for ch in nome.chars() {
+        match ch {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' => c.push(ch),
+            'á' | 'à' | 'â' | 'ã' | 'ä' => c.push('a'),
+            'Á' | 'À' | 'Â' | 'Ã' | 'Ä' => c.push('A'),
+            'é' | 'è' | 'ê' | 'ë' => c.push('e'),
+            'É' | 'È' | 'Ê' | 'Ë' => c.push('E'),
+            'í' | 'ì' | 'î' | 'ï' => c.push('i'),
+            'Í' | 'Ì' | 'Î' | 'Ï' => c.push('I'),
+            'ó' | 'ò' | 'ô' | 'õ' | 'ö' => c.push('o'),
+            'Ó' | 'Ò' | 'Ô' | 'Õ' | 'Ö' => c.push('O'),
+            'ú' | 'ù' | 'û' | 'ü' => c.push('u'),
+            'Ú' | 'Ù' | 'Û' | 'Ü' => c.push('U'),
+            'ç' => c.push('c'),
+            'Ç' => c.push('C'),
+            outro => c.push_str(&format!("_u{:04x}", outro as u32)),
+        }
+    }
+    if c.starts_with(|ch: char| ch.is_ascii_digit()) {
+        c.insert(0, '_');
+    }
+    if RESERVADAS.contains(&c.as_str()) || PREFIXOS_RESERVADOS.iter().any(|p| c.starts_with(p)) {
+        c.push('_');
+    }
+    c
+}
+
+/// Nome C de um símbolo qualificado (`Espaco.Classe` → `Espaco_Classe`).
+fn nome_global(fqn: &str) -> String {
+    fqn.split('.')
+        .map(identificador)
+        .collect::<Vec<_>>()
+        .join("_")
+}
+
+/// Literal de texto C. Bytes UTF-8 vão sem escape; controles usam octal.
+fn literal_texto(texto: &str) -> String {
+    let mut c = String::from("\"");
+    let mut anterior = '\0';
+    for ch in texto.chars() {
+        match ch {
+            '"' => c.push_str("\\\""),
+            '\\' => c.push_str("\\\\"),
+            '\n' => c.push_str("\\n"),
+            '\t' => c.push_str("\\t"),
+            '\r' => c.push_str("\\r"),
+            // Evita trígrafos (`??=`)
+            '?' if anterior == '?' => c.push_str("\\?"),
+            ch if (ch as u32) < 0x20 || ch as u32 == 0x7f => {
+                c.push_str(&format!("\\{:03o}", ch as u32))
+            }
+            ch => c.push(ch),
+        }
+        anterior = ch;
+    }
+    c.push('"');
+    c
+}
+
+/// Literal real C a partir do literal da linguagem (`2.5f`, `1e3`, `10.25m`).
+fn literal_real(texto: &str, sufixo: &str) -> String {
+    let numero = texto.trim_end_matches(['f', 'F', 'm', 'M', 'd', 'D']);
+    let mut c = numero.to_string();
+    if !c.contains(['.', 'e', 'E']) {
+        c.push_str(".0");
+    }
+    c.push_str(sufixo);
+    c
+}
+
+fn externo(func: &ast::DeclaracaoFuncao) -> Option<(&str, &str)> {
+    let atributo = func.attributes.iter().find(|a| a.name == "Externo")?;
+    match atributo.arguments.as_slice() {
+        [ast::Expressao::Texto(biblioteca), ast::Expressao::Texto(simbolo)] => {
+            Some((biblioteca, simbolo))
+        }
+        _ => None,
+    }
+}
+
+/// Tipo C de parâmetros e retornos de funções externas (o verificador só aceita estes).
+fn tipo_externo(tipo: &TipoC) -> &'static str {
+    match tipo {
+        TipoC::Inteiro | TipoC::Booleano => "int",
+        TipoC::Duplo => "double",
+        TipoC::Flutuante => "float",
+        TipoC::Texto => "const char *",
+        _ => "void",
+    }
+}
+
+/// O corpo gerado usa `este` (locais com esse nome são renomeados por `identificador`).
+fn usa_este(corpo: &str) -> bool {
+    corpo.match_indices("este").any(|(i, _)| {
+        let antes = corpo[..i].chars().next_back();
+        let depois = corpo[i + 4..].chars().next();
+        ![antes, depois]
+            .iter()
+            .flatten()
+            .any(|c| c.is_ascii_alphanumeric() || *c == '_')
+    })
+}
+
+/// Declaração C `tipo nome`, sem espaço depois de `*`.
+fn declarar(tipo: &str, nome: &str) -> String {
+    if tipo.ends_with('*') {
+        format!("{}{}", tipo, nome)
+    } else {
+        format!("{} {}", tipo, nome)
+    }
+}
+
+/// O gerador de código para o alvo C.
+pub struct CGenerator<'a> {
+    /// Classes, funções e enumerações do programa, com os nomes resolvidos pelo verificador.
+    tabelas: Tabelas<'a>,
+    /// Classe -> entradas da vtable (método, classe que introduz a entrada), herdadas primeiro.
+    vtables: HashMap<String, Vec<(String, String)>>,
+    /// Nomes C de funções e globais, que variáveis locais não podem esconder.
+    nomes_globais: HashSet<String>,
+    despachos: BTreeMap<(String, usize), Despacho>,
+    bibliotecas_externas: Vec<String>,
+    /// Verificações de execução (índices, referências nulas, divisão por zero); desligadas por
+    /// `disable_runtime_checks` (`--sem-verificacoes`).
+    verificacoes: bool,
+    posicao: Option<ast::Posicao>,
+    // Estado da função em geração
+    namespace: String,
+    classe_atual: Option<String>,
+    tem_este: bool,
+    escopos: Vec<HashMap<String, (String, TipoC)>>,
+    locais_usados: HashSet<String>,
+    retorno: TipoC,
+    prelude: Vec<String>,
+    corpo: String,
+    nivel: usize,
+    temporarios: usize,
+}
+
+impl<'a> Escopo<'a, (String, TipoC)> for CGenerator<'a> {
+    fn tabelas(&self) -> &Tabelas<'a> {
+        &self.tabelas
+    }
+
+    fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    fn classe_atual(&self) -> Option<&str> {
+        self.classe_atual.as_deref()
+    }
+
+    fn tem_este(&self) -> bool {
+        self.tem_este
+    }
+
+    /// Variável local (ou parâmetro) visível no escopo atual.
+    fn local(&self, nome: &str) -> Option<(String, TipoC)> {
+        self.escopos.iter().rev().find_map(|e| e.get(nome).cloned())
+    }
+}
+
+impl<'a> CGenerator<'a> {
+    pub fn new(
+        programa: &'a ast::Programa,
+        type_checker: &'a type_checker::VerificadorTipos<'a>,
+    ) -> Self {
+        Self {
+            tabelas: Tabelas::new(programa, type_checker, nome_global, nome_global),
+            vtables: HashMap::new(),
+            nomes_globais: HashSet::new(),
+            despachos: BTreeMap::new(),
+            bibliotecas_externas: Vec::new(),
+            verificacoes: true,
+            posicao: None,
+            namespace: String::new(),
+            classe_atual: None,
+            tem_este: false,
+            escopos: Vec::new(),
+            locais_usados: HashSet::new(),
+            retorno: TipoC::Vazio,
+            prelude: Vec::new(),
+            corpo: String::new(),
+            nivel: 0,
+            temporarios: 0,
+        }
+    }
+
+    /// Gera acessos por índice, a membros e divisões sem as verificações de execução.
+    pub fn disable_runtime_checks(&mut self) {
+        self.verificacoes = false;
+    }
+
+    /// Bibliotecas C usadas pelas funções externas do programa, na ordem em que aparecem.
+    pub fn external_libraries(&self) -> &[String] {
+        &self.bibliotecas_externas
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* TABELAS DO PROGRAMA                                                 */
+    /* ------------------------------------------------------------------ */
+
+    fn profundidade(&self, fqn: &str) -> usize {
+        self.tabelas.hierarquia(fqn).len() - 1
+    }
+
+    /// Campo ou propriedade de instância: caminho C a partir de um ponteiro da classe `fqn`
+    /// (`base.base.Nome`), tipo e classe que o declara.
+    fn buscar_campo(&self, fqn: &str, nome: &str) -> Option<(String, TipoC, String)> {
+        let membro = self.tabelas.buscar_membro(fqn, nome, false)?;
+        Some(self.campo(nome, &membro))
+    }
+
+    fn campo(&self, nome: &str, membro: &Membro) -> (String, TipoC, String) {
+        let caminho = format!("{}{}", "base.".repeat(membro.nivel), identificador(nome));
+        (
+            caminho,
+            self.tipo_c(membro.tipo, &membro.classe.namespace),
+            membro.classe.fqn.clone(),
+        )
+    }
+
+    /// Classe (ou ancestral) que declara o membro estático `nome`, e o tipo dele.
+    fn dono_estatico(&self, fqn: &str, nome: &str) -> Option<(String, TipoC)> {
+        let membro = self.tabelas.buscar_membro(fqn, nome, true)?;
+        Some((
+            membro.classe.fqn.clone(),
+            self.tipo_c(membro.tipo, &membro.classe.namespace),
+        ))
+    }
+
+    fn estatico_c(&self, classe: &str, nome: &str) -> String {
+        format!(
+            "{}_{}",
+            self.tabelas.classes[classe].simbolo,
+            identificador(nome)
+        )
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* TIPOS                                                               */
+    /* ------------------------------------------------------------------ */
+
+    fn tipo_c(&self, tipo: &ast::Tipo, namespace: &str) -> TipoC {
+        match tipo {
+            ast::Tipo::Inteiro | ast::Tipo::Enum(_) => TipoC::Inteiro,
+            ast::Tipo::Booleano => TipoC::Booleano,
+            ast::Tipo::Texto => TipoC::Texto,
+            ast::Tipo::Flutuante => TipoC::Flutuante,
+            ast::Tipo::Duplo | ast::Tipo::Decimal => TipoC::Duplo,
+            ast::Tipo::Vazio => TipoC::Vazio,
+            ast::Tipo::Lista(item) => TipoC::Array(Box::new(self.tipo_c(item, namespace))),
+            ast::Tipo::Opcional(tipo) => self.tipo_c(tipo, namespace),
+            ast::Tipo::Classe(nome) | ast::Tipo::Aplicado { nome, .. } => {
+                if nome == "objeto" {
+                    return TipoC::Objeto;
+                }
+                match self.tabelas.tipo_nomeado(namespace, nome) {
+                    Some(TipoNomeado::Classe(classe)) => TipoC::Classe(classe),
+                    Some(TipoNomeado::Enum(_)) => TipoC::Inteiro,
+                    Some(TipoNomeado::Interface(interface)) => TipoC::Interface(interface),
+                    None => TipoC::Objeto,
+                }
+            }
+            ast::Tipo::Objeto
+            | ast::Tipo::Generico(_)
+            | ast::Tipo::Funcao(..)
+            | ast::Tipo::Inferido => TipoC::Objeto,
+        }
+    }
+
+    fn tipo_retorno(&self, tipo: &Option<ast::Tipo>, namespace: &str) -> TipoC {
+        tipo.as_ref()
+            .map(|t| self.tipo_c(t, namespace))
+            .unwrap_or(TipoC::Vazio)
+    }
+
+    fn c_tipo(&self, tipo: &TipoC) -> String {
+        match tipo {
+            TipoC::Inteiro => "int64_t".to_string(),
+            TipoC::Booleano => "bool".to_string(),
+            TipoC::Flutuante => "float".to_string(),
+            TipoC::Duplo => "double".to_string(),
+            TipoC::Texto => "const char *".to_string(),
+            TipoC::Vazio => "void".to_string(),
+            TipoC::Array(_) => "PdsArray *".to_string(),
+            TipoC::Classe(fqn) => format!("{} *", self.tabelas.classes[fqn].simbolo),
+            TipoC::Interface(_) | TipoC::Objeto | TipoC::Nulo => "PdsObjeto *".to_string(),
+        }
+    }
+
+    fn elemento(tipo: &TipoC) -> &'static str {
+        match tipo {
+            TipoC::Inteiro => "PDS_ELEMENTO_INTEIRO",
+            TipoC::Flutuante => "PDS_ELEMENTO_FLUTUANTE",
+            TipoC::Duplo => "PDS_ELEMENTO_DUPLO",
+            TipoC::Booleano => "PDS_ELEMENTO_BOOLEANO",
+            TipoC::Texto => "PDS_ELEMENTO_TEXTO",
+            _ => "PDS_ELEMENTO_OBJETO",
+        }
+    }
+
+    /// Valor de variáveis sem inicializador, como `ValorRuntime::padrao` do interpretador.
+    fn valor_padrao(tipo: &TipoC) -> &'static str {
+        match tipo {
+            TipoC::Inteiro => "0",
+            TipoC::Flutuante => "0.0f",
+            TipoC::Duplo => "0.0",
+            TipoC::Booleano => "false",
+            TipoC::Texto => "\"\"",
+            _ => "NULL",
+        }
+    }
+
+    /// Tipo dos itens de um literal `[a, b, ...]`.
+    fn tipo_itens(&self, tipos: &[TipoC]) -> TipoC {
+        let mut comum: Option<TipoC> = None;
+        for tipo in tipos.iter().filter(|t| **t != TipoC::Nulo) {
+            comum = Some(match (comum, tipo) {
+                (None, t) => t.clone(),
+                (Some(a), b) if a == *b => a,
+                (Some(TipoC::Classe(a)), TipoC::Classe(b)) => self
+                    .tabelas
+                    .ancestral_comum(&a, b)
+                    .map(TipoC::Classe)
+                    .unwrap_or(TipoC::Objeto),
+                _ => TipoC::Objeto,
+            });
+        }
+        match comum {
+            Some(tipo) => tipo,
+            None => TipoC::Objeto,
+        }
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* VTABLES                                                             */
+    /* ------------------------------------------------------------------ */
+
+    /// Classe que introduz a entrada da vtable do método, se ele é chamado pela vtable.
+    fn entrada_vtable(&self, classe: &str, metodo: &str) -> Option<String> {
+        self.vtables
+            .get(classe)?
+            .iter()
+            .find(|(nome, _)| nome == metodo)
+            .map(|(_, introduz)| introduz.clone())
+    }
+
+    /// Tipo do primeiro parâmetro do método `metodo` declarado em `classe`: a classe que
+    /// introduz a entrada da vtable, para que as implementações tenham a mesma assinatura.
+    fn receptor(&self, classe: &str, metodo: &str) -> String {
+        self.entrada_vtable(classe, metodo)
+            .unwrap_or_else(|| classe.to_string())
+    }
+
+    fn metodo_c(&self, classe: &str, metodo: &str) -> String {
+        format!(
+            "{}_{}",
+            self.tabelas.classes[classe].simbolo,
+            identificador(metodo)
+        )
+    }
+
+    /// Assinatura C (retorno, parâmetros) de um método, sem o receptor.
+    fn assinatura(&self, classe: &str, metodo: &ast::MetodoClasse) -> (TipoC, Vec<TipoC>) {
+        let namespace = &self.tabelas.classes[classe].namespace;
+        (
+            self.tipo_retorno(&metodo.tipo_retorno, namespace),
+            metodo
+                .parametros
+                .iter()
+                .map(|p| self.tipo_c(&p.tipo, namespace))
+                .collect(),
+        )
+    }
+
+    /// Ponteiros de função de um método na vtable (`void (*falar)(Animal *este)`).
+    fn ponteiro_metodo(&self, classe: &str, metodo: &ast::MetodoClasse) -> String {
+        let (retorno, parametros) = self.assinatura(classe, metodo);
+        let mut lista = vec![format!("{} *", self.tabelas.classes[classe].simbolo)];
+        lista.extend(parametros.iter().map(|p| self.c_tipo(p)));
+        let retorno = self.c_tipo(&retorno);
+        format!(
+            "{}(*{})({})",
+            if retorno.ends_with('*') {
+                retorno
+            } else {
+                format!("{} ", retorno)
+            },
+            identificador(&metodo.nome),
+            lista.join(", ")
+        )
+    }
+
+    /// Classe mais próxima (ela mesma ou ancestral) com inicializadores de campos de instância.
+    fn inicializador(&self, fqn: &str) -> Option<String> {
+        self.tabelas
+            .hierarquia(fqn)
+            .into_iter()
+            .find(|c| {
+                c.decl
+                    .campos
+                    .iter()
+                    .any(|f| !f.eh_estatica && f.valor_inicial.is_some())
+                    || c.decl
+                        .propriedades
+                        .iter()
+                        .any(|p| !p.eh_estatica && p.valor_inicial.is_some())
+            })
+            .map(|c| c.fqn.clone())
+    }
+
+    /// Classe que declara o campo `Nome` exibido por `imprima(objeto)`.
+    fn classe_do_nome(&self, fqn: &str) -> Option<String> {
+        self.buscar_campo(fqn, "Nome").map(|(_, _, dono)| dono)
+    }
+
+    fn construtor_c(&self, classe: &str, prefixo: &str, indice: usize) -> String {
+        let c = &self.tabelas.classes[classe];
+        if c.decl.construtores.len() > 1 {
+            format!("{}_{}_{}", c.simbolo, prefixo, indice)
+        } else {
+            format!("{}_{}", c.simbolo, prefixo)
+        }
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* GERAÇÃO DO ARQUIVO                                                  */
+    /* ------------------------------------------------------------------ */
+
+    pub fn generate(&mut self) -> Result<String, String> {
+        self.vtables = self.tabelas.vtables();
+        self.declarar_nomes_globais();
+
+        let mut saida = String::new();
+        saida.push_str("/* Gerado pelo compilador Por do Sol (--target=c). */\n");
+        saida.push_str("#include \"c_runtime.h\"\n\n");
+
+        saida.push_str(&self.definir_structs());
+        saida.push_str(&self.declarar_externas());
+
+        let funcoes = self.gerar_funcoes()?;
+        let estaticos = self.gerar_estaticos()?;
+        let globais = self.gerar_comandos_globais()?;
+        let despachos = self.gerar_despachos();
+
+        saida.push_str(&self.prototipos());
+        saida.push_str(&self.definir_vtables());
+        saida.push_str(&estaticos);
+        saida.push_str(&despachos);
+        saida.push_str(&funcoes);
+        saida.push_str(&globais);
+        saida.push_str(&self.gerar_main(!estaticos.is_empty(), !globais.is_empty()));
+        Ok(saida)
+    }
+
+    fn declarar_nomes_globais(&mut self) {
+        let mut nomes: HashSet<String> = self
+            .tabelas
+            .funcoes
+            .values()
+            .map(|f| f.simbolo.clone())
+            .collect();
+        for classe in self.tabelas.classes.values() {
+            nomes.insert(classe.simbolo.clone());
+        }
+        for f in self.tabelas.funcoes.values() {
+            if let Some((_, simbolo)) = externo(f.decl) {
+                nomes.insert(simbolo.to_string());
+            }
+        }
+        self.nomes_globais = nomes;
+    }
+
+    fn definir_structs(&self) -> String {
+        let mut saida = String::new();
+        if self.tabelas.ordem_classes.is_empty() {
+            return saida;
+        }
+        for fqn in &self.tabelas.ordem_classes {
+            let c = &self.tabelas.classes[fqn].simbolo;
+            saida.push_str(&format!("typedef struct {0} {0};\n", c));
+        }
+        saida.push('\n');
+        // Pais antes dos filhos: a struct do pai é o primeiro membro da struct do filho
+        let mut definidas = HashSet::new();
+        for fqn in &self.tabelas.ordem_classes {
+            self.definir_struct(fqn, &mut definidas, &mut saida);
+        }
+        saida
+    }
+
+    fn definir_struct(&self, fqn: &str, definidas: &mut HashSet<String>, saida: &mut String) {
+        if !definidas.insert(fqn.to_string()) {
+            return;
+        }
+        let classe = &self.tabelas.classes[fqn];
+        if let Some(pai) = &classe.pai {
+            self.definir_struct(pai, definidas, saida);
+        }
+        saida.push_str(&format!("struct {} {{\n", classe.simbolo));
+        match &classe.pai {
+            Some(pai) => saida.push_str(&format!(
+                "    {} base;\n",
+                self.tabelas.classes[pai].simbolo
+            )),
+            None => saida.push_str("    PdsObjeto base;\n"),
+        }
+        let campos = classe
+            .decl
+            .campos
+            .iter()
+            .filter(|c| !c.eh_estatica)
+            .map(|c| (&c.nome, &c.tipo))
+            .chain(
+                classe
+                    .decl
+                    .propriedades
+                    .iter()
+                    .filter(|p| !p.eh_estatica)
+                    .map(|p| (&p.nome, &p.tipo)),
+            );
+        for (nome, tipo) in campos {
+            let tipo = self.c_tipo(&self.tipo_c(tipo, &classe.namespace));
+            saida.push_str(&format!("    {};\n", declarar(&tipo, &identificador(nome))));
+        }
+        saida.push_str("};\n\n");
+
+        // Vtable: começa com a do pai (ou com o descritor PdsClasse)
+        saida.push_str(&format!("typedef struct {0}_vtable {{\n", classe.simbolo));
+        match &classe.pai {
+            Some(pai) => saida.push_str(&format!(
+                "    {}_vtable base;\n",
+                self.tabelas.classes[pai].simbolo
+            )),
+            None => saida.push_str("    PdsClasse base;\n"),
+        }
+        for (nome, introduz) in &self.vtables[fqn] {
+            if introduz != fqn {
+                continue;
+            }
+            if let Some(metodo) = classe.decl.metodos.iter().find(|m| m.nome == *nome) {
+                saida.push_str(&format!("    {};\n", self.ponteiro_metodo(fqn, metodo)));
+            }
+        }
+        saida.push_str(&format!("}} {}_vtable;\n\n", classe.simbolo));
+    }
+
+    fn declarar_externas(&mut self) -> String {
+        let mut saida = String::new();
+        let mut declarados = HashSet::new();
+        for fqn in self.tabelas.ordem_funcoes.clone() {
+            let funcao = &self.tabelas.funcoes[&fqn];
+            let Some((biblioteca, simbolo)) = externo(funcao.decl) else {
+                continue;
+            };
+            if !self.bibliotecas_externas.iter().any(|b| b == biblioteca) {
+                self.bibliotecas_externas.push(biblioteca.to_string());
+            }
+            if !declarados.insert(simbolo.to_string()) {
+                continue;
+            }
+            let retorno = self.tipo_retorno(&funcao.decl.tipo_retorno, &funcao.namespace);
+            let parametros: Vec<&str> = funcao
+                .decl
+                .parametros
+                .iter()
+                .map(|p| tipo_externo(&self.tipo_c(&p.tipo, &funcao.namespace)))
+                .collect();
+            let parametros = if parametros.is_empty() {
+                "void".to_string()
+            } else {
+                parametros.join(", ")
+            };
+            saida.push_str(&format!(
+                "extern {}({});\n",
+                declarar(tipo_externo(&retorno), simbolo),
+                parametros
+            ));
+        }
+        if !saida.is_empty() {
+            saida.insert_str(0, "/* Funções externas ([Externo]) */\n");
+            saida.push('\n');
+        }
+        saida
+    }
+
+    /// Protótipos de todas as funções geradas, para que a ordem das definições não importe.
+    fn prototipos(&self) -> String {
+        let mut saida = String::new();
+        for fqn in &self.tabelas.ordem_classes {
+            let classe = &self.tabelas.classes[fqn];
+            if let Some(dono) = self.classe_do_nome(fqn).filter(|d| d == fqn) {
+                saida.push_str(&format!(
+                    "static const char *{}_texto(const PdsObjeto *objeto);\n",
+                    self.tabelas.classes[&dono].simbolo
+                ));
+            }
+            if self.inicializador(fqn).as_deref() == Some(fqn) {
+                saida.push_str(&format!(
+                    "static void {0}_inicializar({0} *este);\n",
+                    classe.simbolo
+                ));
+            }
+            if !classe.decl.eh_abstrata && !classe.decl.eh_estatica {
+                for (i, _) in self.novos(fqn) {
+                    saida.push_str(&format!("{};\n", self.assinatura_novo(fqn, i)));
+                }
+            }
+            for i in 0..classe.decl.construtores.len() {
+                saida.push_str(&format!("{};\n", self.assinatura_construir(fqn, i)));
+            }
+            for propriedade in classe.decl.propriedades.iter().filter(|p| !p.eh_estatica) {
+                if let Some(assinatura) = self.assinatura_obter(fqn, propriedade) {
+                    saida.push_str(&format!("{};\n", assinatura));
+                }
+                if let Some(assinatura) = self.assinatura_definir(fqn, propriedade) {
+                    saida.push_str(&format!("{};\n", assinatura));
+                }
+            }
+            for metodo in classe.decl.metodos.iter().filter(|m| !m.eh_abstrato) {
+                saida.push_str(&format!("{};\n", self.assinatura_metodo(fqn, metodo)));
+            }
+            for (nome, introduz) in &self.vtables[fqn] {
+                if introduz != fqn {
+                    continue;
+                }
+                if let Some(metodo) = classe.decl.metodos.iter().find(|m| m.nome == *nome) {
+                    saida.push_str(&format!("{};\n", self.assinatura_virtual(fqn, metodo)));
+                }
+            }
+        }
+        for fqn in &self.tabelas.ordem_funcoes {
+            let funcao = &self.tabelas.funcoes[fqn];
+            if externo(funcao.decl).is_some() || chave_nativa(&funcao.decl.attributes).is_some() {
+                continue;
+            }
+            saida.push_str(&format!("{};\n", self.assinatura_funcao(fqn)));
+            if funcao.decl.eh_assincrona {
+                saida.push_str(&format!(
+                    "static Task *{}_iniciar({});\n",
+                    funcao.simbolo,
+                    self.lista_parametros(&funcao.decl.parametros, &funcao.namespace, None)
+                ));
+            }
+        }
+        for ((nome, n), despacho) in &self.despachos {
+            saida.push_str(&format!(
+                "{};\n",
+                self.assinatura_despacho(nome, *n, despacho)
+            ));
+        }
+        if !saida.is_empty() {
+            saida.push('\n');
+        }
+        saida
+    }
+
+    fn lista_parametros(
+        &self,
+        parametros: &[ast::Parametro],
+        namespace: &str,
+        receptor: Option<String>,
+    ) -> String {
+        let mut lista: Vec<String> = receptor.into_iter().collect();
+        for p in parametros {
+            lista.push(declarar(
+                &self.c_tipo(&self.tipo_c(&p.tipo, namespace)),
+                &identificador(&p.nome),
+            ));
+        }
+        if lista.is_empty() {
+            "void".to_string()
+        } else {
+            lista.join(", ")
+        }
+    }
+
+    fn assinatura_funcao(&self, fqn: &str) -> String {
+        let funcao = &self.tabelas.funcoes[fqn];
+        let retorno = self.tipo_retorno(&funcao.decl.tipo_retorno, &funcao.namespace);
+        format!(
+            "{}({})",
+            declarar(&self.c_tipo(&retorno), &funcao.simbolo),
+            self.lista_parametros(&funcao.decl.parametros, &funcao.namespace, None)
+        )
+    }
+
+    fn assinatura_metodo(&self, classe: &str, metodo: &ast::MetodoClasse) -> String {
+        let c = &self.tabelas.classes[classe];
+        let (retorno, _) = self.assinatura(classe, metodo);
+        let receptor = self.receptor(classe, &metodo.nome);
+        let nome_receptor = if receptor == classe { "este" } else { "objeto" };
+        let receptor = (!metodo.eh_estatica).then(|| {
+            format!(
+                "{} *{}",
+                self.tabelas.classes[&receptor].simbolo, nome_receptor
+            )
+        });
+        format!(
+            "{}({})",
+            declarar(&self.c_tipo(&retorno), &self.metodo_c(classe, &metodo.nome)),
+            self.lista_parametros(&metodo.parametros, &c.namespace, receptor)
+        )
+    }
+
+    fn assinatura_virtual(&self, classe: &str, metodo: &ast::MetodoClasse) -> String {
+        let c = &self.tabelas.classes[classe];
+        let (retorno, _) = self.assinatura(classe, metodo);
+        format!(
+            "static {}({})",
+            declarar(
+                &self.c_tipo(&retorno),
+                &format!("{}_virtual", self.metodo_c(classe, &metodo.nome))
+            ),
+            self.lista_parametros(
+                &metodo.parametros,
+                &c.namespace,
+                Some(format!("{} *este", c.simbolo))
+            )
+        )
+    }
+
+    fn assinatura_obter(
+        &self,
+        classe: &str,
+        propriedade: &ast::PropriedadeClasse,
+    ) -> Option<String> {
+        propriedade.obter.as_ref()?.corpo.as_ref()?;
+        let c = &self.tabelas.classes[classe];
+        let tipo = self.c_tipo(&self.tipo_c(&propriedade.tipo, &c.namespace));
+        Some(format!(
+            "{}({} *este)",
+            declarar(
+                &tipo,
+                &format!("{}_obter_{}", c.simbolo, identificador(&propriedade.nome))
+            ),
+            c.simbolo
+        ))
+    }
+
+    fn assinatura_definir(
+        &self,
+        classe: &str,
+        propriedade: &ast::PropriedadeClasse,
+    ) -> Option<String> {
+        propriedade.definir.as_ref()?.corpo.as_ref()?;
+        let c = &self.tabelas.classes[classe];
+        let tipo = self.c_tipo(&self.tipo_c(&propriedade.tipo, &c.namespace));
+        Some(format!(
+            "void {}_definir_{}({} *este, {})",
+            c.simbolo,
+            identificador(&propriedade.nome),
+            c.simbolo,
+            declarar(&tipo, "valor")
+        ))
+    }
+
+    /// Construtores `novo` da classe: um por construtor declarado, ou um sem parâmetros.
+    fn novos(&self, classe: &str) -> Vec<(usize, Option<&'a ast::ConstrutorClasse>)> {
+        let decl = self.tabelas.classes[classe].decl;
+        if decl.construtores.is_empty() {
+            vec![(0, None)]
+        } else {
+            decl.construtores
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (i, Some(c)))
+                .collect()
+        }
+    }
+
+    fn assinatura_novo(&self, classe: &str, indice: usize) -> String {
+        let c = &self.tabelas.classes[classe];
+        let parametros = match c.decl.construtores.get(indice) {
+            Some(construtor) => self.lista_parametros(&construtor.parametros, &c.namespace, None),
+            None => "void".to_string(),
+        };
+        format!(
+            "{} *{}({})",
+            c.simbolo,
+            self.construtor_c(classe, "novo", indice),
+            parametros
+        )
+    }
+
+    fn assinatura_construir(&self, classe: &str, indice: usize) -> String {
+        let c = &self.tabelas.classes[classe];
+        let construtor = &c.decl.construtores[indice];
+        format!(
+            "void {}({})",
+            self.construtor_c(classe, "construir", indice),
+            self.lista_parametros(
+                &construtor.parametros,
+                &c.namespace,
+                Some(format!("{} *este", c.simbolo))
+            )
+        )
+    }
+
+    fn assinatura_despacho(&self, nome: &str, n: usize, despacho: &Despacho) -> String {
+        let mut parametros = vec!["PdsObjeto *objeto".to_string()];
+        for (i, tipo) in despacho.parametros.iter().enumerate() {
+            parametros.push(declarar(&self.c_tipo(tipo), &format!("a{}", i)));
+        }
+        format!(
+            "static {}({})",
+            declarar(
+                &self.c_tipo(&despacho.retorno),
+                &Self::nome_despacho(nome, n)
+            ),
+            parametros.join(", ")
+        )
+    }
+
+    fn nome_despacho(nome: &str, n: usize) -> String {
+        format!(
+            "pds_despachar_{}_{}",
+            identificador(nome).trim_end_matches('_'),
+            n
+        )
+    }
+
+    /// Instâncias das vtables: o descritor de cada classe com as implementações mais derivadas.
+    fn definir_vtables(&self) -> String {
+        let mut saida = String::new();
+        for fqn in &self.tabelas.ordem_classes {
+            let classe = &self.tabelas.classes[fqn];
+            let profundidade = self.profundidade(fqn);
+            let pai = match &classe.pai {
+                Some(pai) => format!(
+                    "(const PdsClasse *)&{}_classe",
+                    self.tabelas.classes[pai].simbolo
+                ),
+                None => "NULL".to_string(),
+            };
+            let texto = match self.classe_do_nome(fqn) {
+                Some(dono) => format!("{}_texto", self.tabelas.classes[&dono].simbolo),
+                None => "NULL".to_string(),
+            };
+            saida.push_str(&format!(
+                "static const {0}_vtable {0}_classe = {{\n",
+                classe.simbolo
+            ));
+            saida.push_str(&format!(
+                "    .{}base = {{{}, {}, {}}},\n",
+                "base.".repeat(profundidade),
+                literal_texto(fqn),
+                pai,
+                texto
+            ));
+            for (nome, introduz) in &self.vtables[fqn] {
+                let implementacao = match self.tabelas.buscar_metodo(fqn, nome, usize::MAX) {
+                    Some((dono, metodo)) if !metodo.eh_abstrato => self.metodo_c(&dono, nome),
+                    _ => "NULL".to_string(),
+                };
+                saida.push_str(&format!(
+                    "    .{}{} = {},\n",
+                    "base.".repeat(profundidade - self.profundidade(introduz)),
+                    identificador(nome),
+                    implementacao
+                ));
+            }
+            saida.push_str("};\n\n");
+        }
+        saida
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* FUNÇÕES, MÉTODOS E CONSTRUTORES                                     */
+    /* ------------------------------------------------------------------ */
+
+    /// Prepara o estado para gerar o corpo de uma função.
+    fn iniciar_funcao(
+        &mut self,
+        namespace: &str,
+        classe: Option<&str>,
+        tem_este: bool,
+        retorno: TipoC,
+    ) {
+        self.namespace = namespace.to_string();
+        self.classe_atual = classe.map(str::to_string);
+        self.tem_este = tem_este;
+        self.retorno = retorno;
+        self.escopos = vec![HashMap::new()];
+        self.locais_usados = HashSet::new();
+        self.prelude.clear();
+        self.corpo = String::new();
+        self.nivel = 1;
+        self.temporarios = 0;
+        self.posicao = None;
+    }
+
+    fn declarar_parametros(&mut self, parametros: &[ast::Parametro], namespace: &str) {
+        for p in parametros {
+            let tipo = self.tipo_c(&p.tipo, namespace);
+            let c = identificador(&p.nome);
+            self.locais_usados.insert(c.clone());
+            self.escopos
+                .last_mut()
+                .unwrap()
+                .insert(p.nome.clone(), (c, tipo));
+        }
+    }
+
+    fn terminar_funcao(&mut self, assinatura: &str) -> String {
+        let corpo = std::mem::take(&mut self.corpo);
+        format!("{} {{\n{}}}\n\n", assinatura, corpo)
+    }
+
+    fn gerar_funcoes(&mut self) -> Result<String, String> {
+        let mut saida = String::new();
+        for fqn in self.tabelas.ordem_classes.clone() {
+            saida.push_str(&self.gerar_classe(&fqn)?);
+        }
+        for fqn in self.tabelas.ordem_funcoes.clone() {
+            let funcao = &self.tabelas.funcoes[&fqn];
+            let decl = funcao.decl;
+            if externo(decl).is_some() || chave_nativa(&decl.attributes).is_some() {
+                continue;
+            }
+            let namespace = funcao.namespace.clone();
+            let retorno = self.tipo_retorno(&decl.tipo_retorno, &namespace);
+            self.iniciar_funcao(&namespace, None, false, retorno.clone());
+            self.declarar_parametros(&decl.parametros, &namespace);
+            self.gerar_bloco(&decl.corpo)?;
+            self.retorno_final(&decl.corpo, &retorno);
+            let assinatura = self.assinatura_funcao(&fqn);
+            saida.push_str(&self.terminar_funcao(&assinatura));
+            if decl.eh_assincrona {
+                saida.push_str(&self.gerar_tarefa(&fqn));
+            }
+        }
+        Ok(saida)
+    }
+
+    /// Funções sem `retorne` no final devolvem o valor padrão do tipo (nulo no interpretador).
+    fn retorno_final(&mut self, corpo: &[ast::Comando], retorno: &TipoC) {
+        if *retorno == TipoC::Vazio || matches!(corpo.last(), Some(ast::Comando::Retorne(_))) {
+            return;
+        }
+        let valor = match retorno {
+            TipoC::Texto => "NULL",
+            tipo => Self::valor_padrao(tipo),
+        };
+        self.linha(&format!("return {};", valor));
+    }
+
+    /// Trampolim para o pool de threads (`void *(void *)`) e a função que inicia a tarefa.
+    fn gerar_tarefa(&self, fqn: &str) -> String {
+        let funcao = &self.tabelas.funcoes[fqn];
+        let retorno = self.tipo_retorno(&funcao.decl.tipo_retorno, &funcao.namespace);
+        let parametros: Vec<(String, TipoC)> = funcao
+            .decl
+            .parametros
+            .iter()
+            .map(|p| {
+                (
+                    identificador(&p.nome),
+                    self.tipo_c(&p.tipo, &funcao.namespace),
+                )
+            })
+            .collect();
+        let c = &funcao.simbolo;
+        let mut saida = String::new();
+        if !parametros.is_empty() {
+            saida.push_str(&format!("typedef struct {}_argumentos {{\n", c));
+            for (nome, tipo) in &parametros {
+                saida.push_str(&format!("    {};\n", declarar(&self.c_tipo(tipo), nome)));
+            }
+            saida.push_str(&format!("}} {}_argumentos;\n\n", c));
+        }
+        let argumentos: Vec<String> = parametros
+            .iter()
+            .map(|(nome, _)| format!("argumentos->{}", nome))
+            .collect();
+        let chamada = format!("{}({})", c, argumentos.join(", "));
+        saida.push_str(&format!("static void *{}_tarefa(void *argumento) {{\n", c));
+        if parametros.is_empty() {
+            saida.push_str("    (void)argumento;\n");
+        } else {
+            saida.push_str(&format!(
+                "    {0}_argumentos *argumentos = ({0}_argumentos *)argumento;\n",
+                c
+            ));
+        }
+        match &retorno {
+            TipoC::Vazio => {
+                saida.push_str(&format!("    {};\n    return NULL;\n", chamada));
+            }
+            tipo if tipo.referencia() || *tipo == TipoC::Texto => {
+                saida.push_str(&format!("    return (void *){};\n", chamada));
+            }
+            tipo => {
+                // Valores escalares voltam em um bloco do coletor
+                let c_tipo = self.c_tipo(tipo);
+                saida.push_str(&format!(
+                    "    {} = ({} *)pds_gc_alocar_atomico(sizeof({}));\n",
+                    declarar(&format!("{} *", c_tipo), "resultado"),
+                    c_tipo,
+                    c_tipo
+                ));
+                saida.push_str(&format!(
+                    "    *resultado = {};\n    return resultado;\n",
+                    chamada
+                ));
+            }
+        }
+        saida.push_str("}\n\n");
+
+        saida.push_str(&format!(
+            "static Task *{}_iniciar({}) {{\n",
+            c,
+            self.lista_parametros(&funcao.decl.parametros, &funcao.namespace, None)
+        ));
+        saida.push_str("    Task *tarefa = task_create(next_task_id());\n");
+        if parametros.is_empty() {
+            saida.push_str(&format!(
+                "    task_submit_to_pool(tarefa, {}_tarefa, NULL);\n",
+                c
+            ));
+        } else {
+            saida.push_str(&format!(
+                "    {0}_argumentos *argumentos = ({0}_argumentos *)pds_gc_alocar(sizeof({0}_argumentos));\n",
+                c
+            ));
+            for (nome, _) in &parametros {
+                saida.push_str(&format!("    argumentos->{0} = {0};\n", nome));
+            }
+            saida.push_str(&format!(
+                "    task_submit_to_pool(tarefa, {}_tarefa, argumentos);\n",
+                c
+            ));
+        }
+        saida.push_str("    return tarefa;\n}\n\n");
+        saida
+    }
+
+    fn gerar_classe(&mut self, fqn: &str) -> Result<String, String> {
+        let mut saida = String::new();
+        let classe = &self.tabelas.classes[fqn];
+        let decl = classe.decl;
+        let namespace = classe.namespace.clone();
+        let c = classe.simbolo.clone();
+        let pai = classe.pai.clone();
+
+        // Texto exibido por imprima: o campo `Nome`, como no interpretador
+        if self.classe_do_nome(fqn).as_deref() == Some(fqn) {
+            let (caminho, tipo, _) = self.buscar_campo(fqn, "Nome").unwrap();
+            let campo = Expr::novo(
+                format!("(({} *)objeto)->{}", c, caminho),
+                tipo,
+                Ordem::Leitura,
+                true,
+            );
+            saida.push_str(&format!(
+                "static const char *{}_texto(const PdsObjeto *objeto) {{\n    return {};\n}}\n\n",
+                c,
+                self.texto_de(campo).codigo
+            ));
+        }
+
+        // Inicializadores de campos, da base para a derivada
+        if self.inicializador(fqn).as_deref() == Some(fqn) {
+            self.iniciar_funcao(&namespace, Some(fqn), true, TipoC::Vazio);
+            if let Some(pai) = pai.as_ref().and_then(|p| self.inicializador(p)) {
+                let chamada = format!(
+                    "{}_inicializar(({} *)este);",
+                    self.tabelas.classes[&pai].simbolo, self.tabelas.classes[&pai].simbolo
+                );
+                self.linha(&chamada);
+            }
+            let iniciais = decl
+                .campos
+                .iter()
+                .filter(|c| !c.eh_estatica)
+                .filter_map(|c| c.valor_inicial.as_ref().map(|e| (&c.nome, e)))
+                .chain(
+                    decl.propriedades
+                        .iter()
+                        .filter(|p| !p.eh_estatica)
+                        .filter_map(|p| p.valor_inicial.as_ref().map(|e| (&p.nome, e))),
+                );
+            for (nome, expr) in iniciais {
+                let (caminho, tipo, _) = self.buscar_campo(fqn, nome).unwrap();
+                let valor = self.expr(expr)?;
+                let valor = self.converter(valor, &tipo);
+                self.linha_com_prelude(&format!("este->{} = {};", caminho, valor.codigo));
+            }
+            let assinatura = format!("static void {0}_inicializar({0} *este)", c);
+            saida.push_str(&self.terminar_funcao(&assinatura));
+        }
+
+        // Construtores
+        for (i, construtor) in decl.construtores.iter().enumerate() {
+            self.iniciar_funcao(&namespace, Some(fqn), true, TipoC::Vazio);
+            self.declarar_parametros(&construtor.parametros, &namespace);
+            if let Some(pai) = pai.clone() {
+                let args = construtor.chamada_pai.as_deref().unwrap_or(&[]);
+                self.construcao_pai(&pai, args)?;
+            }
+            self.gerar_bloco(&construtor.corpo)?;
+            let assinatura = self.assinatura_construir(fqn, i);
+            saida.push_str(&self.terminar_funcao(&assinatura));
+        }
+        if !decl.eh_abstrata && !decl.eh_estatica {
+            for (i, construtor) in self.novos(fqn) {
+                let mut corpo = format!(
+                    "    {0} *este = ({0} *)pds_gc_alocar(sizeof({0}));\n    ((PdsObjeto *)este)->classe = (const PdsClasse *)&{0}_classe;\n",
+                    c
+                );
+                if let Some(inicializador) = self.inicializador(fqn) {
+                    let ci = &self.tabelas.classes[&inicializador].simbolo;
+                    if inicializador == fqn {
+                        corpo.push_str(&format!("    {}_inicializar(este);\n", ci));
+                    } else {
+                        corpo.push_str(&format!("    {0}_inicializar(({0} *)este);\n", ci));
+                    }
+                }
+                match construtor {
+                    Some(construtor) => {
+                        let mut args = vec!["este".to_string()];
+                        args.extend(construtor.parametros.iter().map(|p| identificador(&p.nome)));
+                        corpo.push_str(&format!(
+                            "    {}({});\n",
+                            self.construtor_c(fqn, "construir", i),
+                            args.join(", ")
+                        ));
+                    }
+                    None => {
+                        // Sem construtor declarado: apenas o construtor padrão da classe pai
+                        if let Some(pai) = pai.clone() {
+                            self.iniciar_funcao(&namespace, Some(fqn), true, TipoC::Vazio);
+                            self.construcao_pai(&pai, &[])?;
+                            corpo.push_str(&std::mem::take(&mut self.corpo));
+                        }
+                    }
+                }
+                corpo.push_str("    return este;\n");
+                saida.push_str(&format!(
+                    "{} {{\n{}}}\n\n",
+                    self.assinatura_novo(fqn, i),
+                    corpo
+                ));
+            }
+        }
+
+        // Acessores de propriedades com corpo
+        for propriedade in decl.propriedades.iter().filter(|p| !p.eh_estatica) {
+            let tipo = self.tipo_c(&propriedade.tipo, &namespace);
+            if let Some(assinatura) = self.assinatura_obter(fqn, propriedade) {
+                let corpo = propriedade.obter.as_ref().unwrap().corpo.as_ref().unwrap();
+                self.iniciar_funcao(&namespace, Some(fqn), true, tipo.clone());
+                self.gerar_bloco(corpo)?;
+                self.retorno_final(corpo, &tipo);
+                saida.push_str(&self.terminar_funcao(&assinatura));
+            }
+            if let Some(assinatura) = self.assinatura_definir(fqn, propriedade) {
+                let corpo = propriedade
+                    .definir
+                    .as_ref()
+                    .unwrap()
+                    .corpo
+                    .as_ref()
+                    .unwrap();
+                self.iniciar_funcao(&namespace, Some(fqn), true, TipoC::Vazio);
+                self.locais_usados.insert("valor".to_string());
+                self.escopos[0].insert("valor".to_string(), ("valor".to_string(), tipo.clone()));
+                self.gerar_bloco(corpo)?;
+                saida.push_str(&self.terminar_funcao(&assinatura));
+            }
+        }
+
+        // Métodos
+        for metodo in decl.metodos.iter().filter(|m| !m.eh_abstrato) {
+            let (retorno, _) = self.assinatura(fqn, metodo);
+            self.iniciar_funcao(&namespace, Some(fqn), !metodo.eh_estatica, retorno.clone());
+            self.declarar_parametros(&metodo.parametros, &namespace);
+            if let Some(chave) = chave_nativa(&metodo.attributes) {
+                return Err(format!(
+                    "Método nativo '{}' ({}) não é suportado no alvo C",
+                    metodo.nome, chave
+                ));
+            }
+            self.gerar_bloco(&metodo.corpo)?;
+            self.retorno_final(&metodo.corpo, &retorno);
+            // Implementações de entradas herdadas recebem o ponteiro da classe que as introduz
+            if !metodo.eh_estatica
+                && self.receptor(fqn, &metodo.nome) != fqn
+                && usa_este(&self.corpo)
+            {
+                self.corpo
+                    .insert_str(0, &format!("    {0} *este = ({0} *)objeto;\n", c));
+            }
+            let assinatura = self.assinatura_metodo(fqn, metodo);
+            saida.push_str(&self.terminar_funcao(&assinatura));
+        }
+
+        // Chamadas pela vtable
+        for (nome, introduz) in self.vtables[fqn].clone() {
+            if introduz != fqn {
+                continue;
+            }
+            let Some(metodo) = decl.metodos.iter().find(|m| m.nome == nome) else {
+                continue;
+            };
+            let (retorno, _) = self.assinatura(fqn, metodo);
+            let mut args = vec!["este".to_string()];
+            args.extend(metodo.parametros.iter().map(|p| identificador(&p.nome)));
+            let chamada = format!(
+                "((const {}_vtable *)((PdsObjeto *)este)->classe)->{}({})",
+                c,
+                identificador(&nome),
+                args.join(", ")
+            );
+            let corpo = if retorno == TipoC::Vazio {
+                format!("    {};\n", chamada)
+            } else {
+                format!("    return {};\n", chamada)
+            };
+            saida.push_str(&format!(
+                "{} {{\n{}}}\n\n",
+                self.assinatura_virtual(fqn, metodo),
+                corpo
+            ));
+        }
+        Ok(saida)
+    }
+
+    /// Chamada ao construtor da classe pai (`: base(...)`), ou ao construtor padrão dela.
+    fn construcao_pai(&mut self, pai: &str, args: &[ast::Expressao]) -> Result<(), String> {
+        match self.tabelas.escolher_construtor(pai, args.len()) {
+            Some(indice) => {
+                let parametros = self.tabelas.classes[pai].decl.construtores[indice]
+                    .parametros
+                    .clone();
+                let namespace = self.tabelas.classes[pai].namespace.clone();
+                let mut argumentos = vec![format!("({} *)este", self.tabelas.classes[pai].simbolo)];
+                argumentos.extend(self.argumentos(&parametros, &namespace, args)?);
+                let chamada = format!(
+                    "{}({});",
+                    self.construtor_c(pai, "construir", indice),
+                    argumentos.join(", ")
+                );
+                self.linha_com_prelude(&chamada);
+            }
+            None => {
+                if let Some(avo) = self.tabelas.classes[pai].pai.clone() {
+                    self.construcao_pai(&avo, &[])?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn gerar_estaticos(&mut self) -> Result<String, String> {
+        let mut declaracoes = String::new();
+        let mut inicializacao = String::new();
+        for fqn in self.tabelas.ordem_classes.clone() {
+            let classe = &self.tabelas.classes[&fqn];
+            let namespace = classe.namespace.clone();
+            let membros: Vec<(&String, &ast::Tipo, &Option<ast::Expressao>)> = classe
+                .decl
+                .campos
+                .iter()
+                .filter(|c| c.eh_estatica)
+                .map(|c| (&c.nome, &c.tipo, &c.valor_inicial))
+                .chain(
+                    classe
+                        .decl
+                        .propriedades
+                        .iter()
+                        .filter(|p| p.eh_estatica)
+                        .map(|p| (&p.nome, &p.tipo, &p.valor_inicial)),
+                )
+                .collect();
+            for (nome, tipo, inicial) in membros {
+                let tipo = self.tipo_c(tipo, &namespace);
+                let global = self.estatico_c(&fqn, nome);
+                declaracoes.push_str(&format!(
+                    "static {};\n",
+                    declarar(&self.c_tipo(&tipo), &global)
+                ));
+                self.iniciar_funcao(&namespace, Some(&fqn), false, TipoC::Vazio);
+                if tipo.referencia() || tipo == TipoC::Texto {
+                    self.linha(&format!(
+                        "pds_gc_adicionar_raiz(&{0}, sizeof({0}));",
+                        global
+                    ));
+                }
+                if let Some(expr) = inicial {
+                    let valor = self.expr(expr)?;
+                    let valor = self.converter(valor, &tipo);
+                    self.linha_com_prelude(&format!("{} = {};", global, valor.codigo));
+                }
+                inicializacao.push_str(&std::mem::take(&mut self.corpo));
+            }
+        }
+        if declaracoes.is_empty() {
+            return Ok(String::new());
+        }
+        Ok(format!(
+            "/* Membros estáticos */\n{}\nstatic void pds_inicializar_estaticos(void) {{\n{}}}\n\n",
+            declaracoes, inicializacao
+        ))
+    }
+
+    fn gerar_comandos_globais(&mut self) -> Result<String, String> {
+        if self.tabelas.globais.is_empty() {
+            return Ok(String::new());
+        }
+        self.iniciar_funcao("", None, false, TipoC::Vazio);
+        for (namespace, comando) in self.tabelas.globais.clone() {
+            self.namespace = namespace;
+            self.gerar_comando(comando)?;
+        }
+        Ok(self.terminar_funcao("static void pds_comandos_globais(void)"))
+    }
+
+    fn gerar_main(&self, estaticos: bool, globais: bool) -> String {
+        let mut saida = String::from("int main(void) {\n");
+        if estaticos {
+            saida.push_str("    pds_inicializar_estaticos();\n");
+        }
+        if globais {
+            saida.push_str("    pds_comandos_globais();\n");
+        }
+        let principal = self
+            .tabelas
+            .ordem_funcoes
+            .iter()
+            .find(|n| *n == "Principal" || *n == "principal" || n.ends_with(".Principal"));
+        if let Some(fqn) = principal {
+            saida.push_str(&format!("    {}();\n", self.tabelas.funcoes[fqn].simbolo));
+        }
+        saida.push_str("    return 0;\n}\n");
+        saida
+    }
+
+    fn gerar_despachos(&mut self) -> String {
+        let mut saida = String::new();
+        for ((nome, n), despacho) in &self.despachos {
+            let mut corpo = String::from("    const PdsClasse *classe = objeto->classe;\n");
+            for fqn in &self.tabelas.ordem_classes {
+                let classe = &self.tabelas.classes[fqn];
+                if classe.decl.eh_abstrata || classe.decl.eh_estatica {
+                    continue;
+                }
+                let Some((dono, metodo)) = self.tabelas.buscar_metodo(fqn, nome, *n) else {
+                    continue;
+                };
+                if metodo.eh_estatica || metodo.eh_abstrato {
+                    continue;
+                }
+                let (retorno, parametros) = self.assinatura(&dono, metodo);
+                let receptor = self.receptor(&dono, nome);
+                let mut args = vec![format!(
+                    "({} *)objeto",
+                    self.tabelas.classes[&receptor].simbolo
+                )];
+                for (i, tipo) in parametros.iter().enumerate() {
+                    let valor = match despacho.parametros.get(i) {
+                        Some(de) => {
+                            let arg =
+                                Expr::novo(format!("a{}", i), de.clone(), Ordem::Estavel, true);
+                            self.converter(arg, tipo).codigo
+                        }
+                        None => Self::valor_padrao(tipo).to_string(),
+                    };
+                    args.push(valor);
+                }
+                let chamada = format!("{}({})", self.metodo_c(&dono, nome), args.join(", "));
+                corpo.push_str(&format!(
+                    "    if (classe == (const PdsClasse *)&{}_classe) {{\n",
+                    classe.simbolo
+                ));
+                if despacho.retorno == TipoC::Vazio {
+                    corpo.push_str(&format!("        {};\n        return;\n", chamada));
+                } else {
+                    let valor = Expr::novo(chamada, retorno, Ordem::Efeito, true);
+                    corpo.push_str(&format!(
+                        "        return {};\n",
+                        self.converter(valor, &despacho.retorno).codigo
+                    ));
+                }
+                corpo.push_str("    }\n");
+            }
+            corpo.push_str(&format!(
+                "    pds_panico(pds_concatenar(5, \"Método '\", {}, \"' não encontrado na classe '\", classe->nome, \"'\"), NULL, 0, 0);\n",
+                literal_texto(&despacho.nome)
+            ));
+            if despacho.retorno != TipoC::Vazio {
+                corpo.push_str(&format!(
+                    "    return {};\n",
+                    match despacho.retorno {
+                        TipoC::Texto => "NULL",
+                        ref tipo => Self::valor_padrao(tipo),
+                    }
+                ));
+            }
+            saida.push_str(&format!(
+                "{} {{\n{}}}\n\n",
+                self.assinatura_despacho(nome, *n, despacho),
+                corpo
+            ));
+        }
+        saida
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* COMANDOS                                                            */
+    /* ------------------------------------------------------------------ */
+
+    fn linha(&mut self, codigo: &str) {
+        for _ in 0..self.nivel {
+            self.corpo.push_str("    ");
+        }
+        self.corpo.push_str(codigo);
+        self.corpo.push('\n');
+    }
+
+    /// Emite os comandos pendentes das expressões (temporários, verificações) e depois `codigo`.
+    fn linha_com_prelude(&mut self, codigo: &str) {
+        self.emitir_prelude();
+        self.linha(codigo);
+    }
+
+    fn emitir_prelude(&mut self) {
+        for linha in std::mem::take(&mut self.prelude) {
+            self.linha(&linha);
+        }
+    }
+
+    fn abrir_escopo(&mut self) {
+        self.escopos.push(HashMap::new());
+        self.nivel += 1;
+    }
+
+    fn fechar_escopo(&mut self) {
+        self.escopos.pop();
+        self.nivel -= 1;
+    }
+
+    fn temporario(&mut self) -> String {
+        self.temporarios += 1;
+        format!("pds_t{}", self.temporarios)
+    }
+
+    /// Guarda o valor em um temporário do prelude, inserido na posição `posicao`.
+    fn guardar(&mut self, valor: Expr, posicao: usize) -> Expr {
+        if valor.tipo == TipoC::Vazio {
+            return valor;
+        }
+        let nome = self.temporario();
+        let declaracao = format!(
+            "{} = {};",
+            declarar(&self.c_tipo(&valor.tipo), &nome),
+            valor.codigo
+        );
+        self.prelude.insert(posicao, declaracao);
+        Expr {
+            codigo: nome,
+            ordem: Ordem::Estavel,
+            atomico: true,
+            partes: Vec::new(),
+            ..valor
+        }
+    }
+
+    fn declarar_local(&mut self, nome: &str, tipo: TipoC) -> String {
+        let base = identificador(nome);
+        let mut c = base.clone();
+        let mut i = 2;
+        while self.locais_usados.contains(&c) || self.nomes_globais.contains(&c) {
+            c = format!("{}_{}", base, i);
+            i += 1;
+        }
+        self.locais_usados.insert(c.clone());
+        self.escopos
+            .last_mut()
+            .unwrap()
+            .insert(nome.to_string(), (c.clone(), tipo));
+        c
+    }
+
+    fn gerar_bloco(&mut self, comandos: &[ast::Comando]) -> Result<(), String> {
+        for comando in comandos {
+            self.gerar_comando(comando)?;
+        }
+        Ok(())
+    }
+
+    /// Corpo de `se`/`enquanto`: sempre entre chaves, com escopo próprio.
+    fn gerar_corpo(&mut self, comando: &ast::Comando) -> Result<(), String> {
+        self.abrir_escopo();
+        match comando {
+            ast::Comando::Bloco(comandos) => self.gerar_bloco(comandos)?,
+            outro => self.gerar_comando(outro)?,
+        }
+        self.fechar_escopo();
+        Ok(())
+    }
+
+    fn gerar_comando(&mut self, comando: &ast::Comando) -> Result<(), String> {
+        match comando {
+            ast::Comando::Posicao(posicao) => self.posicao = Some(posicao.clone()),
+            ast::Comando::DeclaracaoVariavel(tipo, nome, inicial) => {
+                let tipo = self.tipo_c(tipo, &self.namespace.clone());
+                let valor = match inicial {
+                    Some(expr) => {
+                        let valor = self.expr(expr)?;
+                        self.converter(valor, &tipo).codigo
+                    }
+                    None => Self::valor_padrao(&tipo).to_string(),
+                };
+                let c = self.declarar_local(nome, tipo.clone());
+                let declaracao = format!("{} = {};", declarar(&self.c_tipo(&tipo), &c), valor);
+                self.linha_com_prelude(&declaracao);
+            }
+            ast::Comando::DeclaracaoVar(nome, expr) => {
+                let valor = self.expr(expr)?;
+                self.declarar_var(nome, valor);
+            }
+            ast::Comando::CriarObjeto(nome, classe, args) => {
+                let valor = self.expr(&ast::Expressao::NovoObjeto(
+                    ast::Tipo::Classe(classe.clone()),
+                    args.clone(),
+                ))?;
+                self.declarar_var(nome, valor);
+            }
+            ast::Comando::Atribuicao(nome, expr) => self.atribuir_variavel(nome, expr)?,
+            ast::Comando::AtribuirPropriedade(alvo, membro, expr)
+            | ast::Comando::AtribuirCampo(alvo, membro, expr) => {
+                self.atribuir_membro(alvo, membro, expr)?
+            }
+            ast::Comando::AtribuirIndice(alvo, indice, expr) => {
+                let marca = self.prelude.len();
+                let mut valores = self.operandos(&[alvo, indice, expr])?;
+                let valor = valores.pop().unwrap();
+                let indice = valores.pop().unwrap();
+                let mut alvo = valores.pop().unwrap();
+                let TipoC::Array(item) = alvo.tipo.clone() else {
+                    return Err("Atribuição por índice requer array e inteiro".to_string());
+                };
+                if self.verificacoes && alvo.ordem != Ordem::Estavel {
+                    alvo = self.guardar(alvo, marca);
+                }
+                let valor = self.converter(valor, &item);
+                let indice = self.indice(&alvo, indice);
+                let atribuicao = format!(
+                    "PDS_ITENS({}, {})[{}] = {};",
+                    alvo.codigo,
+                    self.c_tipo(&item),
+                    indice,
+                    valor.codigo
+                );
+                self.linha_com_prelude(&atribuicao);
+            }
+            ast::Comando::Imprima(expr) => {
+                let valor = self.expr(expr)?;
+                let texto = self.texto_de(valor);
+                self.linha_com_prelude(&format!("pds_imprimir({});", texto.codigo));
+            }
+            ast::Comando::Se(condicao, entao, senao) => {
+                let condicao = self.condicao(condicao)?;
+                self.linha_com_prelude(&format!("if ({}) {{", condicao));
+                self.gerar_corpo(entao)?;
+                let mut senao = senao.as_deref();
+                while let Some(comando) = senao {
+                    // `senão se` sem prelude vira `else if`
+                    if let ast::Comando::Se(condicao, entao, proximo) = comando {
+                        let marca_corpo = self.corpo.len();
+                        let codigo = self.condicao(condicao)?;
+                        if self.prelude.is_empty() {
+                            self.linha(&format!("}} else if ({}) {{", codigo));
+                            self.gerar_corpo(entao)?;
+                            senao = proximo.as_deref();
+                            continue;
+                        }
+                        self.corpo.truncate(marca_corpo);
+                        self.prelude.clear();
+                    }
+                    self.linha("} else {");
+                    self.gerar_corpo(comando)?;
+                    senao = None;
+                }
+                self.linha("}");
+            }
+            ast::Comando::Enquanto(condicao, corpo) => {
+                self.gerar_laco(None, Some(condicao), None, corpo)?;
+            }
+            ast::Comando::Para(inicio, condicao, incremento, corpo) => {
+                self.gerar_laco(
+                    inicio.as_deref(),
+                    condicao.as_ref(),
+                    incremento.as_deref(),
+                    corpo,
+                )?;
+            }
+            ast::Comando::Bloco(comandos) => {
+                self.linha("{");
+                self.abrir_escopo();
+                self.gerar_bloco(comandos)?;
+                self.fechar_escopo();
+                self.linha("}");
+            }
+            ast::Comando::Retorne(expr) => {
+                let retorno = self.retorno.clone();
+                match (expr, &retorno) {
+                    (Some(expr), TipoC::Vazio) => {
+                        let valor = self.expr(expr)?;
+                        self.descartar(valor);
+                        self.linha("return;");
+                    }
+                    (Some(expr), tipo) => {
+                        let valor = self.expr(expr)?;
+                        let valor = self.converter(valor, tipo);
+                        self.linha_com_prelude(&format!("return {};", valor.codigo));
+                    }
+                    (None, TipoC::Vazio) => self.linha("return;"),
+                    (None, tipo) => {
+                        let valor = match tipo {
+                            TipoC::Texto => "NULL",
+                            tipo => Self::valor_padrao(tipo),
+                        };
+                        self.linha(&format!("return {};", valor));
+                    }
+                }
+            }
+            ast::Comando::Expressao(expr) => {
+                let valor = self.expr(expr)?;
+                self.descartar(valor);
+            }
+            ast::Comando::ChamarMetodo(alvo, metodo, args) => {
+                let valor = self.chamada_metodo(alvo, metodo, args)?;
+                self.descartar(valor);
+            }
+            ast::Comando::AcessarCampo(variavel, campo) => {
+                let valor = self.expr(&ast::Expressao::AcessoMembro(
+                    Box::new(ast::Expressao::Identificador(variavel.clone())),
+                    campo.clone(),
+                ))?;
+                self.descartar(valor);
+            }
+        }
+        Ok(())
+    }
+
+    fn declarar_var(&mut self, nome: &str, valor: Expr) {
+        let tipo = match valor.tipo {
+            TipoC::Nulo | TipoC::Vazio => TipoC::Objeto,
+            ref tipo => tipo.clone(),
+        };
+        let valor = self.converter(valor, &tipo);
+        let c = self.declarar_local(nome, tipo.clone());
+        let declaracao = format!("{} = {};", declarar(&self.c_tipo(&tipo), &c), valor.codigo);
+        self.linha_com_prelude(&declaracao);
+    }
+
+    /// Avalia a expressão só pelos efeitos.
+    fn descartar(&mut self, valor: Expr) {
+        let codigo = if valor.ordem == Ordem::Efeito {
+            format!("{};", valor.codigo)
+        } else if valor.ordem == Ordem::Estavel && valor.prelude_vazio() {
+            String::new()
+        } else {
+            format!("(void){};", valor.atomo())
+        };
+        if codigo.is_empty() {
+            self.emitir_prelude();
+        } else {
+            self.linha_com_prelude(&codigo);
+        }
+    }
+
+    /// Condição de `se`/`enquanto`, sem parênteses externos.
+    fn condicao(&mut self, expr: &ast::Expressao) -> Result<String, String> {
+        let valor = self.expr(expr)?;
+        if valor.tipo != TipoC::Booleano {
+            return Err(format!(
+                "Condição deve ser booleana, obtido {}",
+                self.nome_tipo(&valor.tipo)
+            ));
+        }
+        Ok(valor.codigo)
+    }
+
+    fn gerar_laco(
+        &mut self,
+        inicio: Option<&ast::Comando>,
+        condicao: Option<&ast::Expressao>,
+        incremento: Option<&ast::Comando>,
+        corpo: &ast::Comando,
+    ) -> Result<(), String> {
+        if let Some(inicio) = inicio {
+            self.linha("{");
+            self.abrir_escopo();
+            self.gerar_comando(inicio)?;
+        }
+        let condicao = match condicao {
+            Some(condicao) => Some(self.condicao(condicao)?),
+            None => None,
+        };
+        let prelude = std::mem::take(&mut self.prelude);
+        match condicao {
+            Some(condicao) if prelude.is_empty() => self.linha(&format!("while ({}) {{", condicao)),
+            Some(condicao) => {
+                // A condição precisa de comandos antes de ser avaliada a cada volta
+                self.linha("while (true) {");
+                self.nivel += 1;
+                for linha in prelude {
+                    self.linha(&linha);
+                }
+                self.linha(&format!("if (!({})) {{", condicao));
+                self.linha("    break;");
+                self.linha("}");
+                self.nivel -= 1;
+            }
+            None => self.linha("while (true) {"),
+        }
+        self.abrir_escopo();
+        match corpo {
+            ast::Comando::Bloco(comandos) => self.gerar_bloco(comandos)?,
+            outro => self.gerar_comando(outro)?,
+        }
+        if let Some(incremento) = incremento {
+            self.gerar_comando(incremento)?;
+        }
+        self.fechar_escopo();
+        self.linha("}");
+        if inicio.is_some() {
+            self.fechar_escopo();
+            self.linha("}");
+        }
+        Ok(())
+    }
+
+    fn atribuir_variavel(&mut self, nome: &str, expr: &ast::Expressao) -> Result<(), String> {
+        let (destino, tipo) = self.variavel_c(nome)?;
+        let valor = self.expr(expr)?;
+        let valor = self.converter(valor, &tipo);
+        self.linha_com_prelude(&format!("{} = {};", destino, valor.codigo));
+        Ok(())
+    }
+
+    fn atribuir_membro(
+        &mut self,
+        alvo: &ast::Expressao,
+        membro: &str,
+        expr: &ast::Expressao,
+    ) -> Result<(), String> {
+        if let Some(estatico) = self.alvo_estatico(alvo) {
+            let AlvoEstatico::Classe(classe) = estatico else {
+                return Err(format!("Não é possível atribuir a '{}'", membro));
+            };
+            let (dono, tipo) = self.dono_estatico(&classe, membro).ok_or_else(|| {
+                format!(
+                    "Membro estático '{}' não encontrado em '{}'",
+                    membro, classe
+                )
+            })?;
+            let valor = self.expr(expr)?;
+            let valor = self.converter(valor, &tipo);
+            let global = self.estatico_c(&dono, membro);
+            self.linha_com_prelude(&format!("{} = {};", global, valor.codigo));
+            return Ok(());
+        }
+        let marca = self.prelude.len();
+        let mut valores = self.operandos(&[alvo, expr])?;
+        let valor = valores.pop().unwrap();
+        let mut objeto = valores.pop().unwrap();
+        let TipoC::Classe(classe) = objeto.tipo.clone() else {
+            return Err(format!(
+                "Não é possível atribuir '{}' em um valor do tipo {}",
+                membro,
+                self.nome_tipo(&objeto.tipo)
+            ));
+        };
+        if objeto.ordem != Ordem::Estavel {
+            objeto = self.guardar(objeto, marca);
+        }
+        if self.verificacoes && !objeto.nao_nulo {
+            let verificacao = self.verificar_nulo(
+                &objeto.codigo,
+                &format!("Atribuição a '{}' em referência nula", membro),
+            );
+            self.prelude.push(verificacao);
+        }
+        if let Some((dono, propriedade)) = self.tabelas.buscar_propriedade(&classe, membro) {
+            if self.assinatura_definir(&dono, propriedade).is_some() {
+                let tipo = self.tipo_c(
+                    &propriedade.tipo,
+                    &self.tabelas.classes[&dono].namespace.clone(),
+                );
+                let valor = self.converter(valor, &tipo);
+                let receptor = self.converter(objeto, &TipoC::Classe(dono.clone()));
+                let chamada = format!(
+                    "{}_definir_{}({}, {});",
+                    self.tabelas.classes[&dono].simbolo,
+                    identificador(membro),
+                    receptor.codigo,
+                    valor.codigo
+                );
+                self.linha_com_prelude(&chamada);
+                return Ok(());
+            }
+        }
+        let (caminho, tipo, _) = self
+            .buscar_campo(&classe, membro)
+            .ok_or_else(|| format!("Membro '{}' não encontrado na classe '{}'", membro, classe))?;
+        let valor = self.converter(valor, &tipo);
+        self.linha_com_prelude(&format!(
+            "{}->{} = {};",
+            objeto.atomo(),
+            caminho,
+            valor.codigo
+        ));
+        Ok(())
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* EXPRESSÕES                                                          */
+    /* ------------------------------------------------------------------ */
+
+    /// Posição atual como argumentos `arquivo, linha, coluna` das funções de verificação.
+    fn posicao_c(&self) -> String {
+        match &self.posicao {
+            Some(p) => format!("{}, {}, {}", literal_texto(&p.arquivo), p.linha, p.coluna),
+            None => "NULL, 0, 0".to_string(),
+        }
+    }
+
+    fn verificar_nulo(&self, referencia: &str, mensagem: &str) -> String {
+        format!(
+            "pds_verificar_nulo({}, {}, {});",
+            referencia,
+            literal_texto(mensagem),
+            self.posicao_c()
+        )
+    }
+
+    fn nome_tipo(&self, tipo: &TipoC) -> String {
+        match tipo {
+            TipoC::Inteiro => "inteiro".to_string(),
+            TipoC::Booleano => "booleano".to_string(),
+            TipoC::Flutuante => "flutuante".to_string(),
+            TipoC::Duplo => "duplo".to_string(),
+            TipoC::Texto => "texto".to_string(),
+            TipoC::Vazio => "vazio".to_string(),
+            TipoC::Array(_) => "array".to_string(),
+            TipoC::Classe(n) | TipoC::Interface(n) => n.clone(),
+            TipoC::Objeto => "objeto".to_string(),
+            TipoC::Nulo => "nulo".to_string(),
+        }
+    }
+
+    /// Gera os operandos na ordem e guarda em temporários os que o C poderia avaliar fora de
+    /// ordem: um operando que lê memória ou tem efeitos não pode ficar depois de uma chamada.
+    fn operandos(&mut self, exprs: &[&ast::Expressao]) -> Result<Vec<Expr>, String> {
+        let mut marcas = Vec::new();
+        let mut valores = Vec::new();
+        for expr in exprs {
+            marcas.push(self.prelude.len());
+            valores.push(self.expr(expr)?);
+        }
+        self.ordenar(&mut valores, &marcas);
+        Ok(valores)
+    }
+
+    fn ordenar(&mut self, valores: &mut [Expr], marcas: &[usize]) {
+        let n = valores.len();
+        let mut efeito_depois = false;
+        let mut leitura_depois = false;
+        for i in (0..n).rev() {
+            let ordem = valores[i].ordem;
+            let prelude_depois = i + 1 < n && self.prelude.len() > marcas[i + 1];
+            let precisa = match ordem {
+                Ordem::Estavel => false,
+                Ordem::Leitura => efeito_depois || prelude_depois,
+                Ordem::Efeito => efeito_depois || leitura_depois || prelude_depois,
+            };
+            if precisa {
+                let posicao = if i + 1 < n {
+                    marcas[i + 1]
+                } else {
+                    self.prelude.len()
+                };
+                valores[i] = self.guardar(valores[i].clone(), posicao);
+            }
+            match ordem {
+                Ordem::Efeito => efeito_depois = true,
+                Ordem::Leitura => leitura_depois = true,
+                Ordem::Estavel => {}
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &ast::Expressao) -> Result<Expr, String> {
+        match expr {
+            ast::Expressao::Inteiro(n) => {
+                let codigo = if *n == i64::MIN {
+                    "INT64_MIN".to_string()
+                } else {
+                    n.to_string()
+                };
+                Ok(Expr::novo(codigo, TipoC::Inteiro, Ordem::Estavel, *n >= 0))
+            }
+            ast::Expressao::Texto(s) => {
+                let mut valor = Expr::novo(literal_texto(s), TipoC::Texto, Ordem::Estavel, true);
+                valor.nao_nulo = true;
+                Ok(valor)
+            }
+            ast::Expressao::Booleano(b) => Ok(Expr::novo(
+                if *b { "true" } else { "false" },
+                TipoC::Booleano,
+                Ordem::Estavel,
+                true,
+            )),
+            ast::Expressao::Decimal(lit) | ast::Expressao::DuploLiteral(lit) => Ok(Expr::novo(
+                literal_real(lit, ""),
+                TipoC::Duplo,
+                Ordem::Estavel,
+                !lit.starts_with('-'),
+            )),
+            ast::Expressao::FlutuanteLiteral(lit) => Ok(Expr::novo(
+                literal_real(lit, "f"),
+                TipoC::Flutuante,
+                Ordem::Estavel,
+                !lit.starts_with('-'),
+            )),
+            ast::Expressao::Nulo => Ok(Expr::novo("NULL", TipoC::Nulo, Ordem::Estavel, true)),
+            ast::Expressao::Este => {
+                let classe = self
+                    .classe_atual
+                    .clone()
+                    .filter(|_| self.tem_este)
+                    .ok_or_else(|| "'este' usado fora de um método de instância".to_string())?;
+                let mut valor = Expr::novo("este", TipoC::Classe(classe), Ordem::Estavel, true);
+                valor.nao_nulo = true;
+                Ok(valor)
+            }
+            ast::Expressao::Identificador(nome) => self.ler_variavel(nome),
+            ast::Expressao::ListaLiteral(itens) => {
+                let refs: Vec<&ast::Expressao> = itens.iter().collect();
+                let valores = self.operandos(&refs)?;
+                let tipos: Vec<TipoC> = valores.iter().map(|v| v.tipo.clone()).collect();
+                let item = self.tipo_itens(&tipos);
+                let ordem = valores
+                    .iter()
+                    .map(|v| v.ordem)
+                    .fold(Ordem::Estavel, |a, b| if b > a { b } else { a });
+                let mut args = vec![valores.len().to_string(), Self::elemento(&item).to_string()];
+                for valor in valores {
+                    let valor = self.converter(valor, &item);
+                    // Argumentos variádicos: inteiros chegam como int64_t e referências como void *
+                    args.push(match item {
+                        TipoC::Inteiro if !valor.codigo.starts_with("(int64_t)") => {
+                            format!("(int64_t){}", valor.atomo())
+                        }
+                        TipoC::Classe(_)
+                        | TipoC::Array(_)
+                        | TipoC::Interface(_)
+                        | TipoC::Objeto => {
+                            format!("(void *){}", valor.atomo())
+                        }
+                        _ => valor.codigo,
+                    });
+                }
+                let mut valor = Expr::novo(
+                    format!("pds_array_de({})", args.join(", ")),
+                    TipoC::Array(Box::new(item)),
+                    ordem,
+                    true,
+                );
+                valor.nao_nulo = true;
+                Ok(valor)
+            }
+            ast::Expressao::NovoArray(tipo, tamanho) => {
+                let item = self.tipo_c(tipo, &self.namespace.clone());
+                let tamanho = self.expr(tamanho)?;
+                let tamanho = self.converter(tamanho, &TipoC::Inteiro);
+                let mut valor = Expr::novo(
+                    format!(
+                        "pds_array_novo({}, {}, {})",
+                        tamanho.codigo,
+                        Self::elemento(&item),
+                        self.posicao_c()
+                    ),
+                    TipoC::Array(Box::new(item)),
+                    Ordem::Efeito,
+                    true,
+                );
+                valor.nao_nulo = true;
+                Ok(valor)
+            }
+            ast::Expressao::NovoObjeto(tipo, args) => self.novo_objeto(tipo, args),
+            ast::Expressao::Aritmetica(op, esq, dir) => {
+                let valores = self.operandos(&[esq, dir])?;
+                let [esq, dir]: [Expr; 2] = valores.try_into().unwrap();
+                self.aritmetica(op, esq, dir)
+            }
+            ast::Expressao::Comparacao(op, esq, dir) => {
+                let valores = self.operandos(&[esq, dir])?;
+                let [esq, dir]: [Expr; 2] = valores.try_into().unwrap();
+                self.comparacao(op, esq, dir)
+            }
+            ast::Expressao::Logica(op, esq, dir) => self.logica(op, esq, dir),
+            ast::Expressao::Unario(op, operando) => {
+                let valor = self.expr(operando)?;
+                match (op, &valor.tipo) {
+                    (ast::OperadorUnario::NegacaoLogica, TipoC::Booleano) => Ok(Expr::novo(
+                        format!("!{}", valor.atomo()),
+                        TipoC::Booleano,
+                        valor.ordem,
+                        false,
+                    )),
+                    (ast::OperadorUnario::NegacaoNumerica, tipo) if tipo.numerico() => {
+                        Ok(Expr::novo(
+                            format!("-{}", valor.atomo()),
+                            tipo.clone(),
+                            valor.ordem,
+                            false,
+                        ))
+                    }
+                    (_, tipo) => Err(format!(
+                        "Operador unário incompatível com {}",
+                        self.nome_tipo(tipo)
+                    )),
+                }
+            }
+            ast::Expressao::StringInterpolada(partes) => {
+                let expressoes: Vec<&ast::Expressao> = partes
+                    .iter()
+                    .filter_map(|p| match p {
+                        ast::PartStringInterpolada::Expressao(e) => Some(e),
+                        ast::PartStringInterpolada::Texto(_) => None,
+                    })
+                    .collect();
+                let mut valores = self.operandos(&expressoes)?.into_iter();
+                let mut textos = Vec::new();
+                for parte in partes {
+                    textos.push(match parte {
+                        ast::PartStringInterpolada::Texto(s) => {
+                            Expr::novo(literal_texto(s), TipoC::Texto, Ordem::Estavel, true)
+                        }
+                        ast::PartStringInterpolada::Expressao(_) => {
+                            let valor = valores.next().unwrap();
+                            self.texto_de(valor)
+                        }
+                    });
+                }
+                Ok(self.concatenar(textos))
+            }
+            ast::Expressao::AcessoMembro(alvo, membro) => self.acesso_membro(alvo, membro),
+            ast::Expressao::AcessoIndice(alvo, indice) => {
+                let marca = self.prelude.len();
+                let valores = self.operandos(&[alvo, indice])?;
+                let [mut alvo, indice]: [Expr; 2] = valores.try_into().unwrap();
+                let indice = self.converter(indice, &TipoC::Inteiro);
+                match alvo.tipo.clone() {
+                    TipoC::Array(item) => {
+                        if self.verificacoes && alvo.ordem != Ordem::Estavel {
+                            alvo = self.guardar(alvo, marca);
+                        }
+                        let posicao = self.indice(&alvo, indice);
+                        Ok(Expr::novo(
+                            format!(
+                                "PDS_ITENS({}, {})[{}]",
+                                alvo.codigo,
+                                self.c_tipo(&item),
+                                posicao
+                            ),
+                            *item,
+                            if self.verificacoes {
+                                Ordem::Efeito
+                            } else {
+                                Ordem::Leitura
+                            },
+                            true,
+                        ))
+                    }
+                    TipoC::Texto => Ok(Expr::novo(
+                        format!(
+                            "pds_texto_caractere({}, {}, {})",
+                            alvo.codigo,
+                            indice.codigo,
+                            self.posicao_c()
+                        ),
+                        TipoC::Texto,
+                        Ordem::Efeito,
+                        true,
+                    )),
+                    _ => Err("Acesso por índice requer array ou texto e um inteiro".to_string()),
+                }
+            }
+            ast::Expressao::ChamadaMetodo(alvo, metodo, args) => {
+                self.chamada_metodo(alvo, metodo, args)
+            }
+            ast::Expressao::Chamada(nome, args) => self.chamada(nome, args, false),
+            ast::Expressao::Aguarde(expr) => match expr.as_ref() {
+                ast::Expressao::Chamada(nome, args) => self.chamada(nome, args, true),
+                // Não há tarefa para aguardar: o valor já está pronto
+                outro => self.expr(outro),
+            },
+        }
+    }
+
+    /// Índice verificado (`pds_indice`) ou direto, com `--sem-verificacoes`.
+    fn indice(&self, array: &Expr, indice: Expr) -> String {
+        if self.verificacoes {
+            format!(
+                "pds_indice({}, {}, {})",
+                array.codigo,
+                indice.codigo,
+                self.posicao_c()
+            )
+        } else {
+            indice.codigo
+        }
+    }
+
+    fn ler_variavel(&mut self, nome: &str) -> Result<Expr, String> {
+        let local = self.local(nome).is_some();
+        let (c, tipo) = self.variavel_c(nome)?;
+        let ordem = if local {
+            Ordem::Estavel
+        } else {
+            Ordem::Leitura
+        };
+        Ok(Expr::novo(c, tipo, ordem, true))
+    }
+
+    /// Expressão C e tipo da variável `nome`.
+    fn variavel_c(&self, nome: &str) -> Result<(String, TipoC), String> {
+        match self.variavel(nome) {
+            Some(Variavel::Local(local)) => Ok(local),
+            Some(Variavel::Campo(membro)) => {
+                let (caminho, tipo, _) = self.campo(nome, &membro);
+                Ok((format!("este->{}", caminho), tipo))
+            }
+            Some(Variavel::Estatico(membro)) => Ok((
+                self.estatico_c(&membro.classe.fqn, nome),
+                self.tipo_c(membro.tipo, &membro.classe.namespace),
+            )),
+            None => Err(format!("Variável '{}' não definida", nome)),
+        }
+    }
+
+    fn acesso_membro(&mut self, alvo: &ast::Expressao, membro: &str) -> Result<Expr, String> {
+        if let Some(estatico) = self.alvo_estatico(alvo) {
+            return match estatico {
+                AlvoEstatico::Classe(classe) => {
+                    let (dono, tipo) = self.dono_estatico(&classe, membro).ok_or_else(|| {
+                        format!(
+                            "Membro estático '{}' não encontrado em '{}'",
+                            membro, classe
+                        )
+                    })?;
+                    Ok(Expr::novo(
+                        self.estatico_c(&dono, membro),
+                        tipo,
+                        Ordem::Leitura,
+                        true,
+                    ))
+                }
+                AlvoEstatico::Enum(en) => self.tabelas.enums[&en]
+                    .valores
+                    .iter()
+                    .position(|v| v == membro)
+                    .map(|i| Expr::novo(i.to_string(), TipoC::Inteiro, Ordem::Estavel, true))
+                    .ok_or_else(|| format!("Valor '{}' não existe na enumeração", membro)),
+                AlvoEstatico::Nativo(nome) => Err(format!("Classe '{}' não encontrada", nome)),
+            };
+        }
+        let marca = self.prelude.len();
+        let mut objeto = self.expr(alvo)?;
+        match objeto.tipo.clone() {
+            TipoC::Texto if membro == "tamanho" => Ok(Expr::novo(
+                format!("pds_texto_tamanho({})", objeto.codigo),
+                TipoC::Inteiro,
+                objeto.ordem,
+                true,
+            )),
+            TipoC::Array(_) if membro == "tamanho" => {
+                if self.verificacoes && !objeto.nao_nulo {
+                    if objeto.ordem != Ordem::Estavel {
+                        objeto = self.guardar(objeto, marca);
+                    }
+                    let verificacao = self.verificar_nulo(
+                        &objeto.codigo,
+                        &format!("Acesso a '{}' em referência nula", membro),
+                    );
+                    self.prelude.push(verificacao);
+                }
+                Ok(Expr::novo(
+                    format!("{}->tamanho", objeto.atomo()),
+                    TipoC::Inteiro,
+                    Ordem::Leitura,
+                    true,
+                ))
+            }
+            TipoC::Classe(classe) => {
+                if self.verificacoes && !objeto.nao_nulo {
+                    if objeto.ordem != Ordem::Estavel {
+                        objeto = self.guardar(objeto, marca);
+                    }
+                    let verificacao = self.verificar_nulo(
+                        &objeto.codigo,
+                        &format!("Acesso a '{}' em referência nula", membro),
+                    );
+                    self.prelude.push(verificacao);
+                }
+                if let Some((dono, propriedade)) = self.tabelas.buscar_propriedade(&classe, membro)
+                {
+                    if self.assinatura_obter(&dono, propriedade).is_some() {
+                        let tipo = self.tipo_c(
+                            &propriedade.tipo,
+                            &self.tabelas.classes[&dono].namespace.clone(),
+                        );
+                        let receptor = self.converter(objeto, &TipoC::Classe(dono.clone()));
+                        return Ok(Expr::novo(
+                            format!(
+                                "{}_obter_{}({})",
+                                self.tabelas.classes[&dono].simbolo,
+                                identificador(membro),
+                                receptor.codigo
+                            ),
+                            tipo,
+                            Ordem::Efeito,
+                            true,
+                        ));
+                    }
+                }
+                let (caminho, tipo, _) = self.buscar_campo(&classe, membro).ok_or_else(|| {
+                    format!("Membro '{}' não encontrado na classe '{}'", membro, classe)
+                })?;
+                Ok(Expr::novo(
+                    format!("{}->{}", objeto.atomo(), caminho),
+                    tipo,
+                    Ordem::Leitura.max_com(objeto.ordem),
+                    true,
+                ))
+            }
+            tipo => Err(format!(
+                "Membro '{}' de valores do tipo {} não é suportado no alvo C",
+                membro,
+                self.nome_tipo(&tipo)
+            )),
+        }
+    }
+
+    fn novo_objeto(&mut self, tipo: &ast::Tipo, args: &[ast::Expressao]) -> Result<Expr, String> {
+        let nome = nome_do_tipo(tipo)
+            .ok_or_else(|| format!("Não é possível instanciar o tipo '{}'", tipo))?;
+        let Some(classe) = self.tabelas.resolver_classe(&self.namespace.clone(), nome) else {
+            // Classe que só existe na biblioteca padrão: erro em tempo de execução, como no interpretador
+            return Ok(self.panico(&format!("Classe '{}' não encontrada", nome), TipoC::Objeto));
+        };
+        let decl = self.tabelas.classes[&classe].decl;
+        if decl.eh_abstrata || decl.eh_estatica {
+            let tipo_classe = if decl.eh_abstrata {
+                "abstrata"
+            } else {
+                "estática"
+            };
+            return Ok(self.panico(
+                &format!(
+                    "Não é possível instanciar a classe {} '{}'",
+                    tipo_classe, classe
+                ),
+                TipoC::Classe(classe),
+            ));
+        }
+        let (indice, argumentos) = match self.tabelas.escolher_construtor(&classe, args.len()) {
+            Some(indice) => {
+                let parametros = decl.construtores[indice].parametros.clone();
+                let namespace = self.tabelas.classes[&classe].namespace.clone();
+                (indice, self.argumentos(&parametros, &namespace, args)?)
+            }
+            None => {
+                // Sem construtor: os argumentos são avaliados e descartados
+                for arg in args {
+                    let valor = self.expr(arg)?;
+                    self.descartar(valor);
+                }
+                (0, Vec::new())
+            }
+        };
+        let mut valor = Expr::novo(
+            format!(
+                "{}({})",
+                self.construtor_c(&classe, "novo", indice),
+                argumentos.join(", ")
+            ),
+            TipoC::Classe(classe),
+            Ordem::Efeito,
+            true,
+        );
+        valor.nao_nulo = true;
+        Ok(valor)
+    }
+
+    /// Erro em tempo de execução no lugar de um valor do tipo `tipo`.
+    fn panico(&self, mensagem: &str, tipo: TipoC) -> Expr {
+        let zero = match &tipo {
+            TipoC::Vazio => String::new(),
+            TipoC::Texto => ", (const char *)NULL".to_string(),
+            tipo if tipo.referencia() => format!(", ({})NULL", self.c_tipo(tipo)),
+            tipo => format!(", {}", Self::valor_padrao(tipo)),
+        };
+        Expr::novo(
+            format!(
+                "(pds_panico({}, {}){})",
+                literal_texto(mensagem),
+                self.posicao_c(),
+                zero
+            ),
+            tipo,
+            Ordem::Efeito,
+            true,
+        )
+    }
+
+    /// Argumentos de uma chamada, convertidos para os tipos dos parâmetros; os omitidos
+    /// recebem o valor padrão declarado.
+    fn argumentos(
+        &mut self,
+        parametros: &[ast::Parametro],
+        namespace: &str,
+        args: &[ast::Expressao],
+    ) -> Result<Vec<String>, String> {
+        let padroes: Vec<&ast::Expressao> = parametros
+            .iter()
+            .skip(args.len())
+            .filter_map(|p| p.valor_padrao.as_ref())
+            .collect();
+        let todos: Vec<&ast::Expressao> = args.iter().chain(padroes).collect();
+        let valores = self.operandos(&todos)?;
+        let mut codigos = Vec::new();
+        for (i, parametro) in parametros.iter().enumerate() {
+            let tipo = self.tipo_c(&parametro.tipo, namespace);
+            codigos.push(match valores.get(i) {
+                Some(valor) => self.converter(valor.clone(), &tipo).codigo,
+                // Parâmetro sem argumento nem valor padrão: nulo no interpretador
+                None => match tipo {
+                    TipoC::Texto => "NULL".to_string(),
+                    ref tipo => Self::valor_padrao(tipo).to_string(),
+                },
+            });
+        }
+        Ok(codigos)
+    }
+
+    /// Chamada sem alvo: método da classe atual, função do programa ou intrínseca de console.
+    fn chamada(
+        &mut self,
+        nome: &str,
+        args: &[ast::Expressao],
+        aguarde: bool,
+    ) -> Result<Expr, String> {
+        if let Some(classe) = self.classe_atual.clone() {
+            if let Some((dono, metodo)) = self.tabelas.buscar_metodo(&classe, nome, args.len()) {
+                if self.tem_este && !metodo.eh_estatica {
+                    let mut este = Expr::novo("este", TipoC::Classe(classe), Ordem::Estavel, true);
+                    este.nao_nulo = true;
+                    return self.chamar_metodo_classe(este, &dono, metodo, args);
+                }
+                return self.chamar_estatico(&dono, metodo, args);
+            }
+        }
+        if let Some(fqn) = self.tabelas.resolver_funcao(&self.namespace, nome) {
+            return self.chamar_funcao(&fqn, args, aguarde);
+        }
+        match nome {
+            "EscreverLinha" | "Escrever" | "LerLinha" => {
+                self.nativo(&format!("Console::{}", nome), args)
+            }
+            _ => self.nativo(nome, args),
+        }
+    }
+
+    fn chamar_funcao(
+        &mut self,
+        fqn: &str,
+        args: &[ast::Expressao],
+        aguarde: bool,
+    ) -> Result<Expr, String> {
+        let funcao = &self.tabelas.funcoes[fqn];
+        let decl = funcao.decl;
+        let namespace = funcao.namespace.clone();
+        let c = funcao.simbolo.clone();
+        if let Some(chave) = chave_nativa(&decl.attributes) {
+            return self.nativo(chave, args);
+        }
+        let retorno = self.tipo_retorno(&decl.tipo_retorno, &namespace);
+        if let Some((_, simbolo)) = externo(decl) {
+            return self.chamar_externa(simbolo, decl, &namespace, args);
+        }
+        let argumentos = self.argumentos(&decl.parametros, &namespace, args)?;
+        if aguarde && decl.eh_assincrona {
+            // A função roda no pool de threads; `aguarde` espera o resultado
+            let tarefa = format!("task_await({}_iniciar({}))", c, argumentos.join(", "));
+            let codigo = match &retorno {
+                TipoC::Vazio => tarefa,
+                tipo if tipo.referencia() || *tipo == TipoC::Texto => {
+                    format!("({}){}", self.c_tipo(tipo), tarefa)
+                }
+                tipo => format!("*({} *){}", self.c_tipo(tipo), tarefa),
+            };
+            let atomico = retorno == TipoC::Vazio;
+            return Ok(Expr::novo(codigo, retorno, Ordem::Efeito, atomico));
+        }
+        Ok(Expr::novo(
+            format!("{}({})", c, argumentos.join(", ")),
+            retorno,
+            Ordem::Efeito,
+            true,
+        ))
+    }
+
+    /// Chamada a uma função `[Externo]`: argumentos e retorno convertidos para os tipos C.
+    fn chamar_externa(
+        &mut self,
+        simbolo: &str,
+        decl: &ast::DeclaracaoFuncao,
+        namespace: &str,
+        args: &[ast::Expressao],
+    ) -> Result<Expr, String> {
+        let refs: Vec<&ast::Expressao> = args.iter().collect();
+        let valores = self.operandos(&refs)?;
+        let mut argumentos = Vec::new();
+        for (parametro, valor) in decl.parametros.iter().zip(valores) {
+            let tipo = self.tipo_c(&parametro.tipo, namespace);
+            let valor = self.converter(valor, &tipo);
+            argumentos.push(match tipo {
+                TipoC::Inteiro | TipoC::Booleano => format!("(int){}", valor.atomo()),
+                _ => valor.codigo,
+            });
+        }
+        let chamada = format!("{}({})", simbolo, argumentos.join(", "));
+        let retorno = self.tipo_retorno(&decl.tipo_retorno, namespace);
+        Ok(match retorno {
+            TipoC::Inteiro => Expr::novo(
+                format!("(int64_t){}", chamada),
+                retorno,
+                Ordem::Efeito,
+                false,
+            ),
+            TipoC::Booleano => {
+                Expr::novo(format!("{} != 0", chamada), retorno, Ordem::Efeito, false)
+            }
+            _ => Expr::novo(chamada, retorno, Ordem::Efeito, true),
+        })
+    }
+
+    fn chamar_estatico(
+        &mut self,
+        classe: &str,
+        metodo: &ast::MetodoClasse,
+        args: &[ast::Expressao],
+    ) -> Result<Expr, String> {
+        if let Some(chave) = chave_nativa(&metodo.attributes) {
+            return self.nativo(chave, args);
+        }
+        let namespace = self.tabelas.classes[classe].namespace.clone();
+        let (retorno, _) = self.assinatura(classe, metodo);
+        let argumentos = self.argumentos(&metodo.parametros, &namespace, args)?;
+        if !metodo.eh_estatica {
+            return Err(format!(
+                "Método de instância '{}' chamado sem objeto em '{}'",
+                metodo.nome, classe
+            ));
+        }
+        Ok(Expr::novo(
+            format!(
+                "{}({})",
+                self.metodo_c(classe, &metodo.nome),
+                argumentos.join(", ")
+            ),
+            retorno,
+            Ordem::Efeito,
+            true,
+        ))
+    }
+
+    fn chamada_metodo(
+        &mut self,
+        alvo: &ast::Expressao,
+        metodo: &str,
+        args: &[ast::Expressao],
+    ) -> Result<Expr, String> {
+        if let Some(estatico) = self.alvo_estatico(alvo) {
+            return match estatico {
+                AlvoEstatico::Classe(classe) => {
+                    let (dono, decl) = self
+                        .tabelas
+                        .buscar_metodo(&classe, metodo, args.len())
+                        .ok_or_else(|| {
+                            format!("Método '{}' não encontrado na classe '{}'", metodo, classe)
+                        })?;
+                    self.chamar_estatico(&dono, decl, args)
+                }
+                AlvoEstatico::Nativo(classe) => {
+                    self.nativo(&format!("{}::{}", classe, metodo), args)
+                }
+                AlvoEstatico::Enum(_) => Err(format!("Enumerações não têm o método '{}'", metodo)),
+            };
+        }
+        let marca = self.prelude.len();
+        let mut objeto = self.expr(alvo)?;
+        match objeto.tipo.clone() {
+            TipoC::Texto => self.metodo_texto(objeto, metodo, args),
+            TipoC::Classe(classe) => {
+                let (dono, decl) = self
+                    .tabelas
+                    .buscar_metodo(&classe, metodo, args.len())
+                    .ok_or_else(|| {
+                        format!("Método '{}' não encontrado na classe '{}'", metodo, classe)
+                    })?;
+                if decl.eh_estatica {
+                    return self.chamar_estatico(&dono, decl, args);
+                }
+                if objeto.ordem != Ordem::Estavel {
+                    objeto = self.guardar(objeto, marca);
+                }
+                self.chamar_metodo_classe(objeto, &dono, decl, args)
+            }
+            TipoC::Interface(_) | TipoC::Objeto => {
+                if objeto.ordem != Ordem::Estavel {
+                    objeto = self.guardar(objeto, marca);
+                }
+                self.chamar_despacho(objeto, metodo, args)
+            }
+            tipo => Err(format!(
+                "Método '{}' não existe para valores do tipo {}",
+                metodo,
+                self.nome_tipo(&tipo)
+            )),
+        }
+    }
+
+    /// Chamada de método de instância: direta, ou pela vtable quando o método tem entrada nela.
+    fn chamar_metodo_classe(
+        &mut self,
+        objeto: Expr,
+        dono: &str,
+        metodo: &ast::MetodoClasse,
+        args: &[ast::Expressao],
+    ) -> Result<Expr, String> {
+        if let Some(chave) = chave_nativa(&metodo.attributes) {
+            return Err(format!(
+                "Método nativo '{}' ({}) não é suportado no alvo C",
+                metodo.nome, chave
+            ));
+        }
+        let namespace = self.tabelas.classes[dono].namespace.clone();
+        let (retorno, _) = self.assinatura(dono, metodo);
+        let argumentos = self.argumentos(&metodo.parametros, &namespace, args)?;
+        if self.verificacoes && !objeto.nao_nulo {
+            let verificacao = self.verificar_nulo(
+                &objeto.codigo,
+                &format!("Chamada de '{}' em referência nula", metodo.nome),
+            );
+            self.prelude.push(verificacao);
+        }
+        let (funcao, receptor) = match self.entrada_vtable(dono, &metodo.nome) {
+            Some(introduz) => (
+                format!("{}_virtual", self.metodo_c(&introduz, &metodo.nome)),
+                introduz,
+            ),
+            None => (self.metodo_c(dono, &metodo.nome), dono.to_string()),
+        };
+        let receptor = self.converter(objeto, &TipoC::Classe(receptor));
+        let mut lista = vec![receptor.codigo];
+        lista.extend(argumentos);
+        Ok(Expr::novo(
+            format!("{}({})", funcao, lista.join(", ")),
+            retorno,
+            Ordem::Efeito,
+            true,
+        ))
+    }
+
+    /// Chamada em interface ou `objeto`: função de despacho pelo nome do método.
+    fn chamar_despacho(
+        &mut self,
+        objeto: Expr,
+        metodo: &str,
+        args: &[ast::Expressao],
+    ) -> Result<Expr, String> {
+        let chave = (metodo.to_string(), args.len());
+        if !self.despachos.contains_key(&chave) {
+            let despacho = self.assinatura_despacho_para(&objeto.tipo, metodo, args.len())?;
+            self.despachos.insert(chave.clone(), despacho);
+        }
+        let (parametros, retorno) = {
+            let despacho = &self.despachos[&chave];
+            (despacho.parametros.clone(), despacho.retorno.clone())
+        };
+        let refs: Vec<&ast::Expressao> = args.iter().collect();
+        let valores = self.operandos(&refs)?;
+        if self.verificacoes && !objeto.nao_nulo {
+            let verificacao = self.verificar_nulo(
+                &objeto.codigo,
+                &format!("Chamada de '{}' em referência nula", metodo),
+            );
+            self.prelude.push(verificacao);
+        }
+        let mut lista = vec![objeto.codigo.clone()];
+        for (valor, tipo) in valores.into_iter().zip(&parametros) {
+            lista.push(self.converter(valor, tipo).codigo);
+        }
+        Ok(Expr::novo(
+            format!(
+                "{}({})",
+                Self::nome_despacho(metodo, args.len()),
+                lista.join(", ")
+            ),
+            retorno,
+            Ordem::Efeito,
+            true,
+        ))
+    }
+
+    /// Tipos da função de despacho: a assinatura da interface, ou do primeiro método encontrado.
+    fn assinatura_despacho_para(
+        &self,
+        tipo: &TipoC,
+        metodo: &str,
+        n: usize,
+    ) -> Result<Despacho, String> {
+        if let TipoC::Interface(interface) = tipo {
+            let interface = &self.tabelas.interfaces[interface];
+            let namespace = interface.namespace.as_str();
+            if let Some(assinatura) = interface
+                .decl
+                .metodos
+                .iter()
+                .find(|m| m.nome == metodo && m.parametros.len() == n)
+            {
+                return Ok(Despacho {
+                    nome: metodo.to_string(),
+                    parametros: assinatura
+                        .parametros
+                        .iter()
+                        .map(|p| self.tipo_c(&p.tipo, namespace))
+                        .collect(),
+                    retorno: self.tipo_retorno(&assinatura.tipo_retorno, namespace),
+                });
+            }
+        }
+        for fqn in &self.tabelas.ordem_classes {
+            if let Some((dono, decl)) = self.tabelas.buscar_metodo(fqn, metodo, n) {
+                if decl.eh_estatica {
+                    continue;
+                }
+                let (retorno, parametros) = self.assinatura(&dono, decl);
+                return Ok(Despacho {
+                    nome: metodo.to_string(),
+                    parametros: parametros.into_iter().take(n).collect(),
+                    retorno,
+                });
+            }
+        }
+        // Nenhuma classe tem o método: a chamada termina o programa com erro
+        Ok(Despacho {
+            nome: metodo.to_string(),
+            parametros: vec![TipoC::Objeto; n],
+            retorno: TipoC::Objeto,
+        })
+    }
+
+    /// Métodos embutidos de `texto` (os mesmos do interpretador).
+    fn metodo_texto(
+        &mut self,
+        texto: Expr,
+        metodo: &str,
+        args: &[ast::Expressao],
+    ) -> Result<Expr, String> {
+        let mut refs: Vec<&ast::Expressao> = args.iter().collect();
+        refs.truncate(2);
+        let mut valores = vec![texto];
+        let marcas_inicio = self.prelude.len();
+        let mut marcas = vec![0];
+        for arg in &refs {
+            marcas.push(self.prelude.len());
+            valores.push(self.expr(arg)?);
+        }
+        marcas[0] = marcas_inicio;
+        self.ordenar(&mut valores, &marcas);
+        let mut valores = valores.into_iter();
+        let texto = valores.next().unwrap();
+        let mut arg = || {
+            valores
+                .next()
+                .map(|v| self.texto_de(v).codigo)
+                .unwrap_or_else(|| "\"\"".to_string())
+        };
+        let (codigo, tipo) = match metodo {
+            "comprimento" => (
+                format!("pds_texto_tamanho({})", texto.codigo),
+                TipoC::Inteiro,
+            ),
+            "ParaMaiusculo" => (
+                format!("pds_texto_maiusculo({})", texto.codigo),
+                TipoC::Texto,
+            ),
+            "ParaMinusculo" => (
+                format!("pds_texto_minusculo({})", texto.codigo),
+                TipoC::Texto,
+            ),
+            "Aparar" => (format!("pds_texto_aparar({})", texto.codigo), TipoC::Texto),
+            "Contem" => (
+                format!("pds_texto_contem({}, {})", texto.codigo, arg()),
+                TipoC::Booleano,
+            ),
+            "Substituir" => {
+                let de = arg();
+                let para = arg();
+                (
+                    format!("pds_texto_substituir({}, {}, {})", texto.codigo, de, para),
+                    TipoC::Texto,
+                )
+            }
+            _ => return Err(erro_metodo_texto(metodo)),
+        };
+        Ok(Expr::novo(
+            codigo,
+            tipo,
+            texto.ordem.max_com(Ordem::Leitura),
+            true,
+        ))
+    }
+
+    /// Funções nativas do interpretador (`runtime::nativos`) disponíveis no alvo C.
+    fn nativo(&mut self, chave: &str, args: &[ast::Expressao]) -> Result<Expr, String> {
+        let refs: Vec<&ast::Expressao> = args.iter().collect();
+        let valores = self.operandos(&refs)?;
+        let textos: Vec<String> = valores
+            .into_iter()
+            .map(|v| self.texto_de(v).codigo)
+            .collect();
+        let texto = |i: usize| textos.get(i).cloned().unwrap_or_else(|| "\"\"".to_string());
+        let posicao = self.posicao_c();
+        let (codigo, tipo) = match chave {
+            "Console::EscreverLinha" | "Console::Escrever" => {
+                let funcao = if chave == "Console::EscreverLinha" {
+                    "pds_imprimir"
+                } else {
+                    "pds_escrever"
+                };
+                let mensagem = match textos.len() {
+                    0 => "\"\"".to_string(),
+                    1 => textos[0].clone(),
+                    n => format!("pds_concatenar({}, {})", n, textos.join(", ")),
+                };
+                (format!("{}({})", funcao, mensagem), TipoC::Vazio)
+            }
+            "Console::LerLinha" => ("pds_ler_linha()".to_string(), TipoC::Texto),
+            "Arquivo::LerTexto" => (
+                format!("pds_arquivo_ler({}, {})", texto(0), posicao),
+                TipoC::Texto,
+            ),
+            "Arquivo::EscreverTexto" | "Arquivo::AdicionarTexto" => (
+                format!(
+                    "pds_arquivo_escrever({}, {}, {}, {})",
+                    texto(0),
+                    texto(1),
+                    chave == "Arquivo::AdicionarTexto",
+                    posicao
+                ),
+                TipoC::Vazio,
+            ),
+            "Arquivo::Existe" => (format!("pds_arquivo_existe({})", texto(0)), TipoC::Booleano),
+            "Arquivo::Excluir" => (
+                format!("pds_arquivo_excluir({}, {})", texto(0), posicao),
+                TipoC::Vazio,
+            ),
+            // As variantes assíncronas usam as tarefas nativas de async_runtime.c
+            "LerArquivoAssíncrono" | "Arquivo::LerTextoAssíncrono" => (
+                format!(
+                    "pds_aguardar_leitura(task_create_read_file({}), {})",
+                    texto(0),
+                    posicao
+                ),
+                TipoC::Texto,
+            ),
+            "EscreverArquivoAssíncrono" | "Arquivo::EscreverTextoAssíncrono" => (
+                format!(
+                    "pds_aguardar_escrita(task_create_write_file({}, {}), {})",
+                    texto(0),
+                    texto(1),
+                    posicao
+                ),
+                TipoC::Objeto,
+            ),
+            "VerificarArquivoAssíncrono" => (
+                format!(
+                    "pds_aguardar_existencia(task_create_file_exists({}))",
+                    texto(0)
+                ),
+                TipoC::Booleano,
+            ),
+            _ if chave.contains("::") => {
+                return Err(format!(
+                    "Função nativa '{}' não é suportada no alvo C",
+                    chave
+                ))
+            }
+            _ => return Err(format!("Função '{}' não encontrada", chave)),
+        };
+        Ok(Expr::novo(codigo, tipo, Ordem::Efeito, true))
+    }
+
+    fn logica(
+        &mut self,
+        op: &ast::OperadorLogico,
+        esq: &ast::Expressao,
+        dir: &ast::Expressao,
+    ) -> Result<Expr, String> {
+        let (operador, curto) = match op {
+            ast::OperadorLogico::E => ("&&", ""),
+            ast::OperadorLogico::Ou => ("||", "!"),
+        };
+        let esq = self.expr(esq)?;
+        let marca = self.prelude.len();
+        let dir = self.expr(dir)?;
+        for valor in [&esq, &dir] {
+            if valor.tipo != TipoC::Booleano {
+                return Err(format!(
+                    "Condição deve ser booleana, obtido {}",
+                    self.nome_tipo(&valor.tipo)
+                ));
+            }
+        }
+        if self.prelude.len() == marca {
+            return Ok(Expr::novo(
+                format!("{} {} {}", esq.atomo(), operador, dir.atomo()),
+                TipoC::Booleano,
+                esq.ordem.max_com(dir.ordem),
+                false,
+            ));
+        }
+        // O lado direito precisa de comandos: só são executados se a avaliação continuar
+        let comandos = self.prelude.split_off(marca);
+        let resultado = self.temporario();
+        self.prelude
+            .push(format!("bool {} = {};", resultado, esq.codigo));
+        self.prelude.push(format!("if ({}{}) {{", curto, resultado));
+        for comando in comandos {
+            self.prelude.push(format!("    {}", comando));
+        }
+        self.prelude
+            .push(format!("    {} = {};", resultado, dir.codigo));
+        self.prelude.push("}".to_string());
+        Ok(Expr::novo(resultado, TipoC::Booleano, Ordem::Estavel, true))
+    }
+
+    fn concatenar(&self, textos: Vec<Expr>) -> Expr {
+        let ordem = textos
+            .iter()
+            .map(|t| t.ordem)
+            .fold(Ordem::Estavel, Ordem::max_com);
+        let mut partes = Vec::new();
+        for texto in textos {
+            if texto.partes.is_empty() {
+                partes.push(texto.codigo);
+            } else {
+                partes.extend(texto.partes);
+            }
+        }
+        let mut valor = Expr::novo(
+            format!("pds_concatenar({}, {})", partes.len(), partes.join(", ")),
+            TipoC::Texto,
+            ordem,
+            true,
+        );
+        valor.partes = partes;
+        valor.nao_nulo = true;
+        valor
+    }
+
+    fn aritmetica(
+        &mut self,
+        op: &ast::OperadorAritmetico,
+        esq: Expr,
+        dir: Expr,
+    ) -> Result<Expr, String> {
+        // Texto em qualquer lado de `+` concatena
+        if *op == ast::OperadorAritmetico::Soma
+            && (esq.tipo == TipoC::Texto || dir.tipo == TipoC::Texto)
+        {
+            let textos = vec![self.texto_de(esq), self.texto_de(dir)];
+            return Ok(self.concatenar(textos));
+        }
+        if !esq.tipo.numerico() || !dir.tipo.numerico() {
+            return Err(format!(
+                "Tipos incompatíveis para {}: {} e {}",
+                match op {
+                    ast::OperadorAritmetico::Soma => "soma",
+                    ast::OperadorAritmetico::Subtracao => "subtração",
+                    ast::OperadorAritmetico::Multiplicacao => "multiplicação",
+                    ast::OperadorAritmetico::Divisao => "divisão",
+                    ast::OperadorAritmetico::Modulo => "módulo",
+                },
+                self.nome_tipo(&esq.tipo),
+                self.nome_tipo(&dir.tipo)
+            ));
+        }
+        // Inteiro com flutuante resulta em flutuante; qualquer duplo promove para duplo
+        let tipo = match (&esq.tipo, &dir.tipo) {
+            (TipoC::Inteiro, TipoC::Inteiro) => TipoC::Inteiro,
+            (TipoC::Duplo, _) | (_, TipoC::Duplo) => TipoC::Duplo,
+            _ => TipoC::Flutuante,
+        };
+        let ordem = esq.ordem.max_com(dir.ordem);
+        let esq = self.converter(esq, &tipo);
+        let dir = self.converter(dir, &tipo);
+        let operador = match op {
+            ast::OperadorAritmetico::Soma => "+",
+            ast::OperadorAritmetico::Subtracao => "-",
+            ast::OperadorAritmetico::Multiplicacao => "*",
+            ast::OperadorAritmetico::Divisao => "/",
+            ast::OperadorAritmetico::Modulo => "%",
+        };
+        let divide = matches!(
+            op,
+            ast::OperadorAritmetico::Divisao | ast::OperadorAritmetico::Modulo
+        );
+        // O C não tem `%` para reais: o resto de `flutuante`/`duplo` sempre usa fmod
+        let resto_real = tipo != TipoC::Inteiro && operador == "%";
+        if !divide || (!self.verificacoes && !resto_real) {
+            return Ok(Expr::novo(
+                format!("{} {} {}", esq.atomo(), operador, dir.atomo()),
+                tipo,
+                ordem,
+                false,
+            ));
+        }
+        let funcao = match (&tipo, operador, self.verificacoes) {
+            (_, "%", false) => "pds_resto_real",
+            (TipoC::Inteiro, "/", _) => "pds_dividir",
+            (TipoC::Inteiro, _, _) => "pds_resto",
+            (_, "/", _) => "pds_dividir_duplo",
+            _ => "pds_resto_duplo",
+        };
+        let mut args = vec![esq.codigo, dir.codigo];
+        if self.verificacoes {
+            args.push(self.posicao_c());
+        }
+        let chamada = format!("{}({})", funcao, args.join(", "));
+        Ok(if tipo == TipoC::Flutuante {
+            Expr::novo(format!("(float){}", chamada), tipo, Ordem::Efeito, false)
+        } else {
+            Expr::novo(chamada, tipo, Ordem::Efeito, true)
+        })
+    }
+
+    fn comparacao(
+        &mut self,
+        op: &ast::OperadorComparacao,
+        esq: Expr,
+        dir: Expr,
+    ) -> Result<Expr, String> {
+        use ast::OperadorComparacao as Op;
+        let igualdade = matches!(op, Op::Igual | Op::Diferente);
+        let operador = match op {
+            Op::Igual => "==",
+            Op::Diferente => "!=",
+            Op::Menor => "<",
+            Op::MaiorQue => ">",
+            Op::MenorIgual => "<=",
+            Op::MaiorIgual => ">=",
+        };
+        let ordem = esq.ordem.max_com(dir.ordem);
+        let booleano =
+            |codigo: String, atomico: bool| Expr::novo(codigo, TipoC::Booleano, ordem, atomico);
+        let negar = |codigo: String| {
+            if *op == Op::Diferente {
+                format!("!{}", codigo)
+            } else {
+                codigo
+            }
+        };
+        // Valores de tipos diferentes nunca são iguais
+        let constante = |esq: &Expr, dir: &Expr| {
+            let resultado = if *op == Op::Igual { "false" } else { "true" };
+            if esq.ordem == Ordem::Estavel && dir.ordem == Ordem::Estavel {
+                booleano(resultado.to_string(), true)
+            } else {
+                booleano(
+                    format!(
+                        "((void){}, (void){}, {})",
+                        esq.atomo(),
+                        dir.atomo(),
+                        resultado
+                    ),
+                    true,
+                )
+            }
+        };
+        match (&esq.tipo, &dir.tipo) {
+            (a, b) if a.numerico() && b.numerico() => {
+                if a == b {
+                    Ok(booleano(
+                        format!("{} {} {}", esq.atomo(), operador, dir.atomo()),
+                        false,
+                    ))
+                } else if igualdade {
+                    Ok(constante(&esq, &dir))
+                } else {
+                    let esq = self.converter(esq, &TipoC::Duplo);
+                    let dir = self.converter(dir, &TipoC::Duplo);
+                    Ok(booleano(
+                        format!("{} {} {}", esq.atomo(), operador, dir.atomo()),
+                        false,
+                    ))
+                }
+            }
+            (TipoC::Booleano, TipoC::Booleano) if igualdade => Ok(booleano(
+                format!("{} {} {}", esq.atomo(), operador, dir.atomo()),
+                false,
+            )),
+            (TipoC::Texto, TipoC::Texto) => {
+                if igualdade {
+                    Ok(booleano(
+                        negar(format!("pds_textos_iguais({}, {})", esq.codigo, dir.codigo)),
+                        *op == Op::Igual,
+                    ))
+                } else {
+                    Ok(booleano(
+                        format!(
+                            "pds_comparar_textos({}, {}) {} 0",
+                            esq.codigo, dir.codigo, operador
+                        ),
+                        false,
+                    ))
+                }
+            }
+            (TipoC::Texto, TipoC::Nulo) | (TipoC::Nulo, TipoC::Texto) if igualdade => Ok(booleano(
+                format!("{} {} {}", esq.atomo(), operador, dir.atomo()),
+                false,
+            )),
+            (a, b) if igualdade && (a.dinamico() || b.dinamico()) => {
+                let esq = self.converter(esq, &TipoC::Objeto);
+                let dir = self.converter(dir, &TipoC::Objeto);
+                Ok(booleano(
+                    negar(format!(
+                        "pds_objetos_iguais({}, {})",
+                        esq.codigo, dir.codigo
+                    )),
+                    *op == Op::Igual,
+                ))
+            }
+            (a, b) if igualdade && a.referencia() && b.referencia() => {
+                let (esq, dir) = if a == b || *a == TipoC::Nulo || *b == TipoC::Nulo {
+                    (esq.atomo(), dir.atomo())
+                } else {
+                    (
+                        format!("(void *){}", esq.atomo()),
+                        format!("(void *){}", dir.atomo()),
+                    )
+                };
+                Ok(booleano(format!("{} {} {}", esq, operador, dir), false))
+            }
+            _ if igualdade => Ok(constante(&esq, &dir)),
+            (a, b) => Err(format!(
+                "Tipos incompatíveis para comparação: {} e {}",
+                self.nome_tipo(a),
+                self.nome_tipo(b)
+            )),
+        }
+    }
+
+    /// Converte o valor para o tipo esperado (promoções numéricas, conversões entre classes e
+    /// caixas para valores primitivos em variáveis do tipo `objeto`).
+    fn converter(&self, valor: Expr, para: &TipoC) -> Expr {
+        let de = valor.tipo.clone();
+        if de == *para || *para == TipoC::Vazio {
+            return valor;
+        }
+        let convertido = |codigo: String, atomico: bool| Expr {
+            codigo,
+            tipo: para.clone(),
+            atomico,
+            partes: Vec::new(),
+            ..valor.clone()
+        };
+        match (&de, para) {
+            (TipoC::Nulo, _) => convertido("NULL".to_string(), true),
+            (a, b) if a.numerico() && b.numerico() => {
+                convertido(format!("({}){}", self.c_tipo(b), valor.atomo()), false)
+            }
+            (TipoC::Classe(_), TipoC::Classe(_))
+            | (TipoC::Interface(_) | TipoC::Objeto, TipoC::Classe(_) | TipoC::Array(_))
+            | (TipoC::Classe(_) | TipoC::Array(_), TipoC::Interface(_) | TipoC::Objeto) => {
+                convertido(format!("({}){}", self.c_tipo(para), valor.atomo()), false)
+            }
+            (TipoC::Interface(_) | TipoC::Objeto, TipoC::Interface(_) | TipoC::Objeto) => {
+                convertido(valor.codigo.clone(), valor.atomico)
+            }
+            (primitivo, TipoC::Interface(_) | TipoC::Objeto) => {
+                let caixa = match primitivo {
+                    TipoC::Inteiro => "pds_caixa_inteiro",
+                    TipoC::Flutuante => "pds_caixa_flutuante",
+                    TipoC::Duplo => "pds_caixa_duplo",
+                    TipoC::Booleano => "pds_caixa_booleano",
+                    TipoC::Texto => "pds_caixa_texto",
+                    _ => return valor,
+                };
+                convertido(format!("{}({})", caixa, valor.codigo), true)
+            }
+            (TipoC::Interface(_) | TipoC::Objeto, primitivo) => {
+                let abrir = match primitivo {
+                    TipoC::Inteiro => "pds_inteiro_de",
+                    TipoC::Flutuante => "pds_flutuante_de",
+                    TipoC::Duplo => "pds_duplo_de",
+                    TipoC::Booleano => "pds_booleano_de",
+                    TipoC::Texto => "pds_texto_de",
+                    _ => return valor,
+                };
+                convertido(format!("{}({})", abrir, valor.codigo), true)
+            }
+            _ => valor,
+        }
+    }
+
+    /// Texto exibido por `imprima` para o valor.
+    fn texto_de(&self, valor: Expr) -> Expr {
+        let funcao = match &valor.tipo {
+            TipoC::Texto => {
+                if valor.nao_nulo {
+                    return valor;
+                }
+                "pds_texto"
+            }
+            TipoC::Inteiro => "pds_texto_inteiro",
+            TipoC::Duplo => "pds_texto_duplo",
+            TipoC::Flutuante => {
+                return Expr::novo(
+                    format!("pds_texto_duplo((double){})", valor.atomo()),
+                    TipoC::Texto,
+                    valor.ordem,
+                    true,
+                )
+            }
+            TipoC::Booleano => "pds_texto_booleano",
+            TipoC::Vazio => {
+                return Expr::novo(
+                    format!("({}, \"nulo\")", valor.codigo),
+                    TipoC::Texto,
+                    valor.ordem,
+                    true,
+                )
+            }
+            _ => "pds_texto_objeto",
+        };
+        let mut texto = Expr::novo(
+            format!("{}({})", funcao, valor.codigo),
+            TipoC::Texto,
+            valor.ordem,
+            true,
+        );
+        texto.nao_nulo = true;
+        texto
+    }
+}
+
+impl Ordem {
+    fn max_com(self, outra: Ordem) -> Ordem {
+        if outra > self {
+            outra
+        } else {
+            self
+        }
+    }
+}
+
+impl Expr {
+    /// Um valor estável não tem comandos pendentes próprios (usado ao descartar expressões).
+    fn prelude_vazio(&self) -> bool {
+        self.ordem == Ordem::Estavel
+    }
+}
@@ -1,111 +1,3350 @@
+//! Gerador de CIL (Common Intermediate Language) para o alvo `--target=cil-bytecode`.
+//!
+//! O programa vira um assembly .NET em formato texto (`.il`), montado pelo `ilasm` do .NET ou do
+//! Mono. O runtime escrito em IL (`src/runtime/cil_runtime.il`) é incluído no arquivo.
+//!
+//! - Cada classe da linguagem vira uma classe .NET com os mesmos campos, propriedades (`get_`,
+//!   `set_` e `.property`), construtores e métodos. As classes raiz derivam de
+//!   `PorDoSol.Objeto`, que executa os inicializadores de campos antes dos construtores.
+//! - Métodos redefiníveis, abstratos ou redefinidos em alguma subclasse são virtuais e chamados
+//!   com `callvirt`, pelas mesmas regras de vtable dos alvos C e wasm.
+//! - Interfaces viram interfaces .NET; as classes que as declaram ganham implementações
+//!   explícitas (`.override`) que encaminham para os métodos da classe.
+//! - Chamadas em valores do tipo `objeto` passam por métodos de despacho pelo nome do método,
+//!   que testam a classe do objeto com `isinst`.
+//! - `inteiro` é `int64`, `flutuante` é `float32`, `decimal`/`duplo` são `float64`; valores
+//!   primitivos guardados em `objeto` usam as caixas do .NET (`box`).
+//! - Funções, inicializadores de estáticos, comandos de nível superior e o ponto de entrada
+//!   ficam na classe `PorDoSol.Programa`.
+//!
+//! Como nos alvos C e wasm, a semântica acompanha o interpretador de AST: ordem de avaliação,
+//! exibição dos valores e mensagens dos erros de execução. [`verificar`] confere a estrutura do
+//! texto gerado (chaves, rótulos, pilha, locais e referências) sem depender do `ilasm`.
+
+use super::tabelas::{
+    chave_nativa, erro_metodo_texto, nome_do_tipo, AlvoEstatico, Escopo, Membro, Tabelas,
+    TipoNomeado, Variavel,
+};
 use crate::ast;
+use crate::type_checker::VerificadorTipos;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Runtime em IL incluído em todo assembly gerado.
+const RUNTIME: &str = include_str!("../runtime/cil_runtime.il");
+
+/// Classe das funções do programa e do ponto de entrada.
+const PROGRAMA: &str = "PorDoSol.Programa";
+
+const CONCATENAR: &str = "call string [mscorlib]System.String::Concat(string, string)";
+const VERIFICAR_NULO: &str =
+    "call void PorDoSol.Runtime::VerificarNulo(object, string, string, int32, int32)";
+
+/// Tipo de um valor no código gerado (os tipos da linguagem já resolvidos).
+#[derive(Debug, Clone, PartialEq)]
+enum TipoIl {
+    Inteiro,
+    Booleano,
+    Flutuante,
+    Duplo,
+    Texto,
+    Vazio,
+    Array(Box<TipoIl>),
+    /// FQN de uma classe do programa.
+    Classe(String),
+    /// FQN de uma interface do programa.
+    Interface(String),
+    /// `objeto`, parâmetros genéricos e classes desconhecidas.
+    Objeto,
+    /// Tipo do literal `nulo`.
+    Nulo,
+}
+
+impl TipoIl {
+    fn numerico(&self) -> bool {
+        matches!(self, TipoIl::Inteiro | TipoIl::Flutuante | TipoIl::Duplo)
+    }
+
+    /// Tipos de valor do .NET (guardados em `objeto` por `box`).
+    fn primitivo(&self) -> bool {
+        self.numerico() || *self == TipoIl::Booleano
+    }
+
+    fn referencia(&self) -> bool {
+        matches!(
+            self,
+            TipoIl::Array(_)
+                | TipoIl::Classe(_)
+                | TipoIl::Interface(_)
+                | TipoIl::Objeto
+                | TipoIl::Nulo
+        )
+    }
+
+    /// Pode conter caixas de valores primitivos.
+    fn dinamico(&self) -> bool {
+        matches!(self, TipoIl::Interface(_) | TipoIl::Objeto)
+    }
+
+    /// Tipo nas assinaturas, campos e locais.
+    fn il(&self) -> String {
+        match self {
+            TipoIl::Inteiro => "int64".to_string(),
+            TipoIl::Booleano => "bool".to_string(),
+            TipoIl::Flutuante => "float32".to_string(),
+            TipoIl::Duplo => "float64".to_string(),
+            TipoIl::Texto => "string".to_string(),
+            TipoIl::Vazio => "void".to_string(),
+            TipoIl::Array(item) => format!("{}[]", item.il()),
+            TipoIl::Classe(fqn) | TipoIl::Interface(fqn) => format!("class {}", nome_tipo(fqn)),
+            TipoIl::Objeto | TipoIl::Nulo => "object".to_string(),
+        }
+    }
+
+    /// Operando de `box`, `newarr`, `castclass` e `isinst`.
+    fn token(&self) -> String {
+        match self {
+            TipoIl::Inteiro => "[mscorlib]System.Int64".to_string(),
+            TipoIl::Booleano => "[mscorlib]System.Boolean".to_string(),
+            TipoIl::Flutuante => "[mscorlib]System.Single".to_string(),
+            TipoIl::Duplo => "[mscorlib]System.Double".to_string(),
+            TipoIl::Texto => "[mscorlib]System.String".to_string(),
+            TipoIl::Array(_) => self.il(),
+            TipoIl::Classe(fqn) | TipoIl::Interface(fqn) => nome_tipo(fqn),
+            _ => "[mscorlib]System.Object".to_string(),
+        }
+    }
+
+    /// Sufixo de `ldelem` e `stelem` para itens deste tipo.
+    fn elemento(&self) -> (&'static str, &'static str) {
+        match self {
+            TipoIl::Inteiro => ("i8", "i8"),
+            TipoIl::Flutuante => ("r4", "r4"),
+            TipoIl::Duplo => ("r8", "r8"),
+            TipoIl::Booleano => ("u1", "i1"),
+            _ => ("ref", "ref"),
+        }
+    }
+}
+
+/// Tipo e nulidade do valor deixado na pilha por uma expressão.
+#[derive(Debug, Clone)]
+struct Valor {
+    tipo: TipoIl,
+    /// Referência que nunca é nula (`este`, objetos recém-criados, literais).
+    nao_nulo: bool,
+}
+
+impl Valor {
+    fn novo(tipo: TipoIl) -> Self {
+        Self {
+            tipo,
+            nao_nulo: false,
+        }
+    }
+
+    fn nao_nulo(tipo: TipoIl) -> Self {
+        Self {
+            tipo,
+            nao_nulo: true,
+        }
+    }
+}
+
+/// Método de despacho pelo nome do método, para receptores do tipo `objeto`.
+#[derive(Clone)]
+struct Despacho {
+    nome: String,
+    parametros: Vec<TipoIl>,
+    retorno: TipoIl,
+}
+
+/// Parâmetro (índice no método, contando `este`) ou variável local.
+#[derive(Debug, Clone, Copy)]
+enum Slot {
+    Argumento(usize),
+    Local(usize),
+}
+
+/// Onde está uma variável: slot, campo de `este` ou campo estático (referências IL).
+enum Acesso {
+    Slot(Slot),
+    Campo(String),
+    Estatico(String),
+}
+
+/// Identificador IL para um nome da linguagem: letras acentuadas perdem o acento e os demais
+/// caracteres não ASCII viram `_uXXXX`.
+fn identificador(nome: &str) -> String {
+    let mut il = String::with_capacity(nome.len());
+    for ch in nome.chars() {
+        match ch {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' => il.push(ch),
+            'á' | 'à' | 'â' | 'ã' | 'ä' => il.push('a'),
+            'Á' | 'À' | 'Â' | 'Ã' | 'Ä' => il.push('A'),
+            'é' | 'è' | 'ê' | 'ë' => il.push('e'),
+            'É' | 'È' | 'Ê' | 'Ë' => il.push('E'),
+            'í' | 'ì' | 'î' | 'ï' => il.push('i'),
+            'Í' | 'Ì' | 'Î' | 'Ï' => il.push('I'),
+            'ó' | 'ò' | 'ô' | 'õ' | 'ö' => il.push('o'),
+            'Ó' | 'Ò' | 'Ô' | 'Õ' | 'Ö' => il.push('O'),
+            'ú' | 'ù' | 'û' | 'ü' => il.push('u'),
+            'Ú' | 'Ù' | 'Û' | 'Ü' => il.push('U'),
+            'ç' => il.push('c'),
+            'Ç' => il.push('C'),
+            outro => il.push_str(&format!("_u{:04x}", outro as u32)),
+        }
+    }
+    // `pds_` é o prefixo dos membros do runtime e dos temporários
+    if il.starts_with("pds_") {
+        il.push('_');
+    }
+    il
+}
+
+/// Nome de membro entre aspas (`'Area'`), o que evita conflitos com palavras reservadas do IL.
+fn nome_membro(nome: &str) -> String {
+    format!("'{}'", identificador(nome))
+}
+
+/// Nome IL de uma classe ou interface do programa (`'Espaco'.'Classe'`).
+fn nome_tipo(fqn: &str) -> String {
+    fqn.split('.')
+        .map(nome_membro)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Nome qualificado sem acentos, usado nos nomes dos métodos de `PorDoSol.Programa`.
+fn nome_global(fqn: &str) -> String {
+    fqn.split('.')
+        .map(identificador)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// `ldstr` de um texto: ASCII visível entre aspas, o resto em `bytearray` (UTF-16LE).
+fn ldstr(texto: &str) -> String {
+    if texto.chars().all(|c| (' '..='~').contains(&c)) {
+        format!(
+            "ldstr \"{}\"",
+            texto.replace('\\', "\\\\").replace('"', "\\\"")
+        )
+    } else {
+        let bytes: Vec<String> = texto
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .map(|b| format!("{:02X}", b))
+            .collect();
+        format!("ldstr bytearray ({})", bytes.join(" "))
+    }
+}
+
+/// Runtime com os textos não ASCII convertidos para `bytearray`.
+fn runtime() -> String {
+    let mut saida = String::with_capacity(RUNTIME.len());
+    for linha in RUNTIME.lines() {
+        let conteudo = linha.trim_start();
+        let texto = conteudo
+            .strip_prefix("ldstr \"")
+            .and_then(|resto| resto.strip_suffix('"'))
+            .filter(|texto| !texto.is_ascii());
+        match texto {
+            Some(texto) => {
+                saida.push_str(&linha[..linha.len() - conteudo.len()]);
+                saida.push_str(&ldstr(texto));
+            }
+            None => saida.push_str(linha),
+        }
+        saida.push('\n');
+    }
+    saida
+}
+
+/// Literal real IL a partir do literal da linguagem (`2.5f`, `1e3`, `10.25m`).
+fn literal_real(texto: &str, flutuante: bool) -> Result<String, String> {
+    let numero = texto
+        .trim_end_matches(['f', 'F', 'm', 'M', 'd', 'D'])
+        .replace('_', "");
+    let valor: f64 = numero
+        .parse()
+        .map_err(|_| format!("Literal real inválido: {}", texto))?;
+    let literal = if flutuante {
+        let valor = valor as f32;
+        if !valor.is_finite() {
+            return Ok(format!("float32(0x{:08X})", valor.to_bits()));
+        }
+        format!("{:e}", valor)
+    } else {
+        if !valor.is_finite() {
+            return Ok(format!("float64(0x{:016X})", valor.to_bits()));
+        }
+        format!("{:e}", valor)
+    };
+    // O ilasm exige o ponto na mantissa: `1e3` vira `1.0e3`
+    Ok(match literal.find('e') {
+        Some(i) if !literal[..i].contains('.') => {
+            format!("{}.0{}", &literal[..i], &literal[i..])
+        }
+        _ => literal,
+    })
+}
+
+fn lista_tipos(tipos: &[TipoIl]) -> String {
+    tipos.iter().map(TipoIl::il).collect::<Vec<_>>().join(", ")
+}
 
 /// O gerador de código para o alvo CIL (Common Intermediate Language) do .NET.
 pub struct CilGenerator<'a> {
-    programa: &'a ast::Programa,
     assembly_name: String,
+    /// Classes, interfaces, funções e enumerações do programa, com os nomes resolvidos pelo
+    /// verificador. O símbolo de uma classe é o nome IL (`'Espaco'.'Classe'`); o de uma função,
+    /// o nome do método estático em `PorDoSol.Programa`.
+    tabelas: Tabelas<'a>,
+    /// Classe -> entradas da vtable (método, classe que introduz a entrada), herdadas primeiro.
+    vtables: HashMap<String, Vec<(String, String)>>,
+    despachos: BTreeMap<(String, usize), Despacho>,
+    /// Verificações de execução (índices, referências nulas, divisão por zero); desligadas por
+    /// `disable_runtime_checks` (`--sem-verificacoes`).
+    verificacoes: bool,
+    posicao: Option<ast::Posicao>,
+    // Estado do método em geração
+    namespace: String,
+    classe_atual: Option<String>,
+    tem_este: bool,
+    escopos: Vec<HashMap<String, (Slot, TipoIl)>>,
+    nomes_usados: HashSet<String>,
+    /// Parâmetros (sem `este`) e locais: nome e tipo IL.
+    parametros: Vec<(String, String)>,
+    locais: Vec<(String, String)>,
+    /// Temporários do comando atual (liberados no início de cada comando).
+    temporarios_em_uso: HashSet<usize>,
+    retorno: TipoIl,
+    codigo: Vec<String>,
+    rotulos: usize,
+}
+
+impl<'a> Escopo<'a, (Slot, TipoIl)> for CilGenerator<'a> {
+    fn tabelas(&self) -> &Tabelas<'a> {
+        &self.tabelas
+    }
+
+    fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    fn classe_atual(&self) -> Option<&str> {
+        self.classe_atual.as_deref()
+    }
+
+    fn tem_este(&self) -> bool {
+        self.tem_este
+    }
+
+    /// Variável local (ou parâmetro) visível no escopo atual.
+    fn local(&self, nome: &str) -> Option<(Slot, TipoIl)> {
+        self.escopos.iter().rev().find_map(|e| e.get(nome).cloned())
+    }
 }
 
 impl<'a> CilGenerator<'a> {
-    pub fn new(programa: &'a ast::Programa, assembly_name: String) -> Self {
+    pub fn new(
+        programa: &'a ast::Programa,
+        verificador: &'a VerificadorTipos<'a>,
+        assembly_name: String,
+    ) -> Self {
         Self {
-            programa,
             assembly_name,
+            tabelas: Tabelas::new(programa, verificador, nome_tipo, |fqn| {
+                format!("'{}'", nome_global(fqn))
+            }),
+            vtables: HashMap::new(),
+            despachos: BTreeMap::new(),
+            verificacoes: true,
+            posicao: None,
+            namespace: String::new(),
+            classe_atual: None,
+            tem_este: false,
+            escopos: Vec::new(),
+            nomes_usados: HashSet::new(),
+            parametros: Vec::new(),
+            locais: Vec::new(),
+            temporarios_em_uso: HashSet::new(),
+            retorno: TipoIl::Vazio,
+            codigo: Vec::new(),
+            rotulos: 0,
         }
     }
 
-    pub fn generate(&self) -> String {
-        let mut code = String::new();
-        code.push_str(&format!(".assembly extern mscorlib\n"));
-        code.push_str(&format!(".assembly {}\n\n", self.assembly_name));
-        code.push_str(".class private auto ansi beforefieldinit Principal extends [mscorlib]System.Object\n{\n");
-        code.push_str("  .method public hidebysig static void Main() cil managed\n  {\n");
-        code.push_str("    .entrypoint\n");
-        code.push_str("    .maxstack  8\n");
+    /// Gera acessos por índice, a membros e divisões sem as verificações de execução.
+    pub fn disable_runtime_checks(&mut self) {
+        self.verificacoes = false;
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* TABELAS DO PROGRAMA                                                 */
+    /* ------------------------------------------------------------------ */
 
-        for declaracao in &self.programa.declaracoes {
-            if let ast::Declaracao::Comando(cmd) = declaracao {
-                code.push_str(&self.generate_comando(cmd));
+    /// Interfaces declaradas no cabeçalho da própria classe.
+    fn interfaces_da_classe(&self, fqn: &str) -> Vec<String> {
+        let classe = &self.tabelas.classes[fqn];
+        let mut interfaces: Vec<String> = Vec::new();
+        let tipos = classe.decl.classe_pai.iter().chain(&classe.decl.interfaces);
+        for tipo in tipos {
+            let interface = nome_do_tipo(tipo)
+                .and_then(|nome| self.tabelas.resolver_interface(&classe.namespace, nome));
+            if let Some(interface) = interface {
+                if !interfaces.contains(&interface) {
+                    interfaces.push(interface);
+                }
             }
         }
+        interfaces
+    }
 
-        code.push_str("    ret\n");
-        code.push_str("  }\n");
-        code.push_str("  .method public hidebysig specialname rtspecialname instance void .ctor() cil managed { ret }\n");
-        code.push_str("}\n");
-        code
+    fn implementa(&self, fqn: &str, interface: &str) -> bool {
+        self.tabelas.hierarquia(fqn).iter().any(|c| {
+            self.interfaces_da_classe(&c.fqn)
+                .iter()
+                .any(|i| i == interface)
+        })
     }
 
-    fn generate_comando(&self, comando: &ast::Comando) -> String {
-        match comando {
-            ast::Comando::Imprima(expr) => self.generate_expressao(expr, true),
-            ast::Comando::DeclaracaoVariavel(_, nome, Some(expr)) => {
-                let mut code = String::new();
-                code.push_str(&format!("    .locals init (object {}\n)", nome));
-                code.push_str(&self.generate_expressao(expr, false));
-                code.push_str(&format!("    stloc.0 // {}", nome));
-                code
+    /// Campo ou propriedade de instância: referência IL ao campo (`T 'Classe'::'campo'`) e tipo.
+    /// Propriedades guardam o valor no campo `<Nome>k__BackingField`, como no C#.
+    fn buscar_campo(&self, fqn: &str, nome: &str) -> Option<(String, TipoIl)> {
+        let membro = self.tabelas.buscar_membro(fqn, nome, false)?;
+        Some(self.campo(nome, &membro))
+    }
+
+    fn campo(&self, nome: &str, membro: &Membro) -> (String, TipoIl) {
+        let campo = if membro.propriedade {
+            Self::campo_propriedade(nome)
+        } else {
+            nome_membro(nome)
+        };
+        let tipo = self.tipo_il(membro.tipo, &membro.classe.namespace);
+        (
+            format!("{} {}::{}", tipo.il(), membro.classe.simbolo, campo),
+            tipo,
+        )
+    }
+
+    fn campo_propriedade(nome: &str) -> String {
+        format!("'<{}>k__BackingField'", identificador(nome))
+    }
+
+    /// Membro estático `nome` da classe ou de um ancestral: referência IL ao campo e tipo.
+    fn dono_estatico(&self, fqn: &str, nome: &str) -> Option<(String, TipoIl)> {
+        let membro = self.tabelas.buscar_membro(fqn, nome, true)?;
+        Some(self.estatico(nome, &membro))
+    }
+
+    fn estatico(&self, nome: &str, membro: &Membro) -> (String, TipoIl) {
+        let tipo = self.tipo_il(membro.tipo, &membro.classe.namespace);
+        (
+            format!(
+                "{} {}::{}",
+                tipo.il(),
+                membro.classe.simbolo,
+                nome_membro(nome)
+            ),
+            tipo,
+        )
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* TIPOS                                                               */
+    /* ------------------------------------------------------------------ */
+
+    fn tipo_il(&self, tipo: &ast::Tipo, namespace: &str) -> TipoIl {
+        match tipo {
+            ast::Tipo::Inteiro | ast::Tipo::Enum(_) => TipoIl::Inteiro,
+            ast::Tipo::Booleano => TipoIl::Booleano,
+            ast::Tipo::Texto => TipoIl::Texto,
+            ast::Tipo::Flutuante => TipoIl::Flutuante,
+            ast::Tipo::Duplo | ast::Tipo::Decimal => TipoIl::Duplo,
+            ast::Tipo::Vazio => TipoIl::Vazio,
+            ast::Tipo::Lista(item) => TipoIl::Array(Box::new(self.tipo_il(item, namespace))),
+            ast::Tipo::Opcional(tipo) => self.tipo_il(tipo, namespace),
+            ast::Tipo::Classe(nome) | ast::Tipo::Aplicado { nome, .. } => {
+                if nome == "objeto" {
+                    return TipoIl::Objeto;
+                }
+                match self.tabelas.tipo_nomeado(namespace, nome) {
+                    Some(TipoNomeado::Classe(classe)) => TipoIl::Classe(classe),
+                    Some(TipoNomeado::Enum(_)) => TipoIl::Inteiro,
+                    Some(TipoNomeado::Interface(interface)) => TipoIl::Interface(interface),
+                    None => TipoIl::Objeto,
+                }
             }
-            _ => format!("    // Comando {:?} não implementado para CIL\n", comando),
+            ast::Tipo::Objeto
+            | ast::Tipo::Generico(_)
+            | ast::Tipo::Funcao(..)
+            | ast::Tipo::Inferido => TipoIl::Objeto,
         }
     }
 
-    fn generate_expressao(&self, expr: &ast::Expressao, is_print: bool) -> String {
-        let mut code = String::new();
-        match expr {
-            ast::Expressao::Texto(s) => {
-                code.push_str(&format!("    ldstr \"{}\n\"\n", s.replace('\n', "")));
-                code.push_str("    call void [mscorlib]System.Console::WriteLine(string)\n");
+    fn tipo_retorno(&self, tipo: &Option<ast::Tipo>, namespace: &str) -> TipoIl {
+        tipo.as_ref()
+            .map(|t| self.tipo_il(t, namespace))
+            .unwrap_or(TipoIl::Vazio)
+    }
+
+    fn nome_tipo(&self, tipo: &TipoIl) -> String {
+        match tipo {
+            TipoIl::Inteiro => "inteiro".to_string(),
+            TipoIl::Booleano => "booleano".to_string(),
+            TipoIl::Flutuante => "flutuante".to_string(),
+            TipoIl::Duplo => "duplo".to_string(),
+            TipoIl::Texto => "texto".to_string(),
+            TipoIl::Vazio => "vazio".to_string(),
+            TipoIl::Array(_) => "array".to_string(),
+            TipoIl::Classe(n) | TipoIl::Interface(n) => n.clone(),
+            TipoIl::Objeto => "objeto".to_string(),
+            TipoIl::Nulo => "nulo".to_string(),
+        }
+    }
+
+    /// Tipo dos itens de um literal `[a, b, ...]`.
+    fn tipo_itens(&self, tipos: &[TipoIl]) -> TipoIl {
+        let mut comum: Option<TipoIl> = None;
+        for tipo in tipos.iter().filter(|t| **t != TipoIl::Nulo) {
+            comum = Some(match (comum, tipo) {
+                (None, t) => t.clone(),
+                (Some(a), b) if a == *b => a,
+                (Some(TipoIl::Classe(a)), TipoIl::Classe(b)) => self
+                    .tabelas
+                    .ancestral_comum(&a, b)
+                    .map(TipoIl::Classe)
+                    .unwrap_or(TipoIl::Objeto),
+                _ => TipoIl::Objeto,
+            });
+        }
+        comum.unwrap_or(TipoIl::Objeto)
+    }
+
+    /// Valores de `de` podem ser usados como `para` sem conversão no IL.
+    fn atribuivel(&self, de: &TipoIl, para: &TipoIl) -> bool {
+        match (de, para) {
+            _ if de == para => true,
+            (TipoIl::Nulo, _) | (_, TipoIl::Objeto) => true,
+            (TipoIl::Classe(a), TipoIl::Classe(b)) => self.tabelas.eh_ancestral(b, a),
+            (TipoIl::Classe(a), TipoIl::Interface(i)) => self.implementa(a, i),
+            (TipoIl::Array(a), TipoIl::Array(b)) => {
+                !a.primitivo() && !b.primitivo() && self.atribuivel(a, b)
             }
-            ast::Expressao::Inteiro(n) => {
-                code.push_str(&format!("    ldc.i4 {}\n", n));
-                if is_print {
-                    code.push_str("    call void [mscorlib]System.Console::WriteLine(int32)\n");
+            _ => false,
+        }
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* VTABLES                                                             */
+    /* ------------------------------------------------------------------ */
+
+    /// Classe que introduz a entrada do método na vtable, se ele é virtual.
+    fn entrada_vtable(&self, classe: &str, metodo: &str) -> Option<&str> {
+        self.vtables
+            .get(classe)?
+            .iter()
+            .find(|(nome, _)| nome == metodo)
+            .map(|(_, introdutor)| introdutor.as_str())
+    }
+
+    /// Assinatura (retorno, parâmetros) de um método, sem o receptor.
+    fn assinatura(&self, classe: &str, metodo: &ast::MetodoClasse) -> (TipoIl, Vec<TipoIl>) {
+        let namespace = &self.tabelas.classes[classe].namespace;
+        (
+            self.tipo_retorno(&metodo.tipo_retorno, namespace),
+            metodo
+                .parametros
+                .iter()
+                .map(|p| self.tipo_il(&p.tipo, namespace))
+                .collect(),
+        )
+    }
+
+    /// Instrução de chamada de um método de instância da classe (`callvirt` se for virtual).
+    fn instrucao_metodo(&self, dono: &str, metodo: &ast::MetodoClasse) -> String {
+        let (retorno, parametros) = self.assinatura(dono, metodo);
+        let chamada = if self.entrada_vtable(dono, &metodo.nome).is_some() {
+            "callvirt"
+        } else {
+            "call"
+        };
+        format!(
+            "{} instance {} {}::{}({})",
+            chamada,
+            retorno.il(),
+            self.tabelas.classes[dono].simbolo,
+            nome_membro(&metodo.nome),
+            lista_tipos(&parametros)
+        )
+    }
+
+    /// Classe mais próxima (ela mesma ou ancestral) com inicializadores de campos de instância.
+    fn inicializador(&self, fqn: &str) -> Option<String> {
+        self.tabelas
+            .hierarquia(fqn)
+            .into_iter()
+            .find(|c| {
+                c.decl
+                    .campos
+                    .iter()
+                    .any(|f| !f.eh_estatica && f.valor_inicial.is_some())
+                    || c.decl
+                        .propriedades
+                        .iter()
+                        .any(|p| !p.eh_estatica && p.valor_inicial.is_some())
+            })
+            .map(|c| c.fqn.clone())
+    }
+
+    /// Classe que declara o campo `Nome` exibido por `imprima(objeto)`.
+    fn classe_do_nome(&self, fqn: &str) -> Option<String> {
+        self.tabelas
+            .hierarquia(fqn)
+            .into_iter()
+            .find(|c| {
+                c.decl
+                    .campos
+                    .iter()
+                    .any(|f| f.nome == "Nome" && !f.eh_estatica)
+                    || c.decl
+                        .propriedades
+                        .iter()
+                        .any(|p| p.nome == "Nome" && !p.eh_estatica)
+            })
+            .map(|c| c.fqn.clone())
+    }
+
+    /// Tipos dos parâmetros do construtor `indice` da classe.
+    fn parametros_construtor(&self, classe: &str, indice: usize) -> Vec<TipoIl> {
+        let classe = &self.tabelas.classes[classe];
+        classe.decl.construtores[indice]
+            .parametros
+            .iter()
+            .map(|p| self.tipo_il(&p.tipo, &classe.namespace))
+            .collect()
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* GERAÇÃO DO ASSEMBLY                                                 */
+    /* ------------------------------------------------------------------ */
+
+    pub fn generate(&mut self) -> Result<String, String> {
+        self.vtables = self.tabelas.vtables();
+
+        let mut interfaces = String::new();
+        for fqn in self.tabelas.ordem_interfaces.clone() {
+            interfaces.push_str(&self.gerar_interface(&fqn));
+        }
+        let mut classes = String::new();
+        for fqn in self.tabelas.ordem_classes.clone() {
+            classes.push_str(&self.gerar_classe(&fqn)?);
+        }
+        let funcoes = self.gerar_funcoes()?;
+        let estaticos = self.gerar_estaticos()?;
+        let globais = self.gerar_comandos_globais()?;
+        let inicio = self.gerar_inicio(estaticos.is_some(), globais.is_some())?;
+        let despachos = self.gerar_despachos()?;
+
+        let nome = identificador(&self.assembly_name);
+        let mut saida = String::new();
+        saida.push_str("// Gerado pelo compilador Por do Sol (--target=cil-bytecode).\n");
+        saida.push_str(".assembly extern mscorlib\n{\n");
+        saida.push_str("  .publickeytoken = (B7 7A 5C 56 19 34 E0 89)\n");
+        saida.push_str("  .ver 4:0:0:0\n}\n");
+        saida.push_str(&format!(".assembly '{}'\n{{\n  .ver 0:0:0:0\n}}\n", nome));
+        saida.push_str(&format!(".module '{}.exe'\n\n", nome));
+        saida.push_str(&runtime());
+        saida.push('\n');
+        saida.push_str(&interfaces);
+        saida.push_str(&classes);
+        saida.push_str(&format!(
+            ".class public abstract sealed auto ansi beforefieldinit {}\n",
+            PROGRAMA
+        ));
+        saida.push_str("       extends [mscorlib]System.Object\n{\n");
+        saida.push_str(&funcoes);
+        saida.push_str(&estaticos.unwrap_or_default());
+        saida.push_str(&globais.unwrap_or_default());
+        saida.push_str(&despachos);
+        saida.push_str(&inicio);
+        saida.push_str("}\n");
+        Ok(saida)
+    }
+
+    fn gerar_interface(&self, fqn: &str) -> String {
+        let interface = &self.tabelas.interfaces[fqn];
+        let mut saida = format!(
+            ".class interface public abstract auto ansi {}\n{{\n",
+            nome_tipo(fqn)
+        );
+        for metodo in &interface.decl.metodos {
+            let retorno = self.tipo_retorno(&metodo.tipo_retorno, &interface.namespace);
+            let parametros: Vec<String> = metodo
+                .parametros
+                .iter()
+                .map(|p| {
+                    format!(
+                        "{} {}",
+                        self.tipo_il(&p.tipo, &interface.namespace).il(),
+                        nome_membro(&p.nome)
+                    )
+                })
+                .collect();
+            saida.push_str(&format!(
+                "  .method public hidebysig newslot abstract virtual instance {} {}({}) cil managed\n  {{\n  }}\n\n",
+                retorno.il(),
+                nome_membro(&metodo.nome),
+                parametros.join(", ")
+            ));
+        }
+        saida.push_str("}\n\n");
+        saida
+    }
+
+    fn gerar_funcoes(&mut self) -> Result<String, String> {
+        let mut saida = String::new();
+        for fqn in self.tabelas.ordem_funcoes.clone() {
+            let funcao = &self.tabelas.funcoes[&fqn];
+            let decl = funcao.decl;
+            if chave_nativa(&decl.attributes).is_some()
+                || decl.attributes.iter().any(|a| a.name == "Externo")
+            {
+                continue;
+            }
+            let namespace = funcao.namespace.clone();
+            let cabecalho = format!(
+                "public hidebysig static {} {}",
+                self.tipo_retorno(&decl.tipo_retorno, &namespace).il(),
+                funcao.simbolo
+            );
+            let retorno = self.tipo_retorno(&decl.tipo_retorno, &namespace);
+            self.iniciar_metodo(&namespace, None, false, retorno.clone());
+            self.declarar_parametros(&decl.parametros, &namespace);
+            self.gerar_bloco(&decl.corpo)?;
+            self.retorno_final(&decl.corpo, &retorno);
+            saida.push_str(&self.terminar_metodo(&cabecalho, &[])?);
+        }
+        Ok(saida)
+    }
+
+    /// Métodos sem `retorne` no final devolvem o valor padrão do tipo (nulo no interpretador).
+    fn retorno_final(&mut self, corpo: &[ast::Comando], retorno: &TipoIl) {
+        if !matches!(corpo.last(), Some(ast::Comando::Retorne(_))) {
+            self.padrao(retorno, false);
+            self.emitir("ret");
+        }
+    }
+
+    fn gerar_classe(&mut self, fqn: &str) -> Result<String, String> {
+        let classe = &self.tabelas.classes[fqn];
+        let decl = classe.decl;
+        let namespace = classe.namespace.clone();
+        let pai = classe.pai.clone();
+        let nome = classe.simbolo.clone();
+
+        let mut saida = if decl.eh_estatica {
+            format!(
+                ".class public abstract sealed auto ansi beforefieldinit {}\n       extends [mscorlib]System.Object\n{{\n",
+                nome
+            )
+        } else {
+            let base = match &pai {
+                Some(pai) => self.tabelas.classes[pai].simbolo.clone(),
+                None => "PorDoSol.Objeto".to_string(),
+            };
+            let mut cabecalho = format!(
+                ".class public {}auto ansi beforefieldinit {}\n       extends {}\n",
+                if decl.eh_abstrata { "abstract " } else { "" },
+                nome,
+                base
+            );
+            let interfaces = self.interfaces_da_classe(fqn);
+            if !interfaces.is_empty() {
+                let nomes: Vec<String> = interfaces.iter().map(|i| nome_tipo(i)).collect();
+                cabecalho.push_str(&format!("       implements {}\n", nomes.join(", ")));
+            }
+            cabecalho.push_str("{\n");
+            cabecalho
+        };
+
+        // Campos; propriedades de instância guardam o valor em um campo de apoio
+        for campo in &decl.campos {
+            saida.push_str(&format!(
+                "  .field public {}{} {}\n",
+                if campo.eh_estatica { "static " } else { "" },
+                self.tipo_il(&campo.tipo, &namespace).il(),
+                nome_membro(&campo.nome)
+            ));
+        }
+        for propriedade in &decl.propriedades {
+            let tipo = self.tipo_il(&propriedade.tipo, &namespace).il();
+            if propriedade.eh_estatica {
+                saida.push_str(&format!(
+                    "  .field public static {} {}\n",
+                    tipo,
+                    nome_membro(&propriedade.nome)
+                ));
+            } else {
+                saida.push_str(&format!(
+                    "  .field public {} {}\n",
+                    tipo,
+                    Self::campo_propriedade(&propriedade.nome)
+                ));
+            }
+        }
+        saida.push('\n');
+
+        if !decl.eh_estatica {
+            saida.push_str(&self.gerar_construtores(fqn)?);
+            saida.push_str(&self.gerar_membros_objeto(fqn)?);
+        }
+        saida.push_str(&self.gerar_propriedades(fqn)?);
+
+        // Métodos
+        for metodo in &decl.metodos {
+            if let Some(chave) = chave_nativa(&metodo.attributes) {
+                return Err(format!(
+                    "Método nativo '{}' ({}) não é suportado no alvo cil",
+                    metodo.nome, chave
+                ));
+            }
+            let (retorno, _) = self.assinatura(fqn, metodo);
+            let modificadores = if metodo.eh_estatica {
+                "static".to_string()
+            } else {
+                let novo = match self.entrada_vtable(fqn, &metodo.nome) {
+                    Some(introdutor) if introdutor == fqn => "newslot virtual ",
+                    Some(_) => "virtual ",
+                    None => "",
+                };
+                format!("{}instance", novo)
+            };
+            if metodo.eh_abstrato && decl.eh_abstrata {
+                let parametros: Vec<String> = metodo
+                    .parametros
+                    .iter()
+                    .map(|p| {
+                        format!(
+                            "{} {}",
+                            self.tipo_il(&p.tipo, &namespace).il(),
+                            nome_membro(&p.nome)
+                        )
+                    })
+                    .collect();
+                saida.push_str(&format!(
+                    "  .method public hidebysig {} {} {}({}) cil managed\n  {{\n  }}\n\n",
+                    modificadores.replacen("virtual", "abstract virtual", 1),
+                    retorno.il(),
+                    nome_membro(&metodo.nome),
+                    parametros.join(", ")
+                ));
+                continue;
+            }
+            let cabecalho = format!(
+                "public hidebysig {} {} {}",
+                modificadores,
+                retorno.il(),
+                nome_membro(&metodo.nome)
+            );
+            self.iniciar_metodo(&namespace, Some(fqn), !metodo.eh_estatica, retorno.clone());
+            self.declarar_parametros(&metodo.parametros, &namespace);
+            self.gerar_bloco(&metodo.corpo)?;
+            self.retorno_final(&metodo.corpo, &retorno);
+            saida.push_str(&self.terminar_metodo(&cabecalho, &[])?);
+        }
+
+        if !decl.eh_estatica {
+            saida.push_str(&self.gerar_abstratos_herdados(fqn)?);
+            for interface in self.interfaces_da_classe(fqn) {
+                saida.push_str(&self.gerar_ponte_interface(fqn, &interface)?);
+            }
+        }
+        saida.push_str("}\n\n");
+        Ok(saida)
+    }
+
+    /// Construtores `.ctor`: um por construtor declarado, ou um sem parâmetros. Chamam o
+    /// construtor da classe pai (`: base(...)` ou o padrão) antes do corpo.
+    fn gerar_construtores(&mut self, fqn: &str) -> Result<String, String> {
+        let classe = &self.tabelas.classes[fqn];
+        let decl = classe.decl;
+        let namespace = classe.namespace.clone();
+        let pai = classe.pai.clone();
+        let construtores: Vec<Option<&'a ast::ConstrutorClasse>> = if decl.construtores.is_empty() {
+            vec![None]
+        } else {
+            decl.construtores.iter().map(Some).collect()
+        };
+        let mut saida = String::new();
+        for construtor in construtores {
+            self.iniciar_metodo(&namespace, Some(fqn), true, TipoIl::Vazio);
+            if let Some(construtor) = construtor {
+                self.declarar_parametros(&construtor.parametros, &namespace);
+            }
+            let args = construtor
+                .and_then(|c| c.chamada_pai.as_deref())
+                .unwrap_or(&[]);
+            match &pai {
+                Some(pai) => self.construcao_pai(pai, args)?,
+                None => {
+                    // PorDoSol.Objeto executa os inicializadores de campos da hierarquia
+                    self.emitir("ldarg.0");
+                    self.emitir("call instance void PorDoSol.Objeto::.ctor()");
                 }
             }
-            ast::Expressao::Decimal(d) => {
-                code.push_str(&format!("    ldstr \"{}\"\n", d));
-                code.push_str("    call class [mscorlib]System.Decimal [mscorlib]System.Decimal::Parse(string)\n");
-                if is_print {
-                    code.push_str("    call void [mscorlib]System.Console::WriteLine(object)\n");
+            match construtor {
+                Some(construtor) => {
+                    self.gerar_bloco(&construtor.corpo)?;
+                    self.retorno_final(&construtor.corpo, &TipoIl::Vazio);
                 }
+                None => self.emitir("ret"),
             }
-            ast::Expressao::Identificador(nome) => {
-                code.push_str(&format!("    ldloc.0 // {}\n", nome));
-                if is_print {
-                    code.push_str("    call void [mscorlib]System.Console::WriteLine(object)\n");
+            saida.push_str(&self.terminar_metodo(
+                "public hidebysig specialname rtspecialname instance void .ctor",
+                &[],
+            )?);
+        }
+        Ok(saida)
+    }
+
+    /// Chamada ao construtor da classe pai (`: base(...)`), ou ao construtor padrão dela.
+    fn construcao_pai(&mut self, pai: &str, args: &[ast::Expressao]) -> Result<(), String> {
+        self.emitir("ldarg.0");
+        let tipos = match self.tabelas.escolher_construtor(pai, args.len()) {
+            Some(indice) => {
+                let parametros = self.tabelas.classes[pai].decl.construtores[indice]
+                    .parametros
+                    .clone();
+                let namespace = self.tabelas.classes[pai].namespace.clone();
+                self.argumentos(&parametros, &namespace, args)?;
+                self.parametros_construtor(pai, indice)
+            }
+            None => {
+                // Classe pai sem construtor: os argumentos são avaliados e descartados
+                for arg in args {
+                    let valor = self.expr(arg)?;
+                    self.descartar(&valor.tipo);
+                }
+                Vec::new()
+            }
+        };
+        self.emitir(format!(
+            "call instance void {}::.ctor({})",
+            self.tabelas.classes[pai].simbolo,
+            lista_tipos(&tipos)
+        ));
+        Ok(())
+    }
+
+    /// Inicializadores de campos, nome da classe e exibição (`ToString`).
+    fn gerar_membros_objeto(&mut self, fqn: &str) -> Result<String, String> {
+        let classe = &self.tabelas.classes[fqn];
+        let decl = classe.decl;
+        let namespace = classe.namespace.clone();
+        let pai = classe.pai.clone();
+        let mut saida = String::new();
+
+        // Inicializadores de campos, da base para a derivada
+        if self.inicializador(fqn).as_deref() == Some(fqn) {
+            self.iniciar_metodo(&namespace, Some(fqn), true, TipoIl::Vazio);
+            if let Some(anterior) = pai.as_ref().and_then(|p| self.inicializador(p)) {
+                self.emitir("ldarg.0");
+                self.emitir(format!(
+                    "call instance void {}::pds_inicializar()",
+                    self.tabelas.classes[&anterior].simbolo
+                ));
+            }
+            let iniciais = decl
+                .campos
+                .iter()
+                .filter(|c| !c.eh_estatica)
+                .filter_map(|c| c.valor_inicial.as_ref().map(|e| (&c.nome, e)))
+                .chain(
+                    decl.propriedades
+                        .iter()
+                        .filter(|p| !p.eh_estatica)
+                        .filter_map(|p| p.valor_inicial.as_ref().map(|e| (&p.nome, e))),
+                );
+            for (nome, expr) in iniciais {
+                self.temporarios_em_uso.clear();
+                let (campo, tipo) = self.buscar_campo(fqn, nome).unwrap();
+                self.emitir("ldarg.0");
+                let valor = self.expr(expr)?;
+                self.converter(&valor.tipo, &tipo);
+                self.emitir(format!("stfld {}", campo));
+            }
+            self.emitir("ret");
+            saida.push_str(&self.terminar_metodo(
+                "public hidebysig virtual instance void pds_inicializar",
+                &[],
+            )?);
+        }
+
+        self.iniciar_metodo(&namespace, Some(fqn), true, TipoIl::Texto);
+        self.emitir(ldstr(fqn));
+        self.emitir("ret");
+        saida.push_str(
+            &self.terminar_metodo("public hidebysig virtual instance string pds_classe", &[])?,
+        );
+
+        // Texto exibido por imprima: o campo `Nome`, como no interpretador
+        if self.classe_do_nome(fqn).as_deref() == Some(fqn) {
+            let (campo, tipo) = self.buscar_campo(fqn, "Nome").unwrap();
+            self.iniciar_metodo(&namespace, Some(fqn), true, TipoIl::Texto);
+            self.emitir("ldarg.0");
+            self.emitir(format!("ldfld {}", campo));
+            self.texto_de(&Valor::novo(tipo));
+            self.emitir("ret");
+            saida.push_str(
+                &self.terminar_metodo("public hidebysig virtual instance string ToString", &[])?,
+            );
+        }
+        Ok(saida)
+    }
+
+    /// Acessores `get_`/`set_` e a declaração `.property` das propriedades de instância.
+    fn gerar_propriedades(&mut self, fqn: &str) -> Result<String, String> {
+        let classe = &self.tabelas.classes[fqn];
+        let decl = classe.decl;
+        let namespace = classe.namespace.clone();
+        let nome_classe = classe.simbolo.clone();
+        let mut saida = String::new();
+        for propriedade in decl.propriedades.iter().filter(|p| !p.eh_estatica) {
+            let tipo = self.tipo_il(&propriedade.tipo, &namespace);
+            let campo = format!(
+                "{} {}::{}",
+                tipo.il(),
+                nome_classe,
+                Self::campo_propriedade(&propriedade.nome)
+            );
+            let nome = identificador(&propriedade.nome);
+            let mut acessores = Vec::new();
+            if let Some(obter) = &propriedade.obter {
+                self.iniciar_metodo(&namespace, Some(fqn), true, tipo.clone());
+                match &obter.corpo {
+                    Some(corpo) => {
+                        self.gerar_bloco(corpo)?;
+                        self.retorno_final(corpo, &tipo);
+                    }
+                    None => {
+                        self.emitir("ldarg.0");
+                        self.emitir(format!("ldfld {}", campo));
+                        self.emitir("ret");
+                    }
                 }
+                saida.push_str(&self.terminar_metodo(
+                    &format!(
+                        "public hidebysig specialname instance {} 'get_{}'",
+                        tipo.il(),
+                        nome
+                    ),
+                    &[],
+                )?);
+                acessores.push(format!(
+                    ".get instance {} {}::'get_{}'()",
+                    tipo.il(),
+                    nome_classe,
+                    nome
+                ));
             }
-            ast::Expressao::Aritmetica(ast::OperadorAritmetico::Soma, _, _) => {
-                let parts = self.flatten_soma(expr);
-                for part in parts {
-                    match part {
-                        ast::Expressao::Texto(s) => code.push_str(&format!("    ldstr \"{}\"\n    call void [mscorlib]System.Console::Write(string)\n", s)),
-                        ast::Expressao::Inteiro(n) => code.push_str(&format!("    ldc.i4 {}\n    call void [mscorlib]System.Console::Write(int32)\n", n)),
-                        _ => {}
+            if let Some(definir) = &propriedade.definir {
+                self.iniciar_metodo(&namespace, Some(fqn), true, TipoIl::Vazio);
+                self.nomes_usados.insert("valor".to_string());
+                self.parametros.push(("valor".to_string(), tipo.il()));
+                self.escopos[0].insert("valor".to_string(), (Slot::Argumento(1), tipo.clone()));
+                match &definir.corpo {
+                    Some(corpo) => {
+                        self.gerar_bloco(corpo)?;
+                        self.retorno_final(corpo, &TipoIl::Vazio);
+                    }
+                    None => {
+                        self.emitir("ldarg.0");
+                        self.emitir("ldarg.1");
+                        self.emitir(format!("stfld {}", campo));
+                        self.emitir("ret");
                     }
                 }
-                code.push_str("    call void [mscorlib]System.Console::WriteLine()\n");
+                saida.push_str(&self.terminar_metodo(
+                    &format!("public hidebysig specialname instance void 'set_{}'", nome),
+                    &[],
+                )?);
+                acessores.push(format!(
+                    ".set instance void {}::'set_{}'({})",
+                    nome_classe,
+                    nome,
+                    tipo.il()
+                ));
             }
-            _ => code.push_str(&format!(
-                "    // Expressão {:?} não implementada para CIL\n",
-                expr
-            )),
+            saida.push_str(&format!(
+                "  .property instance {} {}()\n  {{\n",
+                tipo.il(),
+                nome_membro(&propriedade.nome)
+            ));
+            for acessor in acessores {
+                saida.push_str(&format!("    {}\n", acessor));
+            }
+            saida.push_str("  }\n\n");
+        }
+        Ok(saida)
+    }
+
+    /// Métodos abstratos herdados e não implementados: como no interpretador, a chamada executa
+    /// o corpo vazio e devolve o valor padrão.
+    fn gerar_abstratos_herdados(&mut self, fqn: &str) -> Result<String, String> {
+        if self.tabelas.classes[fqn].decl.eh_abstrata {
+            return Ok(String::new());
+        }
+        let namespace = self.tabelas.classes[fqn].namespace.clone();
+        let mut saida = String::new();
+        for (nome, _) in self.vtables[fqn].clone() {
+            let Some((dono, metodo)) = self.tabelas.buscar_metodo(fqn, &nome, usize::MAX) else {
+                continue;
+            };
+            if dono == fqn || !metodo.eh_abstrato {
+                continue;
+            }
+            let (retorno, parametros) = self.assinatura(&dono, metodo);
+            self.iniciar_metodo(&namespace, Some(fqn), true, retorno.clone());
+            for (parametro, tipo) in metodo.parametros.iter().zip(&parametros) {
+                self.parametros
+                    .push((identificador(&parametro.nome), tipo.il()));
+            }
+            self.padrao(&retorno, false);
+            self.emitir("ret");
+            saida.push_str(&self.terminar_metodo(
+                &format!(
+                    "public hidebysig virtual instance {} {}",
+                    retorno.il(),
+                    nome_membro(&nome)
+                ),
+                &[],
+            )?);
+        }
+        Ok(saida)
+    }
+
+    /// Implementação explícita dos métodos da interface, encaminhada para o método da classe
+    /// (ou para o despacho pelo nome, se a classe não tiver o método).
+    fn gerar_ponte_interface(&mut self, fqn: &str, interface: &str) -> Result<String, String> {
+        let namespace = self.tabelas.classes[fqn].namespace.clone();
+        let decl = self.tabelas.interfaces[interface].decl;
+        let namespace_interface = self.tabelas.interfaces[interface].namespace.clone();
+        let mut saida = String::new();
+        for assinatura in &decl.metodos {
+            let retorno = self.tipo_retorno(&assinatura.tipo_retorno, &namespace_interface);
+            let tipos: Vec<TipoIl> = assinatura
+                .parametros
+                .iter()
+                .map(|p| self.tipo_il(&p.tipo, &namespace_interface))
+                .collect();
+            let n = tipos.len();
+            self.iniciar_metodo(&namespace, Some(fqn), true, retorno.clone());
+            self.declarar_parametros(&assinatura.parametros, &namespace_interface);
+            self.emitir("ldarg.0");
+            let metodo = self
+                .tabelas
+                .buscar_metodo(fqn, &assinatura.nome, n)
+                .filter(|(_, m)| !m.eh_estatica);
+            let (destinos, retorno_destino, instrucao) = match metodo {
+                Some((dono, metodo)) => {
+                    let (retorno_metodo, parametros) = self.assinatura(&dono, metodo);
+                    (
+                        parametros,
+                        retorno_metodo,
+                        self.instrucao_metodo(&dono, metodo),
+                    )
+                }
+                None => {
+                    let despacho = self.despacho(
+                        &TipoIl::Interface(interface.to_string()),
+                        &assinatura.nome,
+                        n,
+                    )?;
+                    let instrucao = self.instrucao_despacho(&despacho, n);
+                    (despacho.parametros, despacho.retorno, instrucao)
+                }
+            };
+            for (i, destino) in destinos.iter().enumerate() {
+                match tipos.get(i) {
+                    Some(tipo) => {
+                        self.carregar(Slot::Argumento(i + 1));
+                        self.converter(tipo, destino);
+                    }
+                    None => self.padrao(destino, true),
+                }
+            }
+            self.emitir(instrucao);
+            self.converter(&retorno_destino, &retorno);
+            self.emitir("ret");
+            let sobrescreve = format!(
+                ".override method instance {} {}::{}({})",
+                retorno.il(),
+                nome_tipo(interface),
+                nome_membro(&assinatura.nome),
+                lista_tipos(&tipos)
+            );
+            saida.push_str(&self.terminar_metodo(
+                &format!(
+                    "private hidebysig newslot virtual final instance {} '{}.{}'",
+                    retorno.il(),
+                    nome_global(interface),
+                    identificador(&assinatura.nome)
+                ),
+                &[sobrescreve],
+            )?);
+        }
+        Ok(saida)
+    }
+
+    /// Membros estáticos com inicializador, na ordem de declaração, em um único método.
+    fn gerar_estaticos(&mut self) -> Result<Option<String>, String> {
+        self.iniciar_metodo("", None, false, TipoIl::Vazio);
+        let mut algum = false;
+        for fqn in self.tabelas.ordem_classes.clone() {
+            let classe = &self.tabelas.classes[&fqn];
+            let namespace = classe.namespace.clone();
+            let iniciais: Vec<(&String, &ast::Expressao)> = classe
+                .decl
+                .campos
+                .iter()
+                .filter(|c| c.eh_estatica)
+                .filter_map(|c| c.valor_inicial.as_ref().map(|e| (&c.nome, e)))
+                .chain(
+                    classe
+                        .decl
+                        .propriedades
+                        .iter()
+                        .filter(|p| p.eh_estatica)
+                        .filter_map(|p| p.valor_inicial.as_ref().map(|e| (&p.nome, e))),
+                )
+                .collect();
+            for (nome, expr) in iniciais {
+                algum = true;
+                self.namespace = namespace.clone();
+                self.classe_atual = Some(fqn.clone());
+                self.temporarios_em_uso.clear();
+                self.escopos.push(HashMap::new());
+                let (campo, tipo) = self.dono_estatico(&fqn, nome).unwrap();
+                let valor = self.expr(expr)?;
+                self.converter(&valor.tipo, &tipo);
+                self.emitir(format!("stsfld {}", campo));
+                self.escopos.pop();
+            }
+        }
+        if !algum {
+            return Ok(None);
+        }
+        self.emitir("ret");
+        self.terminar_metodo(
+            "public hidebysig static void pds_inicializar_estaticos",
+            &[],
+        )
+        .map(Some)
+    }
+
+    fn gerar_comandos_globais(&mut self) -> Result<Option<String>, String> {
+        if self.tabelas.globais.is_empty() {
+            return Ok(None);
+        }
+        self.iniciar_metodo("", None, false, TipoIl::Vazio);
+        for (namespace, comando) in self.tabelas.globais.clone() {
+            self.namespace = namespace;
+            self.gerar_comando(comando)?;
+        }
+        self.emitir("ret");
+        self.terminar_metodo("public hidebysig static void pds_comandos_globais", &[])
+            .map(Some)
+    }
+
+    /// Ponto de entrada: estáticos, comandos globais e `Principal`.
+    fn gerar_inicio(&mut self, estaticos: bool, globais: bool) -> Result<String, String> {
+        self.iniciar_metodo("", None, false, TipoIl::Vazio);
+        if estaticos {
+            self.emitir(format!(
+                "call void {}::pds_inicializar_estaticos()",
+                PROGRAMA
+            ));
+        }
+        if globais {
+            self.emitir(format!("call void {}::pds_comandos_globais()", PROGRAMA));
+        }
+        let principal = self
+            .tabelas
+            .ordem_funcoes
+            .iter()
+            .find(|n| *n == "Principal" || *n == "principal" || n.ends_with(".Principal"))
+            .cloned();
+        if let Some(fqn) = principal {
+            let valor = self.chamar_funcao(&fqn, &[])?;
+            self.descartar(&valor.tipo);
+        }
+        self.emitir("ret");
+        self.terminar_metodo(
+            "public hidebysig static void pds_iniciar",
+            &[".entrypoint".to_string()],
+        )
+    }
+
+    fn nome_despacho(nome: &str, n: usize) -> String {
+        format!("'despachar.{}.{}'", identificador(nome), n)
+    }
+
+    fn instrucao_despacho(&self, despacho: &Despacho, n: usize) -> String {
+        let mut tipos = vec![TipoIl::Objeto];
+        tipos.extend(despacho.parametros.iter().cloned());
+        format!(
+            "call {} {}::{}({})",
+            despacho.retorno.il(),
+            PROGRAMA,
+            Self::nome_despacho(&despacho.nome, n),
+            lista_tipos(&tipos)
+        )
+    }
+
+    /// Métodos de despacho: testam as classes da mais derivada para a base.
+    fn gerar_despachos(&mut self) -> Result<String, String> {
+        let mut classes: Vec<String> = self
+            .tabelas
+            .ordem_classes
+            .iter()
+            .filter(|fqn| {
+                let decl = self.tabelas.classes[*fqn].decl;
+                !decl.eh_abstrata && !decl.eh_estatica
+            })
+            .cloned()
+            .collect();
+        classes.sort_by_key(|fqn| std::cmp::Reverse(self.tabelas.hierarquia(fqn).len()));
+
+        let mut saida = String::new();
+        for ((nome, n), despacho) in self.despachos.clone() {
+            self.iniciar_metodo("", None, false, despacho.retorno.clone());
+            self.parametros
+                .push(("objeto".to_string(), "object".to_string()));
+            for (i, tipo) in despacho.parametros.iter().enumerate() {
+                self.parametros.push((format!("a{}", i), tipo.il()));
+            }
+            for fqn in &classes {
+                let Some((dono, metodo)) = self.tabelas.buscar_metodo(fqn, &nome, n) else {
+                    continue;
+                };
+                if metodo.eh_estatica {
+                    continue;
+                }
+                let (retorno, parametros) = self.assinatura(&dono, metodo);
+                let proximo = self.rotulo("proximo");
+                let classe = self.tabelas.classes[fqn].simbolo.clone();
+                self.emitir("ldarg.0");
+                self.emitir(format!("isinst {}", classe));
+                self.emitir(format!("brfalse {}", proximo));
+                self.emitir("ldarg.0");
+                self.emitir(format!("castclass {}", classe));
+                for (i, tipo) in parametros.iter().enumerate() {
+                    match despacho.parametros.get(i) {
+                        Some(de) => {
+                            self.carregar(Slot::Argumento(i + 1));
+                            self.converter(de, tipo);
+                        }
+                        None => self.padrao(tipo, true),
+                    }
+                }
+                self.emitir(self.instrucao_metodo(&dono, metodo));
+                self.converter(&retorno, &despacho.retorno);
+                self.emitir("ret");
+                self.emitir(format!("{}:", proximo));
+            }
+            self.emitir(ldstr(&despacho.nome));
+            self.emitir("ldarg.0");
+            self.emitir("call void PorDoSol.Runtime::MetodoNaoEncontrado(string, object)");
+            self.padrao(&despacho.retorno, false);
+            self.emitir("ret");
+            saida.push_str(&self.terminar_metodo(
+                &format!(
+                    "public hidebysig static {} {}",
+                    despacho.retorno.il(),
+                    Self::nome_despacho(&nome, n)
+                ),
+                &[],
+            )?);
+        }
+        Ok(saida)
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* MÉTODOS E COMANDOS                                                  */
+    /* ------------------------------------------------------------------ */
+
+    /// Prepara o estado para gerar o corpo de um método.
+    fn iniciar_metodo(
+        &mut self,
+        namespace: &str,
+        classe: Option<&str>,
+        tem_este: bool,
+        retorno: TipoIl,
+    ) {
+        self.namespace = namespace.to_string();
+        self.classe_atual = classe.map(str::to_string);
+        self.tem_este = tem_este;
+        self.retorno = retorno;
+        self.escopos = vec![HashMap::new()];
+        self.nomes_usados = HashSet::new();
+        self.parametros = Vec::new();
+        self.locais = Vec::new();
+        self.temporarios_em_uso.clear();
+        self.codigo = Vec::new();
+        self.rotulos = 0;
+        self.posicao = None;
+    }
+
+    fn declarar_parametros(&mut self, parametros: &[ast::Parametro], namespace: &str) {
+        for p in parametros {
+            let tipo = self.tipo_il(&p.tipo, namespace);
+            let nome = identificador(&p.nome);
+            self.nomes_usados.insert(nome.clone());
+            self.parametros.push((nome, tipo.il()));
+            let indice = self.parametros.len() - 1 + self.tem_este as usize;
+            self.escopos
+                .last_mut()
+                .unwrap()
+                .insert(p.nome.clone(), (Slot::Argumento(indice), tipo));
         }
-        code
     }
 
-    fn flatten_soma(&self, expr: &'a ast::Expressao) -> Vec<&'a ast::Expressao> {
-        let mut parts = Vec::new();
-        let mut stack = vec![expr];
-        while let Some(e) = stack.pop() {
-            if let ast::Expressao::Aritmetica(ast::OperadorAritmetico::Soma, esq, dir) = e {
-                stack.push(dir);
-                stack.push(esq);
+    /// Texto do método: cabeçalho, diretivas, `.maxstack` calculado, locais e instruções.
+    fn terminar_metodo(&mut self, cabecalho: &str, diretivas: &[String]) -> Result<String, String> {
+        let codigo = std::mem::take(&mut self.codigo);
+        let maxstack = profundidade_maxima(codigo.iter().map(String::as_str))
+            .map_err(|e| format!("Erro interno do gerador CIL em '{}': {}", cabecalho, e))?;
+        let parametros: Vec<String> = self
+            .parametros
+            .iter()
+            .map(|(nome, tipo)| format!("{} '{}'", tipo, nome))
+            .collect();
+        let mut saida = format!(
+            "  .method {}({}) cil managed\n  {{\n",
+            cabecalho,
+            parametros.join(", ")
+        );
+        for diretiva in diretivas {
+            saida.push_str(&format!("    {}\n", diretiva));
+        }
+        saida.push_str(&format!("    .maxstack {}\n", maxstack));
+        if !self.locais.is_empty() {
+            let locais: Vec<String> = self
+                .locais
+                .iter()
+                .map(|(nome, tipo)| format!("{} '{}'", tipo, nome))
+                .collect();
+            saida.push_str(&format!("    .locals init ({})\n", locais.join(", ")));
+        }
+        for instrucao in codigo {
+            let recuo = if instrucao.ends_with(':') {
+                "  "
             } else {
-                parts.push(e);
+                "    "
+            };
+            saida.push_str(recuo);
+            saida.push_str(&instrucao);
+            saida.push('\n');
+        }
+        saida.push_str("  }\n\n");
+        Ok(saida)
+    }
+
+    fn emitir(&mut self, instrucao: impl Into<String>) {
+        self.codigo.push(instrucao.into());
+    }
+
+    /// Gera `gerar` em separado, devolvendo as instruções sem emiti-las.
+    fn capturar<T>(
+        &mut self,
+        gerar: impl FnOnce(&mut Self) -> Result<T, String>,
+    ) -> Result<(Vec<String>, T), String> {
+        let anterior = std::mem::take(&mut self.codigo);
+        let resultado = gerar(self);
+        let capturado = std::mem::replace(&mut self.codigo, anterior);
+        Ok((capturado, resultado?))
+    }
+
+    /// Rótulo novo no método atual (`L3_fim`).
+    fn rotulo(&mut self, nome: &str) -> String {
+        self.rotulos += 1;
+        format!("L{}_{}", self.rotulos, nome)
+    }
+
+    fn carregar(&mut self, slot: Slot) {
+        let instrucao = match slot {
+            Slot::Argumento(i @ 0..=3) => format!("ldarg.{}", i),
+            Slot::Argumento(i @ 4..=255) => format!("ldarg.s {}", i),
+            Slot::Argumento(i) => format!("ldarg {}", i),
+            Slot::Local(i @ 0..=3) => format!("ldloc.{}", i),
+            Slot::Local(i @ 4..=255) => format!("ldloc.s {}", i),
+            Slot::Local(i) => format!("ldloc {}", i),
+        };
+        self.emitir(instrucao);
+    }
+
+    fn guardar(&mut self, slot: Slot) {
+        let instrucao = match slot {
+            Slot::Argumento(i @ 0..=255) => format!("starg.s {}", i),
+            Slot::Argumento(i) => format!("starg {}", i),
+            Slot::Local(i @ 0..=3) => format!("stloc.{}", i),
+            Slot::Local(i @ 4..=255) => format!("stloc.s {}", i),
+            Slot::Local(i) => format!("stloc {}", i),
+        };
+        self.emitir(instrucao);
+    }
+
+    /// Local auxiliar do comando atual, reaproveitado nos comandos seguintes.
+    fn temporario(&mut self, tipo: &TipoIl) -> Slot {
+        let tipo = tipo.il();
+        let livre = self.locais.iter().enumerate().position(|(i, (nome, t))| {
+            nome.starts_with("pds_t") && *t == tipo && !self.temporarios_em_uso.contains(&i)
+        });
+        let indice = livre.unwrap_or_else(|| {
+            self.locais
+                .push((format!("pds_t{}", self.locais.len()), tipo));
+            self.locais.len() - 1
+        });
+        self.temporarios_em_uso.insert(indice);
+        Slot::Local(indice)
+    }
+
+    fn declarar_local(&mut self, nome: &str, tipo: TipoIl) -> Slot {
+        let base = identificador(nome);
+        let mut il = base.clone();
+        let mut i = 2;
+        while self.nomes_usados.contains(&il) {
+            il = format!("{}_{}", base, i);
+            i += 1;
+        }
+        self.nomes_usados.insert(il.clone());
+        self.locais.push((il, tipo.il()));
+        let slot = Slot::Local(self.locais.len() - 1);
+        self.escopos
+            .last_mut()
+            .unwrap()
+            .insert(nome.to_string(), (slot, tipo));
+        slot
+    }
+
+    fn gerar_bloco(&mut self, comandos: &[ast::Comando]) -> Result<(), String> {
+        for comando in comandos {
+            self.gerar_comando(comando)?;
+        }
+        Ok(())
+    }
+
+    /// Corpo de `se`/`enquanto`, com escopo próprio.
+    fn gerar_corpo(&mut self, comando: &ast::Comando) -> Result<(), String> {
+        self.escopos.push(HashMap::new());
+        match comando {
+            ast::Comando::Bloco(comandos) => self.gerar_bloco(comandos)?,
+            outro => self.gerar_comando(outro)?,
+        }
+        self.escopos.pop();
+        Ok(())
+    }
+
+    fn gerar_comando(&mut self, comando: &ast::Comando) -> Result<(), String> {
+        self.temporarios_em_uso.clear();
+        match comando {
+            ast::Comando::Posicao(posicao) => self.posicao = Some(posicao.clone()),
+            ast::Comando::DeclaracaoVariavel(tipo, nome, inicial) => {
+                let tipo = match self.tipo_il(tipo, &self.namespace.clone()) {
+                    TipoIl::Vazio => TipoIl::Objeto,
+                    tipo => tipo,
+                };
+                match inicial {
+                    Some(expr) => {
+                        let valor = self.expr(expr)?;
+                        self.converter(&valor.tipo, &tipo);
+                    }
+                    None => self.padrao(&tipo, true),
+                }
+                let slot = self.declarar_local(nome, tipo);
+                self.guardar(slot);
+            }
+            ast::Comando::DeclaracaoVar(nome, expr) => {
+                let valor = self.expr(expr)?;
+                self.declarar_var(nome, valor);
+            }
+            ast::Comando::CriarObjeto(nome, classe, args) => {
+                let valor = self.expr(&ast::Expressao::NovoObjeto(
+                    ast::Tipo::Classe(classe.clone()),
+                    args.clone(),
+                ))?;
+                self.declarar_var(nome, valor);
+            }
+            ast::Comando::Atribuicao(nome, expr) => self.atribuir_variavel(nome, expr)?,
+            ast::Comando::AtribuirPropriedade(alvo, membro, expr)
+            | ast::Comando::AtribuirCampo(alvo, membro, expr) => {
+                self.atribuir_membro(alvo, membro, expr)?
+            }
+            ast::Comando::AtribuirIndice(alvo, indice, expr) => {
+                self.atribuir_indice(alvo, indice, expr)?
+            }
+            ast::Comando::Imprima(expr) => {
+                let valor = self.expr(expr)?;
+                self.texto_de(&valor);
+                self.emitir("call void PorDoSol.Runtime::Imprimir(string)");
+            }
+            ast::Comando::Se(condicao, entao, senao) => {
+                let rotulo_senao = self.rotulo("senao");
+                self.condicao(condicao)?;
+                self.emitir(format!("brfalse {}", rotulo_senao));
+                self.gerar_corpo(entao)?;
+                match senao {
+                    Some(senao) => {
+                        let rotulo_fim = self.rotulo("fim");
+                        self.emitir(format!("br {}", rotulo_fim));
+                        self.emitir(format!("{}:", rotulo_senao));
+                        self.gerar_corpo(senao)?;
+                        self.emitir(format!("{}:", rotulo_fim));
+                    }
+                    None => self.emitir(format!("{}:", rotulo_senao)),
+                }
+            }
+            ast::Comando::Enquanto(condicao, corpo) => {
+                self.gerar_laco(None, Some(condicao), None, corpo)?;
+            }
+            ast::Comando::Para(inicio, condicao, incremento, corpo) => {
+                self.gerar_laco(
+                    inicio.as_deref(),
+                    condicao.as_ref(),
+                    incremento.as_deref(),
+                    corpo,
+                )?;
+            }
+            ast::Comando::Bloco(comandos) => {
+                self.escopos.push(HashMap::new());
+                self.gerar_bloco(comandos)?;
+                self.escopos.pop();
+            }
+            ast::Comando::Retorne(expr) => {
+                let retorno = self.retorno.clone();
+                match expr {
+                    Some(expr) => {
+                        let valor = self.expr(expr)?;
+                        self.converter(&valor.tipo, &retorno);
+                    }
+                    None => self.padrao(&retorno, false),
+                }
+                self.emitir("ret");
+            }
+            ast::Comando::Expressao(expr) => {
+                let valor = self.expr(expr)?;
+                self.descartar(&valor.tipo);
+            }
+            ast::Comando::ChamarMetodo(alvo, metodo, args) => {
+                let valor = self.chamada_metodo(alvo, metodo, args)?;
+                self.descartar(&valor.tipo);
+            }
+            ast::Comando::AcessarCampo(variavel, campo) => {
+                let valor = self.expr(&ast::Expressao::AcessoMembro(
+                    Box::new(ast::Expressao::Identificador(variavel.clone())),
+                    campo.clone(),
+                ))?;
+                self.descartar(&valor.tipo);
+            }
+        }
+        Ok(())
+    }
+
+    /// Variável de `var x = ...`, com o valor já na pilha.
+    fn declarar_var(&mut self, nome: &str, valor: Valor) {
+        let tipo = match valor.tipo {
+            TipoIl::Nulo | TipoIl::Vazio => TipoIl::Objeto,
+            ref tipo => tipo.clone(),
+        };
+        self.converter(&valor.tipo, &tipo);
+        let slot = self.declarar_local(nome, tipo);
+        self.guardar(slot);
+    }
+
+    /// Remove da pilha um valor avaliado só pelos efeitos.
+    fn descartar(&mut self, tipo: &TipoIl) {
+        if *tipo != TipoIl::Vazio {
+            self.emitir("pop");
+        }
+    }
+
+    /// Condição de `se`/`enquanto`, deixada na pilha.
+    fn condicao(&mut self, expr: &ast::Expressao) -> Result<(), String> {
+        let valor = self.expr(expr)?;
+        if valor.tipo != TipoIl::Booleano {
+            return Err(format!(
+                "Condição deve ser booleana, obtido {}",
+                self.nome_tipo(&valor.tipo)
+            ));
+        }
+        Ok(())
+    }
+
+    fn gerar_laco(
+        &mut self,
+        inicio: Option<&ast::Comando>,
+        condicao: Option<&ast::Expressao>,
+        incremento: Option<&ast::Comando>,
+        corpo: &ast::Comando,
+    ) -> Result<(), String> {
+        self.escopos.push(HashMap::new());
+        if let Some(inicio) = inicio {
+            self.gerar_comando(inicio)?;
+        }
+        let rotulo_laco = self.rotulo("laco");
+        let rotulo_fim = self.rotulo("fim");
+        self.emitir(format!("{}:", rotulo_laco));
+        if let Some(condicao) = condicao {
+            self.condicao(condicao)?;
+            self.emitir(format!("brfalse {}", rotulo_fim));
+        }
+        self.gerar_corpo(corpo)?;
+        if let Some(incremento) = incremento {
+            self.gerar_comando(incremento)?;
+        }
+        self.emitir(format!("br {}", rotulo_laco));
+        self.emitir(format!("{}:", rotulo_fim));
+        self.escopos.pop();
+        Ok(())
+    }
+
+    fn atribuir_variavel(&mut self, nome: &str, expr: &ast::Expressao) -> Result<(), String> {
+        let (acesso, tipo) = self.acesso(nome)?;
+        if let Acesso::Campo(_) = acesso {
+            self.emitir("ldarg.0");
+        }
+        let valor = self.expr(expr)?;
+        self.converter(&valor.tipo, &tipo);
+        match acesso {
+            Acesso::Slot(slot) => self.guardar(slot),
+            Acesso::Campo(campo) => self.emitir(format!("stfld {}", campo)),
+            Acesso::Estatico(campo) => self.emitir(format!("stsfld {}", campo)),
+        }
+        Ok(())
+    }
+
+    fn atribuir_membro(
+        &mut self,
+        alvo: &ast::Expressao,
+        membro: &str,
+        expr: &ast::Expressao,
+    ) -> Result<(), String> {
+        if let Some(estatico) = self.alvo_estatico(alvo) {
+            let AlvoEstatico::Classe(classe) = estatico else {
+                return Err(format!("Não é possível atribuir a '{}'", membro));
+            };
+            let (campo, tipo) = self.dono_estatico(&classe, membro).ok_or_else(|| {
+                format!(
+                    "Membro estático '{}' não encontrado em '{}'",
+                    membro, classe
+                )
+            })?;
+            let valor = self.expr(expr)?;
+            self.converter(&valor.tipo, &tipo);
+            self.emitir(format!("stsfld {}", campo));
+            return Ok(());
+        }
+        let objeto = self.expr(alvo)?;
+        let TipoIl::Classe(classe) = objeto.tipo.clone() else {
+            return Err(format!(
+                "Não é possível atribuir '{}' em um valor do tipo {}",
+                membro,
+                self.nome_tipo(&objeto.tipo)
+            ));
+        };
+        // O interpretador avalia o valor antes de verificar a referência
+        let referencia = if self.verificacoes && !objeto.nao_nulo {
+            let temporario = self.temporario(&TipoIl::Objeto);
+            self.emitir("dup");
+            self.guardar(temporario);
+            Some(temporario)
+        } else {
+            None
+        };
+        let setter = self
+            .tabelas
+            .buscar_propriedade(&classe, membro)
+            .filter(|(_, p)| p.definir.as_ref().is_some_and(|a| a.corpo.is_some()));
+        let (tipo, destino) = match setter {
+            Some((dono, propriedade)) => {
+                let namespace = self.tabelas.classes[&dono].namespace.clone();
+                let tipo = self.tipo_il(&propriedade.tipo, &namespace);
+                let destino = format!(
+                    "call instance void {}::'set_{}'({})",
+                    self.tabelas.classes[&dono].simbolo,
+                    identificador(membro),
+                    tipo.il()
+                );
+                (tipo, destino)
+            }
+            None => {
+                let (campo, tipo) = self.buscar_campo(&classe, membro).ok_or_else(|| {
+                    format!("Membro '{}' não encontrado na classe '{}'", membro, classe)
+                })?;
+                (tipo, format!("stfld {}", campo))
+            }
+        };
+        let valor = self.expr(expr)?;
+        self.converter(&valor.tipo, &tipo);
+        if let Some(referencia) = referencia {
+            self.carregar(referencia);
+            self.verificar_nulo(&format!("Atribuição a '{}' em referência nula", membro));
+        }
+        self.emitir(destino);
+        Ok(())
+    }
+
+    fn atribuir_indice(
+        &mut self,
+        alvo: &ast::Expressao,
+        indice: &ast::Expressao,
+        expr: &ast::Expressao,
+    ) -> Result<(), String> {
+        let array = self.expr(alvo)?;
+        let TipoIl::Array(item) = array.tipo.clone() else {
+            return Err("Atribuição por índice requer array e inteiro".to_string());
+        };
+        let guardar = format!("stelem.{}", item.elemento().1);
+        if !self.verificacoes {
+            let posicao = self.expr(indice)?;
+            self.converter(&posicao.tipo, &TipoIl::Inteiro);
+            self.emitir("conv.i4");
+            let valor = self.expr(expr)?;
+            self.converter(&valor.tipo, &item);
+            self.emitir(guardar);
+            return Ok(());
+        }
+        // Índice verificado depois de avaliar o valor, como no interpretador
+        let temp_array = self.temporario(&array.tipo);
+        let temp_indice = self.temporario(&TipoIl::Inteiro);
+        let temp_valor = self.temporario(&item);
+        self.guardar(temp_array);
+        let posicao = self.expr(indice)?;
+        self.converter(&posicao.tipo, &TipoIl::Inteiro);
+        self.guardar(temp_indice);
+        let valor = self.expr(expr)?;
+        self.converter(&valor.tipo, &item);
+        self.guardar(temp_valor);
+        self.carregar(temp_array);
+        self.emitir("dup");
+        self.carregar(temp_indice);
+        self.posicao_il();
+        self.emitir(
+            "call int32 PorDoSol.Runtime::Indice(class [mscorlib]System.Array, int64, string, int32, int32)",
+        );
+        self.carregar(temp_valor);
+        self.emitir(guardar);
+        Ok(())
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* EXPRESSÕES                                                          */
+    /* ------------------------------------------------------------------ */
+
+    /// Posição atual como argumentos `arquivo, linha, coluna` das funções de verificação.
+    fn posicao_il(&mut self) {
+        match self.posicao.clone() {
+            Some(p) => {
+                self.emitir(ldstr(&p.arquivo));
+                self.emitir(format!("ldc.i4 {}", p.linha));
+                self.emitir(format!("ldc.i4 {}", p.coluna));
+            }
+            None => {
+                self.emitir("ldnull");
+                self.emitir("ldc.i4.0");
+                self.emitir("ldc.i4.0");
+            }
+        }
+    }
+
+    /// Verifica a referência no topo da pilha e a consome.
+    fn verificar_nulo(&mut self, mensagem: &str) {
+        self.emitir(ldstr(mensagem));
+        self.posicao_il();
+        self.emitir(VERIFICAR_NULO);
+    }
+
+    /// Verifica a referência no topo da pilha e a mantém.
+    fn nao_nulo(&mut self, mensagem: &str) {
+        self.emitir("dup");
+        self.verificar_nulo(mensagem);
+    }
+
+    /// Valor de variáveis sem inicializador (`vazio_texto`: `""`, como `ValorRuntime::padrao`)
+    /// ou de retornos e parâmetros omitidos (texto nulo).
+    fn padrao(&mut self, tipo: &TipoIl, vazio_texto: bool) {
+        match tipo {
+            TipoIl::Vazio => {}
+            TipoIl::Texto if vazio_texto => self.emitir("ldstr \"\""),
+            TipoIl::Inteiro => self.emitir("ldc.i8 0"),
+            TipoIl::Flutuante => self.emitir("ldc.r4 0.0"),
+            TipoIl::Duplo => self.emitir("ldc.r8 0.0"),
+            TipoIl::Booleano => self.emitir("ldc.i4.0"),
+            _ => self.emitir("ldnull"),
+        }
+    }
+
+    fn expr(&mut self, expr: &ast::Expressao) -> Result<Valor, String> {
+        match expr {
+            ast::Expressao::Inteiro(n) => {
+                self.emitir(format!("ldc.i8 {}", n));
+                Ok(Valor::novo(TipoIl::Inteiro))
+            }
+            ast::Expressao::Texto(s) => {
+                self.emitir(ldstr(s));
+                Ok(Valor::nao_nulo(TipoIl::Texto))
+            }
+            ast::Expressao::Booleano(b) => {
+                self.emitir(format!("ldc.i4.{}", *b as i32));
+                Ok(Valor::novo(TipoIl::Booleano))
+            }
+            ast::Expressao::Decimal(lit) | ast::Expressao::DuploLiteral(lit) => {
+                self.emitir(format!("ldc.r8 {}", literal_real(lit, false)?));
+                Ok(Valor::novo(TipoIl::Duplo))
+            }
+            ast::Expressao::FlutuanteLiteral(lit) => {
+                self.emitir(format!("ldc.r4 {}", literal_real(lit, true)?));
+                Ok(Valor::novo(TipoIl::Flutuante))
+            }
+            ast::Expressao::Nulo => {
+                self.emitir("ldnull");
+                Ok(Valor::novo(TipoIl::Nulo))
+            }
+            ast::Expressao::Este => {
+                let classe = self
+                    .classe_atual
+                    .clone()
+                    .filter(|_| self.tem_este)
+                    .ok_or_else(|| "'este' usado fora de um método de instância".to_string())?;
+                self.emitir("ldarg.0");
+                Ok(Valor::nao_nulo(TipoIl::Classe(classe)))
+            }
+            ast::Expressao::Identificador(nome) => self.ler_variavel(nome),
+            ast::Expressao::ListaLiteral(itens) => self.lista(itens),
+            ast::Expressao::NovoArray(tipo, tamanho) => {
+                let item = self.tipo_il(tipo, &self.namespace.clone());
+                let valor = self.expr(tamanho)?;
+                self.converter(&valor.tipo, &TipoIl::Inteiro);
+                if self.verificacoes {
+                    self.posicao_il();
+                    self.emitir(
+                        "call int32 PorDoSol.Runtime::Tamanho(int64, string, int32, int32)",
+                    );
+                } else {
+                    self.emitir("conv.i4");
+                }
+                if item == TipoIl::Texto {
+                    // Arrays de texto começam com textos vazios, como no interpretador
+                    self.emitir("call string[] PorDoSol.Runtime::NovoArrayTexto(int32)");
+                } else {
+                    self.emitir(format!("newarr {}", item.token()));
+                }
+                Ok(Valor::nao_nulo(TipoIl::Array(Box::new(item))))
+            }
+            ast::Expressao::NovoObjeto(tipo, args) => self.novo_objeto(tipo, args),
+            ast::Expressao::Aritmetica(op, esq, dir) => {
+                let esq = self.expr(esq)?;
+                let (codigo, dir) = self.capturar(|g| g.expr(dir))?;
+                self.aritmetica(op, esq, codigo, dir)
+            }
+            ast::Expressao::Comparacao(op, esq, dir) => {
+                let esq = self.expr(esq)?;
+                let (codigo, dir) = self.capturar(|g| g.expr(dir))?;
+                self.comparacao(op, esq, codigo, dir)
+            }
+            ast::Expressao::Logica(op, esq, dir) => self.logica(op, esq, dir),
+            ast::Expressao::Unario(op, operando) => {
+                let valor = self.expr(operando)?;
+                match (op, &valor.tipo) {
+                    (ast::OperadorUnario::NegacaoLogica, TipoIl::Booleano) => {
+                        self.emitir("ldc.i4.0");
+                        self.emitir("ceq");
+                    }
+                    (ast::OperadorUnario::NegacaoNumerica, tipo) if tipo.numerico() => {
+                        self.emitir("neg");
+                    }
+                    (_, tipo) => {
+                        return Err(format!(
+                            "Operador unário incompatível com {}",
+                            self.nome_tipo(tipo)
+                        ))
+                    }
+                }
+                Ok(Valor::novo(valor.tipo))
+            }
+            ast::Expressao::StringInterpolada(partes) => {
+                if partes.is_empty() {
+                    self.emitir("ldstr \"\"");
+                }
+                for (i, parte) in partes.iter().enumerate() {
+                    match parte {
+                        ast::PartStringInterpolada::Texto(s) => self.emitir(ldstr(s)),
+                        ast::PartStringInterpolada::Expressao(e) => {
+                            let valor = self.expr(e)?;
+                            self.texto_de(&valor);
+                        }
+                    }
+                    if i > 0 {
+                        self.emitir(CONCATENAR);
+                    }
+                }
+                Ok(Valor::nao_nulo(TipoIl::Texto))
+            }
+            ast::Expressao::AcessoMembro(alvo, membro) => self.acesso_membro(alvo, membro),
+            ast::Expressao::AcessoIndice(alvo, indice) => {
+                let array = self.expr(alvo)?;
+                let TipoIl::Array(item) = array.tipo else {
+                    return Err(match array.tipo {
+                        TipoIl::Texto => {
+                            "Acesso por índice em texto não é suportado no alvo cil".to_string()
+                        }
+                        _ => "Acesso por índice requer array ou texto e um inteiro".to_string(),
+                    });
+                };
+                if self.verificacoes {
+                    self.emitir("dup");
+                }
+                let posicao = self.expr(indice)?;
+                self.converter(&posicao.tipo, &TipoIl::Inteiro);
+                if self.verificacoes {
+                    self.posicao_il();
+                    self.emitir(
+                        "call int32 PorDoSol.Runtime::Indice(class [mscorlib]System.Array, int64, string, int32, int32)",
+                    );
+                } else {
+                    self.emitir("conv.i4");
+                }
+                self.emitir(format!("ldelem.{}", item.elemento().0));
+                Ok(Valor::novo(*item))
+            }
+            ast::Expressao::ChamadaMetodo(alvo, metodo, args) => {
+                self.chamada_metodo(alvo, metodo, args)
+            }
+            ast::Expressao::Chamada(nome, args) => self.chamada(nome, args),
+            // Não há tarefas neste alvo: a função assíncrona executa na hora
+            ast::Expressao::Aguarde(expr) => self.expr(expr),
+        }
+    }
+
+    fn lista(&mut self, itens: &[ast::Expressao]) -> Result<Valor, String> {
+        let mut valores = Vec::new();
+        for item in itens {
+            valores.push(self.capturar(|g| g.expr(item))?);
+        }
+        let tipos: Vec<TipoIl> = valores.iter().map(|(_, v)| v.tipo.clone()).collect();
+        let item = self.tipo_itens(&tipos);
+        let tipo = TipoIl::Array(Box::new(item.clone()));
+        let array = self.temporario(&tipo);
+        self.emitir(format!("ldc.i4 {}", valores.len()));
+        self.emitir(format!("newarr {}", item.token()));
+        self.guardar(array);
+        let guardar = format!("stelem.{}", item.elemento().1);
+        for (i, (codigo, valor)) in valores.into_iter().enumerate() {
+            self.carregar(array);
+            self.emitir(format!("ldc.i4 {}", i));
+            self.codigo.extend(codigo);
+            self.converter(&valor.tipo, &item);
+            self.emitir(guardar.clone());
+        }
+        self.carregar(array);
+        Ok(Valor::nao_nulo(tipo))
+    }
+
+    fn ler_variavel(&mut self, nome: &str) -> Result<Valor, String> {
+        let (acesso, tipo) = self.acesso(nome)?;
+        match acesso {
+            Acesso::Slot(slot) => self.carregar(slot),
+            Acesso::Campo(campo) => {
+                self.emitir("ldarg.0");
+                self.emitir(format!("ldfld {}", campo));
+            }
+            Acesso::Estatico(campo) => self.emitir(format!("ldsfld {}", campo)),
+        }
+        Ok(Valor::novo(tipo))
+    }
+
+    fn acesso(&self, nome: &str) -> Result<(Acesso, TipoIl), String> {
+        match self.variavel(nome) {
+            Some(Variavel::Local((slot, tipo))) => Ok((Acesso::Slot(slot), tipo)),
+            Some(Variavel::Campo(membro)) => {
+                let (campo, tipo) = self.campo(nome, &membro);
+                Ok((Acesso::Campo(campo), tipo))
+            }
+            Some(Variavel::Estatico(membro)) => {
+                let (campo, tipo) = self.estatico(nome, &membro);
+                Ok((Acesso::Estatico(campo), tipo))
+            }
+            None => Err(format!("Variável '{}' não definida", nome)),
+        }
+    }
+
+    fn acesso_membro(&mut self, alvo: &ast::Expressao, membro: &str) -> Result<Valor, String> {
+        if let Some(estatico) = self.alvo_estatico(alvo) {
+            return match estatico {
+                AlvoEstatico::Classe(classe) => {
+                    let (campo, tipo) = self.dono_estatico(&classe, membro).ok_or_else(|| {
+                        format!(
+                            "Membro estático '{}' não encontrado em '{}'",
+                            membro, classe
+                        )
+                    })?;
+                    self.emitir(format!("ldsfld {}", campo));
+                    Ok(Valor::novo(tipo))
+                }
+                AlvoEstatico::Enum(en) => {
+                    let posicao = self.tabelas.enums[&en]
+                        .valores
+                        .iter()
+                        .position(|v| v == membro)
+                        .ok_or_else(|| format!("Valor '{}' não existe na enumeração", membro))?;
+                    self.emitir(format!("ldc.i8 {}", posicao));
+                    Ok(Valor::novo(TipoIl::Inteiro))
+                }
+                AlvoEstatico::Nativo(nome) => Err(format!("Classe '{}' não encontrada", nome)),
+            };
+        }
+        let objeto = self.expr(alvo)?;
+        let mensagem = format!("Acesso a '{}' em referência nula", membro);
+        match objeto.tipo.clone() {
+            TipoIl::Texto if membro == "tamanho" => {
+                self.emitir("call int64 PorDoSol.Runtime::TamanhoTexto(string)");
+                Ok(Valor::novo(TipoIl::Inteiro))
+            }
+            TipoIl::Array(_) if membro == "tamanho" => {
+                if self.verificacoes && !objeto.nao_nulo {
+                    self.nao_nulo(&mensagem);
+                }
+                self.emitir("ldlen");
+                self.emitir("conv.i8");
+                Ok(Valor::novo(TipoIl::Inteiro))
+            }
+            TipoIl::Classe(classe) => {
+                if self.verificacoes && !objeto.nao_nulo {
+                    self.nao_nulo(&mensagem);
+                }
+                if let Some((dono, propriedade)) = self.tabelas.buscar_propriedade(&classe, membro)
+                {
+                    if propriedade
+                        .obter
+                        .as_ref()
+                        .is_some_and(|a| a.corpo.is_some())
+                    {
+                        let namespace = self.tabelas.classes[&dono].namespace.clone();
+                        let tipo = self.tipo_il(&propriedade.tipo, &namespace);
+                        self.emitir(format!(
+                            "call instance {} {}::'get_{}'()",
+                            tipo.il(),
+                            self.tabelas.classes[&dono].simbolo,
+                            identificador(membro)
+                        ));
+                        return Ok(Valor::novo(tipo));
+                    }
+                }
+                let (campo, tipo) = self.buscar_campo(&classe, membro).ok_or_else(|| {
+                    format!("Membro '{}' não encontrado na classe '{}'", membro, classe)
+                })?;
+                self.emitir(format!("ldfld {}", campo));
+                Ok(Valor::novo(tipo))
+            }
+            tipo => Err(format!(
+                "Membro '{}' de valores do tipo {} não é suportado no alvo cil",
+                membro,
+                self.nome_tipo(&tipo)
+            )),
+        }
+    }
+
+    fn novo_objeto(&mut self, tipo: &ast::Tipo, args: &[ast::Expressao]) -> Result<Valor, String> {
+        let nome = nome_do_tipo(tipo)
+            .ok_or_else(|| format!("Não é possível instanciar o tipo '{}'", tipo))?;
+        let Some(classe) = self.tabelas.resolver_classe(&self.namespace.clone(), nome) else {
+            // Classe que só existe na biblioteca padrão: erro em tempo de execução, como no interpretador
+            self.panico(&format!("Classe '{}' não encontrada", nome));
+            self.emitir("ldnull");
+            return Ok(Valor::novo(TipoIl::Objeto));
+        };
+        let decl = self.tabelas.classes[&classe].decl;
+        if decl.eh_abstrata || decl.eh_estatica {
+            let tipo_classe = if decl.eh_abstrata {
+                "abstrata"
+            } else {
+                "estática"
+            };
+            self.panico(&format!(
+                "Não é possível instanciar a classe {} '{}'",
+                tipo_classe, classe
+            ));
+            self.emitir("ldnull");
+            return Ok(Valor::novo(TipoIl::Classe(classe)));
+        }
+        let tipos = match self.tabelas.escolher_construtor(&classe, args.len()) {
+            Some(indice) => {
+                let parametros = decl.construtores[indice].parametros.clone();
+                let namespace = self.tabelas.classes[&classe].namespace.clone();
+                self.argumentos(&parametros, &namespace, args)?;
+                self.parametros_construtor(&classe, indice)
+            }
+            None => {
+                // Sem construtor: os argumentos são avaliados e descartados
+                for arg in args {
+                    let valor = self.expr(arg)?;
+                    self.descartar(&valor.tipo);
+                }
+                Vec::new()
+            }
+        };
+        self.emitir(format!(
+            "newobj instance void {}::.ctor({})",
+            self.tabelas.classes[&classe].simbolo,
+            lista_tipos(&tipos)
+        ));
+        Ok(Valor::nao_nulo(TipoIl::Classe(classe)))
+    }
+
+    /// Erro em tempo de execução (o programa termina na chamada).
+    fn panico(&mut self, mensagem: &str) {
+        self.emitir(ldstr(mensagem));
+        self.posicao_il();
+        self.emitir("call void PorDoSol.Runtime::Panico(string, string, int32, int32)");
+    }
+
+    /// Argumentos de uma chamada, convertidos para os tipos dos parâmetros; os omitidos
+    /// recebem o valor padrão declarado.
+    fn argumentos(
+        &mut self,
+        parametros: &[ast::Parametro],
+        namespace: &str,
+        args: &[ast::Expressao],
+    ) -> Result<(), String> {
+        for (i, parametro) in parametros.iter().enumerate() {
+            let tipo = self.tipo_il(&parametro.tipo, namespace);
+            match args.get(i).or(parametro.valor_padrao.as_ref()) {
+                Some(expr) => {
+                    let valor = self.expr(expr)?;
+                    self.converter(&valor.tipo, &tipo);
+                }
+                // Parâmetro sem argumento nem valor padrão: nulo no interpretador
+                None => self.padrao(&tipo, false),
+            }
+        }
+        for arg in args.iter().skip(parametros.len()) {
+            let valor = self.expr(arg)?;
+            self.descartar(&valor.tipo);
+        }
+        Ok(())
+    }
+
+    /// Chamada sem alvo: método da classe atual, função do programa ou intrínseca de console.
+    fn chamada(&mut self, nome: &str, args: &[ast::Expressao]) -> Result<Valor, String> {
+        if let Some(classe) = self.classe_atual.clone() {
+            if let Some((dono, metodo)) = self.tabelas.buscar_metodo(&classe, nome, args.len()) {
+                if self.tem_este && !metodo.eh_estatica {
+                    self.emitir("ldarg.0");
+                    let este = Valor::nao_nulo(TipoIl::Classe(classe));
+                    return self.chamar_metodo_classe(este, &dono, metodo, args);
+                }
+                return self.chamar_estatico(&dono, metodo, args);
+            }
+        }
+        if let Some(fqn) = self.tabelas.resolver_funcao(&self.namespace, nome) {
+            return self.chamar_funcao(&fqn, args);
+        }
+        match nome {
+            "EscreverLinha" | "Escrever" | "LerLinha" => {
+                self.nativo(&format!("Console::{}", nome), args)
+            }
+            _ => self.nativo(nome, args),
+        }
+    }
+
+    fn chamar_funcao(&mut self, fqn: &str, args: &[ast::Expressao]) -> Result<Valor, String> {
+        let funcao = &self.tabelas.funcoes[fqn];
+        let decl = funcao.decl;
+        let namespace = funcao.namespace.clone();
+        let il = funcao.simbolo.clone();
+        if let Some(chave) = chave_nativa(&decl.attributes) {
+            return self.nativo(chave, args);
+        }
+        if decl.attributes.iter().any(|a| a.name == "Externo") {
+            return Err(format!(
+                "Função externa '{}' não é suportada no alvo cil",
+                decl.nome
+            ));
+        }
+        let retorno = self.tipo_retorno(&decl.tipo_retorno, &namespace);
+        let parametros: Vec<TipoIl> = decl
+            .parametros
+            .iter()
+            .map(|p| self.tipo_il(&p.tipo, &namespace))
+            .collect();
+        self.argumentos(&decl.parametros, &namespace, args)?;
+        self.emitir(format!(
+            "call {} {}::{}({})",
+            retorno.il(),
+            PROGRAMA,
+            il,
+            lista_tipos(&parametros)
+        ));
+        Ok(Valor::novo(retorno))
+    }
+
+    fn chamar_estatico(
+        &mut self,
+        classe: &str,
+        metodo: &ast::MetodoClasse,
+        args: &[ast::Expressao],
+    ) -> Result<Valor, String> {
+        if let Some(chave) = chave_nativa(&metodo.attributes) {
+            return self.nativo(chave, args);
+        }
+        if !metodo.eh_estatica {
+            return Err(format!(
+                "Método de instância '{}' chamado sem objeto em '{}'",
+                metodo.nome, classe
+            ));
+        }
+        let namespace = self.tabelas.classes[classe].namespace.clone();
+        let (retorno, parametros) = self.assinatura(classe, metodo);
+        self.argumentos(&metodo.parametros, &namespace, args)?;
+        self.emitir(format!(
+            "call {} {}::{}({})",
+            retorno.il(),
+            self.tabelas.classes[classe].simbolo,
+            nome_membro(&metodo.nome),
+            lista_tipos(&parametros)
+        ));
+        Ok(Valor::novo(retorno))
+    }
+
+    fn chamada_metodo(
+        &mut self,
+        alvo: &ast::Expressao,
+        metodo: &str,
+        args: &[ast::Expressao],
+    ) -> Result<Valor, String> {
+        if let Some(estatico) = self.alvo_estatico(alvo) {
+            return match estatico {
+                AlvoEstatico::Classe(classe) => {
+                    let (dono, decl) = self
+                        .tabelas
+                        .buscar_metodo(&classe, metodo, args.len())
+                        .ok_or_else(|| {
+                            format!("Método '{}' não encontrado na classe '{}'", metodo, classe)
+                        })?;
+                    self.chamar_estatico(&dono, decl, args)
+                }
+                AlvoEstatico::Nativo(classe) => {
+                    self.nativo(&format!("{}::{}", classe, metodo), args)
+                }
+                AlvoEstatico::Enum(_) => Err(format!("Enumerações não têm o método '{}'", metodo)),
+            };
+        }
+        let objeto = self.expr(alvo)?;
+        match objeto.tipo.clone() {
+            TipoIl::Texto => self.metodo_texto(metodo, args),
+            TipoIl::Classe(classe) => {
+                let (dono, decl) = self
+                    .tabelas
+                    .buscar_metodo(&classe, metodo, args.len())
+                    .ok_or_else(|| {
+                        format!("Método '{}' não encontrado na classe '{}'", metodo, classe)
+                    })?;
+                if decl.eh_estatica {
+                    self.descartar(&objeto.tipo);
+                    return self.chamar_estatico(&dono, decl, args);
+                }
+                self.chamar_metodo_classe(objeto, &dono, decl, args)
+            }
+            TipoIl::Interface(_) | TipoIl::Objeto => self.chamar_despacho(objeto, metodo, args),
+            tipo => Err(format!(
+                "Método '{}' não existe para valores do tipo {}",
+                metodo,
+                self.nome_tipo(&tipo)
+            )),
+        }
+    }
+
+    /// Guarda o receptor no topo da pilha para verificá-lo depois dos argumentos.
+    fn guardar_receptor(&mut self, objeto: &Valor) -> Option<Slot> {
+        if !self.verificacoes || objeto.nao_nulo {
+            return None;
+        }
+        let receptor = self.temporario(&TipoIl::Objeto);
+        self.emitir("dup");
+        self.guardar(receptor);
+        Some(receptor)
+    }
+
+    /// O interpretador avalia os argumentos antes de verificar a referência.
+    fn verificar_receptor(&mut self, receptor: Option<Slot>, metodo: &str) {
+        if let Some(receptor) = receptor {
+            self.carregar(receptor);
+            self.verificar_nulo(&format!("Chamada de '{}' em referência nula", metodo));
+        }
+    }
+
+    /// Chamada de método de instância com o receptor na pilha: `callvirt` quando o método tem
+    /// entrada na vtable, `call` nos demais.
+    fn chamar_metodo_classe(
+        &mut self,
+        objeto: Valor,
+        dono: &str,
+        metodo: &ast::MetodoClasse,
+        args: &[ast::Expressao],
+    ) -> Result<Valor, String> {
+        if let Some(chave) = chave_nativa(&metodo.attributes) {
+            return Err(format!(
+                "Método nativo '{}' ({}) não é suportado no alvo cil",
+                metodo.nome, chave
+            ));
+        }
+        let namespace = self.tabelas.classes[dono].namespace.clone();
+        let (retorno, _) = self.assinatura(dono, metodo);
+        let receptor = self.guardar_receptor(&objeto);
+        self.argumentos(&metodo.parametros, &namespace, args)?;
+        self.verificar_receptor(receptor, &metodo.nome);
+        self.emitir(self.instrucao_metodo(dono, metodo));
+        Ok(Valor::novo(retorno))
+    }
+
+    /// Chamada em interface (`callvirt` no método da interface) ou em `objeto` (método de
+    /// despacho pelo nome).
+    fn chamar_despacho(
+        &mut self,
+        objeto: Valor,
+        metodo: &str,
+        args: &[ast::Expressao],
+    ) -> Result<Valor, String> {
+        if let TipoIl::Interface(interface) = &objeto.tipo {
+            let interface = &self.tabelas.interfaces[interface];
+            let namespace = interface.namespace.clone();
+            let assinatura = interface
+                .decl
+                .metodos
+                .iter()
+                .find(|m| m.nome == metodo && m.parametros.len() == args.len());
+            if let Some(assinatura) = assinatura {
+                let retorno = self.tipo_retorno(&assinatura.tipo_retorno, &namespace);
+                let tipos: Vec<TipoIl> = assinatura
+                    .parametros
+                    .iter()
+                    .map(|p| self.tipo_il(&p.tipo, &namespace))
+                    .collect();
+                let instrucao = format!(
+                    "callvirt instance {} {}::{}({})",
+                    retorno.il(),
+                    objeto.tipo.token(),
+                    nome_membro(metodo),
+                    lista_tipos(&tipos)
+                );
+                let receptor = self.guardar_receptor(&objeto);
+                self.argumentos(&assinatura.parametros, &namespace, args)?;
+                self.verificar_receptor(receptor, metodo);
+                self.emitir(instrucao);
+                return Ok(Valor::novo(retorno));
+            }
+        }
+        let despacho = self.despacho(&objeto.tipo, metodo, args.len())?;
+        let receptor = self.guardar_receptor(&objeto);
+        for (arg, tipo) in args.iter().zip(&despacho.parametros) {
+            let valor = self.expr(arg)?;
+            self.converter(&valor.tipo, tipo);
+        }
+        self.verificar_receptor(receptor, metodo);
+        self.emitir(self.instrucao_despacho(&despacho, args.len()));
+        Ok(Valor::novo(despacho.retorno))
+    }
+
+    /// Método de despacho para `metodo` com `n` argumentos, criado no primeiro uso.
+    fn despacho(&mut self, tipo: &TipoIl, metodo: &str, n: usize) -> Result<Despacho, String> {
+        let chave = (metodo.to_string(), n);
+        if !self.despachos.contains_key(&chave) {
+            let despacho = self.assinatura_despacho_para(tipo, metodo, n)?;
+            self.despachos.insert(chave.clone(), despacho);
+        }
+        Ok(self.despachos[&chave].clone())
+    }
+
+    /// Tipos do despacho: a assinatura da interface, ou do primeiro método encontrado.
+    fn assinatura_despacho_para(
+        &self,
+        tipo: &TipoIl,
+        metodo: &str,
+        n: usize,
+    ) -> Result<Despacho, String> {
+        if let TipoIl::Interface(interface) = tipo {
+            let interface = &self.tabelas.interfaces[interface];
+            if let Some(assinatura) = interface
+                .decl
+                .metodos
+                .iter()
+                .find(|m| m.nome == metodo && m.parametros.len() == n)
+            {
+                return Ok(Despacho {
+                    nome: metodo.to_string(),
+                    parametros: assinatura
+                        .parametros
+                        .iter()
+                        .map(|p| self.tipo_il(&p.tipo, &interface.namespace))
+                        .collect(),
+                    retorno: self.tipo_retorno(&assinatura.tipo_retorno, &interface.namespace),
+                });
+            }
+        }
+        for fqn in &self.tabelas.ordem_classes {
+            if let Some((dono, decl)) = self.tabelas.buscar_metodo(fqn, metodo, n) {
+                if decl.eh_estatica {
+                    continue;
+                }
+                let (retorno, parametros) = self.assinatura(&dono, decl);
+                let mut parametros: Vec<TipoIl> = parametros.into_iter().take(n).collect();
+                parametros.resize(n, TipoIl::Objeto);
+                return Ok(Despacho {
+                    nome: metodo.to_string(),
+                    parametros,
+                    retorno,
+                });
+            }
+        }
+        // Nenhuma classe tem o método: a chamada termina o programa com erro
+        Ok(Despacho {
+            nome: metodo.to_string(),
+            parametros: vec![TipoIl::Objeto; n],
+            retorno: TipoIl::Objeto,
+        })
+    }
+
+    /// Métodos embutidos de `texto` (os mesmos do interpretador), com o texto na pilha.
+    fn metodo_texto(&mut self, metodo: &str, args: &[ast::Expressao]) -> Result<Valor, String> {
+        let (instrucao, n_args, tipo) = match metodo {
+            "comprimento" => (
+                "call int64 PorDoSol.Runtime::TamanhoTexto(string)",
+                0,
+                TipoIl::Inteiro,
+            ),
+            "ParaMaiusculo" => (
+                "callvirt instance string [mscorlib]System.String::ToUpperInvariant()",
+                0,
+                TipoIl::Texto,
+            ),
+            "ParaMinusculo" => (
+                "callvirt instance string [mscorlib]System.String::ToLowerInvariant()",
+                0,
+                TipoIl::Texto,
+            ),
+            "Aparar" => (
+                "callvirt instance string [mscorlib]System.String::Trim()",
+                0,
+                TipoIl::Texto,
+            ),
+            "Contem" => (
+                "callvirt instance bool [mscorlib]System.String::Contains(string)",
+                1,
+                TipoIl::Booleano,
+            ),
+            "Substituir" => (
+                "call string PorDoSol.Runtime::Substituir(string, string, string)",
+                2,
+                TipoIl::Texto,
+            ),
+            _ => return Err(erro_metodo_texto(metodo)),
+        };
+        // Como no alvo C: só os dois primeiros argumentos são avaliados
+        self.textos_argumentos(&args[..args.len().min(2)], n_args)?;
+        self.emitir(instrucao);
+        Ok(Valor::novo(tipo))
+    }
+
+    /// Avalia os argumentos como textos e deixa exatamente `n` na pilha: os excedentes são
+    /// descartados e os que faltam viram `""`.
+    fn textos_argumentos(&mut self, args: &[ast::Expressao], n: usize) -> Result<(), String> {
+        for arg in args {
+            let valor = self.expr(arg)?;
+            self.texto_de(&valor);
+        }
+        for _ in n..args.len() {
+            self.emitir("pop");
+        }
+        for _ in args.len()..n {
+            self.emitir("ldstr \"\"");
+        }
+        Ok(())
+    }
+
+    /// Funções nativas do interpretador (`runtime::nativos`) disponíveis no alvo cil.
+    fn nativo(&mut self, chave: &str, args: &[ast::Expressao]) -> Result<Valor, String> {
+        match chave {
+            "Console::EscreverLinha" | "Console::Escrever" => {
+                if args.is_empty() {
+                    self.emitir("ldstr \"\"");
+                }
+                for (i, arg) in args.iter().enumerate() {
+                    let valor = self.expr(arg)?;
+                    self.texto_de(&valor);
+                    if i > 0 {
+                        self.emitir(CONCATENAR);
+                    }
+                }
+                if chave == "Console::EscreverLinha" {
+                    self.emitir("call void PorDoSol.Runtime::Imprimir(string)");
+                } else {
+                    self.emitir("call void PorDoSol.Runtime::Escrever(string)");
+                }
+                Ok(Valor::novo(TipoIl::Vazio))
+            }
+            "Console::LerLinha" => {
+                for arg in args {
+                    let valor = self.expr(arg)?;
+                    self.descartar(&valor.tipo);
+                }
+                self.emitir("call string PorDoSol.Runtime::LerLinha()");
+                Ok(Valor::nao_nulo(TipoIl::Texto))
+            }
+            "Arquivo::LerTexto" | "LerArquivoAssíncrono" | "Arquivo::LerTextoAssíncrono" => {
+                self.textos_argumentos(args, 1)?;
+                if chave == "Arquivo::LerTexto" {
+                    self.emitir(ldstr(chave));
+                    self.emitir("ldnull");
+                } else {
+                    self.emitir(ldstr("LerArquivoAssíncrono"));
+                    self.emitir(ldstr(
+                        "LerArquivoAssíncrono: não foi possível ler o arquivo",
+                    ));
+                }
+                self.posicao_il();
+                self.emitir(
+                    "call string PorDoSol.Runtime::LerArquivo(string, string, string, string, int32, int32)",
+                );
+                Ok(Valor::nao_nulo(TipoIl::Texto))
+            }
+            "Arquivo::EscreverTexto"
+            | "Arquivo::AdicionarTexto"
+            | "EscreverArquivoAssíncrono"
+            | "Arquivo::EscreverTextoAssíncrono" => {
+                self.textos_argumentos(args, 2)?;
+                let assincrono = chave.ends_with("Assíncrono");
+                let adicionar = chave == "Arquivo::AdicionarTexto";
+                self.emitir(format!("ldc.i4.{}", adicionar as i32));
+                if assincrono {
+                    self.emitir(ldstr("EscreverArquivoAssíncrono"));
+                    self.emitir(ldstr(
+                        "EscreverArquivoAssíncrono: não foi possível gravar o arquivo",
+                    ));
+                } else {
+                    self.emitir(ldstr(chave));
+                    self.emitir("ldnull");
+                }
+                self.posicao_il();
+                self.emitir(
+                    "call void PorDoSol.Runtime::EscreverArquivo(string, string, bool, string, string, string, int32, int32)",
+                );
+                if assincrono {
+                    // Como no interpretador, a escrita concluída resulta em nulo
+                    self.emitir("ldnull");
+                    return Ok(Valor::novo(TipoIl::Objeto));
+                }
+                Ok(Valor::novo(TipoIl::Vazio))
+            }
+            "Arquivo::Existe" | "VerificarArquivoAssíncrono" => {
+                self.textos_argumentos(args, 1)?;
+                self.emitir("call bool PorDoSol.Runtime::ArquivoExiste(string)");
+                Ok(Valor::novo(TipoIl::Booleano))
+            }
+            "Arquivo::Excluir" => {
+                self.textos_argumentos(args, 1)?;
+                self.posicao_il();
+                self.emitir(
+                    "call void PorDoSol.Runtime::ExcluirArquivo(string, string, int32, int32)",
+                );
+                Ok(Valor::novo(TipoIl::Vazio))
+            }
+            _ if chave.contains("::") || chave.ends_with("Assíncrono") => Err(format!(
+                "Função nativa '{}' não é suportada no alvo cil",
+                chave
+            )),
+            _ => Err(format!("Função '{}' não encontrada", chave)),
+        }
+    }
+
+    fn logica(
+        &mut self,
+        op: &ast::OperadorLogico,
+        esq: &ast::Expressao,
+        dir: &ast::Expressao,
+    ) -> Result<Valor, String> {
+        let esq = self.expr(esq)?;
+        let (codigo, dir) = self.capturar(|g| g.expr(dir))?;
+        for valor in [&esq, &dir] {
+            if valor.tipo != TipoIl::Booleano {
+                return Err(format!(
+                    "Condição deve ser booleana, obtido {}",
+                    self.nome_tipo(&valor.tipo)
+                ));
+            }
+        }
+        // O lado direito só é avaliado se o resultado ainda não estiver decidido
+        let (desvio, decidido, valor_decidido) = match op {
+            ast::OperadorLogico::E => ("brfalse", self.rotulo("falso"), "ldc.i4.0"),
+            ast::OperadorLogico::Ou => ("brtrue", self.rotulo("verdadeiro"), "ldc.i4.1"),
+        };
+        let fim = self.rotulo("fim");
+        self.emitir(format!("{} {}", desvio, decidido));
+        self.codigo.extend(codigo);
+        self.emitir(format!("br {}", fim));
+        self.emitir(format!("{}:", decidido));
+        self.emitir(valor_decidido);
+        self.emitir(format!("{}:", fim));
+        Ok(Valor::novo(TipoIl::Booleano))
+    }
+
+    fn aritmetica(
+        &mut self,
+        op: &ast::OperadorAritmetico,
+        esq: Valor,
+        codigo_dir: Vec<String>,
+        dir: Valor,
+    ) -> Result<Valor, String> {
+        // Texto em qualquer lado de `+` concatena
+        if *op == ast::OperadorAritmetico::Soma
+            && (esq.tipo == TipoIl::Texto || dir.tipo == TipoIl::Texto)
+        {
+            self.texto_de(&esq);
+            self.codigo.extend(codigo_dir);
+            self.texto_de(&dir);
+            self.emitir(CONCATENAR);
+            return Ok(Valor::nao_nulo(TipoIl::Texto));
+        }
+        if !esq.tipo.numerico() || !dir.tipo.numerico() {
+            return Err(format!(
+                "Tipos incompatíveis para {}: {} e {}",
+                match op {
+                    ast::OperadorAritmetico::Soma => "soma",
+                    ast::OperadorAritmetico::Subtracao => "subtração",
+                    ast::OperadorAritmetico::Multiplicacao => "multiplicação",
+                    ast::OperadorAritmetico::Divisao => "divisão",
+                    ast::OperadorAritmetico::Modulo => "módulo",
+                },
+                self.nome_tipo(&esq.tipo),
+                self.nome_tipo(&dir.tipo)
+            ));
+        }
+        // Inteiro com flutuante resulta em flutuante; qualquer duplo promove para duplo
+        let tipo = match (&esq.tipo, &dir.tipo) {
+            (TipoIl::Inteiro, TipoIl::Inteiro) => TipoIl::Inteiro,
+            (TipoIl::Duplo, _) | (_, TipoIl::Duplo) => TipoIl::Duplo,
+            _ => TipoIl::Flutuante,
+        };
+        // O resto de `flutuante` é calculado em `duplo` (exato) e convertido de volta
+        let resto_flutuante = tipo == TipoIl::Flutuante && *op == ast::OperadorAritmetico::Modulo;
+        let operandos = if resto_flutuante {
+            TipoIl::Duplo
+        } else {
+            tipo.clone()
+        };
+        self.converter(&esq.tipo, &operandos);
+        self.codigo.extend(codigo_dir);
+        self.converter(&dir.tipo, &operandos);
+        match op {
+            ast::OperadorAritmetico::Soma => self.emitir("add"),
+            ast::OperadorAritmetico::Subtracao => self.emitir("sub"),
+            ast::OperadorAritmetico::Multiplicacao => self.emitir("mul"),
+            ast::OperadorAritmetico::Divisao => {
+                if self.verificacoes {
+                    self.posicao_il();
+                    self.emitir(match operandos {
+                        TipoIl::Inteiro => {
+                            "call int64 PorDoSol.Runtime::Dividir(int64, int64, string, int32, int32)"
+                        }
+                        TipoIl::Flutuante => {
+                            "call float32 PorDoSol.Runtime::DividirFlutuante(float32, float32, string, int32, int32)"
+                        }
+                        _ => {
+                            "call float64 PorDoSol.Runtime::DividirDuplo(float64, float64, string, int32, int32)"
+                        }
+                    });
+                } else {
+                    self.emitir("div");
+                }
+            }
+            ast::OperadorAritmetico::Modulo => {
+                if self.verificacoes {
+                    self.posicao_il();
+                    self.emitir(match operandos {
+                        TipoIl::Inteiro => {
+                            "call int64 PorDoSol.Runtime::Resto(int64, int64, string, int32, int32)"
+                        }
+                        _ => {
+                            "call float64 PorDoSol.Runtime::RestoDuplo(float64, float64, string, int32, int32)"
+                        }
+                    });
+                } else {
+                    self.emitir("rem");
+                }
+            }
+        }
+        if resto_flutuante {
+            self.emitir("conv.r4");
+        }
+        Ok(Valor::novo(tipo))
+    }
+
+    /// Comparação de dois números (ou booleanos) do mesmo tipo na pilha.
+    fn comparar_numeros(&mut self, op: &ast::OperadorComparacao, real: bool) {
+        use ast::OperadorComparacao as Op;
+        // `a <= b` é `!(a > b)`; nos reais, `cgt.un` dá verdadeiro com NaN e a negação, falso
+        let (instrucao, negar) = match op {
+            Op::Igual => ("ceq", false),
+            Op::Diferente => ("ceq", true),
+            Op::Menor => ("clt", false),
+            Op::MaiorQue => ("cgt", false),
+            Op::MenorIgual if real => ("cgt.un", true),
+            Op::MenorIgual => ("cgt", true),
+            Op::MaiorIgual if real => ("clt.un", true),
+            Op::MaiorIgual => ("clt", true),
+        };
+        self.emitir(instrucao);
+        if negar {
+            self.emitir("ldc.i4.0");
+            self.emitir("ceq");
+        }
+    }
+
+    fn comparacao(
+        &mut self,
+        op: &ast::OperadorComparacao,
+        esq: Valor,
+        codigo_dir: Vec<String>,
+        dir: Valor,
+    ) -> Result<Valor, String> {
+        use ast::OperadorComparacao as Op;
+        let igualdade = matches!(op, Op::Igual | Op::Diferente);
+        let booleano = Valor::novo(TipoIl::Booleano);
+        let (a, b) = (esq.tipo.clone(), dir.tipo.clone());
+        match (&a, &b) {
+            (x, y) if x.numerico() && y.numerico() => {
+                if x == y {
+                    self.codigo.extend(codigo_dir);
+                    self.comparar_numeros(op, *x != TipoIl::Inteiro);
+                } else if igualdade {
+                    self.constante(op, &a, codigo_dir, &b);
+                } else {
+                    self.converter(&a, &TipoIl::Duplo);
+                    self.codigo.extend(codigo_dir);
+                    self.converter(&b, &TipoIl::Duplo);
+                    self.comparar_numeros(op, true);
+                }
+            }
+            (TipoIl::Booleano, TipoIl::Booleano) if igualdade => {
+                self.codigo.extend(codigo_dir);
+                self.comparar_numeros(op, false);
+            }
+            (TipoIl::Texto, TipoIl::Texto) => {
+                self.codigo.extend(codigo_dir);
+                if igualdade {
+                    self.emitir("call bool [mscorlib]System.String::op_Equality(string, string)");
+                    if *op == Op::Diferente {
+                        self.emitir("ldc.i4.0");
+                        self.emitir("ceq");
+                    }
+                } else {
+                    self.emitir("call int32 PorDoSol.Runtime::CompararTextos(string, string)");
+                    self.emitir("ldc.i4.0");
+                    self.comparar_numeros(op, false);
+                }
+            }
+            (TipoIl::Texto, TipoIl::Nulo) | (TipoIl::Nulo, TipoIl::Texto) if igualdade => {
+                self.codigo.extend(codigo_dir);
+                self.comparar_numeros(op, false);
+            }
+            (x, y) if igualdade && (x.dinamico() || y.dinamico()) => {
+                self.converter(&a, &TipoIl::Objeto);
+                self.codigo.extend(codigo_dir);
+                self.converter(&b, &TipoIl::Objeto);
+                self.emitir("call bool PorDoSol.Runtime::ObjetosIguais(object, object)");
+                if *op == Op::Diferente {
+                    self.emitir("ldc.i4.0");
+                    self.emitir("ceq");
+                }
+            }
+            (x, y) if igualdade && x.referencia() && y.referencia() => {
+                self.codigo.extend(codigo_dir);
+                self.comparar_numeros(op, false);
+            }
+            _ if igualdade => self.constante(op, &a, codigo_dir, &b),
+            (x, y) => {
+                return Err(format!(
+                    "Tipos incompatíveis para comparação: {} e {}",
+                    self.nome_tipo(x),
+                    self.nome_tipo(y)
+                ))
+            }
+        }
+        Ok(booleano)
+    }
+
+    /// Valores de tipos diferentes nunca são iguais (os operandos são avaliados e descartados).
+    fn constante(
+        &mut self,
+        op: &ast::OperadorComparacao,
+        esq: &TipoIl,
+        codigo_dir: Vec<String>,
+        dir: &TipoIl,
+    ) {
+        self.descartar(esq);
+        self.codigo.extend(codigo_dir);
+        self.descartar(dir);
+        let resultado = *op != ast::OperadorComparacao::Igual;
+        self.emitir(format!("ldc.i4.{}", resultado as i32));
+    }
+
+    /// Converte o valor no topo da pilha para o tipo esperado (promoções numéricas, caixas
+    /// para valores primitivos em `objeto` e conversões de referências).
+    fn converter(&mut self, de: &TipoIl, para: &TipoIl) {
+        if de == para {
+            return;
+        }
+        if *para == TipoIl::Vazio {
+            self.descartar(de);
+            return;
+        }
+        match (de, para) {
+            // Chamada de função sem retorno usada como valor: nulo
+            (TipoIl::Vazio, _) => self.padrao(para, false),
+            (TipoIl::Inteiro | TipoIl::Duplo, TipoIl::Flutuante) => self.emitir("conv.r4"),
+            (TipoIl::Inteiro | TipoIl::Flutuante, TipoIl::Duplo) => self.emitir("conv.r8"),
+            (TipoIl::Flutuante, TipoIl::Inteiro) => {
+                self.emitir("conv.r8");
+                self.emitir("call int64 PorDoSol.Runtime::Truncar(float64)");
+            }
+            (TipoIl::Duplo, TipoIl::Inteiro) => {
+                self.emitir("call int64 PorDoSol.Runtime::Truncar(float64)")
+            }
+            (x, TipoIl::Objeto | TipoIl::Interface(_)) if x.primitivo() => {
+                self.emitir(format!("box {}", x.token()));
+                if let TipoIl::Interface(_) = para {
+                    self.emitir(format!("castclass {}", para.token()));
+                }
+            }
+            (TipoIl::Objeto | TipoIl::Interface(_), y) if y.primitivo() || *y == TipoIl::Texto => {
+                let funcao = match y {
+                    TipoIl::Inteiro => "InteiroDe",
+                    TipoIl::Flutuante => "FlutuanteDe",
+                    TipoIl::Duplo => "DuploDe",
+                    TipoIl::Booleano => "BooleanoDe",
+                    _ => "TextoDe",
+                };
+                self.emitir(format!(
+                    "call {} PorDoSol.Runtime::{}(object)",
+                    y.il(),
+                    funcao
+                ));
+            }
+            (x, y)
+                if (x.referencia() || *x == TipoIl::Texto)
+                    && (y.referencia() || *y == TipoIl::Texto) =>
+            {
+                if !self.atribuivel(x, y) {
+                    self.emitir(format!("castclass {}", y.token()));
+                }
+            }
+            // Tipos que o verificador não deixa chegar aqui: o valor padrão do destino
+            _ => {
+                self.descartar(de);
+                self.padrao(para, false);
+            }
+        }
+    }
+
+    /// Converte o valor no topo da pilha no texto exibido por `imprima`.
+    fn texto_de(&mut self, valor: &Valor) {
+        match &valor.tipo {
+            TipoIl::Texto => {
+                if !valor.nao_nulo {
+                    self.emitir("call string PorDoSol.Runtime::Texto(string)");
+                }
+            }
+            TipoIl::Inteiro => self.emitir("call string PorDoSol.Runtime::Texto(int64)"),
+            TipoIl::Duplo => self.emitir("call string PorDoSol.Runtime::Texto(float64)"),
+            TipoIl::Flutuante => {
+                self.emitir("conv.r8");
+                self.emitir("call string PorDoSol.Runtime::Texto(float64)");
+            }
+            TipoIl::Booleano => self.emitir("call string PorDoSol.Runtime::Texto(bool)"),
+            TipoIl::Vazio => self.emitir("ldstr \"nulo\""),
+            _ => self.emitir("call string PorDoSol.Runtime::Texto(object)"),
+        }
+    }
+}
+
+/* ---------------------------------------------------------------------- */
+/* PILHA DE AVALIAÇÃO E VERIFICAÇÃO ESTRUTURAL                             */
+/* ---------------------------------------------------------------------- */
+
+fn opcode(instrucao: &str) -> &str {
+    instrucao.split_whitespace().next().unwrap_or("")
+}
+
+/// Rótulo de destino de um desvio.
+fn alvo_desvio(instrucao: &str) -> Option<&str> {
+    let opcode = opcode(instrucao);
+    let desvio = matches!(
+        opcode,
+        "br" | "br.s" | "brfalse" | "brfalse.s" | "brtrue" | "brtrue.s" | "leave" | "leave.s"
+    ) || ["beq", "bne", "blt", "bgt", "ble", "bge"]
+        .iter()
+        .any(|prefixo| opcode.starts_with(prefixo));
+    if desvio {
+        instrucao.split_whitespace().last()
+    } else {
+        None
+    }
+}
+
+/// Valores retirados e empilhados por `call`, `callvirt` e `newobj`, pela assinatura.
+fn efeito_chamada(instrucao: &str) -> Result<(usize, usize), String> {
+    let mut palavras = instrucao.split_whitespace();
+    let opcode = palavras.next().unwrap_or("");
+    let mut retorno = palavras.next().unwrap_or("");
+    let instancia = retorno == "instance";
+    if instancia {
+        retorno = palavras.next().unwrap_or("");
+    }
+    let invalida = || format!("chamada sem assinatura: {}", instrucao);
+    let separador = instrucao.find("::").ok_or_else(invalida)?;
+    let abre = instrucao[separador..]
+        .find('(')
+        .map(|i| i + separador)
+        .ok_or_else(invalida)?;
+    let fecha = instrucao.rfind(')').ok_or_else(invalida)?;
+    let lista = instrucao[abre + 1..fecha].trim();
+    let argumentos = if lista.is_empty() {
+        0
+    } else {
+        lista.matches(',').count() + 1
+    };
+    Ok(match opcode {
+        "newobj" => (argumentos, 1),
+        _ => (
+            argumentos + instancia as usize,
+            (retorno != "void") as usize,
+        ),
+    })
+}
+
+/// Valores retirados e empilhados por uma instrução.
+fn efeito(instrucao: &str) -> Result<(usize, usize), String> {
+    let opcode = opcode(instrucao);
+    let carrega = ["ldarg", "ldloc", "ldc."]
+        .iter()
+        .any(|prefixo| opcode.starts_with(prefixo));
+    Ok(match opcode {
+        "call" | "callvirt" | "newobj" => return efeito_chamada(instrucao),
+        _ if carrega => (0, 1),
+        "ldnull" | "ldstr" | "ldsfld" => (0, 1),
+        "dup" => (1, 2),
+        _ if opcode.starts_with("stloc") || opcode.starts_with("starg") => (1, 0),
+        "stsfld" | "pop" | "throw" | "brfalse" | "brfalse.s" | "brtrue" | "brtrue.s" => (1, 0),
+        "add" | "sub" | "mul" | "div" | "rem" | "ceq" | "clt" | "cgt" | "clt.un" | "cgt.un"
+        | "and" | "or" | "xor" => (2, 1),
+        _ if opcode.starts_with("conv.") => (1, 1),
+        "neg" | "not" | "box" | "unbox.any" | "castclass" | "isinst" | "newarr" | "ldlen"
+        | "ldfld" => (1, 1),
+        "stfld" => (2, 0),
+        _ if opcode.starts_with("stelem") => (3, 0),
+        _ if opcode.starts_with("ldelem") => (2, 1),
+        _ if alvo_desvio(instrucao).is_some() && opcode.starts_with('b') && opcode != "br" => {
+            (2, 0)
+        }
+        "br" | "br.s" | "leave" | "leave.s" | "ret" | "nop" => (0, 0),
+        outro => return Err(format!("instrução desconhecida: {}", outro)),
+    })
+}
+
+/// Profundidade máxima da pilha de avaliação de um corpo de método (para `.maxstack`),
+/// conferindo que cada rótulo é alcançado sempre com a mesma profundidade.
+fn profundidade_maxima<'s>(instrucoes: impl IntoIterator<Item = &'s str>) -> Result<usize, String> {
+    let mut rotulos: HashMap<&str, usize> = HashMap::new();
+    let mut atual = Some(0usize);
+    let mut maximo = 0;
+    for instrucao in instrucoes {
+        if let Some(rotulo) = instrucao.strip_suffix(':') {
+            let profundidade = match (atual, rotulos.get(rotulo).copied()) {
+                (Some(a), Some(r)) if a != r => {
+                    return Err(format!(
+                        "pilha com {} e {} valores no rótulo {}",
+                        a, r, rotulo
+                    ))
+                }
+                (Some(a), _) => a,
+                (None, Some(r)) => r,
+                // Código só alcançado por desvios para trás
+                (None, None) => 0,
+            };
+            rotulos.insert(rotulo, profundidade);
+            atual = Some(profundidade);
+            continue;
+        }
+        if instrucao.starts_with("catch ") {
+            // O bloco catch começa com a exceção na pilha
+            atual = Some(1);
+            maximo = maximo.max(1);
+            continue;
+        }
+        let profundidade = atual.unwrap_or(0);
+        let (retira, empilha) = efeito(instrucao)?;
+        if retira > profundidade {
+            return Err(format!("pilha vazia em '{}'", instrucao));
+        }
+        let nova = profundidade - retira + empilha;
+        maximo = maximo.max(nova);
+        let opcode = opcode(instrucao);
+        if let Some(alvo) = alvo_desvio(instrucao) {
+            let destino = if opcode.starts_with("leave") { 0 } else { nova };
+            match rotulos.get(alvo) {
+                Some(&r) if r != destino => {
+                    return Err(format!(
+                        "pilha com {} e {} valores no rótulo {}",
+                        destino, r, alvo
+                    ))
+                }
+                _ => {
+                    rotulos.insert(alvo, destino);
+                }
+            }
+        }
+        atual = match opcode {
+            "br" | "br.s" | "leave" | "leave.s" | "ret" | "throw" => None,
+            _ => Some(nova),
+        };
+    }
+    Ok(maximo)
+}
+
+/// Nome sem as aspas (`'Espaco'.'Classe'` vira `Espaco.Classe`).
+fn sem_aspas(nome: &str) -> String {
+    nome.replace('\'', "")
+}
+
+/// Nome do membro declarado em uma linha `.method` ou `.field`.
+fn membro_declarado(linha: &str) -> String {
+    let antes = match linha.find('(') {
+        Some(i) if linha.starts_with(".method") => &linha[..i],
+        _ => linha,
+    };
+    sem_aspas(antes.split_whitespace().last().unwrap_or(""))
+}
+
+/// Itens de uma lista separada por vírgulas (parâmetros, locais).
+fn itens(lista: &str) -> Vec<String> {
+    let lista = lista.trim();
+    if lista.is_empty() {
+        return Vec::new();
+    }
+    lista
+        .split(',')
+        .map(|item| sem_aspas(item.split_whitespace().last().unwrap_or("")))
+        .collect()
+}
+
+/// Referência `Classe::membro` de uma instrução ou diretiva, fora do mscorlib.
+fn referencia_membro(instrucao: &str) -> Option<String> {
+    let separador = instrucao.find("::")?;
+    let classe = instrucao[..separador].split_whitespace().last()?;
+    if classe.starts_with('[') {
+        return None;
+    }
+    let resto = &instrucao[separador + 2..];
+    let fim = resto.find(['(', ' ']).unwrap_or(resto.len());
+    Some(format!(
+        "{}::{}",
+        sem_aspas(classe),
+        sem_aspas(&resto[..fim])
+    ))
+}
+
+/// Índice ou nome do operando de `ldarg`/`starg`/`ldloc`/`stloc` (`ldloc.2`, `ldarg.s nome`).
+fn operando_slot(instrucao: &str) -> Option<String> {
+    let opcode = opcode(instrucao);
+    let sufixo = opcode.rsplit('.').next().unwrap_or("");
+    if sufixo.chars().all(|c| c.is_ascii_digit()) && opcode.contains('.') {
+        return Some(sufixo.to_string());
+    }
+    instrucao.split_whitespace().nth(1).map(sem_aspas)
+}
+
+/// Confere a estrutura de um assembly em IL: chaves balanceadas, um único `.entrypoint` e, em
+/// cada método, `.maxstack` suficiente, pilha consistente nos rótulos, desvios para rótulos
+/// existentes, parâmetros e locais existentes e referências a membros declarados no arquivo.
+/// Não substitui o `ilasm` (tipos e assinaturas não são conferidos).
+pub fn verificar(il: &str) -> Result<(), String> {
+    let linhas: Vec<&str> = il
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with("//"))
+        .collect();
+
+    // Classes e membros declarados
+    let mut declarados: HashSet<String> = HashSet::new();
+    let mut blocos: Vec<Option<String>> = Vec::new();
+    let mut classe_pendente: Option<String> = None;
+    let mut pontos_entrada = 0;
+    for linha in &linhas {
+        if linha.starts_with("ldstr") {
+            continue;
+        }
+        if linha.starts_with(".class ") {
+            classe_pendente = linha.split_whitespace().last().map(sem_aspas);
+        } else if linha.starts_with(".method ") || linha.starts_with(".field ") {
+            if let Some(classe) = blocos.iter().rev().find_map(|c| c.clone()) {
+                declarados.insert(format!("{}::{}", classe, membro_declarado(linha)));
+            }
+        } else if *linha == ".entrypoint" {
+            pontos_entrada += 1;
+        }
+        for ch in linha.chars() {
+            match ch {
+                '{' => blocos.push(classe_pendente.take()),
+                '}' => {
+                    blocos
+                        .pop()
+                        .ok_or_else(|| "chave '}' sem '{' correspondente".to_string())?;
+                }
+                _ => {}
+            }
+        }
+    }
+    if !blocos.is_empty() {
+        return Err(format!("{} chave(s) '{{' sem '}}'", blocos.len()));
+    }
+    if pontos_entrada != 1 {
+        return Err(format!(
+            "o assembly deve ter um .entrypoint (encontrados: {})",
+            pontos_entrada
+        ));
+    }
+
+    // Corpos dos métodos
+    let mut i = 0;
+    while i < linhas.len() {
+        let cabecalho = linhas[i];
+        i += 1;
+        if !cabecalho.starts_with(".method ") {
+            continue;
+        }
+        let mut nivel = 0;
+        let mut corpo = Vec::new();
+        while i < linhas.len() {
+            let linha = linhas[i];
+            i += 1;
+            match linha {
+                "{" => nivel += 1,
+                "}" => nivel -= 1,
+                _ => {}
+            }
+            if nivel == 0 {
+                break;
+            }
+            if nivel > 1 || !matches!(linha, "{") {
+                corpo.push(linha);
+            }
+        }
+        verificar_metodo(cabecalho, &corpo, &declarados)
+            .map_err(|e| format!("{}: {}", membro_declarado(cabecalho), e))?;
+    }
+    Ok(())
+}
+
+fn verificar_metodo(
+    cabecalho: &str,
+    corpo: &[&str],
+    declarados: &HashSet<String>,
+) -> Result<(), String> {
+    if cabecalho.contains(" abstract ") {
+        return if corpo.is_empty() {
+            Ok(())
+        } else {
+            Err("método abstrato com corpo".to_string())
+        };
+    }
+    let maxstack: usize = corpo
+        .iter()
+        .find_map(|l| l.strip_prefix(".maxstack "))
+        .ok_or_else(|| "sem .maxstack".to_string())?
+        .trim()
+        .parse()
+        .map_err(|_| ".maxstack inválido".to_string())?;
+    let locais = corpo
+        .iter()
+        .find_map(|l| l.strip_prefix(".locals init ("))
+        .map(|l| itens(l.trim_end_matches(')')))
+        .unwrap_or_default();
+    let abre = cabecalho.find('(').ok_or("cabeçalho sem parâmetros")?;
+    let fecha = cabecalho.rfind(')').ok_or("cabeçalho sem parâmetros")?;
+    let mut parametros = itens(&cabecalho[abre + 1..fecha]);
+    if cabecalho.contains(" instance ") {
+        parametros.insert(0, "este".to_string());
+    }
+
+    let instrucoes: Vec<&str> = corpo
+        .iter()
+        .copied()
+        .filter(|l| !l.starts_with('.') && !matches!(*l, "{" | "}"))
+        .collect();
+    let profundidade = profundidade_maxima(instrucoes.iter().copied())?;
+    if profundidade > maxstack {
+        return Err(format!(
+            ".maxstack {} menor que a pilha usada ({})",
+            maxstack, profundidade
+        ));
+    }
+    let rotulos: HashSet<&str> = instrucoes
+        .iter()
+        .filter_map(|l| l.strip_suffix(':'))
+        .collect();
+    for instrucao in &instrucoes {
+        if let Some(alvo) = alvo_desvio(instrucao) {
+            if !rotulos.contains(alvo) {
+                return Err(format!("rótulo '{}' não definido", alvo));
+            }
+        }
+        let opcode = opcode(instrucao);
+        let slots = if opcode.starts_with("ldarg") || opcode.starts_with("starg") {
+            Some(&parametros)
+        } else if opcode.starts_with("ldloc") || opcode.starts_with("stloc") {
+            Some(&locais)
+        } else {
+            None
+        };
+        if let (Some(slots), Some(operando)) = (slots, operando_slot(instrucao)) {
+            let existe = match operando.parse::<usize>() {
+                Ok(indice) => indice < slots.len(),
+                Err(_) => slots.contains(&operando),
+            };
+            if !existe {
+                return Err(format!("'{}' fora dos parâmetros/locais", instrucao));
+            }
+        }
+        let referencia = matches!(
+            opcode,
+            "call" | "callvirt" | "newobj" | "ldfld" | "stfld" | "ldsfld" | "stsfld"
+        );
+        if referencia {
+            if let Some(membro) = referencia_membro(instrucao) {
+                if !declarados.contains(&membro) {
+                    return Err(format!("'{}' não declarado", membro));
+                }
+            }
+        }
+    }
+    for diretiva in corpo.iter().filter(|l| l.starts_with(".override ")) {
+        if let Some(membro) = referencia_membro(diretiva) {
+            if !declarados.contains(&membro) {
+                return Err(format!("'{}' não declarado", membro));
             }
         }
-        parts
     }
+    Ok(())
 }
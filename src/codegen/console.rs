@@ -1,83 +1,3100 @@
+//! Gerador de C# para o alvo `--target=console` (projeto de console .NET).
+//!
+//! O programa inteiro vira código C# legível, compilado com `dotnet build`/`dotnet run`:
+//!
+//! - `espaco` e módulos viram `namespace`; classes aninhadas ficam no mesmo namespace da classe
+//!   que as contém, como nos outros alvos.
+//! - Classes, interfaces, enumerações e parâmetros genéricos viram os equivalentes do C#.
+//!   Métodos redefiníveis, abstratos ou redefinidos em alguma subclasse são
+//!   `virtual`/`abstract`/`override`, pelas mesmas regras de vtable dos alvos C, wasm e cil.
+//! - Propriedades com corpo em `obter`/`definir` viram `get`/`set` (`valor` vira `value`).
+//! - Funções ficam na classe estática `Programa` do namespace onde foram declaradas.
+//! - `assíncrona`/`aguarde` viram `async Task<T>`/`await`; fora de funções assíncronas o
+//!   `aguarde` espera a tarefa com `GetAwaiter().GetResult()`.
+//! - `inteiro` é `long`, `flutuante` é `float`, `duplo` é `double`, `decimal` é `decimal`;
+//!   `Lista<T>` da biblioteca padrão é `List<T>` e os arrays da linguagem são arrays do C#.
+//! - Funções nativas (Console, Arquivo, Diretorio) e métodos de texto e de `Lista` viram
+//!   chamadas da BCL; receptores do tipo `objeto` são chamados com `dynamic`.
+//!
+//! A exibição dos valores (`imprima`, concatenação e interpolação) e as mensagens dos erros de
+//! execução acompanham o interpretador pelo runtime `PorDoSol.cs` (`src/runtime/cs_runtime.cs`),
+//! gravado no projeto junto do `Program.cs`.
+
+use super::tabelas::{
+    aceita_argumentos, chave_nativa, erro_metodo_texto, nome_do_tipo, qualificar, AlvoEstatico,
+    Escopo, Membro, Tabelas, TipoNomeado,
+};
 use crate::ast;
+use crate::type_checker::VerificadorTipos;
+use std::collections::{HashMap, HashSet};
+
+/// Runtime em C# copiado para o projeto gerado (`PorDoSol.cs`).
+const RUNTIME: &str = include_str!("../runtime/cs_runtime.cs");
+
+/// Namespace do runtime e do ponto de entrada.
+const NAMESPACE_RUNTIME: &str = "PorDoSol";
+
+/// Palavras reservadas do C#: identificadores da linguagem com esses nomes ganham `@`.
+const PALAVRAS_RESERVADAS: &[&str] = &[
+    "abstract",
+    "as",
+    "base",
+    "bool",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "checked",
+    "class",
+    "const",
+    "continue",
+    "decimal",
+    "default",
+    "delegate",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "event",
+    "explicit",
+    "extern",
+    "false",
+    "finally",
+    "fixed",
+    "float",
+    "for",
+    "foreach",
+    "goto",
+    "if",
+    "implicit",
+    "in",
+    "int",
+    "interface",
+    "internal",
+    "is",
+    "lock",
+    "long",
+    "namespace",
+    "new",
+    "null",
+    "object",
+    "operator",
+    "out",
+    "override",
+    "params",
+    "private",
+    "protected",
+    "public",
+    "readonly",
+    "ref",
+    "return",
+    "sbyte",
+    "sealed",
+    "short",
+    "sizeof",
+    "stackalloc",
+    "static",
+    "string",
+    "struct",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "uint",
+    "ulong",
+    "unchecked",
+    "unsafe",
+    "ushort",
+    "using",
+    "virtual",
+    "void",
+    "volatile",
+    "while",
+];
+
+// Precedência dos operadores do C#, da mais fraca para a mais forte
+const OU: u8 = 4;
+const E: u8 = 5;
+const IGUALDADE: u8 = 9;
+const RELACIONAL: u8 = 10;
+const ADITIVA: u8 = 12;
+const MULTIPLICATIVA: u8 = 13;
+const UNARIA: u8 = 14;
+const PRIMARIA: u8 = 15;
+
+/// Tipo de um valor no código C# gerado.
+#[derive(Debug, Clone, PartialEq)]
+enum TipoCs {
+    Inteiro,
+    Booleano,
+    Flutuante,
+    Duplo,
+    Decimal,
+    Texto,
+    Vazio,
+    /// Array da linguagem (`T[]`).
+    Array(Box<TipoCs>),
+    /// `Lista<T>` da biblioteca padrão (`List<T>`).
+    Lista(Box<TipoCs>),
+    Classe(String, Vec<TipoCs>),
+    Interface(String, Vec<TipoCs>),
+    Enum(String),
+    /// Parâmetro de tipo (`T`).
+    Generico(String),
+    Tarefa(Box<TipoCs>),
+    Objeto,
+    Nulo,
+}
+
+impl TipoCs {
+    fn numerico(&self) -> bool {
+        matches!(
+            self,
+            TipoCs::Inteiro | TipoCs::Flutuante | TipoCs::Duplo | TipoCs::Decimal
+        )
+    }
+
+    /// Tipos de valor do C# (não aceitam `null`).
+    fn valor(&self) -> bool {
+        self.numerico() || matches!(self, TipoCs::Booleano | TipoCs::Enum(_))
+    }
+
+    /// Valores cujo tipo só é conhecido em tempo de execução.
+    fn dinamico(&self) -> bool {
+        matches!(self, TipoCs::Objeto | TipoCs::Generico(_))
+    }
+}
+
+/// Expressão C# gerada: código, tipo e precedência do operador mais externo.
+#[derive(Debug, Clone)]
+struct Cs {
+    codigo: String,
+    tipo: TipoCs,
+    prec: u8,
+    /// Pode ser usada como comando (chamada, `new`, `await`).
+    instrucao: bool,
+}
+
+impl Cs {
+    fn novo(codigo: impl Into<String>, tipo: TipoCs, prec: u8) -> Self {
+        Self {
+            codigo: codigo.into(),
+            tipo,
+            prec,
+            instrucao: false,
+        }
+    }
+
+    fn primaria(codigo: impl Into<String>, tipo: TipoCs) -> Self {
+        Self::novo(codigo, tipo, PRIMARIA)
+    }
+
+    fn chamada(codigo: impl Into<String>, tipo: TipoCs) -> Self {
+        Self {
+            instrucao: true,
+            ..Self::primaria(codigo, tipo)
+        }
+    }
+
+    /// Código do operando, entre parênteses se a precedência for menor que `minimo`.
+    fn operando(&self, minimo: u8) -> String {
+        if self.prec < minimo {
+            format!("({})", self.codigo)
+        } else {
+            self.codigo.clone()
+        }
+    }
+
+    fn literal_inteiro(&self) -> bool {
+        let digitos = self.codigo.strip_prefix('-').unwrap_or(&self.codigo);
+        self.tipo == TipoCs::Inteiro
+            && !digitos.is_empty()
+            && digitos.bytes().all(|b| b.is_ascii_digit())
+    }
+}
+
+/// Identificador C# para um nome da linguagem (letras acentuadas são válidas no C#).
+fn identificador(nome: &str) -> String {
+    if PALAVRAS_RESERVADAS.contains(&nome) {
+        format!("@{}", nome)
+    } else {
+        nome.to_string()
+    }
+}
+
+/// Literal de texto do C#. Os textos da linguagem não têm sequências de escape.
+fn literal_texto(texto: &str) -> String {
+    format!("\"{}\"", escapar(texto, false))
+}
+
+fn escapar(texto: &str, interpolado: bool) -> String {
+    let mut saida = String::with_capacity(texto.len());
+    for ch in texto.chars() {
+        match ch {
+            '\\' => saida.push_str("\\\\"),
+            '"' => saida.push_str("\\\""),
+            '\n' => saida.push_str("\\n"),
+            '\r' => saida.push_str("\\r"),
+            '\t' => saida.push_str("\\t"),
+            '{' if interpolado => saida.push_str("{{"),
+            '}' if interpolado => saida.push_str("}}"),
+            c if c.is_control() => saida.push_str(&format!("\\u{:04x}", c as u32)),
+            c => saida.push(c),
+        }
+    }
+    saida
+}
+
+/// Separa `Espaco.Nome` em namespace e nome.
+fn dividir(fqn: &str) -> (&str, &str) {
+    match fqn.rfind('.') {
+        Some(i) => (&fqn[..i], &fqn[i + 1..]),
+        None => ("", fqn),
+    }
+}
+
+fn externo(func: &ast::DeclaracaoFuncao) -> Option<(&str, &str)> {
+    let atributo = func.attributes.iter().find(|a| a.name == "Externo")?;
+    match atributo.arguments.as_slice() {
+        [ast::Expressao::Texto(biblioteca), ast::Expressao::Texto(simbolo)] => {
+            Some((biblioteca, simbolo))
+        }
+        _ => None,
+    }
+}
+
+fn acesso(modificador: &ast::ModificadorAcesso) -> &'static str {
+    match modificador {
+        ast::ModificadorAcesso::Publico => "public",
+        ast::ModificadorAcesso::Privado => "private",
+        ast::ModificadorAcesso::Protegido => "protected",
+    }
+}
+
+/// O último comando (ignorando marcadores de posição) é um `retorne`.
+fn termina_com_retorno(corpo: &[ast::Comando]) -> bool {
+    matches!(
+        corpo
+            .iter()
+            .rfind(|c| !matches!(c, ast::Comando::Posicao(_))),
+        Some(ast::Comando::Retorne(_))
+    )
+}
+
+/// Arquivo `.csproj` do projeto de console gerado.
+pub fn projeto(nome: &str) -> String {
+    format!(
+        r#"<Project Sdk="Microsoft.NET.Sdk">
+
+  <PropertyGroup>
+    <OutputType>Exe</OutputType>
+    <TargetFramework>net8.0</TargetFramework>
+    <AssemblyName>{nome}</AssemblyName>
+    <RootNamespace>{nome}</RootNamespace>
+    <ImplicitUsings>disable</ImplicitUsings>
+    <Nullable>disable</Nullable>
+    <InvariantGlobalization>true</InvariantGlobalization>
+    <NoWarn>$(NoWarn);CS0162;CS0168;CS0219;CS0472;CS1998;CS4014</NoWarn>
+  </PropertyGroup>
+
+</Project>
+"#,
+        nome = nome
+    )
+}
+
+/// Runtime em C# (`PorDoSol.cs`) usado pelo código gerado.
+pub fn runtime() -> &'static str {
+    RUNTIME
+}
 
 /// O gerador de código para o alvo Console Application em C#.
 pub struct ConsoleGenerator<'a> {
     programa: &'a ast::Programa,
+    /// Classes, interfaces, funções e enumerações do programa, com os nomes resolvidos pelo
+    /// verificador.
+    tabelas: Tabelas<'a>,
+    /// Classe -> entradas da vtable (método, classe que introduz a entrada), herdadas primeiro.
+    vtables: HashMap<String, Vec<(String, String)>>,
+    // Estado do código em geração
+    /// Namespace da linguagem usado para resolver nomes.
+    namespace: String,
+    /// Namespace C# onde o código está sendo escrito.
+    local: String,
+    classe_atual: Option<String>,
+    /// O código está na classe `Programa` do namespace `local`.
+    em_programa: bool,
+    tem_este: bool,
+    genericos: Vec<String>,
+    escopos: Vec<HashMap<String, (String, TipoCs)>>,
+    nomes_usados: HashSet<String>,
+    retorno: TipoCs,
+    assincrono: bool,
+    saida: String,
+    nivel: usize,
+}
+
+impl<'a> Escopo<'a, (String, TipoCs)> for ConsoleGenerator<'a> {
+    fn tabelas(&self) -> &Tabelas<'a> {
+        &self.tabelas
+    }
+
+    fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    fn classe_atual(&self) -> Option<&str> {
+        self.classe_atual.as_deref()
+    }
+
+    fn tem_este(&self) -> bool {
+        self.tem_este
+    }
+
+    fn local(&self, nome: &str) -> Option<(String, TipoCs)> {
+        self.escopos.iter().rev().find_map(|e| e.get(nome).cloned())
+    }
+
+    /// Como o padrão, mas os membros de uma classe estática também são valores, mesmo sem
+    /// `estatico`.
+    fn alvo_estatico(&self, expr: &ast::Expressao) -> Option<AlvoEstatico> {
+        let membro_estatico = |nome: &str| {
+            self.classe_atual
+                .as_deref()
+                .is_some_and(|classe| self.dono_estatico(classe, nome).is_some())
+        };
+        self.tabelas.alvo_estatico(expr, &self.namespace, |nome| {
+            self.variavel(nome).is_some() || membro_estatico(nome)
+        })
+    }
 }
 
 impl<'a> ConsoleGenerator<'a> {
-    pub fn new(programa: &'a ast::Programa) -> Self {
-        Self { programa }
+    pub fn new(programa: &'a ast::Programa, verificador: &'a VerificadorTipos<'a>) -> Self {
+        // Os nomes C# dependem do namespace onde aparecem (`referencia_tipo`)
+        let fqn = |fqn: &str| fqn.to_string();
+        let mut gerador = Self {
+            programa,
+            tabelas: Tabelas::new(programa, verificador, fqn, fqn),
+            vtables: HashMap::new(),
+            namespace: String::new(),
+            local: String::new(),
+            classe_atual: None,
+            em_programa: false,
+            tem_este: false,
+            genericos: Vec::new(),
+            escopos: Vec::new(),
+            nomes_usados: HashSet::new(),
+            retorno: TipoCs::Vazio,
+            assincrono: false,
+            saida: String::new(),
+            nivel: 0,
+        };
+        gerador.vtables = gerador.tabelas.vtables();
+        gerador
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* TABELAS DO PROGRAMA                                                 */
+    /* ------------------------------------------------------------------ */
+
+    fn existe_tipo(&self, fqn: &str) -> bool {
+        self.tabelas.classes.contains_key(fqn)
+            || self.tabelas.interfaces.contains_key(fqn)
+            || self.tabelas.enums.contains_key(fqn)
+    }
+
+    /// Interfaces declaradas no cabeçalho da própria classe, com os argumentos de tipo.
+    fn interfaces_da_classe(&self, fqn: &str) -> Vec<(String, Vec<ast::Tipo>)> {
+        let classe = &self.tabelas.classes[fqn];
+        let mut interfaces: Vec<(String, Vec<ast::Tipo>)> = Vec::new();
+        let tipos = classe.decl.classe_pai.iter().chain(&classe.decl.interfaces);
+        for tipo in tipos {
+            let interface = nome_do_tipo(tipo)
+                .and_then(|nome| self.tabelas.resolver_interface(&classe.namespace, nome));
+            if let Some(interface) = interface {
+                if !interfaces.iter().any(|(i, _)| *i == interface) {
+                    let args = match tipo {
+                        ast::Tipo::Aplicado { args, .. } => args.clone(),
+                        _ => Vec::new(),
+                    };
+                    interfaces.push((interface, args));
+                }
+            }
+        }
+        interfaces
+    }
+
+    fn implementa(&self, fqn: &str, interface: &str) -> bool {
+        self.tabelas.hierarquia(fqn).iter().any(|c| {
+            self.interfaces_da_classe(&c.fqn)
+                .iter()
+                .any(|(i, _)| i == interface)
+        })
+    }
+
+    /// Campo ou propriedade de instância: classe que o declara e tipo declarado.
+    fn buscar_campo(&self, fqn: &str, nome: &str) -> Option<(String, &'a ast::Tipo)> {
+        let Membro { classe, tipo, .. } = self.tabelas.buscar_membro(fqn, nome, false)?;
+        Some((classe.fqn.clone(), tipo))
+    }
+
+    /// Membro estático `nome` da classe ou de um ancestral: classe que o declara e tipo. Numa
+    /// classe estática, todos os membros são estáticos.
+    fn dono_estatico(&self, fqn: &str, nome: &str) -> Option<(String, &'a ast::Tipo)> {
+        let estatica = self
+            .tabelas
+            .classes
+            .get(fqn)
+            .is_some_and(|c| c.decl.eh_estatica);
+        let Membro { classe, tipo, .. } = self
+            .tabelas
+            .buscar_membro(fqn, nome, true)
+            .or_else(|| estatica.then(|| self.tabelas.buscar_membro(fqn, nome, false))?)?;
+        Some((classe.fqn.clone(), tipo))
+    }
+
+    /// Classe que declara o campo `Nome` exibido por `imprima(objeto)`.
+    fn classe_do_nome(&self, fqn: &str) -> Option<String> {
+        self.tabelas
+            .hierarquia(fqn)
+            .into_iter()
+            .find(|c| {
+                c.decl
+                    .campos
+                    .iter()
+                    .any(|f| f.nome == "Nome" && !f.eh_estatica)
+                    || c.decl
+                        .propriedades
+                        .iter()
+                        .any(|p| p.nome == "Nome" && !p.eh_estatica)
+            })
+            .map(|c| c.fqn.clone())
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* VTABLES                                                             */
+    /* ------------------------------------------------------------------ */
+
+    /// Classe que introduz a entrada do método na vtable, se ele é virtual.
+    fn entrada_vtable(&self, classe: &str, metodo: &str) -> Option<&str> {
+        self.vtables
+            .get(classe)?
+            .iter()
+            .find(|(nome, _)| nome == metodo)
+            .map(|(_, introdutor)| introdutor.as_str())
+    }
+
+    /// Método de um ancestral com o mesmo nome e número de parâmetros (redefinido com `override`).
+    fn metodo_base(&self, fqn: &str, metodo: &ast::MetodoClasse) -> Option<&'a ast::MetodoClasse> {
+        self.tabelas
+            .hierarquia(fqn)
+            .into_iter()
+            .skip(1)
+            .find_map(|classe| {
+                classe.decl.metodos.iter().find(|m| {
+                    m.nome == metodo.nome
+                        && !m.eh_estatica
+                        && m.parametros.len() == metodo.parametros.len()
+                })
+            })
+    }
+
+    /// Argumentos dos parâmetros de tipo de `ancestral` vistos de `fqn` (por exemplo, `[texto]`
+    /// para `Base<T>` em `classe Deriv : Base<texto>`).
+    fn argumentos_ancestral(&self, fqn: &str, ancestral: &str) -> Vec<TipoCs> {
+        let mut atual = fqn.to_string();
+        let mut args: Vec<TipoCs> = self.tabelas.classes[fqn]
+            .decl
+            .generic_params
+            .iter()
+            .map(|g| TipoCs::Generico(g.clone()))
+            .collect();
+        while atual != ancestral {
+            let classe = &self.tabelas.classes[&atual];
+            let Some(pai) = classe.pai.clone() else {
+                break;
+            };
+            let args_pai = match &classe.decl.classe_pai {
+                Some(ast::Tipo::Aplicado { args: tipos, .. }) => tipos
+                    .iter()
+                    .map(|t| {
+                        let tipo = self.tipo_em(t, &classe.namespace, &classe.decl.generic_params);
+                        substituir(&tipo, &classe.decl.generic_params, &args)
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+            args = args_pai;
+            atual = pai;
+        }
+        args
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* TIPOS                                                               */
+    /* ------------------------------------------------------------------ */
+
+    fn tipo_em(&self, tipo: &ast::Tipo, namespace: &str, genericos: &[String]) -> TipoCs {
+        let args_de = |args: &[ast::Tipo]| -> Vec<TipoCs> {
+            args.iter()
+                .map(|a| self.tipo_em(a, namespace, genericos))
+                .collect()
+        };
+        match tipo {
+            ast::Tipo::Inteiro => TipoCs::Inteiro,
+            ast::Tipo::Booleano => TipoCs::Booleano,
+            ast::Tipo::Texto => TipoCs::Texto,
+            ast::Tipo::Flutuante => TipoCs::Flutuante,
+            ast::Tipo::Duplo => TipoCs::Duplo,
+            ast::Tipo::Decimal => TipoCs::Decimal,
+            ast::Tipo::Vazio => TipoCs::Vazio,
+            ast::Tipo::Lista(item) => {
+                TipoCs::Array(Box::new(self.tipo_em(item, namespace, genericos)))
+            }
+            ast::Tipo::Opcional(tipo) => self.tipo_em(tipo, namespace, genericos),
+            ast::Tipo::Generico(nome) => TipoCs::Generico(nome.clone()),
+            ast::Tipo::Enum(nome) => match self.tabelas.resolver_enum(namespace, nome) {
+                Some(en) => TipoCs::Enum(en),
+                None => TipoCs::Inteiro,
+            },
+            ast::Tipo::Classe(nome) | ast::Tipo::Aplicado { nome, .. } => {
+                let args = match tipo {
+                    ast::Tipo::Aplicado { args, .. } => args_de(args),
+                    _ => Vec::new(),
+                };
+                if nome == "objeto" {
+                    return TipoCs::Objeto;
+                }
+                if genericos.contains(nome) {
+                    return TipoCs::Generico(nome.clone());
+                }
+                match self.tabelas.tipo_nomeado(namespace, nome) {
+                    Some(TipoNomeado::Classe(classe)) => return TipoCs::Classe(classe, args),
+                    Some(TipoNomeado::Enum(en)) => return TipoCs::Enum(en),
+                    Some(TipoNomeado::Interface(interface)) => {
+                        return TipoCs::Interface(interface, args)
+                    }
+                    None => {}
+                }
+                let item = || Box::new(args.first().cloned().unwrap_or(TipoCs::Objeto));
+                match nome.as_str() {
+                    "Lista" | "Sistema.Colecoes.Lista" => TipoCs::Lista(item()),
+                    "Tarefa" => TipoCs::Tarefa(item()),
+                    _ => TipoCs::Objeto,
+                }
+            }
+            ast::Tipo::Objeto | ast::Tipo::Funcao(..) | ast::Tipo::Inferido => TipoCs::Objeto,
+        }
+    }
+
+    /// Tipo no contexto do código em geração.
+    fn tipo(&self, tipo: &ast::Tipo) -> TipoCs {
+        self.tipo_em(tipo, &self.namespace, &self.genericos)
+    }
+
+    fn tipo_retorno(&self, tipo: &Option<ast::Tipo>) -> TipoCs {
+        tipo.as_ref().map(|t| self.tipo(t)).unwrap_or(TipoCs::Vazio)
+    }
+
+    /// Tipo de um membro declarado na classe `dono`, visto por um receptor do tipo `receptor`
+    /// (os parâmetros genéricos da classe são trocados pelos argumentos do receptor).
+    fn tipo_membro(
+        &self,
+        dono: &str,
+        tipo: &ast::Tipo,
+        genericos_metodo: &[String],
+        receptor: Option<&TipoCs>,
+    ) -> TipoCs {
+        let classe = &self.tabelas.classes[dono];
+        let mut genericos = classe.decl.generic_params.clone();
+        genericos.extend(genericos_metodo.iter().cloned());
+        let tipo = self.tipo_em(tipo, &classe.namespace, &genericos);
+        match receptor {
+            Some(TipoCs::Classe(fqn, args)) if fqn == dono => {
+                substituir(&tipo, &classe.decl.generic_params, args)
+            }
+            Some(TipoCs::Classe(fqn, args)) if self.tabelas.eh_ancestral(dono, fqn) => {
+                let genericos_receptor = &self.tabelas.classes[fqn].decl.generic_params;
+                let args_dono: Vec<TipoCs> = self
+                    .argumentos_ancestral(fqn, dono)
+                    .iter()
+                    .map(|t| substituir(t, genericos_receptor, args))
+                    .collect();
+                substituir(&tipo, &classe.decl.generic_params, &args_dono)
+            }
+            _ => tipo,
+        }
+    }
+
+    /// Tipo de `este` dentro da classe (os parâmetros de tipo continuam genéricos).
+    fn tipo_este(&self, fqn: &str) -> TipoCs {
+        let genericos = self.tabelas.classes[fqn]
+            .decl
+            .generic_params
+            .iter()
+            .map(|g| TipoCs::Generico(g.clone()))
+            .collect();
+        TipoCs::Classe(fqn.to_string(), genericos)
+    }
+
+    /// Nome C# do tipo, visto do namespace onde o código está sendo escrito.
+    fn nome_cs(&self, tipo: &TipoCs) -> String {
+        let argumentos = |args: &[TipoCs]| {
+            if args.is_empty() {
+                String::new()
+            } else {
+                let nomes: Vec<String> = args.iter().map(|a| self.nome_cs(a)).collect();
+                format!("<{}>", nomes.join(", "))
+            }
+        };
+        match tipo {
+            TipoCs::Inteiro => "long".to_string(),
+            TipoCs::Booleano => "bool".to_string(),
+            TipoCs::Flutuante => "float".to_string(),
+            TipoCs::Duplo => "double".to_string(),
+            TipoCs::Decimal => "decimal".to_string(),
+            TipoCs::Texto => "string".to_string(),
+            TipoCs::Vazio => "void".to_string(),
+            TipoCs::Array(item) => format!("{}[]", self.nome_cs(item)),
+            TipoCs::Lista(item) => format!("{}<{}>", self.bcl("List"), self.nome_cs(item)),
+            TipoCs::Classe(fqn, args) | TipoCs::Interface(fqn, args) => {
+                format!("{}{}", self.referencia_tipo(fqn), argumentos(args))
+            }
+            TipoCs::Enum(fqn) => self.referencia_tipo(fqn),
+            TipoCs::Generico(nome) => identificador(nome),
+            TipoCs::Tarefa(item) if **item == TipoCs::Vazio => self.bcl("Task"),
+            TipoCs::Tarefa(item) => format!("{}<{}>", self.bcl("Task"), self.nome_cs(item)),
+            TipoCs::Objeto | TipoCs::Nulo => "object".to_string(),
+        }
+    }
+
+    /// Nome de um tipo do programa: simples se visível do namespace atual, senão `global::`.
+    fn referencia_tipo(&self, fqn: &str) -> String {
+        let (namespace, nome) = dividir(fqn);
+        let visivel = namespace.is_empty()
+            || self.local == namespace
+            || self.local.starts_with(&format!("{}.", namespace));
+        // Um tipo com o mesmo nome em um namespace mais interno esconderia o tipo
+        let mut prefixo = self.local.as_str();
+        let mut escondido = false;
+        while prefixo.len() > namespace.len() {
+            escondido |= self.existe_tipo(&qualificar(prefixo, nome))
+                || (prefixo == NAMESPACE_RUNTIME
+                    && ["Runtime", "ErroExecucao", "Inicio"].contains(&nome));
+            prefixo = prefixo.rfind('.').map(|i| &prefixo[..i]).unwrap_or("");
+        }
+        if visivel && !escondido {
+            identificador(nome)
+        } else {
+            let partes: Vec<String> = fqn.split('.').map(identificador).collect();
+            format!("global::{}", partes.join("."))
+        }
+    }
+
+    /// Referência a uma função (`Programa` do namespace onde foi declarada).
+    fn referencia_funcao(&self, fqn: &str) -> String {
+        let (namespace, nome) = dividir(fqn);
+        if namespace == self.local && self.em_programa {
+            identificador(nome)
+        } else if namespace == self.local {
+            format!("Programa.{}", identificador(nome))
+        } else {
+            let partes: Vec<String> = qualificar(namespace, "Programa")
+                .split('.')
+                .map(identificador)
+                .collect();
+            format!("global::{}.{}", partes.join("."), identificador(nome))
+        }
+    }
+
+    /// Nome de uma classe da BCL ou do runtime; qualificado se um tipo do programa o esconde.
+    fn bcl(&self, nome: &str) -> String {
+        let escondido = self
+            .tabelas
+            .classes
+            .keys()
+            .chain(self.tabelas.interfaces.keys())
+            .chain(self.tabelas.enums.keys())
+            .any(|fqn| dividir(fqn).1 == nome);
+        if !escondido {
+            return nome.to_string();
+        }
+        let namespace = match nome {
+            "Console" => "System",
+            "File" | "Directory" => "System.IO",
+            "Task" => "System.Threading.Tasks",
+            "List" => "System.Collections.Generic",
+            _ => NAMESPACE_RUNTIME,
+        };
+        format!("global::{}.{}", namespace, nome)
+    }
+
+    /// Expressão que termina o programa com um erro em tempo de execução.
+    fn falhar(&self, mensagem: &str) -> Cs {
+        Cs::chamada(
+            format!(
+                "{}.Falhar<object>({})",
+                self.bcl("Runtime"),
+                literal_texto(mensagem)
+            ),
+            TipoCs::Objeto,
+        )
+    }
+
+    /// Tipo dos itens de um literal `[a, b, ...]`.
+    fn tipo_itens(&self, tipos: &[TipoCs]) -> TipoCs {
+        let mut comum: Option<TipoCs> = None;
+        for tipo in tipos.iter().filter(|t| **t != TipoCs::Nulo) {
+            comum = Some(match (comum, tipo) {
+                (None, t) => t.clone(),
+                (Some(a), b) if a == *b => a,
+                (Some(TipoCs::Classe(a, _)), TipoCs::Classe(b, _)) => self
+                    .tabelas
+                    .ancestral_comum(&a, b)
+                    .map(|c| TipoCs::Classe(c, Vec::new()))
+                    .unwrap_or(TipoCs::Objeto),
+                _ => TipoCs::Objeto,
+            });
+        }
+        comum.unwrap_or(TipoCs::Objeto)
+    }
+
+    /// Valores de `de` podem ser usados como `para` sem conversão explícita no C#.
+    fn atribuivel(&self, de: &TipoCs, para: &TipoCs) -> bool {
+        match (de, para) {
+            _ if de == para => true,
+            (TipoCs::Nulo, para) => !para.valor() && !matches!(para, TipoCs::Generico(_)),
+            (TipoCs::Vazio, _) | (_, TipoCs::Objeto) => true,
+            (TipoCs::Inteiro, TipoCs::Flutuante | TipoCs::Duplo | TipoCs::Decimal) => true,
+            (TipoCs::Flutuante, TipoCs::Duplo) => true,
+            (TipoCs::Classe(a, _), TipoCs::Classe(b, _)) => {
+                a == b || self.tabelas.eh_ancestral(b, a)
+            }
+            (TipoCs::Classe(a, _), TipoCs::Interface(i, _)) => self.implementa(a, i),
+            (TipoCs::Array(a), TipoCs::Array(b)) => {
+                !a.valor() && !b.valor() && self.atribuivel(a, b)
+            }
+            _ => false,
+        }
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* ESCRITA                                                             */
+    /* ------------------------------------------------------------------ */
+
+    fn linha(&mut self, texto: impl AsRef<str>) {
+        let texto = texto.as_ref();
+        if !texto.is_empty() {
+            for _ in 0..self.nivel {
+                self.saida.push_str("    ");
+            }
+            self.saida.push_str(texto);
+        }
+        self.saida.push('\n');
+    }
+
+    fn abrir(&mut self) {
+        self.linha("{");
+        self.nivel += 1;
+    }
+
+    fn fechar(&mut self) {
+        self.nivel -= 1;
+        self.linha("}");
+    }
+
+    /// Linha em branco entre declarações (não logo depois de `{`).
+    fn separar(&mut self) {
+        if !self.saida.is_empty() && !self.saida.ends_with("{\n") && !self.saida.ends_with("\n\n") {
+            self.saida.push('\n');
+        }
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* GERAÇÃO DO PROGRAMA                                                 */
+    /* ------------------------------------------------------------------ */
+
+    /// Gera o `Program.cs` do projeto.
+    pub fn generate(&mut self) -> Result<String, String> {
+        self.saida.clear();
+        self.linha("// Gerado pelo compilador Por do Sol (alvo console).");
+        self.linha("using System;");
+        self.linha("using System.Collections.Generic;");
+        self.linha("using System.IO;");
+        if self
+            .tabelas
+            .funcoes
+            .values()
+            .any(|f| externo(f.decl).is_some())
+        {
+            self.linha("using System.Runtime.InteropServices;");
+        }
+        self.linha("using System.Threading.Tasks;");
+        self.linha("using PorDoSol;");
+
+        let programa = self.programa;
+        self.gerar_declaracoes(&programa.declaracoes, "")?;
+        for namespace in &programa.namespaces {
+            self.gerar_namespace(&namespace.nome, &namespace.declaracoes, "")?;
+        }
+        self.gerar_inicio()?;
+        Ok(std::mem::take(&mut self.saida))
+    }
+
+    fn gerar_namespace(
+        &mut self,
+        nome: &str,
+        declaracoes: &'a [ast::Declaracao],
+        envolvente: &str,
+    ) -> Result<(), String> {
+        self.separar();
+        let partes: Vec<String> = nome.split('.').map(identificador).collect();
+        self.linha(format!("namespace {}", partes.join(".")));
+        self.abrir();
+        self.gerar_declaracoes(declaracoes, &qualificar(envolvente, nome))?;
+        self.fechar();
+        Ok(())
+    }
+
+    /// Tipos na ordem do fonte; as funções do namespace ficam na classe `Programa`, depois.
+    fn gerar_declaracoes(
+        &mut self,
+        declaracoes: &'a [ast::Declaracao],
+        namespace: &str,
+    ) -> Result<(), String> {
+        let mut funcoes = Vec::new();
+        for decl in declaracoes {
+            self.local = namespace.to_string();
+            match decl {
+                ast::Declaracao::DeclaracaoClasse(classe) => {
+                    self.gerar_classe_e_aninhadas(classe, &qualificar(namespace, &classe.nome))?
+                }
+                ast::Declaracao::DeclaracaoInterface(interface) => {
+                    self.gerar_interface(&qualificar(namespace, &interface.nome))
+                }
+                ast::Declaracao::DeclaracaoEnum(en) => self.gerar_enum(en),
+                ast::Declaracao::DeclaracaoFuncao(funcao) => funcoes.push(funcao),
+                ast::Declaracao::DeclaracaoNamespace(ns) => {
+                    self.gerar_namespace(&ns.nome, &ns.declaracoes, namespace)?
+                }
+                ast::Declaracao::DeclaracaoModulo(modulo) => {
+                    self.gerar_namespace(&modulo.nome, &modulo.conteudo, namespace)?
+                }
+                ast::Declaracao::Comando(_)
+                | ast::Declaracao::DeclaracaoTipo(_)
+                | ast::Declaracao::Importacao(_)
+                | ast::Declaracao::Exportacao(_) => {}
+            }
+        }
+        if !funcoes.is_empty() {
+            self.local = namespace.to_string();
+            self.separar();
+            self.linha("public static partial class Programa");
+            self.abrir();
+            for funcao in funcoes {
+                self.gerar_funcao(&qualificar(namespace, &funcao.nome))?;
+            }
+            self.fechar();
+        }
+        Ok(())
+    }
+
+    fn gerar_enum(&mut self, en: &ast::DeclaracaoEnum) {
+        self.separar();
+        self.linha(format!("public enum {}", identificador(&en.nome)));
+        self.abrir();
+        for valor in &en.valores {
+            self.linha(format!("{},", identificador(valor)));
+        }
+        self.fechar();
+    }
+
+    fn gerar_interface(&mut self, fqn: &str) {
+        let interface = &self.tabelas.interfaces[fqn];
+        let decl = interface.decl;
+        self.namespace = interface.namespace.clone();
+        self.genericos = decl.generic_params.clone();
+        self.separar();
+        self.linha(format!(
+            "public interface {}{}",
+            identificador(&decl.nome),
+            parametros_genericos(&decl.generic_params)
+        ));
+        self.abrir();
+        for assinatura in &decl.metodos {
+            let retorno = self.tipo_retorno(&assinatura.tipo_retorno);
+            let parametros = self.lista_parametros(&assinatura.parametros);
+            self.linha(format!(
+                "{} {}({});",
+                self.nome_cs(&retorno),
+                identificador(&assinatura.nome),
+                parametros
+            ));
+        }
+        self.fechar();
+        self.genericos.clear();
+    }
+
+    fn gerar_classe_e_aninhadas(
+        &mut self,
+        classe: &'a ast::DeclaracaoClasse,
+        fqn: &str,
+    ) -> Result<(), String> {
+        let namespace = self.tabelas.classes[fqn].namespace.clone();
+        self.gerar_classe(fqn)?;
+        for aninhada in &classe.nested_classes {
+            self.local = namespace.clone();
+            self.gerar_classe_e_aninhadas(aninhada, &qualificar(&namespace, &aninhada.nome))?;
+        }
+        Ok(())
+    }
+
+    fn iniciar_membro(&mut self, fqn: &str, genericos_metodo: &[String], tem_este: bool) {
+        let classe = &self.tabelas.classes[fqn];
+        self.namespace = classe.namespace.clone();
+        self.classe_atual = Some(fqn.to_string());
+        self.em_programa = false;
+        self.tem_este = tem_este;
+        self.genericos = classe.decl.generic_params.clone();
+        self.genericos.extend(genericos_metodo.iter().cloned());
+        self.escopos = vec![HashMap::new()];
+        self.nomes_usados.clear();
+        self.retorno = TipoCs::Vazio;
+        self.assincrono = false;
+    }
+
+    fn gerar_classe(&mut self, fqn: &str) -> Result<(), String> {
+        let classe = &self.tabelas.classes[fqn];
+        let decl = classe.decl;
+        let pai = classe.pai.clone();
+        self.iniciar_membro(fqn, &[], false);
+
+        let mut cabecalho = String::from(match decl.modificador {
+            ast::ModificadorAcesso::Publico => "public ",
+            _ => "internal ",
+        });
+        if decl.eh_estatica {
+            cabecalho.push_str("static ");
+        } else if decl.eh_abstrata {
+            cabecalho.push_str("abstract ");
+        }
+        cabecalho.push_str(&format!(
+            "class {}{}",
+            identificador(&decl.nome),
+            parametros_genericos(&decl.generic_params)
+        ));
+        let mut bases = Vec::new();
+        if pai.is_some() {
+            let tipo = self.tipo(decl.classe_pai.as_ref().unwrap());
+            bases.push(self.nome_cs(&tipo));
+        }
+        for tipo in decl.classe_pai.iter().chain(&decl.interfaces) {
+            if let tipo @ TipoCs::Interface(..) = self.tipo(tipo) {
+                let nome = self.nome_cs(&tipo);
+                if !bases.contains(&nome) {
+                    bases.push(nome);
+                }
+            }
+        }
+        if !bases.is_empty() {
+            cabecalho.push_str(&format!(" : {}", bases.join(", ")));
+        }
+        self.separar();
+        self.linha(cabecalho);
+        self.abrir();
+
+        // Campos e propriedades
+        for campo in &decl.campos {
+            self.iniciar_membro(fqn, &[], false);
+            let tipo = self.tipo(&campo.tipo);
+            let mut texto = acesso(&campo.modificador).to_string();
+            if campo.eh_estatica || decl.eh_estatica {
+                texto.push_str(" static");
+            }
+            texto.push_str(&format!(
+                " {} {}",
+                self.nome_cs(&tipo),
+                identificador(&campo.nome)
+            ));
+            if let Some(valor) = &campo.valor_inicial {
+                let valor = self.expr(valor);
+                texto.push_str(&format!(" = {}", self.converter(valor, &tipo).codigo));
+            }
+            self.linha(format!("{};", texto));
+        }
+        for propriedade in &decl.propriedades {
+            self.gerar_propriedade(fqn, propriedade)?;
+        }
+
+        if !decl.eh_estatica {
+            self.gerar_construtores(fqn)?;
+        }
+        for metodo in &decl.metodos {
+            self.gerar_metodo(fqn, metodo)?;
+        }
+        if !decl.eh_estatica {
+            self.gerar_abstratos_herdados(fqn)?;
+            for (interface, args) in self.interfaces_da_classe(fqn) {
+                self.gerar_ponte_interface(fqn, &interface, &args)?;
+            }
+            // Texto exibido por imprima: o campo `Nome`, como no interpretador
+            let tem_to_string = decl
+                .metodos
+                .iter()
+                .any(|m| m.nome == "ToString" && m.parametros.is_empty());
+            if self.classe_do_nome(fqn).as_deref() == Some(fqn) && !tem_to_string {
+                self.separar();
+                let texto = format!(
+                    "public override string ToString() => {}.Texto(Nome);",
+                    self.bcl("Runtime")
+                );
+                self.linha(texto);
+            }
+        }
+        self.fechar();
+        self.classe_atual = None;
+        self.genericos.clear();
+        Ok(())
+    }
+
+    fn gerar_propriedade(
+        &mut self,
+        fqn: &str,
+        propriedade: &'a ast::PropriedadeClasse,
+    ) -> Result<(), String> {
+        let estatica = propriedade.eh_estatica || self.tabelas.classes[fqn].decl.eh_estatica;
+        self.iniciar_membro(fqn, &[], !estatica);
+        let tipo = self.tipo(&propriedade.tipo);
+        let nome_tipo = self.nome_cs(&tipo);
+        let nome = identificador(&propriedade.nome);
+        let mut texto = acesso(&propriedade.modificador).to_string();
+        if estatica {
+            texto.push_str(" static");
+        }
+        texto.push_str(&format!(" {} {}", nome_tipo, nome));
+
+        let com_corpo =
+            |a: &Option<ast::AcessorPropriedade>| a.as_ref().is_some_and(|a| a.corpo.is_some());
+        if !com_corpo(&propriedade.obter) && !com_corpo(&propriedade.definir) {
+            // Propriedade automática
+            let mut acessores = Vec::new();
+            let obter = propriedade.obter.clone().or_else(|| {
+                propriedade
+                    .definir
+                    .is_none()
+                    .then_some(ast::AcessorPropriedade {
+                        modificador: None,
+                        corpo: None,
+                    })
+            });
+            for (palavra, acessor) in [("get", &obter), ("set", &propriedade.definir)] {
+                match acessor {
+                    Some(acessor) => acessores.push(modificar_acessor(acessor, palavra)),
+                    // O C# exige `get` em propriedades automáticas; `set` sempre existe
+                    None if palavra == "set" => acessores.push("set;".to_string()),
+                    None => acessores.push("get;".to_string()),
+                }
+            }
+            texto.push_str(&format!(" {{ {} }}", acessores.join(" ")));
+            if let Some(valor) = &propriedade.valor_inicial {
+                let valor = self.expr(valor);
+                texto.push_str(&format!(" = {};", self.converter(valor, &tipo).codigo));
+            }
+            self.linha(texto);
+            return Ok(());
+        }
+
+        // Acessores com corpo; um acessor automático usa um campo de apoio
+        let apoio = format!("__{}", propriedade.nome);
+        let precisa_apoio = [&propriedade.obter, &propriedade.definir]
+            .iter()
+            .any(|a| a.as_ref().is_some_and(|a| a.corpo.is_none()));
+        if precisa_apoio {
+            let mut campo = String::from("private");
+            if estatica {
+                campo.push_str(" static");
+            }
+            campo.push_str(&format!(" {} {}", nome_tipo, apoio));
+            if let Some(valor) = &propriedade.valor_inicial {
+                let valor = self.expr(valor);
+                campo.push_str(&format!(" = {}", self.converter(valor, &tipo).codigo));
+            }
+            self.linha(format!("{};", campo));
+        }
+        self.separar();
+        self.linha(texto);
+        self.abrir();
+        if let Some(obter) = &propriedade.obter {
+            let prefixo = obter
+                .modificador
+                .as_ref()
+                .map(|m| format!("{} ", acesso(m)))
+                .unwrap_or_default();
+            match &obter.corpo {
+                Some(corpo) => {
+                    self.iniciar_membro(fqn, &[], !estatica);
+                    self.retorno = tipo.clone();
+                    self.linha(format!("{}get", prefixo));
+                    self.gerar_corpo(corpo);
+                }
+                None => self.linha(format!("{}get => {};", prefixo, apoio)),
+            }
+        }
+        if let Some(definir) = &propriedade.definir {
+            let prefixo = definir
+                .modificador
+                .as_ref()
+                .map(|m| format!("{} ", acesso(m)))
+                .unwrap_or_default();
+            match &definir.corpo {
+                Some(corpo) => {
+                    self.iniciar_membro(fqn, &[], !estatica);
+                    self.nomes_usados.insert("value".to_string());
+                    self.escopos[0]
+                        .insert("valor".to_string(), ("value".to_string(), tipo.clone()));
+                    self.linha(format!("{}set", prefixo));
+                    self.gerar_corpo(corpo);
+                }
+                None => self.linha(format!("{}set => {} = value;", prefixo, apoio)),
+            }
+        }
+        self.fechar();
+        Ok(())
+    }
+
+    /// Lista de parâmetros `tipo nome = padrão`, declarados no escopo atual.
+    fn declarar_parametros(&mut self, parametros: &[ast::Parametro]) -> String {
+        let mut lista = Vec::new();
+        for parametro in parametros {
+            let tipo = self.tipo(&parametro.tipo);
+            let nome = self.declarar_local(&parametro.nome, tipo.clone());
+            let mut texto = format!("{} {}", self.nome_cs(&tipo), nome);
+            if let Some(padrao) = &parametro.valor_padrao {
+                let padrao = self.expr(padrao);
+                texto.push_str(&format!(" = {}", self.converter(padrao, &tipo).codigo));
+            }
+            lista.push(texto);
+        }
+        lista.join(", ")
+    }
+
+    /// Parâmetros de uma assinatura sem corpo (interfaces).
+    fn lista_parametros(&mut self, parametros: &[ast::Parametro]) -> String {
+        let escopos = std::mem::replace(&mut self.escopos, vec![HashMap::new()]);
+        let usados = std::mem::take(&mut self.nomes_usados);
+        let lista = self.declarar_parametros(parametros);
+        self.escopos = escopos;
+        self.nomes_usados = usados;
+        lista
+    }
+
+    fn gerar_construtores(&mut self, fqn: &str) -> Result<(), String> {
+        let classe = &self.tabelas.classes[fqn];
+        let decl = classe.decl;
+        let pai = classe.pai.clone();
+        let nome = identificador(&decl.nome);
+        if decl.construtores.is_empty() {
+            // O construtor padrão do C# chamaria `base()`, que a classe pai pode não ter
+            if let Some(chamada) = pai.as_ref().and_then(|p| self.chamada_base_padrao(p)) {
+                self.separar();
+                self.linha(format!("public {}() : {}", nome, chamada));
+                self.abrir();
+                self.fechar();
+            }
+            return Ok(());
+        }
+        for construtor in &decl.construtores {
+            self.iniciar_membro(fqn, &[], true);
+            let parametros = self.declarar_parametros(&construtor.parametros);
+            let mut cabecalho = format!(
+                "{} {}({})",
+                acesso(&construtor.modificador),
+                nome,
+                parametros
+            );
+            if let Some(pai) = &pai {
+                let chamada = match &construtor.chamada_pai {
+                    Some(args) => Some(self.chamada_base(pai, args)),
+                    None => self.chamada_base_padrao(pai),
+                };
+                if let Some(chamada) = chamada {
+                    cabecalho.push_str(&format!(" : {}", chamada));
+                }
+            }
+            self.separar();
+            self.linha(cabecalho);
+            self.gerar_corpo(&construtor.corpo);
+        }
+        Ok(())
+    }
+
+    /// `base(...)` com os argumentos convertidos para o construtor escolhido da classe pai.
+    fn chamada_base(&mut self, pai: &str, args: &[ast::Expressao]) -> String {
+        let este = self.classe_atual.as_deref().map(|c| self.tipo_este(c));
+        match self.tabelas.escolher_construtor(pai, args.len()) {
+            Some(indice) => {
+                let parametros = &self.tabelas.classes[pai].decl.construtores[indice].parametros;
+                let tipos: Vec<TipoCs> = parametros
+                    .iter()
+                    .map(|p| self.tipo_membro(pai, &p.tipo, &[], este.as_ref()))
+                    .collect();
+                format!("base({})", self.argumentos(parametros, &tipos, args))
+            }
+            None => "base()".to_string(),
+        }
+    }
+
+    /// `base(...)` implícito: o interpretador chama o construtor da classe pai sem argumentos,
+    /// deixando os parâmetros sem valor padrão com o valor padrão do tipo.
+    fn chamada_base_padrao(&mut self, pai: &str) -> Option<String> {
+        let construtores = &self.tabelas.classes[pai].decl.construtores;
+        if construtores.is_empty()
+            || construtores
+                .iter()
+                .any(|c| aceita_argumentos(&c.parametros, 0))
+        {
+            return None;
+        }
+        Some(self.chamada_base(pai, &[]))
+    }
+
+    fn gerar_metodo(&mut self, fqn: &str, metodo: &'a ast::MetodoClasse) -> Result<(), String> {
+        let decl = self.tabelas.classes[fqn].decl;
+        let estatico = metodo.eh_estatica || decl.eh_estatica;
+        self.iniciar_membro(fqn, &metodo.generic_params, !estatico);
+        let retorno = self.tipo_retorno(&metodo.tipo_retorno);
+        self.retorno = retorno.clone();
+        self.assincrono = metodo.eh_assincrona;
+        let parametros = self.declarar_parametros(&metodo.parametros);
+
+        let mut modificadores = acesso(&metodo.modificador);
+        let mut tipo_metodo = None;
+        if estatico {
+            tipo_metodo = Some("static");
+        } else if metodo.nome == "ToString" && metodo.parametros.is_empty() {
+            modificadores = "public";
+            tipo_metodo = Some("override");
+        } else if let Some(introdutor) = self.entrada_vtable(fqn, &metodo.nome) {
+            let base = self.metodo_base(fqn, metodo).filter(|_| introdutor != fqn);
+            if let Some(base) = base {
+                // A redefinição mantém o acesso do método redefinido
+                modificadores = acesso_virtual(&base.modificador);
+                tipo_metodo = Some("override");
+            } else {
+                modificadores = acesso_virtual(&metodo.modificador);
+                tipo_metodo = Some(if metodo.eh_abstrato && decl.eh_abstrata {
+                    "abstract"
+                } else {
+                    "virtual"
+                });
+            }
+        }
+        let mut cabecalho = modificadores.to_string();
+        if let Some(tipo_metodo) = tipo_metodo {
+            cabecalho.push(' ');
+            cabecalho.push_str(tipo_metodo);
+        }
+        if metodo.eh_assincrona {
+            cabecalho.push_str(" async");
+        }
+        let nome_retorno = self.nome_retorno(&retorno, metodo.eh_assincrona);
+        cabecalho.push_str(&format!(
+            " {} {}{}({})",
+            nome_retorno,
+            identificador(&metodo.nome),
+            parametros_genericos(&metodo.generic_params),
+            parametros
+        ));
+        self.separar();
+        if tipo_metodo == Some("abstract") {
+            self.linha(format!("{};", cabecalho));
+            return Ok(());
+        }
+        self.linha(cabecalho);
+        if let Some(chave) = chave_nativa(&metodo.attributes) {
+            // Métodos [Nativo] da biblioteca padrão: o corpo chama a implementação da BCL
+            let args: Vec<ast::Expressao> = metodo
+                .parametros
+                .iter()
+                .map(|p| ast::Expressao::Identificador(p.nome.clone()))
+                .collect();
+            let corpo = match retorno {
+                TipoCs::Vazio => vec![ast::Comando::Expressao(ast::Expressao::Chamada(
+                    chave.to_string(),
+                    args,
+                ))],
+                _ => vec![ast::Comando::Retorne(Some(ast::Expressao::Chamada(
+                    chave.to_string(),
+                    args,
+                )))],
+            };
+            self.gerar_corpo(&corpo);
+            return Ok(());
+        }
+        self.gerar_corpo(&metodo.corpo);
+        Ok(())
+    }
+
+    fn nome_retorno(&self, retorno: &TipoCs, assincrono: bool) -> String {
+        if assincrono {
+            self.nome_cs(&TipoCs::Tarefa(Box::new(retorno.clone())))
+        } else {
+            self.nome_cs(retorno)
+        }
+    }
+
+    /// Métodos abstratos herdados e não implementados: o C# exige a implementação, e a chamada
+    /// falha com o mesmo erro do interpretador.
+    fn gerar_abstratos_herdados(&mut self, fqn: &str) -> Result<(), String> {
+        if self.tabelas.classes[fqn].decl.eh_abstrata {
+            return Ok(());
+        }
+        for (nome, _) in self.vtables[fqn].clone() {
+            let Some((dono, metodo)) = self.tabelas.buscar_metodo(fqn, &nome, usize::MAX) else {
+                continue;
+            };
+            if dono == fqn || !metodo.eh_abstrato {
+                continue;
+            }
+            // Os parâmetros de tipo da classe abstrata são trocados pelos argumentos herdados
+            let este = self.tipo_este(fqn);
+            let no_herdeiro = |gerador: &Self, tipo: &ast::Tipo| {
+                gerador.tipo_membro(&dono, tipo, &metodo.generic_params, Some(&este))
+            };
+            let retorno = metodo
+                .tipo_retorno
+                .as_ref()
+                .map(|t| no_herdeiro(self, t))
+                .unwrap_or(TipoCs::Vazio);
+            self.iniciar_membro(fqn, &metodo.generic_params, true);
+            let mut parametros = Vec::new();
+            for parametro in &metodo.parametros {
+                let tipo = no_herdeiro(self, &parametro.tipo);
+                let nome = self.declarar_local(&parametro.nome, tipo.clone());
+                parametros.push(format!("{} {}", self.nome_cs(&tipo), nome));
+            }
+            let mut cabecalho = format!("{} override", acesso_virtual(&metodo.modificador));
+            if metodo.eh_assincrona {
+                cabecalho.push_str(" async");
+            }
+            cabecalho.push_str(&format!(
+                " {} {}{}({})",
+                self.nome_retorno(&retorno, metodo.eh_assincrona),
+                identificador(&nome),
+                parametros_genericos(&metodo.generic_params),
+                parametros.join(", ")
+            ));
+            self.separar();
+            self.linha(cabecalho);
+            self.abrir();
+            let mensagem = format!("Método abstrato '{}' não implementado em '{}'", nome, fqn);
+            self.linha(format!(
+                "throw new {}({});",
+                self.bcl("ErroExecucao"),
+                literal_texto(&mensagem)
+            ));
+            self.fechar();
+        }
+        Ok(())
     }
 
-    pub fn generate(&self) -> String {
-        let mut code = String::new();
-        for declaracao in &self.programa.declaracoes {
-            if let ast::Declaracao::Comando(cmd) = declaracao {
-                code.push_str(&self.generate_comando(cmd, 4));
+    /// Métodos da interface que a classe não implementa diretamente com a mesma assinatura
+    /// ganham uma implementação explícita que encaminha para o método da classe (ou, sem ele,
+    /// para o método de mesmo nome do objeto, como o despacho pelo nome do interpretador).
+    fn gerar_ponte_interface(
+        &mut self,
+        fqn: &str,
+        interface: &str,
+        args_interface: &[ast::Tipo],
+    ) -> Result<(), String> {
+        let decl = self.tabelas.interfaces[interface].decl;
+        let namespace_interface = self.tabelas.interfaces[interface].namespace.clone();
+        let namespace_classe = self.tabelas.classes[fqn].namespace.clone();
+        let args: Vec<TipoCs> = {
+            self.iniciar_membro(fqn, &[], true);
+            args_interface.iter().map(|t| self.tipo(t)).collect()
+        };
+        let este = self.tipo_este(fqn);
+        let nome_interface = self.nome_cs(&TipoCs::Interface(interface.to_string(), args.clone()));
+        for assinatura in &decl.metodos {
+            let na_interface = |gerador: &Self, tipo: &ast::Tipo| {
+                substituir(
+                    &gerador.tipo_em(tipo, &namespace_interface, &decl.generic_params),
+                    &decl.generic_params,
+                    &args,
+                )
+            };
+            let retorno = assinatura
+                .tipo_retorno
+                .as_ref()
+                .map(|t| na_interface(self, t))
+                .unwrap_or(TipoCs::Vazio);
+            let tipos: Vec<TipoCs> = assinatura
+                .parametros
+                .iter()
+                .map(|p| na_interface(self, &p.tipo))
+                .collect();
+            let n = tipos.len();
+            let metodo = self
+                .tabelas
+                .buscar_metodo(fqn, &assinatura.nome, n)
+                .filter(|(_, m)| !m.eh_estatica);
+            if let Some((dono, metodo)) = &metodo {
+                let retorno_metodo = metodo
+                    .tipo_retorno
+                    .as_ref()
+                    .map(|t| self.tipo_membro(dono, t, &metodo.generic_params, Some(&este)))
+                    .unwrap_or(TipoCs::Vazio);
+                let parametros_metodo: Vec<TipoCs> = metodo
+                    .parametros
+                    .iter()
+                    .map(|p| self.tipo_membro(dono, &p.tipo, &metodo.generic_params, Some(&este)))
+                    .collect();
+                let implicito = metodo.modificador == ast::ModificadorAcesso::Publico
+                    && !metodo.eh_assincrona
+                    && retorno_metodo == retorno
+                    && parametros_metodo == tipos;
+                if implicito {
+                    continue;
+                }
+            }
+
+            // Implementação explícita
+            self.iniciar_membro(fqn, &[], true);
+            self.namespace = namespace_classe.clone();
+            let mut lista = Vec::new();
+            let mut valores = Vec::new();
+            for (parametro, tipo) in assinatura.parametros.iter().zip(&tipos) {
+                let nome = self.declarar_local(&parametro.nome, tipo.clone());
+                lista.push(format!("{} {}", self.nome_cs(tipo), nome));
+                valores.push(Cs::primaria(nome, tipo.clone()));
+            }
+            let chamada = match &metodo {
+                Some((dono, metodo)) => {
+                    let destinos: Vec<TipoCs> = metodo
+                        .parametros
+                        .iter()
+                        .map(|p| {
+                            self.tipo_membro(dono, &p.tipo, &metodo.generic_params, Some(&este))
+                        })
+                        .collect();
+                    let argumentos: Vec<String> = valores
+                        .into_iter()
+                        .zip(&destinos)
+                        .map(|(v, t)| self.converter(v, t).codigo)
+                        .collect();
+                    let retorno_metodo = metodo
+                        .tipo_retorno
+                        .as_ref()
+                        .map(|t| self.tipo_membro(dono, t, &metodo.generic_params, Some(&este)))
+                        .unwrap_or(TipoCs::Vazio);
+                    let tipo = if metodo.eh_assincrona {
+                        TipoCs::Tarefa(Box::new(retorno_metodo))
+                    } else {
+                        retorno_metodo
+                    };
+                    Cs::chamada(
+                        format!("{}({})", identificador(&metodo.nome), argumentos.join(", ")),
+                        tipo,
+                    )
+                }
+                None => {
+                    let argumentos: Vec<String> = valores.into_iter().map(|v| v.codigo).collect();
+                    Cs::chamada(
+                        format!(
+                            "((dynamic)this).{}({})",
+                            identificador(&assinatura.nome),
+                            argumentos.join(", ")
+                        ),
+                        TipoCs::Objeto,
+                    )
+                }
+            };
+            self.separar();
+            self.linha(format!(
+                "{} {}.{}({})",
+                self.nome_cs(&retorno),
+                nome_interface,
+                identificador(&assinatura.nome),
+                lista.join(", ")
+            ));
+            self.abrir();
+            if retorno == TipoCs::Vazio {
+                self.linha(format!("{};", chamada.codigo));
+            } else {
+                let valor = self.converter(chamada, &retorno);
+                self.linha(format!("return {};", valor.codigo));
             }
+            self.fechar();
+        }
+        Ok(())
+    }
+
+    fn gerar_funcao(&mut self, fqn: &str) -> Result<(), String> {
+        let funcao = &self.tabelas.funcoes[fqn];
+        let decl = funcao.decl;
+        self.namespace = funcao.namespace.clone();
+        self.local = funcao.namespace.clone();
+        self.classe_atual = None;
+        self.em_programa = true;
+        self.tem_este = false;
+        self.genericos = decl.generic_params.clone();
+        self.escopos = vec![HashMap::new()];
+        self.nomes_usados.clear();
+        self.retorno = self.tipo_retorno(&decl.tipo_retorno);
+        self.assincrono = decl.eh_assincrona;
+        let retorno = self.retorno.clone();
+        let parametros = self.declarar_parametros(&decl.parametros);
+        let nome = identificador(&decl.nome);
+        self.separar();
+
+        if let Some((biblioteca, simbolo)) = externo(decl) {
+            self.gerar_externo(decl, biblioteca, simbolo, &retorno, &parametros);
+            return Ok(());
+        }
+
+        let mut cabecalho = format!("{} static", acesso(&decl.modificador));
+        if decl.eh_assincrona {
+            cabecalho.push_str(" async");
+        }
+        cabecalho.push_str(&format!(
+            " {} {}{}({})",
+            self.nome_retorno(&retorno, decl.eh_assincrona),
+            nome,
+            parametros_genericos(&decl.generic_params),
+            parametros
+        ));
+        self.linha(cabecalho);
+        if let Some(chave) = chave_nativa(&decl.attributes) {
+            let args: Vec<ast::Expressao> = decl
+                .parametros
+                .iter()
+                .map(|p| ast::Expressao::Identificador(p.nome.clone()))
+                .collect();
+            let chamada = ast::Expressao::Chamada(chave.to_string(), args);
+            let corpo = match retorno {
+                TipoCs::Vazio => vec![ast::Comando::Expressao(chamada)],
+                _ => vec![ast::Comando::Retorne(Some(chamada))],
+            };
+            self.gerar_corpo(&corpo);
+        } else {
+            self.gerar_corpo(&decl.corpo);
+        }
+        self.em_programa = false;
+        Ok(())
+    }
+
+    /// Função `[Externo("biblioteca", "símbolo")]`: declaração `DllImport` com os tipos C e uma
+    /// função com os tipos da linguagem que converte argumentos e retorno.
+    fn gerar_externo(
+        &mut self,
+        decl: &ast::DeclaracaoFuncao,
+        biblioteca: &str,
+        simbolo: &str,
+        retorno: &TipoCs,
+        parametros: &str,
+    ) {
+        let nome = identificador(&decl.nome);
+        let tipo_c = |tipo: &TipoCs| match tipo {
+            TipoCs::Inteiro | TipoCs::Booleano => "int",
+            TipoCs::Duplo => "double",
+            TipoCs::Flutuante => "float",
+            // Textos devolvidos pertencem à biblioteca C: o ponteiro é copiado, não liberado
+            TipoCs::Texto => "IntPtr",
+            _ => "void",
+        };
+        let mut nativos = Vec::new();
+        let mut argumentos = Vec::new();
+        for parametro in &decl.parametros {
+            let tipo = self.tipo(&parametro.tipo);
+            let (nome_local, _) = self.local(&parametro.nome).unwrap();
+            match tipo {
+                TipoCs::Texto => nativos.push(format!(
+                    "[MarshalAs(UnmanagedType.LPUTF8Str)] string {}",
+                    nome_local
+                )),
+                _ => nativos.push(format!("{} {}", tipo_c(&tipo), nome_local)),
+            }
+            argumentos.push(match tipo {
+                TipoCs::Inteiro => format!("(int){}", nome_local),
+                TipoCs::Booleano => format!("{} ? 1 : 0", nome_local),
+                _ => nome_local,
+            });
+        }
+        self.linha(format!(
+            "[DllImport({}, EntryPoint = {})]",
+            literal_texto(biblioteca),
+            literal_texto(simbolo)
+        ));
+        self.linha(format!(
+            "private static extern {} {}_nativo({});",
+            tipo_c(retorno),
+            decl.nome,
+            nativos.join(", ")
+        ));
+        self.separar();
+        self.linha(format!(
+            "{} static {} {}({})",
+            acesso(&decl.modificador),
+            self.nome_cs(retorno),
+            nome,
+            parametros
+        ));
+        self.abrir();
+        let chamada = format!("{}_nativo({})", decl.nome, argumentos.join(", "));
+        match retorno {
+            TipoCs::Vazio => self.linha(format!("{};", chamada)),
+            TipoCs::Booleano => self.linha(format!("return {} != 0;", chamada)),
+            TipoCs::Texto => self.linha(format!(
+                "return Marshal.PtrToStringUTF8({}) ?? \"\";",
+                chamada
+            )),
+            _ => self.linha(format!("return {};", chamada)),
+        }
+        self.fechar();
+    }
+
+    /// Ponto de entrada: comandos de nível superior e a função `Principal`, com os erros de
+    /// execução exibidos como no interpretador.
+    fn gerar_inicio(&mut self) -> Result<(), String> {
+        self.separar();
+        self.linha(format!("namespace {}", NAMESPACE_RUNTIME));
+        self.abrir();
+        self.linha("internal static class Inicio");
+        self.abrir();
+        self.linha("private static int Main()");
+        self.abrir();
+        self.linha("return Runtime.Executar(() =>");
+        self.abrir();
+        self.local = NAMESPACE_RUNTIME.to_string();
+        self.classe_atual = None;
+        self.em_programa = false;
+        self.tem_este = false;
+        self.genericos.clear();
+        self.escopos = vec![HashMap::new()];
+        self.nomes_usados.clear();
+        self.retorno = TipoCs::Vazio;
+        self.assincrono = false;
+        for (namespace, comando) in self.tabelas.globais.clone() {
+            self.namespace = namespace;
+            self.comando(comando);
+        }
+        let principal = self
+            .tabelas
+            .ordem_funcoes
+            .iter()
+            .find(|n| *n == "Principal" || *n == "principal" || n.ends_with(".Principal"));
+        if let Some(fqn) = principal.cloned() {
+            let referencia = self.referencia_funcao(&fqn);
+            if self.tabelas.funcoes[&fqn].decl.eh_assincrona {
+                self.linha(format!("{}().GetAwaiter().GetResult();", referencia));
+            } else {
+                self.linha(format!("{}();", referencia));
+            }
+        }
+        self.nivel -= 1;
+        self.linha("});");
+        self.fechar();
+        self.fechar();
+        self.fechar();
+        Ok(())
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* COMANDOS                                                            */
+    /* ------------------------------------------------------------------ */
+
+    /// Declara uma variável no escopo atual. O C# não aceita redeclarar um nome em escopos
+    /// internos, então nomes repetidos no mesmo método ganham um sufixo.
+    fn declarar_local(&mut self, nome: &str, tipo: TipoCs) -> String {
+        let base = identificador(nome);
+        let mut cs = base.clone();
+        let mut n = 2;
+        while self.nomes_usados.contains(&cs) {
+            cs = format!("{}_{}", base, n);
+            n += 1;
         }
-        code
+        self.nomes_usados.insert(cs.clone());
+        self.escopos
+            .last_mut()
+            .unwrap()
+            .insert(nome.to_string(), (cs.clone(), tipo));
+        cs
     }
 
-    fn generate_comando(&self, comando: &ast::Comando, indent: usize) -> String {
-        let prefix = " ".repeat(indent);
+    /// Corpo de método ou acessor entre chaves; métodos com retorno terminam com `return`.
+    fn gerar_corpo(&mut self, corpo: &[ast::Comando]) {
+        self.abrir();
+        for comando in corpo {
+            self.comando(comando);
+        }
+        if self.retorno != TipoCs::Vazio && !termina_com_retorno(corpo) {
+            // Como no interpretador, o fim do corpo devolve o valor padrão
+            let tipo = self.nome_cs(&self.retorno.clone());
+            self.linha(format!("return default({});", tipo));
+        }
+        self.fechar();
+    }
+
+    /// Bloco de `se`/`enquanto`/`para`, sempre entre chaves.
+    fn bloco(&mut self, comando: &ast::Comando) {
+        self.abrir();
+        self.escopos.push(HashMap::new());
         match comando {
-            ast::Comando::DeclaracaoVariavel(tipo, nome, Some(expr)) => {
-                format!(
-                    "{}{} {} = {};\n",
-                    prefix,
-                    self.map_type(tipo),
-                    nome,
-                    self.generate_expressao(expr)
+            ast::Comando::Bloco(comandos) => {
+                for comando in comandos {
+                    self.comando(comando);
+                }
+            }
+            comando => self.comando(comando),
+        }
+        self.escopos.pop();
+        self.fechar();
+    }
+
+    fn comando(&mut self, comando: &ast::Comando) {
+        match comando {
+            ast::Comando::DeclaracaoVariavel(tipo, nome, valor) => {
+                let tipo = self.tipo(tipo);
+                let valor = match valor {
+                    Some(valor) => {
+                        let valor = self.expr(valor);
+                        self.converter(valor, &tipo).codigo
+                    }
+                    None => padrao(&tipo, &self.nome_cs(&tipo)),
+                };
+                let nome_tipo = self.nome_cs(&tipo);
+                let nome = self.declarar_local(nome, tipo);
+                self.linha(format!("{} {} = {};", nome_tipo, nome, valor));
+            }
+            ast::Comando::DeclaracaoVar(nome, valor) => {
+                let valor = self.expr(valor);
+                let tipo = match &valor.tipo {
+                    TipoCs::Nulo | TipoCs::Vazio => TipoCs::Objeto,
+                    tipo => tipo.clone(),
+                };
+                // `var` só onde o C# deduz o mesmo tipo (literais inteiros seriam `int`)
+                let nome_tipo = match &tipo {
+                    TipoCs::Inteiro | TipoCs::Flutuante | TipoCs::Objeto => self.nome_cs(&tipo),
+                    _ => "var".to_string(),
+                };
+                let valor = self.converter(valor, &tipo);
+                let nome = self.declarar_local(nome, tipo);
+                self.linha(format!("{} {} = {};", nome_tipo, nome, valor.codigo));
+            }
+            ast::Comando::Atribuicao(nome, valor) => self.atribuir_variavel(nome, valor),
+            ast::Comando::AtribuirPropriedade(alvo, membro, valor)
+            | ast::Comando::AtribuirCampo(alvo, membro, valor) => {
+                let destino = self.acesso_membro(alvo, membro);
+                let valor = self.expr(valor);
+                let valor = self.converter(valor, &destino.tipo);
+                self.linha(format!("{} = {};", destino.codigo, valor.codigo));
+            }
+            ast::Comando::AtribuirIndice(alvo, indice, valor) => {
+                let destino = self.acesso_indice(alvo, indice);
+                let valor = self.expr(valor);
+                let valor = self.converter(valor, &destino.tipo);
+                self.linha(format!("{} = {};", destino.codigo, valor.codigo));
+            }
+            ast::Comando::Imprima(valor) => {
+                let valor = self.expr(valor);
+                let texto = self.texto_de(valor);
+                self.linha(format!(
+                    "{}.WriteLine({});",
+                    self.bcl("Console"),
+                    texto.codigo
+                ));
+            }
+            ast::Comando::Se(condicao, entao, senao) => {
+                let condicao = self.condicao(condicao);
+                self.linha(format!("if ({})", condicao));
+                self.bloco(entao);
+                let mut senao = senao.as_deref();
+                while let Some(comando) = senao {
+                    match comando {
+                        ast::Comando::Se(condicao, entao, proximo) => {
+                            let condicao = self.condicao(condicao);
+                            self.linha(format!("else if ({})", condicao));
+                            self.bloco(entao);
+                            senao = proximo.as_deref();
+                        }
+                        comando => {
+                            self.linha("else");
+                            self.bloco(comando);
+                            senao = None;
+                        }
+                    }
+                }
+            }
+            ast::Comando::Enquanto(condicao, corpo) => {
+                let condicao = self.condicao(condicao);
+                self.linha(format!("while ({})", condicao));
+                self.bloco(corpo);
+            }
+            ast::Comando::Para(inicio, condicao, incremento, corpo) => {
+                self.escopos.push(HashMap::new());
+                let inicio = match inicio.as_deref() {
+                    Some(ast::Comando::DeclaracaoVar(nome, valor)) => {
+                        let valor = self.expr(valor);
+                        let tipo = match &valor.tipo {
+                            TipoCs::Nulo | TipoCs::Vazio => TipoCs::Objeto,
+                            tipo => tipo.clone(),
+                        };
+                        let nome_tipo = match &tipo {
+                            TipoCs::Inteiro | TipoCs::Flutuante | TipoCs::Objeto => {
+                                self.nome_cs(&tipo)
+                            }
+                            _ => "var".to_string(),
+                        };
+                        let valor = self.converter(valor, &tipo);
+                        let nome = self.declarar_local(nome, tipo);
+                        format!("{} {} = {}", nome_tipo, nome, valor.codigo)
+                    }
+                    Some(ast::Comando::Expressao(expr)) => self.instrucao(expr),
+                    Some(comando) => {
+                        // Outros comandos de início executam antes do laço
+                        self.comando(comando);
+                        String::new()
+                    }
+                    None => String::new(),
+                };
+                let condicao = condicao
+                    .as_ref()
+                    .map(|c| self.condicao(c))
+                    .unwrap_or_default();
+                let incremento = match incremento.as_deref() {
+                    Some(ast::Comando::Expressao(expr)) => self.instrucao(expr),
+                    _ => String::new(),
+                };
+                let cabecalho = format!("for ({}; {}; {})", inicio, condicao, incremento);
+                self.linha(cabecalho.replace("; )", ";)"));
+                self.bloco(corpo);
+                self.escopos.pop();
+            }
+            ast::Comando::Bloco(comandos) => {
+                self.abrir();
+                self.escopos.push(HashMap::new());
+                for comando in comandos {
+                    self.comando(comando);
+                }
+                self.escopos.pop();
+                self.fechar();
+            }
+            ast::Comando::Retorne(valor) => {
+                let retorno = self.retorno.clone();
+                match valor {
+                    None if retorno == TipoCs::Vazio => self.linha("return;"),
+                    None => {
+                        let tipo = self.nome_cs(&retorno);
+                        self.linha(format!("return default({});", tipo));
+                    }
+                    Some(valor) if retorno == TipoCs::Vazio => {
+                        // Função sem tipo de retorno: o valor é avaliado e descartado
+                        let instrucao = self.instrucao(valor);
+                        self.linha(format!("{};", instrucao));
+                        self.linha("return;");
+                    }
+                    Some(valor) => {
+                        let valor = self.expr(valor);
+                        let valor = self.converter(valor, &retorno);
+                        self.linha(format!("return {};", valor.codigo));
+                    }
+                }
+            }
+            ast::Comando::Expressao(expr) => {
+                let instrucao = self.instrucao(expr);
+                self.linha(format!("{};", instrucao));
+            }
+            ast::Comando::ChamarMetodo(alvo, metodo, args) => {
+                let chamada = self.chamada_metodo(alvo, metodo, args);
+                self.linha(format!("{};", como_instrucao(chamada)));
+            }
+            ast::Comando::CriarObjeto(nome, classe, args) => {
+                let objeto = self.novo_objeto(&ast::Tipo::Classe(classe.clone()), args);
+                let tipo = objeto.tipo.clone();
+                let nome = self.declarar_local(nome, tipo);
+                self.linha(format!("var {} = {};", nome, objeto.codigo));
+            }
+            ast::Comando::AcessarCampo(objeto, campo) => {
+                let acesso =
+                    self.acesso_membro(&ast::Expressao::Identificador(objeto.clone()), campo);
+                self.linha(format!("{};", como_instrucao(acesso)));
+            }
+            ast::Comando::Posicao(_) => {}
+        }
+    }
+
+    /// Expressão usada como comando (o C# só aceita chamadas, atribuições e `new`/`await`).
+    fn instrucao(&mut self, expr: &ast::Expressao) -> String {
+        let valor = self.expr(expr);
+        como_instrucao(valor)
+    }
+
+    fn condicao(&mut self, expr: &ast::Expressao) -> String {
+        let valor = self.expr(expr);
+        self.converter(valor, &TipoCs::Booleano).codigo
+    }
+
+    fn atribuir_variavel(&mut self, nome: &str, valor: &ast::Expressao) {
+        let destino = self.ler_variavel(nome);
+        let valor = self.expr(valor);
+        match destino {
+            Some(destino) => {
+                let valor = self.converter(valor, &destino.tipo);
+                self.linha(format!("{} = {};", destino.codigo, valor.codigo));
+            }
+            None => {
+                // Atribuição a um nome ainda não declarado cria a variável
+                let tipo = match &valor.tipo {
+                    TipoCs::Nulo | TipoCs::Vazio => TipoCs::Objeto,
+                    tipo => tipo.clone(),
+                };
+                let nome_tipo = self.nome_cs(&tipo);
+                let valor = self.converter(valor, &tipo);
+                let nome = self.declarar_local(nome, tipo);
+                self.linha(format!("{} {} = {};", nome_tipo, nome, valor.codigo));
+            }
+        }
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* EXPRESSÕES                                                          */
+    /* ------------------------------------------------------------------ */
+
+    fn expr(&mut self, expr: &ast::Expressao) -> Cs {
+        match expr {
+            ast::Expressao::Inteiro(n) => {
+                let prec = if *n < 0 { UNARIA } else { PRIMARIA };
+                Cs::novo(n.to_string(), TipoCs::Inteiro, prec)
+            }
+            ast::Expressao::Texto(texto) => Cs::primaria(literal_texto(texto), TipoCs::Texto),
+            ast::Expressao::Booleano(b) => {
+                Cs::primaria(if *b { "true" } else { "false" }, TipoCs::Booleano)
+            }
+            ast::Expressao::Decimal(literal) => {
+                let numero = literal.trim_end_matches(['m', 'M']);
+                Cs::primaria(format!("{}m", numero), TipoCs::Decimal)
+            }
+            ast::Expressao::FlutuanteLiteral(literal) => {
+                let numero = literal.trim_end_matches(['f', 'F']);
+                Cs::primaria(format!("{}f", numero), TipoCs::Flutuante)
+            }
+            ast::Expressao::DuploLiteral(literal) => {
+                let mut numero = literal.clone();
+                if !numero.contains(['.', 'e', 'E']) {
+                    numero.push_str(".0");
+                }
+                Cs::primaria(numero, TipoCs::Duplo)
+            }
+            ast::Expressao::Nulo => Cs::primaria("null", TipoCs::Nulo),
+            ast::Expressao::Este => match &self.classe_atual {
+                Some(classe) if self.tem_este => {
+                    let genericos = self.tabelas.classes[classe]
+                        .decl
+                        .generic_params
+                        .iter()
+                        .map(|g| TipoCs::Generico(g.clone()))
+                        .collect();
+                    Cs::primaria("this", TipoCs::Classe(classe.clone(), genericos))
+                }
+                _ => self.falhar("'este' fora de um método de instância"),
+            },
+            ast::Expressao::Identificador(nome) => self
+                .ler_variavel(nome)
+                .unwrap_or_else(|| self.falhar(&format!("Variável '{}' não definida", nome))),
+            ast::Expressao::ListaLiteral(itens) => self.lista(itens),
+            ast::Expressao::Aritmetica(op, esq, dir) => self.aritmetica(op, esq, dir),
+            ast::Expressao::Comparacao(op, esq, dir) => self.comparacao(op, esq, dir),
+            ast::Expressao::Logica(op, esq, dir) => {
+                let (texto, prec) = match op {
+                    ast::OperadorLogico::E => ("&&", E),
+                    ast::OperadorLogico::Ou => ("||", OU),
+                };
+                let a = self.expr(esq);
+                let a = self.converter(a, &TipoCs::Booleano);
+                let b = self.expr(dir);
+                let b = self.converter(b, &TipoCs::Booleano);
+                Cs::novo(
+                    format!("{} {} {}", a.operando(prec), texto, b.operando(prec + 1)),
+                    TipoCs::Booleano,
+                    prec,
                 )
             }
-            ast::Comando::DeclaracaoVar(nome, expr) => {
+            ast::Expressao::Unario(op, valor) => {
+                let valor = self.expr(valor);
+                match op {
+                    ast::OperadorUnario::NegacaoLogica => {
+                        let valor = self.converter(valor, &TipoCs::Booleano);
+                        Cs::novo(
+                            format!("!{}", valor.operando(UNARIA)),
+                            TipoCs::Booleano,
+                            UNARIA,
+                        )
+                    }
+                    ast::OperadorUnario::NegacaoNumerica => {
+                        let valor = self.numero(valor);
+                        let mut operando = valor.operando(UNARIA);
+                        if operando.starts_with('-') {
+                            operando = format!("({})", operando);
+                        }
+                        Cs::novo(format!("-{}", operando), valor.tipo, UNARIA)
+                    }
+                }
+            }
+            ast::Expressao::NovoObjeto(tipo, args) => self.novo_objeto(tipo, args),
+            ast::Expressao::NovoArray(tipo, tamanho) => {
+                let item = self.tipo(tipo);
+                let tamanho = self.expr(tamanho);
+                let tamanho = self.converter(tamanho, &TipoCs::Inteiro);
+                let tipo = TipoCs::Array(Box::new(item.clone()));
+                if item == TipoCs::Texto {
+                    // Arrays de texto começam com textos vazios, como no interpretador
+                    return Cs::chamada(
+                        format!("{}.NovoArrayTexto({})", self.bcl("Runtime"), tamanho.codigo),
+                        tipo,
+                    );
+                }
+                // `new T[n][]` para arrays de arrays
+                let nome = self.nome_cs(&item);
+                let codigo = match nome.find('[') {
+                    Some(i) => format!("new {}[{}]{}", &nome[..i], tamanho.codigo, &nome[i..]),
+                    None => format!("new {}[{}]", nome, tamanho.codigo),
+                };
+                Cs::chamada(codigo, tipo)
+            }
+            ast::Expressao::AcessoMembro(alvo, membro) => self.acesso_membro(alvo, membro),
+            ast::Expressao::AcessoIndice(alvo, indice) => self.acesso_indice(alvo, indice),
+            ast::Expressao::ChamadaMetodo(alvo, metodo, args) => {
+                self.chamada_metodo(alvo, metodo, args)
+            }
+            ast::Expressao::Chamada(nome, args) => self.chamada(nome, args),
+            ast::Expressao::StringInterpolada(partes) => self.interpolacao(partes),
+            ast::Expressao::Aguarde(valor) => {
+                let valor = self.expr(valor);
+                match valor.tipo.clone() {
+                    TipoCs::Tarefa(resultado) if self.assincrono => Cs {
+                        instrucao: true,
+                        ..Cs::novo(
+                            format!("await {}", valor.operando(UNARIA)),
+                            *resultado,
+                            UNARIA,
+                        )
+                    },
+                    TipoCs::Tarefa(resultado) => Cs::chamada(
+                        format!("{}.GetAwaiter().GetResult()", valor.operando(PRIMARIA)),
+                        *resultado,
+                    ),
+                    // Valores que não são tarefas já estão prontos
+                    _ => valor,
+                }
+            }
+        }
+    }
+
+    /// Variável local, parâmetro, campo de `este` ou membro estático da classe atual.
+    fn ler_variavel(&mut self, nome: &str) -> Option<Cs> {
+        if let Some((cs, tipo)) = self.local(nome) {
+            return Some(Cs::primaria(cs, tipo));
+        }
+        let classe = self.classe_atual.clone()?;
+        if self.tem_este {
+            if let Some((dono, tipo)) = self.buscar_campo(&classe, nome) {
+                let tipo = self.tipo_membro(&dono, tipo, &[], Some(&self.tipo_este(&classe)));
+                return Some(Cs::primaria(identificador(nome), tipo));
+            }
+        }
+        let (dono, tipo) = self.dono_estatico(&classe, nome)?;
+        let tipo = self.tipo_membro(&dono, tipo, &[], None);
+        let codigo = if self.tabelas.eh_ancestral(&dono, &classe) {
+            identificador(nome)
+        } else {
+            format!("{}.{}", self.referencia_tipo(&dono), identificador(nome))
+        };
+        Some(Cs::primaria(codigo, tipo))
+    }
+
+    fn lista(&mut self, itens: &[ast::Expressao]) -> Cs {
+        let valores: Vec<Cs> = itens.iter().map(|i| self.expr(i)).collect();
+        let tipos: Vec<TipoCs> = valores.iter().map(|v| v.tipo.clone()).collect();
+        let item = self.tipo_itens(&tipos);
+        let nome = self.nome_cs(&item);
+        let tipo = TipoCs::Array(Box::new(item.clone()));
+        if valores.is_empty() {
+            return Cs::chamada(format!("new {}[0]", nome), tipo);
+        }
+        let codigos: Vec<String> = valores
+            .into_iter()
+            .map(|v| self.converter(v, &item).codigo)
+            .collect();
+        Cs::chamada(format!("new {}[] {{ {} }}", nome, codigos.join(", ")), tipo)
+    }
+
+    fn acesso_membro(&mut self, alvo: &ast::Expressao, membro: &str) -> Cs {
+        if let Some(estatico) = self.alvo_estatico(alvo) {
+            return match estatico {
+                AlvoEstatico::Classe(classe) => match self.dono_estatico(&classe, membro) {
+                    Some((dono, tipo)) => {
+                        let tipo = self.tipo_membro(&dono, tipo, &[], None);
+                        Cs::primaria(
+                            format!("{}.{}", self.referencia_tipo(&dono), identificador(membro)),
+                            tipo,
+                        )
+                    }
+                    None => self.falhar(&format!(
+                        "Membro estático '{}' não encontrado em '{}'",
+                        membro, classe
+                    )),
+                },
+                AlvoEstatico::Enum(en) => Cs::primaria(
+                    format!("{}.{}", self.referencia_tipo(&en), identificador(membro)),
+                    TipoCs::Enum(en),
+                ),
+                AlvoEstatico::Nativo(nome) => {
+                    self.falhar(&format!("Classe '{}' não encontrada", nome))
+                }
+            };
+        }
+        let objeto = self.expr(alvo);
+        let receptor = objeto.operando(PRIMARIA);
+        match objeto.tipo.clone() {
+            TipoCs::Texto if membro == "tamanho" || membro == "comprimento" => Cs::chamada(
+                format!("{}.Comprimento({})", self.bcl("Runtime"), objeto.codigo),
+                TipoCs::Inteiro,
+            ),
+            TipoCs::Array(_) if membro == "tamanho" => {
+                Cs::primaria(format!("{}.Length", receptor), TipoCs::Inteiro)
+            }
+            TipoCs::Lista(_)
+                if membro == "tamanho" || membro == "Tamanho" || membro == "Contagem" =>
+            {
+                Cs::primaria(format!("{}.Count", receptor), TipoCs::Inteiro)
+            }
+            TipoCs::Classe(classe, args) => match self.buscar_campo(&classe, membro) {
+                Some((dono, tipo)) => {
+                    let receptor_tipo = TipoCs::Classe(classe, args);
+                    let tipo = self.tipo_membro(&dono, tipo, &[], Some(&receptor_tipo));
+                    Cs::primaria(format!("{}.{}", receptor, identificador(membro)), tipo)
+                }
+                None => Cs::primaria(
+                    format!("((dynamic){}).{}", receptor, identificador(membro)),
+                    TipoCs::Objeto,
+                ),
+            },
+            _ => Cs::primaria(
                 format!(
-                    "{}var {} = {};\n",
-                    prefix,
-                    nome,
-                    self.generate_expressao(expr)
+                    "((dynamic){}).{}",
+                    objeto.operando(UNARIA),
+                    identificador(membro)
+                ),
+                TipoCs::Objeto,
+            ),
+        }
+    }
+
+    fn acesso_indice(&mut self, alvo: &ast::Expressao, indice: &ast::Expressao) -> Cs {
+        let objeto = self.expr(alvo);
+        let indice = self.expr(indice);
+        let receptor = objeto.operando(PRIMARIA);
+        match objeto.tipo.clone() {
+            TipoCs::Array(item) => {
+                let indice = self.converter(indice, &TipoCs::Inteiro);
+                Cs::primaria(format!("{}[{}]", receptor, indice.codigo), *item)
+            }
+            TipoCs::Lista(item) => {
+                let indice = self.indice_int(indice);
+                Cs::primaria(format!("{}[{}]", receptor, indice), *item)
+            }
+            TipoCs::Texto => {
+                let indice = self.indice_int(indice);
+                Cs::chamada(
+                    format!("{}[{}].ToString()", receptor, indice),
+                    TipoCs::Texto,
                 )
             }
-            ast::Comando::Imprima(expr) => {
+            _ => Cs::primaria(
                 format!(
-                    "{}Console.WriteLine({});\n",
-                    prefix,
-                    self.generate_expressao(expr)
+                    "((dynamic){})[{}]",
+                    objeto.operando(UNARIA),
+                    self.converter(indice, &TipoCs::Inteiro).codigo
+                ),
+                TipoCs::Objeto,
+            ),
+        }
+    }
+
+    /// Índice `int` para `List<T>` e textos.
+    fn indice_int(&mut self, indice: Cs) -> String {
+        if indice.literal_inteiro() {
+            return indice.codigo;
+        }
+        let indice = self.converter(indice, &TipoCs::Inteiro);
+        format!("(int){}", indice.operando(UNARIA))
+    }
+
+    fn novo_objeto(&mut self, tipo_ast: &ast::Tipo, args: &[ast::Expressao]) -> Cs {
+        let tipo = self.tipo(tipo_ast);
+        match &tipo {
+            TipoCs::Classe(classe, _) => {
+                let classe = classe.clone();
+                let codigo = match self.tabelas.escolher_construtor(&classe, args.len()) {
+                    Some(indice) => {
+                        let parametros =
+                            &self.tabelas.classes[&classe].decl.construtores[indice].parametros;
+                        let tipos: Vec<TipoCs> = parametros
+                            .iter()
+                            .map(|p| self.tipo_membro(&classe, &p.tipo, &[], Some(&tipo)))
+                            .collect();
+                        self.argumentos(parametros, &tipos, args)
+                    }
+                    None => String::new(),
+                };
+                Cs::chamada(format!("new {}({})", self.nome_cs(&tipo), codigo), tipo)
+            }
+            TipoCs::Lista(_) => Cs::chamada(format!("new {}()", self.nome_cs(&tipo)), tipo),
+            _ => {
+                let nome = match tipo_ast {
+                    ast::Tipo::Classe(nome) | ast::Tipo::Aplicado { nome, .. } => nome.clone(),
+                    tipo => tipo.to_string(),
+                };
+                self.falhar(&format!("Classe '{}' não encontrada", nome))
+            }
+        }
+    }
+
+    /// Argumentos convertidos para os tipos dos parâmetros; os que faltam (sem valor padrão)
+    /// recebem o valor padrão do tipo, como os parâmetros sem argumento no interpretador.
+    fn argumentos(
+        &mut self,
+        parametros: &[ast::Parametro],
+        tipos: &[TipoCs],
+        args: &[ast::Expressao],
+    ) -> String {
+        let mut codigos = Vec::new();
+        for (i, tipo) in tipos.iter().enumerate() {
+            match args.get(i) {
+                Some(arg) => {
+                    let valor = self.expr(arg);
+                    codigos.push(self.converter(valor, tipo).codigo);
+                }
+                None if parametros[i].valor_padrao.is_some() => break,
+                None => codigos.push(format!("default({})", self.nome_cs(tipo))),
+            }
+        }
+        codigos.join(", ")
+    }
+
+    fn chamada(&mut self, nome: &str, args: &[ast::Expressao]) -> Cs {
+        if let Some(classe) = self.classe_atual.clone() {
+            if let Some((dono, metodo)) = self.tabelas.buscar_metodo(&classe, nome, args.len()) {
+                if let Some(chave) = chave_nativa(&metodo.attributes) {
+                    return self.nativo(chave, args);
+                }
+                let prefixo = if metodo.eh_estatica && !self.tabelas.eh_ancestral(&dono, &classe) {
+                    format!("{}.", self.referencia_tipo(&dono))
+                } else {
+                    String::new()
+                };
+                let este = self.tipo_este(&classe);
+                return self.chamar_metodo(prefixo, &dono, metodo, Some(&este), args);
+            }
+        }
+        if let Some(fqn) = self.tabelas.resolver_funcao(&self.namespace, nome) {
+            let decl = self.tabelas.funcoes[&fqn].decl;
+            if let Some(chave) = chave_nativa(&decl.attributes) {
+                return self.nativo(chave, args);
+            }
+            let namespace = self.tabelas.funcoes[&fqn].namespace.clone();
+            let mut genericos = decl.generic_params.clone();
+            genericos.extend(self.genericos.iter().cloned());
+            let tipos: Vec<TipoCs> = decl
+                .parametros
+                .iter()
+                .map(|p| self.tipo_em(&p.tipo, &namespace, &decl.generic_params))
+                .collect();
+            let mut retorno = decl
+                .tipo_retorno
+                .as_ref()
+                .map(|t| self.tipo_em(t, &namespace, &decl.generic_params))
+                .unwrap_or(TipoCs::Vazio);
+            if decl.eh_assincrona {
+                retorno = TipoCs::Tarefa(Box::new(retorno));
+            }
+            let codigo = self.argumentos(&decl.parametros, &tipos, args);
+            let retorno = sem_genericos(&retorno, &decl.generic_params);
+            return Cs::chamada(
+                format!("{}({})", self.referencia_funcao(&fqn), codigo),
+                retorno,
+            );
+        }
+        match nome {
+            "EscreverLinha" | "Escrever" | "LerLinha" => {
+                self.nativo(&format!("Console::{}", nome), args)
+            }
+            _ => self.nativo(nome, args),
+        }
+    }
+
+    /// Chamada de um método da classe `dono` (`prefixo` é o receptor com o ponto, ou vazio).
+    fn chamar_metodo(
+        &mut self,
+        prefixo: String,
+        dono: &str,
+        metodo: &ast::MetodoClasse,
+        receptor: Option<&TipoCs>,
+        args: &[ast::Expressao],
+    ) -> Cs {
+        let tipos: Vec<TipoCs> = metodo
+            .parametros
+            .iter()
+            .map(|p| self.tipo_membro(dono, &p.tipo, &metodo.generic_params, receptor))
+            .collect();
+        let mut retorno = metodo
+            .tipo_retorno
+            .as_ref()
+            .map(|t| self.tipo_membro(dono, t, &metodo.generic_params, receptor))
+            .unwrap_or(TipoCs::Vazio);
+        if metodo.eh_assincrona {
+            retorno = TipoCs::Tarefa(Box::new(retorno));
+        }
+        let retorno = sem_genericos(&retorno, &metodo.generic_params);
+        let codigo = self.argumentos(&metodo.parametros, &tipos, args);
+        Cs::chamada(
+            format!("{}{}({})", prefixo, identificador(&metodo.nome), codigo),
+            retorno,
+        )
+    }
+
+    fn chamada_metodo(
+        &mut self,
+        alvo: &ast::Expressao,
+        metodo: &str,
+        args: &[ast::Expressao],
+    ) -> Cs {
+        if let Some(estatico) = self.alvo_estatico(alvo) {
+            return match estatico {
+                AlvoEstatico::Classe(classe) => {
+                    match self.tabelas.buscar_metodo(&classe, metodo, args.len()) {
+                        Some((dono, decl)) => {
+                            if let Some(chave) = chave_nativa(&decl.attributes) {
+                                return self.nativo(chave, args);
+                            }
+                            let prefixo = format!("{}.", self.referencia_tipo(&dono));
+                            self.chamar_metodo(prefixo, &dono, decl, None, args)
+                        }
+                        None => self.falhar(&format!(
+                            "Método '{}' não encontrado na classe '{}'",
+                            metodo, classe
+                        )),
+                    }
+                }
+                AlvoEstatico::Nativo(classe) => {
+                    self.nativo(&format!("{}::{}", classe, metodo), args)
+                }
+                AlvoEstatico::Enum(_) => {
+                    self.falhar(&format!("Enumerações não têm o método '{}'", metodo))
+                }
+            };
+        }
+        let objeto = self.expr(alvo);
+        match objeto.tipo.clone() {
+            TipoCs::Texto => self.metodo_texto(objeto, metodo, args),
+            TipoCs::Lista(item) => self.metodo_lista(objeto, &item, metodo, args),
+            TipoCs::Classe(classe, _) => {
+                match self.tabelas.buscar_metodo(&classe, metodo, args.len()) {
+                    Some((dono, decl)) => {
+                        if decl.eh_estatica {
+                            let prefixo = format!("{}.", self.referencia_tipo(&dono));
+                            return self.chamar_metodo(prefixo, &dono, decl, None, args);
+                        }
+                        let prefixo = format!("{}.", objeto.operando(PRIMARIA));
+                        self.chamar_metodo(prefixo, &dono, decl, Some(&objeto.tipo), args)
+                    }
+                    None => self.chamada_dinamica(objeto, metodo, args),
+                }
+            }
+            TipoCs::Interface(interface, args_interface) => {
+                let decl = self.tabelas.interfaces[&interface].decl;
+                let namespace = self.tabelas.interfaces[&interface].namespace.clone();
+                let Some(assinatura) = decl.metodos.iter().find(|m| m.nome == metodo) else {
+                    return self.chamada_dinamica(objeto, metodo, args);
+                };
+                let na_interface = |gerador: &Self, tipo: &ast::Tipo| {
+                    substituir(
+                        &gerador.tipo_em(tipo, &namespace, &decl.generic_params),
+                        &decl.generic_params,
+                        &args_interface,
+                    )
+                };
+                let tipos: Vec<TipoCs> = assinatura
+                    .parametros
+                    .iter()
+                    .map(|p| na_interface(self, &p.tipo))
+                    .collect();
+                let retorno = assinatura
+                    .tipo_retorno
+                    .as_ref()
+                    .map(|t| na_interface(self, t))
+                    .unwrap_or(TipoCs::Vazio);
+                let codigo = self.argumentos(&assinatura.parametros, &tipos, args);
+                Cs::chamada(
+                    format!(
+                        "{}.{}({})",
+                        objeto.operando(PRIMARIA),
+                        identificador(metodo),
+                        codigo
+                    ),
+                    retorno,
                 )
             }
-            _ => format!(
-                "{}// Comando {:?} não implementado para Console\n",
-                prefix, comando
+            _ => self.chamada_dinamica(objeto, metodo, args),
+        }
+    }
+
+    /// Chamada pelo nome do método em um objeto de tipo desconhecido (`dynamic`).
+    fn chamada_dinamica(&mut self, objeto: Cs, metodo: &str, args: &[ast::Expressao]) -> Cs {
+        let codigos: Vec<String> = args
+            .iter()
+            .map(|a| {
+                let valor = self.expr(a);
+                objeto_de(valor)
+            })
+            .collect();
+        Cs::chamada(
+            format!(
+                "((dynamic){}).{}({})",
+                objeto.operando(UNARIA),
+                identificador(metodo),
+                codigos.join(", ")
+            ),
+            TipoCs::Objeto,
+        )
+    }
+
+    /// Os `N` primeiros argumentos como texto C#; os que faltam viram `""`.
+    fn textos<const N: usize>(&mut self, args: &[ast::Expressao]) -> [String; N] {
+        std::array::from_fn(|i| match args.get(i) {
+            Some(arg) => self.texto_arg(arg),
+            None => "\"\"".to_string(),
+        })
+    }
+
+    fn texto_arg(&mut self, arg: &ast::Expressao) -> String {
+        let valor = self.expr(arg);
+        self.texto_de(valor).codigo
+    }
+
+    fn metodo_texto(&mut self, texto: Cs, metodo: &str, args: &[ast::Expressao]) -> Cs {
+        let receptor = texto.operando(PRIMARIA);
+        match metodo {
+            "comprimento" | "tamanho" => Cs::chamada(
+                format!("{}.Comprimento({})", self.bcl("Runtime"), texto.codigo),
+                TipoCs::Inteiro,
             ),
+            "ParaMaiusculo" => {
+                Cs::chamada(format!("{}.ToUpperInvariant()", receptor), TipoCs::Texto)
+            }
+            "ParaMinusculo" => {
+                Cs::chamada(format!("{}.ToLowerInvariant()", receptor), TipoCs::Texto)
+            }
+            "Aparar" => Cs::chamada(format!("{}.Trim()", receptor), TipoCs::Texto),
+            "Contem" => {
+                let [parte] = self.textos(args);
+                Cs::chamada(
+                    format!("{}.Contains({}, StringComparison.Ordinal)", receptor, parte),
+                    TipoCs::Booleano,
+                )
+            }
+            "Substituir" => {
+                let antigo_literal =
+                    matches!(args.first(), Some(ast::Expressao::Texto(t)) if !t.is_empty());
+                let [antigo, novo] = self.textos(args);
+                if antigo_literal {
+                    Cs::chamada(
+                        format!("{}.Replace({}, {})", receptor, antigo, novo),
+                        TipoCs::Texto,
+                    )
+                } else {
+                    // Texto antigo vazio: o `replace` do interpretador não falha
+                    Cs::chamada(
+                        format!(
+                            "{}.Substituir({}, {}, {})",
+                            self.bcl("Runtime"),
+                            texto.codigo,
+                            antigo,
+                            novo
+                        ),
+                        TipoCs::Texto,
+                    )
+                }
+            }
+            _ => self.falhar(&erro_metodo_texto(metodo)),
         }
     }
 
-    fn generate_expressao(&self, expr: &ast::Expressao) -> String {
-        match expr {
-            ast::Expressao::Texto(s) => format!("{}", s),
-            ast::Expressao::Inteiro(n) => n.to_string(),
-            ast::Expressao::Decimal(d) => format!("{}m", d),
-            ast::Expressao::Identificador(name) => name.clone(),
-            ast::Expressao::Aritmetica(ast::OperadorAritmetico::Soma, esq, dir) => {
+    /// Métodos de `Sistema.Colecoes.Lista`, como na VM de bytecode.
+    fn metodo_lista(
+        &mut self,
+        lista: Cs,
+        item: &TipoCs,
+        metodo: &str,
+        args: &[ast::Expressao],
+    ) -> Cs {
+        let receptor = lista.operando(PRIMARIA);
+        let argumento = |gerador: &mut Self, tipo: &TipoCs| -> Cs {
+            match args.first() {
+                Some(arg) => {
+                    let valor = gerador.expr(arg);
+                    gerador.converter(valor, tipo)
+                }
+                None => Cs::primaria(format!("default({})", gerador.nome_cs(tipo)), tipo.clone()),
+            }
+        };
+        match metodo {
+            "Adicionar" => {
+                let valor = argumento(self, item);
+                Cs::chamada(format!("{}.Add({})", receptor, valor.codigo), TipoCs::Vazio)
+            }
+            "Obter" => {
+                let indice = argumento(self, &TipoCs::Inteiro);
+                let indice = self.indice_int(indice);
+                Cs::primaria(format!("{}[{}]", receptor, indice), item.clone())
+            }
+            "Tamanho" | "Contagem" => Cs::primaria(format!("{}.Count", receptor), TipoCs::Inteiro),
+            "Remover" | "RemoverEm" => {
+                let indice = argumento(self, &TipoCs::Inteiro);
+                let indice = self.indice_int(indice);
+                Cs::chamada(format!("{}.RemoveAt({})", receptor, indice), TipoCs::Vazio)
+            }
+            "Limpar" => Cs::chamada(format!("{}.Clear()", receptor), TipoCs::Vazio),
+            "Contem" => {
+                let valor = argumento(self, item);
+                Cs::chamada(
+                    format!("{}.Contains({})", receptor, valor.codigo),
+                    TipoCs::Booleano,
+                )
+            }
+            _ => self.falhar(&format!("Método '{}' não existe em Lista", metodo)),
+        }
+    }
+
+    /// Funções nativas do interpretador (`runtime::nativos`) como chamadas da BCL.
+    fn nativo(&mut self, chave: &str, args: &[ast::Expressao]) -> Cs {
+        let chamada = |codigo: String, tipo: TipoCs| Cs::chamada(codigo, tipo);
+        let tarefa = |tipo: TipoCs| TipoCs::Tarefa(Box::new(tipo));
+        match chave {
+            "Console::EscreverLinha" | "Console::Escrever" => {
+                // Os argumentos são concatenados, como no interpretador
+                let metodo = if chave == "Console::EscreverLinha" {
+                    "WriteLine"
+                } else {
+                    "Write"
+                };
+                let textos: Vec<String> = args.iter().map(|a| self.texto_arg(a)).collect();
+                let texto = match textos.len() {
+                    0 if metodo == "WriteLine" => String::new(),
+                    0 => "\"\"".to_string(),
+                    _ => textos.join(" + "),
+                };
+                chamada(
+                    format!("{}.{}({})", self.bcl("Console"), metodo, texto),
+                    TipoCs::Vazio,
+                )
+            }
+            "Console::LerLinha" => {
+                chamada(format!("{}.LerLinha()", self.bcl("Runtime")), TipoCs::Texto)
+            }
+            "Arquivo::LerTexto" => {
+                let [caminho] = self.textos(args);
+                chamada(
+                    format!("{}.ReadAllText({})", self.bcl("File"), caminho),
+                    TipoCs::Texto,
+                )
+            }
+            "LerArquivoAssíncrono" | "Arquivo::LerTextoAssíncrono" => {
+                let [caminho] = self.textos(args);
+                chamada(
+                    format!("{}.ReadAllTextAsync({})", self.bcl("File"), caminho),
+                    tarefa(TipoCs::Texto),
+                )
+            }
+            "Arquivo::EscreverTexto" | "Arquivo::AdicionarTexto" => {
+                let [caminho, conteudo] = self.textos(args);
+                let metodo = if chave == "Arquivo::EscreverTexto" {
+                    "WriteAllText"
+                } else {
+                    "AppendAllText"
+                };
+                chamada(
+                    format!("{}.{}({}, {})", self.bcl("File"), metodo, caminho, conteudo),
+                    TipoCs::Vazio,
+                )
+            }
+            "EscreverArquivoAssíncrono"
+            | "Arquivo::EscreverTextoAssíncrono"
+            | "AdicionarTextoAssíncrono"
+            | "Arquivo::AdicionarTextoAssíncrono" => {
+                let [caminho, conteudo] = self.textos(args);
+                let metodo = if chave.contains("Escrever") {
+                    "WriteAllTextAsync"
+                } else {
+                    "AppendAllTextAsync"
+                };
+                chamada(
+                    format!("{}.{}({}, {})", self.bcl("File"), metodo, caminho, conteudo),
+                    tarefa(TipoCs::Vazio),
+                )
+            }
+            "Arquivo::Existe" => {
+                let [caminho] = self.textos(args);
+                chamada(
+                    format!("{}.Exists({})", self.bcl("File"), caminho),
+                    TipoCs::Booleano,
+                )
+            }
+            "VerificarArquivoAssíncrono" | "Arquivo::ExisteAssíncrono" => {
+                let [caminho] = self.textos(args);
+                chamada(
+                    format!(
+                        "{}.FromResult({}.Exists({}))",
+                        self.bcl("Task"),
+                        self.bcl("File"),
+                        caminho
+                    ),
+                    tarefa(TipoCs::Booleano),
+                )
+            }
+            "Arquivo::Excluir" => {
+                let [caminho] = self.textos(args);
+                chamada(
+                    format!("{}.Delete({})", self.bcl("File"), caminho),
+                    TipoCs::Vazio,
+                )
+            }
+            "Arquivo::Copiar" | "Arquivo::Mover" => {
+                let [origem, destino] = self.textos(args);
+                let metodo = if chave == "Arquivo::Copiar" {
+                    "Copy"
+                } else {
+                    "Move"
+                };
+                chamada(
+                    format!(
+                        "{}.{}({}, {}, true)",
+                        self.bcl("File"),
+                        metodo,
+                        origem,
+                        destino
+                    ),
+                    TipoCs::Vazio,
+                )
+            }
+            "Diretorio::Existe" => {
+                let [caminho] = self.textos(args);
+                chamada(
+                    format!("{}.Exists({})", self.bcl("Directory"), caminho),
+                    TipoCs::Booleano,
+                )
+            }
+            "Diretorio::Criar" => {
+                let [caminho] = self.textos(args);
+                chamada(
+                    format!("{}.CreateDirectory({})", self.bcl("Directory"), caminho),
+                    TipoCs::Vazio,
+                )
+            }
+            "Diretorio::Excluir" => {
+                let [caminho] = self.textos(args);
+                let recursivo = match args.get(1) {
+                    Some(arg) => {
+                        let valor = self.expr(arg);
+                        self.converter(valor, &TipoCs::Booleano).codigo
+                    }
+                    None => "false".to_string(),
+                };
+                chamada(
+                    format!(
+                        "{}.Delete({}, {})",
+                        self.bcl("Directory"),
+                        caminho,
+                        recursivo
+                    ),
+                    TipoCs::Vazio,
+                )
+            }
+            "Diretorio::ObterAtual" => chamada(
+                format!("{}.GetCurrentDirectory()", self.bcl("Directory")),
+                TipoCs::Texto,
+            ),
+            "Diretorio::DefinirAtual" => {
+                let [caminho] = self.textos(args);
+                chamada(
+                    format!("{}.SetCurrentDirectory({})", self.bcl("Directory"), caminho),
+                    TipoCs::Vazio,
+                )
+            }
+            _ if chave.contains("::") || chave.ends_with("Assíncrono") => {
+                self.falhar(&format!("Função nativa '{}' não implementada", chave))
+            }
+            _ => self.falhar(&format!("Função '{}' não encontrada", chave)),
+        }
+    }
+
+    fn interpolacao(&mut self, partes: &[ast::PartStringInterpolada]) -> Cs {
+        let mut codigo = String::from("$\"");
+        for parte in partes {
+            match parte {
+                ast::PartStringInterpolada::Texto(texto) => codigo.push_str(&escapar(texto, true)),
+                ast::PartStringInterpolada::Expressao(expr) => {
+                    let valor = self.expr(expr);
+                    let valor = match valor.tipo {
+                        TipoCs::Texto | TipoCs::Inteiro => valor,
+                        _ => self.texto_de(valor),
+                    };
+                    // `:` e `?` encerrariam a expressão na interpolação do C#
+                    if valor.codigo.contains([':', '?']) {
+                        codigo.push_str(&format!("{{({})}}", valor.codigo));
+                    } else {
+                        codigo.push_str(&format!("{{{}}}", valor.codigo));
+                    }
+                }
+            }
+        }
+        codigo.push('"');
+        Cs::primaria(codigo, TipoCs::Texto)
+    }
+
+    /// Texto exibido para o valor, como no `imprima` do interpretador.
+    fn texto_de(&self, valor: Cs) -> Cs {
+        match valor.tipo {
+            TipoCs::Texto => valor,
+            TipoCs::Nulo => Cs::primaria("\"nulo\"", TipoCs::Texto),
+            _ => Cs::chamada(
+                format!("{}.Texto({})", self.bcl("Runtime"), objeto_de(valor)),
+                TipoCs::Texto,
+            ),
+        }
+    }
+
+    /// Operando numérico: enumerações viram `long` e valores dinâmicos, `dynamic`.
+    fn numero(&mut self, valor: Cs) -> Cs {
+        match &valor.tipo {
+            TipoCs::Enum(_) | TipoCs::Booleano => self.converter(valor, &TipoCs::Inteiro),
+            tipo if tipo.numerico() => valor,
+            _ => Cs::novo(
+                format!("(dynamic){}", valor.operando(UNARIA)),
+                TipoCs::Objeto,
+                UNARIA,
+            ),
+        }
+    }
+
+    fn aritmetica(
+        &mut self,
+        op: &ast::OperadorAritmetico,
+        esq: &ast::Expressao,
+        dir: &ast::Expressao,
+    ) -> Cs {
+        let a = self.expr(esq);
+        let b = self.expr(dir);
+        if *op == ast::OperadorAritmetico::Soma
+            && (a.tipo == TipoCs::Texto || b.tipo == TipoCs::Texto)
+        {
+            // Concatenação: o outro lado é exibido como no interpretador
+            let texto = |gerador: &Self, v: Cs| match v.tipo {
+                TipoCs::Texto | TipoCs::Inteiro => v,
+                _ => gerador.texto_de(v),
+            };
+            let a = texto(self, a);
+            let b = texto(self, b);
+            return Cs::novo(
+                format!("{} + {}", a.operando(ADITIVA), b.operando(ADITIVA + 1)),
+                TipoCs::Texto,
+                ADITIVA,
+            );
+        }
+        let mut a = self.numero(a);
+        let b = self.numero(b);
+        let tipo = if a.tipo.dinamico() || b.tipo.dinamico() {
+            TipoCs::Objeto
+        } else if a.tipo == TipoCs::Decimal || b.tipo == TipoCs::Decimal {
+            TipoCs::Decimal
+        } else if a.tipo == TipoCs::Duplo || b.tipo == TipoCs::Duplo {
+            TipoCs::Duplo
+        } else if a.tipo == TipoCs::Flutuante || b.tipo == TipoCs::Flutuante {
+            TipoCs::Flutuante
+        } else {
+            TipoCs::Inteiro
+        };
+        // Constantes inteiras são `int` no C#: o produto de duas poderia estourar
+        if a.literal_inteiro() && b.literal_inteiro() {
+            a.codigo.push('L');
+        }
+        let (a, b) = if tipo == TipoCs::Objeto {
+            (a, b)
+        } else {
+            (self.converter(a, &tipo), self.converter(b, &tipo))
+        };
+        let (texto, prec) = match op {
+            ast::OperadorAritmetico::Soma => ("+", ADITIVA),
+            ast::OperadorAritmetico::Subtracao => ("-", ADITIVA),
+            ast::OperadorAritmetico::Multiplicacao => ("*", MULTIPLICATIVA),
+            ast::OperadorAritmetico::Divisao => ("/", MULTIPLICATIVA),
+            ast::OperadorAritmetico::Modulo => {
+                if tipo == TipoCs::Inteiro {
+                    // O interpretador distingue "Módulo por zero" de "Divisão por zero"
+                    return Cs::chamada(
+                        format!("{}.Resto({}, {})", self.bcl("Runtime"), a.codigo, b.codigo),
+                        TipoCs::Inteiro,
+                    );
+                }
+                ("%", MULTIPLICATIVA)
+            }
+        };
+        Cs::novo(
+            format!("{} {} {}", a.operando(prec), texto, b.operando(prec + 1)),
+            tipo,
+            prec,
+        )
+    }
+
+    fn comparacao(
+        &mut self,
+        op: &ast::OperadorComparacao,
+        esq: &ast::Expressao,
+        dir: &ast::Expressao,
+    ) -> Cs {
+        let a = self.expr(esq);
+        let b = self.expr(dir);
+        let igualdade = matches!(
+            op,
+            ast::OperadorComparacao::Igual | ast::OperadorComparacao::Diferente
+        );
+        let diferente = *op == ast::OperadorComparacao::Diferente;
+        if igualdade {
+            let mesmo_tipo = a.tipo == b.tipo && !a.tipo.dinamico();
+            let nulo = a.tipo == TipoCs::Nulo || b.tipo == TipoCs::Nulo;
+            let referencias = |t: &TipoCs| {
+                matches!(
+                    t,
+                    TipoCs::Classe(..)
+                        | TipoCs::Interface(..)
+                        | TipoCs::Array(_)
+                        | TipoCs::Lista(_)
+                )
+            };
+            if mesmo_tipo || (nulo && !a.tipo.valor() && !b.tipo.valor()) {
+                let texto = if diferente { "!=" } else { "==" };
+                return Cs::novo(
+                    format!(
+                        "{} {} {}",
+                        a.operando(IGUALDADE),
+                        texto,
+                        b.operando(IGUALDADE + 1)
+                    ),
+                    TipoCs::Booleano,
+                    IGUALDADE,
+                );
+            }
+            let codigo = if referencias(&a.tipo) && referencias(&b.tipo) {
+                format!("object.ReferenceEquals({}, {})", a.codigo, b.codigo)
+            } else {
+                // Valores de tipos diferentes nunca são iguais
                 format!(
-                    "{} + {}",
-                    self.generate_expressao(esq),
-                    self.generate_expressao(dir)
+                    "{}.Iguais({}, {})",
+                    self.bcl("Runtime"),
+                    objeto_de(a),
+                    objeto_de(b)
                 )
+            };
+            return if diferente {
+                Cs::novo(format!("!{}", codigo), TipoCs::Booleano, UNARIA)
+            } else {
+                Cs::chamada(codigo, TipoCs::Booleano)
+            };
+        }
+        let texto = match op {
+            ast::OperadorComparacao::Menor => "<",
+            ast::OperadorComparacao::MaiorQue => ">",
+            ast::OperadorComparacao::MenorIgual => "<=",
+            _ => ">=",
+        };
+        if a.tipo == TipoCs::Texto && b.tipo == TipoCs::Texto {
+            return Cs::novo(
+                format!(
+                    "string.CompareOrdinal({}, {}) {} 0",
+                    a.codigo, b.codigo, texto
+                ),
+                TipoCs::Booleano,
+                RELACIONAL,
+            );
+        }
+        let a = self.numero(a);
+        let b = self.numero(b);
+        let (a, b) = match (&a.tipo, &b.tipo) {
+            (TipoCs::Decimal, TipoCs::Duplo | TipoCs::Flutuante) => {
+                let b = self.converter(b, &TipoCs::Decimal);
+                (a, b)
             }
-            _ => format!("ERRO: Expressao {:?} nao suportada", expr),
+            (TipoCs::Duplo | TipoCs::Flutuante, TipoCs::Decimal) => {
+                let a = self.converter(a, &TipoCs::Decimal);
+                (a, b)
+            }
+            _ => (a, b),
+        };
+        Cs::novo(
+            format!(
+                "{} {} {}",
+                a.operando(RELACIONAL),
+                texto,
+                b.operando(RELACIONAL + 1)
+            ),
+            TipoCs::Booleano,
+            RELACIONAL,
+        )
+    }
+
+    /// Converte o valor para o tipo `para`, com as conversões explícitas que o C# exige.
+    fn converter(&self, valor: Cs, para: &TipoCs) -> Cs {
+        let de = valor.tipo.clone();
+        if matches!(para, TipoCs::Objeto) {
+            let codigo = objeto_de(valor.clone());
+            return Cs {
+                codigo,
+                tipo: para.clone(),
+                ..valor
+            };
+        }
+        if self.atribuivel(&de, para) {
+            return valor;
+        }
+        if de == TipoCs::Nulo {
+            return Cs::primaria(format!("default({})", self.nome_cs(para)), para.clone());
+        }
+        if *para == TipoCs::Texto {
+            return self.texto_de(valor);
+        }
+        let conversao = |v: &Cs, tipo: &str| {
+            Cs::novo(
+                format!("({}){}", tipo, v.operando(UNARIA)),
+                para.clone(),
+                UNARIA,
+            )
+        };
+        let nome = self.nome_cs(para);
+        match (&de, para) {
+            // Valores dinâmicos e de classes base passam pela conversão de referência
+            (TipoCs::Enum(_), TipoCs::Inteiro) => conversao(&valor, "long"),
+            (TipoCs::Booleano, TipoCs::Inteiro) => Cs::novo(
+                format!("{} ? 1L : 0L", valor.operando(RELACIONAL)),
+                TipoCs::Inteiro,
+                2,
+            ),
+            (de, para) if de.numerico() && para.numerico() => conversao(&valor, &nome),
+            (TipoCs::Inteiro, TipoCs::Enum(_)) => conversao(&valor, &nome),
+            (TipoCs::Vazio, _) => valor,
+            (TipoCs::Objeto | TipoCs::Generico(_), _)
+            | (TipoCs::Classe(..) | TipoCs::Interface(..), _) => conversao(&valor, &nome),
+            _ => Cs::novo(
+                format!("({})(object){}", nome, valor.operando(UNARIA)),
+                para.clone(),
+                UNARIA,
+            ),
         }
     }
+}
 
-    fn map_type(&self, tipo: &ast::Tipo) -> &str {
-        match tipo {
-            ast::Tipo::Inteiro => "int",
-            ast::Tipo::Texto => "string",
-            ast::Tipo::Booleano => "bool",
-            ast::Tipo::Decimal => "decimal",
-            _ => "object",
+/// Valor usado como `object`: constantes inteiras ganham o sufixo `L` (a caixa é de `long`).
+fn objeto_de(valor: Cs) -> String {
+    if valor.literal_inteiro() {
+        if valor.prec < PRIMARIA {
+            format!("({}L)", valor.codigo)
+        } else {
+            format!("{}L", valor.codigo)
         }
+    } else {
+        valor.codigo
+    }
+}
+
+/// Código de uma expressão usada como comando; valores são descartados com `_ =`.
+fn como_instrucao(valor: Cs) -> String {
+    if valor.instrucao || valor.tipo == TipoCs::Vazio {
+        valor.codigo
+    } else {
+        format!("_ = {}", valor.codigo)
+    }
+}
+
+fn padrao(tipo: &TipoCs, nome: &str) -> String {
+    match tipo {
+        TipoCs::Inteiro => "0".to_string(),
+        TipoCs::Booleano => "false".to_string(),
+        TipoCs::Flutuante => "0f".to_string(),
+        TipoCs::Duplo => "0.0".to_string(),
+        TipoCs::Decimal => "0m".to_string(),
+        // Variáveis de texto começam vazias, como no interpretador
+        TipoCs::Texto => "\"\"".to_string(),
+        TipoCs::Enum(_) | TipoCs::Generico(_) => format!("default({})", nome),
+        _ => "null".to_string(),
+    }
+}
+
+/// `private` não pode ser `virtual` no C#: métodos privados redefiníveis ficam `protected`.
+fn acesso_virtual(modificador: &ast::ModificadorAcesso) -> &'static str {
+    match modificador {
+        ast::ModificadorAcesso::Privado => "protected",
+        modificador => acesso(modificador),
+    }
+}
+
+fn modificar_acessor(acessor: &ast::AcessorPropriedade, palavra: &str) -> String {
+    match &acessor.modificador {
+        Some(modificador) => format!("{} {};", acesso(modificador), palavra),
+        None => format!("{};", palavra),
+    }
+}
+
+fn parametros_genericos(parametros: &[String]) -> String {
+    if parametros.is_empty() {
+        String::new()
+    } else {
+        let nomes: Vec<String> = parametros.iter().map(|p| identificador(p)).collect();
+        format!("<{}>", nomes.join(", "))
+    }
+}
+
+/// Troca os parâmetros de tipo `parametros` pelos argumentos `args`.
+fn substituir(tipo: &TipoCs, parametros: &[String], args: &[TipoCs]) -> TipoCs {
+    match tipo {
+        TipoCs::Generico(nome) => parametros
+            .iter()
+            .position(|p| p == nome)
+            .and_then(|i| args.get(i).cloned())
+            .unwrap_or_else(|| tipo.clone()),
+        TipoCs::Array(item) => TipoCs::Array(Box::new(substituir(item, parametros, args))),
+        TipoCs::Lista(item) => TipoCs::Lista(Box::new(substituir(item, parametros, args))),
+        TipoCs::Tarefa(item) => TipoCs::Tarefa(Box::new(substituir(item, parametros, args))),
+        TipoCs::Classe(fqn, a) => TipoCs::Classe(
+            fqn.clone(),
+            a.iter().map(|t| substituir(t, parametros, args)).collect(),
+        ),
+        TipoCs::Interface(fqn, a) => TipoCs::Interface(
+            fqn.clone(),
+            a.iter().map(|t| substituir(t, parametros, args)).collect(),
+        ),
+        tipo => tipo.clone(),
+    }
+}
+
+/// Retorno de uma chamada a método genérico: os parâmetros de tipo do método são inferidos
+/// pelo C#, então o valor é tratado como `objeto`.
+fn sem_genericos(tipo: &TipoCs, parametros: &[String]) -> TipoCs {
+    match tipo {
+        TipoCs::Generico(nome) if parametros.contains(nome) => TipoCs::Objeto,
+        tipo => tipo.clone(),
     }
 }
@@ -1,6 +1,22 @@
 use crate::ast;
+use crate::paralelo;
 use crate::type_checker;
 use std::collections::HashMap;
+use std::sync::Mutex;
+
+mod debug_info;
+mod externo;
+
+/// Instanciações genéricas em ordem estável: por nome da classe (ou interface) e, para a mesma,
+/// na ordem em que foram encontradas.
+fn instancias_ordenadas(mapa: &HashMap<String, Vec<Vec<ast::Tipo>>>) -> Vec<(String, Vec<ast::Tipo>)> {
+    let mut nomes: Vec<&String> = mapa.keys().collect();
+    nomes.sort();
+    nomes
+        .into_iter()
+        .flat_map(|nome| mapa[nome].iter().map(move |args| (nome.clone(), args.clone())))
+        .collect()
+}
 
 /// O gerador de código para o alvo LLVM IR.
 pub struct LlvmGenerator<'a> {
@@ -28,6 +44,30 @@ pub struct LlvmGenerator<'a> {
     /// Símbolos de trampolins que adaptam funções assíncronas da linguagem à
     /// assinatura `void* (void*)` exigida pelo runtime C.
     async_wrapper_symbols: HashMap<String, String>,
+    /// Definições desses trampolins (FQN, texto), emitidas entre o header e o corpo.
+    async_wrappers: Vec<(String, String)>,
+    /// Entra no nome das globais de texto (`@.str`, `@.chk`) de cada gerador de declaração, que
+    /// trabalham em paralelo (ver `generate_definitions`); vazio no gerador principal.
+    prefixo_globais: String,
+    /// Metadados DWARF, quando habilitados com `enable_debug_info` (`--debug-info`).
+    debug: Option<debug_info::DebugInfo>,
+    /// Em programas, só `main` precisa ser visível fora do módulo (`generate`); bibliotecas
+    /// exportam todas as funções (`generate_for_library`).
+    linkage_interna: bool,
+    /// Globais (símbolo, tipo) que podem apontar para o heap do coletor: campos estáticos.
+    gc_roots: Vec<(String, String)>,
+    /// Verificações de execução (índices, referências nulas, divisão por zero); desligadas por
+    /// `disable_runtime_checks` (`--sem-verificacoes`).
+    verificacoes: bool,
+    /// Posição do último `Comando::Posicao`, informada ao `pds_panico` quando uma verificação falha.
+    posicao_atual: Option<ast::Posicao>,
+    /// Constantes de texto das verificações (mensagens e arquivos), emitidas uma vez cada.
+    textos_verificacao: HashMap<String, String>,
+    /// Bibliotecas C das funções `[Externo(...)]`, repassadas ao clang como `-l`.
+    bibliotecas_externas: Vec<String>,
+    /// Construções que o alvo não sabe gerar. A geração continua com um valor qualquer e
+    /// `generate` devolve os erros, como o verificador de tipos faz com os seus.
+    erros: Mutex<Vec<String>>,
 }
 
 impl<'a> LlvmGenerator<'a> {
@@ -52,10 +92,63 @@ impl<'a> LlvmGenerator<'a> {
             applied_class_insts: HashMap::new(),
             applied_iface_insts: HashMap::new(),
             async_wrapper_symbols: HashMap::new(),
+            async_wrappers: Vec::new(),
+            prefixo_globais: String::new(),
+            debug: None,
+            linkage_interna: false,
+            gc_roots: Vec::new(),
+            verificacoes: true,
+            posicao_atual: None,
+            textos_verificacao: HashMap::new(),
+            bibliotecas_externas: Vec::new(),
+            erros: Mutex::default(),
+        }
+    }
+
+    /// Gera acessos por índice, a membros e divisões sem as verificações de execução.
+    pub fn disable_runtime_checks(&mut self) {
+        self.verificacoes = false;
+    }
+
+    /// Prefixo de linkage dos `define`: `internal` permite ao otimizador remover, especializar
+    /// e fazer inlining de funções que não escapam do módulo.
+    fn linkage(&self) -> &'static str {
+        if self.linkage_interna {
+            "internal "
+        } else {
+            ""
+        }
+    }
+
+    /// Registra um erro de geração e devolve `valor` no lugar do que não pôde ser gerado.
+    fn falhar<T>(&self, mensagem: String, valor: T) -> T {
+        self.registrar_erros(vec![mensagem]);
+        valor
+    }
+
+    /// `falhar` para expressões: um `inteiro` converte para qualquer tipo que venha depois.
+    fn falhar_expressao(&self, mensagem: String) -> (String, ast::Tipo) {
+        self.falhar(mensagem, ("0".to_string(), ast::Tipo::Inteiro))
+    }
+
+    fn registrar_erros(&self, erros: Vec<String>) {
+        if let Ok(mut registrados) = self.erros.lock() {
+            registrados.extend(erros);
+        }
+    }
+
+    /// O módulo montado, ou os erros de geração, um por linha.
+    fn resultado(&mut self, modulo: String) -> Result<String, String> {
+        let erros = std::mem::take(self.erros.get_mut().unwrap_or_else(|e| e.into_inner()));
+        if erros.is_empty() {
+            Ok(modulo)
+        } else {
+            Err(erros.join("\n"))
         }
     }
 
-    pub fn generate(&mut self) -> String {
+    pub fn generate(&mut self) -> Result<String, String> {
+        self.linkage_interna = true;
         // Coleta instâncias genéricas (Aplicado) usadas no programa, antes de gerar tipos
         self.collect_applied_instantiations();
         self.prepare_header();
@@ -71,20 +164,7 @@ impl<'a> LlvmGenerator<'a> {
         self.define_static_globals();
 
         // Gera definições de funções e classes.
-        for declaracao in &self.programa.declaracoes {
-            match declaracao {
-                ast::Declaracao::DeclaracaoFuncao(func) => {
-                    self.generate_funcao(func, "");
-                }
-                ast::Declaracao::DeclaracaoClasse(class) => {
-                    self.generate_classe_definitions(class, "");
-                }
-                _ => {}
-            }
-        }
-        for ns in &self.programa.namespaces {
-            self.generate_namespace_definitions(ns);
-        }
+        self.generate_definitions();
 
         // Gera métodos para classes genéricas aplicadas (monomorfização)
         self.generate_applied_class_methods();
@@ -97,6 +177,11 @@ impl<'a> LlvmGenerator<'a> {
 
         self.body.push_str("define i32 @main() {\n");
         self.body.push_str("entry:\n");
+        let primeira_posicao = self.programa.declaracoes.iter().find_map(|d| match d {
+            ast::Declaracao::Comando(ast::Comando::Posicao(p)) => Some(p),
+            _ => None,
+        });
+        self.debug_begin_function("main", "main", &ast::Tipo::Inteiro, &[], primeira_posicao);
 
         // Comandos globais (top-level) no namespace raiz
         for decl in &self.programa.declaracoes {
@@ -113,19 +198,24 @@ impl<'a> LlvmGenerator<'a> {
 
         self.body.push_str("  ret i32 0\n");
         self.body.push_str("}\n");
+        self.debug_finish_function();
+        self.hoist_allocas_to_entry();
 
         // Anexa e restaura
         old_body.push_str(&self.body);
         self.body = old_body;
         self.variables = old_vars;
 
-        format!("{}{}", self.header, self.body)
+        self.register_gc_roots();
+        let modulo = self.montar_modulo();
+        self.resultado(modulo)
     }
 
-    pub fn generate_for_library(&mut self) -> String {
+    pub fn generate_for_library(&mut self) -> Result<String, String> {
         // Coleta instâncias genéricas (Aplicado) usadas no programa
         self.collect_applied_instantiations();
         self.prepare_header();
+        self.declare_foreign_functions();
         // Constrói vtables antes de definir structs
         self.build_all_vtables();
         self.define_all_structs();
@@ -137,25 +227,14 @@ impl<'a> LlvmGenerator<'a> {
         self.define_static_globals();
 
         // Gera definições de funções e classes (mas SEM função main)
-        for declaracao in &self.programa.declaracoes {
-            match declaracao {
-                ast::Declaracao::DeclaracaoFuncao(func) => {
-                    self.generate_funcao(func, "");
-                }
-                ast::Declaracao::DeclaracaoClasse(class) => {
-                    self.generate_classe_definitions(class, "");
-                }
-                _ => {}
-            }
-        }
-        for ns in &self.programa.namespaces {
-            self.generate_namespace_definitions(ns);
-        }
+        self.generate_definitions();
         
         // Gera métodos para classes genéricas aplicadas (monomorfização)
         self.generate_applied_class_methods();
 
-        format!("{}{}", self.header, self.body)
+        self.register_gc_roots();
+        let modulo = self.montar_modulo();
+        self.resultado(modulo)
     }
 
     // Nome canônico e estável para tipos em mangling
@@ -362,12 +441,7 @@ impl<'a> LlvmGenerator<'a> {
 
     fn define_all_applied_interface_structs(&mut self) {
         // snapshot para evitar empréstimos conflitantes
-        let mut items: Vec<(String, Vec<ast::Tipo>)> = Vec::new();
-        for (iface_fqn, insts) in &self.applied_iface_insts {
-            for args in insts {
-                items.push((iface_fqn.clone(), args.clone()));
-            }
-        }
+        let items = instancias_ordenadas(&self.applied_iface_insts);
         for (iface_fqn, args) in items {
             let mangled = self.mangle_aplicado_name(&iface_fqn, &args);
             let sanitized = mangled.replace('.', "_");
@@ -380,9 +454,9 @@ impl<'a> LlvmGenerator<'a> {
         // Procura no escopo global
         for decl in &self.programa.declaracoes {
             if let ast::Declaracao::DeclaracaoFuncao(func) = decl {
-                if func.nome == "Principal" {
+                if func.nome == "Principal" || func.nome == "principal" {
                     // No global, FQN é apenas o nome simples
-                    return Some("Principal".to_string());
+                    return Some(func.nome.clone());
                 }
             }
         }
@@ -390,7 +464,7 @@ impl<'a> LlvmGenerator<'a> {
         for ns in &self.programa.namespaces {
             for decl in &ns.declaracoes {
                 if let ast::Declaracao::DeclaracaoFuncao(func) = decl {
-                    if func.nome == "Principal" {
+                    if func.nome == "Principal" || func.nome == "principal" {
                         return Some(format!("{}.{}", ns.nome, func.nome));
                     }
                 }
@@ -409,12 +483,7 @@ impl<'a> LlvmGenerator<'a> {
 
         // Define structs especializados para classes aplicadas (monomorfização superficial)
         // snapshot para evitar empréstimo duplo
-        let mut items: Vec<(String, Vec<ast::Tipo>)> = Vec::new();
-        for (base_fqn, insts) in &self.applied_class_insts {
-            for args in insts {
-                items.push((base_fqn.clone(), args.clone()));
-            }
-        }
+        let items = instancias_ordenadas(&self.applied_class_insts);
         for (base_fqn, args) in items {
             self.define_applied_struct(&base_fqn, &args);
         }
@@ -484,12 +553,7 @@ impl<'a> LlvmGenerator<'a> {
     
     fn generate_applied_class_methods(&mut self) {
         // Para cada instanciação de classe genérica coletada, gera métodos especializados
-        let mut applied_items: Vec<(String, Vec<ast::Tipo>)> = Vec::new();
-        for (base_fqn, insts) in &self.applied_class_insts {
-            for args in insts {
-                applied_items.push((base_fqn.clone(), args.clone()));
-            }
-        }
+        let applied_items = instancias_ordenadas(&self.applied_class_insts);
         
         for (base_fqn, args) in applied_items {
             let class_decl = match self.type_checker.classes.get(&base_fqn) {
@@ -550,13 +614,18 @@ impl<'a> LlvmGenerator<'a> {
         let tipo_retorno_llvm = self.map_type_to_llvm_arg(&tipo_retorno_resolvido);
         
         let mut params_llvm = Vec::new();
-        let self_type = self.map_type_to_llvm_ptr(&ast::Tipo::Classe(mangled_name.to_string()));
-        params_llvm.push(format!("{0} %param.self", self_type));
+        let self_tipo = ast::Tipo::Classe(mangled_name.to_string());
+        let mut tipos_params = vec![self_tipo.clone()];
+        let self_type = self.map_type_to_llvm_ptr(&self_tipo);
+        // Chamar método em `nulo` é erro nos outros backends; aqui `este` é assumido não nulo
+        let atributos_self = if metodo.eh_estatica { "" } else { "nonnull " };
+        params_llvm.push(format!("{0} {1}%param.self", self_type, atributos_self));
         
         for param in &metodo.parametros {
             let tipo_param_subst = self.subst_generics_local(&param.tipo, &subst);
             let tipo_param_resolvido = self.resolve_type(&tipo_param_subst, &namespace);
             let tipo_param_llvm = self.map_type_to_llvm_arg(&tipo_param_resolvido);
+            tipos_params.push(tipo_param_resolvido);
             params_llvm.push(format!("{0} %param.{1}", tipo_param_llvm, param.nome));
         }
         
@@ -566,12 +635,20 @@ impl<'a> LlvmGenerator<'a> {
         self.variables.clear();
         
         self.body.push_str(&format!(
-            "define {0} @\"{1}\"({2}) {{ \n",
+            "define {3}{0} @\"{1}\"({2}) {{ \n",
             tipo_retorno_llvm,
             nome_metodo,
-            params_llvm.join(", ")
+            params_llvm.join(", "),
+            self.linkage()
         ));
         self.body.push_str("entry:\n");
+        self.debug_begin_function(
+            &format!("{}.{}", mangled_name, metodo.nome),
+            &nome_metodo,
+            &tipo_retorno_resolvido,
+            &tipos_params,
+            debug_info::first_position(&metodo.corpo),
+        );
         
         let self_ptr_reg = "%var.self".to_string();
         self.body.push_str(&format!(
@@ -582,6 +659,7 @@ impl<'a> LlvmGenerator<'a> {
             "  store {0} %param.self, {0}* {1}\n",
             self_type, self_ptr_reg
         ));
+        self.debug_declare("este", &self_ptr_reg, &self_tipo, true);
         self.variables.insert(
             "self".to_string(),
             (self_ptr_reg, ast::Tipo::Classe(mangled_name.to_string())),
@@ -599,6 +677,7 @@ impl<'a> LlvmGenerator<'a> {
                 "  {0} = alloca {1}, align {2}\n",
                 ptr_reg, llvm_type, align
             ));
+            self.debug_declare(&param.nome, &ptr_reg, &tipo_param_resolvido, true);
             let param_reg = format!("%param.{}", param.nome);
             self.body.push_str(&format!(
                 "  store {0} {1}, {0}* {2}\n",
@@ -625,6 +704,8 @@ impl<'a> LlvmGenerator<'a> {
         }
         
         self.body.push_str("}\n");
+        self.debug_finish_function();
+        self.hoist_allocas_to_entry();
         old_body.push_str(&self.body);
         self.body = old_body;
         self.variables = old_vars;
@@ -649,13 +730,18 @@ impl<'a> LlvmGenerator<'a> {
         let tipo_retorno_llvm = "void".to_string();
         
         let mut params_llvm = Vec::new();
-        let self_type = self.map_type_to_llvm_ptr(&ast::Tipo::Classe(mangled_name.to_string()));
-        params_llvm.push(format!("{0} %param.self", self_type));
+        let self_tipo = ast::Tipo::Classe(mangled_name.to_string());
+        let mut tipos_params = vec![self_tipo.clone()];
+        let self_type = self.map_type_to_llvm_ptr(&self_tipo);
+        // O objeto acabou de ser alocado por `novo` e não é nulo. Não é `noalias`: o construtor
+        // pode guardar `este` em um campo ou estático e escrever por esse apelido
+        params_llvm.push(format!("{0} nonnull %param.self", self_type));
         
         for param in &construtor.parametros {
             let tipo_param_subst = self.subst_generics_local(&param.tipo, &subst);
             let tipo_param_resolvido = self.resolve_type(&tipo_param_subst, &namespace);
             let tipo_param_llvm = self.map_type_to_llvm_arg(&tipo_param_resolvido);
+            tipos_params.push(tipo_param_resolvido);
             params_llvm.push(format!("{0} %param.{1}", tipo_param_llvm, param.nome));
         }
         
@@ -665,12 +751,20 @@ impl<'a> LlvmGenerator<'a> {
         self.variables.clear();
         
         self.body.push_str(&format!(
-            "define {0} @\"{1}\"({2}) {{ \n",
+            "define {3}{0} @\"{1}\"({2}) {{ \n",
             tipo_retorno_llvm,
             nome_ctor,
-            params_llvm.join(", ")
+            params_llvm.join(", "),
+            self.linkage()
         ));
         self.body.push_str("entry:\n");
+        self.debug_begin_function(
+            &format!("{}.construtor", mangled_name),
+            &nome_ctor,
+            &ast::Tipo::Vazio,
+            &tipos_params,
+            debug_info::first_position(&construtor.corpo),
+        );
         
         // Aloca e armazena self
         let self_ptr_reg = "%var.self".to_string();
@@ -682,6 +776,7 @@ impl<'a> LlvmGenerator<'a> {
             "  store {0} %param.self, {0}* {1}\n",
             self_type, self_ptr_reg
         ));
+        self.debug_declare("este", &self_ptr_reg, &self_tipo, true);
         self.variables.insert(
             "self".to_string(),
             (self_ptr_reg, ast::Tipo::Classe(mangled_name.to_string())),
@@ -699,6 +794,7 @@ impl<'a> LlvmGenerator<'a> {
                 "  {0} = alloca {1}, align {2}\n",
                 ptr_reg, llvm_type, align
             ));
+            self.debug_declare(&param.nome, &ptr_reg, &tipo_param_resolvido, true);
             let param_reg = format!("%param.{}", param.nome);
             self.body.push_str(&format!(
                 "  store {0} {1}, {0}* {2}\n",
@@ -714,32 +810,153 @@ impl<'a> LlvmGenerator<'a> {
         
         self.body.push_str("  ret void\n");
         self.body.push_str("}\n");
+        self.debug_finish_function();
+        self.hoist_allocas_to_entry();
         old_body.push_str(&self.body);
         self.body = old_body;
         self.variables = old_vars;
     }
 
-    fn generate_namespace_definitions(&mut self, ns: &'a ast::DeclaracaoNamespace) {
-        let old_namespace = self.namespace_path.clone();
-        self.namespace_path = if old_namespace.is_empty() {
-            ns.nome.clone()
-        } else {
-            format!("{}.{}", old_namespace, ns.nome)
-        };
+    /// Gera as funções e classes do nível superior e dos namespaces, na ordem do fonte.
+    ///
+    /// Cada declaração vai para um gerador próprio (`gerador_de_declaracao`) e elas são geradas
+    /// em paralelo; os resultados são juntados na ordem das declarações, então o módulo não
+    /// depende do número de threads. Com `--debug-info`, os metadados são numerados em
+    /// sequência e tudo é gerado em um só gerador.
+    fn generate_definitions(&mut self) {
+        let programa = self.programa;
+        let declaracoes: Vec<(&'a str, &'a ast::Declaracao)> = programa
+            .declaracoes
+            .iter()
+            .map(|decl| ("", decl))
+            .chain(programa.namespaces.iter().flat_map(|ns| {
+                ns.declaracoes.iter().map(move |decl| (ns.nome.as_str(), decl))
+            }))
+            .filter(|(_, decl)| {
+                matches!(
+                    decl,
+                    ast::Declaracao::DeclaracaoFuncao(_) | ast::Declaracao::DeclaracaoClasse(_)
+                )
+            })
+            .collect();
 
-        for decl in &ns.declaracoes {
-            match decl {
-                ast::Declaracao::DeclaracaoFuncao(func) => {
-                    self.generate_funcao(func, &self.namespace_path.clone());
-                }
-                ast::Declaracao::DeclaracaoClasse(class) => {
-                    self.generate_classe_definitions(class, &self.namespace_path.clone());
+        if self.debug.is_some() {
+            let old_namespace = std::mem::take(&mut self.namespace_path);
+            for (namespace, decl) in declaracoes {
+                self.namespace_path = namespace.to_string();
+                self.generate_definition(namespace, decl);
+            }
+            self.namespace_path = old_namespace;
+            return;
+        }
+
+        let unidades: Vec<(usize, &'a str, &'a ast::Declaracao)> = declaracoes
+            .into_iter()
+            .enumerate()
+            .map(|(indice, (namespace, decl))| (indice, namespace, decl))
+            .collect();
+        let geradores = paralelo::mapear(&unidades, |&(indice, namespace, decl)| {
+            let mut gerador = self.gerador_de_declaracao(indice, namespace);
+            gerador.generate_definition(namespace, decl);
+            gerador
+        });
+        for gerador in geradores {
+            self.absorver(gerador);
+        }
+    }
+
+    fn generate_definition(&mut self, namespace: &str, decl: &'a ast::Declaracao) {
+        match decl {
+            ast::Declaracao::DeclaracaoFuncao(func) => self.generate_funcao(func, namespace),
+            ast::Declaracao::DeclaracaoClasse(class) => {
+                self.generate_classe_definitions(class, namespace)
+            }
+            _ => {}
+        }
+    }
+
+    /// Gerador para uma declaração: compartilha o que foi preparado antes das definições
+    /// (vtables, instanciações genéricas, trampolins já criados) e começa com header e corpo
+    /// vazios, que `absorver` junta aos do gerador principal.
+    fn gerador_de_declaracao(&self, indice: usize, namespace: &str) -> Self {
+        Self {
+            programa: self.programa,
+            type_checker: self.type_checker,
+            resolved_classes: self.resolved_classes,
+            header: String::new(),
+            body: String::new(),
+            string_counter: 0,
+            temp_counter: 0,
+            variables: HashMap::new(),
+            namespace_path: namespace.to_string(),
+            classe_atual: None,
+            vtables: self.vtables.clone(),
+            vtable_index: self.vtable_index.clone(),
+            applied_class_insts: self.applied_class_insts.clone(),
+            applied_iface_insts: self.applied_iface_insts.clone(),
+            async_wrapper_symbols: self.async_wrapper_symbols.clone(),
+            async_wrappers: Vec::new(),
+            prefixo_globais: format!("{}.", indice),
+            debug: None,
+            linkage_interna: self.linkage_interna,
+            gc_roots: Vec::new(),
+            verificacoes: self.verificacoes,
+            posicao_atual: None,
+            textos_verificacao: HashMap::new(),
+            bibliotecas_externas: Vec::new(),
+            erros: Mutex::default(),
+        }
+    }
+
+    /// Junta o que `gerador` produziu. Um trampolim criado por mais de uma declaração fica só
+    /// com a primeira definição.
+    fn absorver(&mut self, gerador: Self) {
+        self.registrar_erros(gerador.erros.into_inner().unwrap_or_default());
+        self.header.push_str(&gerador.header);
+        self.body.push_str(&gerador.body);
+        for (fqn, definicao) in gerador.async_wrappers {
+            if !self.async_wrapper_symbols.contains_key(&fqn) {
+                self.async_wrapper_symbols
+                    .insert(fqn.clone(), gerador.async_wrapper_symbols[&fqn].clone());
+                self.async_wrappers.push((fqn, definicao));
+            }
+        }
+        for (base_fqn, instancias) in gerador.applied_class_insts {
+            let conhecidas = self.applied_class_insts.entry(base_fqn).or_default();
+            for args in instancias {
+                if !conhecidas.contains(&args) {
+                    conhecidas.push(args);
                 }
-                _ => {}
             }
         }
+    }
 
-        self.namespace_path = old_namespace;
+    fn montar_modulo(&self) -> String {
+        let mut modulo = self.header.clone();
+        for (_, definicao) in &self.async_wrappers {
+            modulo.push_str(definicao);
+        }
+        modulo.push_str(&self.body);
+        modulo.push_str(&self.debug_metadata());
+        modulo
+    }
+
+    /// Registra os campos estáticos como raízes do coletor antes de `main`, via
+    /// `llvm.global_ctors` (vale também para bibliotecas, que não têm `main`).
+    fn register_gc_roots(&mut self) {
+        if self.gc_roots.is_empty() {
+            return;
+        }
+        self.body
+            .push_str("\ndefine internal void @pds_gc_registrar_estaticos() {\nentry:\n");
+        for (sym, ty) in &self.gc_roots {
+            self.body.push_str(&format!(
+                "  call void @pds_gc_adicionar_raiz(i8* bitcast ({0}* {1} to i8*), i64 8)\n",
+                ty, sym
+            ));
+        }
+        self.body.push_str("  ret void\n}\n");
+        self.body.push_str("@llvm.global_ctors = appending global [1 x { i32, void ()*, i8* }] [{ i32, void ()*, i8* } { i32 65535, void ()* @pds_gc_registrar_estaticos, i8* null }]\n");
     }
 
     fn define_static_globals(&mut self) {
@@ -755,6 +972,9 @@ impl<'a> LlvmGenerator<'a> {
                 if campo.eh_estatica {
                     let sym = this.static_global_symbol(fqn, &campo.nome);
                     let ty = this.map_type_to_llvm_storage(&campo.tipo);
+                    if ty.ends_with('*') {
+                        this.gc_roots.push((sym.clone(), ty.clone()));
+                    }
                     if let Some(init) = &campo.valor_inicial {
                         if let Some((val, _)) = this.const_llvm_init_for_expr(init, &campo.tipo) {
                             this.header.push_str(&format!(
@@ -780,6 +1000,9 @@ impl<'a> LlvmGenerator<'a> {
                 if prop.eh_estatica {
                     let sym = this.static_global_symbol(fqn, &prop.nome);
                     let ty = this.map_type_to_llvm_storage(&prop.tipo);
+                    if ty.ends_with('*') {
+                        this.gc_roots.push((sym.clone(), ty.clone()));
+                    }
                     if let Some(init) = &prop.valor_inicial {
                         if let Some((val, _)) = this.const_llvm_init_for_expr(init, &prop.tipo) {
                             this.header.push_str(&format!(
@@ -889,12 +1112,17 @@ impl<'a> LlvmGenerator<'a> {
         let tipo_retorno_llvm = "void".to_string();
 
         let mut params_llvm = Vec::new();
-        let self_type = self.map_type_to_llvm_ptr(&ast::Tipo::Classe(classe_nome.clone()));
-        params_llvm.push(format!("{0} %param.self", self_type));
+        let self_tipo = ast::Tipo::Classe(classe_nome.clone());
+        let mut tipos_params = vec![self_tipo.clone()];
+        let self_type = self.map_type_to_llvm_ptr(&self_tipo);
+        // O objeto acabou de ser alocado por `novo` e não é nulo. Não é `noalias`: o construtor
+        // pode guardar `este` em um campo ou estático e escrever por esse apelido
+        params_llvm.push(format!("{0} nonnull %param.self", self_type));
 
         for param in &construtor.parametros {
             let tipo_param_resolvido = self.resolve_type(&param.tipo, namespace);
             let tipo_param_llvm = self.map_type_to_llvm_arg(&tipo_param_resolvido);
+            tipos_params.push(tipo_param_resolvido);
             params_llvm.push(format!("{0} %param.{1}", tipo_param_llvm, param.nome));
         }
 
@@ -904,12 +1132,20 @@ impl<'a> LlvmGenerator<'a> {
         self.variables.clear();
 
         self.body.push_str(&format!(
-            "define {0} @\"{1}\"({2}) {{ \n",
+            "define {3}{0} @\"{1}\"({2}) {{ \n",
             tipo_retorno_llvm,
             nome_ctor,
-            params_llvm.join(", ")
+            params_llvm.join(", "),
+            self.linkage()
         ));
         self.body.push_str("entry:\n");
+        self.debug_begin_function(
+            &format!("{}.construtor", classe_nome),
+            &nome_ctor,
+            &ast::Tipo::Vazio,
+            &tipos_params,
+            debug_info::first_position(&construtor.corpo),
+        );
 
         // Aloca e armazena self
         let self_ptr_reg = "%var.self".to_string();
@@ -921,6 +1157,7 @@ impl<'a> LlvmGenerator<'a> {
             "  store {0} %param.self, {0}* {1}\n",
             self_type, self_ptr_reg
         ));
+        self.debug_declare("este", &self_ptr_reg, &self_tipo, true);
         self.variables.insert(
             "self".to_string(),
             (self_ptr_reg, ast::Tipo::Classe(classe_nome.clone())),
@@ -975,10 +1212,10 @@ impl<'a> LlvmGenerator<'a> {
                             } else if let Some(def_expr) = &param.valor_padrao {
                                 final_args.push(self.generate_expressao(def_expr));
                             } else {
-                                panic!(
+                                final_args.push(self.falhar_expressao(format!(
                                     "Argumento obrigatório ausente para parâmetro '{}' do construtor base de '{}'",
                                     param.nome, parent_fqn
-                                );
+                                )));
                             }
                         }
 
@@ -1036,6 +1273,8 @@ impl<'a> LlvmGenerator<'a> {
         }
 
         self.body.push_str("}\n");
+        self.debug_finish_function();
+        self.hoist_allocas_to_entry();
         old_body.push_str(&self.body);
         self.body = old_body;
         self.variables = old_vars;
@@ -1047,9 +1286,20 @@ impl<'a> LlvmGenerator<'a> {
         self.header.push_str("declare i32 @printf(i8*, ...)\n");
         self.header.push_str("declare i32 @scanf(i8*, ...)\n");
         self.header.push_str("declare i8* @malloc(i64)\n");
+        // Coletor de lixo do runtime nativo (src/runtime/gc_runtime.c)
+        self.header.push_str("declare noalias i8* @pds_gc_alocar(i64)\n");
+        self.header.push_str("declare noalias i8* @pds_gc_alocar_atomico(i64)\n");
+        self.header.push_str("declare void @pds_gc_adicionar_raiz(i8*, i64)\n");
+        // Erro de execução: mensagem, arquivo, linha e coluna; não retorna
+        self.header
+            .push_str("declare void @pds_panico(i8*, i8*, i32, i32) noreturn cold\n");
         self.header
             .push_str("declare i32 @sprintf(i8*, i8*, ...)\n");
         self.header.push_str("declare i64 @strlen(i8*)\n");
+        if self.debug.is_some() {
+            self.header
+                .push_str("declare void @llvm.dbg.declare(metadata, metadata, metadata)\n");
+        }
         // ABI do runtime nativo de async/await. `task` permanece opaco para
         // que o layout com mutex/condition variable seja exclusivo do C.
         self.header.push_str("%task = type opaque\n");
@@ -1085,7 +1335,6 @@ impl<'a> LlvmGenerator<'a> {
         // Formato para ler uma linha inteira (até CR/LF), consumindo finais de linha
         // "%255[^\r\n]%*[\r\n]" em C; em IR usamos escapes hex: \0D (CR) e \0A (LF)
         self.header.push_str("@.scanline_fmt = private unnamed_addr constant [16 x i8] c\"%255[^\\0D\\0A]%*[\\0D\\0A]\\00\", align 1\n");
-        self.header.push_str("@.oob_msg = private unnamed_addr constant [23 x i8] c\"Indice fora dos limites\", align 1\n");
     }
 
     fn setup_parameters(&mut self, params: &[ast::Parametro]) {
@@ -1100,6 +1349,7 @@ impl<'a> LlvmGenerator<'a> {
                 ptr_reg, llvm_type, align
             ));
 
+            self.debug_declare(&param.nome, &ptr_reg, &var_type, true);
             let param_reg = format!("%param.{0}", param.nome);
             self.body.push_str(&format!(
                 "  store {0} {1}, {0}* {2}\n",
@@ -1161,42 +1411,15 @@ impl<'a> LlvmGenerator<'a> {
                 let (val_reg, val_tipo) = self.generate_expressao(val);
                 let elem_tipo = match arr_tipo {
                     ast::Tipo::Lista(boxed) => *boxed,
-                    _ => panic!("Atribuição por índice requer array, obtido: {:?}", arr_tipo),
+                    _ => {
+                        return self.falhar(
+                            format!("Atribuição por índice requer array, obtido: {:?}", arr_tipo),
+                            (),
+                        )
+                    }
                 };
                 let (data_ptr, len_reg) = self.get_array_data_and_len(&arr_reg);
-                // Bounds check: idx < 0 || idx >= len
-                let neg = self.get_unique_temp_name();
-                self.body
-                    .push_str(&format!("  {0} = icmp slt i32 {1}, 0\n", neg, idx_reg));
-                let ge = self.get_unique_temp_name();
-                self.body.push_str(&format!(
-                    "  {0} = icmp sge i32 {1}, {2}\n",
-                    ge, idx_reg, len_reg
-                ));
-                let oob = self.get_unique_temp_name();
-                self.body
-                    .push_str(&format!("  {0} = or i1 {1}, {2}\n", oob, neg, ge));
-                let ok_label = self.get_unique_label("idx.ok");
-                let oob_label = self.get_unique_label("idx.oob");
-                let end_label = self.get_unique_label("idx.end");
-                self.body.push_str(&format!(
-                    "  br i1 {0}, label %{1}, label %{2}\n",
-                    oob, oob_label, ok_label
-                ));
-                // oob path
-                self.body.push_str(&format!("{0}:\n", oob_label));
-                let msg_ptr = self.get_unique_temp_name();
-                self.body.push_str(&format!(
-                    "  {0} = getelementptr inbounds [23 x i8], [23 x i8]* @.oob_msg, i32 0, i32 0\n",
-                    msg_ptr
-                ));
-                self.body.push_str(&format!(
-                    "  call i32 (i8*, ...) @printf(i8* getelementptr inbounds ([4 x i8], [4 x i8]* @.println_fmt, i32 0, i32 0), i8* {0})\n",
-                    msg_ptr
-                ));
-                self.body.push_str(&format!("  br label %{0}\n", end_label));
-                // ok path
-                self.body.push_str(&format!("{0}:\n", ok_label));
+                self.emit_index_check(&idx_reg, &len_reg);
                 let elem_ptr_t = self.map_type_to_llvm_arg(&elem_tipo);
                 let casted = self.get_unique_temp_name();
                 self.body.push_str(&format!(
@@ -1214,8 +1437,6 @@ impl<'a> LlvmGenerator<'a> {
                     "  store {0} {1}, {0}* {2}\n",
                     elem_store_ty, coerced, slot
                 ));
-                self.body.push_str(&format!("  br label %{0}\n", end_label));
-                self.body.push_str(&format!("{0}:\n", end_label));
             }
             ast::Comando::Expressao(expr) => {
                 self.generate_expressao(expr);
@@ -1320,11 +1541,23 @@ impl<'a> LlvmGenerator<'a> {
                            self.mangle_aplicado_name(&fqn_base, &norm_args)
                         }
                     },
-                    _ => panic!(
-                        "Atribuição de propriedade em algo que não é uma classe: {:?}",
-                        obj_type
-                    ),
+                    _ => {
+                        return self.falhar(
+                            format!(
+                                "Atribuição de propriedade em algo que não é uma classe: {:?}",
+                                obj_type
+                            ),
+                            (),
+                        )
+                    }
                 };
+                let obj_ptr_type = self.map_type_to_llvm_ptr(&obj_type);
+                self.emit_null_check(
+                    obj_expr,
+                    &obj_ptr_reg,
+                    &obj_ptr_type,
+                    &format!("Atribuição a '{}' em referência nula", prop_nome),
+                );
                 let (member_ptr_reg, member_type) =
                     self.get_member_ptr(&obj_ptr_reg, &class_name, prop_nome);
                 let llvm_type = self.map_type_to_llvm_storage(&member_type);
@@ -1344,14 +1577,22 @@ impl<'a> LlvmGenerator<'a> {
                     argumentos.clone(),
                 ));
             }
-            _ => panic!(
-                "Comando não suportado para geração de LLVM IR: {:?}",
-                comando
+            ast::Comando::Posicao(pos) => {
+                self.posicao_atual = Some(pos.clone());
+                self.debug_position(pos);
+            }
+            _ => self.falhar(
+                format!("Comando não suportado para geração de LLVM IR: {:?}", comando),
+                (),
             ),
         }
     }
 
     fn generate_funcao(&mut self, func: &'a ast::DeclaracaoFuncao, namespace: &str) {
+        // Funções externas só têm `declare` (declare_foreign_functions)
+        if externo::externo(func).is_some() {
+            return;
+        }
         let nome_funcao = self
             .type_checker
             .resolver_nome_funcao(&func.nome, namespace)
@@ -1363,9 +1604,11 @@ impl<'a> LlvmGenerator<'a> {
         let tipo_retorno_llvm = self.map_type_to_llvm_arg(&tipo_retorno_resolvido);
 
         let mut params_llvm = Vec::new();
+        let mut tipos_params = Vec::new();
         for param in &func.parametros {
             let tipo_param_resolvido = self.resolve_type(&param.tipo, namespace);
             let tipo_param_llvm = self.map_type_to_llvm_arg(&tipo_param_resolvido);
+            tipos_params.push(tipo_param_resolvido);
             params_llvm.push(format!("{0} %param.{1}", tipo_param_llvm, param.nome));
         }
 
@@ -1375,12 +1618,20 @@ impl<'a> LlvmGenerator<'a> {
         self.variables.clear();
 
         self.body.push_str(&format!(
-            "define {0} @\"{1}\"({2}) {{ \n",
+            "define {3}{0} @\"{1}\"({2}) {{ \n",
             tipo_retorno_llvm,
             nome_funcao,
-            params_llvm.join(", ")
+            params_llvm.join(", "),
+            self.linkage()
         ));
         self.body.push_str("entry:\n");
+        self.debug_begin_function(
+            &func.nome,
+            &nome_funcao,
+            &tipo_retorno_resolvido,
+            &tipos_params,
+            debug_info::first_position(&func.corpo),
+        );
 
         self.setup_parameters(&func.parametros);
 
@@ -1401,6 +1652,8 @@ impl<'a> LlvmGenerator<'a> {
         }
 
         self.body.push_str("}\n");
+        self.debug_finish_function();
+        self.hoist_allocas_to_entry();
         old_body.push_str(&self.body);
 
         self.body = old_body;
@@ -1408,7 +1661,7 @@ impl<'a> LlvmGenerator<'a> {
     }
 
     fn generate_metodo(&mut self, metodo: &'a ast::MetodoClasse) {
-        let classe_nome = self.classe_atual.as_ref().unwrap();
+        let classe_nome = self.classe_atual.clone().unwrap();
         let namespace = classe_nome.rsplit_once('.').map_or("", |(ns, _)| ns);
         let nome_metodo = format!("{0}::{1}", classe_nome, metodo.nome).replace('.', "_");
 
@@ -1419,12 +1672,17 @@ impl<'a> LlvmGenerator<'a> {
         let tipo_retorno_llvm = self.map_type_to_llvm_arg(&tipo_retorno_resolvido);
 
         let mut params_llvm = Vec::new();
-        let self_type = self.map_type_to_llvm_ptr(&ast::Tipo::Classe(classe_nome.clone()));
-        params_llvm.push(format!("{0} %param.self", self_type));
+        let self_tipo = ast::Tipo::Classe(classe_nome.clone());
+        let mut tipos_params = vec![self_tipo.clone()];
+        let self_type = self.map_type_to_llvm_ptr(&self_tipo);
+        // Chamar método em `nulo` é erro nos outros backends; aqui `este` é assumido não nulo
+        let atributos_self = if metodo.eh_estatica { "" } else { "nonnull " };
+        params_llvm.push(format!("{0} {1}%param.self", self_type, atributos_self));
 
         for param in &metodo.parametros {
             let tipo_param_resolvido = self.resolve_type(&param.tipo, namespace);
             let tipo_param_llvm = self.map_type_to_llvm_arg(&tipo_param_resolvido);
+            tipos_params.push(tipo_param_resolvido);
             params_llvm.push(format!("{0} %param.{1}", tipo_param_llvm, param.nome));
         }
 
@@ -1434,12 +1692,20 @@ impl<'a> LlvmGenerator<'a> {
         self.variables.clear();
 
         self.body.push_str(&format!(
-            "define {0} @\"{1}\"({2}) {{ \n",
+            "define {3}{0} @\"{1}\"({2}) {{ \n",
             tipo_retorno_llvm,
             nome_metodo,
-            params_llvm.join(", ")
+            params_llvm.join(", "),
+            self.linkage()
         ));
         self.body.push_str("entry:\n");
+        self.debug_begin_function(
+            &format!("{}.{}", classe_nome, metodo.nome),
+            &nome_metodo,
+            &tipo_retorno_resolvido,
+            &tipos_params,
+            debug_info::first_position(&metodo.corpo),
+        );
 
         let self_ptr_reg = "%var.self".to_string();
         self.body.push_str(&format!(
@@ -1450,6 +1716,7 @@ impl<'a> LlvmGenerator<'a> {
             "  store {0} %param.self, {0}* {1}\n",
             self_type, self_ptr_reg
         ));
+        self.debug_declare("este", &self_ptr_reg, &self_tipo, true);
         self.variables.insert(
             "self".to_string(),
             (self_ptr_reg, ast::Tipo::Classe(classe_nome.clone())),
@@ -1474,6 +1741,8 @@ impl<'a> LlvmGenerator<'a> {
         }
 
         self.body.push_str("}\n");
+        self.debug_finish_function();
+        self.hoist_allocas_to_entry();
         old_body.push_str(&self.body);
         self.body = old_body;
         self.variables = old_vars;
@@ -1494,6 +1763,7 @@ impl<'a> LlvmGenerator<'a> {
             "  {0} = alloca {1}, align {2}\n",
             ptr_reg, llvm_type, align
         ));
+        self.debug_declare(name, &ptr_reg, &var_type, false);
         let coerced = self.ensure_value_type(value_reg, &value_type, &var_type);
         self.body.push_str(&format!(
             "  store {0} {1}, {0}* {2}\n",
@@ -1512,10 +1782,9 @@ impl<'a> LlvmGenerator<'a> {
         let fqn_class_name = self
             .type_checker
             .resolver_nome_classe(class_name, &self.namespace_path);
-        let resolved_info = self
-            .resolved_classes
-            .get(&fqn_class_name)
-            .unwrap_or_else(|| panic!("Classe '{}' não encontrada.", fqn_class_name));
+        let Some(resolved_info) = self.resolved_classes.get(&fqn_class_name) else {
+            return self.falhar_expressao(format!("Classe '{}' não encontrada.", fqn_class_name));
+        };
 
         // Índice 0 é o vptr; campos começam em 1
         let mut current_index = 1;
@@ -1560,10 +1829,10 @@ impl<'a> LlvmGenerator<'a> {
             return (member_ptr_reg, member_type);
         }
 
-        panic!(
+        self.falhar_expressao(format!(
             "Membro '{}' não encontrado na classe '{}'",
             member_name, class_name
-        );
+        ))
     }
 
     // Encontra o FQN da classe onde um método foi originalmente declarado.
@@ -1622,7 +1891,7 @@ impl<'a> LlvmGenerator<'a> {
             }
         }
 
-        panic!("Atribuição a variável não declarada '{}'", name);
+        self.falhar(format!("Atribuição a variável não declarada '{}'", name), ())
     }
 
     /// Retorna o helper do runtime e o tipo produzido por cada operação de
@@ -1641,13 +1910,12 @@ impl<'a> LlvmGenerator<'a> {
         operation: &str,
         arguments: &[ast::Expressao],
     ) -> (String, ast::Tipo) {
-        let (runtime_function, result_type) =
-            self.native_async_operation(operation).unwrap_or_else(|| {
-                panic!(
-                    "Opera\u{00e7}\u{00e3}o async nativa desconhecida: {}",
-                    operation
-                )
-            });
+        let Some((runtime_function, result_type)) = self.native_async_operation(operation) else {
+            return self.falhar_expressao(format!(
+                "Opera\u{00e7}\u{00e3}o async nativa desconhecida: {}",
+                operation
+            ));
+        };
 
         let expected_arguments = if runtime_function == "task_create_write_file" {
             2
@@ -1655,12 +1923,12 @@ impl<'a> LlvmGenerator<'a> {
             1
         };
         if arguments.len() != expected_arguments {
-            panic!(
+            return self.falhar_expressao(format!(
                 "{} requer {} argumento(s), mas recebeu {}",
                 operation,
                 expected_arguments,
                 arguments.len()
-            );
+            ));
         }
 
         let mut values = Vec::new();
@@ -1787,19 +2055,20 @@ impl<'a> LlvmGenerator<'a> {
         );
         let return_llvm = self.map_type_to_llvm_arg(&return_type);
 
+        let mut definicao = String::new();
         if !param_types.is_empty() {
             let fields = param_types
                 .iter()
                 .map(|ty| self.map_type_to_llvm_storage(ty))
                 .collect::<Vec<_>>()
                 .join(", ");
-            self.header
-                .push_str(&format!("{} = type {{ {} }}\n", args_type, fields));
+            definicao.push_str(&format!("{} = type {{ {} }}\n", args_type, fields));
         }
 
         let mut wrapper = String::new();
         wrapper.push_str(&format!(
-            "define i8* @\"{}\"(i8* %raw_args) {{\nentry:\n",
+            "define {}i8* @\"{}\"(i8* %raw_args) {{\nentry:\n",
+            self.linkage(),
             wrapper_symbol
         ));
         let mut call_arguments = Vec::new();
@@ -1845,7 +2114,7 @@ impl<'a> LlvmGenerator<'a> {
             ));
         } else {
             wrapper.push_str(&format!(
-                "  %result = call {} @\"{}\"({})\n  %size_ptr = getelementptr {}, {}* null, i32 1\n  %size = ptrtoint {}* %size_ptr to i64\n  %memory = call i8* @malloc(i64 %size)\n  %boxed = bitcast i8* %memory to {}*\n  store {} %result, {}* %boxed\n  ret i8* %memory\n}}\n",
+                "  %result = call {} @\"{}\"({})\n  %size_ptr = getelementptr {}, {}* null, i32 1\n  %size = ptrtoint {}* %size_ptr to i64\n  %memory = call i8* @pds_gc_alocar_atomico(i64 %size)\n  %boxed = bitcast i8* %memory to {}*\n  store {} %result, {}* %boxed\n  ret i8* %memory\n}}\n",
                 return_llvm,
                 target_symbol,
                 call_arguments.join(", "),
@@ -1857,7 +2126,8 @@ impl<'a> LlvmGenerator<'a> {
                 return_llvm
             ));
         }
-        self.header.push_str(&wrapper);
+        definicao.push_str(&wrapper);
+        self.async_wrappers.push((fqn.to_string(), definicao));
         self.async_wrapper_symbols
             .insert(fqn.to_string(), wrapper_symbol.clone());
         wrapper_symbol
@@ -1868,19 +2138,19 @@ impl<'a> LlvmGenerator<'a> {
         fqn: &str,
         arguments: &[ast::Expressao],
     ) -> (String, ast::Tipo) {
-        let function = self.find_async_function(fqn).unwrap_or_else(|| {
-            panic!(
+        let Some(function) = self.find_async_function(fqn) else {
+            return self.falhar_expressao(format!(
                 "'{}' n\u{00e3}o \u{00e9} uma fun\u{00e7}\u{00e3}o ass\u{00ed}ncrona declarada",
                 fqn
-            )
-        });
+            ));
+        };
         if function.parametros.len() != arguments.len() {
-            panic!(
+            return self.falhar_expressao(format!(
                 "Fun\u{00e7}\u{00e3}o ass\u{00ed}ncrona '{}' requer {} argumento(s), mas recebeu {}",
                 fqn,
                 function.parametros.len(),
                 arguments.len()
-            );
+            ));
         }
         let namespace = self.get_namespace_from_fqn(fqn);
         let wrapper_symbol = self.ensure_async_wrapper(fqn, &function, &namespace);
@@ -1904,7 +2174,7 @@ impl<'a> LlvmGenerator<'a> {
             let memory = self.get_unique_temp_name();
             let typed_args = self.get_unique_temp_name();
             self.body.push_str(&format!(
-                "  {0} = getelementptr {1}, {1}* null, i32 1\n  {2} = ptrtoint {1}* {0} to i64\n  {3} = call i8* @malloc(i64 {2})\n  {4} = bitcast i8* {3} to {1}*\n",
+                "  {0} = getelementptr {1}, {1}* null, i32 1\n  {2} = ptrtoint {1}* {0} to i64\n  {3} = call i8* @pds_gc_alocar(i64 {2})\n  {4} = bitcast i8* {3} to {1}*\n",
                 size_ptr, args_type, size, memory, typed_args
             ));
             for (index, (argument, expected_type)) in
@@ -1987,9 +2257,10 @@ impl<'a> LlvmGenerator<'a> {
                 let (l, r, llvm_op, llvm_ty) = match result_tipo {
                     Inteiro => {
                         let op_code = match op {
-                            ast::OperadorAritmetico::Soma => "add",
-                            ast::OperadorAritmetico::Subtracao => "sub",
-                            ast::OperadorAritmetico::Multiplicacao => "mul",
+                            // `inteiro` tem sinal: estouro não é definido (como em C)
+                            ast::OperadorAritmetico::Soma => "add nsw",
+                            ast::OperadorAritmetico::Subtracao => "sub nsw",
+                            ast::OperadorAritmetico::Multiplicacao => "mul nsw",
                             ast::OperadorAritmetico::Divisao => "sdiv",
                             ast::OperadorAritmetico::Modulo => "srem",
                         };
@@ -2021,6 +2292,39 @@ impl<'a> LlvmGenerator<'a> {
                     }
                     _ => unreachable!(),
                 };
+                if self.verificacoes
+                    && matches!(
+                        op,
+                        ast::OperadorAritmetico::Divisao | ast::OperadorAritmetico::Modulo
+                    )
+                {
+                    // `sdiv`/`srem` por zero não têm resultado definido; a VM também rejeita
+                    // divisão por zero de reais
+                    let zero = self.get_unique_temp_name();
+                    let cmp = if llvm_ty == "i32" {
+                        format!("icmp eq i32 {0}, 0", r)
+                    } else {
+                        format!("fcmp oeq {0} {1}, 0.0", llvm_ty, r)
+                    };
+                    self.body.push_str(&format!("  {0} = {1}\n", zero, cmp));
+                    let mensagem = match op {
+                        ast::OperadorAritmetico::Modulo => "Módulo por zero",
+                        _ => "Divisão por zero",
+                    };
+                    self.emit_runtime_check(&zero, mensagem);
+                    if llvm_ty == "i32" {
+                        // O quociente de INT_MIN / -1 não cabe em i32: `sdiv` e `srem` também
+                        // não têm resultado definido (e disparam exceção no x86)
+                        let minimo = self.get_unique_temp_name();
+                        let menos_um = self.get_unique_temp_name();
+                        let estouro = self.get_unique_temp_name();
+                        self.body.push_str(&format!(
+                            "  {0} = icmp eq i32 {1}, -2147483648\n  {2} = icmp eq i32 {3}, -1\n  {4} = and i1 {0}, {2}\n",
+                            minimo, l, menos_um, r, estouro
+                        ));
+                        self.emit_runtime_check(&estouro, "Estouro na divisão inteira");
+                    }
+                }
                 let result_reg = self.get_unique_temp_name();
                 self.body.push_str(&format!(
                     "  {0} = {1} {2} {3}, {4}\n",
@@ -2047,7 +2351,12 @@ impl<'a> LlvmGenerator<'a> {
                         self.define_applied_struct(&fqn_base, &norm_args);
                         (mangled_name.clone(), ast::Tipo::Aplicado { nome: mangled_name, args: norm_args.clone() }, fqn_base)
                     },
-                    _ => panic!("Instanciação de tipo não suportado em LLVM IR: {:?}", tipo),
+                    _ => {
+                        return self.falhar_expressao(format!(
+                            "Instanciação de tipo não suportado em LLVM IR: {:?}",
+                            tipo
+                        ))
+                    }
                 };
                 let fqn = self
                     .type_checker
@@ -2055,7 +2364,10 @@ impl<'a> LlvmGenerator<'a> {
                 // Bloquear instanciação de classe abstrata
                 if let Some(class_decl) = self.type_checker.classes.get(&fqn) {
                     if class_decl.eh_abstrata {
-                        panic!("Não é possível instanciar classe abstrata: {}", fqn);
+                        return self.falhar_expressao(format!(
+                            "Não é possível instanciar classe abstrata: {}",
+                            fqn
+                        ));
                     }
                 }
                 let sanitized_fqn = nome_classe.replace('.', "_");
@@ -2082,7 +2394,7 @@ impl<'a> LlvmGenerator<'a> {
 
                 let malloc_ptr_reg = self.get_unique_temp_name();
                 self.body.push_str(&format!(
-                    "  {0} = call i8* @malloc(i64 {1})\n",
+                    "  {0} = call i8* @pds_gc_alocar(i64 {1})\n",
                     malloc_ptr_reg, size_reg
                 ));
 
@@ -2153,7 +2465,7 @@ impl<'a> LlvmGenerator<'a> {
                                 if let Some(def_expr) = &param.valor_padrao {
                                     final_args.push(self.generate_expressao(def_expr));
                                 } else {
-                                    panic!("Argumento obrigatório ausente para parâmetro '{}' do construtor de '{}'", param.nome, base_fqn);
+                                    final_args.push(self.falhar_expressao(format!("Argumento obrigatório ausente para parâmetro '{}' do construtor de '{}'", param.nome, base_fqn)));
                                 }
                             }
                         }
@@ -2182,7 +2494,7 @@ impl<'a> LlvmGenerator<'a> {
                 // Para biblioteca padrão, criamos um array vazio como placeholder
                 // Em uma implementação completa, precisaria alocar dinamicamente
                 let array_reg = self.get_unique_temp_name();
-                self.body.push_str(&format!("  {} = call i8* @malloc(i64 16)\n", array_reg));
+                self.body.push_str(&format!("  {} = call i8* @pds_gc_alocar(i64 16)\n", array_reg));
                 // Inicializa array vazio: tamanho = 0, ponteiro = null
                 let gep_len = self.get_unique_temp_name();
                 self.body.push_str(&format!("  {} = getelementptr inbounds [2 x i32], [2 x i32]* {}, i32 0, i32 0\n", gep_len, array_reg));
@@ -2218,10 +2530,16 @@ impl<'a> LlvmGenerator<'a> {
                     "  {0} = mul i64 {1}, {2}\n",
                     total_size, sizeof_t, len
                 ));
+                // Elementos sem ponteiros não precisam ser percorridos pelo coletor
+                let alocador = if elem_ty_arg.ends_with('*') {
+                    "pds_gc_alocar"
+                } else {
+                    "pds_gc_alocar_atomico"
+                };
                 let data_i8 = self.get_unique_temp_name();
                 self.body.push_str(&format!(
-                    "  {0} = call i8* @malloc(i64 {1})\n",
-                    data_i8, total_size
+                    "  {0} = call i8* @{1}(i64 {2})\n",
+                    data_i8, alocador, total_size
                 ));
                 // Escrever elementos
                 let data_typed = self.get_unique_temp_name();
@@ -2269,7 +2587,7 @@ impl<'a> LlvmGenerator<'a> {
                 ));
                 let array_mem = self.get_unique_temp_name();
                 self.body.push_str(&format!(
-                    "  {0} = call i8* @malloc(i64 {1})\n",
+                    "  {0} = call i8* @pds_gc_alocar(i64 {1})\n",
                     array_mem, array_size
                 ));
                 let array_ptr = self.get_unique_temp_name();
@@ -2302,44 +2620,15 @@ impl<'a> LlvmGenerator<'a> {
                 let (idx_reg, _idx_tipo) = self.generate_expressao(idx);
                 let elem_tipo = match arr_tipo.clone() {
                     ast::Tipo::Lista(boxed) => *boxed,
-                    _ => panic!("Acesso por índice requer array, obtido: {:?}", arr_tipo),
+                    _ => {
+                        return self.falhar_expressao(format!(
+                            "Acesso por índice requer array, obtido: {:?}",
+                            arr_tipo
+                        ))
+                    }
                 };
                 let (data_ptr, len_reg) = self.get_array_data_and_len(&arr_reg);
-                // Bounds
-                let neg = self.get_unique_temp_name();
-                self.body
-                    .push_str(&format!("  {0} = icmp slt i32 {1}, 0\n", neg, idx_reg));
-                let ge = self.get_unique_temp_name();
-                self.body.push_str(&format!(
-                    "  {0} = icmp sge i32 {1}, {2}\n",
-                    ge, idx_reg, len_reg
-                ));
-                let oob = self.get_unique_temp_name();
-                self.body
-                    .push_str(&format!("  {0} = or i1 {1}, {2}\n", oob, neg, ge));
-                let ok_label = self.get_unique_label("idx.ok");
-                let oob_label = self.get_unique_label("idx.oob");
-                let end_label = self.get_unique_label("idx.end");
-                self.body.push_str(&format!(
-                    "  br i1 {0}, label %{1}, label %{2}\n",
-                    oob, oob_label, ok_label
-                ));
-                // oob
-                self.body.push_str(&format!("{0}:\n", oob_label));
-                let msg_ptr = self.get_unique_temp_name();
-                self.body.push_str(&format!(
-                    "  {0} = getelementptr inbounds [23 x i8], [23 x i8]* @.oob_msg, i32 0, i32 0\n",
-                    msg_ptr
-                ));
-                self.body.push_str(&format!(
-                    "  call i32 (i8*, ...) @printf(i8* getelementptr inbounds ([4 x i8], [4 x i8]* @.println_fmt, i32 0, i32 0), i8* {0})\n",
-                    msg_ptr
-                ));
-                // valor padrão
-                let default_reg = self.zero_value_of(&elem_tipo);
-                self.body.push_str(&format!("  br label %{0}\n", end_label));
-                // ok
-                self.body.push_str(&format!("{0}:\n", ok_label));
+                self.emit_index_check(&idx_reg, &len_reg);
                 let elem_ty_arg = self.map_type_to_llvm_arg(&elem_tipo);
                 let casted = self.get_unique_temp_name();
                 self.body.push_str(&format!(
@@ -2357,19 +2646,7 @@ impl<'a> LlvmGenerator<'a> {
                     "  {0} = load {1}, {1}* {2}\n",
                     loaded, elem_store_ty, slot
                 ));
-                let phi = self.get_unique_temp_name();
-                // phi do resultado
-                self.body.push_str(&format!(
-                    "  br label %{0}\n{0}:\n  {1} = phi {2} [ {3}, %{4} ], [ {5}, %{6} ]\n",
-                    end_label,
-                    phi,
-                    self.map_type_to_llvm_arg(&elem_tipo),
-                    default_reg,
-                    oob_label,
-                    loaded,
-                    ok_label
-                ));
-                (phi, elem_tipo)
+                (loaded, elem_tipo)
             }
             ast::Expressao::Chamada(nome_funcao, argumentos) => {
                 let fqn_func_name = self
@@ -2417,24 +2694,31 @@ impl<'a> LlvmGenerator<'a> {
                 }
                 if short_name == "LerLinha" {
                     // Implementação real usando scanf("%255[^\r\n]%*[\r\n]", buffer)
-                    // 1) Aloca um buffer local [256 x i8]
-                    let buf_alloca = self.get_unique_temp_name();
-                    self.body
-                        .push_str(&format!("  {0} = alloca [256 x i8], align 1\n", buf_alloca));
-                    // 2) GEP para i8* do início
-                    let buf_ptr = self.get_unique_temp_name();
-                    self.body.push_str(&format!(
-                        "  {0} = getelementptr inbounds [256 x i8], [256 x i8]* {1}, i32 0, i32 0\n",
-                        buf_ptr, buf_alloca
-                    ));
-                    // 3) scanf no buffer
+                    // 1) Aloca um buffer de 256 bytes no heap
+                    let buf_ptr = self.allocate_string_buffer(256);
+                    // 2) scanf no buffer
                     self.body.push_str(&format!(
                         "  call i32 (i8*, ...) @scanf(i8* getelementptr inbounds ([16 x i8], [16 x i8]* @.scanline_fmt, i32 0, i32 0), i8* {0})\n",
                         buf_ptr
                     ));
-                    // 4) Retorna i8* para o buffer
+                    // 3) Retorna i8* para o buffer
                     return (buf_ptr, ast::Tipo::Texto);
                 }
+                // Dentro de um método de instância, `Metodo(...)` sem objeto é `este.Metodo(...)`.
+                let metodo_da_classe = self.variables.contains_key("self")
+                    && self
+                        .classe_atual
+                        .as_ref()
+                        .and_then(|classe| self.resolved_classes.get(classe))
+                        .and_then(|c| c.methods.get(nome_funcao.as_str()))
+                        .is_some_and(|m| !m.eh_estatica);
+                if metodo_da_classe {
+                    return self.generate_expressao(&ast::Expressao::ChamadaMetodo(
+                        Box::new(ast::Expressao::Este),
+                        nome_funcao.clone(),
+                        argumentos.clone(),
+                    ));
+                }
                 let func = self
                     .programa
                     .declaracoes
@@ -2480,11 +2764,16 @@ impl<'a> LlvmGenerator<'a> {
                     })
                 }) {
                     Some(f) => f,
-                    None => panic!(
-                        "Função '{}' não encontrada nem como intrínseca nem no código do usuário",
-                        fqn_func_name
-                    ),
+                    None => {
+                        return self.falhar_expressao(format!(
+                            "Função '{}' não encontrada nem como intrínseca nem no código do usuário",
+                            fqn_func_name
+                        ))
+                    }
                 };
+                if let Some((_, simbolo)) = externo::externo(func) {
+                    return self.call_foreign_function(func, simbolo, argumentos);
+                }
                 let return_type_decl = func.tipo_retorno.clone().unwrap_or(ast::Tipo::Vazio);
                 let return_type = self.resolve_type(&return_type_decl, &self.namespace_path);
                 let return_type_llvm = self.map_type_to_llvm_arg(&return_type);
@@ -2555,12 +2844,12 @@ impl<'a> LlvmGenerator<'a> {
                            self.mangle_aplicado_name(&fqn_base, &norm_args)
                         }
                     },
-                    _ => panic!(
-                        "Chamada de método em algo que não é um objeto. metodo='{}' obj_type={:?} obj_expr={:?}",
-                        metodo_nome,
-                        &obj_type,
-                        obj_expr
-                    ),
+                    _ => {
+                        return self.falhar_expressao(format!(
+                            "Chamada de método '{}' em valor do tipo {} não suportada no alvo LLVM",
+                            metodo_nome, obj_type
+                        ))
+                    }
                 };
                 // Determina se é virtual (tem índice de vtable)
                 let vtable_idx_opt = self
@@ -2570,7 +2859,7 @@ impl<'a> LlvmGenerator<'a> {
 
                 // Resolve tipo de retorno pela classe estática
                 // Para classes genéricas mangled, tenta base name se não encontrar no mangled
-                let resolved_method = self
+                let resolved_method = match self
                     .resolved_classes
                     .get(&fqn_class_name)
                     .and_then(|c| c.methods.get(metodo_nome))
@@ -2587,12 +2876,15 @@ impl<'a> LlvmGenerator<'a> {
                             None
                         }
                     })
-                    .unwrap_or_else(|| {
-                        panic!(
+                {
+                    Some(metodo) => metodo,
+                    None => {
+                        return self.falhar_expressao(format!(
                             "Método '{}' não encontrado em '{}'",
                             metodo_nome, fqn_class_name
-                        )
-                    });
+                        ))
+                    }
+                };
                 let return_type = resolved_method
                     .tipo_retorno
                     .clone()
@@ -2601,12 +2893,38 @@ impl<'a> LlvmGenerator<'a> {
 
                 // Prepara argumentos
                 let obj_ptr_type = self.map_type_to_llvm_ptr(&obj_type);
+                self.emit_null_check(
+                    obj_expr,
+                    &obj_reg,
+                    &obj_ptr_type,
+                    &format!("Chamada de '{}' em referência nula", metodo_nome),
+                );
                 let mut args_llvm_sig: Vec<String> = Vec::new();
                 let mut args_values: Vec<(String, ast::Tipo)> = Vec::new();
                 args_llvm_sig.push(obj_ptr_type.clone());
                 args_values.push((obj_reg.clone(), obj_type.clone()));
-                for arg in argumentos {
-                    let (arg_reg, arg_type) = self.generate_expressao(arg);
+                // Preenche os parâmetros omitidos com o valor padrão e converte numéricos para o
+                // tipo declarado, como no construtor.
+                for (idx, param) in resolved_method.parametros.iter().enumerate() {
+                    let (arg_reg, arg_type) = if let Some(arg) = argumentos.get(idx) {
+                        self.generate_expressao(arg)
+                    } else if let Some(def_expr) = &param.valor_padrao {
+                        self.generate_expressao(def_expr)
+                    } else {
+                        self.falhar_expressao(format!(
+                            "Argumento obrigatório ausente para parâmetro '{}' do método '{}'",
+                            param.nome, metodo_nome
+                        ))
+                    };
+                    let (arg_reg, arg_type) =
+                        if matches!(param.tipo, ast::Tipo::Flutuante | ast::Tipo::Duplo) {
+                            (
+                                self.ensure_value_type(&arg_reg, &arg_type, &param.tipo),
+                                param.tipo.clone(),
+                            )
+                        } else {
+                            (arg_reg, arg_type)
+                        };
                     args_llvm_sig.push(self.map_type_to_llvm_arg(&arg_type));
                     args_values.push((arg_reg, arg_type));
                 }
@@ -2752,10 +3070,12 @@ impl<'a> LlvmGenerator<'a> {
                             result_reg, pred, left_reg, right_reg
                         ));
                     }
-                    _ => panic!(
-                        "Comparação não suportada entre tipos: {:?} e {:?}",
-                        left_type, right_type
-                    ),
+                    _ => {
+                        return self.falhar_expressao(format!(
+                            "Comparação não suportada entre tipos: {:?} e {:?}",
+                            left_type, right_type
+                        ))
+                    }
                 }
                 (result_reg, ast::Tipo::Booleano)
             }
@@ -2871,11 +3191,20 @@ impl<'a> LlvmGenerator<'a> {
                            self.mangle_aplicado_name(&fqn_base, &norm_args)
                         }
                     },
-                    _ => panic!(
-                        "Acesso de membro em algo que não é uma classe: {:?}",
-                        obj_type
-                    ),
+                    _ => {
+                        return self.falhar_expressao(format!(
+                            "Acesso de membro em algo que não é uma classe: {:?}",
+                            obj_type
+                        ))
+                    }
                 };
+                let obj_ptr_type = self.map_type_to_llvm_ptr(&obj_type);
+                self.emit_null_check(
+                    obj_expr,
+                    &obj_reg,
+                    &obj_ptr_type,
+                    &format!("Acesso a '{}' em referência nula", membro_nome),
+                );
                 let (member_ptr_reg, member_type) =
                     self.get_member_ptr(&obj_reg, &class_name, membro_nome);
                 let loaded_reg = self.get_unique_temp_name();
@@ -2908,24 +3237,24 @@ impl<'a> LlvmGenerator<'a> {
                             "LerTextoAssíncrono" => "LerArquivoAssíncrono",
                             "EscreverTextoAssíncrono" => "EscreverArquivoAssíncrono",
                             "ExisteAssíncrono" => "VerificarArquivoAssíncrono",
-                            _ => panic!(
-                                "Método async '{}' não é suportado pelo runtime LLVM",
-                                method
-                            ),
+                            _ => {
+                                return self.falhar_expressao(format!(
+                                    "Método async '{}' não é suportado pelo runtime LLVM",
+                                    method
+                                ))
+                            }
                         };
                         self.await_native_operation(operation, arguments)
                     } else {
-                        panic!("aguarde requer uma chamada async nativa ou uma função marcada como assíncrona")
+                        self.falhar_expressao("aguarde requer uma chamada async nativa ou uma função marcada como assíncrona".to_string())
                     }
                 }
-                _ => panic!("aguarde requer uma chamada async"),
+                _ => self.falhar_expressao("aguarde requer uma chamada async".to_string()),
             },
             ast::Expressao::Este => self.load_variable("self"),
             ast::Expressao::Nulo => {
-                // Para nulo, retornamos um null pointer para o tipo genérico
-                let null_reg = self.get_unique_temp_name();
-                self.body.push_str(&format!("  {} = getelementptr i8, i8* null, i32 0\n", null_reg));
-                (null_reg, ast::Tipo::Classe("objeto".to_string()))
+                // A constante `null` serve para qualquer tipo de ponteiro do destino
+                ("null".to_string(), ast::Tipo::Classe("objeto".to_string()))
             }
             ast::Expressao::Unario(op, expr) => {
                 let (reg, tipo) = self.generate_expressao(expr);
@@ -2934,7 +3263,7 @@ impl<'a> LlvmGenerator<'a> {
                     ast::OperadorUnario::NegacaoNumerica => {
                         match tipo {
                             ast::Tipo::Inteiro => {
-                                self.body.push_str(&format!("  {} = sub i32 0, {}\n", result_reg, reg));
+                                self.body.push_str(&format!("  {} = sub nsw i32 0, {}\n", result_reg, reg));
                             }
                             ast::Tipo::Flutuante => {
                                 let ensured = self.ensure_float(&reg, &tipo);
@@ -2944,7 +3273,12 @@ impl<'a> LlvmGenerator<'a> {
                                 let ensured = self.ensure_double(&reg, &tipo);
                                 self.body.push_str(&format!("  {} = fsub double -0.0, {}\n", result_reg, ensured));
                             }
-                            _ => panic!("Negação numérica não suportada para tipo: {:?}", tipo),
+                            _ => {
+                                return self.falhar_expressao(format!(
+                                    "Negação numérica não suportada para tipo: {:?}",
+                                    tipo
+                                ))
+                            }
                         }
                     }
                     ast::OperadorUnario::NegacaoLogica => {
@@ -2996,7 +3330,7 @@ impl<'a> LlvmGenerator<'a> {
             }
         }
 
-        panic!("Variável ou membro de classe não declarado: '{}'", name);
+        self.falhar_expressao(format!("Variável ou membro de classe não declarado: '{}'", name))
     }
 
     fn get_safe_string_ptr(&mut self, reg: &str) -> String {
@@ -3022,7 +3356,21 @@ impl<'a> LlvmGenerator<'a> {
 
     fn ensure_string(&mut self, reg: &str, tipo: &ast::Tipo) -> String {
         match tipo {
-            ast::Tipo::Texto => self.get_safe_string_ptr(reg),
+            // Texto nulo aparece como "nulo", como no interpretador.
+            ast::Tipo::Texto => {
+                let is_null_reg = self.get_unique_temp_name();
+                self.body.push_str(&format!(
+                    "  {0} = icmp eq i8* {1}, null\n",
+                    is_null_reg, reg
+                ));
+                let nulo_str = self.create_global_string("nulo");
+                let result_reg = self.get_unique_temp_name();
+                self.body.push_str(&format!(
+                    "  {0} = select i1 {1}, i8* {2}, i8* {3}\n",
+                    result_reg, is_null_reg, nulo_str, reg
+                ));
+                result_reg
+            }
             ast::Tipo::Decimal => self.get_safe_string_ptr(reg),
             ast::Tipo::Inteiro => self.convert_int_to_string(reg),
             ast::Tipo::Enum(_) => self.convert_int_to_string(reg),
@@ -3076,14 +3424,7 @@ impl<'a> LlvmGenerator<'a> {
     }
 
     fn convert_float_to_string(&mut self, f_reg: &str) -> String {
-        let buffer = self.get_unique_temp_name();
-        self.body
-            .push_str(&format!("  {0} = alloca [64 x i8], align 1\n", buffer));
-        let buffer_ptr = self.get_unique_temp_name();
-        self.body.push_str(&format!(
-            "  {0} = getelementptr inbounds [64 x i8], [64 x i8]* {1}, i32 0, i32 0\n",
-            buffer_ptr, buffer
-        ));
+        let buffer_ptr = self.allocate_string_buffer(64);
         let fmt_ptr = self.get_unique_temp_name();
         self.body.push_str(&format!(
             "  {0} = getelementptr inbounds [3 x i8], [3 x i8]* @.float_fmt, i32 0, i32 0\n",
@@ -3102,14 +3443,7 @@ impl<'a> LlvmGenerator<'a> {
     }
 
     fn convert_double_to_string(&mut self, d_reg: &str) -> String {
-        let buffer = self.get_unique_temp_name();
-        self.body
-            .push_str(&format!("  {0} = alloca [64 x i8], align 1\n", buffer));
-        let buffer_ptr = self.get_unique_temp_name();
-        self.body.push_str(&format!(
-            "  {0} = getelementptr inbounds [64 x i8], [64 x i8]* {1}, i32 0, i32 0\n",
-            buffer_ptr, buffer
-        ));
+        let buffer_ptr = self.allocate_string_buffer(64);
         let fmt_ptr = self.get_unique_temp_name();
         self.body.push_str(&format!(
             "  {0} = getelementptr inbounds [3 x i8], [3 x i8]* @.double_fmt, i32 0, i32 0\n",
@@ -3137,7 +3471,7 @@ impl<'a> LlvmGenerator<'a> {
                     .push_str(&format!("  {0} = fptrunc double {1} to float\n", tmp, reg));
                 tmp
             }
-            _ => panic!("Conversão para float não suportada: {:?}", tipo),
+            _ => self.falhar(format!("Conversão para float não suportada: {:?}", tipo), reg.to_string()),
         }
     }
 
@@ -3156,7 +3490,7 @@ impl<'a> LlvmGenerator<'a> {
                     .push_str(&format!("  {0} = fpext float {1} to double\n", tmp, reg));
                 tmp
             }
-            _ => panic!("Conversão para double não suportada: {:?}", tipo),
+            _ => self.falhar(format!("Conversão para double não suportada: {:?}", tipo), reg.to_string()),
         }
     }
 
@@ -3175,19 +3509,43 @@ impl<'a> LlvmGenerator<'a> {
                     .push_str(&format!("  {0} = icmp ne i32 {1}, 0\n", tmp, reg));
                 tmp
             }
-            _ => panic!("Conversão para bool não suportada: {:?}", tipo),
+            _ => self.falhar(format!("Conversão para bool não suportada: {:?}", tipo), reg.to_string()),
         }
     }
 
-    fn convert_int_to_string(&mut self, int_reg: &str) -> String {
-        let buffer = self.get_unique_temp_name();
-        self.body
-            .push_str(&format!("  {0} = alloca [21 x i8], align 1\n", buffer));
+    /// Move os `alloca` da função em geração para o bloco `entry`: variáveis declaradas dentro
+    /// de laços reservam a pilha uma única vez, e não a cada iteração.
+    fn hoist_allocas_to_entry(&mut self) {
+        let inicio = match self.body.find("\nentry:\n") {
+            Some(pos) => pos + "\nentry:\n".len(),
+            None => return,
+        };
+        let mut allocas = String::new();
+        let mut demais = String::new();
+        for linha in self.body[inicio..].split_inclusive('\n') {
+            if linha.trim_start().starts_with('%') && linha.contains(" = alloca ") {
+                allocas.push_str(linha);
+            } else {
+                demais.push_str(linha);
+            }
+        }
+        self.body.truncate(inicio);
+        self.body.push_str(&allocas);
+        self.body.push_str(&demais);
+    }
+
+    /// Buffer de texto no heap do coletor (o texto pode sobreviver à função e ao laço atual).
+    fn allocate_string_buffer(&mut self, tamanho: usize) -> String {
         let buffer_ptr = self.get_unique_temp_name();
         self.body.push_str(&format!(
-            "  {0} = getelementptr inbounds [21 x i8], [21 x i8]* {1}, i32 0, i32 0\n",
-            buffer_ptr, buffer
+            "  {0} = call i8* @pds_gc_alocar_atomico(i64 {1})\n",
+            buffer_ptr, tamanho
         ));
+        buffer_ptr
+    }
+
+    fn convert_int_to_string(&mut self, int_reg: &str) -> String {
+        let buffer_ptr = self.allocate_string_buffer(21);
 
         let format_specifier_ptr = self.get_unique_temp_name();
         self.body.push_str(&format!(
@@ -3232,7 +3590,7 @@ impl<'a> LlvmGenerator<'a> {
 
         let buffer_reg = self.get_unique_temp_name();
         self.body.push_str(&format!(
-            "  {0} = call i8* @malloc(i64 {1})\n",
+            "  {0} = call i8* @pds_gc_alocar_atomico(i64 {1})\n",
             buffer_reg, alloc_size_reg
         ));
 
@@ -3263,7 +3621,7 @@ impl<'a> LlvmGenerator<'a> {
 
     fn create_global_string(&mut self, text: &str) -> String {
         let str_len = text.len() + 1;
-        let str_name = format!("@.str.{0}", self.string_counter);
+        let str_name = format!("@.str.{0}{1}", self.prefixo_globais, self.string_counter);
         self.string_counter += 1;
         let sanitized_text = text
             .replace('\\', "\\")
@@ -3282,6 +3640,90 @@ impl<'a> LlvmGenerator<'a> {
         ptr_register
     }
 
+    /// Ponteiro constante para um texto das verificações, sem repetir a global no header.
+    fn check_string_constant(&mut self, text: &str) -> String {
+        if let Some(ptr) = self.textos_verificacao.get(text) {
+            return ptr.clone();
+        }
+        let name = format!(
+            "@.chk.{0}{1}",
+            self.prefixo_globais,
+            self.textos_verificacao.len()
+        );
+        let len = text.len() + 1;
+        self.header.push_str(&format!(
+            "{0} = private unnamed_addr constant [{1} x i8] c\"{2}\\00\", align 1\n",
+            name,
+            len,
+            debug_info::escape(text)
+        ));
+        let ptr = format!(
+            "getelementptr inbounds ([{0} x i8], [{0} x i8]* {1}, i32 0, i32 0)",
+            len, name
+        );
+        self.textos_verificacao.insert(text.to_string(), ptr.clone());
+        ptr
+    }
+
+    /// Se `falha` (i1) for verdadeiro, termina o programa com `mensagem` e a posição do comando
+    /// atual. O código emitido depois continua no bloco em que a verificação passou.
+    fn emit_runtime_check(&mut self, falha: &str, mensagem: &str) {
+        let erro_label = self.get_unique_label("chk.erro");
+        let ok_label = self.get_unique_label("chk.ok");
+        self.body.push_str(&format!(
+            "  br i1 {0}, label %{1}, label %{2}\n",
+            falha, erro_label, ok_label
+        ));
+        self.body.push_str(&format!("{0}:\n", erro_label));
+        let mensagem_ptr = self.check_string_constant(mensagem);
+        let (arquivo_ptr, linha, coluna) = match self.posicao_atual.clone() {
+            Some(pos) => (
+                self.check_string_constant(&pos.arquivo),
+                pos.linha,
+                pos.coluna,
+            ),
+            None => ("null".to_string(), 0, 0),
+        };
+        self.body.push_str(&format!(
+            "  call void @pds_panico(i8* {0}, i8* {1}, i32 {2}, i32 {3})\n  unreachable\n",
+            mensagem_ptr, arquivo_ptr, linha, coluna
+        ));
+        self.body.push_str(&format!("{0}:\n", ok_label));
+    }
+
+    /// Verifica `0 <= idx < len` antes de um acesso por índice (mesmas mensagens da VM).
+    fn emit_index_check(&mut self, idx_reg: &str, len_reg: &str) {
+        if !self.verificacoes {
+            return;
+        }
+        let neg = self.get_unique_temp_name();
+        self.body
+            .push_str(&format!("  {0} = icmp slt i32 {1}, 0\n", neg, idx_reg));
+        self.emit_runtime_check(&neg, "Índice negativo");
+        let ge = self.get_unique_temp_name();
+        self.body.push_str(&format!(
+            "  {0} = icmp sge i32 {1}, {2}\n",
+            ge, idx_reg, len_reg
+        ));
+        self.emit_runtime_check(&ge, "Índice fora do intervalo");
+    }
+
+    /// Verifica que o objeto não é `nulo` antes de acessar um membro. `este` e objetos recém
+    /// criados nunca são nulos e não são verificados.
+    fn emit_null_check(&mut self, obj_expr: &ast::Expressao, obj_reg: &str, obj_ptr_type: &str, mensagem: &str) {
+        if !self.verificacoes
+            || matches!(obj_expr, ast::Expressao::Este | ast::Expressao::NovoObjeto(..))
+        {
+            return;
+        }
+        let nulo = self.get_unique_temp_name();
+        self.body.push_str(&format!(
+            "  {0} = icmp eq {1} {2}, null\n",
+            nulo, obj_ptr_type, obj_reg
+        ));
+        self.emit_runtime_check(&nulo, mensagem);
+    }
+
     fn get_unique_temp_name(&mut self) -> String {
         let name = format!("%tmp.{0}", self.temp_counter);
         self.temp_counter += 1;
@@ -3357,7 +3799,10 @@ impl<'a> LlvmGenerator<'a> {
             ast::Tipo::Classe(_) => self.map_type_to_llvm_ptr(tipo),
             ast::Tipo::Aplicado { .. } => self.map_type_to_llvm_ptr(tipo),
             ast::Tipo::Lista(_) => "%array*".to_string(),
-            _ => panic!("Tipo LLVM não mapeado para armazenamento: {:?}", tipo),
+            _ => self.falhar(
+                format!("Tipo LLVM não mapeado para armazenamento: {:?}", tipo),
+                "i8*".to_string(),
+            ),
         }
     }
 
@@ -3380,7 +3825,10 @@ impl<'a> LlvmGenerator<'a> {
                 format!("%class.{0}*", sanitized_name)
             }
             ast::Tipo::Lista(_) => "%array**".to_string(),
-            _ => panic!("Não é possível criar um ponteiro para o tipo: {:?}", tipo),
+            _ => self.falhar(
+                format!("Não é possível criar um ponteiro para o tipo: {:?}", tipo),
+                "i8**".to_string(),
+            ),
         }
     }
 
@@ -3397,7 +3845,10 @@ impl<'a> LlvmGenerator<'a> {
             ast::Tipo::Classe(_) => self.map_type_to_llvm_ptr(tipo),
             ast::Tipo::Aplicado { .. } => self.map_type_to_llvm_ptr(tipo),
             ast::Tipo::Lista(_) => "%array*".to_string(),
-            _ => panic!("Tipo LLVM não mapeado para argumento: {:?}", tipo),
+            _ => self.falhar(
+                format!("Tipo LLVM não mapeado para argumento: {:?}", tipo),
+                "i8*".to_string(),
+            ),
         }
     }
 }
@@ -3423,12 +3874,7 @@ impl<'a> LlvmGenerator<'a> {
         }
         
         // Criar vtables para classes genéricas aplicadas (monomorfização)
-        let mut applied_items: Vec<(String, Vec<ast::Tipo>)> = Vec::new();
-        for (base_fqn, insts) in &self.applied_class_insts {
-            for args in insts {
-                applied_items.push((base_fqn.clone(), args.clone()));
-            }
-        }
+        let applied_items = instancias_ordenadas(&self.applied_class_insts);
         for (base_fqn, args) in applied_items {
             let mangled_name = self.mangle_aplicado_name(&base_fqn, &args);
             // Reusa a vtable da classe base para instanciações genéricas
@@ -3515,6 +3961,7 @@ impl<'a> LlvmGenerator<'a> {
     }
 
     fn declare_external_functions(&mut self) {
+        self.declare_foreign_functions();
         if self.type_checker.biblioteca_externa.is_none() {
             return;
         }
@@ -3541,8 +3988,8 @@ impl<'a> LlvmGenerator<'a> {
                 if ret_llvm.starts_with("%class.") {
                     undefined_structs.insert(ret_llvm.trim_end_matches('*').to_string());
                 }
-                for (p_tipo, _) in &m.parametros {
-                    let p_llvm = self.map_string_to_llvm_type(p_tipo);
+                for p in &m.parametros {
+                    let p_llvm = self.map_string_to_llvm_type(&p.tipo);
                     if p_llvm.starts_with("%class.") {
                         undefined_structs.insert(p_llvm.trim_end_matches('*').to_string());
                     }
@@ -3588,8 +4035,8 @@ impl<'a> LlvmGenerator<'a> {
                     vec![self_ptr_ty.clone()]
                 };
 
-                for (p_tipo, _p_nome) in &m.parametros {
-                    params_llvm.push(self.map_string_to_llvm_type(p_tipo));
+                for p in &m.parametros {
+                    params_llvm.push(self.map_string_to_llvm_type(&p.tipo));
                 }
 
                 self.header.push_str(&format!(
@@ -3627,15 +4074,20 @@ impl<'a> LlvmGenerator<'a> {
                     let fun_sym = format!("{}::{}", decl_cls, metodo_nome).replace('.', "_");
 
                     // Descobre a assinatura exata do método na classe declarante
-                    let metodo_decl = self
+                    let Some(metodo_decl) = self
                         .type_checker
                         .classes
                         .get(decl_cls)
                         .and_then(|c| c.metodos.iter().find(|m| m.nome == *metodo_nome))
-                        .unwrap_or_else(|| panic!(
-                            "Método '{}' não encontrado em classe declarante '{}' ao construir vtable de '{}'",
-                            metodo_nome, decl_cls, fqn
-                        ));
+                    else {
+                        return self.falhar(
+                            format!(
+                                "Método '{}' não encontrado em classe declarante '{}' ao construir vtable de '{}'",
+                                metodo_nome, decl_cls, fqn
+                            ),
+                            "i8* null".to_string(),
+                        );
+                    };
 
                     // Resolve tipos no namespace da classe declarante
                     let decl_ns = self.get_namespace_from_fqn(decl_cls);
@@ -3713,22 +4165,4 @@ impl<'a> LlvmGenerator<'a> {
         ));
         (data_ptr, len_reg)
     }
-
-    fn zero_value_of(&mut self, tipo: &ast::Tipo) -> String {
-        match tipo {
-            ast::Tipo::Inteiro | ast::Tipo::Enum(_) => "0".to_string(),
-            ast::Tipo::Booleano => "0".to_string(),
-            ast::Tipo::Flutuante => {
-                let z = self.get_unique_temp_name();
-                self.body
-                    .push_str(&format!("  {0} = fptrunc double 0.0 to float\n", z));
-                z
-            }
-            ast::Tipo::Duplo => "0.0".to_string(),
-            ast::Tipo::Texto | ast::Tipo::Decimal | ast::Tipo::Classe(_) | ast::Tipo::Lista(_) => {
-                "null".to_string()
-            }
-            _ => "0".to_string(),
-        }
-    }
 }
@@ -0,0 +1,510 @@
+//! Metadados DWARF para o LLVM IR (`--debug-info`).
+//!
+//! O gerador monta o IR como texto; aqui ficam os nós `!N` de depuração. Cada função recebe um
+//! `DISubprogram`, cada `Comando::Posicao` vira um `DILocation` anexado (`!dbg`) às instruções
+//! seguintes, e as variáveis locais e parâmetros são declarados com `llvm.dbg.declare`, com
+//! tipos que seguem o layout de `map_type_to_llvm_storage` e de `define_struct`.
+
+use super::LlvmGenerator;
+use crate::ast;
+use std::collections::HashMap;
+
+/// Nós de metadados do módulo e os caches de arquivos e tipos.
+pub(super) struct DebugInfo {
+    nodes: Vec<String>,
+    files: HashMap<String, usize>,
+    types: HashMap<String, usize>,
+    directory: String,
+    main_file: usize,
+    compile_unit: usize,
+    module_flags: Vec<usize>,
+    function: Option<DebugFunction>,
+}
+
+/// Função em geração. As posições são guardadas como offsets em `body`, que começa vazio a
+/// cada função; o `!dbg` é anexado às linhas quando a função termina.
+struct DebugFunction {
+    subprogram: usize,
+    file: usize,
+    line: usize,
+    default_location: usize,
+    locations: Vec<(usize, usize)>,
+    next_arg: usize,
+}
+
+/// Escapa uma string para metadados LLVM (`\XX` para aspas, barras e bytes não imprimíveis).
+pub(super) fn escape(texto: &str) -> String {
+    let mut saida = String::new();
+    for byte in texto.bytes() {
+        if byte.is_ascii_graphic() && byte != b'"' && byte != b'\\' || byte == b' ' {
+            saida.push(byte as char);
+        } else {
+            saida.push_str(&format!("\\{:02X}", byte));
+        }
+    }
+    saida
+}
+
+impl DebugInfo {
+    pub(super) fn new(arquivo_principal: &str) -> Self {
+        let directory = std::env::current_dir()
+            .map(|p| p.display().to_string())
+            .unwrap_or_default();
+        let mut info = DebugInfo {
+            nodes: Vec::new(),
+            files: HashMap::new(),
+            types: HashMap::new(),
+            directory,
+            main_file: 0,
+            compile_unit: 0,
+            module_flags: Vec::new(),
+            function: None,
+        };
+        info.main_file = info.file(arquivo_principal);
+        info.compile_unit = info.node(format!(
+            "distinct !DICompileUnit(language: DW_LANG_C99, file: !{}, producer: \"compilador-portugues {}\", isOptimized: false, runtimeVersion: 0, emissionKind: FullDebug)",
+            info.main_file,
+            env!("CARGO_PKG_VERSION")
+        ));
+        let dwarf = info.node("!{i32 2, !\"Dwarf Version\", i32 4}".to_string());
+        let versao = info.node("!{i32 2, !\"Debug Info Version\", i32 3}".to_string());
+        info.module_flags = vec![dwarf, versao];
+        info
+    }
+
+    fn node(&mut self, corpo: String) -> usize {
+        self.nodes.push(corpo);
+        self.nodes.len() - 1
+    }
+
+    /// Reserva um nó para tipos recursivos (classe com campo do próprio tipo).
+    fn reserve(&mut self) -> usize {
+        self.node(String::new())
+    }
+
+    fn set(&mut self, id: usize, corpo: String) {
+        self.nodes[id] = corpo;
+    }
+
+    fn tuple(&mut self, ids: &[Option<usize>]) -> usize {
+        let itens: Vec<String> = ids
+            .iter()
+            .map(|id| id.map_or("null".to_string(), |i| format!("!{}", i)))
+            .collect();
+        self.node(format!("!{{{}}}", itens.join(", ")))
+    }
+
+    fn file(&mut self, caminho: &str) -> usize {
+        if let Some(&id) = self.files.get(caminho) {
+            return id;
+        }
+        let path = std::path::Path::new(caminho);
+        let (diretorio, nome) = if path.is_absolute() {
+            // gdb/lldb casam `break conta.pr:17` pelo nome do arquivo
+            let diretorio = path.parent().map(|p| p.display().to_string());
+            let nome = path.file_name().map(|n| n.to_string_lossy().into_owned());
+            (
+                diretorio.unwrap_or_default(),
+                nome.unwrap_or_else(|| caminho.to_string()),
+            )
+        } else {
+            (self.directory.clone(), caminho.to_string())
+        };
+        let id = self.node(format!(
+            "!DIFile(filename: \"{}\", directory: \"{}\")",
+            escape(&nome),
+            escape(&diretorio)
+        ));
+        self.files.insert(caminho.to_string(), id);
+        id
+    }
+
+    fn basic(&mut self, nome: &str, bits: u32, encoding: &str) -> usize {
+        self.node(format!(
+            "!DIBasicType(name: \"{}\", size: {}, encoding: {})",
+            nome, bits, encoding
+        ))
+    }
+
+    fn pointer(&mut self, base: Option<usize>) -> String {
+        format!(
+            "!DIDerivedType(tag: DW_TAG_pointer_type, baseType: {}, size: 64)",
+            base.map_or("null".to_string(), |b| format!("!{}", b))
+        )
+    }
+
+    fn location(&mut self, linha: usize, coluna: usize, escopo: usize) -> usize {
+        self.node(format!(
+            "!DILocation(line: {}, column: {}, scope: !{})",
+            linha, coluna, escopo
+        ))
+    }
+
+    /// Texto final dos metadados, anexado ao fim do módulo.
+    pub(super) fn render(&self) -> String {
+        let mut saida = String::from("\n");
+        saida.push_str(&format!("!llvm.dbg.cu = !{{!{}}}\n", self.compile_unit));
+        let flags: Vec<String> = self
+            .module_flags
+            .iter()
+            .map(|f| format!("!{}", f))
+            .collect();
+        saida.push_str(&format!("!llvm.module.flags = !{{{}}}\n", flags.join(", ")));
+        for (id, corpo) in self.nodes.iter().enumerate() {
+            saida.push_str(&format!("!{} = {}\n", id, corpo));
+        }
+        saida
+    }
+}
+
+/// Primeira posição de um corpo, usada como linha da declaração da função.
+pub(super) fn first_position(corpo: &[ast::Comando]) -> Option<&ast::Posicao> {
+    corpo.iter().find_map(|c| match c {
+        ast::Comando::Posicao(p) => Some(p),
+        _ => None,
+    })
+}
+
+/// Tamanho e alinhamento (em bits) do tipo como campo de struct, como em `define_struct`.
+fn storage_layout(tipo: &ast::Tipo) -> (u64, u64) {
+    match tipo {
+        ast::Tipo::Inteiro | ast::Tipo::Enum(_) | ast::Tipo::Flutuante => (32, 32),
+        ast::Tipo::Booleano => (8, 8),
+        _ => (64, 64),
+    }
+}
+
+impl<'a> LlvmGenerator<'a> {
+    /// Ativa a emissão de metadados de depuração; `arquivo` é o fonte principal da unidade.
+    pub fn enable_debug_info(&mut self, arquivo: &str) {
+        self.debug = Some(DebugInfo::new(arquivo));
+    }
+
+    pub(super) fn debug_metadata(&self) -> String {
+        self.debug
+            .as_ref()
+            .map(DebugInfo::render)
+            .unwrap_or_default()
+    }
+
+    /// Tipo DWARF de um tipo da linguagem (já resolvido), ou `None` se não houver equivalente.
+    fn debug_type(&mut self, tipo: &ast::Tipo) -> Option<usize> {
+        let chave = format!("{:?}", tipo);
+        if let Some(&id) = self.debug.as_ref()?.types.get(&chave) {
+            return Some(id);
+        }
+        let id = match tipo {
+            ast::Tipo::Inteiro => self.debug.as_mut()?.basic("inteiro", 32, "DW_ATE_signed"),
+            ast::Tipo::Booleano => self.debug.as_mut()?.basic("booleano", 8, "DW_ATE_boolean"),
+            ast::Tipo::Flutuante => self.debug.as_mut()?.basic("flutuante", 32, "DW_ATE_float"),
+            ast::Tipo::Duplo => self.debug.as_mut()?.basic("duplo", 64, "DW_ATE_float"),
+            ast::Tipo::Texto | ast::Tipo::Decimal => {
+                // `texto` é um `char*` com nome próprio: o depurador mostra a string
+                let debug = self.debug.as_mut()?;
+                let caractere = debug.basic("caractere", 8, "DW_ATE_signed_char");
+                let ponteiro = debug.pointer(Some(caractere));
+                let ponteiro = debug.node(ponteiro);
+                debug.node(format!(
+                    "!DIDerivedType(tag: DW_TAG_typedef, name: \"{}\", file: !{}, baseType: !{})",
+                    tipo, debug.main_file, ponteiro
+                ))
+            }
+            ast::Tipo::Enum(nome) => {
+                let valores = self
+                    .type_checker
+                    .enums
+                    .get(nome)
+                    .map(|e| e.valores.clone())
+                    .unwrap_or_default();
+                let base = self.debug_type(&ast::Tipo::Inteiro);
+                let debug = self.debug.as_mut()?;
+                let enumeradores: Vec<Option<usize>> = valores
+                    .iter()
+                    .enumerate()
+                    .map(|(i, v)| {
+                        Some(debug.node(format!(
+                            "!DIEnumerator(name: \"{}\", value: {})",
+                            escape(v),
+                            i
+                        )))
+                    })
+                    .collect();
+                let elementos = debug.tuple(&enumeradores);
+                debug.node(format!(
+                    "!DICompositeType(tag: DW_TAG_enumeration_type, name: \"{}\", file: !{}, size: 32, baseType: !{}, elements: !{})",
+                    escape(nome),
+                    debug.main_file,
+                    base?,
+                    elementos
+                ))
+            }
+            ast::Tipo::Classe(nome) | ast::Tipo::Aplicado { nome, .. } => {
+                // Objetos são ponteiros para `%class.X`; o ponteiro entra no cache antes da
+                // struct para que campos do próprio tipo o reutilizem
+                let ponteiro = self.debug.as_mut()?.reserve();
+                self.debug.as_mut()?.types.insert(chave.clone(), ponteiro);
+                let estrutura = self.debug_class_struct(nome);
+                let corpo = self.debug.as_mut()?.pointer(estrutura);
+                self.debug.as_mut()?.set(ponteiro, corpo);
+                ponteiro
+            }
+            ast::Tipo::Lista(elemento) => {
+                let elemento = self.debug_type(elemento);
+                let tamanho = self.debug_type(&ast::Tipo::Inteiro);
+                let debug = self.debug.as_mut()?;
+                let estrutura = debug.reserve();
+                let dados = debug.pointer(elemento);
+                let dados = debug.node(dados);
+                let membros = [("tamanho", tamanho?, 32, 0), ("dados", dados, 64, 64)]
+                    .iter()
+                    .map(|(nome, base, bits, offset)| {
+                        Some(debug.node(format!(
+                            "!DIDerivedType(tag: DW_TAG_member, name: \"{}\", scope: !{}, file: !{}, baseType: !{}, size: {}, offset: {})",
+                            nome, estrutura, debug.main_file, base, bits, offset
+                        )))
+                    })
+                    .collect::<Vec<_>>();
+                let elementos = debug.tuple(&membros);
+                debug.set(
+                    estrutura,
+                    format!(
+                        "!DICompositeType(tag: DW_TAG_structure_type, name: \"{}\", file: !{}, size: 128, elements: !{})",
+                        escape(&tipo.to_string()),
+                        debug.main_file,
+                        elementos
+                    ),
+                );
+                let ponteiro = debug.pointer(Some(estrutura));
+                debug.node(ponteiro)
+            }
+            _ => return None,
+        };
+        self.debug.as_mut()?.types.insert(chave, id);
+        Some(id)
+    }
+
+    /// `%class.X` como struct DWARF: ponteiro da vtable, campos e propriedades, na ordem e
+    /// com o alinhamento natural usados por `define_struct`.
+    fn debug_class_struct(&mut self, fqn: &str) -> Option<usize> {
+        let estrutura = self.debug.as_mut()?.reserve();
+        let main_file = self.debug.as_ref()?.main_file;
+        let Some(info) = self.resolved_classes.get(fqn) else {
+            // Interfaces e classes genéricas aplicadas: struct só declarada
+            self.debug.as_mut()?.set(
+                estrutura,
+                format!(
+                    "!DICompositeType(tag: DW_TAG_structure_type, name: \"{}\", file: !{}, flags: DIFlagFwdDecl)",
+                    escape(fqn),
+                    main_file
+                ),
+            );
+            return Some(estrutura);
+        };
+        let namespace = self.get_namespace_from_fqn(fqn);
+        let mut campos: Vec<(String, ast::Tipo)> = info
+            .fields
+            .iter()
+            .map(|f| (f.nome.clone(), f.tipo.clone()))
+            .collect();
+        campos.extend(
+            info.properties
+                .iter()
+                .map(|p| (p.nome.clone(), p.tipo.clone())),
+        );
+
+        let vtable = {
+            let debug = self.debug.as_mut()?;
+            let corpo = debug.pointer(None);
+            let base = debug.node(corpo);
+            let corpo = debug.pointer(Some(base));
+            debug.node(corpo)
+        };
+        let mut membros = vec![Some(self.debug.as_mut()?.node(format!(
+            "!DIDerivedType(tag: DW_TAG_member, name: \"_vtable\", scope: !{}, file: !{}, baseType: !{}, size: 64, offset: 0)",
+            estrutura, main_file, vtable
+        )))];
+        let mut offset = 64u64;
+        let mut alinhamento = 64u64;
+        for (nome, tipo) in campos {
+            let tipo = self.resolve_type(&tipo, &namespace);
+            let (bits, align) = storage_layout(&tipo);
+            offset = offset.div_ceil(align) * align;
+            alinhamento = alinhamento.max(align);
+            if let Some(base) = self.debug_type(&tipo) {
+                membros.push(Some(self.debug.as_mut()?.node(format!(
+                    "!DIDerivedType(tag: DW_TAG_member, name: \"{}\", scope: !{}, file: !{}, baseType: !{}, size: {}, offset: {})",
+                    escape(&nome), estrutura, main_file, base, bits, offset
+                ))));
+            }
+            offset += bits;
+        }
+        let tamanho = offset.div_ceil(alinhamento) * alinhamento;
+        let debug = self.debug.as_mut()?;
+        let elementos = debug.tuple(&membros);
+        debug.set(
+            estrutura,
+            format!(
+                "!DICompositeType(tag: DW_TAG_structure_type, name: \"{}\", file: !{}, size: {}, elements: !{})",
+                escape(fqn),
+                main_file,
+                tamanho,
+                elementos
+            ),
+        );
+        Some(estrutura)
+    }
+
+    /// Abre o `DISubprogram` da função cujo `define` acabou de ser escrito em `body`.
+    /// `params` inclui `este` em métodos e construtores.
+    pub(super) fn debug_begin_function(
+        &mut self,
+        nome: &str,
+        simbolo: &str,
+        retorno: &ast::Tipo,
+        params: &[ast::Tipo],
+        posicao: Option<&ast::Posicao>,
+    ) {
+        if self.debug.is_none() {
+            return;
+        }
+        let mut tipos = vec![self.debug_type(retorno)];
+        tipos.extend(params.iter().map(|p| self.debug_type(p)));
+        let Some(debug) = self.debug.as_mut() else {
+            return;
+        };
+        let linha = posicao.map_or(0, |p| p.linha);
+        let arquivo = posicao.map_or(debug.main_file, |p| debug.file(&p.arquivo));
+        let tipos = debug.tuple(&tipos);
+        let assinatura = debug.node(format!("!DISubroutineType(types: !{})", tipos));
+        let subprogram = debug.node(format!(
+            "distinct !DISubprogram(name: \"{}\", linkageName: \"{}\", scope: !{}, file: !{}, line: {}, type: !{}, scopeLine: {}, spFlags: DISPFlagDefinition, unit: !{})",
+            escape(nome),
+            escape(simbolo),
+            arquivo,
+            arquivo,
+            linha,
+            assinatura,
+            linha,
+            debug.compile_unit
+        ));
+        let default_location = debug.location(linha, 0, subprogram);
+        debug.function = Some(DebugFunction {
+            subprogram,
+            file: arquivo,
+            line: linha,
+            default_location,
+            locations: Vec::new(),
+            next_arg: 1,
+        });
+    }
+
+    /// Marca o início das instruções de um comando (`Comando::Posicao`).
+    pub(super) fn debug_position(&mut self, posicao: &ast::Posicao) {
+        let offset = self.body.len();
+        let Some(debug) = self.debug.as_mut() else {
+            return;
+        };
+        let Some(subprogram) = debug.function.as_ref().map(|f| f.subprogram) else {
+            return;
+        };
+        let local = debug.location(posicao.linha, posicao.coluna, subprogram);
+        if let Some(funcao) = debug.function.as_mut() {
+            funcao.line = posicao.linha;
+            funcao.locations.push((offset, local));
+        }
+    }
+
+    /// Declara a variável local (ou parâmetro) alocada em `ptr_reg` para o depurador.
+    pub(super) fn debug_declare(
+        &mut self,
+        nome: &str,
+        ptr_reg: &str,
+        tipo: &ast::Tipo,
+        parametro: bool,
+    ) {
+        if self
+            .debug
+            .as_ref()
+            .and_then(|d| d.function.as_ref())
+            .is_none()
+        {
+            return;
+        }
+        let Some(tipo_debug) = self.debug_type(tipo) else {
+            return;
+        };
+        let armazenamento = self.map_type_to_llvm_storage(tipo);
+        let Some(debug) = self.debug.as_mut() else {
+            return;
+        };
+        let Some(funcao) = debug.function.as_mut() else {
+            return;
+        };
+        let arg = if parametro {
+            funcao.next_arg += 1;
+            format!("arg: {}, ", funcao.next_arg - 1)
+        } else {
+            String::new()
+        };
+        let (escopo, arquivo, linha) = (funcao.subprogram, funcao.file, funcao.line);
+        let variavel = debug.node(format!(
+            "!DILocalVariable(name: \"{}\", {}scope: !{}, file: !{}, line: {}, type: !{})",
+            escape(nome),
+            arg,
+            escopo,
+            arquivo,
+            linha,
+            tipo_debug
+        ));
+        self.body.push_str(&format!(
+            "  call void @llvm.dbg.declare(metadata {0}* {1}, metadata !{2}, metadata !DIExpression())\n",
+            armazenamento, ptr_reg, variavel
+        ));
+    }
+
+    /// Fecha a função: anexa o `DISubprogram` ao `define` e `!dbg` a cada instrução.
+    pub(super) fn debug_finish_function(&mut self) {
+        let Some(funcao) = self.debug.as_mut().and_then(|d| d.function.take()) else {
+            return;
+        };
+        let mut saida = String::with_capacity(self.body.len() * 2);
+        let mut posicoes = funcao.locations.iter().peekable();
+        let mut atual = funcao.default_location;
+        let mut offset = 0;
+        for linha in self.body.split_inclusive('\n') {
+            while let Some(&&(inicio, local)) = posicoes.peek() {
+                if inicio > offset {
+                    break;
+                }
+                atual = local;
+                posicoes.next();
+            }
+            offset += linha.len();
+
+            let conteudo = linha.trim_end_matches('\n');
+            let instrucao = conteudo.trim_start();
+            if conteudo.starts_with("define ") {
+                if let Some(chave) = conteudo.rfind('{') {
+                    saida.push_str(&format!(
+                        "{}!dbg !{} {}\n",
+                        &conteudo[..chave],
+                        funcao.subprogram,
+                        &conteudo[chave..]
+                    ));
+                    continue;
+                }
+            }
+            if !conteudo.starts_with("  ") || instrucao.is_empty() || instrucao.starts_with(';') {
+                saida.push_str(linha);
+                continue;
+            }
+            // Comentário no fim da linha (`unreachable ; ...`) fica depois do anexo
+            let (codigo, comentario) = match conteudo.find(" ;") {
+                Some(i) => conteudo.split_at(i),
+                None => (conteudo, ""),
+            };
+            saida.push_str(&format!("{}, !dbg !{}{}\n", codigo, atual, comentario));
+        }
+        self.body = saida;
+    }
+}
@@ -0,0 +1,155 @@
+//! Funções C chamadas pelo executável (`[Externo("biblioteca", "símbolo")] externo função ...`).
+//!
+//! Cada função externa vira um `declare` com a ABI do C e não tem corpo no módulo. Nas chamadas,
+//! os argumentos são convertidos para o tipo C: `inteiro` → `int`, `duplo` → `double`,
+//! `flutuante` → `float`, `texto` → `char*` e `booleano` → `int` (0 ou 1). As bibliotecas
+//! citadas são repassadas ao clang como `-l` (veja `codegen::flag_biblioteca`).
+
+use super::LlvmGenerator;
+use crate::ast;
+use std::collections::HashSet;
+
+/// Biblioteca e símbolo C de uma função marcada com `[Externo(...)]`.
+pub(super) fn externo(func: &ast::DeclaracaoFuncao) -> Option<(&str, &str)> {
+    let atributo = func.attributes.iter().find(|a| a.name == "Externo")?;
+    match atributo.arguments.as_slice() {
+        [ast::Expressao::Texto(biblioteca), ast::Expressao::Texto(simbolo)] => {
+            Some((biblioteca, simbolo))
+        }
+        _ => None,
+    }
+}
+
+/// Tipo do parâmetro ou retorno na ABI do C (o verificador de tipos só aceita estes).
+fn c_type(tipo: &ast::Tipo) -> &'static str {
+    match tipo {
+        ast::Tipo::Inteiro | ast::Tipo::Booleano => "i32",
+        ast::Tipo::Duplo => "double",
+        ast::Tipo::Flutuante => "float",
+        ast::Tipo::Texto => "i8*",
+        _ => "void",
+    }
+}
+
+impl<'a> LlvmGenerator<'a> {
+    /// Bibliotecas C usadas pelas funções externas do programa, na ordem em que aparecem.
+    pub fn external_libraries(&self) -> &[String] {
+        &self.bibliotecas_externas
+    }
+
+    /// Emite um `declare` por símbolo C e registra as bibliotecas para o linker.
+    pub(super) fn declare_foreign_functions(&mut self) {
+        let programa = self.programa;
+        let funcoes = programa
+            .declaracoes
+            .iter()
+            .chain(
+                programa
+                    .namespaces
+                    .iter()
+                    .flat_map(|ns| ns.declaracoes.iter()),
+            )
+            .filter_map(|d| match d {
+                ast::Declaracao::DeclaracaoFuncao(f) => Some(f),
+                _ => None,
+            });
+        let mut declarados = HashSet::new();
+        for func in funcoes {
+            let Some((biblioteca, simbolo)) = externo(func) else {
+                continue;
+            };
+            if !self.bibliotecas_externas.iter().any(|b| b == biblioteca) {
+                self.bibliotecas_externas.push(biblioteca.to_string());
+            }
+            // Símbolos da libc usados pelo gerador (printf, strlen...) já estão declarados
+            if !declarados.insert(simbolo) || self.runtime_declaration(simbolo).is_some() {
+                continue;
+            }
+            let retorno = c_type(func.tipo_retorno.as_ref().unwrap_or(&ast::Tipo::Vazio));
+            let parametros: Vec<&str> = func.parametros.iter().map(|p| c_type(&p.tipo)).collect();
+            self.header.push_str(&format!(
+                "declare {0} @\"{1}\"({2})\n",
+                retorno,
+                simbolo,
+                parametros.join(", ")
+            ));
+        }
+    }
+
+    /// Tipo (`ret (params)`) de um símbolo que o próprio gerador já declara no header.
+    fn runtime_declaration(&self, simbolo: &str) -> Option<String> {
+        let marca = format!(" @{}(", simbolo);
+        let linha = self
+            .header
+            .lines()
+            .find(|l| l.starts_with("declare ") && l.contains(&marca))?;
+        let (antes, depois) = linha.split_once(&marca)?;
+        let retorno = antes
+            .trim_start_matches("declare ")
+            .trim_start_matches("noalias ");
+        let parametros = &depois[..depois.rfind(')')?];
+        Some(format!("{} ({})", retorno, parametros))
+    }
+
+    /// Chama o símbolo C de `func`, convertendo os argumentos e o retorno.
+    pub(super) fn call_foreign_function(
+        &mut self,
+        func: &ast::DeclaracaoFuncao,
+        simbolo: &str,
+        argumentos: &[ast::Expressao],
+    ) -> (String, ast::Tipo) {
+        let mut args = Vec::new();
+        for (arg, param) in argumentos.iter().zip(&func.parametros) {
+            let (reg, tipo) = self.generate_expressao(arg);
+            let valor = if param.tipo == ast::Tipo::Booleano {
+                let bool_reg = self.ensure_bool(&reg, &tipo);
+                let int_reg = self.get_unique_temp_name();
+                self.body
+                    .push_str(&format!("  {0} = zext i1 {1} to i32\n", int_reg, bool_reg));
+                int_reg
+            } else {
+                self.ensure_value_type(&reg, &tipo, &param.tipo)
+            };
+            args.push(format!("{0} {1}", c_type(&param.tipo), valor));
+        }
+
+        let retorno = func.tipo_retorno.clone().unwrap_or(ast::Tipo::Vazio);
+        let tipo_funcao = format!(
+            "{} ({})",
+            c_type(&retorno),
+            func.parametros
+                .iter()
+                .map(|p| c_type(&p.tipo))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        // Se o gerador declarou o símbolo com outra assinatura (ex.: `strlen` com retorno i64),
+        // chama pelo tipo declarado pelo usuário
+        let alvo = match self.runtime_declaration(simbolo) {
+            Some(declarado) if declarado != tipo_funcao => {
+                format!(
+                    "bitcast ({0}* @{1} to {2}*)",
+                    declarado, simbolo, tipo_funcao
+                )
+            }
+            _ => format!("@\"{}\"", simbolo),
+        };
+        let chamada = format!("call {0} {1}({2})", c_type(&retorno), alvo, args.join(", "));
+        if retorno == ast::Tipo::Vazio {
+            self.body.push_str(&format!("  {}\n", chamada));
+            return (String::new(), retorno);
+        }
+        let resultado = self.get_unique_temp_name();
+        self.body
+            .push_str(&format!("  {0} = {1}\n", resultado, chamada));
+        if retorno == ast::Tipo::Booleano {
+            let bool_reg = self.get_unique_temp_name();
+            self.body.push_str(&format!(
+                "  {0} = icmp ne i32 {1}, 0\n",
+                bool_reg, resultado
+            ));
+            return (bool_reg, retorno);
+        }
+        (resultado, retorno)
+    }
+}
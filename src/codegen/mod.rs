@@ -1,7 +1,10 @@
 pub mod bytecode;
+pub mod c;
 pub mod cil;
 pub mod console;
 pub mod llvm_ir;
+pub mod tabelas;
+pub mod wasm;
 
 use crate::ast;
 use std::env;
@@ -30,8 +33,143 @@ fn clang_executable() -> PathBuf {
     PathBuf::from("clang")
 }
 
-/// Compila o LLVM IR gerado e vincula o runtime nativo de async/await.
-/// Se existir LLVM IR da biblioteca padrão, também faz linkagem automática.
+/// `llvm-link` usado para unir o programa à biblioteca padrão antes de otimizar.
+/// Procura em `LLVM_LINK` e ao lado do clang escolhido; senão, no `PATH`.
+fn llvm_link_executable() -> PathBuf {
+    if let Some(path) = env::var_os("LLVM_LINK") {
+        return PathBuf::from(path);
+    }
+    let nome = if cfg!(windows) { "llvm-link.exe" } else { "llvm-link" };
+    let clang = clang_executable();
+    if let Some(dir) = clang.parent().filter(|d| !d.as_os_str().is_empty()) {
+        if dir.join(nome).is_file() {
+            return dir.join(nome);
+        }
+    }
+    PathBuf::from(nome)
+}
+
+/// Nível de otimização do pipeline LLVM (`-O0` a `-O3` e `-Os` no `compilador`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NivelOtimizacao {
+    #[default]
+    O0,
+    O1,
+    O2,
+    O3,
+    Os,
+}
+
+impl NivelOtimizacao {
+    /// Interpreta uma opção `-O<nível>`. `None` se a opção não for de otimização.
+    pub fn de_opcao(opcao: &str) -> Option<Result<Self, String>> {
+        let nivel = opcao.strip_prefix("-O")?;
+        Some(match nivel {
+            "0" => Ok(Self::O0),
+            "1" => Ok(Self::O1),
+            "2" => Ok(Self::O2),
+            "3" => Ok(Self::O3),
+            "s" => Ok(Self::Os),
+            _ => Err(format!(
+                "Nível de otimização inválido: {} (use -O0, -O1, -O2, -O3 ou -Os)",
+                opcao
+            )),
+        })
+    }
+
+    /// Opção equivalente do clang.
+    pub fn flag(self) -> &'static str {
+        match self {
+            Self::O0 => "-O0",
+            Self::O1 => "-O1",
+            Self::O2 => "-O2",
+            Self::O3 => "-O3",
+            Self::Os => "-Os",
+        }
+    }
+}
+
+/// Une o IR do programa ao da biblioteca padrão com `llvm-link`, para que o clang otimize os
+/// dois como um único módulo (inlining entre programa e stdlib). `None` se `llvm-link` falhar.
+fn unir_com_stdlib(ll_path: &Path, stdlib_ll_path: &Path) -> Option<PathBuf> {
+    let unido = ll_path.with_extension("unido.ll");
+    let output = Command::new(llvm_link_executable())
+        .arg(ll_path)
+        .arg(stdlib_ll_path)
+        .arg("-S")
+        .arg("-o")
+        .arg(&unido)
+        .output()
+        .ok()?;
+    output.status.success().then_some(unido)
+}
+
+/// IR da biblioteca padrão gerado antes do coletor de lixo aloca com `malloc`: o coletor não
+/// percorre esses blocos e liberaria objetos referenciados só por eles.
+fn rejeitar_stdlib_sem_coletor(stdlib_ll_path: &Path) -> Result<(), String> {
+    let ir = fs::read_to_string(stdlib_ll_path).map_err(|e| {
+        format!(
+            "Falha ao ler a biblioteca padrão {}: {}",
+            stdlib_ll_path.display(),
+            e
+        )
+    })?;
+    if ir.contains("call i8* @malloc(") {
+        return Err(format!(
+            "A biblioteca padrão {} foi gerada antes do coletor de lixo e aloca com malloc; \
+             recompile-a com --compilar-biblioteca",
+            stdlib_ll_path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// A VM de bytecode não carrega bibliotecas C: funções `[Externo(...)]` só existem no alvo LLVM.
+fn rejeitar_funcoes_externas(programa: &ast::Programa) -> Result<(), String> {
+    let externa = programa
+        .declaracoes
+        .iter()
+        .chain(programa.namespaces.iter().flat_map(|ns| ns.declaracoes.iter()))
+        .find_map(|d| match d {
+            ast::Declaracao::DeclaracaoFuncao(f)
+                if f.attributes.iter().any(|a| a.name == "Externo") =>
+            {
+                Some(&f.nome)
+            }
+            _ => None,
+        });
+    match externa {
+        Some(nome) => Err(format!(
+            "Função externa '{}' não é suportada no alvo bytecode; use --target=llvm-ir",
+            nome
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Opção `-l` do clang para a biblioteca de um `[Externo("biblioteca", "símbolo")]`: aceita
+/// `"m"`, `"libm"`, `"libm.so"` ou `"m.lib"`. A libc (e, no Windows, a libm, que faz parte do
+/// CRT) já é vinculada e não gera opção.
+pub fn flag_biblioteca(nome: &str) -> Option<String> {
+    let sem_extensao = [".so", ".dylib", ".dll", ".lib", ".a"]
+        .iter()
+        .find_map(|ext| nome.strip_suffix(ext))
+        .unwrap_or(nome);
+    let base = sem_extensao
+        .strip_prefix("lib")
+        .filter(|b| !b.is_empty())
+        .unwrap_or(sem_extensao);
+    match base {
+        "c" => None,
+        "m" if cfg!(windows) => None,
+        _ => Some(format!("-l{}", base)),
+    }
+}
+
+/// Compila o LLVM IR gerado e vincula o runtime nativo (async/await e coletor de lixo) e as
+/// bibliotecas C das funções externas (`bibliotecas`).
+/// Se existir LLVM IR da biblioteca padrão, também faz linkagem automática; com otimização,
+/// o programa e a stdlib são unidos antes (via `llvm-link`, quando disponível).
 ///
 /// O caminho do runtime é resolvido a partir do manifesto do compilador, para
 /// que a compilação funcione independentemente do diretório atual do processo.
@@ -39,31 +177,58 @@ pub fn compilar_llvm_ir_com_runtime(
     ll_path: &Path,
     nome_base: &str,
     stdlib_path: Option<&Path>,
+    otimizacao: NivelOtimizacao,
+    bibliotecas: &[String],
 ) -> Result<(), String> {
-    let runtime_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+    let runtime_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
         .join("src")
-        .join("runtime")
-        .join("async_runtime.c");
-
-    if !runtime_path.is_file() {
-        return Err(format!(
-            "Runtime async nativo não encontrado: {}",
-            runtime_path.display()
-        ));
+        .join("runtime");
+    let runtime_path = runtime_dir.join("async_runtime.c");
+    // Coletor de lixo: o código gerado aloca objetos, arrays e textos com pds_gc_alocar*
+    let gc_path = runtime_dir.join("gc_runtime.c");
+
+    for arquivo in [&runtime_path, &gc_path] {
+        if !arquivo.is_file() {
+            return Err(format!(
+                "Runtime nativo não encontrado: {}",
+                arquivo.display()
+            ));
+        }
     }
 
     let mut command = Command::new(clang_executable());
-    command.arg(ll_path);
-    command.arg(&runtime_path);
+    command.arg(otimizacao.flag());
 
     // Se existe LLVM IR da biblioteca padrão, adiciona para linkagem
-    if let Some(stdlib_dir) = stdlib_path {
-        let stdlib_ll_path = stdlib_dir.join("dist").join("sistema.ll");
-        if stdlib_ll_path.exists() {
+    let stdlib_ll_path = stdlib_path
+        .map(|dir| dir.join("dist").join("sistema.ll"))
+        .filter(|p| p.exists());
+    if let Some(stdlib_ll_path) = &stdlib_ll_path {
+        rejeitar_stdlib_sem_coletor(stdlib_ll_path)?;
+    }
+    match stdlib_ll_path {
+        Some(stdlib_ll_path) => {
             println!("  📦 Linkando com biblioteca padrão LLVM IR...");
-            command.arg(&stdlib_ll_path);
+            let unido = (otimizacao != NivelOtimizacao::O0)
+                .then(|| unir_com_stdlib(ll_path, &stdlib_ll_path))
+                .flatten();
+            match unido {
+                Some(unido) => {
+                    command.arg(unido);
+                }
+                None => {
+                    command.arg(ll_path).arg(&stdlib_ll_path);
+                }
+            }
+        }
+        None => {
+            command.arg(ll_path);
         }
     }
+    command.arg(&runtime_path);
+    command.arg(&gc_path);
+    // Depois dos objetos que usam os símbolos, como o linker espera
+    command.args(bibliotecas.iter().filter_map(|b| flag_biblioteca(b)));
 
     let mut output_file = nome_base.to_string();
     #[cfg(windows)]
@@ -90,6 +255,80 @@ pub fn compilar_llvm_ir_com_runtime(
     Ok(())
 }
 
+/// Compilador C usado pelo alvo `--target=c`: a variável `CC`, ou o primeiro entre `cc`, `gcc`
+/// e `clang` que estiver no PATH.
+fn compilador_c() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("CC") {
+        return Some(PathBuf::from(path));
+    }
+    ["cc", "gcc", "clang"]
+        .iter()
+        .find(|nome| Command::new(nome).arg("--version").output().is_ok())
+        .map(PathBuf::from)
+}
+
+/// Compila o arquivo `.c` gerado pelo alvo C com o runtime (`c_runtime.c`, `gc_runtime.c` e
+/// `async_runtime.c`) em um executável `nome_base`.
+pub fn compilar_c_com_runtime(
+    c_path: &Path,
+    nome_base: &str,
+    otimizacao: NivelOtimizacao,
+    bibliotecas: &[String],
+) -> Result<(), String> {
+    let runtime_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src")
+        .join("runtime");
+    let fontes = ["c_runtime.c", "gc_runtime.c", "async_runtime.c"].map(|f| runtime_dir.join(f));
+    for arquivo in &fontes {
+        if !arquivo.is_file() {
+            return Err(format!(
+                "Runtime nativo não encontrado: {}",
+                arquivo.display()
+            ));
+        }
+    }
+
+    let compilador = compilador_c().ok_or_else(|| {
+        "Nenhum compilador C encontrado (defina CC ou instale cc, gcc ou clang)".to_string()
+    })?;
+    let mut command = Command::new(&compilador);
+    command.arg("-std=c99").arg(otimizacao.flag());
+    // Inteiros da linguagem têm aritmética modular; o tcc não aceita a opção
+    if !compilador.to_string_lossy().contains("tcc") {
+        command.arg("-fwrapv");
+    }
+    command.arg("-I").arg(&runtime_dir);
+    command.arg(c_path);
+    command.args(&fontes);
+    command.args(bibliotecas.iter().filter_map(|b| flag_biblioteca(b)));
+
+    let mut output_file = nome_base.to_string();
+    if cfg!(windows) && !output_file.ends_with(".exe") {
+        output_file.push_str(".exe");
+    }
+    command.arg("-o").arg(&output_file);
+
+    #[cfg(not(windows))]
+    command.arg("-lm").arg("-pthread");
+
+    let output = command.output().map_err(|e| {
+        format!(
+            "Falha ao executar o compilador C ({}): {}",
+            compilador.display(),
+            e
+        )
+    })?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Erro do compilador C: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(())
+}
+
 pub struct GeradorCodigo;
 
 impl GeradorCodigo {
@@ -105,37 +344,67 @@ impl GeradorCodigo {
     ) -> Result<(), String> {
         let mut generator =
             llvm_ir::LlvmGenerator::new(programa, type_checker, &type_checker.resolved_classes);
-        let code = generator.generate();
+        let code = generator.generate()?;
         let ll_path = format!("{}.ll", nome_base);
         fs::write(&ll_path, code).map_err(|e| e.to_string())?;
 
-        compilar_llvm_ir_com_runtime(Path::new(&ll_path), nome_base, None)
+        compilar_llvm_ir_com_runtime(
+            Path::new(&ll_path),
+            nome_base,
+            None,
+            NivelOtimizacao::default(),
+            generator.external_libraries(),
+        )
     }
 
-    pub fn gerar_cil(&self, programa: &ast::Programa, nome_base: &str) -> Result<(), String> {
-        let generator = cil::CilGenerator::new(programa, nome_base.to_string());
-        let code = generator.generate();
+    pub fn gerar_cil(
+        &self,
+        programa: &ast::Programa,
+        type_checker: &crate::type_checker::VerificadorTipos,
+        nome_base: &str,
+        verificacoes: bool,
+    ) -> Result<(), String> {
+        // `nome_base` pode incluir o diretório de saída; o assembly leva só o nome do arquivo
+        let nome = Path::new(nome_base)
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| nome_base.to_string());
+        let mut generator = cil::CilGenerator::new(programa, type_checker, nome);
+        if !verificacoes {
+            generator.disable_runtime_checks();
+        }
+        let code = generator.generate()?;
+        cil::verificar(&code).map_err(|e| format!("Erro interno do gerador CIL: {}", e))?;
         fs::write(format!("{}.il", nome_base), code).map_err(|e| e.to_string())
     }
 
-    pub fn gerar_console(&self, programa: &ast::Programa, nome_base: &str) -> Result<(), String> {
-        let generator = console::ConsoleGenerator::new(programa);
-        let main_body = generator.generate();
+    /// Gera o projeto de console .NET em `./<nome_base>/`: `<nome>.csproj`, `Program.cs` com o
+    /// programa traduzido para C# e `PorDoSol.cs` com o runtime.
+    pub fn gerar_console(
+        &self,
+        programa: &ast::Programa,
+        type_checker: &crate::type_checker::VerificadorTipos,
+        nome_base: &str,
+    ) -> Result<(), String> {
+        let mut generator = console::ConsoleGenerator::new(programa, type_checker);
+        let program_cs = generator.generate()?;
 
-        let dir_projeto = format!("./{}", nome_base);
+        let dir_projeto = Path::new(".").join(nome_base);
         fs::create_dir_all(&dir_projeto).map_err(|e| e.to_string())?;
-
-        let csproj = format!(
-            "<Project Sdk=\"Microsoft.NET.Sdk\">\n  <PropertyGroup>\n    <OutputType>Exe</OutputType>\n    <TargetFramework>net8.0</TargetFramework>\n    <ImplicitUsings>enable</ImplicitUsings>\n    <Nullable>enable</Nullable>\n  </PropertyGroup>\n</Project>"
-        );
-        fs::write(format!("{}/{}.csproj", dir_projeto, nome_base), csproj)
-            .map_err(|e| e.to_string())?;
-
-        let program_cs = format!(
-            r#"namespace {}\n{{\n    class Program\n    {{\n        static void Main(string[] args)\n        {{\n{}\n        }}\n    }}\n}}"#,
-            nome_base, main_body
-        );
-        fs::write(format!("{}/Program.cs", dir_projeto), program_cs).map_err(|e| e.to_string())
+        let nome: String = Path::new(nome_base)
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| nome_base.to_string())
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '_' { c } else { '_' })
+            .collect();
+        fs::write(
+            dir_projeto.join(format!("{}.csproj", nome)),
+            console::projeto(&nome),
+        )
+        .map_err(|e| e.to_string())?;
+        fs::write(dir_projeto.join("Program.cs"), program_cs).map_err(|e| e.to_string())?;
+        fs::write(dir_projeto.join("PorDoSol.cs"), console::runtime()).map_err(|e| e.to_string())
     }
 
     pub fn gerar_bytecode<'a>(
@@ -144,9 +413,12 @@ impl GeradorCodigo {
         type_checker: &'a crate::type_checker::VerificadorTipos,
         nome_base: &str,
     ) -> Result<(), String> {
+        rejeitar_funcoes_externas(programa)?;
         let mut generator = bytecode::BytecodeGenerator::new(programa, type_checker);
         let bytecode = generator.generate();
-        fs::write(format!("{}.pbc", nome_base), bytecode.join("\n")).map_err(|e| e.to_string())
+        let pbc = crate::artefato::selar(crate::artefato::Tipo::Bytecode, &[], &bytecode.join("\n"));
+        crate::artefato::gravar(Path::new(&format!("{}.pbc", nome_base)), &pbc)
+            .map_err(|e| e.to_string())
     }
 
     pub fn gerar_bytecode_para_arquivo<'a>(
@@ -154,10 +426,22 @@ impl GeradorCodigo {
         programa: &'a ast::Programa,
         type_checker: &'a crate::type_checker::VerificadorTipos,
         output_path: &Path,
+        bibliotecas: &[String],
+        bytecode: Option<Vec<String>>,
     ) -> Result<(), String> {
-        let mut generator = bytecode::BytecodeGenerator::new(programa, type_checker);
-        let bytecode = generator.generate();
-        fs::write(output_path, bytecode.join("\n")).map_err(|e| e.to_string())
+        rejeitar_funcoes_externas(programa)?;
+        // `LOAD_LIBRARY <arquivo.pbl>`: a VM carrega as definições da biblioteca antes das do
+        // programa, resolvendo o arquivo a partir do diretório do .pbc
+        let mut linhas: Vec<String> = bibliotecas
+            .iter()
+            .map(|b| format!("LOAD_LIBRARY {}", b))
+            .collect();
+        // O bytecode já vem pronto da compilação incremental (ver `incremental`)
+        linhas.extend(bytecode.unwrap_or_else(|| {
+            bytecode::BytecodeGenerator::new(programa, type_checker).generate()
+        }));
+        let pbc = crate::artefato::selar(crate::artefato::Tipo::Bytecode, &[], &linhas.join("\n"));
+        crate::artefato::gravar(output_path, &pbc).map_err(|e| e.to_string())
     }
 
     pub fn gerar_bytecode_para_biblioteca<'a>(
@@ -171,130 +455,36 @@ impl GeradorCodigo {
     }
 
     /// Gera um arquivo `.pbl` (Biblioteca Por do Sol) composto de:
+    ///   0. Cabeçalho `[PBL]` — nome, versão, formato e hash do conteúdo (ver `artefato`)
     ///   1. Seção `[MANIFESTO]` — metadados de tipos públicos (usado pelo compilador para análise semântica)
     ///   2. Seção `[BYTECODE]`  — bytecode dos métodos com corpo (carregado pelo runtime quando necessário)
     ///
     /// O formato é inspirado no modelo de Reference Assemblies do .NET:
     ///   • O compilador lê apenas o [MANIFESTO] para verificação de tipos.
     ///   • O runtime carrega o [BYTECODE] sob demanda (tree-shaking futuro).
+    ///
+    /// `bytecode` é o da compilação incremental, quando já foi montado.
     pub fn gerar_pbl<'a>(
         &mut self,
         programa: &'a ast::Programa,
         type_checker: &'a mut crate::type_checker::VerificadorTipos,
         nome_biblioteca: &str,
         versao: &str,
+        bytecode: Option<Vec<String>>,
     ) -> Result<String, String> {
         use std::fmt::Write as FmtWrite;
         let mut manifesto = String::new();
         let mut bytecode_secao = String::new();
 
-        // Cabeçalho do arquivo .pbl
-        writeln!(manifesto, "[PBL]").ok();
-        writeln!(manifesto, "nome={}", nome_biblioteca).ok();
-        writeln!(manifesto, "versao={}", versao).ok();
-        writeln!(manifesto).ok();
         writeln!(manifesto, "[MANIFESTO]").ok();
 
-        // Itera namespaces e classes para gerar o manifesto
+        // Declarações de topo e de cada namespace entram no manifesto com o nome completo
+        for decl in &programa.declaracoes {
+            escrever_manifesto(&mut manifesto, type_checker, "", decl);
+        }
         for ns in &programa.namespaces {
-            let ns_nome = &ns.nome;
             for decl in &ns.declaracoes {
-                if let ast::Declaracao::DeclaracaoClasse(cl) = decl {
-                    let fqn = format!("{}.{}", ns_nome, cl.nome);
-                    if cl.eh_estatica {
-                        writeln!(manifesto, "DEFINE_STATIC_CLASS {}", fqn).ok();
-                    } else {
-                        writeln!(manifesto, "DEFINE_CLASS {} NULO", fqn).ok();
-                    }
-                    for prop in &cl.propriedades {
-                        writeln!(manifesto, "PROPERTY {} {} {}", fqn, prop.nome, prop.tipo).ok();
-                    }
-                    for campo in &cl.campos {
-                        if campo.modificador == ast::ModificadorAcesso::Publico {
-                            writeln!(manifesto, "FIELD {} {} {}", fqn, campo.nome, campo.tipo).ok();
-                        }
-                    }
-                    for metodo in &cl.metodos {
-                        let ret = metodo
-                            .tipo_retorno
-                            .as_ref()
-                            .map(|t| t.to_string())
-                            .unwrap_or_else(|| "vazio".to_string());
-                        let params: Vec<String> = metodo
-                            .parametros
-                            .iter()
-                            .map(|p| format!("{}:{}", p.tipo, p.nome))
-                            .collect();
-                        let is_nativo = metodo.attributes.iter().any(|a| a.name == "Nativo");
-                        // Extrai a chave nativa se presente
-                        let chave_nativa = metodo
-                            .attributes
-                            .iter()
-                            .find(|a| a.name == "Nativo")
-                            .and_then(|a| a.arguments.first())
-                            .and_then(|e| {
-                                if let ast::Expressao::Texto(s) = e {
-                                    Some(s.clone())
-                                } else {
-                                    None
-                                }
-                            });
-                        if metodo.eh_estatica {
-                            if let Some(chave) = &chave_nativa {
-                                writeln!(
-                                    manifesto,
-                                    "DEFINE_STATIC_NATIVE_METHOD {} {} {} {} {}",
-                                    fqn,
-                                    metodo.nome,
-                                    ret,
-                                    chave,
-                                    params.join(" ")
-                                )
-                                .ok();
-                            } else {
-                                writeln!(
-                                    manifesto,
-                                    "DEFINE_STATIC_METHOD {} {} {} {} {}",
-                                    fqn,
-                                    metodo.nome,
-                                    ret,
-                                    params.len(),
-                                    params.join(" ")
-                                )
-                                .ok();
-                            }
-                        } else {
-                            if let Some(chave) = &chave_nativa {
-                                writeln!(
-                                    manifesto,
-                                    "DEFINE_NATIVE_METHOD {} {} {} {} {}",
-                                    fqn,
-                                    metodo.nome,
-                                    ret,
-                                    chave,
-                                    params.join(" ")
-                                )
-                                .ok();
-                            } else {
-                                writeln!(
-                                    manifesto,
-                                    "DEFINE_METHOD {} {} {} {} {}",
-                                    fqn,
-                                    metodo.nome,
-                                    ret,
-                                    params.len(),
-                                    params.join(" ")
-                                )
-                                .ok();
-                            }
-                        }
-                        // Métodos com corpo entram no bytecode
-                        if !is_nativo && !metodo.eh_abstrato && !metodo.corpo.is_empty() {
-                            // Placeholder - o bytecode completo será gerado abaixo
-                            // Não movemos o type_checker aqui
-                        }
-                    }
-                }
+                escrever_manifesto(&mut manifesto, type_checker, &ns.nome, decl);
             }
         }
 
@@ -302,13 +492,255 @@ impl GeradorCodigo {
         writeln!(manifesto, "[BYTECODE]").ok();
 
         // Gera o bytecode completo para a seção [BYTECODE]
-        let mut gen = bytecode::BytecodeGenerator::new(programa, type_checker);
-        let bc = gen.generate_for_library();
+        let bc = bytecode.unwrap_or_else(|| {
+            bytecode::BytecodeGenerator::new(programa, type_checker).generate_for_library()
+        });
         for linha in &bc {
             writeln!(bytecode_secao, "{}", linha).ok();
         }
 
-        Ok(format!("{}\n{}", manifesto, bytecode_secao))
+        Ok(crate::artefato::selar(
+            crate::artefato::Tipo::Biblioteca,
+            &[("nome", nome_biblioteca), ("versao", versao)],
+            &format!("{}\n{}", manifesto, bytecode_secao),
+        ))
+    }
+}
+
+/// Escreve no manifesto `.pbl` a parte pública de uma declaração: classes, interfaces,
+/// enumerações e funções, com genéricos, valores padrão, acesso e modificadores.
+/// Membros privados ficam de fora, exceto os métodos `[Nativo]`.
+fn escrever_manifesto(
+    saida: &mut String,
+    tc: &crate::type_checker::VerificadorTipos,
+    ns: &str,
+    decl: &ast::Declaracao,
+) {
+    use std::fmt::Write as FmtWrite;
+    let qualificar = |nome: &str| {
+        if ns.is_empty() {
+            nome.to_string()
+        } else {
+            format!("{}.{}", ns, nome)
+        }
+    };
+    match decl {
+        ast::Declaracao::DeclaracaoClasse(cl) if cl.modificador != ast::ModificadorAcesso::Privado => {
+            let fqn = qualificar(&cl.nome);
+            let genericos = &cl.generic_params;
+            let tipo = |t: &ast::Tipo| tc.tipo_no_manifesto(t, ns, genericos);
+            if cl.eh_estatica {
+                writeln!(saida, "DEFINE_STATIC_CLASS {}", fqn).ok();
+            } else {
+                // O primeiro tipo após ':' pode ser uma interface (estilo C#)
+                let mut interfaces: Vec<String> = cl.interfaces.iter().map(tipo).collect();
+                let mut pai = "NULO".to_string();
+                if let Some(base) = &cl.classe_pai {
+                    let escrito = tipo(base);
+                    let eh_classe = tc
+                        .resolved_classes
+                        .get(&fqn)
+                        .is_some_and(|ci| ci.parent_name.is_some());
+                    if eh_classe {
+                        pai = escrito;
+                    } else {
+                        interfaces.insert(0, escrito);
+                    }
+                }
+                let mut linha = format!("DEFINE_CLASS {} {}", fqn, pai);
+                if cl.eh_abstrata {
+                    linha.push_str(" abstrata");
+                }
+                marcar_lista(&mut linha, "genericos", genericos);
+                marcar_lista(&mut linha, "interfaces", &interfaces);
+                writeln!(saida, "{}", linha).ok();
+            }
+            for ctor in &cl.construtores {
+                if let Some(acesso) = acesso_exportado(&ctor.modificador) {
+                    writeln!(
+                        saida,
+                        "DEFINE_CONSTRUCTOR {} {}{}{}",
+                        fqn,
+                        ctor.parametros.len(),
+                        parametros_manifesto(&ctor.parametros, &tipo),
+                        acesso
+                    )
+                    .ok();
+                }
+            }
+            for prop in &cl.propriedades {
+                if let Some(acesso) = acesso_exportado(&prop.modificador) {
+                    let estatica = if prop.eh_estatica || cl.eh_estatica { " estatica" } else { "" };
+                    writeln!(saida, "PROPERTY {} {} {}{}{}", fqn, prop.nome, tipo(&prop.tipo), estatica, acesso).ok();
+                }
+            }
+            for campo in &cl.campos {
+                if let Some(acesso) = acesso_exportado(&campo.modificador) {
+                    let estatica = if campo.eh_estatica || cl.eh_estatica { " estatica" } else { "" };
+                    writeln!(saida, "FIELD {} {} {}{}{}", fqn, campo.nome, tipo(&campo.tipo), estatica, acesso).ok();
+                }
+            }
+            for metodo in &cl.metodos {
+                // Extrai a chave nativa se presente
+                let chave_nativa = metodo
+                    .attributes
+                    .iter()
+                    .find(|a| a.name == "Nativo")
+                    .and_then(|a| a.arguments.first())
+                    .and_then(|e| {
+                        if let ast::Expressao::Texto(s) = e {
+                            Some(s.clone())
+                        } else {
+                            None
+                        }
+                    });
+                let acesso = match acesso_exportado(&metodo.modificador) {
+                    Some(acesso) => acesso,
+                    None if chave_nativa.is_some() => "",
+                    None => continue,
+                };
+                let genericos_metodo = [genericos.clone(), metodo.generic_params.clone()].concat();
+                let tipo = |t: &ast::Tipo| tc.tipo_no_manifesto(t, ns, &genericos_metodo);
+                let ret = metodo
+                    .tipo_retorno
+                    .as_ref()
+                    .map(tipo)
+                    .unwrap_or_else(|| "vazio".to_string());
+                let estatico = metodo.eh_estatica || cl.eh_estatica;
+                let mut linha = match &chave_nativa {
+                    Some(chave) => format!(
+                        "{} {} {} {} {}",
+                        if estatico { "DEFINE_STATIC_NATIVE_METHOD" } else { "DEFINE_NATIVE_METHOD" },
+                        fqn,
+                        metodo.nome,
+                        ret,
+                        chave
+                    ),
+                    None => format!(
+                        "{} {} {} {} {}",
+                        if estatico { "DEFINE_STATIC_METHOD" } else { "DEFINE_METHOD" },
+                        fqn,
+                        metodo.nome,
+                        ret,
+                        metodo.parametros.len()
+                    ),
+                };
+                linha.push_str(&parametros_manifesto(&metodo.parametros, &tipo));
+                linha.push_str(acesso);
+                for (marcado, marcador) in [
+                    (metodo.eh_abstrato, " abstrato"),
+                    (metodo.eh_virtual, " redefinivel"),
+                    (metodo.eh_override, " sobrescreve"),
+                ] {
+                    if marcado {
+                        linha.push_str(marcador);
+                    }
+                }
+                marcar_lista(&mut linha, "genericos", &metodo.generic_params);
+                writeln!(saida, "{}", linha).ok();
+            }
+        }
+        ast::Declaracao::DeclaracaoInterface(iface) => {
+            let fqn = qualificar(&iface.nome);
+            let mut linha = format!("DEFINE_INTERFACE {}", fqn);
+            marcar_lista(&mut linha, "genericos", &iface.generic_params);
+            writeln!(saida, "{}", linha).ok();
+            let tipo = |t: &ast::Tipo| tc.tipo_no_manifesto(t, ns, &iface.generic_params);
+            for sig in &iface.metodos {
+                let ret = sig
+                    .tipo_retorno
+                    .as_ref()
+                    .map(tipo)
+                    .unwrap_or_else(|| "vazio".to_string());
+                writeln!(
+                    saida,
+                    "INTERFACE_METHOD {} {} {} {}{}",
+                    fqn,
+                    sig.nome,
+                    ret,
+                    sig.parametros.len(),
+                    parametros_manifesto(&sig.parametros, &tipo)
+                )
+                .ok();
+            }
+        }
+        ast::Declaracao::DeclaracaoEnum(en) => {
+            writeln!(saida, "DEFINE_ENUM {} {}", qualificar(&en.nome), en.valores.join(" ")).ok();
+        }
+        ast::Declaracao::DeclaracaoFuncao(func) if func.modificador != ast::ModificadorAcesso::Privado => {
+            let tipo = |t: &ast::Tipo| tc.tipo_no_manifesto(t, ns, &func.generic_params);
+            let ret = func
+                .tipo_retorno
+                .as_ref()
+                .map(tipo)
+                .unwrap_or_else(|| "vazio".to_string());
+            let mut linha = format!(
+                "DEFINE_FUNCTION {} {} {}{}",
+                qualificar(&func.nome),
+                ret,
+                func.parametros.len(),
+                parametros_manifesto(&func.parametros, &tipo)
+            );
+            marcar_lista(&mut linha, "genericos", &func.generic_params);
+            writeln!(saida, "{}", linha).ok();
+        }
+        _ => {}
+    }
+}
+
+/// Marcador de acesso de um membro exportado; `None` para os privados.
+fn acesso_exportado(modificador: &ast::ModificadorAcesso) -> Option<&'static str> {
+    match modificador {
+        ast::ModificadorAcesso::Publico => Some(""),
+        ast::ModificadorAcesso::Protegido => Some(" acesso=protegido"),
+        ast::ModificadorAcesso::Privado => None,
+    }
+}
+
+fn marcar_lista(linha: &mut String, chave: &str, itens: &[String]) {
+    if !itens.is_empty() {
+        linha.push_str(&format!(" {}={}", chave, itens.join(",")));
+    }
+}
+
+/// ` tipo:nome tipo:nome=padrão …`, com o valor padrão sem espaços.
+fn parametros_manifesto(
+    parametros: &[ast::Parametro],
+    tipo: &dyn Fn(&ast::Tipo) -> String,
+) -> String {
+    parametros
+        .iter()
+        .map(|p| match &p.valor_padrao {
+            Some(padrao) => format!(
+                " {}:{}={}",
+                tipo(&p.tipo),
+                p.nome,
+                crate::library_loader::codificar_padrao(&valor_padrao_manifesto(padrao))
+            ),
+            None => format!(" {}:{}", tipo(&p.tipo), p.nome),
+        })
+        .collect()
+}
+
+/// Valor padrão como aparece na documentação da biblioteca; o valor real é aplicado
+/// pelo próprio método (SET_DEFAULT), então expressões compostas viram `...`.
+fn valor_padrao_manifesto(expr: &ast::Expressao) -> String {
+    match expr {
+        ast::Expressao::Inteiro(n) => n.to_string(),
+        ast::Expressao::Texto(s) => format!("{:?}", s),
+        ast::Expressao::Booleano(b) => if *b { "verdadeiro" } else { "falso" }.to_string(),
+        ast::Expressao::Decimal(s)
+        | ast::Expressao::FlutuanteLiteral(s)
+        | ast::Expressao::DuploLiteral(s) => s.clone(),
+        ast::Expressao::Nulo => "nulo".to_string(),
+        ast::Expressao::Identificador(nome) => nome.clone(),
+        ast::Expressao::AcessoMembro(obj, membro) => {
+            format!("{}.{}", valor_padrao_manifesto(obj), membro)
+        }
+        ast::Expressao::Unario(ast::OperadorUnario::NegacaoNumerica, inner) => {
+            format!("-{}", valor_padrao_manifesto(inner))
+        }
+        _ => "...".to_string(),
     }
 }
 
@@ -317,7 +749,7 @@ impl GeradorCodigo {
 pub fn gerar_llvm_ir_puro<'a>(
     programa: &'a ast::Programa,
     type_checker: &'a mut crate::type_checker::VerificadorTipos<'a>,
-) -> String {
+) -> Result<String, String> {
     let mut generator =
         llvm_ir::LlvmGenerator::new(programa, type_checker, &type_checker.resolved_classes);
     generator.generate()
@@ -331,7 +763,7 @@ pub fn gerar_llvm_ir_para_biblioteca<'a>(
 ) -> Result<(), String> {
     let mut generator =
         llvm_ir::LlvmGenerator::new(programa, type_checker, &type_checker.resolved_classes);
-    let code = generator.generate_for_library();
+    let code = generator.generate_for_library()?;
     // nome_base já deve incluir o caminho completo sem extensão
     let ll_path = format!("{}.ll", nome_base);
     fs::write(&ll_path, code).map_err(|e| e.to_string())?;
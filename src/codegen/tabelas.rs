@@ -0,0 +1,499 @@
+//! Tabelas do programa para os backends que geram código a partir da AST (C, WebAssembly, CIL
+//! e C#) e para o interpretador de AST: classes com a classe pai resolvida, interfaces, funções,
+//! enumerações e comandos de nível superior, a busca de membros na hierarquia, as vtables, a
+//! resolução de nomes de variáveis ([`Escopo`]) e o alvo de `X.membro` quando `X` não é um valor.
+//!
+//! Os nomes são resolvidos pelo [`VerificadorTipos`], o mesmo que aceitou o programa: um
+//! backend não tem como entender um tipo de um jeito diferente da verificação.
+
+use crate::ast;
+use crate::type_checker::VerificadorTipos;
+use std::collections::HashMap;
+
+pub struct Classe<'a> {
+    pub fqn: String,
+    pub namespace: String,
+    pub decl: &'a ast::DeclaracaoClasse,
+    pub pai: Option<String>,
+    /// Nome da classe no código gerado.
+    pub simbolo: String,
+}
+
+pub struct Interface<'a> {
+    pub namespace: String,
+    pub decl: &'a ast::DeclaracaoInterface,
+}
+
+pub struct Funcao<'a> {
+    pub namespace: String,
+    pub decl: &'a ast::DeclaracaoFuncao,
+    /// Nome da função no código gerado.
+    pub simbolo: String,
+}
+
+/// Alvo de `X.membro` quando `X` não é um valor.
+pub enum AlvoEstatico {
+    Classe(String),
+    Enum(String),
+    /// Classe desconhecida no programa; pode ser uma classe nativa (`Console`, `Arquivo`...).
+    Nativo(String),
+}
+
+/// Nome de tipo declarado no programa.
+pub enum TipoNomeado {
+    Classe(String),
+    Enum(String),
+    Interface(String),
+}
+
+/// Campo ou propriedade achado na hierarquia de uma classe.
+pub struct Membro<'t, 'a> {
+    /// Classe que declara o membro
+    pub classe: &'t Classe<'a>,
+    /// Quantos ancestrais acima da classe procurada (0 na própria classe)
+    pub nivel: usize,
+    pub tipo: &'a ast::Tipo,
+    pub propriedade: bool,
+}
+
+/// Nome usado como variável no corpo de uma função ou método.
+pub enum Variavel<'t, 'a, L> {
+    /// Local ou parâmetro, com o que o backend guarda para ele.
+    Local(L),
+    /// Campo ou propriedade de `este`.
+    Campo(Membro<'t, 'a>),
+    /// Membro estático da classe atual ou de um ancestral.
+    Estatico(Membro<'t, 'a>),
+}
+
+/// Corpo de função ou método em geração (ou em execução, no interpretador): o que é preciso
+/// para resolver um nome como variável ou como alvo estático. `L` é o que o backend guarda para
+/// uma variável local ou parâmetro.
+pub trait Escopo<'a, L> {
+    fn tabelas(&self) -> &Tabelas<'a>;
+    fn namespace(&self) -> &str;
+    fn classe_atual(&self) -> Option<&str>;
+    /// Se o corpo é de um método de instância.
+    fn tem_este(&self) -> bool;
+    fn local(&self, nome: &str) -> Option<L>;
+
+    /// Locais e parâmetros primeiro, depois campos de `este`, depois estáticos da classe atual.
+    fn variavel(&self, nome: &str) -> Option<Variavel<'_, 'a, L>> {
+        if let Some(local) = self.local(nome) {
+            return Some(Variavel::Local(local));
+        }
+        let classe = self.classe_atual()?;
+        if self.tem_este() {
+            if let Some(membro) = self.tabelas().buscar_membro(classe, nome, false) {
+                return Some(Variavel::Campo(membro));
+            }
+        }
+        self.tabelas()
+            .buscar_membro(classe, nome, true)
+            .map(Variavel::Estatico)
+    }
+
+    /// Interpreta `X` em `X.membro` como classe, enumeração ou classe nativa, se `X` não for
+    /// variável.
+    fn alvo_estatico(&self, expr: &ast::Expressao) -> Option<AlvoEstatico> {
+        self.tabelas()
+            .alvo_estatico(expr, self.namespace(), |nome| self.variavel(nome).is_some())
+    }
+}
+
+pub fn qualificar(namespace: &str, nome: &str) -> String {
+    if namespace.is_empty() {
+        nome.to_string()
+    } else {
+        format!("{}.{}", namespace, nome)
+    }
+}
+
+pub fn nome_do_tipo(tipo: &ast::Tipo) -> Option<&str> {
+    match tipo {
+        ast::Tipo::Classe(n) | ast::Tipo::Enum(n) => Some(n),
+        ast::Tipo::Aplicado { nome, .. } => Some(nome),
+        _ => None,
+    }
+}
+
+/// Escolhe a sobrecarga pelo número de argumentos, considerando valores padrão.
+pub fn aceita_argumentos(parametros: &[ast::Parametro], n: usize) -> bool {
+    n <= parametros.len() && parametros[n..].iter().all(|p| p.valor_padrao.is_some())
+}
+
+/// Erro de chamada a um método que `texto` não tem.
+pub fn erro_metodo_texto(metodo: &str) -> String {
+    format!("Método '{}' não existe para valores do tipo texto", metodo)
+}
+
+pub fn chave_nativa(attributes: &[ast::Attribute]) -> Option<&str> {
+    attributes
+        .iter()
+        .find(|a| a.name == "Nativo")
+        .and_then(|a| match a.arguments.first() {
+            Some(ast::Expressao::Texto(chave)) => Some(chave.as_str()),
+            _ => None,
+        })
+}
+
+pub struct Tabelas<'a> {
+    pub verificador: &'a VerificadorTipos<'a>,
+    pub classes: HashMap<String, Classe<'a>>,
+    /// Ordem de declaração (inicialização dos estáticos, como no interpretador).
+    pub ordem_classes: Vec<String>,
+    pub interfaces: HashMap<String, Interface<'a>>,
+    pub ordem_interfaces: Vec<String>,
+    pub funcoes: HashMap<String, Funcao<'a>>,
+    pub ordem_funcoes: Vec<String>,
+    pub enums: HashMap<String, &'a ast::DeclaracaoEnum>,
+    /// Comandos de nível superior, com o namespace onde aparecem.
+    pub globais: Vec<(String, &'a ast::Comando)>,
+}
+
+impl<'a> Tabelas<'a> {
+    /// Registra as declarações de `programa`. `simbolo_classe` e `simbolo_funcao` dão o nome de
+    /// uma classe e de uma função (pelo nome qualificado) no código gerado.
+    pub fn new(
+        programa: &'a ast::Programa,
+        verificador: &'a VerificadorTipos<'a>,
+        simbolo_classe: impl Fn(&str) -> String,
+        simbolo_funcao: impl Fn(&str) -> String,
+    ) -> Self {
+        let mut tabelas = Self {
+            verificador,
+            classes: HashMap::new(),
+            ordem_classes: Vec::new(),
+            interfaces: HashMap::new(),
+            ordem_interfaces: Vec::new(),
+            funcoes: HashMap::new(),
+            ordem_funcoes: Vec::new(),
+            enums: HashMap::new(),
+            globais: Vec::new(),
+        };
+        tabelas.registrar(&programa.declaracoes, "");
+        for namespace in &programa.namespaces {
+            tabelas.registrar(&namespace.declaracoes, &namespace.nome);
+        }
+        for classe in tabelas.classes.values_mut() {
+            classe.simbolo = simbolo_classe(&classe.fqn);
+        }
+        for (fqn, funcao) in tabelas.funcoes.iter_mut() {
+            funcao.simbolo = simbolo_funcao(fqn);
+        }
+        tabelas.resolver_pais();
+        tabelas
+    }
+
+    fn registrar(&mut self, declaracoes: &'a [ast::Declaracao], namespace: &str) {
+        for decl in declaracoes {
+            match decl {
+                ast::Declaracao::DeclaracaoClasse(classe) => {
+                    self.registrar_classe(classe, namespace)
+                }
+                ast::Declaracao::DeclaracaoInterface(interface) => {
+                    let fqn = qualificar(namespace, &interface.nome);
+                    self.ordem_interfaces.push(fqn.clone());
+                    self.interfaces.insert(
+                        fqn,
+                        Interface {
+                            namespace: namespace.to_string(),
+                            decl: interface,
+                        },
+                    );
+                }
+                ast::Declaracao::DeclaracaoFuncao(funcao) => {
+                    let fqn = qualificar(namespace, &funcao.nome);
+                    self.ordem_funcoes.push(fqn.clone());
+                    self.funcoes.insert(
+                        fqn,
+                        Funcao {
+                            namespace: namespace.to_string(),
+                            decl: funcao,
+                            simbolo: String::new(),
+                        },
+                    );
+                }
+                ast::Declaracao::DeclaracaoEnum(en) => {
+                    self.enums.insert(qualificar(namespace, &en.nome), en);
+                }
+                ast::Declaracao::DeclaracaoNamespace(ns) => {
+                    self.registrar(&ns.declaracoes, &qualificar(namespace, &ns.nome))
+                }
+                ast::Declaracao::DeclaracaoModulo(modulo) => {
+                    self.registrar(&modulo.conteudo, &qualificar(namespace, &modulo.nome))
+                }
+                ast::Declaracao::Comando(cmd) => self.globais.push((namespace.to_string(), cmd)),
+                ast::Declaracao::DeclaracaoTipo(_)
+                | ast::Declaracao::Importacao(_)
+                | ast::Declaracao::Exportacao(_) => {}
+            }
+        }
+    }
+
+    fn registrar_classe(&mut self, classe: &'a ast::DeclaracaoClasse, namespace: &str) {
+        // Classes aninhadas são registradas como irmãs, no mesmo namespace
+        for aninhada in &classe.nested_classes {
+            self.registrar_classe(aninhada, namespace);
+        }
+        let fqn = qualificar(namespace, &classe.nome);
+        self.ordem_classes.push(fqn.clone());
+        self.classes.insert(
+            fqn.clone(),
+            Classe {
+                fqn,
+                namespace: namespace.to_string(),
+                decl: classe,
+                pai: None,
+                simbolo: String::new(),
+            },
+        );
+    }
+
+    fn resolver_pais(&mut self) {
+        let pais: Vec<(String, Option<String>)> = self
+            .ordem_classes
+            .iter()
+            .map(|fqn| {
+                let classe = &self.classes[fqn];
+                // O primeiro tipo do cabeçalho pode ser uma interface; nesse caso não há classe pai
+                let pai = classe
+                    .decl
+                    .classe_pai
+                    .as_ref()
+                    .and_then(nome_do_tipo)
+                    .and_then(|nome| self.resolver_classe(&classe.namespace, nome))
+                    .filter(|pai| pai != fqn);
+                (fqn.clone(), pai)
+            })
+            .collect();
+        for (fqn, pai) in pais {
+            if let Some(classe) = self.classes.get_mut(&fqn) {
+                classe.pai = pai;
+            }
+        }
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* NOMES                                                               */
+    /* ------------------------------------------------------------------ */
+
+    pub fn resolver_classe(&self, namespace: &str, nome: &str) -> Option<String> {
+        Some(self.verificador.resolver_nome_classe(nome, namespace))
+            .filter(|fqn| self.classes.contains_key(fqn))
+    }
+
+    pub fn resolver_interface(&self, namespace: &str, nome: &str) -> Option<String> {
+        Some(self.verificador.resolver_nome_interface(nome, namespace))
+            .filter(|fqn| self.interfaces.contains_key(fqn))
+    }
+
+    pub fn resolver_enum(&self, namespace: &str, nome: &str) -> Option<String> {
+        Some(self.verificador.resolver_nome_enum(nome, namespace))
+            .filter(|fqn| self.enums.contains_key(fqn))
+    }
+
+    pub fn resolver_funcao(&self, namespace: &str, nome: &str) -> Option<String> {
+        Some(self.verificador.resolver_nome_funcao(nome, namespace))
+            .filter(|fqn| self.funcoes.contains_key(fqn))
+    }
+
+    /// Classe, enumeração ou interface do programa com o nome `nome`, visto de `namespace`.
+    pub fn tipo_nomeado(&self, namespace: &str, nome: &str) -> Option<TipoNomeado> {
+        if let Some(classe) = self.resolver_classe(namespace, nome) {
+            return Some(TipoNomeado::Classe(classe));
+        }
+        if let Some(en) = self.resolver_enum(namespace, nome) {
+            return Some(TipoNomeado::Enum(en));
+        }
+        self.resolver_interface(namespace, nome)
+            .map(TipoNomeado::Interface)
+    }
+
+    /// Interpreta `X` em `X.membro` como classe, enumeração ou classe nativa. Não é alvo
+    /// estático quando o primeiro nome de `X` é um valor no código em `namespace`
+    /// (`eh_variavel`).
+    pub fn alvo_estatico(
+        &self,
+        expr: &ast::Expressao,
+        namespace: &str,
+        eh_variavel: impl Fn(&str) -> bool,
+    ) -> Option<AlvoEstatico> {
+        let mut partes = Vec::new();
+        let mut atual = expr;
+        loop {
+            match atual {
+                ast::Expressao::Identificador(nome) => {
+                    partes.push(nome.as_str());
+                    break;
+                }
+                ast::Expressao::AcessoMembro(obj, membro) => {
+                    partes.push(membro.as_str());
+                    atual = obj;
+                }
+                _ => return None,
+            }
+        }
+        partes.reverse();
+        let primeiro = partes[0];
+        if eh_variavel(primeiro) {
+            return None;
+        }
+        let nome = partes.join(".");
+        if let Some(classe) = self.resolver_classe(namespace, &nome) {
+            return Some(AlvoEstatico::Classe(classe));
+        }
+        if let Some(en) = self.resolver_enum(namespace, &nome) {
+            return Some(AlvoEstatico::Enum(en));
+        }
+        Some(AlvoEstatico::Nativo(partes.last().unwrap().to_string()))
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* HIERARQUIA                                                          */
+    /* ------------------------------------------------------------------ */
+
+    /// A classe e seus ancestrais, da derivada para a base.
+    pub fn hierarquia(&self, fqn: &str) -> Vec<&Classe<'a>> {
+        let mut cadeia = Vec::new();
+        let mut atual = self.classes.get(fqn);
+        while let Some(classe) = atual {
+            cadeia.push(classe);
+            atual = classe.pai.as_ref().and_then(|p| self.classes.get(p));
+        }
+        cadeia
+    }
+
+    pub fn eh_ancestral(&self, ancestral: &str, fqn: &str) -> bool {
+        self.hierarquia(fqn).iter().any(|c| c.fqn == ancestral)
+    }
+
+    /// Menor ancestral comum de duas classes.
+    pub fn ancestral_comum(&self, a: &str, b: &str) -> Option<String> {
+        self.hierarquia(a)
+            .into_iter()
+            .map(|c| c.fqn.clone())
+            .find(|c| self.eh_ancestral(c, b))
+    }
+
+    /// Vtable de cada classe: (método, classe que introduz a entrada). Uma entrada para cada
+    /// método redefinível, abstrato ou redefinido em alguma subclasse; as entradas herdadas vêm
+    /// primeiro, na mesma ordem da vtable da classe pai.
+    pub fn vtables(&self) -> HashMap<String, Vec<(String, String)>> {
+        let mut vtables = HashMap::new();
+        for fqn in &self.ordem_classes {
+            self.vtable(fqn, &mut vtables);
+        }
+        vtables
+    }
+
+    fn vtable(
+        &self,
+        fqn: &str,
+        vtables: &mut HashMap<String, Vec<(String, String)>>,
+    ) -> Vec<(String, String)> {
+        if let Some(entradas) = vtables.get(fqn) {
+            return entradas.clone();
+        }
+        let classe = &self.classes[fqn];
+        let mut entradas = match &classe.pai {
+            Some(pai) => self.vtable(pai, vtables),
+            None => Vec::new(),
+        };
+        for metodo in classe.decl.metodos.iter().filter(|m| !m.eh_estatica) {
+            if entradas.iter().any(|(nome, _)| *nome == metodo.nome) {
+                continue;
+            }
+            let redefinido = self.ordem_classes.iter().any(|outra| {
+                outra != fqn
+                    && self.eh_ancestral(fqn, outra)
+                    && self.classes[outra]
+                        .decl
+                        .metodos
+                        .iter()
+                        .any(|m| m.nome == metodo.nome && !m.eh_estatica)
+            });
+            if metodo.eh_virtual || metodo.eh_abstrato || metodo.eh_override || redefinido {
+                entradas.push((metodo.nome.clone(), fqn.to_string()));
+            }
+        }
+        vtables.insert(fqn.to_string(), entradas.clone());
+        entradas
+    }
+
+    /// Construtor escolhido para `n` argumentos, como no interpretador.
+    pub fn escolher_construtor(&self, classe: &str, n: usize) -> Option<usize> {
+        let construtores = &self.classes[classe].decl.construtores;
+        construtores
+            .iter()
+            .position(|c| aceita_argumentos(&c.parametros, n))
+            .or(if construtores.is_empty() {
+                None
+            } else {
+                Some(0)
+            })
+    }
+
+    /// Procura o método na classe e nos ancestrais (a classe mais derivada vence).
+    pub fn buscar_metodo(
+        &self,
+        fqn: &str,
+        nome: &str,
+        n_args: usize,
+    ) -> Option<(String, &'a ast::MetodoClasse)> {
+        for classe in self.hierarquia(fqn) {
+            let candidatos = || classe.decl.metodos.iter().filter(|m| m.nome == nome);
+            if let Some(metodo) = candidatos()
+                .find(|m| aceita_argumentos(&m.parametros, n_args))
+                .or_else(|| candidatos().next())
+            {
+                return Some((classe.fqn.clone(), metodo));
+            }
+        }
+        None
+    }
+
+    /// Propriedade de instância `nome` na classe ou nos ancestrais.
+    pub fn buscar_propriedade(
+        &self,
+        fqn: &str,
+        nome: &str,
+    ) -> Option<(String, &'a ast::PropriedadeClasse)> {
+        self.hierarquia(fqn).into_iter().find_map(|classe| {
+            classe
+                .decl
+                .propriedades
+                .iter()
+                .find(|p| p.nome == nome && !p.eh_estatica)
+                .map(|p| (classe.fqn.clone(), p))
+        })
+    }
+
+    /// Campo ou propriedade `nome`, de instância ou `estatico`, na classe ou num ancestral (a
+    /// classe mais derivada vence; na mesma classe, o campo vence a propriedade).
+    pub fn buscar_membro(&self, fqn: &str, nome: &str, estatico: bool) -> Option<Membro<'_, 'a>> {
+        self.hierarquia(fqn)
+            .into_iter()
+            .enumerate()
+            .find_map(|(nivel, classe)| {
+                let decl = classe.decl;
+                let campo = decl
+                    .campos
+                    .iter()
+                    .find(|c| c.nome == nome && c.eh_estatica == estatico)
+                    .map(|c| (&c.tipo, false));
+                let (tipo, propriedade) = campo.or_else(|| {
+                    decl.propriedades
+                        .iter()
+                        .find(|p| p.nome == nome && p.eh_estatica == estatico)
+                        .map(|p| (&p.tipo, true))
+                })?;
+                Some(Membro {
+                    classe,
+                    nivel,
+                    tipo,
+                    propriedade,
+                })
+            })
+    }
+}
@@ -0,0 +1,2559 @@
+//! Gerador de WebAssembly para o alvo `--target=wasm`.
+//!
+//! O programa vira um módulo em formato texto (`.wat`), montado também em binário (`.wasm`) por
+//! [`montar`]. O runtime escrito em WAT (`src/runtime/wasm_runtime.wat`) é incluído no módulo;
+//! a saída do console, a leitura de linhas, a formatação de reais e o fim do programa em erros
+//! de execução ficam com o hospedeiro, pelas funções importadas do módulo `pds` (ver
+//! `docs/wasm.md` e o carregador `src/runtime/pds_wasm.js`).
+//!
+//! - Textos, objetos, arrays e caixas de valores primitivos ficam na memória linear, alocados
+//!   por um alocador que só cresce (não há coletor de lixo); referências são endereços `i32` e
+//!   o endereço 0 é `nulo`.
+//! - `inteiro` é `i64`, `flutuante` é `f32`, `decimal`/`duplo` são `f64` e `booleano` é `i32`.
+//! - Cada classe tem um descritor nos dados estáticos: nome, classe pai, função de texto e a
+//!   vtable, com índices na tabela `$pds_funcoes` chamados por `call_indirect`. As entradas da
+//!   vtable seguem as mesmas regras do alvo C.
+//! - Chamadas em valores do tipo interface ou `objeto` passam por funções de despacho pelo nome
+//!   do método, que comparam o descritor da classe do objeto.
+//! - `aguarde` executa a função `assíncrona` na hora; arquivos e funções `[Externo]` não existem
+//!   neste alvo.
+//!
+//! Como no alvo C, a semântica acompanha o interpretador de AST: ordem de avaliação, exibição
+//! dos valores e mensagens dos erros de execução.
+
+use super::tabelas::{
+    chave_nativa, erro_metodo_texto, nome_do_tipo, AlvoEstatico, Escopo, Membro, Tabelas,
+    TipoNomeado, Variavel,
+};
+use crate::ast;
+use crate::type_checker;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Runtime em WAT incluído em todo módulo gerado.
+const RUNTIME: &str = include_str!("../runtime/wasm_runtime.wat");
+
+/// Área de rascunho do runtime (formatação de números), logo depois do endereço nulo.
+const BUFFER: u32 = 16;
+const TAMANHO_BUFFER: u32 = 512;
+/// Início dos dados estáticos (textos literais e descritores de classes).
+const INICIO_DADOS: u32 = BUFFER + TAMANHO_BUFFER;
+
+/// Textos usados pelo runtime, expostos como globais com o endereço de cada um.
+const TEXTOS_RUNTIME: &[(&str, &str)] = &[
+    ("pds_txt_nulo", "nulo"),
+    ("pds_txt_verdadeiro", "verdadeiro"),
+    ("pds_txt_falso", "falso"),
+    ("pds_txt_vazio", ""),
+    ("pds_txt_abre", "["),
+    ("pds_txt_virgula", ", "),
+    ("pds_txt_fecha", "]"),
+    ("pds_txt_objeto", "Objeto<"),
+    ("pds_txt_maior", ">"),
+    ("pds_txt_quebra", "\n"),
+    ("pds_txt_metodo", "Método '"),
+    ("pds_txt_nao_encontrado", "' não encontrado na classe '"),
+    ("pds_txt_aspa", "'"),
+    ("pds_txt_conversao", "Conversão inválida para "),
+    ("pds_txt_valor_nulo", ": valor nulo"),
+    ("pds_txt_dois_pontos", ": "),
+    ("pds_msg_memoria", "Memória insuficiente"),
+    ("pds_msg_divisao", "Divisão por zero"),
+    ("pds_msg_modulo", "Módulo por zero"),
+    ("pds_msg_tamanho", "Tamanho de array negativo"),
+    (
+        "pds_msg_indice_invalido",
+        "Acesso por índice requer array ou texto e um inteiro",
+    ),
+    ("pds_msg_indice_negativo", "Índice negativo"),
+    ("pds_msg_indice_fora", "Índice fora do intervalo"),
+];
+
+/// Classes do runtime (arrays e caixas): global do descritor, nome e função de texto.
+const CLASSES_RUNTIME: &[(&str, &str, &str)] = &[
+    ("pds_classe_array", "array", "pds_texto_array"),
+    ("pds_classe_inteiro", "inteiro", "pds_texto_caixa_inteiro"),
+    (
+        "pds_classe_flutuante",
+        "flutuante",
+        "pds_texto_caixa_flutuante",
+    ),
+    ("pds_classe_duplo", "duplo", "pds_texto_caixa_duplo"),
+    (
+        "pds_classe_booleano",
+        "booleano",
+        "pds_texto_caixa_booleano",
+    ),
+    ("pds_classe_texto", "texto", "pds_texto_caixa_texto"),
+];
+
+/// Monta o módulo em formato texto no formato binário.
+pub fn montar(wat: &str) -> Result<Vec<u8>, String> {
+    wat::parse_str(wat).map_err(|e| format!("Erro ao montar o módulo WebAssembly: {}", e))
+}
+
+/// Tipo de um valor no código gerado (os tipos da linguagem já resolvidos).
+#[derive(Debug, Clone, PartialEq)]
+enum TipoW {
+    Inteiro,
+    Booleano,
+    Flutuante,
+    Duplo,
+    Texto,
+    Vazio,
+    Array(Box<TipoW>),
+    /// FQN de uma classe do programa.
+    Classe(String),
+    /// FQN de uma interface; o valor é um objeto ou uma caixa.
+    Interface(String),
+    /// `objeto`, parâmetros genéricos e classes desconhecidas.
+    Objeto,
+    /// Tipo do literal `nulo`.
+    Nulo,
+}
+
+impl TipoW {
+    fn numerico(&self) -> bool {
+        matches!(self, TipoW::Inteiro | TipoW::Flutuante | TipoW::Duplo)
+    }
+
+    fn referencia(&self) -> bool {
+        matches!(
+            self,
+            TipoW::Array(_) | TipoW::Classe(_) | TipoW::Interface(_) | TipoW::Objeto | TipoW::Nulo
+        )
+    }
+
+    /// Pode conter caixas de valores primitivos.
+    fn dinamico(&self) -> bool {
+        matches!(self, TipoW::Interface(_) | TipoW::Objeto)
+    }
+
+    /// Tipo de valor WebAssembly (`None` para `vazio`).
+    fn valtype(&self) -> Option<&'static str> {
+        match self {
+            TipoW::Inteiro => Some("i64"),
+            TipoW::Flutuante => Some("f32"),
+            TipoW::Duplo => Some("f64"),
+            TipoW::Vazio => None,
+            _ => Some("i32"),
+        }
+    }
+
+    /// Bytes ocupados em campos de objetos e itens de arrays.
+    fn tamanho(&self) -> u32 {
+        match self {
+            TipoW::Inteiro | TipoW::Duplo => 8,
+            _ => 4,
+        }
+    }
+
+    fn carregar(&self) -> String {
+        format!("{}.load", self.valtype().unwrap_or("i32"))
+    }
+
+    fn guardar(&self) -> String {
+        format!("{}.store", self.valtype().unwrap_or("i32"))
+    }
+}
+
+/// Tipo e nulidade do valor deixado na pilha por uma expressão.
+#[derive(Debug, Clone)]
+struct Valor {
+    tipo: TipoW,
+    /// Referência que nunca é nula (`este`, objetos recém-criados, literais).
+    nao_nulo: bool,
+}
+
+impl Valor {
+    fn novo(tipo: TipoW) -> Self {
+        Self {
+            tipo,
+            nao_nulo: false,
+        }
+    }
+
+    fn nao_nulo(tipo: TipoW) -> Self {
+        Self {
+            tipo,
+            nao_nulo: true,
+        }
+    }
+}
+
+/// Onde está uma variável: local (ou parâmetro), campo de `este` (deslocamento) ou global.
+enum Acesso {
+    Local(String),
+    Campo(u32),
+    Global(String),
+}
+
+/// Função de despacho pelo nome do método, para receptores do tipo interface ou `objeto`.
+struct Despacho {
+    nome: String,
+    parametros: Vec<TipoW>,
+    retorno: TipoW,
+}
+
+/// Identificador WAT para um nome da linguagem: letras acentuadas perdem o acento e os demais
+/// caracteres não ASCII viram `_uXXXX`.
+fn identificador(nome: &str) -> String {
+    let mut w = String::with_capacity(nome.len());
+    for ch in nome.chars() {
+        match ch {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' => w.push(ch),
+            'á' | 'à' | 'â' | 'ã' | 'ä' => w.push('a'),
+            'Á' | 'À' | 'Â' | 'Ã' | 'Ä' => w.push('A'),
+            'é' | 'è' | 'ê' | 'ë' => w.push('e'),
+            'É' | 'È' | 'Ê' | 'Ë' => w.push('E'),
+            'í' | 'ì' | 'î' | 'ï' => w.push('i'),
+            'Í' | 'Ì' | 'Î' | 'Ï' => w.push('I'),
+            'ó' | 'ò' | 'ô' | 'õ' | 'ö' => w.push('o'),
+            'Ó' | 'Ò' | 'Ô' | 'Õ' | 'Ö' => w.push('O'),
+            'ú' | 'ù' | 'û' | 'ü' => w.push('u'),
+            'Ú' | 'Ù' | 'Û' | 'Ü' => w.push('U'),
+            'ç' => w.push('c'),
+            'Ç' => w.push('C'),
+            outro => w.push_str(&format!("_u{:04x}", outro as u32)),
+        }
+    }
+    // `este` é o receptor dos métodos e `pds_` é o prefixo do runtime e dos temporários
+    if w == "este" || w.starts_with("pds_") {
+        w.push('_');
+    }
+    w
+}
+
+/// Nome WAT de um símbolo qualificado (`Espaco.Classe`, sem acentos).
+fn nome_global(fqn: &str) -> String {
+    fqn.split('.')
+        .map(identificador)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Bytes como string WAT: ASCII visível sem escape, o resto em `\hh`.
+fn string_wat(bytes: &[u8]) -> String {
+    let mut w = String::from("\"");
+    for &b in bytes {
+        match b {
+            b'"' | b'\\' => w.push_str(&format!("\\{:02x}", b)),
+            0x20..=0x7e => w.push(b as char),
+            _ => w.push_str(&format!("\\{:02x}", b)),
+        }
+    }
+    w.push('"');
+    w
+}
+
+/// Literal real WAT a partir do literal da linguagem (`2.5f`, `1e3`, `10.25m`).
+fn literal_real(texto: &str) -> String {
+    let numero = texto.trim_end_matches(['f', 'F', 'm', 'M', 'd', 'D']);
+    let mut w = numero.to_string();
+    if !w.contains(['.', 'e', 'E']) {
+        w.push_str(".0");
+    }
+    w
+}
+
+fn alinhar(valor: u32, alinhamento: u32) -> u32 {
+    valor.div_ceil(alinhamento) * alinhamento
+}
+
+/// O gerador de código para o alvo WebAssembly.
+pub struct WasmGenerator<'a> {
+    /// Classes, funções e enumerações do programa, com os nomes resolvidos pelo verificador.
+    tabelas: Tabelas<'a>,
+    /// Classe -> entradas da vtable (método, classe que introduz a entrada), herdadas primeiro.
+    vtables: HashMap<String, Vec<(String, String)>>,
+    /// Classe -> deslocamento de cada campo de instância (incluindo os herdados) e tamanho.
+    layouts: HashMap<String, (HashMap<String, u32>, u32)>,
+    despachos: BTreeMap<(String, usize), Despacho>,
+    /// Dados estáticos a partir de `INICIO_DADOS`, com o início e o fim de cada item.
+    dados: Vec<u8>,
+    itens_dados: Vec<(usize, usize)>,
+    textos: HashMap<String, u32>,
+    /// Descritores das classes do programa e do runtime.
+    descritores: HashMap<String, u32>,
+    /// Funções da tabela `$pds_funcoes`, na ordem dos índices.
+    tabela: Vec<String>,
+    /// Tipos de função usados por `call_indirect` (assinatura -> nome).
+    tipos_funcao: BTreeMap<String, String>,
+    /// Verificações de execução (índices, referências nulas, divisão por zero); desligadas por
+    /// `disable_runtime_checks` (`--sem-verificacoes`).
+    verificacoes: bool,
+    posicao: Option<ast::Posicao>,
+    // Estado da função em geração
+    namespace: String,
+    classe_atual: Option<String>,
+    tem_este: bool,
+    escopos: Vec<HashMap<String, (String, TipoW)>>,
+    locais_usados: HashSet<String>,
+    parametros: Vec<(String, &'static str)>,
+    locais: Vec<(String, &'static str)>,
+    /// Temporários do comando atual (liberados no início de cada comando).
+    temporarios_em_uso: HashSet<String>,
+    retorno: TipoW,
+    codigo: Vec<String>,
+    rotulos: usize,
+}
+
+impl<'a> Escopo<'a, (String, TipoW)> for WasmGenerator<'a> {
+    fn tabelas(&self) -> &Tabelas<'a> {
+        &self.tabelas
+    }
+
+    fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    fn classe_atual(&self) -> Option<&str> {
+        self.classe_atual.as_deref()
+    }
+
+    fn tem_este(&self) -> bool {
+        self.tem_este
+    }
+
+    /// Variável local (ou parâmetro) visível no escopo atual.
+    fn local(&self, nome: &str) -> Option<(String, TipoW)> {
+        self.escopos.iter().rev().find_map(|e| e.get(nome).cloned())
+    }
+}
+
+impl<'a> WasmGenerator<'a> {
+    pub fn new(
+        programa: &'a ast::Programa,
+        type_checker: &'a type_checker::VerificadorTipos<'a>,
+    ) -> Self {
+        Self {
+            tabelas: Tabelas::new(programa, type_checker, nome_global, nome_global),
+            vtables: HashMap::new(),
+            layouts: HashMap::new(),
+            despachos: BTreeMap::new(),
+            dados: Vec::new(),
+            itens_dados: Vec::new(),
+            textos: HashMap::new(),
+            descritores: HashMap::new(),
+            tabela: Vec::new(),
+            tipos_funcao: BTreeMap::new(),
+            verificacoes: true,
+            posicao: None,
+            namespace: String::new(),
+            classe_atual: None,
+            tem_este: false,
+            escopos: Vec::new(),
+            locais_usados: HashSet::new(),
+            parametros: Vec::new(),
+            locais: Vec::new(),
+            temporarios_em_uso: HashSet::new(),
+            retorno: TipoW::Vazio,
+            codigo: Vec::new(),
+            rotulos: 0,
+        }
+    }
+
+    /// Gera acessos por índice, a membros e divisões sem as verificações de execução.
+    pub fn disable_runtime_checks(&mut self) {
+        self.verificacoes = false;
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* TABELAS DO PROGRAMA                                                 */
+    /* ------------------------------------------------------------------ */
+
+    /// Campos e propriedades de instância declarados na própria classe.
+    fn campos_proprios(&self, fqn: &str) -> Vec<(&'a String, &'a ast::Tipo)> {
+        let decl = self.tabelas.classes[fqn].decl;
+        decl.campos
+            .iter()
+            .filter(|c| !c.eh_estatica)
+            .map(|c| (&c.nome, &c.tipo))
+            .chain(
+                decl.propriedades
+                    .iter()
+                    .filter(|p| !p.eh_estatica)
+                    .map(|p| (&p.nome, &p.tipo)),
+            )
+            .collect()
+    }
+
+    /// Campo ou propriedade de instância: deslocamento no objeto, tipo e classe que o declara.
+    fn buscar_campo(&self, fqn: &str, nome: &str) -> Option<(u32, TipoW, String)> {
+        let membro = self.tabelas.buscar_membro(fqn, nome, false)?;
+        Some(self.campo(nome, &membro))
+    }
+
+    fn campo(&self, nome: &str, membro: &Membro) -> (u32, TipoW, String) {
+        let classe = membro.classe;
+        (
+            self.layouts[&classe.fqn].0[nome],
+            self.tipo_w(membro.tipo, &classe.namespace),
+            classe.fqn.clone(),
+        )
+    }
+
+    /// Classe (ou ancestral) que declara o membro estático `nome`, e o tipo dele.
+    fn dono_estatico(&self, fqn: &str, nome: &str) -> Option<(String, TipoW)> {
+        let membro = self.tabelas.buscar_membro(fqn, nome, true)?;
+        Some((
+            membro.classe.fqn.clone(),
+            self.tipo_w(membro.tipo, &membro.classe.namespace),
+        ))
+    }
+
+    fn estatico_w(&self, classe: &str, nome: &str) -> String {
+        format!(
+            "${}.{}",
+            self.tabelas.classes[classe].simbolo,
+            identificador(nome)
+        )
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* TIPOS E LAYOUT                                                      */
+    /* ------------------------------------------------------------------ */
+
+    fn tipo_w(&self, tipo: &ast::Tipo, namespace: &str) -> TipoW {
+        match tipo {
+            ast::Tipo::Inteiro | ast::Tipo::Enum(_) => TipoW::Inteiro,
+            ast::Tipo::Booleano => TipoW::Booleano,
+            ast::Tipo::Texto => TipoW::Texto,
+            ast::Tipo::Flutuante => TipoW::Flutuante,
+            ast::Tipo::Duplo | ast::Tipo::Decimal => TipoW::Duplo,
+            ast::Tipo::Vazio => TipoW::Vazio,
+            ast::Tipo::Lista(item) => TipoW::Array(Box::new(self.tipo_w(item, namespace))),
+            ast::Tipo::Opcional(tipo) => self.tipo_w(tipo, namespace),
+            ast::Tipo::Classe(nome) | ast::Tipo::Aplicado { nome, .. } => {
+                if nome == "objeto" {
+                    return TipoW::Objeto;
+                }
+                match self.tabelas.tipo_nomeado(namespace, nome) {
+                    Some(TipoNomeado::Classe(classe)) => TipoW::Classe(classe),
+                    Some(TipoNomeado::Enum(_)) => TipoW::Inteiro,
+                    Some(TipoNomeado::Interface(interface)) => TipoW::Interface(interface),
+                    None => TipoW::Objeto,
+                }
+            }
+            ast::Tipo::Objeto
+            | ast::Tipo::Generico(_)
+            | ast::Tipo::Funcao(..)
+            | ast::Tipo::Inferido => TipoW::Objeto,
+        }
+    }
+
+    fn tipo_retorno(&self, tipo: &Option<ast::Tipo>, namespace: &str) -> TipoW {
+        tipo.as_ref()
+            .map(|t| self.tipo_w(t, namespace))
+            .unwrap_or(TipoW::Vazio)
+    }
+
+    /// Código do elemento de arrays usado pelo runtime (`$pds_tamanho_item`, exibição).
+    fn elemento(tipo: &TipoW) -> u32 {
+        match tipo {
+            TipoW::Inteiro => 0,
+            TipoW::Flutuante => 1,
+            TipoW::Duplo => 2,
+            TipoW::Booleano => 3,
+            TipoW::Texto => 4,
+            _ => 5,
+        }
+    }
+
+    fn nome_tipo(&self, tipo: &TipoW) -> String {
+        match tipo {
+            TipoW::Inteiro => "inteiro".to_string(),
+            TipoW::Booleano => "booleano".to_string(),
+            TipoW::Flutuante => "flutuante".to_string(),
+            TipoW::Duplo => "duplo".to_string(),
+            TipoW::Texto => "texto".to_string(),
+            TipoW::Vazio => "vazio".to_string(),
+            TipoW::Array(_) => "array".to_string(),
+            TipoW::Classe(n) | TipoW::Interface(n) => n.clone(),
+            TipoW::Objeto => "objeto".to_string(),
+            TipoW::Nulo => "nulo".to_string(),
+        }
+    }
+
+    /// Tipo dos itens de um literal `[a, b, ...]`.
+    fn tipo_itens(&self, tipos: &[TipoW]) -> TipoW {
+        let mut comum: Option<TipoW> = None;
+        for tipo in tipos.iter().filter(|t| **t != TipoW::Nulo) {
+            comum = Some(match (comum, tipo) {
+                (None, t) => t.clone(),
+                (Some(a), b) if a == *b => a,
+                (Some(TipoW::Classe(a)), TipoW::Classe(b)) => self
+                    .tabelas
+                    .ancestral_comum(&a, b)
+                    .map(TipoW::Classe)
+                    .unwrap_or(TipoW::Objeto),
+                _ => TipoW::Objeto,
+            });
+        }
+        comum.unwrap_or(TipoW::Objeto)
+    }
+
+    /// Campos de instância depois do descritor (4 bytes) ou dos campos da classe pai, cada um
+    /// alinhado pelo próprio tamanho.
+    fn construir_layouts(&mut self) {
+        for fqn in self.tabelas.ordem_classes.clone() {
+            self.layout(&fqn);
+        }
+    }
+
+    fn layout(&mut self, fqn: &str) -> u32 {
+        if let Some((_, tamanho)) = self.layouts.get(fqn) {
+            return *tamanho;
+        }
+        let mut fim = match self.tabelas.classes[fqn].pai.clone() {
+            Some(pai) => self.layout(&pai),
+            None => 4,
+        };
+        let namespace = self.tabelas.classes[fqn].namespace.clone();
+        let mut deslocamentos = HashMap::new();
+        for (nome, tipo) in self.campos_proprios(fqn) {
+            let tamanho = self.tipo_w(tipo, &namespace).tamanho();
+            let deslocamento = alinhar(fim, tamanho);
+            deslocamentos.insert(nome.clone(), deslocamento);
+            fim = deslocamento + tamanho;
+        }
+        self.layouts.insert(fqn.to_string(), (deslocamentos, fim));
+        fim
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* VTABLES, TABELA DE FUNÇÕES E DESCRITORES                            */
+    /* ------------------------------------------------------------------ */
+
+    /// Posição da entrada do método na vtable da classe, se ele é chamado pela vtable.
+    fn entrada_vtable(&self, classe: &str, metodo: &str) -> Option<usize> {
+        self.vtables
+            .get(classe)?
+            .iter()
+            .position(|(nome, _)| nome == metodo)
+    }
+
+    fn metodo_w(&self, classe: &str, metodo: &str) -> String {
+        format!(
+            "${}.{}",
+            self.tabelas.classes[classe].simbolo,
+            identificador(metodo)
+        )
+    }
+
+    /// Assinatura (retorno, parâmetros) de um método, sem o receptor.
+    fn assinatura(&self, classe: &str, metodo: &ast::MetodoClasse) -> (TipoW, Vec<TipoW>) {
+        let namespace = &self.tabelas.classes[classe].namespace;
+        (
+            self.tipo_retorno(&metodo.tipo_retorno, namespace),
+            metodo
+                .parametros
+                .iter()
+                .map(|p| self.tipo_w(&p.tipo, namespace))
+                .collect(),
+        )
+    }
+
+    /// Classe mais próxima (ela mesma ou ancestral) com inicializadores de campos de instância.
+    fn inicializador(&self, fqn: &str) -> Option<String> {
+        self.tabelas
+            .hierarquia(fqn)
+            .into_iter()
+            .find(|c| {
+                c.decl
+                    .campos
+                    .iter()
+                    .any(|f| !f.eh_estatica && f.valor_inicial.is_some())
+                    || c.decl
+                        .propriedades
+                        .iter()
+                        .any(|p| !p.eh_estatica && p.valor_inicial.is_some())
+            })
+            .map(|c| c.fqn.clone())
+    }
+
+    /// Classe que declara o campo `Nome` exibido por `imprima(objeto)`.
+    fn classe_do_nome(&self, fqn: &str) -> Option<String> {
+        self.buscar_campo(fqn, "Nome").map(|(_, _, dono)| dono)
+    }
+
+    /// Funções especiais da classe (`#novo`, `#construir`, `#texto`...), que não colidem com
+    /// os métodos (`.metodo`).
+    fn especial_w(&self, classe: &str, nome: &str) -> String {
+        format!("${}#{}", self.tabelas.classes[classe].simbolo, nome)
+    }
+
+    fn construtor_w(&self, classe: &str, prefixo: &str, indice: usize) -> String {
+        if self.tabelas.classes[classe].decl.construtores.len() > 1 {
+            self.especial_w(classe, &format!("{}.{}", prefixo, indice))
+        } else {
+            self.especial_w(classe, prefixo)
+        }
+    }
+
+    /// Construtores `novo` da classe: um por construtor declarado, ou um sem parâmetros.
+    fn novos(&self, classe: &str) -> Vec<(usize, Option<&'a ast::ConstrutorClasse>)> {
+        let decl = self.tabelas.classes[classe].decl;
+        if decl.construtores.is_empty() {
+            vec![(0, None)]
+        } else {
+            decl.construtores
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (i, Some(c)))
+                .collect()
+        }
+    }
+
+    fn indice_tabela(&mut self, funcao: String) -> i32 {
+        match self.tabela.iter().position(|f| *f == funcao) {
+            Some(i) => i as i32,
+            None => {
+                self.tabela.push(funcao);
+                self.tabela.len() as i32 - 1
+            }
+        }
+    }
+
+    /// Nome do tipo de função para `call_indirect` com a assinatura dada.
+    fn tipo_funcao(&mut self, parametros: &[&str], retorno: Option<&str>) -> String {
+        let mut assinatura = String::from("(func");
+        if !parametros.is_empty() {
+            assinatura.push_str(&format!(" (param {})", parametros.join(" ")));
+        }
+        if let Some(retorno) = retorno {
+            assinatura.push_str(&format!(" (result {})", retorno));
+        }
+        assinatura.push(')');
+        let proximo = format!("$pds_tipo.{}", self.tipos_funcao.len());
+        self.tipos_funcao
+            .entry(assinatura)
+            .or_insert(proximo)
+            .clone()
+    }
+
+    /// Textos literais ficam nos dados estáticos, um por conteúdo.
+    fn texto(&mut self, texto: &str) -> u32 {
+        if let Some(endereco) = self.textos.get(texto) {
+            return *endereco;
+        }
+        let endereco = self.reservar(4 + texto.len());
+        let inicio = (endereco - INICIO_DADOS) as usize;
+        self.dados[inicio..inicio + 4].copy_from_slice(&(texto.len() as u32).to_le_bytes());
+        self.dados[inicio + 4..].copy_from_slice(texto.as_bytes());
+        self.textos.insert(texto.to_string(), endereco);
+        endereco
+    }
+
+    fn reservar(&mut self, tamanho: usize) -> u32 {
+        let inicio = alinhar(self.dados.len() as u32, 4) as usize;
+        self.dados.resize(inicio + tamanho, 0);
+        self.itens_dados.push((inicio, inicio + tamanho));
+        INICIO_DADOS + inicio as u32
+    }
+
+    fn escrever_dado(&mut self, endereco: u32, valor: i32) {
+        let inicio = (endereco - INICIO_DADOS) as usize;
+        self.dados[inicio..inicio + 4].copy_from_slice(&valor.to_le_bytes());
+    }
+
+    /// Descritores `[nome][pai][texto][vtable...]` das classes do runtime e do programa.
+    fn construir_descritores(&mut self) {
+        for (global, nome, texto) in CLASSES_RUNTIME {
+            let endereco = self.reservar(12);
+            let nome = self.texto(nome);
+            let indice = self.indice_tabela(format!("${}", texto));
+            self.escrever_dado(endereco, nome as i32);
+            self.escrever_dado(endereco + 8, indice);
+            self.descritores.insert(global.to_string(), endereco);
+        }
+        for fqn in self.tabelas.ordem_classes.clone() {
+            let entradas = self.vtables[&fqn].len();
+            let endereco = self.reservar(12 + 4 * entradas);
+            self.descritores.insert(fqn, endereco);
+        }
+        for fqn in self.tabelas.ordem_classes.clone() {
+            let endereco = self.descritores[&fqn];
+            let nome = self.texto(&fqn);
+            self.escrever_dado(endereco, nome as i32);
+            if let Some(pai) = self.tabelas.classes[&fqn].pai.clone() {
+                let pai = self.descritores[&pai];
+                self.escrever_dado(endereco + 4, pai as i32);
+            }
+            let texto = match self.classe_do_nome(&fqn) {
+                Some(dono) => self.indice_tabela(self.especial_w(&dono, "texto")),
+                None => -1,
+            };
+            self.escrever_dado(endereco + 8, texto);
+            for (i, (nome, _)) in self.vtables[&fqn].clone().into_iter().enumerate() {
+                let indice = match self.tabelas.buscar_metodo(&fqn, &nome, usize::MAX) {
+                    Some((dono, metodo)) if !metodo.eh_abstrato => {
+                        self.indice_tabela(self.metodo_w(&dono, &nome))
+                    }
+                    _ => -1,
+                };
+                self.escrever_dado(endereco + 12 + 4 * i as u32, indice);
+            }
+        }
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* GERAÇÃO DO MÓDULO                                                   */
+    /* ------------------------------------------------------------------ */
+
+    pub fn generate(&mut self) -> Result<String, String> {
+        self.vtables = self.tabelas.vtables();
+        self.construir_layouts();
+        self.construir_descritores();
+
+        let funcoes = self.gerar_funcoes()?;
+        let estaticos = self.gerar_estaticos()?;
+        let globais = self.gerar_comandos_globais()?;
+        let despachos = self.gerar_despachos();
+        let inicio = self.gerar_inicio(!estaticos.1.is_empty(), !globais.is_empty());
+
+        let mut globais_runtime = format!("  (global $pds_buffer i32 (i32.const {}))\n", BUFFER);
+        for (global, texto) in TEXTOS_RUNTIME {
+            let endereco = self.texto(texto);
+            globais_runtime.push_str(&format!(
+                "  (global ${} i32 (i32.const {}))\n",
+                global, endereco
+            ));
+        }
+        for (global, _, _) in CLASSES_RUNTIME {
+            globais_runtime.push_str(&format!(
+                "  (global ${} i32 (i32.const {}))\n",
+                global, self.descritores[*global]
+            ));
+        }
+        // O heap começa depois dos dados estáticos
+        let fim_dados = alinhar(INICIO_DADOS + self.dados.len() as u32, 8);
+        globais_runtime.push_str(&format!(
+            "  (global $pds_heap (mut i32) (i32.const {}))\n",
+            fim_dados
+        ));
+        let paginas = fim_dados.div_ceil(65536) + 1;
+
+        let mut saida = String::new();
+        saida.push_str(";; Gerado pelo compilador Por do Sol (--target=wasm).\n(module\n");
+        saida.push_str(RUNTIME);
+        saida.push('\n');
+        saida.push_str(&format!("  (memory (export \"memory\") {})\n", paginas));
+        saida.push_str(&format!(
+            "  (table $pds_funcoes {} funcref)\n",
+            self.tabela.len()
+        ));
+        if !self.tabela.is_empty() {
+            saida.push_str(&format!(
+                "  (elem (i32.const 0) func {})\n",
+                self.tabela.join(" ")
+            ));
+        }
+        for (assinatura, nome) in &self.tipos_funcao {
+            saida.push_str(&format!("  (type {} {})\n", nome, assinatura));
+        }
+        saida.push('\n');
+        saida.push_str(&globais_runtime);
+        saida.push_str(&estaticos.0);
+        saida.push('\n');
+        for (inicio, fim) in &self.itens_dados {
+            saida.push_str(&format!(
+                "  (data (i32.const {}) {})\n",
+                INICIO_DADOS + *inicio as u32,
+                string_wat(&self.dados[*inicio..*fim])
+            ));
+        }
+        saida.push('\n');
+        saida.push_str(&despachos);
+        saida.push_str(&funcoes);
+        saida.push_str(&estaticos.1);
+        saida.push_str(&globais);
+        saida.push_str(&inicio);
+        saida.push_str(")\n");
+        Ok(saida)
+    }
+
+    fn gerar_funcoes(&mut self) -> Result<String, String> {
+        let mut saida = String::new();
+        for fqn in self.tabelas.ordem_classes.clone() {
+            saida.push_str(&self.gerar_classe(&fqn)?);
+        }
+        for fqn in self.tabelas.ordem_funcoes.clone() {
+            let funcao = &self.tabelas.funcoes[&fqn];
+            let decl = funcao.decl;
+            if chave_nativa(&decl.attributes).is_some()
+                || decl.attributes.iter().any(|a| a.name == "Externo")
+            {
+                continue;
+            }
+            let namespace = funcao.namespace.clone();
+            let nome = format!("${}", funcao.simbolo);
+            let retorno = self.tipo_retorno(&decl.tipo_retorno, &namespace);
+            self.iniciar_funcao(&namespace, None, false, retorno.clone());
+            self.declarar_parametros(&decl.parametros, &namespace);
+            self.gerar_bloco(&decl.corpo)?;
+            self.retorno_final(&decl.corpo, &retorno);
+            saida.push_str(&self.terminar_funcao(&nome, &retorno));
+        }
+        Ok(saida)
+    }
+
+    /// Funções sem `retorne` no final devolvem o valor padrão do tipo (nulo no interpretador).
+    fn retorno_final(&mut self, corpo: &[ast::Comando], retorno: &TipoW) {
+        if *retorno != TipoW::Vazio && !matches!(corpo.last(), Some(ast::Comando::Retorne(_))) {
+            self.padrao(retorno, false);
+        }
+    }
+
+    fn gerar_classe(&mut self, fqn: &str) -> Result<String, String> {
+        let mut saida = String::new();
+        let classe = &self.tabelas.classes[fqn];
+        let decl = classe.decl;
+        let namespace = classe.namespace.clone();
+        let pai = classe.pai.clone();
+
+        // Texto exibido por imprima: o campo `Nome`, como no interpretador
+        if self.classe_do_nome(fqn).as_deref() == Some(fqn) {
+            let (deslocamento, tipo, _) = self.buscar_campo(fqn, "Nome").unwrap();
+            self.iniciar_funcao(&namespace, Some(fqn), true, TipoW::Texto);
+            self.emitir("local.get $este");
+            self.emitir(format!("{} offset={}", tipo.carregar(), deslocamento));
+            self.texto_de(&Valor::novo(tipo));
+            let nome = self.especial_w(fqn, "texto");
+            saida.push_str(&self.terminar_funcao(&nome, &TipoW::Texto));
+        }
+
+        // Inicializadores de campos, da base para a derivada
+        if self.inicializador(fqn).as_deref() == Some(fqn) {
+            self.iniciar_funcao(&namespace, Some(fqn), true, TipoW::Vazio);
+            if let Some(pai) = pai.as_ref().and_then(|p| self.inicializador(p)) {
+                self.emitir("local.get $este");
+                self.emitir(format!("call {}", self.especial_w(&pai, "inicializar")));
+            }
+            let iniciais = decl
+                .campos
+                .iter()
+                .filter(|c| !c.eh_estatica)
+                .filter_map(|c| c.valor_inicial.as_ref().map(|e| (&c.nome, e)))
+                .chain(
+                    decl.propriedades
+                        .iter()
+                        .filter(|p| !p.eh_estatica)
+                        .filter_map(|p| p.valor_inicial.as_ref().map(|e| (&p.nome, e))),
+                );
+            for (nome, expr) in iniciais {
+                let (deslocamento, tipo, _) = self.buscar_campo(fqn, nome).unwrap();
+                self.emitir("local.get $este");
+                let valor = self.expr(expr)?;
+                self.converter(&valor.tipo, &tipo);
+                self.emitir(format!("{} offset={}", tipo.guardar(), deslocamento));
+            }
+            let nome = self.especial_w(fqn, "inicializar");
+            saida.push_str(&self.terminar_funcao(&nome, &TipoW::Vazio));
+        }
+
+        // Construtores
+        for (i, construtor) in decl.construtores.iter().enumerate() {
+            self.iniciar_funcao(&namespace, Some(fqn), true, TipoW::Vazio);
+            self.declarar_parametros(&construtor.parametros, &namespace);
+            if let Some(pai) = pai.clone() {
+                let args = construtor.chamada_pai.as_deref().unwrap_or(&[]);
+                self.construcao_pai(&pai, args)?;
+            }
+            self.gerar_bloco(&construtor.corpo)?;
+            let nome = self.construtor_w(fqn, "construir", i);
+            saida.push_str(&self.terminar_funcao(&nome, &TipoW::Vazio));
+        }
+        if !decl.eh_abstrata && !decl.eh_estatica {
+            for (i, construtor) in self.novos(fqn) {
+                let tipo = TipoW::Classe(fqn.to_string());
+                self.iniciar_funcao(&namespace, Some(fqn), false, tipo.clone());
+                if let Some(construtor) = construtor {
+                    self.declarar_parametros(&construtor.parametros, &namespace);
+                }
+                // `este` é um local: o objeto recém-alocado
+                self.locais.push(("$este".to_string(), "i32"));
+                self.tem_este = true;
+                self.emitir(format!("i32.const {}", self.layouts[fqn].1));
+                self.emitir("call $pds_alocar");
+                self.emitir("local.tee $este");
+                self.emitir(format!("i32.const {}", self.descritores[fqn]));
+                self.emitir("i32.store");
+                if let Some(inicializador) = self.inicializador(fqn) {
+                    self.emitir("local.get $este");
+                    self.emitir(format!(
+                        "call {}",
+                        self.especial_w(&inicializador, "inicializar")
+                    ));
+                }
+                match construtor {
+                    Some(construtor) => {
+                        self.emitir("local.get $este");
+                        for p in &construtor.parametros {
+                            self.emitir(format!("local.get ${}", identificador(&p.nome)));
+                        }
+                        self.emitir(format!("call {}", self.construtor_w(fqn, "construir", i)));
+                    }
+                    None => {
+                        // Sem construtor declarado: apenas o construtor padrão da classe pai
+                        if let Some(pai) = pai.clone() {
+                            self.construcao_pai(&pai, &[])?;
+                        }
+                    }
+                }
+                self.emitir("local.get $este");
+                let nome = self.construtor_w(fqn, "novo", i);
+                saida.push_str(&self.terminar_funcao(&nome, &tipo));
+            }
+        }
+
+        // Acessores de propriedades com corpo
+        for propriedade in decl.propriedades.iter().filter(|p| !p.eh_estatica) {
+            let tipo = self.tipo_w(&propriedade.tipo, &namespace);
+            if let Some(corpo) = propriedade.obter.as_ref().and_then(|a| a.corpo.as_ref()) {
+                self.iniciar_funcao(&namespace, Some(fqn), true, tipo.clone());
+                self.gerar_bloco(corpo)?;
+                self.retorno_final(corpo, &tipo);
+                let nome =
+                    self.especial_w(fqn, &format!("obter.{}", identificador(&propriedade.nome)));
+                saida.push_str(&self.terminar_funcao(&nome, &tipo));
+            }
+            if let Some(corpo) = propriedade.definir.as_ref().and_then(|a| a.corpo.as_ref()) {
+                self.iniciar_funcao(&namespace, Some(fqn), true, TipoW::Vazio);
+                self.locais_usados.insert("valor".to_string());
+                self.escopos[0].insert("valor".to_string(), ("$valor".to_string(), tipo.clone()));
+                self.parametros
+                    .push(("$valor".to_string(), tipo.valtype().unwrap_or("i32")));
+                self.gerar_bloco(corpo)?;
+                let nome = self.especial_w(
+                    fqn,
+                    &format!("definir.{}", identificador(&propriedade.nome)),
+                );
+                saida.push_str(&self.terminar_funcao(&nome, &TipoW::Vazio));
+            }
+        }
+
+        // Métodos
+        for metodo in decl.metodos.iter().filter(|m| !m.eh_abstrato) {
+            if let Some(chave) = chave_nativa(&metodo.attributes) {
+                return Err(format!(
+                    "Método nativo '{}' ({}) não é suportado no alvo wasm",
+                    metodo.nome, chave
+                ));
+            }
+            let (retorno, _) = self.assinatura(fqn, metodo);
+            self.iniciar_funcao(&namespace, Some(fqn), !metodo.eh_estatica, retorno.clone());
+            self.declarar_parametros(&metodo.parametros, &namespace);
+            self.gerar_bloco(&metodo.corpo)?;
+            self.retorno_final(&metodo.corpo, &retorno);
+            let nome = self.metodo_w(fqn, &metodo.nome);
+            saida.push_str(&self.terminar_funcao(&nome, &retorno));
+        }
+        Ok(saida)
+    }
+
+    /// Chamada ao construtor da classe pai (`: base(...)`), ou ao construtor padrão dela.
+    fn construcao_pai(&mut self, pai: &str, args: &[ast::Expressao]) -> Result<(), String> {
+        match self.tabelas.escolher_construtor(pai, args.len()) {
+            Some(indice) => {
+                let parametros = self.tabelas.classes[pai].decl.construtores[indice]
+                    .parametros
+                    .clone();
+                let namespace = self.tabelas.classes[pai].namespace.clone();
+                self.emitir("local.get $este");
+                self.argumentos(&parametros, &namespace, args)?;
+                self.emitir(format!(
+                    "call {}",
+                    self.construtor_w(pai, "construir", indice)
+                ));
+            }
+            None => {
+                if let Some(avo) = self.tabelas.classes[pai].pai.clone() {
+                    self.construcao_pai(&avo, &[])?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Globais dos membros estáticos e a função que os inicializa.
+    fn gerar_estaticos(&mut self) -> Result<(String, String), String> {
+        let mut declaracoes = String::new();
+        let mut corpo = Vec::new();
+        for fqn in self.tabelas.ordem_classes.clone() {
+            let classe = &self.tabelas.classes[&fqn];
+            let namespace = classe.namespace.clone();
+            let membros: Vec<(&String, &ast::Tipo, &Option<ast::Expressao>)> = classe
+                .decl
+                .campos
+                .iter()
+                .filter(|c| c.eh_estatica)
+                .map(|c| (&c.nome, &c.tipo, &c.valor_inicial))
+                .chain(
+                    classe
+                        .decl
+                        .propriedades
+                        .iter()
+                        .filter(|p| p.eh_estatica)
+                        .map(|p| (&p.nome, &p.tipo, &p.valor_inicial)),
+                )
+                .collect();
+            for (nome, tipo, inicial) in membros {
+                let tipo = self.tipo_w(tipo, &namespace);
+                let global = self.estatico_w(&fqn, nome);
+                let valtype = tipo.valtype().unwrap_or("i32");
+                declaracoes.push_str(&format!(
+                    "  (global {} (mut {}) ({}.const 0))\n",
+                    global, valtype, valtype
+                ));
+                if let Some(expr) = inicial {
+                    self.iniciar_funcao(&namespace, Some(&fqn), false, TipoW::Vazio);
+                    let valor = self.expr(expr)?;
+                    self.converter(&valor.tipo, &tipo);
+                    self.emitir(format!("global.set {}", global));
+                    corpo.push((
+                        std::mem::take(&mut self.codigo),
+                        std::mem::take(&mut self.locais),
+                    ));
+                }
+            }
+        }
+        if corpo.is_empty() {
+            return Ok((declaracoes, String::new()));
+        }
+        self.iniciar_funcao("", None, false, TipoW::Vazio);
+        for (codigo, locais) in corpo {
+            self.codigo.extend(codigo);
+            self.locais.extend(locais);
+        }
+        let funcao = self.terminar_funcao("$pds_inicializar_estaticos", &TipoW::Vazio);
+        Ok((declaracoes, funcao))
+    }
+
+    fn gerar_comandos_globais(&mut self) -> Result<String, String> {
+        if self.tabelas.globais.is_empty() {
+            return Ok(String::new());
+        }
+        self.iniciar_funcao("", None, false, TipoW::Vazio);
+        for (namespace, comando) in self.tabelas.globais.clone() {
+            self.namespace = namespace;
+            self.gerar_comando(comando)?;
+        }
+        Ok(self.terminar_funcao("$pds_comandos_globais", &TipoW::Vazio))
+    }
+
+    /// Ponto de entrada exportado como `_start`: estáticos, comandos globais e `Principal`.
+    fn gerar_inicio(&self, estaticos: bool, globais: bool) -> String {
+        let mut saida = String::from("  (func $pds_iniciar (export \"_start\")\n");
+        if estaticos {
+            saida.push_str("    call $pds_inicializar_estaticos\n");
+        }
+        if globais {
+            saida.push_str("    call $pds_comandos_globais\n");
+        }
+        let principal = self
+            .tabelas
+            .ordem_funcoes
+            .iter()
+            .find(|n| *n == "Principal" || *n == "principal" || n.ends_with(".Principal"));
+        if let Some(fqn) = principal {
+            let funcao = &self.tabelas.funcoes[fqn];
+            saida.push_str(&format!("    call ${}\n", funcao.simbolo));
+            if self.tipo_retorno(&funcao.decl.tipo_retorno, &funcao.namespace) != TipoW::Vazio {
+                saida.push_str("    drop\n");
+            }
+        }
+        saida.push_str("  )\n");
+        saida
+    }
+
+    fn nome_despacho(nome: &str, n: usize) -> String {
+        format!("$pds_despachar.{}.{}", identificador(nome), n)
+    }
+
+    fn gerar_despachos(&mut self) -> String {
+        let mut saida = String::new();
+        let chaves: Vec<(String, usize)> = self.despachos.keys().cloned().collect();
+        for (nome, n) in chaves {
+            let (parametros_despacho, retorno_despacho, nome_metodo) = {
+                let despacho = &self.despachos[&(nome.clone(), n)];
+                (
+                    despacho.parametros.clone(),
+                    despacho.retorno.clone(),
+                    despacho.nome.clone(),
+                )
+            };
+            self.iniciar_funcao("", None, false, retorno_despacho.clone());
+            self.parametros.push(("$objeto".to_string(), "i32"));
+            for (i, tipo) in parametros_despacho.iter().enumerate() {
+                self.parametros
+                    .push((format!("$a{}", i), tipo.valtype().unwrap_or("i32")));
+            }
+            self.locais.push(("$classe".to_string(), "i32"));
+            self.emitir("local.get $objeto");
+            self.emitir("i32.load");
+            self.emitir("local.set $classe");
+            for fqn in self.tabelas.ordem_classes.clone() {
+                let classe = &self.tabelas.classes[&fqn];
+                if classe.decl.eh_abstrata || classe.decl.eh_estatica {
+                    continue;
+                }
+                let Some((dono, metodo)) = self.tabelas.buscar_metodo(&fqn, &nome, n) else {
+                    continue;
+                };
+                if metodo.eh_estatica || metodo.eh_abstrato {
+                    continue;
+                }
+                let (retorno, parametros) = self.assinatura(&dono, metodo);
+                self.emitir("local.get $classe");
+                self.emitir(format!("i32.const {}", self.descritores[&fqn]));
+                self.emitir("i32.eq");
+                self.emitir("if");
+                self.emitir("local.get $objeto");
+                for (i, tipo) in parametros.iter().enumerate() {
+                    match parametros_despacho.get(i) {
+                        Some(de) => {
+                            self.emitir(format!("local.get $a{}", i));
+                            self.converter(de, tipo);
+                        }
+                        None => self.padrao(tipo, true),
+                    }
+                }
+                self.emitir(format!("call {}", self.metodo_w(&dono, &nome)));
+                self.converter(&retorno, &retorno_despacho);
+                self.emitir("return");
+                self.emitir("end");
+            }
+            let metodo = self.texto(&nome_metodo);
+            self.emitir(format!("i32.const {}", metodo));
+            self.emitir("local.get $objeto");
+            self.emitir("call $pds_metodo_nao_encontrado");
+            self.emitir("unreachable");
+            let funcao = Self::nome_despacho(&nome, n);
+            saida.push_str(&self.terminar_funcao(&funcao, &retorno_despacho));
+        }
+        saida
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* FUNÇÕES E COMANDOS                                                  */
+    /* ------------------------------------------------------------------ */
+
+    /// Prepara o estado para gerar o corpo de uma função.
+    fn iniciar_funcao(
+        &mut self,
+        namespace: &str,
+        classe: Option<&str>,
+        tem_este: bool,
+        retorno: TipoW,
+    ) {
+        self.namespace = namespace.to_string();
+        self.classe_atual = classe.map(str::to_string);
+        self.tem_este = tem_este;
+        self.retorno = retorno;
+        self.escopos = vec![HashMap::new()];
+        self.locais_usados = HashSet::new();
+        self.parametros = Vec::new();
+        self.locais = Vec::new();
+        self.temporarios_em_uso.clear();
+        self.codigo = Vec::new();
+        self.rotulos = 0;
+        self.posicao = None;
+        if tem_este {
+            self.parametros.push(("$este".to_string(), "i32"));
+        }
+    }
+
+    fn declarar_parametros(&mut self, parametros: &[ast::Parametro], namespace: &str) {
+        for p in parametros {
+            let tipo = self.tipo_w(&p.tipo, namespace);
+            let w = identificador(&p.nome);
+            self.locais_usados.insert(w.clone());
+            self.parametros
+                .push((format!("${}", w), tipo.valtype().unwrap_or("i32")));
+            self.escopos
+                .last_mut()
+                .unwrap()
+                .insert(p.nome.clone(), (format!("${}", w), tipo));
+        }
+    }
+
+    /// Texto da função: cabeçalho, locais e as instruções indentadas pelos blocos.
+    fn terminar_funcao(&mut self, nome: &str, retorno: &TipoW) -> String {
+        let mut saida = format!("  (func {}", nome);
+        for (parametro, tipo) in &self.parametros {
+            saida.push_str(&format!(" (param {} {})", parametro, tipo));
+        }
+        if let Some(tipo) = retorno.valtype() {
+            saida.push_str(&format!(" (result {})", tipo));
+        }
+        saida.push('\n');
+        for (local, tipo) in &self.locais {
+            saida.push_str(&format!("    (local {} {})\n", local, tipo));
+        }
+        let mut nivel = 0usize;
+        for instrucao in std::mem::take(&mut self.codigo) {
+            let palavra = instrucao.split_whitespace().next().unwrap_or("");
+            if matches!(palavra, "end" | "else") {
+                nivel = nivel.saturating_sub(1);
+            }
+            saida.push_str(&"  ".repeat(nivel + 2));
+            saida.push_str(&instrucao);
+            saida.push('\n');
+            if matches!(palavra, "block" | "loop" | "if" | "else") {
+                nivel += 1;
+            }
+        }
+        saida.push_str("  )\n\n");
+        saida
+    }
+
+    fn emitir(&mut self, instrucao: impl Into<String>) {
+        self.codigo.push(instrucao.into());
+    }
+
+    /// Gera `gerar` em separado, devolvendo as instruções sem emiti-las.
+    fn capturar<T>(
+        &mut self,
+        gerar: impl FnOnce(&mut Self) -> Result<T, String>,
+    ) -> Result<(Vec<String>, T), String> {
+        let anterior = std::mem::take(&mut self.codigo);
+        let resultado = gerar(self);
+        let capturado = std::mem::replace(&mut self.codigo, anterior);
+        Ok((capturado, resultado?))
+    }
+
+    fn rotulo(&mut self) -> usize {
+        self.rotulos += 1;
+        self.rotulos
+    }
+
+    /// Local auxiliar do comando atual, reaproveitado nos comandos seguintes.
+    fn temporario(&mut self, valtype: &'static str) -> String {
+        let livre = self
+            .locais
+            .iter()
+            .find(|(nome, tipo)| {
+                nome.starts_with("$pds_t")
+                    && *tipo == valtype
+                    && !self.temporarios_em_uso.contains(nome)
+            })
+            .map(|(nome, _)| nome.clone());
+        let nome = livre.unwrap_or_else(|| {
+            let nome = format!("$pds_t{}", self.locais.len());
+            self.locais.push((nome.clone(), valtype));
+            nome
+        });
+        self.temporarios_em_uso.insert(nome.clone());
+        nome
+    }
+
+    fn declarar_local(&mut self, nome: &str, tipo: TipoW) -> String {
+        let base = identificador(nome);
+        let mut w = base.clone();
+        let mut i = 2;
+        while self.locais_usados.contains(&w) {
+            w = format!("{}_{}", base, i);
+            i += 1;
+        }
+        self.locais_usados.insert(w.clone());
+        let w = format!("${}", w);
+        self.locais
+            .push((w.clone(), tipo.valtype().unwrap_or("i32")));
+        self.escopos
+            .last_mut()
+            .unwrap()
+            .insert(nome.to_string(), (w.clone(), tipo));
+        w
+    }
+
+    fn gerar_bloco(&mut self, comandos: &[ast::Comando]) -> Result<(), String> {
+        for comando in comandos {
+            self.gerar_comando(comando)?;
+        }
+        Ok(())
+    }
+
+    /// Corpo de `se`/`enquanto`, com escopo próprio.
+    fn gerar_corpo(&mut self, comando: &ast::Comando) -> Result<(), String> {
+        self.escopos.push(HashMap::new());
+        match comando {
+            ast::Comando::Bloco(comandos) => self.gerar_bloco(comandos)?,
+            outro => self.gerar_comando(outro)?,
+        }
+        self.escopos.pop();
+        Ok(())
+    }
+
+    fn gerar_comando(&mut self, comando: &ast::Comando) -> Result<(), String> {
+        self.temporarios_em_uso.clear();
+        match comando {
+            ast::Comando::Posicao(posicao) => self.posicao = Some(posicao.clone()),
+            ast::Comando::DeclaracaoVariavel(tipo, nome, inicial) => {
+                let tipo = match self.tipo_w(tipo, &self.namespace.clone()) {
+                    TipoW::Vazio => TipoW::Objeto,
+                    tipo => tipo,
+                };
+                match inicial {
+                    Some(expr) => {
+                        let valor = self.expr(expr)?;
+                        self.converter(&valor.tipo, &tipo);
+                    }
+                    None => self.padrao(&tipo, true),
+                }
+                let w = self.declarar_local(nome, tipo);
+                self.emitir(format!("local.set {}", w));
+            }
+            ast::Comando::DeclaracaoVar(nome, expr) => {
+                let valor = self.expr(expr)?;
+                self.declarar_var(nome, valor);
+            }
+            ast::Comando::CriarObjeto(nome, classe, args) => {
+                let valor = self.expr(&ast::Expressao::NovoObjeto(
+                    ast::Tipo::Classe(classe.clone()),
+                    args.clone(),
+                ))?;
+                self.declarar_var(nome, valor);
+            }
+            ast::Comando::Atribuicao(nome, expr) => self.atribuir_variavel(nome, expr)?,
+            ast::Comando::AtribuirPropriedade(alvo, membro, expr)
+            | ast::Comando::AtribuirCampo(alvo, membro, expr) => {
+                self.atribuir_membro(alvo, membro, expr)?
+            }
+            ast::Comando::AtribuirIndice(alvo, indice, expr) => {
+                self.atribuir_indice(alvo, indice, expr)?
+            }
+            ast::Comando::Imprima(expr) => {
+                let valor = self.expr(expr)?;
+                self.texto_de(&valor);
+                self.emitir("call $pds_imprimir");
+            }
+            ast::Comando::Se(condicao, entao, senao) => {
+                self.condicao(condicao)?;
+                self.emitir("if");
+                self.gerar_corpo(entao)?;
+                if let Some(senao) = senao {
+                    self.emitir("else");
+                    self.gerar_corpo(senao)?;
+                }
+                self.emitir("end");
+            }
+            ast::Comando::Enquanto(condicao, corpo) => {
+                self.gerar_laco(None, Some(condicao), None, corpo)?;
+            }
+            ast::Comando::Para(inicio, condicao, incremento, corpo) => {
+                self.gerar_laco(
+                    inicio.as_deref(),
+                    condicao.as_ref(),
+                    incremento.as_deref(),
+                    corpo,
+                )?;
+            }
+            ast::Comando::Bloco(comandos) => {
+                self.escopos.push(HashMap::new());
+                self.gerar_bloco(comandos)?;
+                self.escopos.pop();
+            }
+            ast::Comando::Retorne(expr) => {
+                let retorno = self.retorno.clone();
+                match expr {
+                    Some(expr) => {
+                        let valor = self.expr(expr)?;
+                        self.converter(&valor.tipo, &retorno);
+                    }
+                    None => self.padrao(&retorno, false),
+                }
+                self.emitir("return");
+            }
+            ast::Comando::Expressao(expr) => {
+                let valor = self.expr(expr)?;
+                self.descartar(&valor.tipo);
+            }
+            ast::Comando::ChamarMetodo(alvo, metodo, args) => {
+                let valor = self.chamada_metodo(alvo, metodo, args)?;
+                self.descartar(&valor.tipo);
+            }
+            ast::Comando::AcessarCampo(variavel, campo) => {
+                let valor = self.expr(&ast::Expressao::AcessoMembro(
+                    Box::new(ast::Expressao::Identificador(variavel.clone())),
+                    campo.clone(),
+                ))?;
+                self.descartar(&valor.tipo);
+            }
+        }
+        Ok(())
+    }
+
+    /// Variável de `var x = ...`, com o valor já na pilha.
+    fn declarar_var(&mut self, nome: &str, valor: Valor) {
+        let tipo = match valor.tipo {
+            TipoW::Nulo | TipoW::Vazio => TipoW::Objeto,
+            ref tipo => tipo.clone(),
+        };
+        self.converter(&valor.tipo, &tipo);
+        let w = self.declarar_local(nome, tipo);
+        self.emitir(format!("local.set {}", w));
+    }
+
+    /// Remove da pilha um valor avaliado só pelos efeitos.
+    fn descartar(&mut self, tipo: &TipoW) {
+        if *tipo != TipoW::Vazio {
+            self.emitir("drop");
+        }
+    }
+
+    /// Condição de `se`/`enquanto`, deixada na pilha.
+    fn condicao(&mut self, expr: &ast::Expressao) -> Result<(), String> {
+        let valor = self.expr(expr)?;
+        if valor.tipo != TipoW::Booleano {
+            return Err(format!(
+                "Condição deve ser booleana, obtido {}",
+                self.nome_tipo(&valor.tipo)
+            ));
+        }
+        Ok(())
+    }
+
+    fn gerar_laco(
+        &mut self,
+        inicio: Option<&ast::Comando>,
+        condicao: Option<&ast::Expressao>,
+        incremento: Option<&ast::Comando>,
+        corpo: &ast::Comando,
+    ) -> Result<(), String> {
+        self.escopos.push(HashMap::new());
+        if let Some(inicio) = inicio {
+            self.gerar_comando(inicio)?;
+        }
+        let rotulo = self.rotulo();
+        self.emitir(format!("block $fim{}", rotulo));
+        self.emitir(format!("loop $laco{}", rotulo));
+        if let Some(condicao) = condicao {
+            self.condicao(condicao)?;
+            self.emitir("i32.eqz");
+            self.emitir(format!("br_if $fim{}", rotulo));
+        }
+        self.gerar_corpo(corpo)?;
+        if let Some(incremento) = incremento {
+            self.gerar_comando(incremento)?;
+        }
+        self.emitir(format!("br $laco{}", rotulo));
+        self.emitir("end");
+        self.emitir("end");
+        self.escopos.pop();
+        Ok(())
+    }
+
+    fn atribuir_variavel(&mut self, nome: &str, expr: &ast::Expressao) -> Result<(), String> {
+        let (acesso, tipo) = self.acesso(nome)?;
+        if let Acesso::Campo(_) = acesso {
+            self.emitir("local.get $este");
+        }
+        let valor = self.expr(expr)?;
+        self.converter(&valor.tipo, &tipo);
+        self.emitir(match acesso {
+            Acesso::Local(w) => format!("local.set {}", w),
+            Acesso::Campo(deslocamento) => format!("{} offset={}", tipo.guardar(), deslocamento),
+            Acesso::Global(global) => format!("global.set {}", global),
+        });
+        Ok(())
+    }
+
+    fn atribuir_membro(
+        &mut self,
+        alvo: &ast::Expressao,
+        membro: &str,
+        expr: &ast::Expressao,
+    ) -> Result<(), String> {
+        if let Some(estatico) = self.alvo_estatico(alvo) {
+            let AlvoEstatico::Classe(classe) = estatico else {
+                return Err(format!("Não é possível atribuir a '{}'", membro));
+            };
+            let (dono, tipo) = self.dono_estatico(&classe, membro).ok_or_else(|| {
+                format!(
+                    "Membro estático '{}' não encontrado em '{}'",
+                    membro, classe
+                )
+            })?;
+            let valor = self.expr(expr)?;
+            self.converter(&valor.tipo, &tipo);
+            self.emitir(format!("global.set {}", self.estatico_w(&dono, membro)));
+            return Ok(());
+        }
+        let objeto = self.expr(alvo)?;
+        let TipoW::Classe(classe) = objeto.tipo.clone() else {
+            return Err(format!(
+                "Não é possível atribuir '{}' em um valor do tipo {}",
+                membro,
+                self.nome_tipo(&objeto.tipo)
+            ));
+        };
+        // O interpretador avalia o valor antes de verificar a referência
+        let verificar = self.verificacoes && !objeto.nao_nulo;
+        let referencia = self.temporario("i32");
+        if verificar {
+            self.emitir(format!("local.tee {}", referencia));
+        }
+        let setter = self
+            .tabelas
+            .buscar_propriedade(&classe, membro)
+            .filter(|(_, p)| p.definir.as_ref().is_some_and(|a| a.corpo.is_some()));
+        let (tipo, destino) = match setter {
+            Some((dono, propriedade)) => {
+                let namespace = self.tabelas.classes[&dono].namespace.clone();
+                (
+                    self.tipo_w(&propriedade.tipo, &namespace),
+                    format!(
+                        "call {}",
+                        self.especial_w(&dono, &format!("definir.{}", identificador(membro)))
+                    ),
+                )
+            }
+            None => {
+                let (deslocamento, tipo, _) =
+                    self.buscar_campo(&classe, membro).ok_or_else(|| {
+                        format!("Membro '{}' não encontrado na classe '{}'", membro, classe)
+                    })?;
+                let guardar = format!("{} offset={}", tipo.guardar(), deslocamento);
+                (tipo, guardar)
+            }
+        };
+        let valor = self.expr(expr)?;
+        self.converter(&valor.tipo, &tipo);
+        if verificar {
+            self.emitir(format!("local.get {}", referencia));
+            self.verificar_nulo(&format!("Atribuição a '{}' em referência nula", membro));
+        }
+        self.emitir(destino);
+        Ok(())
+    }
+
+    fn atribuir_indice(
+        &mut self,
+        alvo: &ast::Expressao,
+        indice: &ast::Expressao,
+        expr: &ast::Expressao,
+    ) -> Result<(), String> {
+        let array = self.expr(alvo)?;
+        let TipoW::Array(item) = array.tipo.clone() else {
+            return Err("Atribuição por índice requer array e inteiro".to_string());
+        };
+        if !self.verificacoes {
+            let posicao = self.expr(indice)?;
+            self.converter(&posicao.tipo, &TipoW::Inteiro);
+            self.emitir(format!("i32.const {}", item.tamanho()));
+            self.emitir("call $pds_endereco_direto");
+            let valor = self.expr(expr)?;
+            self.converter(&valor.tipo, &item);
+            self.emitir(format!("{} offset=16", item.guardar()));
+            return Ok(());
+        }
+        // Índice verificado depois de avaliar o valor, como no interpretador
+        let temp_array = self.temporario("i32");
+        let temp_indice = self.temporario("i64");
+        let temp_valor = self.temporario(item.valtype().unwrap_or("i32"));
+        self.emitir(format!("local.set {}", temp_array));
+        let posicao = self.expr(indice)?;
+        self.converter(&posicao.tipo, &TipoW::Inteiro);
+        self.emitir(format!("local.set {}", temp_indice));
+        let valor = self.expr(expr)?;
+        self.converter(&valor.tipo, &item);
+        self.emitir(format!("local.set {}", temp_valor));
+        self.emitir(format!("local.get {}", temp_array));
+        self.emitir(format!("local.get {}", temp_indice));
+        self.emitir(format!("i32.const {}", item.tamanho()));
+        self.posicao_w();
+        self.emitir("call $pds_endereco");
+        self.emitir(format!("local.get {}", temp_valor));
+        self.emitir(format!("{} offset=16", item.guardar()));
+        Ok(())
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* EXPRESSÕES                                                          */
+    /* ------------------------------------------------------------------ */
+
+    /// Posição atual como argumentos `arquivo, linha, coluna` das funções de verificação.
+    fn posicao_w(&mut self) {
+        let (arquivo, linha, coluna) = match self.posicao.clone() {
+            Some(p) => (self.texto(&p.arquivo), p.linha, p.coluna),
+            None => (0, 0, 0),
+        };
+        self.emitir(format!("i32.const {}", arquivo));
+        self.emitir(format!("i32.const {}", linha));
+        self.emitir(format!("i32.const {}", coluna));
+    }
+
+    /// Verifica a referência no topo da pilha e a consome.
+    fn verificar_nulo(&mut self, mensagem: &str) {
+        let mensagem = self.texto(mensagem);
+        self.emitir(format!("i32.const {}", mensagem));
+        self.posicao_w();
+        self.emitir("call $pds_verificar_nulo");
+    }
+
+    /// Verifica a referência no topo da pilha e a mantém.
+    fn nao_nulo(&mut self, mensagem: &str) {
+        let mensagem = self.texto(mensagem);
+        self.emitir(format!("i32.const {}", mensagem));
+        self.posicao_w();
+        self.emitir("call $pds_nao_nulo");
+    }
+
+    /// Valor de variáveis sem inicializador (`vazio_texto`: `""`, como `ValorRuntime::padrao`)
+    /// ou de retornos e parâmetros omitidos (texto nulo).
+    fn padrao(&mut self, tipo: &TipoW, vazio_texto: bool) {
+        match tipo {
+            TipoW::Vazio => {}
+            TipoW::Texto if vazio_texto => {
+                let vazio = self.texto("");
+                self.emitir(format!("i32.const {}", vazio));
+            }
+            tipo => {
+                let valtype = tipo.valtype().unwrap_or("i32");
+                self.emitir(format!("{}.const 0", valtype));
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &ast::Expressao) -> Result<Valor, String> {
+        match expr {
+            ast::Expressao::Inteiro(n) => {
+                self.emitir(format!("i64.const {}", n));
+                Ok(Valor::novo(TipoW::Inteiro))
+            }
+            ast::Expressao::Texto(s) => {
+                let endereco = self.texto(s);
+                self.emitir(format!("i32.const {}", endereco));
+                Ok(Valor::nao_nulo(TipoW::Texto))
+            }
+            ast::Expressao::Booleano(b) => {
+                self.emitir(format!("i32.const {}", *b as i32));
+                Ok(Valor::novo(TipoW::Booleano))
+            }
+            ast::Expressao::Decimal(lit) | ast::Expressao::DuploLiteral(lit) => {
+                self.emitir(format!("f64.const {}", literal_real(lit)));
+                Ok(Valor::novo(TipoW::Duplo))
+            }
+            ast::Expressao::FlutuanteLiteral(lit) => {
+                self.emitir(format!("f32.const {}", literal_real(lit)));
+                Ok(Valor::novo(TipoW::Flutuante))
+            }
+            ast::Expressao::Nulo => {
+                self.emitir("i32.const 0");
+                Ok(Valor::novo(TipoW::Nulo))
+            }
+            ast::Expressao::Este => {
+                let classe = self
+                    .classe_atual
+                    .clone()
+                    .filter(|_| self.tem_este)
+                    .ok_or_else(|| "'este' usado fora de um método de instância".to_string())?;
+                self.emitir("local.get $este");
+                Ok(Valor::nao_nulo(TipoW::Classe(classe)))
+            }
+            ast::Expressao::Identificador(nome) => self.ler_variavel(nome),
+            ast::Expressao::ListaLiteral(itens) => self.lista(itens),
+            ast::Expressao::NovoArray(tipo, tamanho) => {
+                let item = self.tipo_w(tipo, &self.namespace.clone());
+                let valor = self.expr(tamanho)?;
+                self.converter(&valor.tipo, &TipoW::Inteiro);
+                self.emitir(format!("i32.const {}", Self::elemento(&item)));
+                self.posicao_w();
+                self.emitir("call $pds_array_novo");
+                Ok(Valor::nao_nulo(TipoW::Array(Box::new(item))))
+            }
+            ast::Expressao::NovoObjeto(tipo, args) => self.novo_objeto(tipo, args),
+            ast::Expressao::Aritmetica(op, esq, dir) => {
+                let esq = self.expr(esq)?;
+                let (codigo, dir) = self.capturar(|g| g.expr(dir))?;
+                self.aritmetica(op, esq, codigo, dir)
+            }
+            ast::Expressao::Comparacao(op, esq, dir) => {
+                let esq = self.expr(esq)?;
+                let (codigo, dir) = self.capturar(|g| g.expr(dir))?;
+                self.comparacao(op, esq, codigo, dir)
+            }
+            ast::Expressao::Logica(op, esq, dir) => self.logica(op, esq, dir),
+            ast::Expressao::Unario(op, operando) => {
+                let valor = self.expr(operando)?;
+                match (op, &valor.tipo) {
+                    (ast::OperadorUnario::NegacaoLogica, TipoW::Booleano) => {
+                        self.emitir("i32.eqz");
+                    }
+                    (ast::OperadorUnario::NegacaoNumerica, TipoW::Inteiro) => {
+                        self.emitir("i64.const -1");
+                        self.emitir("i64.mul");
+                    }
+                    (ast::OperadorUnario::NegacaoNumerica, TipoW::Flutuante) => {
+                        self.emitir("f32.neg");
+                    }
+                    (ast::OperadorUnario::NegacaoNumerica, TipoW::Duplo) => {
+                        self.emitir("f64.neg");
+                    }
+                    (_, tipo) => {
+                        return Err(format!(
+                            "Operador unário incompatível com {}",
+                            self.nome_tipo(tipo)
+                        ))
+                    }
+                }
+                Ok(Valor::novo(valor.tipo))
+            }
+            ast::Expressao::StringInterpolada(partes) => {
+                if partes.is_empty() {
+                    let vazio = self.texto("");
+                    self.emitir(format!("i32.const {}", vazio));
+                }
+                for (i, parte) in partes.iter().enumerate() {
+                    match parte {
+                        ast::PartStringInterpolada::Texto(s) => {
+                            let endereco = self.texto(s);
+                            self.emitir(format!("i32.const {}", endereco));
+                        }
+                        ast::PartStringInterpolada::Expressao(e) => {
+                            let valor = self.expr(e)?;
+                            self.texto_de(&valor);
+                        }
+                    }
+                    if i > 0 {
+                        self.emitir("call $pds_concatenar");
+                    }
+                }
+                Ok(Valor::nao_nulo(TipoW::Texto))
+            }
+            ast::Expressao::AcessoMembro(alvo, membro) => self.acesso_membro(alvo, membro),
+            ast::Expressao::AcessoIndice(alvo, indice) => {
+                let array = self.expr(alvo)?;
+                let TipoW::Array(item) = array.tipo else {
+                    return Err(match array.tipo {
+                        TipoW::Texto => {
+                            "Acesso por índice em texto não é suportado no alvo wasm".to_string()
+                        }
+                        _ => "Acesso por índice requer array ou texto e um inteiro".to_string(),
+                    });
+                };
+                let posicao = self.expr(indice)?;
+                self.converter(&posicao.tipo, &TipoW::Inteiro);
+                self.emitir(format!("i32.const {}", item.tamanho()));
+                if self.verificacoes {
+                    self.posicao_w();
+                    self.emitir("call $pds_endereco");
+                } else {
+                    self.emitir("call $pds_endereco_direto");
+                }
+                self.emitir(format!("{} offset=16", item.carregar()));
+                Ok(Valor::novo(*item))
+            }
+            ast::Expressao::ChamadaMetodo(alvo, metodo, args) => {
+                self.chamada_metodo(alvo, metodo, args)
+            }
+            ast::Expressao::Chamada(nome, args) => self.chamada(nome, args),
+            // Não há tarefas neste alvo: a função assíncrona executa na hora
+            ast::Expressao::Aguarde(expr) => self.expr(expr),
+        }
+    }
+
+    fn lista(&mut self, itens: &[ast::Expressao]) -> Result<Valor, String> {
+        let mut valores = Vec::new();
+        for item in itens {
+            valores.push(self.capturar(|g| g.expr(item))?);
+        }
+        let tipos: Vec<TipoW> = valores.iter().map(|(_, v)| v.tipo.clone()).collect();
+        let item = self.tipo_itens(&tipos);
+        let array = self.temporario("i32");
+        self.emitir(format!("i64.const {}", valores.len()));
+        self.emitir(format!("i32.const {}", Self::elemento(&item)));
+        self.posicao_w();
+        self.emitir("call $pds_array_novo");
+        self.emitir(format!("local.set {}", array));
+        for (i, (codigo, valor)) in valores.into_iter().enumerate() {
+            self.emitir(format!("local.get {}", array));
+            self.codigo.extend(codigo);
+            self.converter(&valor.tipo, &item);
+            self.emitir(format!(
+                "{} offset={}",
+                item.guardar(),
+                16 + i as u32 * item.tamanho()
+            ));
+        }
+        self.emitir(format!("local.get {}", array));
+        Ok(Valor::nao_nulo(TipoW::Array(Box::new(item))))
+    }
+
+    fn ler_variavel(&mut self, nome: &str) -> Result<Valor, String> {
+        let (acesso, tipo) = self.acesso(nome)?;
+        match acesso {
+            Acesso::Local(w) => self.emitir(format!("local.get {}", w)),
+            Acesso::Campo(deslocamento) => {
+                self.emitir("local.get $este");
+                self.emitir(format!("{} offset={}", tipo.carregar(), deslocamento));
+            }
+            Acesso::Global(global) => self.emitir(format!("global.get {}", global)),
+        }
+        Ok(Valor::novo(tipo))
+    }
+
+    fn acesso(&self, nome: &str) -> Result<(Acesso, TipoW), String> {
+        match self.variavel(nome) {
+            Some(Variavel::Local((w, tipo))) => Ok((Acesso::Local(w), tipo)),
+            Some(Variavel::Campo(membro)) => {
+                let (deslocamento, tipo, _) = self.campo(nome, &membro);
+                Ok((Acesso::Campo(deslocamento), tipo))
+            }
+            Some(Variavel::Estatico(membro)) => Ok((
+                Acesso::Global(self.estatico_w(&membro.classe.fqn, nome)),
+                self.tipo_w(membro.tipo, &membro.classe.namespace),
+            )),
+            None => Err(format!("Variável '{}' não definida", nome)),
+        }
+    }
+
+    fn acesso_membro(&mut self, alvo: &ast::Expressao, membro: &str) -> Result<Valor, String> {
+        if let Some(estatico) = self.alvo_estatico(alvo) {
+            return match estatico {
+                AlvoEstatico::Classe(classe) => {
+                    let (dono, tipo) = self.dono_estatico(&classe, membro).ok_or_else(|| {
+                        format!(
+                            "Membro estático '{}' não encontrado em '{}'",
+                            membro, classe
+                        )
+                    })?;
+                    self.emitir(format!("global.get {}", self.estatico_w(&dono, membro)));
+                    Ok(Valor::novo(tipo))
+                }
+                AlvoEstatico::Enum(en) => {
+                    let posicao = self.tabelas.enums[&en]
+                        .valores
+                        .iter()
+                        .position(|v| v == membro)
+                        .ok_or_else(|| format!("Valor '{}' não existe na enumeração", membro))?;
+                    self.emitir(format!("i64.const {}", posicao));
+                    Ok(Valor::novo(TipoW::Inteiro))
+                }
+                AlvoEstatico::Nativo(nome) => Err(format!("Classe '{}' não encontrada", nome)),
+            };
+        }
+        let objeto = self.expr(alvo)?;
+        let mensagem = format!("Acesso a '{}' em referência nula", membro);
+        match objeto.tipo.clone() {
+            TipoW::Texto if membro == "tamanho" => {
+                self.emitir("call $pds_texto_tamanho");
+                Ok(Valor::novo(TipoW::Inteiro))
+            }
+            TipoW::Array(_) if membro == "tamanho" => {
+                if self.verificacoes && !objeto.nao_nulo {
+                    self.nao_nulo(&mensagem);
+                }
+                self.emitir("i32.load offset=4");
+                self.emitir("i64.extend_i32_u");
+                Ok(Valor::novo(TipoW::Inteiro))
+            }
+            TipoW::Classe(classe) => {
+                if self.verificacoes && !objeto.nao_nulo {
+                    self.nao_nulo(&mensagem);
+                }
+                if let Some((dono, propriedade)) = self.tabelas.buscar_propriedade(&classe, membro)
+                {
+                    if propriedade
+                        .obter
+                        .as_ref()
+                        .is_some_and(|a| a.corpo.is_some())
+                    {
+                        let namespace = self.tabelas.classes[&dono].namespace.clone();
+                        let tipo = self.tipo_w(&propriedade.tipo, &namespace);
+                        self.emitir(format!(
+                            "call {}",
+                            self.especial_w(&dono, &format!("obter.{}", identificador(membro)))
+                        ));
+                        return Ok(Valor::novo(tipo));
+                    }
+                }
+                let (deslocamento, tipo, _) =
+                    self.buscar_campo(&classe, membro).ok_or_else(|| {
+                        format!("Membro '{}' não encontrado na classe '{}'", membro, classe)
+                    })?;
+                self.emitir(format!("{} offset={}", tipo.carregar(), deslocamento));
+                Ok(Valor::novo(tipo))
+            }
+            tipo => Err(format!(
+                "Membro '{}' de valores do tipo {} não é suportado no alvo wasm",
+                membro,
+                self.nome_tipo(&tipo)
+            )),
+        }
+    }
+
+    fn novo_objeto(&mut self, tipo: &ast::Tipo, args: &[ast::Expressao]) -> Result<Valor, String> {
+        let nome = nome_do_tipo(tipo)
+            .ok_or_else(|| format!("Não é possível instanciar o tipo '{}'", tipo))?;
+        let Some(classe) = self.tabelas.resolver_classe(&self.namespace.clone(), nome) else {
+            // Classe que só existe na biblioteca padrão: erro em tempo de execução, como no interpretador
+            self.panico(&format!("Classe '{}' não encontrada", nome));
+            return Ok(Valor::novo(TipoW::Objeto));
+        };
+        let decl = self.tabelas.classes[&classe].decl;
+        if decl.eh_abstrata || decl.eh_estatica {
+            let tipo_classe = if decl.eh_abstrata {
+                "abstrata"
+            } else {
+                "estática"
+            };
+            self.panico(&format!(
+                "Não é possível instanciar a classe {} '{}'",
+                tipo_classe, classe
+            ));
+            return Ok(Valor::novo(TipoW::Classe(classe)));
+        }
+        let indice = match self.tabelas.escolher_construtor(&classe, args.len()) {
+            Some(indice) => {
+                let parametros = decl.construtores[indice].parametros.clone();
+                let namespace = self.tabelas.classes[&classe].namespace.clone();
+                self.argumentos(&parametros, &namespace, args)?;
+                indice
+            }
+            None => {
+                // Sem construtor: os argumentos são avaliados e descartados
+                for arg in args {
+                    let valor = self.expr(arg)?;
+                    self.descartar(&valor.tipo);
+                }
+                0
+            }
+        };
+        self.emitir(format!(
+            "call {}",
+            self.construtor_w(&classe, "novo", indice)
+        ));
+        Ok(Valor::nao_nulo(TipoW::Classe(classe)))
+    }
+
+    /// Erro em tempo de execução no lugar de um valor.
+    fn panico(&mut self, mensagem: &str) {
+        let mensagem = self.texto(mensagem);
+        self.emitir(format!("i32.const {}", mensagem));
+        self.posicao_w();
+        self.emitir("call $pds_panico");
+        self.emitir("unreachable");
+    }
+
+    /// Argumentos de uma chamada, convertidos para os tipos dos parâmetros; os omitidos
+    /// recebem o valor padrão declarado.
+    fn argumentos(
+        &mut self,
+        parametros: &[ast::Parametro],
+        namespace: &str,
+        args: &[ast::Expressao],
+    ) -> Result<(), String> {
+        for (i, parametro) in parametros.iter().enumerate() {
+            let tipo = self.tipo_w(&parametro.tipo, namespace);
+            match args.get(i).or(parametro.valor_padrao.as_ref()) {
+                Some(expr) => {
+                    let valor = self.expr(expr)?;
+                    self.converter(&valor.tipo, &tipo);
+                }
+                // Parâmetro sem argumento nem valor padrão: nulo no interpretador
+                None => self.padrao(&tipo, false),
+            }
+        }
+        for arg in args.iter().skip(parametros.len()) {
+            let valor = self.expr(arg)?;
+            self.descartar(&valor.tipo);
+        }
+        Ok(())
+    }
+
+    /// Chamada sem alvo: método da classe atual, função do programa ou intrínseca de console.
+    fn chamada(&mut self, nome: &str, args: &[ast::Expressao]) -> Result<Valor, String> {
+        if let Some(classe) = self.classe_atual.clone() {
+            if let Some((dono, metodo)) = self.tabelas.buscar_metodo(&classe, nome, args.len()) {
+                if self.tem_este && !metodo.eh_estatica {
+                    self.emitir("local.get $este");
+                    let este = Valor::nao_nulo(TipoW::Classe(classe));
+                    return self.chamar_metodo_classe(este, &dono, metodo, args);
+                }
+                return self.chamar_estatico(&dono, metodo, args);
+            }
+        }
+        if let Some(fqn) = self.tabelas.resolver_funcao(&self.namespace, nome) {
+            return self.chamar_funcao(&fqn, args);
+        }
+        match nome {
+            "EscreverLinha" | "Escrever" | "LerLinha" => {
+                self.nativo(&format!("Console::{}", nome), args)
+            }
+            _ => self.nativo(nome, args),
+        }
+    }
+
+    fn chamar_funcao(&mut self, fqn: &str, args: &[ast::Expressao]) -> Result<Valor, String> {
+        let funcao = &self.tabelas.funcoes[fqn];
+        let decl = funcao.decl;
+        let namespace = funcao.namespace.clone();
+        let w = funcao.simbolo.clone();
+        if let Some(chave) = chave_nativa(&decl.attributes) {
+            return self.nativo(chave, args);
+        }
+        if decl.attributes.iter().any(|a| a.name == "Externo") {
+            return Err(format!(
+                "Função externa '{}' não é suportada no alvo wasm",
+                decl.nome
+            ));
+        }
+        let retorno = self.tipo_retorno(&decl.tipo_retorno, &namespace);
+        self.argumentos(&decl.parametros, &namespace, args)?;
+        self.emitir(format!("call ${}", w));
+        Ok(Valor::novo(retorno))
+    }
+
+    fn chamar_estatico(
+        &mut self,
+        classe: &str,
+        metodo: &ast::MetodoClasse,
+        args: &[ast::Expressao],
+    ) -> Result<Valor, String> {
+        if let Some(chave) = chave_nativa(&metodo.attributes) {
+            return self.nativo(chave, args);
+        }
+        if !metodo.eh_estatica {
+            return Err(format!(
+                "Método de instância '{}' chamado sem objeto em '{}'",
+                metodo.nome, classe
+            ));
+        }
+        let namespace = self.tabelas.classes[classe].namespace.clone();
+        let (retorno, _) = self.assinatura(classe, metodo);
+        self.argumentos(&metodo.parametros, &namespace, args)?;
+        self.emitir(format!("call {}", self.metodo_w(classe, &metodo.nome)));
+        Ok(Valor::novo(retorno))
+    }
+
+    fn chamada_metodo(
+        &mut self,
+        alvo: &ast::Expressao,
+        metodo: &str,
+        args: &[ast::Expressao],
+    ) -> Result<Valor, String> {
+        if let Some(estatico) = self.alvo_estatico(alvo) {
+            return match estatico {
+                AlvoEstatico::Classe(classe) => {
+                    let (dono, decl) = self
+                        .tabelas
+                        .buscar_metodo(&classe, metodo, args.len())
+                        .ok_or_else(|| {
+                            format!("Método '{}' não encontrado na classe '{}'", metodo, classe)
+                        })?;
+                    self.chamar_estatico(&dono, decl, args)
+                }
+                AlvoEstatico::Nativo(classe) => {
+                    self.nativo(&format!("{}::{}", classe, metodo), args)
+                }
+                AlvoEstatico::Enum(_) => Err(format!("Enumerações não têm o método '{}'", metodo)),
+            };
+        }
+        let objeto = self.expr(alvo)?;
+        match objeto.tipo.clone() {
+            TipoW::Texto => self.metodo_texto(metodo, args),
+            TipoW::Classe(classe) => {
+                let (dono, decl) = self
+                    .tabelas
+                    .buscar_metodo(&classe, metodo, args.len())
+                    .ok_or_else(|| {
+                        format!("Método '{}' não encontrado na classe '{}'", metodo, classe)
+                    })?;
+                if decl.eh_estatica {
+                    self.descartar(&objeto.tipo);
+                    return self.chamar_estatico(&dono, decl, args);
+                }
+                self.chamar_metodo_classe(objeto, &dono, decl, args)
+            }
+            TipoW::Interface(_) | TipoW::Objeto => self.chamar_despacho(objeto, metodo, args),
+            tipo => Err(format!(
+                "Método '{}' não existe para valores do tipo {}",
+                metodo,
+                self.nome_tipo(&tipo)
+            )),
+        }
+    }
+
+    /// Chamada de método de instância com o receptor na pilha: direta, ou pela vtable
+    /// (`call_indirect`) quando o método tem entrada nela.
+    fn chamar_metodo_classe(
+        &mut self,
+        objeto: Valor,
+        dono: &str,
+        metodo: &ast::MetodoClasse,
+        args: &[ast::Expressao],
+    ) -> Result<Valor, String> {
+        if let Some(chave) = chave_nativa(&metodo.attributes) {
+            return Err(format!(
+                "Método nativo '{}' ({}) não é suportado no alvo wasm",
+                metodo.nome, chave
+            ));
+        }
+        let namespace = self.tabelas.classes[dono].namespace.clone();
+        let (retorno, parametros) = self.assinatura(dono, metodo);
+        let entrada = self.entrada_vtable(dono, &metodo.nome);
+        let verificar = self.verificacoes && !objeto.nao_nulo;
+        let receptor = self.temporario("i32");
+        if verificar || entrada.is_some() {
+            self.emitir(format!("local.tee {}", receptor));
+        }
+        self.argumentos(&metodo.parametros, &namespace, args)?;
+        // O interpretador avalia os argumentos antes de verificar a referência
+        if verificar {
+            self.emitir(format!("local.get {}", receptor));
+            self.verificar_nulo(&format!("Chamada de '{}' em referência nula", metodo.nome));
+        }
+        match entrada {
+            Some(indice) => {
+                let mut tipos = vec!["i32"];
+                tipos.extend(parametros.iter().filter_map(TipoW::valtype));
+                let tipo = self.tipo_funcao(&tipos, retorno.valtype());
+                self.emitir(format!("local.get {}", receptor));
+                self.emitir("i32.load");
+                self.emitir(format!("i32.load offset={}", 12 + 4 * indice));
+                self.emitir(format!("call_indirect $pds_funcoes (type {})", tipo));
+            }
+            None => {
+                self.emitir(format!("call {}", self.metodo_w(dono, &metodo.nome)));
+            }
+        }
+        Ok(Valor::novo(retorno))
+    }
+
+    /// Chamada em interface ou `objeto`: função de despacho pelo nome do método.
+    fn chamar_despacho(
+        &mut self,
+        objeto: Valor,
+        metodo: &str,
+        args: &[ast::Expressao],
+    ) -> Result<Valor, String> {
+        let chave = (metodo.to_string(), args.len());
+        if !self.despachos.contains_key(&chave) {
+            let despacho = self.assinatura_despacho_para(&objeto.tipo, metodo, args.len())?;
+            self.despachos.insert(chave.clone(), despacho);
+        }
+        let (parametros, retorno) = {
+            let despacho = &self.despachos[&chave];
+            (despacho.parametros.clone(), despacho.retorno.clone())
+        };
+        let verificar = self.verificacoes && !objeto.nao_nulo;
+        let receptor = self.temporario("i32");
+        if verificar {
+            self.emitir(format!("local.tee {}", receptor));
+        }
+        for (arg, tipo) in args.iter().zip(&parametros) {
+            let valor = self.expr(arg)?;
+            self.converter(&valor.tipo, tipo);
+        }
+        if verificar {
+            self.emitir(format!("local.get {}", receptor));
+            self.verificar_nulo(&format!("Chamada de '{}' em referência nula", metodo));
+        }
+        self.emitir(format!("call {}", Self::nome_despacho(metodo, args.len())));
+        Ok(Valor::novo(retorno))
+    }
+
+    /// Tipos da função de despacho: a assinatura da interface, ou do primeiro método encontrado.
+    fn assinatura_despacho_para(
+        &self,
+        tipo: &TipoW,
+        metodo: &str,
+        n: usize,
+    ) -> Result<Despacho, String> {
+        if let TipoW::Interface(interface) = tipo {
+            let interface = &self.tabelas.interfaces[interface];
+            let namespace = interface.namespace.as_str();
+            if let Some(assinatura) = interface
+                .decl
+                .metodos
+                .iter()
+                .find(|m| m.nome == metodo && m.parametros.len() == n)
+            {
+                return Ok(Despacho {
+                    nome: metodo.to_string(),
+                    parametros: assinatura
+                        .parametros
+                        .iter()
+                        .map(|p| self.tipo_w(&p.tipo, namespace))
+                        .collect(),
+                    retorno: self.tipo_retorno(&assinatura.tipo_retorno, namespace),
+                });
+            }
+        }
+        for fqn in &self.tabelas.ordem_classes {
+            if let Some((dono, decl)) = self.tabelas.buscar_metodo(fqn, metodo, n) {
+                if decl.eh_estatica {
+                    continue;
+                }
+                let (retorno, parametros) = self.assinatura(&dono, decl);
+                let mut parametros: Vec<TipoW> = parametros.into_iter().take(n).collect();
+                parametros.resize(n, TipoW::Objeto);
+                return Ok(Despacho {
+                    nome: metodo.to_string(),
+                    parametros,
+                    retorno,
+                });
+            }
+        }
+        // Nenhuma classe tem o método: a chamada termina o programa com erro
+        Ok(Despacho {
+            nome: metodo.to_string(),
+            parametros: vec![TipoW::Objeto; n],
+            retorno: TipoW::Objeto,
+        })
+    }
+
+    /// Métodos embutidos de `texto` (os mesmos do interpretador), com o texto na pilha.
+    fn metodo_texto(&mut self, metodo: &str, args: &[ast::Expressao]) -> Result<Valor, String> {
+        let (funcao, n_args, tipo) = match metodo {
+            "comprimento" => ("$pds_texto_tamanho", 0, TipoW::Inteiro),
+            "ParaMaiusculo" => ("$pds_texto_maiusculo", 0, TipoW::Texto),
+            "ParaMinusculo" => ("$pds_texto_minusculo", 0, TipoW::Texto),
+            "Aparar" => ("$pds_texto_aparar", 0, TipoW::Texto),
+            "Contem" => ("$pds_texto_contem", 1, TipoW::Booleano),
+            "Substituir" => ("$pds_texto_substituir", 2, TipoW::Texto),
+            _ => return Err(erro_metodo_texto(metodo)),
+        };
+        // Como no alvo C: só os dois primeiros argumentos são avaliados
+        for arg in args.iter().take(2) {
+            let valor = self.expr(arg)?;
+            self.texto_de(&valor);
+        }
+        let avaliados = args.len().min(2);
+        for _ in n_args..avaliados {
+            self.emitir("drop");
+        }
+        for _ in avaliados..n_args {
+            let vazio = self.texto("");
+            self.emitir(format!("i32.const {}", vazio));
+        }
+        self.emitir(format!("call {}", funcao));
+        Ok(Valor::novo(tipo))
+    }
+
+    /// Funções nativas do interpretador (`runtime::nativos`) disponíveis no alvo wasm.
+    fn nativo(&mut self, chave: &str, args: &[ast::Expressao]) -> Result<Valor, String> {
+        match chave {
+            "Console::EscreverLinha" | "Console::Escrever" => {
+                if args.is_empty() {
+                    let vazio = self.texto("");
+                    self.emitir(format!("i32.const {}", vazio));
+                }
+                for (i, arg) in args.iter().enumerate() {
+                    let valor = self.expr(arg)?;
+                    self.texto_de(&valor);
+                    if i > 0 {
+                        self.emitir("call $pds_concatenar");
+                    }
+                }
+                if chave == "Console::EscreverLinha" {
+                    self.emitir("call $pds_imprimir");
+                } else {
+                    self.emitir("call $pds_escrever");
+                }
+                Ok(Valor::novo(TipoW::Vazio))
+            }
+            "Console::LerLinha" => {
+                for arg in args {
+                    let valor = self.expr(arg)?;
+                    self.descartar(&valor.tipo);
+                }
+                self.emitir("call $pds_ler_linha");
+                Ok(Valor::nao_nulo(TipoW::Texto))
+            }
+            _ if chave.contains("::") || chave.ends_with("Assíncrono") => Err(format!(
+                "Função nativa '{}' não é suportada no alvo wasm",
+                chave
+            )),
+            _ => Err(format!("Função '{}' não encontrada", chave)),
+        }
+    }
+
+    fn logica(
+        &mut self,
+        op: &ast::OperadorLogico,
+        esq: &ast::Expressao,
+        dir: &ast::Expressao,
+    ) -> Result<Valor, String> {
+        let esq = self.expr(esq)?;
+        let (codigo, dir) = self.capturar(|g| g.expr(dir))?;
+        for valor in [&esq, &dir] {
+            if valor.tipo != TipoW::Booleano {
+                return Err(format!(
+                    "Condição deve ser booleana, obtido {}",
+                    self.nome_tipo(&valor.tipo)
+                ));
+            }
+        }
+        // O lado direito só é avaliado se o resultado ainda não estiver decidido
+        self.emitir("if (result i32)");
+        match op {
+            ast::OperadorLogico::E => {
+                self.codigo.extend(codigo);
+                self.emitir("else");
+                self.emitir("i32.const 0");
+            }
+            ast::OperadorLogico::Ou => {
+                self.emitir("i32.const 1");
+                self.emitir("else");
+                self.codigo.extend(codigo);
+            }
+        }
+        self.emitir("end");
+        Ok(Valor::novo(TipoW::Booleano))
+    }
+
+    fn aritmetica(
+        &mut self,
+        op: &ast::OperadorAritmetico,
+        esq: Valor,
+        codigo_dir: Vec<String>,
+        dir: Valor,
+    ) -> Result<Valor, String> {
+        // Texto em qualquer lado de `+` concatena
+        if *op == ast::OperadorAritmetico::Soma
+            && (esq.tipo == TipoW::Texto || dir.tipo == TipoW::Texto)
+        {
+            self.texto_de(&esq);
+            self.codigo.extend(codigo_dir);
+            self.texto_de(&dir);
+            self.emitir("call $pds_concatenar");
+            return Ok(Valor::nao_nulo(TipoW::Texto));
+        }
+        if !esq.tipo.numerico() || !dir.tipo.numerico() {
+            return Err(format!(
+                "Tipos incompatíveis para {}: {} e {}",
+                match op {
+                    ast::OperadorAritmetico::Soma => "soma",
+                    ast::OperadorAritmetico::Subtracao => "subtração",
+                    ast::OperadorAritmetico::Multiplicacao => "multiplicação",
+                    ast::OperadorAritmetico::Divisao => "divisão",
+                    ast::OperadorAritmetico::Modulo => "módulo",
+                },
+                self.nome_tipo(&esq.tipo),
+                self.nome_tipo(&dir.tipo)
+            ));
+        }
+        // Inteiro com flutuante resulta em flutuante; qualquer duplo promove para duplo
+        let tipo = match (&esq.tipo, &dir.tipo) {
+            (TipoW::Inteiro, TipoW::Inteiro) => TipoW::Inteiro,
+            (TipoW::Duplo, _) | (_, TipoW::Duplo) => TipoW::Duplo,
+            _ => TipoW::Flutuante,
+        };
+        // O resto de `flutuante` é calculado em `duplo` (exato) e convertido de volta
+        let resto_flutuante = tipo == TipoW::Flutuante && *op == ast::OperadorAritmetico::Modulo;
+        let operandos = if resto_flutuante {
+            TipoW::Duplo
+        } else {
+            tipo.clone()
+        };
+        self.converter(&esq.tipo, &operandos);
+        self.codigo.extend(codigo_dir);
+        self.converter(&dir.tipo, &operandos);
+        let prefixo = operandos.valtype().unwrap();
+        match op {
+            ast::OperadorAritmetico::Soma => self.emitir(format!("{}.add", prefixo)),
+            ast::OperadorAritmetico::Subtracao => self.emitir(format!("{}.sub", prefixo)),
+            ast::OperadorAritmetico::Multiplicacao => self.emitir(format!("{}.mul", prefixo)),
+            ast::OperadorAritmetico::Divisao => {
+                if self.verificacoes {
+                    self.posicao_w();
+                    self.emitir(match operandos {
+                        TipoW::Inteiro => "call $pds_dividir",
+                        TipoW::Flutuante => "call $pds_dividir_flutuante",
+                        _ => "call $pds_dividir_duplo",
+                    });
+                } else if operandos == TipoW::Inteiro {
+                    self.emitir("i64.div_s");
+                } else {
+                    self.emitir(format!("{}.div", prefixo));
+                }
+            }
+            ast::OperadorAritmetico::Modulo => match (&operandos, self.verificacoes) {
+                (TipoW::Inteiro, true) => {
+                    self.posicao_w();
+                    self.emitir("call $pds_resto");
+                }
+                (TipoW::Inteiro, false) => self.emitir("i64.rem_s"),
+                (_, true) => {
+                    self.posicao_w();
+                    self.emitir("call $pds_resto_duplo");
+                }
+                (_, false) => self.emitir("call $pds_host_resto"),
+            },
+        }
+        if resto_flutuante {
+            self.emitir("f32.demote_f64");
+        }
+        Ok(Valor::novo(tipo))
+    }
+
+    fn comparacao(
+        &mut self,
+        op: &ast::OperadorComparacao,
+        esq: Valor,
+        codigo_dir: Vec<String>,
+        dir: Valor,
+    ) -> Result<Valor, String> {
+        use ast::OperadorComparacao as Op;
+        let igualdade = matches!(op, Op::Igual | Op::Diferente);
+        let booleano = Valor::novo(TipoW::Booleano);
+        let (a, b) = (esq.tipo.clone(), dir.tipo.clone());
+        let numero = |tipo: &TipoW| -> String {
+            let prefixo = tipo.valtype().unwrap_or("i32");
+            let sinal = if prefixo.starts_with('i') { "_s" } else { "" };
+            match op {
+                Op::Igual => format!("{}.eq", prefixo),
+                Op::Diferente => format!("{}.ne", prefixo),
+                Op::Menor => format!("{}.lt{}", prefixo, sinal),
+                Op::MaiorQue => format!("{}.gt{}", prefixo, sinal),
+                Op::MenorIgual => format!("{}.le{}", prefixo, sinal),
+                Op::MaiorIgual => format!("{}.ge{}", prefixo, sinal),
+            }
+        };
+        match (&a, &b) {
+            (x, y) if x.numerico() && y.numerico() => {
+                if x == y {
+                    self.codigo.extend(codigo_dir);
+                    self.emitir(numero(x));
+                } else if igualdade {
+                    self.constante(op, &a, codigo_dir, &b);
+                } else {
+                    self.converter(&a, &TipoW::Duplo);
+                    self.codigo.extend(codigo_dir);
+                    self.converter(&b, &TipoW::Duplo);
+                    self.emitir(numero(&TipoW::Duplo));
+                }
+            }
+            (TipoW::Booleano, TipoW::Booleano) if igualdade => {
+                self.codigo.extend(codigo_dir);
+                self.emitir(numero(&TipoW::Booleano));
+            }
+            (TipoW::Texto, TipoW::Texto) => {
+                self.codigo.extend(codigo_dir);
+                if igualdade {
+                    self.emitir("call $pds_textos_iguais");
+                    if *op == Op::Diferente {
+                        self.emitir("i32.eqz");
+                    }
+                } else {
+                    self.emitir("call $pds_comparar_textos");
+                    self.emitir("i32.const 0");
+                    self.emitir(numero(&TipoW::Booleano));
+                }
+            }
+            (TipoW::Texto, TipoW::Nulo) | (TipoW::Nulo, TipoW::Texto) if igualdade => {
+                self.codigo.extend(codigo_dir);
+                self.emitir(numero(&TipoW::Booleano));
+            }
+            (x, y) if igualdade && (x.dinamico() || y.dinamico()) => {
+                self.converter(&a, &TipoW::Objeto);
+                self.codigo.extend(codigo_dir);
+                self.converter(&b, &TipoW::Objeto);
+                self.emitir("call $pds_objetos_iguais");
+                if *op == Op::Diferente {
+                    self.emitir("i32.eqz");
+                }
+            }
+            (x, y) if igualdade && x.referencia() && y.referencia() => {
+                self.codigo.extend(codigo_dir);
+                self.emitir(numero(&TipoW::Booleano));
+            }
+            _ if igualdade => self.constante(op, &a, codigo_dir, &b),
+            (x, y) => {
+                return Err(format!(
+                    "Tipos incompatíveis para comparação: {} e {}",
+                    self.nome_tipo(x),
+                    self.nome_tipo(y)
+                ))
+            }
+        }
+        Ok(booleano)
+    }
+
+    /// Valores de tipos diferentes nunca são iguais (os operandos são avaliados e descartados).
+    fn constante(
+        &mut self,
+        op: &ast::OperadorComparacao,
+        esq: &TipoW,
+        codigo_dir: Vec<String>,
+        dir: &TipoW,
+    ) {
+        self.descartar(esq);
+        self.codigo.extend(codigo_dir);
+        self.descartar(dir);
+        let resultado = *op != ast::OperadorComparacao::Igual;
+        self.emitir(format!("i32.const {}", resultado as i32));
+    }
+
+    /// Converte o valor no topo da pilha para o tipo esperado (promoções numéricas e caixas
+    /// para valores primitivos em variáveis do tipo `objeto`).
+    fn converter(&mut self, de: &TipoW, para: &TipoW) {
+        if de == para {
+            return;
+        }
+        if *para == TipoW::Vazio {
+            self.descartar(de);
+            return;
+        }
+        let instrucao = match (de, para) {
+            (TipoW::Vazio, _) => {
+                // Chamada de função sem retorno usada como valor: nulo
+                self.padrao(para, false);
+                return;
+            }
+            (TipoW::Inteiro, TipoW::Flutuante) => "f32.convert_i64_s",
+            (TipoW::Inteiro, TipoW::Duplo) => "f64.convert_i64_s",
+            (TipoW::Flutuante, TipoW::Duplo) => "f64.promote_f32",
+            (TipoW::Duplo, TipoW::Flutuante) => "f32.demote_f64",
+            (TipoW::Flutuante, TipoW::Inteiro) => "i64.trunc_sat_f32_s",
+            (TipoW::Duplo, TipoW::Inteiro) => "i64.trunc_sat_f64_s",
+            (x, TipoW::Interface(_) | TipoW::Objeto) if !x.referencia() => match x {
+                TipoW::Inteiro => "call $pds_caixa_inteiro",
+                TipoW::Flutuante => "call $pds_caixa_flutuante",
+                TipoW::Duplo => "call $pds_caixa_duplo",
+                TipoW::Booleano => "call $pds_caixa_booleano",
+                _ => "call $pds_caixa_texto",
+            },
+            (TipoW::Interface(_) | TipoW::Objeto, y) if !y.referencia() => match y {
+                TipoW::Inteiro => "call $pds_inteiro_de",
+                TipoW::Flutuante => "call $pds_flutuante_de",
+                TipoW::Duplo => "call $pds_duplo_de",
+                TipoW::Booleano => "call $pds_booleano_de",
+                _ => "call $pds_texto_de",
+            },
+            (x, y) if x.valtype() == y.valtype() => return,
+            // Tipos que o verificador não deixa chegar aqui: o valor padrão do destino
+            _ => {
+                self.descartar(de);
+                self.padrao(para, false);
+                return;
+            }
+        };
+        self.emitir(instrucao);
+    }
+
+    /// Converte o valor no topo da pilha no texto exibido por `imprima`.
+    fn texto_de(&mut self, valor: &Valor) {
+        match &valor.tipo {
+            TipoW::Texto => {
+                if !valor.nao_nulo {
+                    self.emitir("call $pds_texto");
+                }
+            }
+            TipoW::Inteiro => self.emitir("call $pds_texto_inteiro"),
+            TipoW::Duplo => self.emitir("call $pds_texto_duplo"),
+            TipoW::Flutuante => {
+                self.emitir("f64.promote_f32");
+                self.emitir("call $pds_texto_duplo");
+            }
+            TipoW::Booleano => self.emitir("call $pds_texto_booleano"),
+            TipoW::Vazio => {
+                let nulo = self.texto("nulo");
+                self.emitir(format!("i32.const {}", nulo));
+            }
+            _ => self.emitir("call $pds_texto_objeto"),
+        }
+    }
+}
@@ -0,0 +1,670 @@
+//! Compatibilidade entre duas versões de uma biblioteca: `compilador compat antiga.pbl nova.pbl`.
+//!
+//! Compara a API pública descrita nos manifestos e classifica cada mudança. As que quebram
+//! programas compilados contra a versão antiga (membro removido, assinatura ou retorno
+//! alterado, novo membro abstrato, classe base trocada, …) pedem versão major; acréscimos
+//! pedem minor; sem mudanças na API, basta um patch. Antes da 1.0.0 tudo desce um nível,
+//! como no Cargo: `^0.3` não aceita a 0.4.
+
+use crate::library_loader::{
+    Biblioteca, LibAcesso, LibClasse, LibConstrutor, LibEnum, LibFuncao, LibInterface, LibMetodo,
+    LibParametro, LibSimbolo,
+};
+use semver::Version;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Incremento {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl Incremento {
+    /// Menor versão após `atual` que comunica a mudança.
+    pub fn aplicar(self, atual: &Version) -> Version {
+        let efetivo = match (atual.major, atual.minor, self) {
+            (0, 0, _) => Incremento::Patch,
+            (0, _, Incremento::Major) => Incremento::Minor,
+            (0, _, _) => Incremento::Patch,
+            _ => self,
+        };
+        match efetivo {
+            Incremento::Major => Version::new(atual.major + 1, 0, 0),
+            Incremento::Minor => Version::new(atual.major, atual.minor + 1, 0),
+            Incremento::Patch => Version::new(atual.major, atual.minor, atual.patch + 1),
+        }
+    }
+}
+
+impl fmt::Display for Incremento {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Incremento::Patch => "patch",
+            Incremento::Minor => "minor",
+            Incremento::Major => "major",
+        })
+    }
+}
+
+/// Uma diferença na API pública.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mudanca {
+    pub quebra: bool,
+    /// `classe Geo.Forma`, `função Geo.Dobro`, …
+    pub simbolo: String,
+    pub descricao: String,
+}
+
+impl fmt::Display for Mudanca {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.simbolo, self.descricao)
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Relatorio {
+    pub mudancas: Vec<Mudanca>,
+}
+
+impl Relatorio {
+    pub fn quebras(&self) -> impl Iterator<Item = &Mudanca> {
+        self.mudancas.iter().filter(|m| m.quebra)
+    }
+
+    pub fn compativeis(&self) -> impl Iterator<Item = &Mudanca> {
+        self.mudancas.iter().filter(|m| !m.quebra)
+    }
+
+    pub fn incremento(&self) -> Incremento {
+        if self.quebras().next().is_some() {
+            Incremento::Major
+        } else if self.mudancas.is_empty() {
+            Incremento::Patch
+        } else {
+            Incremento::Minor
+        }
+    }
+
+    /// Confere a versão nova contra a antiga. `Err` explica por que ela não basta.
+    pub fn verificar_versao(&self, antiga: &Version, nova: &Version) -> Result<(), String> {
+        let minima = self.incremento().aplicar(antiga);
+        if *nova >= minima {
+            return Ok(());
+        }
+        Err(format!(
+            "a versão {} não basta para mudanças {}: use {} ou maior",
+            nova,
+            self.incremento(),
+            minima
+        ))
+    }
+
+    fn registrar(&mut self, quebra: bool, simbolo: &str, descricao: String) {
+        self.mudancas.push(Mudanca {
+            quebra,
+            simbolo: simbolo.to_string(),
+            descricao,
+        });
+    }
+}
+
+/// Compara as APIs públicas de duas versões da mesma biblioteca.
+pub fn comparar(antiga: &Biblioteca, nova: &Biblioteca) -> Relatorio {
+    let mut relatorio = Relatorio::default();
+    let nomes: BTreeSet<&String> = antiga.simbolos.keys().chain(nova.simbolos.keys()).collect();
+    for fqn in nomes {
+        match (antiga.simbolos.get(fqn), nova.simbolos.get(fqn)) {
+            (Some(a), None) => {
+                relatorio.registrar(true, &rotulo(a, fqn), "removida da biblioteca".to_string())
+            }
+            (None, Some(n)) => {
+                relatorio.registrar(false, &rotulo(n, fqn), "adicionada".to_string())
+            }
+            (Some(a), Some(n)) => comparar_simbolo(&mut relatorio, fqn, a, n, antiga, nova),
+            (None, None) => {}
+        }
+    }
+    relatorio
+}
+
+fn rotulo(simbolo: &LibSimbolo, fqn: &str) -> String {
+    let tipo = match simbolo {
+        LibSimbolo::Classe(_) => "classe",
+        LibSimbolo::Interface(_) => "interface",
+        LibSimbolo::Enum(_) => "enumeração",
+        LibSimbolo::Funcao(_) => "função",
+    };
+    format!("{} {}", tipo, fqn)
+}
+
+fn comparar_simbolo(
+    relatorio: &mut Relatorio,
+    fqn: &str,
+    antigo: &LibSimbolo,
+    novo: &LibSimbolo,
+    antiga: &Biblioteca,
+    nova: &Biblioteca,
+) {
+    let simbolo = rotulo(antigo, fqn);
+    match (antigo, novo) {
+        (LibSimbolo::Classe(a), LibSimbolo::Classe(n)) => {
+            comparar_classe(relatorio, &simbolo, a, n, antiga, nova)
+        }
+        (LibSimbolo::Interface(a), LibSimbolo::Interface(n)) => {
+            comparar_interface(relatorio, &simbolo, a, n)
+        }
+        (LibSimbolo::Enum(a), LibSimbolo::Enum(n)) => comparar_enum(relatorio, &simbolo, a, n),
+        (LibSimbolo::Funcao(a), LibSimbolo::Funcao(n)) => comparar_funcao(relatorio, &simbolo, a, n),
+        _ => relatorio.registrar(true, &simbolo, format!("virou {}", rotulo(novo, fqn))),
+    }
+}
+
+fn comparar_classe(
+    relatorio: &mut Relatorio,
+    simbolo: &str,
+    a: &LibClasse,
+    n: &LibClasse,
+    antiga: &Biblioteca,
+    nova: &Biblioteca,
+) {
+    if a.eh_estatica != n.eh_estatica {
+        let agora = if n.eh_estatica { "estática" } else { "de instância" };
+        relatorio.registrar(true, simbolo, format!("passou a ser {}", agora));
+    }
+    if !a.eh_abstrata && n.eh_abstrata {
+        relatorio.registrar(true, simbolo, "passou a ser abstrata".to_string());
+    } else if a.eh_abstrata && !n.eh_abstrata {
+        relatorio.registrar(false, simbolo, "deixou de ser abstrata".to_string());
+    }
+    comparar_genericos(relatorio, simbolo, "", &a.genericos, &n.genericos);
+
+    let pai_antigo = a.nome_pai.as_deref().map(|p| normalizar_tipo(p, &a.genericos));
+    let pai_novo = n.nome_pai.as_deref().map(|p| normalizar_tipo(p, &n.genericos));
+    if pai_antigo != pai_novo {
+        relatorio.registrar(
+            true,
+            simbolo,
+            format!(
+                "classe base mudou de {} para {}",
+                a.nome_pai.as_deref().unwrap_or("nenhuma"),
+                n.nome_pai.as_deref().unwrap_or("nenhuma")
+            ),
+        );
+    }
+    let interfaces_antigas: Vec<String> =
+        a.interfaces.iter().map(|i| normalizar_tipo(i, &a.genericos)).collect();
+    let interfaces_novas: Vec<String> =
+        n.interfaces.iter().map(|i| normalizar_tipo(i, &n.genericos)).collect();
+    for (texto, normalizada) in a.interfaces.iter().zip(&interfaces_antigas) {
+        if !interfaces_novas.contains(normalizada) {
+            relatorio.registrar(true, simbolo, format!("deixou de implementar {}", texto));
+        }
+    }
+    for (texto, normalizada) in n.interfaces.iter().zip(&interfaces_novas) {
+        if !interfaces_antigas.contains(normalizada) {
+            relatorio.registrar(false, simbolo, format!("passou a implementar {}", texto));
+        }
+    }
+
+    if !n.eh_estatica {
+        comparar_construtores(relatorio, simbolo, a, n);
+    }
+    comparar_membros(relatorio, simbolo, a, n, antiga, nova);
+
+    let mut nomes: Vec<&String> = a.metodos.keys().chain(n.metodos.keys()).collect();
+    nomes.sort();
+    nomes.dedup();
+    for nome in nomes {
+        match (a.metodos.get(nome), n.metodos.get(nome)) {
+            (Some(ma), Some(mn)) => {
+                comparar_metodo(relatorio, simbolo, ma, mn, &a.genericos, &n.genericos)
+            }
+            (Some(ma), None) => match metodo_herdado(nova, n, nome) {
+                // Subiu para uma classe base da própria biblioteca
+                Some((mn, base)) => {
+                    comparar_metodo(relatorio, simbolo, ma, mn, &a.genericos, &base.genericos)
+                }
+                None => relatorio.registrar(true, simbolo, format!("método '{}' removido", nome)),
+            },
+            (None, Some(mn)) => {
+                if mn.eh_abstrato && metodo_herdado(antiga, a, nome).is_none() {
+                    relatorio.registrar(true, simbolo, format!("novo método abstrato '{}'", nome));
+                } else if metodo_herdado(antiga, a, nome).is_none() {
+                    relatorio.registrar(false, simbolo, format!("método '{}' adicionado", nome));
+                }
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+/// Método que a classe herda de uma base da mesma biblioteca, com a classe que o declara.
+fn metodo_herdado<'a>(
+    biblioteca: &'a Biblioteca,
+    classe: &'a LibClasse,
+    nome: &str,
+) -> Option<(&'a LibMetodo, &'a LibClasse)> {
+    let mut atual = classe;
+    for _ in 0..32 {
+        let pai = atual.nome_pai.as_deref()?;
+        let base = pai.split('<').next().unwrap_or(pai);
+        match biblioteca.simbolos.get(base) {
+            Some(LibSimbolo::Classe(classe_pai)) => {
+                if let Some(metodo) = classe_pai.metodos.get(nome) {
+                    return Some((metodo, classe_pai));
+                }
+                atual = classe_pai;
+            }
+            _ => return None,
+        }
+    }
+    None
+}
+
+/// Propriedades e campos, que o consumidor acessa do mesmo jeito.
+fn membros(classe: &LibClasse) -> HashMap<&str, (&str, bool, LibAcesso)> {
+    classe
+        .propriedades
+        .iter()
+        .map(|p| (p.nome.as_str(), (p.tipo.as_str(), p.eh_estatica, p.acesso)))
+        .chain(
+            classe
+                .campos
+                .iter()
+                .map(|c| (c.nome.as_str(), (c.tipo.as_str(), c.eh_estatica, c.acesso))),
+        )
+        .collect()
+}
+
+fn comparar_membros(
+    relatorio: &mut Relatorio,
+    simbolo: &str,
+    a: &LibClasse,
+    n: &LibClasse,
+    antiga: &Biblioteca,
+    nova: &Biblioteca,
+) {
+    let (antigos, novos) = (membros(a), membros(n));
+    let herdado = |biblioteca: &Biblioteca, classe: &LibClasse, nome: &str| {
+        let mut atual = classe.nome_pai.clone();
+        for _ in 0..32 {
+            let pai = atual?;
+            match biblioteca.simbolos.get(pai.split('<').next().unwrap_or(&pai)) {
+                Some(LibSimbolo::Classe(base)) if membros(base).contains_key(nome) => {
+                    return Some(());
+                }
+                Some(LibSimbolo::Classe(base)) => atual = base.nome_pai.clone(),
+                _ => return None,
+            }
+        }
+        None
+    };
+
+    let mut nomes: Vec<&&str> = antigos.keys().chain(novos.keys()).collect();
+    nomes.sort();
+    nomes.dedup();
+    for nome in nomes {
+        match (antigos.get(*nome), novos.get(*nome)) {
+            (Some((tipo_a, estatico_a, acesso_a)), Some((tipo_n, estatico_n, acesso_n))) => {
+                if normalizar_tipo(tipo_a, &a.genericos) != normalizar_tipo(tipo_n, &n.genericos) {
+                    relatorio.registrar(
+                        true,
+                        simbolo,
+                        format!("tipo de '{}' mudou de {} para {}", nome, tipo_a, tipo_n),
+                    );
+                }
+                if estatico_a != estatico_n {
+                    let agora = if *estatico_n { "estático" } else { "de instância" };
+                    relatorio.registrar(true, simbolo, format!("'{}' passou a ser {}", nome, agora));
+                }
+                comparar_acesso(relatorio, simbolo, &format!("'{}'", nome), *acesso_a, *acesso_n);
+            }
+            (Some(_), None) if herdado(nova, n, nome).is_none() => {
+                relatorio.registrar(true, simbolo, format!("membro '{}' removido", nome))
+            }
+            (None, Some(_)) if herdado(antiga, a, nome).is_none() => {
+                relatorio.registrar(false, simbolo, format!("membro '{}' adicionado", nome))
+            }
+            _ => {}
+        }
+    }
+}
+
+fn comparar_construtores(relatorio: &mut Relatorio, simbolo: &str, a: &LibClasse, n: &LibClasse) {
+    // Sem construtor declarado, a classe tem só o construtor vazio público
+    let implicito = [LibConstrutor::default()];
+    let antigos = if a.construtores.is_empty() { &implicito[..] } else { &a.construtores };
+    let novos = if n.construtores.is_empty() { &implicito[..] } else { &n.construtores };
+
+    for antigo in antigos {
+        let aceita = |novo: &&LibConstrutor| {
+            comparar_parametros(&antigo.parametros, &novo.parametros, &a.genericos, &n.genericos)
+                .is_ok()
+                && !(antigo.acesso == LibAcesso::Publico && novo.acesso == LibAcesso::Protegido)
+        };
+        if !novos.iter().any(|novo| aceita(&novo)) {
+            relatorio.registrar(
+                true,
+                simbolo,
+                format!("construtor {} removido ou alterado", lista_parametros(&antigo.parametros)),
+            );
+        }
+    }
+    for novo in novos {
+        let existia = antigos.iter().any(|antigo| {
+            tipos(&antigo.parametros, &a.genericos) == tipos(&novo.parametros, &n.genericos)
+        });
+        if !existia {
+            relatorio.registrar(
+                false,
+                simbolo,
+                format!("construtor {} adicionado", lista_parametros(&novo.parametros)),
+            );
+        }
+    }
+}
+
+fn comparar_interface(relatorio: &mut Relatorio, simbolo: &str, a: &LibInterface, n: &LibInterface) {
+    comparar_genericos(relatorio, simbolo, "", &a.genericos, &n.genericos);
+    let mut nomes: Vec<&String> = a.metodos.keys().chain(n.metodos.keys()).collect();
+    nomes.sort();
+    nomes.dedup();
+    for nome in nomes {
+        match (a.metodos.get(nome), n.metodos.get(nome)) {
+            (Some(ma), Some(mn)) => {
+                comparar_metodo(relatorio, simbolo, ma, mn, &a.genericos, &n.genericos)
+            }
+            (Some(_), None) => {
+                relatorio.registrar(true, simbolo, format!("método '{}' removido", nome))
+            }
+            // Quem implementa a interface ainda não tem o método
+            (None, Some(_)) => {
+                relatorio.registrar(true, simbolo, format!("novo método exigido '{}'", nome))
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+fn comparar_enum(relatorio: &mut Relatorio, simbolo: &str, a: &LibEnum, n: &LibEnum) {
+    // Os programas guardam o índice de cada valor: só acrescentar no fim é compatível
+    for (indice, valor) in a.valores.iter().enumerate() {
+        match n.valores.iter().position(|v| v == valor) {
+            None => relatorio.registrar(true, simbolo, format!("valor '{}' removido", valor)),
+            Some(novo) if novo != indice => relatorio.registrar(
+                true,
+                simbolo,
+                format!("valor '{}' mudou de posição ({} → {})", valor, indice, novo),
+            ),
+            Some(_) => {}
+        }
+    }
+    for valor in n.valores.iter().filter(|v| !a.valores.contains(v)) {
+        relatorio.registrar(false, simbolo, format!("valor '{}' adicionado", valor));
+    }
+}
+
+fn comparar_funcao(relatorio: &mut Relatorio, simbolo: &str, a: &LibFuncao, n: &LibFuncao) {
+    comparar_genericos(relatorio, simbolo, "", &a.genericos, &n.genericos);
+    comparar_assinatura(
+        relatorio,
+        simbolo,
+        "",
+        (&a.parametros, &a.tipo_retorno, &a.genericos),
+        (&n.parametros, &n.tipo_retorno, &n.genericos),
+    );
+}
+
+fn comparar_metodo(
+    relatorio: &mut Relatorio,
+    simbolo: &str,
+    a: &LibMetodo,
+    n: &LibMetodo,
+    genericos_classe_a: &[String],
+    genericos_classe_n: &[String],
+) {
+    let contexto = format!("método '{}': ", a.nome);
+    if a.eh_estatica != n.eh_estatica {
+        let agora = if n.eh_estatica { "estático" } else { "de instância" };
+        relatorio.registrar(true, simbolo, format!("{}passou a ser {}", contexto, agora));
+    }
+    comparar_acesso(relatorio, simbolo, &format!("método '{}'", a.nome), a.acesso, n.acesso);
+    if !a.eh_abstrato && n.eh_abstrato {
+        relatorio.registrar(true, simbolo, format!("{}passou a ser abstrato", contexto));
+    }
+    let redefinivel = |m: &LibMetodo| m.eh_redefinivel || m.eh_abstrato || m.eh_sobrescrita;
+    if redefinivel(a) && !redefinivel(n) {
+        relatorio.registrar(true, simbolo, format!("{}deixou de ser redefinível", contexto));
+    } else if !redefinivel(a) && redefinivel(n) {
+        relatorio.registrar(false, simbolo, format!("{}passou a ser redefinível", contexto));
+    }
+    comparar_genericos(relatorio, simbolo, &contexto, &a.genericos, &n.genericos);
+
+    let genericos_a = [genericos_classe_a, &a.genericos].concat();
+    let genericos_n = [genericos_classe_n, &n.genericos].concat();
+    comparar_assinatura(
+        relatorio,
+        simbolo,
+        &contexto,
+        (&a.parametros, &a.tipo_retorno, &genericos_a),
+        (&n.parametros, &n.tipo_retorno, &genericos_n),
+    );
+}
+
+type Assinatura<'a> = (&'a [LibParametro], &'a str, &'a [String]);
+
+fn comparar_assinatura(
+    relatorio: &mut Relatorio,
+    simbolo: &str,
+    contexto: &str,
+    (params_a, retorno_a, genericos_a): Assinatura,
+    (params_n, retorno_n, genericos_n): Assinatura,
+) {
+    if normalizar_tipo(retorno_a, genericos_a) != normalizar_tipo(retorno_n, genericos_n) {
+        relatorio.registrar(
+            true,
+            simbolo,
+            format!("{}retorno mudou de {} para {}", contexto, retorno_a, retorno_n),
+        );
+    }
+    match comparar_parametros(params_a, params_n, genericos_a, genericos_n) {
+        Ok(compativeis) => {
+            for descricao in compativeis {
+                relatorio.registrar(false, simbolo, format!("{}{}", contexto, descricao));
+            }
+        }
+        Err(descricao) => relatorio.registrar(
+            true,
+            simbolo,
+            format!(
+                "{}{}: {} → {}",
+                contexto,
+                descricao,
+                lista_parametros(params_a),
+                lista_parametros(params_n)
+            ),
+        ),
+    }
+}
+
+/// Se toda chamada válida com os parâmetros antigos continua válida com os novos.
+/// `Ok` traz as mudanças compatíveis (parâmetros opcionais novos, valores padrão);
+/// `Err`, o motivo da quebra.
+fn comparar_parametros(
+    antigos: &[LibParametro],
+    novos: &[LibParametro],
+    genericos_a: &[String],
+    genericos_n: &[String],
+) -> Result<Vec<String>, String> {
+    if novos.len() < antigos.len() {
+        return Err("parâmetros removidos".to_string());
+    }
+    let mut compativeis = Vec::new();
+    for (indice, (antigo, novo)) in antigos.iter().zip(novos).enumerate() {
+        if normalizar_tipo(&antigo.tipo, genericos_a) != normalizar_tipo(&novo.tipo, genericos_n) {
+            return Err(format!(
+                "parâmetro {} ('{}') mudou de {} para {}",
+                indice + 1,
+                antigo.nome,
+                antigo.tipo,
+                novo.tipo
+            ));
+        }
+        match (&antigo.padrao, &novo.padrao) {
+            (Some(_), None) => {
+                return Err(format!("parâmetro '{}' deixou de ser opcional", novo.nome));
+            }
+            (Some(de), Some(para)) if de != para => compativeis.push(format!(
+                "valor padrão de '{}' mudou de {} para {}",
+                novo.nome, de, para
+            )),
+            _ => {}
+        }
+    }
+    for extra in &novos[antigos.len()..] {
+        match &extra.padrao {
+            None => return Err(format!("novo parâmetro obrigatório '{}'", extra.nome)),
+            Some(_) => compativeis.push(format!("novo parâmetro opcional '{}'", extra.nome)),
+        }
+    }
+    Ok(compativeis)
+}
+
+fn comparar_genericos(
+    relatorio: &mut Relatorio,
+    simbolo: &str,
+    contexto: &str,
+    antigos: &[String],
+    novos: &[String],
+) {
+    if antigos.len() != novos.len() {
+        relatorio.registrar(
+            true,
+            simbolo,
+            format!(
+                "{}parâmetros de tipo mudaram de <{}> para <{}>",
+                contexto,
+                antigos.join(","),
+                novos.join(",")
+            ),
+        );
+    }
+}
+
+fn comparar_acesso(
+    relatorio: &mut Relatorio,
+    simbolo: &str,
+    membro: &str,
+    antigo: LibAcesso,
+    novo: LibAcesso,
+) {
+    match (antigo, novo) {
+        (LibAcesso::Publico, LibAcesso::Protegido) => {
+            relatorio.registrar(true, simbolo, format!("{} passou a ser protegido", membro))
+        }
+        (LibAcesso::Protegido, LibAcesso::Publico) => {
+            relatorio.registrar(false, simbolo, format!("{} passou a ser público", membro))
+        }
+        _ => {}
+    }
+}
+
+/// Tipo do manifesto com os parâmetros de tipo trocados pela posição (`T` → `$0`), para que
+/// renomeá-los não conte como mudança.
+fn normalizar_tipo(tipo: &str, genericos: &[String]) -> String {
+    let mut saida = String::new();
+    let mut nome = String::new();
+    let fechar = |nome: &mut String, saida: &mut String| {
+        match genericos.iter().position(|g| g == nome) {
+            Some(indice) => saida.push_str(&format!("${}", indice)),
+            None => saida.push_str(nome),
+        }
+        nome.clear();
+    };
+    for c in tipo.chars() {
+        if c.is_alphanumeric() || c == '_' || c == '.' {
+            nome.push(c);
+        } else {
+            fechar(&mut nome, &mut saida);
+            saida.push(c);
+        }
+    }
+    fechar(&mut nome, &mut saida);
+    saida
+}
+
+fn tipos(parametros: &[LibParametro], genericos: &[String]) -> Vec<String> {
+    parametros
+        .iter()
+        .map(|p| normalizar_tipo(&p.tipo, genericos))
+        .collect()
+}
+
+fn lista_parametros(parametros: &[LibParametro]) -> String {
+    let itens: Vec<String> = parametros
+        .iter()
+        .map(|p| match &p.padrao {
+            Some(padrao) => format!("{} {} = {}", p.tipo, p.nome, padrao),
+            None => format!("{} {}", p.tipo, p.nome),
+        })
+        .collect();
+    format!("({})", itens.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::library_loader::ler_manifesto;
+
+    fn comparar_manifestos(antigo: &str, novo: &str) -> Relatorio {
+        comparar(
+            &ler_manifesto(&format!("[MANIFESTO]\n{}", antigo)),
+            &ler_manifesto(&format!("[MANIFESTO]\n{}", novo)),
+        )
+    }
+
+    #[test]
+    fn parametro_opcional_novo_e_compativel() {
+        let relatorio = comparar_manifestos(
+            "DEFINE_FUNCTION Saudar texto 1 texto:nome",
+            "DEFINE_FUNCTION Saudar texto 2 texto:nome texto:prefixo=\"Oi\"",
+        );
+        assert_eq!(relatorio.incremento(), Incremento::Minor);
+        assert_eq!(
+            relatorio.mudancas[0].to_string(),
+            "função Saudar: novo parâmetro opcional 'prefixo'"
+        );
+    }
+
+    #[test]
+    fn renomear_parametro_de_tipo_nao_muda_a_api() {
+        let relatorio = comparar_manifestos(
+            "DEFINE_CLASS Caixa NULO genericos=T\nDEFINE_METHOD Caixa Pegar T 1 lista<T>:itens",
+            "DEFINE_CLASS Caixa NULO genericos=U\nDEFINE_METHOD Caixa Pegar U 1 lista<U>:itens",
+        );
+        assert!(relatorio.mudancas.is_empty(), "{:?}", relatorio.mudancas);
+    }
+
+    #[test]
+    fn valores_de_enumeracao_so_podem_ser_acrescentados_no_fim() {
+        let relatorio = comparar_manifestos("DEFINE_ENUM Cor A B", "DEFINE_ENUM Cor B A C");
+        let quebras: Vec<String> = relatorio.quebras().map(|m| m.descricao.clone()).collect();
+        assert_eq!(
+            quebras,
+            ["valor 'A' mudou de posição (0 → 1)", "valor 'B' mudou de posição (1 → 0)"]
+        );
+        assert_eq!(relatorio.compativeis().count(), 1);
+    }
+
+    #[test]
+    fn incremento_considera_versoes_antes_da_1_0() {
+        let v = |s: &str| Version::parse(s).unwrap();
+        assert_eq!(Incremento::Major.aplicar(&v("1.4.2")), v("2.0.0"));
+        assert_eq!(Incremento::Minor.aplicar(&v("1.4.2")), v("1.5.0"));
+        assert_eq!(Incremento::Major.aplicar(&v("0.3.1")), v("0.4.0"));
+        assert_eq!(Incremento::Minor.aplicar(&v("0.3.1")), v("0.3.2"));
+        assert_eq!(Incremento::Major.aplicar(&v("0.0.7")), v("0.0.8"));
+    }
+}
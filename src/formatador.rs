@@ -0,0 +1,552 @@
+// src/formatador.rs
+
+//! Formatador de código-fonte (`compilador formatar`).
+//!
+//! Trabalha direto sobre os tokens de [`Token`] e sobre o trivia entre eles (espaços,
+//! quebras de linha e comentários `//`). Nenhum token ou comentário é descartado: apenas o
+//! espaçamento é reescrito, e o resultado é conferido contra a entrada antes de ser devolvido.
+
+use logos::Logos;
+
+use crate::ast::MapaLinhas;
+use crate::error::{ErroCompilador, TipoErro};
+use crate::lexer::Token;
+
+pub const INDENTACAO: &str = "    ";
+
+#[derive(Debug)]
+struct Comentario<'a> {
+    texto: &'a str,
+    /// Quebras de linha entre o token (ou comentário) anterior e este comentário.
+    quebras_antes: usize,
+}
+
+#[derive(Debug)]
+struct Peca<'a> {
+    token: Token,
+    texto: &'a str,
+    comentarios: Vec<Comentario<'a>>,
+    /// Quebras de linha entre o último comentário (ou token) anterior e este token.
+    quebras_antes: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bloco {
+    /// Bloco comum: um membro/comando por linha, conteúdo indentado.
+    Normal,
+    /// Valores de `enumeração`: um por linha, separados por vírgula.
+    Enumeracao,
+    /// Bloco vazio (`{ }`) ou propriedade automática (`{ obter; definir; }`).
+    EmLinha,
+}
+
+/// Formata um arquivo `.pr` no estilo canônico.
+pub fn formatar(fonte: &str) -> Result<String, Box<ErroCompilador>> {
+    let (pecas, finais) = separar_pecas(fonte)?;
+    let saida = Formatador::new(&pecas).executar(&finais);
+
+    // Rede de segurança: o formatador nunca pode alterar tokens ou perder comentários
+    let (novas, novos_finais) = separar_pecas(&saida)?;
+    if assinatura(&pecas, &finais) != assinatura(&novas, &novos_finais) {
+        return Err(Box::new(ErroCompilador::novo(
+            TipoErro::Sintático,
+            "O formatador alteraria o significado do arquivo; nada foi modificado.".to_string(),
+        )));
+    }
+    Ok(saida)
+}
+
+type Pecas<'a> = (Vec<Peca<'a>>, Vec<Comentario<'a>>);
+
+fn separar_pecas(fonte: &str) -> Result<Pecas<'_>, Box<ErroCompilador>> {
+    let mut pecas = Vec::new();
+    let mut fim_anterior = 0;
+    for (tok, span) in Token::lexer(fonte).spanned() {
+        let token = tok.map_err(|_| {
+            let pos = MapaLinhas::novo("", fonte).posicao(span.start);
+            Box::new(
+                ErroCompilador::novo(
+                    TipoErro::Léxico,
+                    format!("Token inválido '{}'", &fonte[span.clone()]),
+                )
+                .com_localizacao(pos.linha, pos.coluna),
+            )
+        })?;
+        let (comentarios, quebras_antes) = separar_trivia(&fonte[fim_anterior..span.start]);
+        pecas.push(Peca {
+            token,
+            texto: &fonte[span.clone()],
+            comentarios,
+            quebras_antes,
+        });
+        fim_anterior = span.end;
+    }
+    let (finais, _) = separar_trivia(&fonte[fim_anterior..]);
+    Ok((pecas, finais))
+}
+
+// O trivia só contém espaços e comentários de linha (o resto seria token ou erro léxico),
+// então cada linha tem no máximo um comentário, que vai até o fim dela.
+fn separar_trivia(trivia: &str) -> (Vec<Comentario<'_>>, usize) {
+    let mut comentarios = Vec::new();
+    let mut quebras = 0;
+    for (i, linha) in trivia.split('\n').enumerate() {
+        if i > 0 {
+            quebras += 1;
+        }
+        if let Some(pos) = linha.find("//") {
+            comentarios.push(Comentario {
+                texto: linha[pos..].trim_end(),
+                quebras_antes: quebras,
+            });
+            quebras = 0;
+        }
+    }
+    (comentarios, quebras)
+}
+
+// Sequência de tokens e comentários, usada para garantir que a formatação não perdeu nada.
+// Dentro de strings interpoladas os espaços são ignorados, pois as expressões são reformatadas.
+fn assinatura(pecas: &[Peca], finais: &[Comentario]) -> Vec<String> {
+    let mut itens = Vec::new();
+    for p in pecas {
+        itens.extend(p.comentarios.iter().map(|c| c.texto.to_string()));
+        itens.push(match &p.token {
+            Token::TStringInterpolada(_) => p.texto.split_whitespace().collect(),
+            _ => p.texto.to_string(),
+        });
+    }
+    itens.extend(finais.iter().map(|c| c.texto.to_string()));
+    itens
+}
+
+fn eh_tipo(t: &Token) -> bool {
+    matches!(
+        t,
+        Token::TTipoInteiro
+            | Token::TTipoTexto
+            | Token::TTipoBooleano
+            | Token::TTipoFlutuante
+            | Token::TTipoDuplo
+            | Token::TTipoDecimal
+            | Token::TTipoVazio
+            | Token::TTipoObjeto
+    )
+}
+
+// Tokens que encerram um valor: depois deles, `-`/`+` é operador binário.
+fn termina_valor(t: &Token) -> bool {
+    matches!(
+        t,
+        Token::TIdentificador(_)
+            | Token::TString(_)
+            | Token::TStringInterpolada(_)
+            | Token::TInteiro(_)
+            | Token::TDecimal(_)
+            | Token::TFlutuanteLiteral(_)
+            | Token::TDuploLiteral(_)
+            | Token::TChar(_)
+            | Token::TCharLit(_)
+            | Token::TVerdadeiro
+            | Token::TFalso
+            | Token::TNulo
+            | Token::TEste
+            | Token::TBase
+            | Token::TParenDir
+            | Token::TColcheteDir
+    ) || eh_tipo(t)
+}
+
+struct Formatador<'a> {
+    pecas: &'a [Peca<'a>],
+    saida: String,
+    nivel: usize,
+    blocos: Vec<Bloco>,
+    parenteses: usize,
+    quebra_pendente: bool,
+    /// `<`/`>` que delimitam argumentos genéricos (e não comparações).
+    genericos: Vec<bool>,
+    /// `-`, `+` e `!` usados como operadores unários.
+    unarios: Vec<bool>,
+    /// `]` que fecha um atributo (`[Teste]`), seguido de quebra de linha.
+    fim_atributo: Vec<bool>,
+}
+
+impl<'a> Formatador<'a> {
+    fn new(pecas: &'a [Peca<'a>]) -> Self {
+        let n = pecas.len();
+        let mut f = Self {
+            pecas,
+            saida: String::new(),
+            nivel: 0,
+            blocos: Vec::new(),
+            parenteses: 0,
+            quebra_pendente: false,
+            genericos: vec![false; n],
+            unarios: vec![false; n],
+            fim_atributo: vec![false; n],
+        };
+        f.marcar_genericos();
+        f.marcar_unarios_e_atributos();
+        f
+    }
+
+    fn token(&self, i: usize) -> Option<&Token> {
+        self.pecas.get(i).map(|p| &p.token)
+    }
+
+    // `Nome<...>` só é genérico se tudo até o `>` correspondente puder fazer parte de um tipo.
+    fn marcar_genericos(&mut self) {
+        for i in 1..self.pecas.len() {
+            if self.pecas[i].token != Token::TMenor
+                || !matches!(self.pecas[i - 1].token, Token::TIdentificador(_))
+            {
+                continue;
+            }
+            let mut profundidade = 0;
+            for j in i..self.pecas.len() {
+                match &self.pecas[j].token {
+                    Token::TMenor => profundidade += 1,
+                    Token::TMaiorQue => {
+                        profundidade -= 1;
+                        if profundidade == 0 {
+                            for k in i..=j {
+                                if matches!(self.pecas[k].token, Token::TMenor | Token::TMaiorQue) {
+                                    self.genericos[k] = true;
+                                }
+                            }
+                            break;
+                        }
+                    }
+                    Token::TIdentificador(_)
+                    | Token::TVirgula
+                    | Token::TPonto
+                    | Token::TColcheteEsq
+                    | Token::TColcheteDir => {}
+                    t if eh_tipo(t) => {}
+                    _ => break,
+                }
+            }
+        }
+    }
+
+    fn marcar_unarios_e_atributos(&mut self) {
+        for i in 0..self.pecas.len() {
+            let anterior = i.checked_sub(1).map(|a| &self.pecas[a].token);
+            match &self.pecas[i].token {
+                Token::TNao => self.unarios[i] = true,
+                Token::TMenos | Token::TMais => {
+                    self.unarios[i] = !anterior.is_some_and(|a| {
+                        termina_valor(a) || (self.genericos[i - 1] && *a == Token::TMaiorQue)
+                    });
+                }
+                Token::TColcheteEsq => {
+                    let inicio_de_declaracao = match anterior {
+                        None => true,
+                        Some(Token::TPontoVirgula | Token::TChaveEsq | Token::TChaveDir) => true,
+                        Some(Token::TColcheteDir) => self.fim_atributo[i - 1],
+                        _ => false,
+                    };
+                    if inicio_de_declaracao
+                        && matches!(self.token(i + 1), Some(Token::TIdentificador(_)))
+                    {
+                        if let Some(fim) =
+                            self.fechamento(i, Token::TColcheteEsq, Token::TColcheteDir)
+                        {
+                            self.fim_atributo[fim] = true;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn fechamento(&self, inicio: usize, abre: Token, fecha: Token) -> Option<usize> {
+        let mut profundidade = 0usize;
+        for j in inicio..self.pecas.len() {
+            if self.pecas[j].token == abre {
+                profundidade += 1;
+            } else if self.pecas[j].token == fecha {
+                profundidade -= 1;
+                if profundidade == 0 {
+                    return Some(j);
+                }
+            }
+        }
+        None
+    }
+
+    fn classificar_bloco(&self, i: usize) -> Bloco {
+        let mut prox = i + 1;
+        if self.token(prox) == Some(&Token::TChaveDir) {
+            return Bloco::EmLinha;
+        }
+        // Listas de `importar { A, B } de "..."` e `exportar { A, B };`
+        if i >= 1 && matches!(self.pecas[i - 1].token, Token::TImportar | Token::TExportar) {
+            return Bloco::EmLinha;
+        }
+        if matches!(
+            self.token(prox),
+            Some(Token::TPublico | Token::TPrivado | Token::TProtegido)
+        ) {
+            prox += 1;
+        }
+        if matches!(self.token(prox), Some(Token::TObter | Token::TDefinir)) {
+            // Propriedade automática fica em uma linha; acessores com corpo viram blocos
+            let fim = self
+                .fechamento(i, Token::TChaveEsq, Token::TChaveDir)
+                .unwrap_or(self.pecas.len());
+            if !self.pecas[i + 1..fim]
+                .iter()
+                .any(|p| p.token == Token::TChaveEsq)
+            {
+                return Bloco::EmLinha;
+            }
+        }
+        if i >= 2 && self.pecas[i - 2].token == Token::TEnumeracao {
+            return Bloco::Enumeracao;
+        }
+        Bloco::Normal
+    }
+
+    fn espaco_antes(&self, i: usize) -> bool {
+        let Some(anterior) = i.checked_sub(1).map(|a| &self.pecas[a].token) else {
+            return false;
+        };
+        let atual = &self.pecas[i].token;
+        if matches!(
+            atual,
+            Token::TPontoVirgula
+                | Token::TVirgula
+                | Token::TParenDir
+                | Token::TColcheteDir
+                | Token::TPonto
+        ) || matches!(
+            anterior,
+            Token::TParenEsq | Token::TColcheteEsq | Token::TPonto
+        ) || self.unarios[i - 1]
+            || self.genericos[i]
+            || (self.genericos[i - 1] && *anterior == Token::TMenor)
+        {
+            return false;
+        }
+        let fim_generico = self.genericos[i - 1] && *anterior == Token::TMaiorQue;
+        match atual {
+            Token::TParenEsq => {
+                !(matches!(
+                    anterior,
+                    Token::TIdentificador(_) | Token::TImprima | Token::TBase | Token::TEste
+                ) || eh_tipo(anterior)
+                    || fim_generico)
+            }
+            Token::TColcheteEsq => {
+                !(matches!(
+                    anterior,
+                    Token::TIdentificador(_) | Token::TColcheteDir | Token::TParenDir
+                ) || eh_tipo(anterior)
+                    || fim_generico)
+            }
+            _ => true,
+        }
+    }
+
+    fn inicio_de_linha(&self) -> bool {
+        self.saida.is_empty() || self.saida.ends_with('\n')
+    }
+
+    // Quebra a linha atual; `em_branco` preserva uma (e só uma) linha vazia do original,
+    // exceto logo depois de abrir um bloco.
+    fn nova_linha(&mut self, em_branco: bool) {
+        let sem_espacos = self.saida.trim_end_matches([' ', '\t']).len();
+        self.saida.truncate(sem_espacos);
+        if self.saida.is_empty() {
+            return;
+        }
+        if !self.saida.ends_with('\n') {
+            self.saida.push('\n');
+        }
+        if em_branco && !self.saida.ends_with("\n\n") && !self.saida.ends_with("{\n") {
+            self.saida.push('\n');
+        }
+    }
+
+    fn indentar(&mut self) {
+        for _ in 0..self.nivel {
+            self.saida.push_str(INDENTACAO);
+        }
+    }
+
+    fn emitir_comentario(&mut self, c: &Comentario) {
+        if c.quebras_antes == 0 && !self.inicio_de_linha() {
+            self.saida.push(' ');
+        } else {
+            self.nova_linha(c.quebras_antes >= 2);
+            self.indentar();
+        }
+        self.saida.push_str(c.texto);
+        self.quebra_pendente = true;
+    }
+
+    fn executar(mut self, finais: &[Comentario]) -> String {
+        let pecas = self.pecas;
+        for (i, peca) in pecas.iter().enumerate() {
+            for c in &peca.comentarios {
+                self.emitir_comentario(c);
+            }
+
+            let token = &peca.token;
+            if *token == Token::TChaveDir && self.blocos.pop() != Some(Bloco::EmLinha) {
+                self.nivel = self.nivel.saturating_sub(1);
+                self.quebra_pendente = true;
+            }
+            if self.quebra_pendente || self.inicio_de_linha() {
+                self.nova_linha(peca.quebras_antes >= 2 && *token != Token::TChaveDir);
+                self.indentar();
+                self.quebra_pendente = false;
+            } else if self.espaco_antes(i) {
+                self.saida.push(' ');
+            }
+            match token {
+                Token::TStringInterpolada(_) => {
+                    self.saida.push_str(&normalizar_interpolada(peca.texto))
+                }
+                _ => self.saida.push_str(peca.texto),
+            }
+
+            let topo = self.blocos.last().copied();
+            match token {
+                Token::TChaveEsq => {
+                    let bloco = self.classificar_bloco(i);
+                    if bloco != Bloco::EmLinha {
+                        self.nivel += 1;
+                        self.quebra_pendente = true;
+                    }
+                    self.blocos.push(bloco);
+                }
+                Token::TChaveDir => {
+                    self.quebra_pendente = !matches!(
+                        self.token(i + 1),
+                        Some(
+                            Token::TSenao
+                                | Token::TDe
+                                | Token::TPontoVirgula
+                                | Token::TVirgula
+                                | Token::TParenDir
+                                | Token::TPonto
+                        )
+                    );
+                }
+                Token::TPontoVirgula => {
+                    self.quebra_pendente = self.parenteses == 0 && topo != Some(Bloco::EmLinha);
+                }
+                Token::TVirgula => {
+                    self.quebra_pendente = self.parenteses == 0 && topo == Some(Bloco::Enumeracao);
+                }
+                Token::TParenEsq | Token::TColcheteEsq => self.parenteses += 1,
+                Token::TParenDir => self.parenteses = self.parenteses.saturating_sub(1),
+                Token::TColcheteDir => {
+                    self.parenteses = self.parenteses.saturating_sub(1);
+                    self.quebra_pendente = self.fim_atributo[i];
+                }
+                _ => {}
+            }
+        }
+
+        for c in finais {
+            self.emitir_comentario(c);
+        }
+        self.nova_linha(false);
+        self.saida
+    }
+}
+
+// Reformata as expressões entre chaves de `$"..."` (`{ a+b }` vira `{a + b}`).
+fn normalizar_interpolada(texto: &str) -> String {
+    let mut saida = String::with_capacity(texto.len());
+    let mut resto = texto;
+    while let Some(inicio) = resto.find('{') {
+        let mut profundidade = 0;
+        let fim = resto[inicio..].char_indices().find_map(|(j, c)| {
+            match c {
+                '{' => profundidade += 1,
+                '}' => {
+                    profundidade -= 1;
+                    if profundidade == 0 {
+                        return Some(inicio + j);
+                    }
+                }
+                _ => {}
+            }
+            None
+        });
+        let Some(fim) = fim else { break };
+        let codigo = &resto[inicio + 1..fim];
+        saida.push_str(&resto[..=inicio]);
+        match formatar(codigo) {
+            Ok(f) if !f.trim().is_empty() && !f.trim().contains('\n') => saida.push_str(f.trim()),
+            _ => saida.push_str(codigo),
+        }
+        saida.push('}');
+        resto = &resto[fim + 1..];
+    }
+    saida.push_str(resto);
+    saida
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reindenta_blocos_e_propriedades() {
+        let fonte = "classe Ponto{publico inteiro X{obter;definir;}\npublico vazio Mover(inteiro dx){\nse(dx>0){X=X+dx;}senão{X=-dx;}}}\n";
+        let esperado = "\
+classe Ponto {
+    publico inteiro X { obter; definir; }
+    publico vazio Mover(inteiro dx) {
+        se (dx > 0) {
+            X = X + dx;
+        } senão {
+            X = -dx;
+        }
+    }
+}
+";
+        assert_eq!(formatar(fonte).unwrap(), esperado);
+    }
+
+    #[test]
+    fn genericos_interpolacao_e_comentarios() {
+        let fonte = "// topo\n\n\n[Teste] função vazio T(){ // fim de linha\nvar l = novo Lista < inteiro >();\n\n\nimprima($\"x={ a+b }\");\nse (a < b) { }\n// último\n}\n";
+        let esperado = "\
+// topo
+
+[Teste]
+função vazio T() { // fim de linha
+    var l = novo Lista<inteiro>();
+
+    imprima($\"x={a + b}\");
+    se (a < b) { }
+    // último
+}
+";
+        assert_eq!(formatar(fonte).unwrap(), esperado);
+    }
+
+    #[test]
+    fn enumeracao_um_valor_por_linha() {
+        assert_eq!(
+            formatar("enumeração Cor { Vermelho, Verde }").unwrap(),
+            "enumeração Cor {\n    Vermelho,\n    Verde\n}\n"
+        );
+    }
+
+    #[test]
+    fn listas_de_importar_e_exportar_em_uma_linha() {
+        assert_eq!(
+            formatar("importar {A,B} de \"util\";\nexportar {C};").unwrap(),
+            "importar { A, B } de \"util\";\nexportar { C };\n"
+        );
+    }
+}
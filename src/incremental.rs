@@ -0,0 +1,549 @@
+//! Compilação incremental: um cache por projeto (`dist/incremental/`) com o resultado de cada
+//! arquivo fonte, para que uma nova compilação refaça só o que mudou.
+//!
+//! - `ast/<chave>`: a AST do arquivo e a sua tabela de símbolos (nomes declarados, nomes usados
+//!   e o hash da assinatura pública). A chave é o hash do conteúdo, do caminho (que vai nas
+//!   posições) e da versão do compilador: um arquivo igual não é lexado nem parseado de novo.
+//! - `unidades/<chave>`: o registro de que o arquivo passou na verificação de tipos, com o
+//!   bytecode gerado para ele. A chave soma ao conteúdo o contexto (bibliotecas carregadas e os
+//!   `usando` do programa) e a assinatura de cada arquivo do qual ele depende, direta ou
+//!   indiretamente. Os corpos de um arquivo com registro não são verificados de novo, e o
+//!   bytecode dele é reaproveitado.
+//!
+//! A assinatura pública são as declarações sem os corpos: mudar só o corpo de um método refaz
+//! apenas o próprio arquivo; mudar uma assinatura refaz também quem depende dele. Um arquivo
+//! depende de outro quando usa algum nome declarado nele (tipo, função, membro, valor de
+//! enumeração, variável global). A comparação é só por nome, sem resolver escopos, e por isso
+//! conservadora: na dúvida, o arquivo é verificado de novo.
+//!
+//! O cache é um acelerador: entradas ilegíveis contam como ausentes, e falhas ao gravar são
+//! ignoradas. Ao fim de uma compilação bem-sucedida, as entradas que ela não usou são apagadas.
+
+use crate::artefato::VERSAO_COMPILADOR;
+use crate::ast::{self, Declaracao, Programa};
+use crate::codegen::bytecode::{self, Trechos};
+use crate::type_checker::VerificadorTipos;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Subdiretório padrão do cache, dentro do `dist/` de um projeto ou biblioteca.
+pub const DIRETORIO: &str = "incremental";
+
+const ANALISES: &str = "ast";
+const UNIDADES: &str = "unidades";
+
+/// Tabela de símbolos de um arquivo, guardada com a AST.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Simbolos {
+    /// Tipos, funções, membros, valores de enumeração e variáveis globais declarados
+    pub declarados: BTreeSet<String>,
+    /// Todo texto que aparece na AST: identificadores, nomes de tipos e literais
+    pub usados: BTreeSet<String>,
+    /// `usando` do arquivo, que valem para o programa inteiro
+    pub usings: Vec<String>,
+    /// Hash das declarações sem os corpos
+    pub assinatura: String,
+}
+
+impl Simbolos {
+    pub fn de(programa: &Programa) -> Self {
+        let mut declarados = BTreeSet::new();
+        for decl in programa
+            .declaracoes
+            .iter()
+            .chain(programa.namespaces.iter().flat_map(|ns| &ns.declaracoes))
+        {
+            declarar(decl, &mut declarados);
+        }
+        let mut usados = BTreeSet::new();
+        if let Ok(valor) = serde_json::to_value(programa) {
+            coletar_textos(&valor, &mut usados);
+        }
+        let publico = Programa {
+            usings: programa.usings.clone(),
+            namespaces: programa
+                .namespaces
+                .iter()
+                .map(|ns| ast::DeclaracaoNamespace {
+                    nome: ns.nome.clone(),
+                    declaracoes: ns.declaracoes.iter().filter_map(sem_corpos).collect(),
+                })
+                .collect(),
+            declaracoes: programa.declaracoes.iter().filter_map(sem_corpos).collect(),
+        };
+        let assinatura = bincode::serialize(&publico)
+            .map(|bytes| hash(&bytes))
+            .unwrap_or_default();
+        Self {
+            declarados,
+            usados,
+            usings: programa.usings.iter().map(|u| u.caminho.clone()).collect(),
+            assinatura,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Analise {
+    programa: Programa,
+    simbolos: Simbolos,
+}
+
+/// Registro de um arquivo verificado; o bytecode fica vazio até algum alvo precisar dele.
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct Registro {
+    trechos: Option<Trechos>,
+}
+
+pub struct CacheIncremental {
+    diretorio: PathBuf,
+    /// Chave da análise e símbolos de cada arquivo analisado nesta compilação
+    analisados: Mutex<HashMap<PathBuf, (String, Simbolos)>>,
+    /// Entradas lidas ou gravadas nesta compilação (as demais são apagadas ao concluir)
+    usadas: Mutex<HashSet<PathBuf>>,
+    analises_reaproveitadas: AtomicUsize,
+}
+
+impl CacheIncremental {
+    pub fn abrir(diretorio: &Path) -> io::Result<Self> {
+        fs::create_dir_all(diretorio.join(ANALISES))?;
+        fs::create_dir_all(diretorio.join(UNIDADES))?;
+        Ok(Self {
+            diretorio: diretorio.to_path_buf(),
+            analisados: Mutex::new(HashMap::new()),
+            usadas: Mutex::new(HashSet::new()),
+            analises_reaproveitadas: AtomicUsize::new(0),
+        })
+    }
+
+    /// A AST de `caminho`: a do cache, se o conteúdo não mudou, ou a de `parsear`. `modo`
+    /// distingue análises diferentes do mesmo arquivo (com e sem tabela de linhas).
+    pub fn analisar<E>(
+        &self,
+        caminho: &Path,
+        codigo: &str,
+        modo: &str,
+        parsear: impl FnOnce() -> Result<Programa, E>,
+    ) -> Result<Programa, E> {
+        let chave = hash(
+            format!("{}\0{}\0{}\0{}", VERSAO_COMPILADOR, modo, caminho.display(), codigo)
+                .as_bytes(),
+        );
+        let arquivo = self.diretorio.join(ANALISES).join(&chave);
+        let analise = match ler::<Analise>(&arquivo) {
+            Some(analise) => {
+                self.analises_reaproveitadas.fetch_add(1, Ordering::Relaxed);
+                analise
+            }
+            None => {
+                let programa = parsear()?;
+                let analise = Analise {
+                    simbolos: Simbolos::de(&programa),
+                    programa,
+                };
+                gravar(&arquivo, &analise);
+                analise
+            }
+        };
+        self.usadas.lock().unwrap().insert(arquivo);
+        self.analisados
+            .lock()
+            .unwrap()
+            .insert(caminho.to_path_buf(), (chave, analise.simbolos));
+        Ok(analise.programa)
+    }
+
+    /// Decide, para cada arquivo do programa (na ordem de junção), se a verificação e o
+    /// bytecode de uma compilação anterior ainda valem. `contexto` identifica o que vem de fora
+    /// dos arquivos (ver [`contexto`]).
+    pub fn planejar(&self, caminhos: &[PathBuf], contexto: &str) -> Plano {
+        let analisados = self.analisados.lock().unwrap();
+        let simbolos: Vec<Option<&(String, Simbolos)>> =
+            caminhos.iter().map(|c| analisados.get(c)).collect();
+
+        // Quem declara cada nome
+        let mut declarantes: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (i, s) in simbolos.iter().enumerate() {
+            for nome in s.iter().flat_map(|(_, s)| &s.declarados) {
+                declarantes.entry(nome).or_default().push(i);
+            }
+        }
+        let diretas: Vec<BTreeSet<usize>> = simbolos
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                s.iter()
+                    .flat_map(|(_, s)| &s.usados)
+                    .filter_map(|nome| declarantes.get(nome.as_str()))
+                    .flatten()
+                    .copied()
+                    .filter(|&j| j != i)
+                    .collect()
+            })
+            .collect();
+        let usings: BTreeSet<&str> = simbolos
+            .iter()
+            .flat_map(|s| s.iter().flat_map(|(_, s)| &s.usings))
+            .map(String::as_str)
+            .collect();
+        let global = format!(
+            "{}\0{}\0{}",
+            VERSAO_COMPILADOR,
+            contexto,
+            usings.into_iter().collect::<Vec<_>>().join(",")
+        );
+
+        let unidades = (0..caminhos.len())
+            .map(|i| {
+                let Some((conteudo, _)) = simbolos[i] else {
+                    return Unidade::default();
+                };
+                let mut chave = format!("{}\0{}", global, conteudo);
+                for j in fecho(&diretas, i) {
+                    match simbolos[j] {
+                        Some((_, s)) => {
+                            chave.push_str(&format!("\0{}={}", caminhos[j].display(), s.assinatura))
+                        }
+                        None => return Unidade::default(),
+                    }
+                }
+                let chave = hash(chave.as_bytes());
+                let arquivo = self.diretorio.join(UNIDADES).join(&chave);
+                let registro = ler::<Registro>(&arquivo);
+                Unidade {
+                    verificada: registro.is_some(),
+                    registro: registro.unwrap_or_default(),
+                    arquivo: Some(arquivo),
+                    alterada: false,
+                }
+            })
+            .collect();
+        Plano { unidades }
+    }
+
+    /// Grava os registros dos arquivos verificados e apaga as entradas que esta compilação não
+    /// usou. Chamado depois que a compilação terminou sem erros.
+    pub fn concluir(&self, plano: &Plano) {
+        let mut usadas = self.usadas.lock().unwrap();
+        for unidade in &plano.unidades {
+            let Some(arquivo) = &unidade.arquivo else { continue };
+            if !unidade.verificada || unidade.alterada {
+                gravar(arquivo, &unidade.registro);
+            }
+            usadas.insert(arquivo.clone());
+        }
+        for subdiretorio in [ANALISES, UNIDADES] {
+            let Ok(entradas) = fs::read_dir(self.diretorio.join(subdiretorio)) else {
+                continue;
+            };
+            for entrada in entradas.flatten() {
+                if !usadas.contains(&entrada.path()) {
+                    let _ = fs::remove_file(entrada.path());
+                }
+            }
+        }
+    }
+
+    /// Resumo para a saída do compilador: quantos arquivos dispensaram cada etapa.
+    pub fn resumo(&self, plano: &Plano) -> String {
+        let total = plano.unidades.len();
+        format!(
+            "♻️  Compilação incremental ({}): análise reaproveitada em {}/{} arquivo(s), verificação e bytecode em {}/{}",
+            self.diretorio.display(),
+            self.analises_reaproveitadas.load(Ordering::Relaxed).min(total),
+            total,
+            plano.verificadas(),
+            total
+        )
+    }
+}
+
+/// Situação de um arquivo do programa no cache.
+#[derive(Default)]
+struct Unidade {
+    /// Entrada do registro; `None` quando o arquivo não passou por [`CacheIncremental::analisar`]
+    arquivo: Option<PathBuf>,
+    /// O arquivo e suas dependências não mudaram desde uma compilação bem-sucedida
+    verificada: bool,
+    registro: Registro,
+    /// Bytecode gerado agora para um arquivo já verificado
+    alterada: bool,
+}
+
+/// O que aproveitar do cache em uma compilação, arquivo por arquivo.
+pub struct Plano {
+    unidades: Vec<Unidade>,
+}
+
+impl Plano {
+    /// Número de arquivos cuja verificação foi reaproveitada.
+    pub fn verificadas(&self) -> usize {
+        self.unidades.iter().filter(|u| u.verificada).count()
+    }
+
+    /// Avisa o verificador de tipos das classes e funções cujos corpos não precisam ser
+    /// verificados de novo. `partes` são os programas de cada arquivo, na ordem do plano.
+    pub fn preparar_verificador(&self, partes: &[Programa], verificador: &mut VerificadorTipos) {
+        for (unidade, parte) in self.unidades.iter().zip(partes) {
+            if !unidade.verificada {
+                continue;
+            }
+            let declaracoes = parte
+                .declaracoes
+                .iter()
+                .map(|d| ("", d))
+                .chain(parte.namespaces.iter().flat_map(|ns| {
+                    ns.declaracoes.iter().map(move |d| (ns.nome.as_str(), d))
+                }));
+            for (namespace, decl) in declaracoes {
+                let nome = match decl {
+                    Declaracao::DeclaracaoClasse(c) => &c.nome,
+                    Declaracao::DeclaracaoFuncao(f) => &f.nome,
+                    _ => continue,
+                };
+                if namespace.is_empty() {
+                    verificador.registrar_corpo_verificado(nome);
+                } else {
+                    verificador.registrar_corpo_verificado(&format!("{}.{}", namespace, nome));
+                }
+            }
+        }
+    }
+
+    /// Bytecode do programa inteiro: o dos arquivos reaproveitados vem do cache, o dos demais
+    /// é gerado agora. O resultado é idêntico ao de gerar o programa combinado de uma vez.
+    pub fn gerar_bytecode(&mut self, partes: &[Programa], verificador: &VerificadorTipos) -> Vec<String> {
+        let mut trechos = Vec::with_capacity(partes.len());
+        for (unidade, parte) in self.unidades.iter_mut().zip(partes) {
+            if unidade.registro.trechos.is_none() {
+                unidade.registro.trechos =
+                    Some(bytecode::BytecodeGenerator::new(parte, verificador).gerar_trechos());
+                unidade.alterada = unidade.verificada;
+            }
+            trechos.push(unidade.registro.trechos.clone().unwrap_or_default());
+        }
+        bytecode::juntar_trechos(&trechos)
+    }
+}
+
+/// Contexto de uma compilação para as chaves do cache: o modo (programa ou biblioteca) e o
+/// conteúdo das bibliotecas carregadas.
+pub fn contexto(modo: &str, bibliotecas: &[PathBuf]) -> String {
+    let mut contexto = modo.to_string();
+    for caminho in bibliotecas {
+        let conteudo = fs::read(caminho).unwrap_or_default();
+        contexto.push_str(&format!("\0{}={}", caminho.display(), hash(&conteudo)));
+    }
+    contexto
+}
+
+/// Arquivos dos quais `i` depende, direta ou indiretamente, em ordem.
+fn fecho(diretas: &[BTreeSet<usize>], i: usize) -> BTreeSet<usize> {
+    let mut fecho = BTreeSet::new();
+    let mut pendentes: Vec<usize> = diretas[i].iter().copied().collect();
+    while let Some(j) = pendentes.pop() {
+        if j != i && fecho.insert(j) {
+            pendentes.extend(diretas[j].iter().copied());
+        }
+    }
+    fecho
+}
+
+fn declarar(decl: &Declaracao, nomes: &mut BTreeSet<String>) {
+    match decl {
+        Declaracao::DeclaracaoClasse(c) => declarar_classe(c, nomes),
+        Declaracao::DeclaracaoFuncao(f) => {
+            nomes.insert(f.nome.clone());
+        }
+        Declaracao::DeclaracaoModulo(m) => {
+            nomes.insert(m.nome.clone());
+            for d in &m.conteudo {
+                declarar(d, nomes);
+            }
+        }
+        Declaracao::DeclaracaoInterface(i) => {
+            nomes.insert(i.nome.clone());
+            nomes.extend(i.metodos.iter().map(|m| m.nome.clone()));
+        }
+        Declaracao::DeclaracaoEnum(e) => {
+            nomes.insert(e.nome.clone());
+            nomes.extend(e.valores.iter().cloned());
+        }
+        Declaracao::DeclaracaoTipo(t) => {
+            nomes.insert(t.nome.clone());
+        }
+        Declaracao::DeclaracaoNamespace(ns) => {
+            for d in &ns.declaracoes {
+                declarar(d, nomes);
+            }
+        }
+        Declaracao::Comando(
+            ast::Comando::DeclaracaoVariavel(_, nome, _) | ast::Comando::DeclaracaoVar(nome, _),
+        ) => {
+            nomes.insert(nome.clone());
+        }
+        _ => {}
+    }
+}
+
+fn declarar_classe(classe: &ast::DeclaracaoClasse, nomes: &mut BTreeSet<String>) {
+    nomes.insert(classe.nome.clone());
+    nomes.extend(classe.campos.iter().map(|c| c.nome.clone()));
+    nomes.extend(classe.propriedades.iter().map(|p| p.nome.clone()));
+    nomes.extend(classe.metodos.iter().map(|m| m.nome.clone()));
+    for aninhada in &classe.nested_classes {
+        declarar_classe(aninhada, nomes);
+    }
+}
+
+/// A declaração sem os corpos de métodos, funções e construtores (só fica marcado se havia
+/// corpo, o que distingue métodos nativos). Marcas de posição no nível superior não entram:
+/// mudam a cada linha acrescentada ao arquivo.
+fn sem_corpos(decl: &Declaracao) -> Option<Declaracao> {
+    Some(match decl {
+        Declaracao::DeclaracaoClasse(c) => Declaracao::DeclaracaoClasse(classe_sem_corpos(c)),
+        Declaracao::DeclaracaoFuncao(f) => {
+            let mut f = f.clone();
+            marcar_corpo(&mut f.corpo);
+            Declaracao::DeclaracaoFuncao(f)
+        }
+        Declaracao::DeclaracaoModulo(m) => Declaracao::DeclaracaoModulo(ast::DeclaracaoModulo {
+            nome: m.nome.clone(),
+            conteudo: m.conteudo.iter().filter_map(sem_corpos).collect(),
+        }),
+        Declaracao::DeclaracaoNamespace(ns) => {
+            Declaracao::DeclaracaoNamespace(ast::DeclaracaoNamespace {
+                nome: ns.nome.clone(),
+                declaracoes: ns.declaracoes.iter().filter_map(sem_corpos).collect(),
+            })
+        }
+        Declaracao::Comando(ast::Comando::Posicao(_)) => return None,
+        outra => outra.clone(),
+    })
+}
+
+fn classe_sem_corpos(classe: &ast::DeclaracaoClasse) -> ast::DeclaracaoClasse {
+    let mut classe = classe.clone();
+    for metodo in &mut classe.metodos {
+        marcar_corpo(&mut metodo.corpo);
+    }
+    for construtor in &mut classe.construtores {
+        marcar_corpo(&mut construtor.corpo);
+        construtor.chamada_pai = None;
+    }
+    for propriedade in &mut classe.propriedades {
+        for acessor in [&mut propriedade.obter, &mut propriedade.definir]
+            .into_iter()
+            .flatten()
+        {
+            if let Some(corpo) = &mut acessor.corpo {
+                marcar_corpo(corpo);
+            }
+        }
+    }
+    classe.nested_classes = classe.nested_classes.iter().map(classe_sem_corpos).collect();
+    classe
+}
+
+fn marcar_corpo(corpo: &mut Vec<ast::Comando>) {
+    if !corpo.is_empty() {
+        *corpo = vec![ast::Comando::Bloco(vec![])];
+    }
+}
+
+fn coletar_textos(valor: &serde_json::Value, textos: &mut BTreeSet<String>) {
+    match valor {
+        serde_json::Value::String(texto) => {
+            textos.insert(texto.clone());
+        }
+        serde_json::Value::Array(itens) => itens.iter().for_each(|v| coletar_textos(v, textos)),
+        serde_json::Value::Object(campos) => campos.values().for_each(|v| coletar_textos(v, textos)),
+        _ => {}
+    }
+}
+
+fn hash(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn ler<T: DeserializeOwned>(arquivo: &Path) -> Option<T> {
+    bincode::deserialize(&fs::read(arquivo).ok()?).ok()
+}
+
+/// Grava pelo nome temporário e renomeia, para que uma compilação interrompida não deixe uma
+/// entrada pela metade.
+fn gravar<T: Serialize>(arquivo: &Path, valor: &T) {
+    let Ok(bytes) = bincode::serialize(valor) else { return };
+    let temporario = arquivo.with_extension("tmp");
+    if fs::write(&temporario, bytes).is_ok() {
+        let _ = fs::rename(&temporario, arquivo);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parsear(codigo: &str) -> Programa {
+        crate::module_system::parsear_fonte(Path::new("a.pr"), codigo, true).unwrap()
+    }
+
+    #[test]
+    fn assinatura_ignora_corpos_e_posicoes() {
+        let base = Simbolos::de(&parsear(
+            "espaco A {\n    publico função inteiro F(inteiro x) { retorne x; }\n}\n",
+        ));
+        let corpo = Simbolos::de(&parsear(
+            "\n\nespaco A {\n    publico função inteiro F(inteiro x) {\n        retorne x * 2;\n    }\n}\n",
+        ));
+        let assinatura = Simbolos::de(&parsear(
+            "espaco A {\n    publico função texto F(inteiro x) { retorne \"\"; }\n}\n",
+        ));
+        assert_eq!(base.assinatura, corpo.assinatura);
+        assert_ne!(base.assinatura, assinatura.assinatura);
+        assert!(base.declarados.contains("F"));
+    }
+
+    #[test]
+    fn dependencias_seguem_os_nomes_usados() {
+        let dir = std::env::temp_dir().join(format!("incremental-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let cache = CacheIncremental::abrir(&dir).unwrap();
+        let arquivos = [
+            ("a.pr", "espaco A {\n    publico função inteiro Um() { retorne 1; }\n}\n"),
+            ("b.pr", "espaco B {\n    publico função inteiro Dois() { retorne A.Um() + 1; }\n}\n"),
+            ("c.pr", "espaco C {\n    publico função inteiro Tres() { retorne B.Dois() + 1; }\n}\n"),
+            ("d.pr", "espaco D {\n    publico função inteiro Zero() { retorne 0; }\n}\n"),
+        ];
+        let caminhos: Vec<PathBuf> = arquivos.iter().map(|(c, _)| PathBuf::from(c)).collect();
+        let analisar = |arquivos: &[(&str, &str)]| {
+            for (caminho, codigo) in arquivos {
+                let caminho = Path::new(caminho);
+                cache
+                    .analisar(caminho, codigo, "fonte", || {
+                        crate::module_system::parsear_fonte(caminho, codigo, false)
+                    })
+                    .unwrap();
+            }
+        };
+        analisar(&arquivos);
+        let plano = cache.planejar(&caminhos, "teste");
+        assert_eq!(plano.verificadas(), 0);
+        cache.concluir(&plano);
+        analisar(&arquivos);
+        assert_eq!(cache.planejar(&caminhos, "teste").verificadas(), 4);
+
+        // Assinatura de A muda: B usa A, C usa B; D não é afetado
+        analisar(&[("a.pr", "espaco A {\n    publico função duplo Um() { retorne 1.0; }\n}\n")]);
+        let plano = cache.planejar(&caminhos, "teste");
+        let verificadas: Vec<bool> = plano.unidades.iter().map(|u| u.verificada).collect();
+        assert_eq!(verificadas, [false, false, false, true]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -38,7 +38,7 @@ pub fn parse_string_interpolada(input: &str) -> Result<ast::Expressao, String> {
                 .collect();
 
             let expr = crate::parser::ExpressaoParser::new()
-                .parse(tokens.iter().cloned())
+                .parse(None, tokens.iter().cloned())
                 .map_err(|e| format!("Erro na expressão interpolada: {:?}", e))?;
 
             parts.push(ast::PartStringInterpolada::Expressao(expr));
@@ -0,0 +1,660 @@
+// Análise do bytecode textual para o JIT.
+//
+// Decodifica as instruções de uma função, verifica se ela é elegível (apenas inteiros,
+// booleanos e duplos; variáveis locais; aritmética; comparações; saltos; chamadas a outras
+// funções elegíveis) e infere os tipos da pilha, das variáveis e do retorno a partir dos
+// tipos dos argumentos da chamada. O bytecode não tem tipos, então cada combinação de tipos
+// de argumentos gera uma especialização própria.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::fmt;
+
+use super::JitError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TipoJit {
+    Inteiro,
+    Duplo,
+    Booleano,
+}
+
+impl fmt::Display for TipoJit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TipoJit::Inteiro => write!(f, "inteiro"),
+            TipoJit::Duplo => write!(f, "duplo"),
+            TipoJit::Booleano => write!(f, "booleano"),
+        }
+    }
+}
+
+/// Valores trocados entre a VM e o código nativo.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValorJit {
+    Inteiro(i64),
+    Duplo(f64),
+    Booleano(bool),
+}
+
+impl ValorJit {
+    pub fn tipo(&self) -> TipoJit {
+        match self {
+            ValorJit::Inteiro(_) => TipoJit::Inteiro,
+            ValorJit::Duplo(_) => TipoJit::Duplo,
+            ValorJit::Booleano(_) => TipoJit::Booleano,
+        }
+    }
+}
+
+/// Função e tipos dos argumentos: a chave de uma especialização.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Assinatura {
+    pub nome: String,
+    pub parametros: Vec<TipoJit>,
+}
+
+impl fmt::Display for Assinatura {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let params: Vec<String> = self.parametros.iter().map(|t| t.to_string()).collect();
+        write!(f, "{}({})", self.nome, params.join(", "))
+    }
+}
+
+/// Corpo de uma função como a VM o guarda (`FuncInfo`).
+#[derive(Debug, Clone, Copy)]
+pub struct FuncaoBytecode<'a> {
+    pub parametros: &'a [String],
+    pub corpo: &'a [String],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpAritmetica {
+    Soma,
+    Subtracao,
+    Multiplicacao,
+    Divisao,
+    Resto,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OpComparacao {
+    Igual,
+    Diferente,
+    Menor,
+    Maior,
+    MenorIgual,
+    MaiorIgual,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Instrucao {
+    ConstInteiro(i64),
+    ConstDuplo(f64),
+    ConstBooleano(bool),
+    Carregar(String),
+    Armazenar(String),
+    Aritmetica(OpAritmetica),
+    NegarNumero,
+    NegarLogico,
+    Comparar(OpComparacao),
+    Saltar(usize),
+    SaltarSeFalso(usize),
+    Chamar(String, usize),
+    Retornar,
+    Descartar,
+    /// `LINE` e comentários: não geram código.
+    Nada,
+}
+
+/// Intrínsecas tratadas pela VM antes de procurar a função (ver `CALL_FUNCTION`).
+const INTRINSECAS: &[&str] = &["EscreverLinha", "LerLinha"];
+
+/// Decodifica uma linha do bytecode; instruções fora do subconjunto numérico são rejeitadas.
+pub fn decodificar(linha: &str) -> Result<Instrucao, JitError> {
+    let partes: Vec<&str> = linha.split_whitespace().collect();
+    let op = partes.first().copied().unwrap_or("");
+    let arg = |i: usize| {
+        partes
+            .get(i)
+            .copied()
+            .ok_or_else(|| JitError::NaoSuportado(format!("'{}' sem argumento", op)))
+    };
+    let numero = |i: usize| -> Result<usize, JitError> {
+        arg(i)?
+            .parse()
+            .map_err(|_| JitError::NaoSuportado(format!("argumento inválido em '{}'", linha)))
+    };
+    Ok(match op {
+        "LOAD_CONST_INT" => {
+            Instrucao::ConstInteiro(arg(1)?.parse().map_err(|_| {
+                JitError::NaoSuportado(format!("constante inválida em '{}'", linha))
+            })?)
+        }
+        "LOAD_CONST_DOUBLE" => {
+            Instrucao::ConstDuplo(arg(1)?.parse().map_err(|_| {
+                JitError::NaoSuportado(format!("constante inválida em '{}'", linha))
+            })?)
+        }
+        "LOAD_CONST_BOOL" => {
+            Instrucao::ConstBooleano(arg(1)?.parse().map_err(|_| {
+                JitError::NaoSuportado(format!("constante inválida em '{}'", linha))
+            })?)
+        }
+        "LOAD_VAR" => Instrucao::Carregar(arg(1)?.to_string()),
+        "STORE_VAR" => Instrucao::Armazenar(arg(1)?.to_string()),
+        "ADD" => Instrucao::Aritmetica(OpAritmetica::Soma),
+        "SUB" => Instrucao::Aritmetica(OpAritmetica::Subtracao),
+        "MUL" => Instrucao::Aritmetica(OpAritmetica::Multiplicacao),
+        "DIV" => Instrucao::Aritmetica(OpAritmetica::Divisao),
+        "MOD" => Instrucao::Aritmetica(OpAritmetica::Resto),
+        "NEGATE_INT" => Instrucao::NegarNumero,
+        "NEGATE_BOOL" => Instrucao::NegarLogico,
+        "COMPARE_EQ" => Instrucao::Comparar(OpComparacao::Igual),
+        "COMPARE_NE" => Instrucao::Comparar(OpComparacao::Diferente),
+        "COMPARE_LT" => Instrucao::Comparar(OpComparacao::Menor),
+        "COMPARE_GT" => Instrucao::Comparar(OpComparacao::Maior),
+        "COMPARE_LE" => Instrucao::Comparar(OpComparacao::MenorIgual),
+        "COMPARE_GE" => Instrucao::Comparar(OpComparacao::MaiorIgual),
+        "JUMP" => Instrucao::Saltar(numero(1)?),
+        "JUMP_IF_FALSE" => Instrucao::SaltarSeFalso(numero(1)?),
+        "CALL_FUNCTION" => Instrucao::Chamar(arg(1)?.to_string(), numero(2)?),
+        "RETURN" => Instrucao::Retornar,
+        "POP" => Instrucao::Descartar,
+        "LINE" | "" => Instrucao::Nada,
+        op if op.starts_with(';') => Instrucao::Nada,
+        _ => return Err(JitError::NaoSuportado(format!("instrução '{}'", op))),
+    })
+}
+
+/// Uma especialização pronta para ser traduzida.
+#[derive(Debug, Clone)]
+pub struct FuncaoAnalisada {
+    pub assinatura: Assinatura,
+    pub parametros: Vec<String>,
+    pub instrucoes: Vec<Instrucao>,
+    /// Tipos da pilha antes de cada instrução; `None` quando a instrução é inalcançável.
+    /// O índice `instrucoes.len()` representa o fim do corpo (retorno implícito).
+    pub pilhas: Vec<Option<Vec<TipoJit>>>,
+    /// Tipo de cada variável local, incluindo os parâmetros.
+    pub locais: BTreeMap<String, TipoJit>,
+    /// `None` para funções sem valor de retorno.
+    pub retorno: Option<TipoJit>,
+    pub chamadas: Vec<Assinatura>,
+}
+
+impl FuncaoAnalisada {
+    /// Assinatura da função chamada pela instrução `Chamar` em `ip`.
+    pub fn chamada_em(&self, ip: usize) -> Option<Assinatura> {
+        let (Instrucao::Chamar(nome, n), Some(pilha)) = (&self.instrucoes[ip], &self.pilhas[ip])
+        else {
+            return None;
+        };
+        Some(Assinatura {
+            nome: nome.clone(),
+            parametros: pilha[pilha.len() - n..].to_vec(),
+        })
+    }
+}
+
+/// Tipo do resultado de uma operação aritmética, seguindo as promoções da VM.
+pub fn tipo_aritmetica(op: OpAritmetica, a: TipoJit, b: TipoJit) -> Option<TipoJit> {
+    use TipoJit::*;
+    match (op, a, b) {
+        (_, Booleano, _) | (_, _, Booleano) => None,
+        (OpAritmetica::Resto, Inteiro, Inteiro) => Some(Inteiro),
+        (OpAritmetica::Resto, _, _) => None,
+        (_, Inteiro, Inteiro) => Some(Inteiro),
+        _ => Some(Duplo),
+    }
+}
+
+fn rejeitar(assinatura: &Assinatura, ip: usize, motivo: impl fmt::Display) -> JitError {
+    JitError::NaoSuportado(format!("{} (instrução {}): {}", assinatura, ip, motivo))
+}
+
+/// Analisa uma especialização. `retorno_de` informa o tipo de retorno (já conhecido ou
+/// presumido) das funções chamadas.
+pub fn analisar_funcao(
+    assinatura: &Assinatura,
+    funcao: FuncaoBytecode,
+    retorno_de: &dyn Fn(&Assinatura) -> Option<TipoJit>,
+    existe: &dyn Fn(&str) -> bool,
+) -> Result<FuncaoAnalisada, JitError> {
+    if funcao.parametros.len() != assinatura.parametros.len() {
+        return Err(JitError::NaoSuportado(format!(
+            "{}: esperava {} argumento(s)",
+            assinatura,
+            funcao.parametros.len()
+        )));
+    }
+    let instrucoes = funcao
+        .corpo
+        .iter()
+        .map(|l| decodificar(l))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| match e {
+            JitError::NaoSuportado(m) | JitError::Interno(m) => {
+                JitError::NaoSuportado(format!("{}: {}", assinatura, m))
+            }
+        })?;
+    let fim = instrucoes.len();
+
+    let mut locais: BTreeMap<String, TipoJit> = funcao
+        .parametros
+        .iter()
+        .cloned()
+        .zip(assinatura.parametros.iter().copied())
+        .collect();
+    let mut estados: Vec<Option<(Vec<TipoJit>, BTreeSet<String>)>> = vec![None; fim + 1];
+    let mut retornos: Vec<Option<TipoJit>> = Vec::new();
+    let mut chamadas: Vec<Assinatura> = Vec::new();
+
+    estados[0] = Some((Vec::new(), locais.keys().cloned().collect()));
+    let mut fila = VecDeque::from([0usize]);
+
+    while let Some(ip) = fila.pop_front() {
+        let (mut pilha, mut definidas) = estados[ip].clone().expect("estado sem entrada");
+        let erro = |motivo: &str| rejeitar(assinatura, ip, motivo);
+        let mut sucessores: Vec<usize> = Vec::new();
+
+        if ip == fim {
+            retornos.push(pilha.last().copied());
+            continue;
+        }
+        macro_rules! desempilhar {
+            () => {
+                pilha.pop().ok_or_else(|| erro("pilha vazia"))?
+            };
+        }
+
+        match &instrucoes[ip] {
+            Instrucao::Nada => sucessores.push(ip + 1),
+            Instrucao::ConstInteiro(_) => {
+                pilha.push(TipoJit::Inteiro);
+                sucessores.push(ip + 1);
+            }
+            Instrucao::ConstDuplo(_) => {
+                pilha.push(TipoJit::Duplo);
+                sucessores.push(ip + 1);
+            }
+            Instrucao::ConstBooleano(_) => {
+                pilha.push(TipoJit::Booleano);
+                sucessores.push(ip + 1);
+            }
+            Instrucao::Carregar(nome) => {
+                // Globais, campos de `este` e variáveis talvez não atribuídas ficam com a VM
+                if !definidas.contains(nome) {
+                    return Err(erro(&format!("variável '{}' não é local", nome)));
+                }
+                pilha.push(locais[nome]);
+                sucessores.push(ip + 1);
+            }
+            Instrucao::Armazenar(nome) => {
+                let tipo = desempilhar!();
+                match locais.get(nome) {
+                    Some(anterior) if *anterior != tipo => {
+                        return Err(erro(&format!(
+                            "variável '{}' recebe {} e {}",
+                            nome, anterior, tipo
+                        )))
+                    }
+                    _ => {
+                        locais.insert(nome.clone(), tipo);
+                    }
+                }
+                definidas.insert(nome.clone());
+                sucessores.push(ip + 1);
+            }
+            Instrucao::Aritmetica(op) => {
+                let b = desempilhar!();
+                let a = desempilhar!();
+                let tipo = tipo_aritmetica(*op, a, b)
+                    .ok_or_else(|| erro(&format!("{:?} entre {} e {}", op, a, b)))?;
+                pilha.push(tipo);
+                sucessores.push(ip + 1);
+            }
+            Instrucao::NegarNumero => {
+                let tipo = desempilhar!();
+                if tipo == TipoJit::Booleano {
+                    return Err(erro("negação numérica de booleano"));
+                }
+                pilha.push(tipo);
+                sucessores.push(ip + 1);
+            }
+            Instrucao::NegarLogico => {
+                if desempilhar!() != TipoJit::Booleano {
+                    return Err(erro("negação lógica de número"));
+                }
+                pilha.push(TipoJit::Booleano);
+                sucessores.push(ip + 1);
+            }
+            Instrucao::Comparar(op) => {
+                let b = desempilhar!();
+                let a = desempilhar!();
+                let ordenada = !matches!(op, OpComparacao::Igual | OpComparacao::Diferente);
+                if ordenada && (a == TipoJit::Booleano || b == TipoJit::Booleano) {
+                    return Err(erro("comparação de ordem entre booleanos"));
+                }
+                pilha.push(TipoJit::Booleano);
+                sucessores.push(ip + 1);
+            }
+            Instrucao::Saltar(alvo) => sucessores.push(*alvo),
+            Instrucao::SaltarSeFalso(alvo) => {
+                if desempilhar!() != TipoJit::Booleano {
+                    return Err(erro("condição não booleana"));
+                }
+                sucessores.push(*alvo);
+                sucessores.push(ip + 1);
+            }
+            Instrucao::Chamar(nome, n) => {
+                let simples = nome.rsplit('.').next().unwrap_or(nome);
+                if INTRINSECAS.contains(&simples) || !existe(nome) {
+                    return Err(erro(&format!("chamada a '{}'", nome)));
+                }
+                if pilha.len() < *n {
+                    return Err(erro("pilha insuficiente para a chamada"));
+                }
+                let chamada = Assinatura {
+                    nome: nome.clone(),
+                    parametros: pilha.split_off(pilha.len() - n),
+                };
+                // A VM empilharia `nulo` como resultado de uma função sem retorno
+                let tipo = retorno_de(&chamada)
+                    .ok_or_else(|| erro(&format!("'{}' não retorna valor", chamada)))?;
+                pilha.push(tipo);
+                if !chamadas.contains(&chamada) {
+                    chamadas.push(chamada);
+                }
+                sucessores.push(ip + 1);
+            }
+            Instrucao::Retornar => retornos.push(pilha.last().copied()),
+            Instrucao::Descartar => {
+                desempilhar!();
+                sucessores.push(ip + 1);
+            }
+        }
+
+        for alvo in sucessores {
+            if alvo > fim {
+                return Err(erro("salto para fora da função"));
+            }
+            match &mut estados[alvo] {
+                slot @ None => {
+                    *slot = Some((pilha.clone(), definidas.clone()));
+                    fila.push_back(alvo);
+                }
+                Some((pilha_alvo, definidas_alvo)) => {
+                    if *pilha_alvo != pilha {
+                        return Err(erro("pilha com formatos diferentes em um ponto de junção"));
+                    }
+                    let intersecao: BTreeSet<String> =
+                        definidas_alvo.intersection(&definidas).cloned().collect();
+                    if intersecao.len() != definidas_alvo.len() {
+                        *definidas_alvo = intersecao;
+                        fila.push_back(alvo);
+                    }
+                }
+            }
+        }
+    }
+
+    let retorno = retornos.first().copied().flatten();
+    if retornos.iter().any(|r| *r != retorno) {
+        return Err(JitError::NaoSuportado(format!(
+            "{}: retornos de tipos diferentes",
+            assinatura
+        )));
+    }
+    Ok(FuncaoAnalisada {
+        assinatura: assinatura.clone(),
+        parametros: funcao.parametros.to_vec(),
+        instrucoes,
+        pilhas: estados.into_iter().map(|e| e.map(|(p, _)| p)).collect(),
+        locais,
+        retorno,
+        chamadas,
+    })
+}
+
+const MAX_ITERACOES: usize = 4;
+
+/// Palpite para o retorno de uma função ainda não analisada: o tipo da primeira constante,
+/// comparação ou parâmetro empilhado logo antes de um `RETURN` (`inteiro` se não houver).
+fn presumir_retorno<'a>(
+    assinatura: &Assinatura,
+    buscar: &dyn Fn(&str) -> Option<FuncaoBytecode<'a>>,
+) -> TipoJit {
+    let Some(funcao) = buscar(&assinatura.nome) else {
+        return TipoJit::Inteiro;
+    };
+    let instrucoes: Vec<Instrucao> = funcao
+        .corpo
+        .iter()
+        .filter_map(|l| decodificar(l).ok())
+        .filter(|i| !matches!(i, Instrucao::Nada))
+        .collect();
+    instrucoes
+        .windows(2)
+        .filter(|par| matches!(par[1], Instrucao::Retornar))
+        .find_map(|par| match &par[0] {
+            Instrucao::ConstInteiro(_) => Some(TipoJit::Inteiro),
+            Instrucao::ConstDuplo(_) => Some(TipoJit::Duplo),
+            Instrucao::ConstBooleano(_) | Instrucao::Comparar(_) | Instrucao::NegarLogico => {
+                Some(TipoJit::Booleano)
+            }
+            Instrucao::Carregar(nome) => funcao
+                .parametros
+                .iter()
+                .position(|p| p == nome)
+                .and_then(|i| assinatura.parametros.get(i).copied()),
+            _ => None,
+        })
+        .unwrap_or(TipoJit::Inteiro)
+}
+
+/// Analisa `raiz` e todas as funções que ela chama (transitivamente), exceto as já
+/// compiladas (`compiladas` devolve o retorno delas). O primeiro item do plano é a raiz.
+///
+/// Chamadas recursivas precisam do tipo de retorno antes de a análise terminar: ele é
+/// presumido pelo que alimenta os `RETURN` da função (ver `presumir_retorno`) e a análise é
+/// refeita com os tipos encontrados até que eles se estabilizem.
+pub fn planejar<'a>(
+    raiz: &Assinatura,
+    buscar: &dyn Fn(&str) -> Option<FuncaoBytecode<'a>>,
+    compiladas: &dyn Fn(&Assinatura) -> Option<Option<TipoJit>>,
+) -> Result<Vec<FuncaoAnalisada>, JitError> {
+    let mut presumidos: HashMap<Assinatura, Option<TipoJit>> = HashMap::new();
+    for _ in 0..MAX_ITERACOES {
+        let retorno_de = |a: &Assinatura| {
+            compiladas(a)
+                .or_else(|| presumidos.get(a).copied())
+                .unwrap_or_else(|| Some(presumir_retorno(a, buscar)))
+        };
+        let existe = |nome: &str| buscar(nome).is_some();
+        let mut plano: Vec<FuncaoAnalisada> = Vec::new();
+        let mut vistas: HashSet<Assinatura> = HashSet::new();
+        let mut fila = VecDeque::from([raiz.clone()]);
+        while let Some(assinatura) = fila.pop_front() {
+            if !vistas.insert(assinatura.clone())
+                || (assinatura != *raiz && compiladas(&assinatura).is_some())
+            {
+                continue;
+            }
+            let funcao = buscar(&assinatura.nome).ok_or_else(|| {
+                JitError::NaoSuportado(format!("função '{}' não encontrada", assinatura.nome))
+            })?;
+            let analisada = analisar_funcao(&assinatura, funcao, &retorno_de, &existe)?;
+            fila.extend(analisada.chamadas.iter().cloned());
+            plano.push(analisada);
+        }
+
+        let divergentes: Vec<(Assinatura, Option<TipoJit>)> = plano
+            .iter()
+            .filter(|f| retorno_de(&f.assinatura) != f.retorno)
+            .map(|f| (f.assinatura.clone(), f.retorno))
+            .collect();
+        if divergentes.is_empty() {
+            return Ok(plano);
+        }
+        presumidos.extend(divergentes);
+    }
+    Err(JitError::NaoSuportado(format!(
+        "{}: tipo de retorno não se estabiliza",
+        raiz
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn funcoes(fontes: &[(&str, &[&str], &str)]) -> HashMap<String, (Vec<String>, Vec<String>)> {
+        fontes
+            .iter()
+            .map(|(nome, params, corpo)| {
+                (
+                    nome.to_string(),
+                    (
+                        params.iter().map(|p| p.to_string()).collect(),
+                        corpo.lines().map(|l| l.trim().to_string()).collect(),
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    fn planejar_em(
+        mapa: &HashMap<String, (Vec<String>, Vec<String>)>,
+        nome: &str,
+        parametros: Vec<TipoJit>,
+    ) -> Result<Vec<FuncaoAnalisada>, JitError> {
+        let buscar = |n: &str| {
+            mapa.get(n).map(|(p, c)| FuncaoBytecode {
+                parametros: p,
+                corpo: c,
+            })
+        };
+        planejar(
+            &Assinatura {
+                nome: nome.to_string(),
+                parametros,
+            },
+            &buscar,
+            &|_| None,
+        )
+    }
+
+    #[test]
+    fn recursao_infere_retorno() {
+        let mapa = funcoes(&[(
+            "Metade",
+            &["n"],
+            "LOAD_VAR n
+             LOAD_CONST_INT 1
+             COMPARE_LE
+             JUMP_IF_FALSE 6
+             LOAD_CONST_DOUBLE 1.0
+             RETURN
+             LOAD_VAR n
+             LOAD_CONST_INT 2
+             DIV
+             CALL_FUNCTION Metade 1
+             LOAD_CONST_DOUBLE 0.5
+             MUL
+             RETURN",
+        )]);
+        let plano = planejar_em(&mapa, "Metade", vec![TipoJit::Inteiro]).unwrap();
+        assert_eq!(plano.len(), 1);
+        assert_eq!(plano[0].retorno, Some(TipoJit::Duplo));
+    }
+
+    #[test]
+    fn recursao_mutua_com_booleanos() {
+        let mapa = funcoes(&[
+            (
+                "EhPar",
+                &["n"],
+                "LOAD_VAR n
+                 LOAD_CONST_INT 0
+                 COMPARE_EQ
+                 JUMP_IF_FALSE 6
+                 LOAD_CONST_BOOL true
+                 RETURN
+                 LOAD_VAR n
+                 LOAD_CONST_INT 1
+                 SUB
+                 CALL_FUNCTION EhImpar 1
+                 RETURN",
+            ),
+            (
+                "EhImpar",
+                &["n"],
+                "LOAD_VAR n
+                 LOAD_CONST_INT 0
+                 COMPARE_EQ
+                 JUMP_IF_FALSE 6
+                 LOAD_CONST_BOOL false
+                 RETURN
+                 LOAD_VAR n
+                 LOAD_CONST_INT 1
+                 SUB
+                 CALL_FUNCTION EhPar 1
+                 RETURN",
+            ),
+        ]);
+        let plano = planejar_em(&mapa, "EhPar", vec![TipoJit::Inteiro]).unwrap();
+        assert_eq!(plano.len(), 2);
+        assert!(plano.iter().all(|f| f.retorno == Some(TipoJit::Booleano)));
+    }
+
+    #[test]
+    fn laco_com_variaveis_locais() {
+        let mapa = funcoes(&[(
+            "Soma",
+            &["n"],
+            "LOAD_CONST_INT 0
+             STORE_VAR total
+             LOAD_VAR n
+             LOAD_CONST_INT 0
+             COMPARE_GT
+             JUMP_IF_FALSE 15
+             LOAD_VAR total
+             LOAD_VAR n
+             ADD
+             STORE_VAR total
+             LOAD_VAR n
+             LOAD_CONST_INT 1
+             SUB
+             STORE_VAR n
+             JUMP 2
+             LOAD_VAR total
+             RETURN",
+        )]);
+        let plano = planejar_em(&mapa, "Soma", vec![TipoJit::Inteiro]).unwrap();
+        assert_eq!(plano[0].locais["total"], TipoJit::Inteiro);
+        assert_eq!(plano[0].retorno, Some(TipoJit::Inteiro));
+    }
+
+    #[test]
+    fn rejeita_texto_globais_e_tipos_mistos() {
+        let mapa = funcoes(&[
+            ("Ola", &[], "LOAD_CONST_STR \"oi\"\nRETURN"),
+            ("Global", &[], "LOAD_VAR contador\nRETURN"),
+            (
+                "Misto",
+                &["b"],
+                "LOAD_CONST_INT 1
+                 STORE_VAR x
+                 LOAD_CONST_DOUBLE 1.5
+                 STORE_VAR x
+                 LOAD_VAR x
+                 RETURN",
+            ),
+            ("Chama", &[], "CALL_FUNCTION Ola 0\nRETURN"),
+        ]);
+        for nome in ["Ola", "Global", "Chama"] {
+            assert!(planejar_em(&mapa, nome, vec![]).is_err(), "{}", nome);
+        }
+        assert!(planejar_em(&mapa, "Misto", vec![TipoJit::Booleano]).is_err());
+    }
+}
@@ -1,22 +1,63 @@
-// Implementação mínima de JIT com Cranelift.
-// Objetivo inicial: compilar uma função somatória como PoC.
+// Tradução das funções analisadas (ver `analise.rs`) para código nativo com Cranelift.
+//
+// Cada especialização vira uma função `(erro: *mut i32, parametros...) -> retorno`, com os
+// tipos nativos i64 (inteiro), f64 (duplo) e i8 (booleano). Erros de execução (divisão por
+// zero) gravam um código em `*erro` e retornam; quem chamou verifica o código após cada
+// chamada e repassa o erro. Para a VM, cada especialização também ganha uma função de
+// entrada `(args: *const u64, erro: *mut i32) -> u64`, de assinatura fixa.
 
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use cranelift_codegen::ir::condcodes::{FloatCC, IntCC};
+use cranelift_codegen::ir::{
+    types, AbiParam, Block, InstBuilder, MemFlags, Signature, Type, UserFuncName, Value,
+};
 use cranelift_codegen::settings::{self, Configurable};
-use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext};
+use cranelift_frontend::{FunctionBuilder, FunctionBuilderContext, Variable};
 use cranelift_jit::{JITBuilder, JITModule};
 use cranelift_module::{FuncId, Linkage, Module};
-use cranelift_native;
 
+use super::analise::{
+    Assinatura, FuncaoAnalisada, Instrucao, OpAritmetica, OpComparacao, TipoJit, ValorJit,
+};
 use super::JitError;
 
-pub struct JitHandle {
-    pub fn_ptr: *const u8,
+const ERRO_DIVISAO_POR_ZERO: i64 = 1;
+const ERRO_MODULO_POR_ZERO: i64 = 2;
+const ERRO_ESTOURO: i64 = 3;
+
+/// Mensagens iguais às da VM para os mesmos erros.
+fn mensagem_erro(codigo: i32) -> String {
+    match codigo as i64 {
+        ERRO_DIVISAO_POR_ZERO => "Divisão por zero".to_string(),
+        ERRO_MODULO_POR_ZERO => "Módulo por zero".to_string(),
+        ERRO_ESTOURO => "Estouro na divisão inteira".to_string(),
+        _ => format!("Erro desconhecido no código nativo ({})", codigo),
+    }
+}
+
+fn tipo_nativo(tipo: TipoJit) -> Type {
+    match tipo {
+        TipoJit::Inteiro => types::I64,
+        TipoJit::Duplo => types::F64,
+        TipoJit::Booleano => types::I8,
+    }
+}
+
+/// Especialização compilada, pronta para ser chamada pela VM.
+#[derive(Debug, Clone)]
+pub struct FuncaoCompilada {
+    pub assinatura: Assinatura,
+    pub retorno: Option<TipoJit>,
+    entrada: *const u8,
 }
 
 pub struct CraneliftJit {
     module: JITModule,
     ctx: cranelift_codegen::Context,
     builder_ctx: FunctionBuilderContext,
+    definidas: HashMap<Assinatura, (FuncId, Option<TipoJit>)>,
+    contador: usize,
 }
 
 impl CraneliftJit {
@@ -28,60 +69,625 @@ impl CraneliftJit {
             .map_err(|e| JitError::Interno(e.to_string()))?
             .finish(flags)
             .map_err(|e| JitError::Interno(e.to_string()))?;
-        let mut jit_builder = JITBuilder::with_isa(isa, cranelift_module::default_libcall_names());
+        let jit_builder = JITBuilder::with_isa(isa, cranelift_module::default_libcall_names());
         let module = JITModule::new(jit_builder);
         Ok(Self {
             module,
             ctx: cranelift_codegen::Context::new(),
             builder_ctx: FunctionBuilderContext::new(),
+            definidas: HashMap::new(),
+            contador: 0,
         })
     }
 
-    // Compila uma função que soma dois i32: fn(i32, i32) -> i32
-    pub fn compilar_soma_i32(&mut self) -> Result<JitHandle, JitError> {
-        use cranelift_codegen::ir::{types, AbiParam, Function, InstBuilder, Signature};
+    /// Tipo de retorno de uma especialização já compilada.
+    pub fn retorno_compilado(&self, assinatura: &Assinatura) -> Option<Option<TipoJit>> {
+        self.definidas.get(assinatura).map(|(_, r)| *r)
+    }
 
+    fn assinatura_nativa(&self, funcao: &FuncaoAnalisada) -> Signature {
         let mut sig = Signature::new(self.module.isa().default_call_conv());
-        sig.params.push(AbiParam::new(types::I32));
-        sig.params.push(AbiParam::new(types::I32));
-        sig.returns.push(AbiParam::new(types::I32));
+        sig.params
+            .push(AbiParam::new(self.module.isa().pointer_type()));
+        for tipo in &funcao.assinatura.parametros {
+            sig.params.push(AbiParam::new(tipo_nativo(*tipo)));
+        }
+        if let Some(tipo) = funcao.retorno {
+            sig.returns.push(AbiParam::new(tipo_nativo(tipo)));
+        }
+        sig
+    }
+
+    fn nome_unico(&mut self, base: &str) -> String {
+        self.contador += 1;
+        format!("{}#{}", base, self.contador)
+    }
+
+    /// Compila todas as funções do plano (a raiz primeiro) e devolve as entradas de cada uma.
+    pub fn compilar(
+        &mut self,
+        plano: &[FuncaoAnalisada],
+    ) -> Result<Vec<FuncaoCompilada>, JitError> {
+        let interno = |e: cranelift_module::ModuleError| JitError::Interno(e.to_string());
+
+        let mut novas = Vec::new();
+        for funcao in plano {
+            let sig = self.assinatura_nativa(funcao);
+            let nome = self.nome_unico(&funcao.assinatura.nome);
+            let id = self
+                .module
+                .declare_function(&nome, Linkage::Local, &sig)
+                .map_err(interno)?;
+            self.definidas
+                .insert(funcao.assinatura.clone(), (id, funcao.retorno));
+            novas.push(id);
+        }
+
+        let resultado = self.definir_todas(plano, &novas);
+        if resultado.is_err() {
+            // Declarações sem corpo não podem ser finalizadas nem chamadas
+            for funcao in plano {
+                self.definidas.remove(&funcao.assinatura);
+            }
+        }
+        let entradas = resultado?;
+        self.module.finalize_definitions().map_err(interno)?;
+
+        Ok(plano
+            .iter()
+            .zip(entradas)
+            .map(|(funcao, id)| FuncaoCompilada {
+                assinatura: funcao.assinatura.clone(),
+                retorno: funcao.retorno,
+                entrada: self.module.get_finalized_function(id),
+            })
+            .collect())
+    }
+
+    fn definir_todas(
+        &mut self,
+        plano: &[FuncaoAnalisada],
+        ids: &[FuncId],
+    ) -> Result<Vec<FuncId>, JitError> {
+        for (funcao, id) in plano.iter().zip(ids) {
+            self.definir_funcao(funcao, *id)?;
+        }
+        plano
+            .iter()
+            .zip(ids)
+            .map(|(funcao, id)| self.definir_entrada(funcao, *id))
+            .collect()
+    }
 
-        let func_id: FuncId = self
+    fn finalizar_definicao(&mut self, id: FuncId) -> Result<(), JitError> {
+        let resultado = self
             .module
-            .declare_function("soma_i32", Linkage::Local, &sig)
-            .map_err(|e| JitError::Interno(e.to_string()))?;
+            .define_function(id, &mut self.ctx)
+            .map_err(|e| JitError::Interno(format!("{:?}", e)));
+        self.module.clear_context(&mut self.ctx);
+        resultado
+    }
 
-        let mut func = Function::with_name_signature(
-            cranelift_codegen::ir::UserFuncName::user(0, func_id.as_u32()),
-            sig,
-        );
-        self.ctx.func = func;
+    fn definir_funcao(&mut self, funcao: &FuncaoAnalisada, id: FuncId) -> Result<(), JitError> {
+        self.ctx.func.signature = self.assinatura_nativa(funcao);
+        self.ctx.func.name = UserFuncName::user(0, id.as_u32());
+
+        // Funções chamadas: referências resolvidas antes de emprestar o contexto ao builder
+        let mut chamadas: HashMap<Assinatura, (FuncId, Option<TipoJit>)> = HashMap::new();
+        for ip in 0..funcao.instrucoes.len() {
+            if let Some(chamada) = funcao.chamada_em(ip) {
+                let definida = *self
+                    .definidas
+                    .get(&chamada)
+                    .ok_or_else(|| JitError::Interno(format!("{} não foi declarada", chamada)))?;
+                chamadas.insert(chamada, definida);
+            }
+        }
 
         let mut builder = FunctionBuilder::new(&mut self.ctx.func, &mut self.builder_ctx);
-        let block = builder.create_block();
-        builder.append_block_params_for_function_params(block);
-        builder.switch_to_block(block);
-        builder.seal_block(block);
-
-        let a = builder.block_params(block)[0];
-        let b = builder.block_params(block)[1];
-        let sum = builder.ins().iadd(a, b);
-        builder.ins().return_(&[sum]);
+        let refs: HashMap<Assinatura, (cranelift_codegen::ir::FuncRef, Option<TipoJit>)> = chamadas
+            .into_iter()
+            .map(|(a, (id, r))| (a, (self.module.declare_func_in_func(id, builder.func), r)))
+            .collect();
+
+        let mut tradutor = Tradutor::new(&mut builder, funcao, refs);
+        tradutor.traduzir();
+        builder.seal_all_blocks();
         builder.finalize();
+        self.finalizar_definicao(id)
+    }
 
-        self.module
-            .define_function(func_id, &mut self.ctx)
+    fn definir_entrada(
+        &mut self,
+        funcao: &FuncaoAnalisada,
+        alvo: FuncId,
+    ) -> Result<FuncId, JitError> {
+        let ptr = self.module.isa().pointer_type();
+        let mut sig = Signature::new(self.module.isa().default_call_conv());
+        sig.params.push(AbiParam::new(ptr));
+        sig.params.push(AbiParam::new(ptr));
+        sig.returns.push(AbiParam::new(types::I64));
+        let nome = self.nome_unico(&format!("entrada_{}", funcao.assinatura.nome));
+        let id = self
+            .module
+            .declare_function(&nome, Linkage::Local, &sig)
             .map_err(|e| JitError::Interno(e.to_string()))?;
-        self.module.clear_context(&mut self.ctx);
-        self.module.finalize_definitions();
+        self.ctx.func.signature = sig;
+        self.ctx.func.name = UserFuncName::user(0, id.as_u32());
+
+        let mut builder = FunctionBuilder::new(&mut self.ctx.func, &mut self.builder_ctx);
+        let alvo_ref = self.module.declare_func_in_func(alvo, builder.func);
+        let bloco = builder.create_block();
+        builder.append_block_params_for_function_params(bloco);
+        builder.switch_to_block(bloco);
+        let (args, erro) = (
+            builder.block_params(bloco)[0],
+            builder.block_params(bloco)[1],
+        );
+
+        let mut valores = vec![erro];
+        for (i, tipo) in funcao.assinatura.parametros.iter().enumerate() {
+            let deslocamento = (i * 8) as i32;
+            let valor = match tipo {
+                TipoJit::Duplo => {
+                    builder
+                        .ins()
+                        .load(types::F64, MemFlags::trusted(), args, deslocamento)
+                }
+                TipoJit::Inteiro => {
+                    builder
+                        .ins()
+                        .load(types::I64, MemFlags::trusted(), args, deslocamento)
+                }
+                TipoJit::Booleano => {
+                    let v = builder
+                        .ins()
+                        .load(types::I64, MemFlags::trusted(), args, deslocamento);
+                    builder.ins().ireduce(types::I8, v)
+                }
+            };
+            valores.push(valor);
+        }
+        let chamada = builder.ins().call(alvo_ref, &valores);
+        let resultado = match (
+            funcao.retorno,
+            builder.inst_results(chamada).first().copied(),
+        ) {
+            (Some(TipoJit::Duplo), Some(v)) => {
+                builder.ins().bitcast(types::I64, MemFlags::new(), v)
+            }
+            (Some(TipoJit::Booleano), Some(v)) => builder.ins().uextend(types::I64, v),
+            (Some(TipoJit::Inteiro), Some(v)) => v,
+            _ => builder.ins().iconst(types::I64, 0),
+        };
+        builder.ins().return_(&[resultado]);
+        builder.seal_all_blocks();
+        builder.finalize();
+        self.finalizar_definicao(id)?;
+        Ok(id)
+    }
+
+    /// Executa uma especialização compilada com argumentos dos tipos da assinatura.
+    pub fn executar(
+        &self,
+        funcao: &FuncaoCompilada,
+        args: &[ValorJit],
+    ) -> Result<Option<ValorJit>, String> {
+        debug_assert!(args.iter().map(ValorJit::tipo).eq(funcao
+            .assinatura
+            .parametros
+            .iter()
+            .copied()));
+        let brutos: Vec<u64> = args
+            .iter()
+            .map(|a| match a {
+                ValorJit::Inteiro(n) => *n as u64,
+                ValorJit::Duplo(x) => x.to_bits(),
+                ValorJit::Booleano(b) => *b as u64,
+            })
+            .collect();
+        let mut erro: i32 = 0;
+        // SAFETY: `entrada` foi gerada por `definir_entrada` com exatamente esta assinatura, lê
+        // um argumento de 8 bytes por parâmetro e o módulo que a contém vive enquanto `self`.
+        let bruto = unsafe {
+            let f: extern "C" fn(*const u64, *mut i32) -> u64 = std::mem::transmute(funcao.entrada);
+            f(brutos.as_ptr(), &mut erro)
+        };
+        if erro != 0 {
+            return Err(mensagem_erro(erro));
+        }
+        Ok(funcao.retorno.map(|tipo| match tipo {
+            TipoJit::Inteiro => ValorJit::Inteiro(bruto as i64),
+            TipoJit::Duplo => ValorJit::Duplo(f64::from_bits(bruto)),
+            TipoJit::Booleano => ValorJit::Booleano(bruto != 0),
+        }))
+    }
+}
+
+/// Traduz as instruções de uma função. A pilha da VM vira um conjunto de variáveis
+/// Cranelift, uma por (profundidade, tipo); o construtor SSA elimina as cópias.
+struct Tradutor<'a, 'b> {
+    b: &'a mut FunctionBuilder<'b>,
+    funcao: &'a FuncaoAnalisada,
+    chamadas: HashMap<Assinatura, (cranelift_codegen::ir::FuncRef, Option<TipoJit>)>,
+    blocos: BTreeMap<usize, Block>,
+    erros: HashMap<i64, Block>,
+    locais: HashMap<String, Variable>,
+    pilha: HashMap<(usize, TipoJit), Variable>,
+    proxima_variavel: u32,
+    ponteiro_erro: Value,
+}
+
+impl<'a, 'b> Tradutor<'a, 'b> {
+    fn new(
+        b: &'a mut FunctionBuilder<'b>,
+        funcao: &'a FuncaoAnalisada,
+        chamadas: HashMap<Assinatura, (cranelift_codegen::ir::FuncRef, Option<TipoJit>)>,
+    ) -> Self {
+        let entrada = b.create_block();
+        b.append_block_params_for_function_params(entrada);
+        b.switch_to_block(entrada);
+        let ponteiro_erro = b.block_params(entrada)[0];
+
+        let mut t = Self {
+            b,
+            funcao,
+            chamadas,
+            blocos: BTreeMap::new(),
+            erros: HashMap::new(),
+            locais: HashMap::new(),
+            pilha: HashMap::new(),
+            proxima_variavel: 0,
+            ponteiro_erro,
+        };
+        for (nome, tipo) in &funcao.locais {
+            let var = t.nova_variavel(*tipo);
+            t.locais.insert(nome.clone(), var);
+        }
+        for (i, nome) in funcao.parametros.iter().enumerate() {
+            let valor = t.b.block_params(entrada)[i + 1];
+            t.b.def_var(t.locais[nome], valor);
+        }
+        t
+    }
+
+    fn nova_variavel(&mut self, tipo: TipoJit) -> Variable {
+        let var = Variable::from_u32(self.proxima_variavel);
+        self.proxima_variavel += 1;
+        self.b.declare_var(var, tipo_nativo(tipo));
+        var
+    }
+
+    fn empilhar(&mut self, profundidade: usize, tipo: TipoJit, valor: Value) {
+        let var = match self.pilha.get(&(profundidade, tipo)) {
+            Some(v) => *v,
+            None => {
+                let v = self.nova_variavel(tipo);
+                self.pilha.insert((profundidade, tipo), v);
+                v
+            }
+        };
+        self.b.def_var(var, valor);
+    }
+
+    fn ler(&mut self, profundidade: usize, tipo: TipoJit) -> Value {
+        let var = self.pilha[&(profundidade, tipo)];
+        self.b.use_var(var)
+    }
+
+    fn zero(&mut self, tipo: TipoJit) -> Value {
+        match tipo {
+            TipoJit::Inteiro => self.b.ins().iconst(types::I64, 0),
+            TipoJit::Duplo => self.b.ins().f64const(0.0),
+            TipoJit::Booleano => self.b.ins().iconst(types::I8, 0),
+        }
+    }
+
+    /// Bloco que grava `codigo` em `*erro` (0 = erro já gravado por uma chamada) e retorna.
+    /// O conteúdo é emitido no fim, por `preencher_erros`.
+    fn bloco_erro(&mut self, codigo: i64) -> Block {
+        if let Some(bloco) = self.erros.get(&codigo) {
+            return *bloco;
+        }
+        let bloco = self.b.create_block();
+        self.erros.insert(codigo, bloco);
+        bloco
+    }
+
+    fn preencher_erros(&mut self) {
+        let mut erros: Vec<(i64, Block)> = self.erros.iter().map(|(c, b)| (*c, *b)).collect();
+        erros.sort_by_key(|(c, _)| *c);
+        for (codigo, bloco) in erros {
+            self.b.switch_to_block(bloco);
+            if codigo != 0 {
+                let v = self.b.ins().iconst(types::I32, codigo);
+                self.b
+                    .ins()
+                    .store(MemFlags::trusted(), v, self.ponteiro_erro, 0);
+            }
+            match self.funcao.retorno {
+                Some(tipo) => {
+                    let z = self.zero(tipo);
+                    self.b.ins().return_(&[z]);
+                }
+                None => {
+                    self.b.ins().return_(&[]);
+                }
+            }
+        }
+    }
+
+    /// Desvia para o bloco de erro quando `condicao` é verdadeira.
+    fn verificar(&mut self, condicao: Value, codigo: i64) {
+        let erro = self.bloco_erro(codigo);
+        let continua = self.b.create_block();
+        self.b.ins().brif(condicao, erro, &[], continua, &[]);
+        self.b.switch_to_block(continua);
+    }
+
+    fn bloco_em(&mut self, ip: usize) -> Block {
+        self.blocos[&ip]
+    }
+
+    fn traduzir(&mut self) {
+        let fim = self.funcao.instrucoes.len();
+        // Início de bloco: alvos de salto e instruções após saltos/retornos
+        let mut lideres: HashSet<usize> = HashSet::new();
+        for (ip, instrucao) in self.funcao.instrucoes.iter().enumerate() {
+            match instrucao {
+                Instrucao::Saltar(alvo) | Instrucao::SaltarSeFalso(alvo) => {
+                    lideres.insert(*alvo);
+                    lideres.insert(ip + 1);
+                }
+                Instrucao::Retornar => {
+                    lideres.insert(ip + 1);
+                }
+                _ => {}
+            }
+        }
+        for ip in lideres {
+            if self.funcao.pilhas.get(ip).is_some_and(Option::is_some) {
+                let bloco = self.b.create_block();
+                self.blocos.insert(ip, bloco);
+            }
+        }
+        let primeiro = self.bloco_em_ou_novo(0);
+        self.b.ins().jump(primeiro, &[]);
+
+        let mut aberto = false;
+        for ip in 0..=fim {
+            if let Some(bloco) = self.blocos.get(&ip).copied() {
+                if aberto {
+                    self.b.ins().jump(bloco, &[]);
+                }
+                self.b.switch_to_block(bloco);
+                aberto = true;
+            }
+            let Some(pilha) = self.funcao.pilhas[ip].clone() else {
+                continue;
+            };
+            if !aberto {
+                continue;
+            }
+            if ip == fim {
+                self.retornar(&pilha);
+                aberto = false;
+                continue;
+            }
+            aberto = self.instrucao(ip, &pilha);
+        }
+        self.preencher_erros();
+    }
+
+    fn bloco_em_ou_novo(&mut self, ip: usize) -> Block {
+        if let Some(b) = self.blocos.get(&ip) {
+            return *b;
+        }
+        let bloco = self.b.create_block();
+        self.blocos.insert(ip, bloco);
+        bloco
+    }
+
+    fn retornar(&mut self, pilha: &[TipoJit]) {
+        match pilha.last() {
+            Some(tipo) if self.funcao.retorno.is_some() => {
+                let v = self.ler(pilha.len() - 1, *tipo);
+                self.b.ins().return_(&[v]);
+            }
+            _ => {
+                self.b.ins().return_(&[]);
+            }
+        }
+    }
+
+    fn para_duplo(&mut self, valor: Value, tipo: TipoJit) -> Value {
+        match tipo {
+            TipoJit::Inteiro => self.b.ins().fcvt_from_sint(types::F64, valor),
+            _ => valor,
+        }
+    }
+
+    /// Traduz a instrução em `ip`; devolve `false` se ela encerra o bloco.
+    fn instrucao(&mut self, ip: usize, pilha: &[TipoJit]) -> bool {
+        let topo = pilha.len();
+        match &self.funcao.instrucoes[ip] {
+            Instrucao::Nada => {}
+            Instrucao::ConstInteiro(n) => {
+                let v = self.b.ins().iconst(types::I64, *n);
+                self.empilhar(topo, TipoJit::Inteiro, v);
+            }
+            Instrucao::ConstDuplo(x) => {
+                let v = self.b.ins().f64const(*x);
+                self.empilhar(topo, TipoJit::Duplo, v);
+            }
+            Instrucao::ConstBooleano(x) => {
+                let v = self.b.ins().iconst(types::I8, *x as i64);
+                self.empilhar(topo, TipoJit::Booleano, v);
+            }
+            Instrucao::Carregar(nome) => {
+                let tipo = self.funcao.locais[nome];
+                let v = self.b.use_var(self.locais[nome]);
+                self.empilhar(topo, tipo, v);
+            }
+            Instrucao::Armazenar(nome) => {
+                let v = self.ler(topo - 1, pilha[topo - 1]);
+                self.b.def_var(self.locais[nome], v);
+            }
+            Instrucao::Aritmetica(op) => {
+                let (ta, tb) = (pilha[topo - 2], pilha[topo - 1]);
+                let a = self.ler(topo - 2, ta);
+                let b = self.ler(topo - 1, tb);
+                let (tipo, v) = self.aritmetica(*op, a, ta, b, tb);
+                self.empilhar(topo - 2, tipo, v);
+            }
+            Instrucao::NegarNumero => {
+                let tipo = pilha[topo - 1];
+                let a = self.ler(topo - 1, tipo);
+                let v = match tipo {
+                    TipoJit::Duplo => self.b.ins().fneg(a),
+                    _ => self.b.ins().ineg(a),
+                };
+                self.empilhar(topo - 1, tipo, v);
+            }
+            Instrucao::NegarLogico => {
+                let a = self.ler(topo - 1, TipoJit::Booleano);
+                let v = self.b.ins().bxor_imm(a, 1);
+                self.empilhar(topo - 1, TipoJit::Booleano, v);
+            }
+            Instrucao::Comparar(op) => {
+                let (ta, tb) = (pilha[topo - 2], pilha[topo - 1]);
+                let a = self.ler(topo - 2, ta);
+                let b = self.ler(topo - 1, tb);
+                let v = self.comparar(*op, a, ta, b, tb);
+                self.empilhar(topo - 2, TipoJit::Booleano, v);
+            }
+            Instrucao::Saltar(alvo) => {
+                let bloco = self.bloco_em(*alvo);
+                self.b.ins().jump(bloco, &[]);
+                return false;
+            }
+            Instrucao::SaltarSeFalso(alvo) => {
+                let condicao = self.ler(topo - 1, TipoJit::Booleano);
+                let (falso, verdadeiro) = (self.bloco_em(*alvo), self.bloco_em(ip + 1));
+                self.b.ins().brif(condicao, verdadeiro, &[], falso, &[]);
+                return false;
+            }
+            Instrucao::Chamar(_, n) => {
+                let assinatura = self.funcao.chamada_em(ip).expect("chamada analisada");
+                let (fref, retorno) = self.chamadas[&assinatura];
+                let mut args = vec![self.ponteiro_erro];
+                for (i, tipo) in assinatura.parametros.iter().enumerate() {
+                    args.push(self.ler(topo - n + i, *tipo));
+                }
+                let inst = self.b.ins().call(fref, &args);
+                let resultado = self.b.inst_results(inst).first().copied();
+
+                let codigo =
+                    self.b
+                        .ins()
+                        .load(types::I32, MemFlags::trusted(), self.ponteiro_erro, 0);
+                self.verificar(codigo, 0);
+                if let (Some(tipo), Some(v)) = (retorno, resultado) {
+                    self.empilhar(topo - n, tipo, v);
+                }
+            }
+            Instrucao::Retornar => {
+                self.retornar(pilha);
+                return false;
+            }
+            Instrucao::Descartar => {}
+        }
+        true
+    }
 
-        let code = self.module.get_finalized_function(func_id);
-        Ok(JitHandle { fn_ptr: code })
+    fn aritmetica(
+        &mut self,
+        op: OpAritmetica,
+        a: Value,
+        ta: TipoJit,
+        b: Value,
+        tb: TipoJit,
+    ) -> (TipoJit, Value) {
+        if ta == TipoJit::Inteiro && tb == TipoJit::Inteiro {
+            let v = match op {
+                OpAritmetica::Soma => self.b.ins().iadd(a, b),
+                OpAritmetica::Subtracao => self.b.ins().isub(a, b),
+                OpAritmetica::Multiplicacao => self.b.ins().imul(a, b),
+                OpAritmetica::Divisao | OpAritmetica::Resto => {
+                    let zero = self.b.ins().icmp_imm(IntCC::Equal, b, 0);
+                    let codigo = if op == OpAritmetica::Divisao {
+                        ERRO_DIVISAO_POR_ZERO
+                    } else {
+                        ERRO_MODULO_POR_ZERO
+                    };
+                    self.verificar(zero, codigo);
+                    // i64::MIN / -1 não cabe em i64 (a instrução nativa geraria uma exceção)
+                    let minimo = self.b.ins().icmp_imm(IntCC::Equal, a, i64::MIN);
+                    let menos_um = self.b.ins().icmp_imm(IntCC::Equal, b, -1);
+                    let estouro = self.b.ins().band(minimo, menos_um);
+                    self.verificar(estouro, ERRO_ESTOURO);
+                    if op == OpAritmetica::Divisao {
+                        self.b.ins().sdiv(a, b)
+                    } else {
+                        self.b.ins().srem(a, b)
+                    }
+                }
+            };
+            return (TipoJit::Inteiro, v);
+        }
+        let a = self.para_duplo(a, ta);
+        let b = self.para_duplo(b, tb);
+        let v = match op {
+            OpAritmetica::Soma => self.b.ins().fadd(a, b),
+            OpAritmetica::Subtracao => self.b.ins().fsub(a, b),
+            OpAritmetica::Multiplicacao => self.b.ins().fmul(a, b),
+            OpAritmetica::Divisao | OpAritmetica::Resto => {
+                let zero = self.b.ins().f64const(0.0);
+                let eh_zero = self.b.ins().fcmp(FloatCC::Equal, b, zero);
+                self.verificar(eh_zero, ERRO_DIVISAO_POR_ZERO);
+                self.b.ins().fdiv(a, b)
+            }
+        };
+        (TipoJit::Duplo, v)
     }
 
-    // Executa soma_i32 compilada: safety: o ponteiro é válido para a assinatura declarada.
-    pub unsafe fn chamar_soma_i32(&self, handle: &JitHandle, x: i32, y: i32) -> i32 {
-        let f: extern "C" fn(i32, i32) -> i32 = std::mem::transmute(handle.fn_ptr);
-        f(x, y)
+    fn comparar(
+        &mut self,
+        op: OpComparacao,
+        a: Value,
+        ta: TipoJit,
+        b: Value,
+        tb: TipoJit,
+    ) -> Value {
+        let igualdade = matches!(op, OpComparacao::Igual | OpComparacao::Diferente);
+        // Na VM, valores de tipos diferentes nunca são iguais (1 == 1.0 é falso)
+        if igualdade && ta != tb {
+            return self
+                .b
+                .ins()
+                .iconst(types::I8, (op == OpComparacao::Diferente) as i64);
+        }
+        if ta != TipoJit::Duplo && tb != TipoJit::Duplo {
+            let cc = match op {
+                OpComparacao::Igual => IntCC::Equal,
+                OpComparacao::Diferente => IntCC::NotEqual,
+                OpComparacao::Menor => IntCC::SignedLessThan,
+                OpComparacao::Maior => IntCC::SignedGreaterThan,
+                OpComparacao::MenorIgual => IntCC::SignedLessThanOrEqual,
+                OpComparacao::MaiorIgual => IntCC::SignedGreaterThanOrEqual,
+            };
+            return self.b.ins().icmp(cc, a, b);
+        }
+        let a = self.para_duplo(a, ta);
+        let b = self.para_duplo(b, tb);
+        let cc = match op {
+            OpComparacao::Igual => FloatCC::Equal,
+            OpComparacao::Diferente => FloatCC::NotEqual,
+            OpComparacao::Menor => FloatCC::LessThan,
+            OpComparacao::Maior => FloatCC::GreaterThan,
+            OpComparacao::MenorIgual => FloatCC::LessThanOrEqual,
+            OpComparacao::MaiorIgual => FloatCC::GreaterThanOrEqual,
+        };
+        self.b.ins().fcmp(cc, a, b)
     }
 }
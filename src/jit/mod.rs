@@ -1,17 +1,37 @@
 // JIT engine (feature-gated) usando Cranelift.
-// Fase 1: esqueleto mínimo que permite compilar uma função "soma" para nativo como prova de conceito.
+// A análise do bytecode (`analise`) não depende do Cranelift; a tradução para código nativo
+// (`cranelift_engine`) e o controle de funções quentes (`nivelado`) só existem com a feature `jit`.
 
+pub mod analise;
 #[cfg(feature = "jit")]
 pub mod cranelift_engine;
+#[cfg(feature = "jit")]
+pub mod nivelado;
 
 #[derive(Debug)]
 pub enum JitError {
-    NaoSuportado(&'static str),
+    NaoSuportado(String),
     Interno(String),
 }
 
+impl std::fmt::Display for JitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JitError::NaoSuportado(m) => write!(f, "não suportado: {}", m),
+            JitError::Interno(m) => write!(f, "erro interno: {}", m),
+        }
+    }
+}
+
+pub use analise::{FuncaoBytecode, TipoJit, ValorJit};
+
 #[cfg(feature = "jit")]
-pub use cranelift_engine::{CraneliftJit, JitHandle};
+pub use cranelift_engine::{CraneliftJit, FuncaoCompilada};
+#[cfg(feature = "jit")]
+pub use nivelado::JitNivelado;
+
+/// Número de chamadas a partir do qual uma função é considerada quente.
+pub const LIMITE_PADRAO: u32 = 50;
 
 #[cfg(not(feature = "jit"))]
 pub struct CraneliftJit;
@@ -19,6 +39,33 @@ pub struct CraneliftJit;
 #[cfg(not(feature = "jit"))]
 impl CraneliftJit {
     pub fn new() -> Result<Self, JitError> {
-        Err(JitError::NaoSuportado("compilado sem feature 'jit'"))
+        Err(JitError::NaoSuportado(
+            "compilado sem feature 'jit'".to_string(),
+        ))
+    }
+}
+
+#[cfg(not(feature = "jit"))]
+pub struct JitNivelado;
+
+#[cfg(not(feature = "jit"))]
+impl JitNivelado {
+    pub fn new(_limite: u32) -> Result<Self, JitError> {
+        Err(JitError::NaoSuportado(
+            "compilado sem feature 'jit'".to_string(),
+        ))
+    }
+
+    pub fn chamar<'a>(
+        &mut self,
+        _nome: &str,
+        _args: &[ValorJit],
+        _buscar: &dyn Fn(&str) -> Option<FuncaoBytecode<'a>>,
+    ) -> Option<Result<Option<ValorJit>, String>> {
+        None
+    }
+
+    pub fn resumo(&self) -> String {
+        String::new()
     }
 }
@@ -0,0 +1,166 @@
+// Execução em níveis: a VM interpreta todas as funções e conta as chamadas de cada uma.
+// Quando uma função fica quente, ela é especializada para os tipos dos argumentos da chamada
+// atual e compilada; chamadas seguintes com os mesmos tipos vão direto ao código nativo.
+// Funções não elegíveis continuam no interpretador (a tentativa não é repetida).
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+
+use super::analise::{self, Assinatura, FuncaoBytecode, ValorJit};
+use super::cranelift_engine::{CraneliftJit, FuncaoCompilada};
+use super::JitError;
+
+enum Estado {
+    Compilada(FuncaoCompilada, u64),
+    NaoElegivel(String),
+}
+
+pub struct JitNivelado {
+    motor: CraneliftJit,
+    limite: u32,
+    chamadas: HashMap<String, u32>,
+    especializacoes: HashMap<Assinatura, Estado>,
+    /// Ordem em que as especializações foram decididas (para o resumo).
+    ordem: Vec<Assinatura>,
+}
+
+impl JitNivelado {
+    pub fn new(limite: u32) -> Result<Self, JitError> {
+        Ok(Self {
+            motor: CraneliftJit::new()?,
+            limite,
+            chamadas: HashMap::new(),
+            especializacoes: HashMap::new(),
+            ordem: Vec::new(),
+        })
+    }
+
+    /// Conta a chamada e, se houver código nativo para `nome` com estes argumentos, executa-o.
+    /// `None` significa "interprete normalmente"; `Some(Ok(None))` é uma função sem retorno.
+    pub fn chamar<'a>(
+        &mut self,
+        nome: &str,
+        args: &[ValorJit],
+        buscar: &dyn Fn(&str) -> Option<FuncaoBytecode<'a>>,
+    ) -> Option<Result<Option<ValorJit>, String>> {
+        let assinatura = Assinatura {
+            nome: nome.to_string(),
+            parametros: args.iter().map(ValorJit::tipo).collect(),
+        };
+        if !self.especializacoes.contains_key(&assinatura) {
+            let contador = self.chamadas.entry(nome.to_string()).or_insert(0);
+            *contador += 1;
+            if *contador < self.limite {
+                return None;
+            }
+            self.compilar(&assinatura, buscar);
+        }
+        match self.especializacoes.get_mut(&assinatura)? {
+            Estado::Compilada(funcao, execucoes) => {
+                *execucoes += 1;
+                Some(self.motor.executar(funcao, args))
+            }
+            Estado::NaoElegivel(_) => None,
+        }
+    }
+
+    fn compilar<'a>(
+        &mut self,
+        raiz: &Assinatura,
+        buscar: &dyn Fn(&str) -> Option<FuncaoBytecode<'a>>,
+    ) {
+        let motor = &self.motor;
+        let resultado = analise::planejar(raiz, buscar, &|a| motor.retorno_compilado(a))
+            .and_then(|plano| self.motor.compilar(&plano));
+        match resultado {
+            Ok(compiladas) => {
+                for funcao in compiladas {
+                    let assinatura = funcao.assinatura.clone();
+                    self.ordem.push(assinatura.clone());
+                    self.especializacoes
+                        .insert(assinatura, Estado::Compilada(funcao, 0));
+                }
+            }
+            Err(e) => {
+                self.ordem.push(raiz.clone());
+                self.especializacoes
+                    .insert(raiz.clone(), Estado::NaoElegivel(e.to_string()));
+            }
+        }
+    }
+
+    /// Especializações compiladas e rejeitadas, na ordem em que foram decididas.
+    pub fn resumo(&self) -> String {
+        let mut texto = String::new();
+        for assinatura in &self.ordem {
+            match &self.especializacoes[assinatura] {
+                Estado::Compilada(funcao, execucoes) => {
+                    let retorno = funcao
+                        .retorno
+                        .map(|t| t.to_string())
+                        .unwrap_or_else(|| "vazio".to_string());
+                    let _ = writeln!(
+                        texto,
+                        "[JIT] compilada: {} -> {} ({} chamada(s) da VM)",
+                        assinatura, retorno, execucoes
+                    );
+                }
+                Estado::NaoElegivel(motivo) => {
+                    let _ = writeln!(texto, "[JIT] interpretada: {}", motivo);
+                }
+            }
+        }
+        texto
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compila_apos_limite_e_executa_nativo() {
+        let parametros = vec!["n".to_string()];
+        let corpo: Vec<String> = "LOAD_VAR n
+             LOAD_CONST_INT 2
+             COMPARE_LT
+             JUMP_IF_FALSE 6
+             LOAD_VAR n
+             RETURN
+             LOAD_VAR n
+             LOAD_CONST_INT 1
+             SUB
+             CALL_FUNCTION Fib 1
+             LOAD_VAR n
+             LOAD_CONST_INT 2
+             SUB
+             CALL_FUNCTION Fib 1
+             ADD
+             RETURN"
+            .lines()
+            .map(|l| l.trim().to_string())
+            .collect();
+        let buscar = |nome: &str| {
+            (nome == "Fib").then_some(FuncaoBytecode {
+                parametros: &parametros,
+                corpo: &corpo,
+            })
+        };
+        let mut jit = JitNivelado::new(3).unwrap();
+        let args = [ValorJit::Inteiro(20)];
+        assert!(jit.chamar("Fib", &args, &buscar).is_none());
+        assert!(jit.chamar("Fib", &args, &buscar).is_none());
+        assert_eq!(
+            jit.chamar("Fib", &args, &buscar),
+            Some(Ok(Some(ValorJit::Inteiro(6765))))
+        );
+        // Outra especialização (duplo) conta a partir do contador já quente
+        assert_eq!(
+            jit.chamar("Fib", &[ValorJit::Duplo(10.0)], &buscar),
+            Some(Ok(Some(ValorJit::Duplo(55.0))))
+        );
+        assert!(jit
+            .resumo()
+            .contains("[JIT] compilada: Fib(inteiro) -> inteiro"));
+    }
+}
@@ -37,6 +37,13 @@ pub enum Token {
     TEspaco,
     #[token("usando")]
     TUsando,
+    // Módulos: importar { A, B } de "utilidades";
+    #[token("importar")]
+    TImportar,
+    #[token("exportar")]
+    TExportar,
+    #[token("de")]
+    TDe,
 
     /* tipos */
     #[token("inteiro")]
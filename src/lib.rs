@@ -13,18 +13,29 @@
 use std::path::PathBuf;
 
 // Declarar módulos principais
+pub mod artefato;
+pub mod avaliacao;
 pub mod ast;
 pub mod error;
+pub mod formatador;
 pub mod lexer;
-// pub mod runtime; // Comentado se não estiver em uso
+pub mod runtime;
 pub mod codegen;
+pub mod compatibilidade;
+pub mod incremental;
 pub mod inferencia_tipos;
 pub mod interpolacao;
 pub mod jit;
 pub mod library_loader;
+pub mod linter;
 pub mod module_system;
 pub mod ownership;
+pub mod pacotes;
+pub mod paralelo;
+pub mod projeto;
 pub mod stdlib;
+pub mod teste_de_mesa;
+pub mod testes;
 pub mod type_checker;
 
 // Parser usando LALRPOP
@@ -40,12 +51,7 @@ pub use type_checker::VerificadorTipos;
 
 // Removida a importação do `BackendType`, que não é mais público.
 pub use codegen::GeradorCodigo;
-// pub use runtime::{
-//     executar_programa_otimizado,
-//     executar_programa_debug,
-//     interpretar_com_classes,
-//     validar_programa
-// };
+pub use runtime::executar_programa_otimizado;
 
 // Estrutura principal do compilador
 pub struct CompiladorPortugues<'a> {
@@ -84,7 +90,7 @@ impl<'a> CompiladorPortugues<'a> {
 
             // Parsing
             let parser = parser::ArquivoParser::new();
-            match parser.parse(tokens.iter().cloned()) {
+            match parser.parse(None, tokens.iter().cloned()) {
                 Ok(mut ast) => {
                     // Interpolação de strings
                     interpolacao::walk_programa(&mut ast, |e| {
@@ -8,67 +8,156 @@
 //! O compilador usa apenas o manifesto para verificação de tipos, sem carregar o bytecode
 //! completo na memória — equivalente ao mecanismo de Reference Assemblies do .NET.
 
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::{self, BufRead, BufReader};
+use crate::artefato;
+use crate::ast::Tipo;
+use std::collections::{HashMap, HashSet};
+use std::io;
 use std::path::Path;
 
-#[derive(Debug, Clone)]
+/// Acesso de um membro exportado. Membros privados não entram no manifesto.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LibAcesso {
+    #[default]
+    Publico,
+    Protegido,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LibParametro {
+    pub tipo: String,
+    pub nome: String,
+    /// Valor padrão como escrito na biblioteca; com ele, o argumento é opcional.
+    pub padrao: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct LibMetodo {
     pub nome: String,
     pub nome_classe: String,
     pub tipo_retorno: String,
-    pub parametros: Vec<(String, String)>, // Vec<(tipo, nome)>
+    pub parametros: Vec<LibParametro>,
     pub aridade: usize,
     /// Para métodos marcados com [Nativo]: chave de despacho no runtime (ex. "Console::EscreverLinha")
     pub chave_nativa: Option<String>,
     pub eh_estatica: bool,
+    pub acesso: LibAcesso,
+    pub eh_abstrato: bool,
+    pub eh_redefinivel: bool,
+    pub eh_sobrescrita: bool,
+    /// Parâmetros de tipo do próprio método: `T Primeiro<T>(lista<T> itens)`
+    pub genericos: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct LibPropriedade {
     pub nome: String,
     pub tipo: String,
+    pub eh_estatica: bool,
+    pub acesso: LibAcesso,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct LibCampo {
     pub nome: String,
     pub tipo: String,
+    pub eh_estatica: bool,
+    pub acesso: LibAcesso,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
+pub struct LibConstrutor {
+    pub parametros: Vec<LibParametro>,
+    pub acesso: LibAcesso,
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct LibClasse {
     pub fqn: String, // Full Qualified Name, e.g., "Sistema.Colecoes.Dicionario"
     pub nome: String,
+    /// Classe base, possivelmente aplicada (`Geo.Forma`, `Util.Caixa<texto>`)
     pub nome_pai: Option<String>,
     pub metodos: HashMap<String, LibMetodo>,
     pub propriedades: Vec<LibPropriedade>,
     pub campos: Vec<LibCampo>,
+    pub construtores: Vec<LibConstrutor>,
     pub eh_estatica: bool,
+    pub eh_abstrata: bool,
+    pub genericos: Vec<String>,
+    /// Interfaces implementadas, como tipos do manifesto (`Geo.IMedida<inteiro>`)
+    pub interfaces: Vec<String>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
+pub struct LibInterface {
+    pub fqn: String,
+    pub genericos: Vec<String>,
+    pub metodos: HashMap<String, LibMetodo>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LibEnum {
+    pub valores: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct LibFuncao {
     pub nome: String,
     pub aridade: usize,
+    pub tipo_retorno: String,
+    pub parametros: Vec<LibParametro>,
+    pub genericos: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
 pub enum LibSimbolo {
     Classe(LibClasse),
+    Interface(LibInterface),
+    Enum(LibEnum),
     Funcao(LibFuncao),
 }
 
+/// Quantos argumentos uma chamada com esses parâmetros precisa ter, no mínimo.
+pub fn obrigatorios(parametros: &[LibParametro]) -> usize {
+    parametros.iter().filter(|p| p.padrao.is_none()).count()
+}
+
 #[derive(Debug, Default, Clone)]
 pub struct Biblioteca {
     pub simbolos: HashMap<String, LibSimbolo>,
+    /// `nome=` e `versao=` do cabeçalho `[PBL]`
+    pub nome: Option<String>,
+    pub versao: Option<String>,
 }
 
 impl Biblioteca {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Acrescenta os símbolos de outra biblioteca (ex.: dependências junto da stdlib).
+    /// Em nomes repetidos, prevalece o já carregado.
+    pub fn mesclar(&mut self, outra: Biblioteca) {
+        for (fqn, simbolo) in outra.simbolos {
+            self.simbolos.entry(fqn).or_insert(simbolo);
+        }
+    }
+
+    /// Namespaces declarados pelos símbolos (`Sistema.IO.Arquivo` → `Sistema.IO` e `Sistema`).
+    pub fn namespaces(&self) -> HashSet<String> {
+        let mut namespaces = HashSet::new();
+        for fqn in self.simbolos.keys() {
+            let partes: Vec<&str> = fqn.split('.').collect();
+            let mut acumulado = String::new();
+            for parte in &partes[..partes.len() - 1] {
+                if !acumulado.is_empty() {
+                    acumulado.push('.');
+                }
+                acumulado.push_str(parte);
+                namespaces.insert(acumulado.clone());
+            }
+        }
+        namespaces
+    }
 }
 
 // ============================================================================
@@ -85,45 +174,70 @@ pub fn carregar_biblioteca(caminho: &Path) -> io::Result<Biblioteca> {
     }
 }
 
+/// Linhas da seção `[BYTECODE]` de um `.pbl`, carregadas pela VM junto com o programa.
+#[allow(dead_code)] // só o interpretador usa; o binário do compilador inclui o módulo também
+pub fn ler_bytecode_pbl(caminho: &Path) -> io::Result<Vec<String>> {
+    let artefato = ler_artefato(caminho)?;
+    Ok(artefato
+        .corpo()
+        .lines()
+        .skip_while(|l| l.trim() != "[BYTECODE]")
+        .skip(1)
+        .filter(|l| !l.trim().is_empty())
+        .map(String::from)
+        .collect())
+}
+
 // ============================================================================
 // Formato .pbl (Biblioteca Por do Sol)
 // ============================================================================
 
+#[derive(PartialEq)]
+enum Secao {
+    Cabecalho,
+    Manifesto,
+    Bytecode,
+}
+
+/// Lê o arquivo conferindo formato, hash e assinatura (ver [`artefato`]).
+fn ler_artefato(caminho: &Path) -> io::Result<artefato::Artefato> {
+    artefato::ler(caminho).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
 fn carregar_pbl(caminho: &Path) -> io::Result<Biblioteca> {
-    let conteudo = std::fs::read_to_string(caminho)?;
+    Ok(ler_manifesto(&ler_artefato(caminho)?.conteudo))
+}
+
+/// Lê a seção `[MANIFESTO]` do conteúdo de um `.pbl`.
+pub fn ler_manifesto(conteudo: &str) -> Biblioteca {
     let mut biblioteca = Biblioteca::new();
-    let mut em_manifesto = false;
+    let mut secao = Secao::Cabecalho;
 
     for linha in conteudo.lines() {
         let linha = linha.trim();
         match linha {
-            "[MANIFESTO]" => {
-                em_manifesto = true;
-                continue;
-            }
-            "[BYTECODE]" | "[PBL]" => {
-                em_manifesto = false;
-                continue;
+            "[PBL]" => secao = Secao::Cabecalho,
+            "[MANIFESTO]" => secao = Secao::Manifesto,
+            "[BYTECODE]" => secao = Secao::Bytecode,
+            _ if secao == Secao::Bytecode => {}
+            _ if linha.is_empty() || linha.starts_with(';') || linha.starts_with('#') => {}
+            // Metadados do cabeçalho; manifestos antigos os repetiam dentro da seção
+            _ if linha.contains('=') && !linha.contains(char::is_whitespace) => {
+                match linha.split_once('=') {
+                    Some(("nome", nome)) => biblioteca.nome = Some(nome.to_string()),
+                    Some(("versao", versao)) => biblioteca.versao = Some(versao.to_string()),
+                    _ => {}
+                }
             }
-            _ => {}
-        }
-        if linha.is_empty() || linha.starts_with(';') || linha.starts_with('#') {
-            continue;
-        }
-        // Ignorar metadados de cabeçalho (nome=, versao=, …)
-        if linha.contains('=')
-            && !linha.starts_with("DEFINE")
-            && !linha.starts_with("PROPERTY")
-            && !linha.starts_with("FIELD")
-        {
-            continue;
+            _ if secao == Secao::Cabecalho => {}
+            _ => processar_linha_manifesto(linha, &mut biblioteca),
         }
-        if em_manifesto {
-            processar_linha_manifesto(linha, &mut biblioteca);
+        if secao == Secao::Bytecode {
+            break;
         }
     }
 
-    Ok(biblioteca)
+    biblioteca
 }
 
 fn processar_linha_manifesto(linha: &str, biblioteca: &mut Biblioteca) {
@@ -131,8 +245,12 @@ fn processar_linha_manifesto(linha: &str, biblioteca: &mut Biblioteca) {
     if partes.is_empty() {
         return;
     }
+    // Argumentos fixos de cada diretiva; o que vem depois são parâmetros (`tipo:nome`)
+    // e marcadores (`abstrata`, `acesso=protegido`, `genericos=T,U`, …).
+    let resto = |fixos: usize| Marcadores::ler(partes.get(fixos..).unwrap_or(&[]));
 
     match partes[0] {
+        // DEFINE_CLASS <fqn> <pai|NULO> [abstrata] [genericos=T,U] [interfaces=A.I,B.J<T>]
         "DEFINE_CLASS" => {
             if let Some(fqn) = partes.get(1) {
                 let nome_pai = partes.get(2).and_then(|&p| {
@@ -142,15 +260,15 @@ fn processar_linha_manifesto(linha: &str, biblioteca: &mut Biblioteca) {
                         Some(p.to_string())
                     }
                 });
-                let nome_simples = fqn.split('.').last().unwrap_or(fqn).to_string();
+                let (_, marcadores) = resto(3);
                 let classe = LibClasse {
                     fqn: fqn.to_string(),
-                    nome: nome_simples,
+                    nome: nome_simples(fqn),
                     nome_pai,
-                    metodos: HashMap::new(),
-                    propriedades: Vec::new(),
-                    campos: Vec::new(),
-                    eh_estatica: false,
+                    eh_abstrata: marcadores.tem("abstrata"),
+                    genericos: marcadores.lista("genericos"),
+                    interfaces: marcadores.lista("interfaces"),
+                    ..Default::default()
                 };
                 biblioteca
                     .simbolos
@@ -159,81 +277,117 @@ fn processar_linha_manifesto(linha: &str, biblioteca: &mut Biblioteca) {
         }
         "DEFINE_STATIC_CLASS" => {
             if let Some(fqn) = partes.get(1) {
-                let nome_simples = fqn.split('.').last().unwrap_or(fqn).to_string();
                 let classe = LibClasse {
                     fqn: fqn.to_string(),
-                    nome: nome_simples,
-                    nome_pai: None,
-                    metodos: HashMap::new(),
-                    propriedades: Vec::new(),
-                    campos: Vec::new(),
+                    nome: nome_simples(fqn),
                     eh_estatica: true,
+                    ..Default::default()
                 };
                 biblioteca
                     .simbolos
                     .insert(fqn.to_string(), LibSimbolo::Classe(classe));
             }
         }
-        "PROPERTY" => {
-            // PROPERTY <fqn_classe> <nome> <tipo>
-            if let (Some(fqn), Some(nome), Some(tipo)) =
+        // DEFINE_INTERFACE <fqn> [genericos=T]
+        "DEFINE_INTERFACE" => {
+            if let Some(fqn) = partes.get(1) {
+                let interface = LibInterface {
+                    fqn: fqn.to_string(),
+                    genericos: resto(2).1.lista("genericos"),
+                    metodos: HashMap::new(),
+                };
+                biblioteca
+                    .simbolos
+                    .insert(fqn.to_string(), LibSimbolo::Interface(interface));
+            }
+        }
+        // INTERFACE_METHOD <fqn> <nome> <ret> <nparams> [params...] [genericos=T]
+        "INTERFACE_METHOD" => {
+            if let (Some(fqn), Some(nome), Some(ret)) =
                 (partes.get(1), partes.get(2), partes.get(3))
             {
-                if let Some(LibSimbolo::Classe(cl)) = biblioteca.simbolos.get_mut(*fqn) {
-                    cl.propriedades.push(LibPropriedade {
-                        nome: nome.to_string(),
-                        tipo: tipo.to_string(),
-                    });
+                let (parametros, marcadores) = resto(5);
+                let metodo = LibMetodo {
+                    nome: nome.to_string(),
+                    nome_classe: fqn.to_string(),
+                    tipo_retorno: ret.to_string(),
+                    aridade: parametros.len(),
+                    parametros,
+                    eh_abstrato: true,
+                    genericos: marcadores.lista("genericos"),
+                    ..Default::default()
+                };
+                if let Some(LibSimbolo::Interface(i)) = biblioteca.simbolos.get_mut(*fqn) {
+                    i.metodos.insert(nome.to_string(), metodo);
                 }
             }
         }
-        "FIELD" => {
-            // FIELD <fqn_classe> <nome> <tipo>
-            if let (Some(fqn), Some(nome), Some(tipo)) =
-                (partes.get(1), partes.get(2), partes.get(3))
-            {
+        // DEFINE_ENUM <fqn> <valor> <valor>...
+        "DEFINE_ENUM" => {
+            if let Some(fqn) = partes.get(1) {
+                let enumeracao = LibEnum {
+                    valores: partes[2..].iter().map(|v| v.to_string()).collect(),
+                };
+                biblioteca
+                    .simbolos
+                    .insert(fqn.to_string(), LibSimbolo::Enum(enumeracao));
+            }
+        }
+        // DEFINE_CONSTRUCTOR <fqn> <nparams> [params...] [acesso=protegido]
+        "DEFINE_CONSTRUCTOR" => {
+            if let Some(fqn) = partes.get(1) {
+                let (parametros, marcadores) = resto(3);
                 if let Some(LibSimbolo::Classe(cl)) = biblioteca.simbolos.get_mut(*fqn) {
-                    cl.campos.push(LibCampo {
-                        nome: nome.to_string(),
-                        tipo: tipo.to_string(),
+                    cl.construtores.push(LibConstrutor {
+                        parametros,
+                        acesso: marcadores.acesso(),
                     });
                 }
             }
         }
-        // Métodos nativos: DEFINE_STATIC_NATIVE_METHOD <fqn> <nome> <ret> <chave> [params...]
-        "DEFINE_STATIC_NATIVE_METHOD" => {
-            if let (Some(fqn), Some(nome), Some(ret), Some(chave)) =
-                (partes.get(1), partes.get(2), partes.get(3), partes.get(4))
+        // PROPERTY / FIELD <fqn_classe> <nome> <tipo> [estatica] [acesso=protegido]
+        "PROPERTY" | "FIELD" => {
+            if let (Some(fqn), Some(nome), Some(tipo)) =
+                (partes.get(1), partes.get(2), partes.get(3))
             {
-                let params = parse_params(&partes[5..]);
-                let metodo = LibMetodo {
-                    nome: nome.to_string(),
-                    nome_classe: fqn.to_string(),
-                    tipo_retorno: ret.to_string(),
-                    parametros: params.clone(),
-                    aridade: params.len(),
-                    chave_nativa: Some(chave.to_string()),
-                    eh_estatica: true,
-                };
+                let (_, marcadores) = resto(4);
                 if let Some(LibSimbolo::Classe(cl)) = biblioteca.simbolos.get_mut(*fqn) {
-                    cl.metodos.insert(nome.to_string(), metodo);
+                    let (eh_estatica, acesso) = (marcadores.tem("estatica"), marcadores.acesso());
+                    if partes[0] == "PROPERTY" {
+                        cl.propriedades.push(LibPropriedade {
+                            nome: nome.to_string(),
+                            tipo: tipo.to_string(),
+                            eh_estatica,
+                            acesso,
+                        });
+                    } else {
+                        cl.campos.push(LibCampo {
+                            nome: nome.to_string(),
+                            tipo: tipo.to_string(),
+                            eh_estatica,
+                            acesso,
+                        });
+                    }
                 }
             }
         }
-        // DEFINE_NATIVE_METHOD <fqn> <nome> <ret> <chave> [params...]
-        "DEFINE_NATIVE_METHOD" => {
+        // Métodos nativos: DEFINE_STATIC_NATIVE_METHOD / DEFINE_NATIVE_METHOD <fqn> <nome> <ret> <chave> [params...]
+        "DEFINE_STATIC_NATIVE_METHOD" | "DEFINE_NATIVE_METHOD" => {
             if let (Some(fqn), Some(nome), Some(ret), Some(chave)) =
                 (partes.get(1), partes.get(2), partes.get(3), partes.get(4))
             {
-                let params = parse_params(&partes[5..]);
+                let (parametros, marcadores) = resto(5);
                 let metodo = LibMetodo {
                     nome: nome.to_string(),
                     nome_classe: fqn.to_string(),
                     tipo_retorno: ret.to_string(),
-                    parametros: params.clone(),
-                    aridade: params.len(),
+                    aridade: parametros.len(),
+                    parametros,
                     chave_nativa: Some(chave.to_string()),
-                    eh_estatica: false,
+                    eh_estatica: partes[0] == "DEFINE_STATIC_NATIVE_METHOD",
+                    acesso: marcadores.acesso(),
+                    genericos: marcadores.lista("genericos"),
+                    ..Default::default()
                 };
                 if let Some(LibSimbolo::Classe(cl)) = biblioteca.simbolos.get_mut(*fqn) {
                     cl.metodos.insert(nome.to_string(), metodo);
@@ -241,37 +395,228 @@ fn processar_linha_manifesto(linha: &str, biblioteca: &mut Biblioteca) {
             }
         }
         // Métodos normais: DEFINE_STATIC_METHOD / DEFINE_METHOD <fqn> <nome> <ret> <nparams> [params...]
+        //   [acesso=protegido] [abstrato] [redefinivel] [sobrescreve] [genericos=T]
         "DEFINE_STATIC_METHOD" | "DEFINE_METHOD" => {
-            let eh_estatica = partes[0] == "DEFINE_STATIC_METHOD";
             if let (Some(fqn), Some(nome), Some(ret)) =
                 (partes.get(1), partes.get(2), partes.get(3))
             {
-                let params = parse_params(if partes.len() > 5 { &partes[5..] } else { &[] });
+                let (parametros, marcadores) = resto(5);
                 let metodo = LibMetodo {
                     nome: nome.to_string(),
                     nome_classe: fqn.to_string(),
                     tipo_retorno: ret.to_string(),
-                    parametros: params.clone(),
-                    aridade: params.len(),
+                    aridade: parametros.len(),
+                    parametros,
                     chave_nativa: None,
-                    eh_estatica,
+                    eh_estatica: partes[0] == "DEFINE_STATIC_METHOD",
+                    acesso: marcadores.acesso(),
+                    eh_abstrato: marcadores.tem("abstrato"),
+                    eh_redefinivel: marcadores.tem("redefinivel"),
+                    eh_sobrescrita: marcadores.tem("sobrescreve"),
+                    genericos: marcadores.lista("genericos"),
                 };
                 if let Some(LibSimbolo::Classe(cl)) = biblioteca.simbolos.get_mut(*fqn) {
                     cl.metodos.insert(nome.to_string(), metodo);
                 }
             }
         }
+        // DEFINE_FUNCTION <fqn> <ret> <nparams> [params...] [genericos=T]
+        "DEFINE_FUNCTION" => {
+            if let (Some(fqn), Some(ret)) = (partes.get(1), partes.get(2)) {
+                let (parametros, marcadores) = resto(4);
+                let funcao = LibFuncao {
+                    nome: fqn.to_string(),
+                    aridade: parametros.len(),
+                    tipo_retorno: ret.to_string(),
+                    parametros,
+                    genericos: marcadores.lista("genericos"),
+                };
+                biblioteca
+                    .simbolos
+                    .insert(fqn.to_string(), LibSimbolo::Funcao(funcao));
+            }
+        }
         _ => {}
     }
 }
 
-fn parse_params(partes: &[&str]) -> Vec<(String, String)> {
+fn nome_simples(fqn: &str) -> String {
+    fqn.rsplit('.').next().unwrap_or(fqn).to_string()
+}
+
+/// Marcadores no fim de uma diretiva: palavras soltas (`abstrata`) e pares `chave=valor`.
+struct Marcadores<'a> {
+    soltos: Vec<&'a str>,
+    valores: HashMap<&'a str, &'a str>,
+}
+
+impl<'a> Marcadores<'a> {
+    /// Separa os parâmetros (`tipo:nome[=padrão]`) dos marcadores.
+    fn ler(partes: &[&'a str]) -> (Vec<LibParametro>, Self) {
+        let mut marcadores = Marcadores {
+            soltos: Vec::new(),
+            valores: HashMap::new(),
+        };
+        let mut parametros = Vec::new();
+        for parte in partes {
+            if let Some((tipo, resto)) = parte.split_once(':') {
+                let (nome, padrao) = match resto.split_once('=') {
+                    Some((nome, padrao)) => (nome, Some(decodificar_padrao(padrao))),
+                    None => (resto, None),
+                };
+                parametros.push(LibParametro {
+                    tipo: tipo.to_string(),
+                    nome: nome.to_string(),
+                    padrao,
+                });
+            } else if let Some((chave, valor)) = parte.split_once('=') {
+                marcadores.valores.insert(chave, valor);
+            } else {
+                marcadores.soltos.push(parte);
+            }
+        }
+        (parametros, marcadores)
+    }
+
+    fn tem(&self, nome: &str) -> bool {
+        self.soltos.contains(&nome)
+    }
+
+    fn acesso(&self) -> LibAcesso {
+        match self.valores.get("acesso") {
+            Some(&"protegido") => LibAcesso::Protegido,
+            _ => LibAcesso::Publico,
+        }
+    }
+
+    /// `genericos=T,U` → `["T", "U"]`; vírgulas dentro de `<…>` não separam.
+    fn lista(&self, chave: &str) -> Vec<String> {
+        self.valores
+            .get(chave)
+            .map(|v| dividir_argumentos(v))
+            .unwrap_or_default()
+    }
+}
+
+/// Escreve um valor padrão sem espaços, para caber num único token do manifesto.
+pub fn codificar_padrao(valor: &str) -> String {
+    valor.replace('%', "%25").replace(' ', "%20")
+}
+
+fn decodificar_padrao(valor: &str) -> String {
+    valor.replace("%20", " ").replace("%25", "%")
+}
+
+fn dividir_argumentos(s: &str) -> Vec<String> {
+    let mut partes = Vec::new();
+    let mut profundidade = 0;
+    let mut atual = String::new();
+    for c in s.chars() {
+        match c {
+            '<' | '(' => profundidade += 1,
+            '>' | ')' => profundidade -= 1,
+            ',' if profundidade == 0 => {
+                partes.push(std::mem::take(&mut atual));
+                continue;
+            }
+            _ => {}
+        }
+        atual.push(c);
+    }
+    if !atual.is_empty() {
+        partes.push(atual);
+    }
+    partes
+}
+
+// ============================================================================
+// Tipos no manifesto
+// ============================================================================
+
+/// Escreve um tipo no formato do manifesto, sem espaços (`lista<T>`, `Util.Par<texto,inteiro>`).
+pub fn tipo_para_manifesto(tipo: &Tipo) -> String {
+    match tipo {
+        Tipo::Lista(t) => format!("lista<{}>", tipo_para_manifesto(t)),
+        Tipo::Aplicado { nome, args } => format!(
+            "{}<{}>",
+            nome,
+            args.iter()
+                .map(tipo_para_manifesto)
+                .collect::<Vec<_>>()
+                .join(",")
+        ),
+        Tipo::Opcional(t) => format!("{}?", tipo_para_manifesto(t)),
+        Tipo::Funcao(params, ret) => format!(
+            "funcao({})->{}",
+            params
+                .iter()
+                .map(tipo_para_manifesto)
+                .collect::<Vec<_>>()
+                .join(","),
+            tipo_para_manifesto(ret)
+        ),
+        outro => outro.to_string(),
+    }
+}
+
+/// Lê um tipo escrito por [`tipo_para_manifesto`]. Os nomes em `genericos` viram
+/// parâmetros de tipo; os demais nomes não primitivos viram classes.
+pub fn tipo_do_manifesto(s: &str, genericos: &[String]) -> Tipo {
+    let s = s.trim();
+    if let Some(base) = s.strip_suffix('?') {
+        return Tipo::Opcional(Box::new(tipo_do_manifesto(base, genericos)));
+    }
+    if let Some(resto) = s.strip_prefix("funcao(") {
+        if let Some((params, ret)) = resto.rsplit_once(")->") {
+            return Tipo::Funcao(
+                dividir_argumentos(params)
+                    .iter()
+                    .map(|p| tipo_do_manifesto(p, genericos))
+                    .collect(),
+                Box::new(tipo_do_manifesto(ret, genericos)),
+            );
+        }
+    }
+    if let (Some(inicio), true) = (s.find('<'), s.ends_with('>')) {
+        let nome = &s[..inicio];
+        let args: Vec<Tipo> = dividir_argumentos(&s[inicio + 1..s.len() - 1])
+            .iter()
+            .map(|a| tipo_do_manifesto(a, genericos))
+            .collect();
+        return match (nome, args.as_slice()) {
+            ("lista", [elemento]) => Tipo::Lista(Box::new(elemento.clone())),
+            _ => Tipo::Aplicado {
+                nome: nome.to_string(),
+                args,
+            },
+        };
+    }
+    match s {
+        "inteiro" => Tipo::Inteiro,
+        "texto" => Tipo::Texto,
+        "booleano" => Tipo::Booleano,
+        "flutuante" => Tipo::Flutuante,
+        "duplo" => Tipo::Duplo,
+        "decimal" => Tipo::Decimal,
+        "vazio" => Tipo::Vazio,
+        "objeto" => Tipo::Objeto,
+        "var" => Tipo::Inferido,
+        _ if genericos.iter().any(|g| g == s) => Tipo::Generico(s.to_string()),
+        _ => Tipo::Classe(s.to_string()),
+    }
+}
+
+fn parse_params(partes: &[&str]) -> Vec<LibParametro> {
     partes
         .iter()
         .filter_map(|p| {
             let mut parts = p.splitn(2, ':');
             if let (Some(tipo), Some(nome)) = (parts.next(), parts.next()) {
-                Some((tipo.to_string(), nome.to_string()))
+                Some(LibParametro {
+                    tipo: tipo.to_string(),
+                    nome: nome.to_string(),
+                    padrao: None,
+                })
             } else {
                 None
             }
@@ -284,12 +629,11 @@ fn parse_params(partes: &[&str]) -> Vec<(String, String)> {
 // ============================================================================
 
 fn carregar_pbc(caminho: &Path) -> io::Result<Biblioteca> {
-    let arquivo = File::open(caminho)?;
-    let leitor = BufReader::new(arquivo);
+    let artefato = ler_artefato(caminho)?;
     let mut biblioteca = Biblioteca::new();
-    let mut iterador_linhas = leitor.lines();
+    let mut iterador_linhas = artefato.corpo().lines();
 
-    while let Some(Ok(linha)) = iterador_linhas.next() {
+    while let Some(linha) = iterador_linhas.next() {
         let partes: Vec<&str> = linha.split_whitespace().collect();
         if partes.is_empty() {
             continue;
@@ -305,15 +649,11 @@ fn carregar_pbc(caminho: &Path) -> io::Result<Biblioteca> {
                             Some(p.to_string())
                         }
                     });
-                    let nome_simples = nome_fqn.split('.').last().unwrap_or(nome_fqn).to_string();
                     let classe = LibClasse {
                         fqn: nome_fqn.to_string(),
-                        nome: nome_simples,
+                        nome: nome_simples(nome_fqn),
                         nome_pai,
-                        metodos: HashMap::new(),
-                        propriedades: Vec::new(),
-                        campos: Vec::new(),
-                        eh_estatica: false,
+                        ..Default::default()
                     };
                     biblioteca
                         .simbolos
@@ -322,15 +662,11 @@ fn carregar_pbc(caminho: &Path) -> io::Result<Biblioteca> {
             }
             "DEFINE_STATIC_CLASS" => {
                 if let Some(nome_fqn) = partes.get(1) {
-                    let nome_simples = nome_fqn.split('.').last().unwrap_or(nome_fqn).to_string();
                     let classe = LibClasse {
                         fqn: nome_fqn.to_string(),
-                        nome: nome_simples,
-                        nome_pai: None,
-                        metodos: HashMap::new(),
-                        propriedades: Vec::new(),
-                        campos: Vec::new(),
+                        nome: nome_simples(nome_fqn),
                         eh_estatica: true,
+                        ..Default::default()
                     };
                     biblioteca
                         .simbolos
@@ -360,6 +696,7 @@ fn carregar_pbc(caminho: &Path) -> io::Result<Biblioteca> {
                             aridade: parametros.len(),
                             chave_nativa: None,
                             eh_estatica,
+                            ..Default::default()
                         };
                         classe.metodos.insert(nome_metodo.to_string(), metodo);
                     }
@@ -376,10 +713,27 @@ fn carregar_pbc(caminho: &Path) -> io::Result<Biblioteca> {
             }
             "DEFINE_FUNCTION" => {
                 if let (Some(nome_funcao), Some(_corpo_len_str)) = (partes.get(1), partes.get(2)) {
-                    let parametros = &partes[3..];
+                    // O bytecode legado não guarda tipos: só nomes e valores padrão
+                    let parametros: Vec<LibParametro> = partes[3..]
+                        .iter()
+                        .map(|p| {
+                            let (nome, padrao) = match p.split_once('=') {
+                                Some((nome, padrao)) => (nome, Some(padrao.to_string())),
+                                None => (*p, None),
+                            };
+                            LibParametro {
+                                tipo: "var".to_string(),
+                                nome: nome.to_string(),
+                                padrao,
+                            }
+                        })
+                        .collect();
                     let funcao = LibFuncao {
                         nome: nome_funcao.to_string(),
                         aridade: parametros.len(),
+                        tipo_retorno: "var".to_string(),
+                        parametros,
+                        genericos: Vec::new(),
                     };
                     biblioteca
                         .simbolos
@@ -392,3 +746,55 @@ fn carregar_pbc(caminho: &Path) -> io::Result<Biblioteca> {
 
     Ok(biblioteca)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn le_diretivas_com_parametros_e_marcadores() {
+        let biblioteca = ler_manifesto(
+            "[PBL]\nnome=geo\n\n[MANIFESTO]\nnome=geo\n\
+             DEFINE_CLASS Geo.Caixa Geo.Base<T> abstrata genericos=T interfaces=Geo.IBox<T>,Geo.IForma\n\
+             DEFINE_METHOD Geo.Caixa Saudar texto 2 texto:nome texto:prefixo=\"Bom%20dia\" acesso=protegido redefinivel\n\
+             DEFINE_FUNCTION Dobro inteiro 1 inteiro:x\n\
+             [BYTECODE]\nDEFINE_CLASS Ignorada NULO\n",
+        );
+        let Some(LibSimbolo::Classe(classe)) = biblioteca.simbolos.get("Geo.Caixa") else {
+            panic!("classe não lida: {:?}", biblioteca.simbolos.keys());
+        };
+        assert_eq!(classe.nome_pai.as_deref(), Some("Geo.Base<T>"));
+        assert!(classe.eh_abstrata);
+        assert_eq!(classe.genericos, ["T"]);
+        assert_eq!(classe.interfaces, ["Geo.IBox<T>", "Geo.IForma"]);
+
+        let metodo = &classe.metodos["Saudar"];
+        assert_eq!(metodo.acesso, LibAcesso::Protegido);
+        assert!(metodo.eh_redefinivel && !metodo.eh_abstrato);
+        assert_eq!(obrigatorios(&metodo.parametros), 1);
+        assert_eq!(metodo.parametros[1].padrao.as_deref(), Some("\"Bom dia\""));
+
+        assert!(
+            matches!(biblioteca.simbolos.get("Dobro"), Some(LibSimbolo::Funcao(f)) if f.aridade == 1)
+        );
+        assert!(!biblioteca.simbolos.contains_key("Ignorada"));
+    }
+
+    #[test]
+    fn tipos_fazem_ida_e_volta_pelo_manifesto() {
+        let genericos = vec!["T".to_string()];
+        for texto in [
+            "lista<T>",
+            "Geo.Caixa<inteiro,texto?>",
+            "funcao(inteiro,T)->texto",
+            "var",
+        ] {
+            let tipo = tipo_do_manifesto(texto, &genericos);
+            assert_eq!(tipo_para_manifesto(&tipo), texto, "{:?}", tipo);
+        }
+        assert_eq!(
+            tipo_do_manifesto("T", &genericos),
+            Tipo::Generico("T".to_string())
+        );
+    }
+}
@@ -0,0 +1,942 @@
+// src/linter.rs
+
+//! Linter de estilo e correção (`compilador analisar`).
+//!
+//! Percorre a AST (com os marcadores `Comando::Posicao`) e emite [`Diagnostico`]s para as
+//! regras de [`REGRAS`]. O nível de cada regra vem de [`ConfiguracaoLint`] (arquivo
+//! `lint.json`) e uma função ou método pode silenciar regras com `[Permitir("regra")]`.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::PathBuf;
+
+use logos::Logos;
+use serde::Deserialize;
+
+use crate::ast::{self, Comando, Declaracao, Expressao, MapaLinhas, Posicao};
+use crate::error::{self, ErroCompilador, TipoErro};
+use crate::lexer::Token;
+
+pub const ATRIBUTO_PERMITIR: &str = "Permitir";
+pub const ARQUIVO_CONFIGURACAO: &str = "lint.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Nivel {
+    Desligada,
+    Aviso,
+    Erro,
+}
+
+impl fmt::Display for Nivel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Nivel::Desligada => write!(f, "desligada"),
+            Nivel::Aviso => write!(f, "aviso"),
+            Nivel::Erro => write!(f, "erro"),
+        }
+    }
+}
+
+pub struct Regra {
+    pub nome: &'static str,
+    pub descricao: &'static str,
+    pub nivel_padrao: Nivel,
+}
+
+pub const REGRAS: &[Regra] = &[
+    Regra {
+        nome: "variavel-nao-utilizada",
+        descricao: "variável local declarada e nunca lida",
+        nivel_padrao: Nivel::Aviso,
+    },
+    Regra {
+        nome: "parametro-nao-utilizado",
+        descricao: "parâmetro nunca lido no corpo da função ou método",
+        nivel_padrao: Nivel::Aviso,
+    },
+    Regra {
+        nome: "metodo-privado-nao-utilizado",
+        descricao: "método privado que nunca é chamado",
+        nivel_padrao: Nivel::Aviso,
+    },
+    Regra {
+        nome: "nomenclatura",
+        descricao: "PascalCase para tipos, métodos, funções e propriedades; camelCase para variáveis e parâmetros",
+        nivel_padrao: Nivel::Aviso,
+    },
+    Regra {
+        nome: "condicao-constante",
+        descricao: "condição de 'se' sempre verdadeira/falsa, ou laço que nunca executa",
+        nivel_padrao: Nivel::Aviso,
+    },
+    Regra {
+        nome: "bloco-vazio",
+        descricao: "bloco sem comandos em 'se', 'senão', laços, funções e métodos",
+        nivel_padrao: Nivel::Aviso,
+    },
+    Regra {
+        nome: "sombreamento",
+        descricao: "variável que esconde outra de mesmo nome em um escopo externo",
+        nivel_padrao: Nivel::Aviso,
+    },
+    Regra {
+        nome: "sobrescreve-ausente",
+        descricao: "método que esconde um método 'redefinível' da classe base sem usar 'sobrescreve'",
+        nivel_padrao: Nivel::Aviso,
+    },
+    Regra {
+        nome: "autoatribuicao",
+        descricao: "atribuição de uma variável ou propriedade a ela mesma",
+        nivel_padrao: Nivel::Erro,
+    },
+];
+
+/// Nível efetivo de cada regra. Formato do `lint.json`:
+///
+/// ```json
+/// { "regras": { "nomenclatura": "desligada", "bloco-vazio": "erro" } }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ConfiguracaoLint {
+    niveis: HashMap<&'static str, Nivel>,
+}
+
+#[derive(Deserialize)]
+struct ArquivoConfiguracao {
+    #[serde(default)]
+    regras: HashMap<String, Nivel>,
+}
+
+impl Default for ConfiguracaoLint {
+    fn default() -> Self {
+        Self {
+            niveis: REGRAS.iter().map(|r| (r.nome, r.nivel_padrao)).collect(),
+        }
+    }
+}
+
+impl ConfiguracaoLint {
+    pub fn de_json(texto: &str) -> Result<Self, String> {
+        let arquivo: ArquivoConfiguracao = serde_json::from_str(texto)
+            .map_err(|e| format!("Configuração de lint inválida: {}", e))?;
+        let mut config = Self::default();
+        for (nome, nivel) in arquivo.regras {
+            let regra = REGRAS.iter().find(|r| r.nome == nome).ok_or_else(|| {
+                format!(
+                    "Regra de lint desconhecida '{}'. Regras disponíveis: {}",
+                    nome,
+                    REGRAS.iter().map(|r| r.nome).collect::<Vec<_>>().join(", ")
+                )
+            })?;
+            config.niveis.insert(regra.nome, nivel);
+        }
+        Ok(config)
+    }
+
+    pub fn nivel(&self, regra: &str) -> Nivel {
+        self.niveis.get(regra).copied().unwrap_or(Nivel::Desligada)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostico {
+    pub regra: &'static str,
+    pub nivel: Nivel,
+    pub mensagem: String,
+    pub posicao: Option<Posicao>,
+}
+
+impl fmt::Display for Diagnostico {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(p) = &self.posicao {
+            write!(f, "{}:{}: ", p.arquivo, p.linha)?;
+        }
+        write!(f, "{}[{}]: {}", self.nivel, self.regra, self.mensagem)
+    }
+}
+
+/// Analisa um arquivo fonte completo (léxico + sintático + regras de lint).
+pub fn analisar_fonte(
+    fonte: &str,
+    arquivo: &str,
+    config: &ConfiguracaoLint,
+) -> Result<Vec<Diagnostico>, Box<ErroCompilador>> {
+    let tokens = Token::lexer(fonte)
+        .spanned()
+        .map(|(tok, span)| tok.map(|t| (span.start, t, span.end)).map_err(|_| span))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|span| {
+            let pos = MapaLinhas::novo(arquivo, fonte).posicao(span.start);
+            Box::new(
+                ErroCompilador::novo(TipoErro::Léxico, "Token inválido encontrado".to_string())
+                    .com_arquivo(PathBuf::from(arquivo))
+                    .com_localizacao(pos.linha, pos.coluna),
+            )
+        })?;
+    let mapa = MapaLinhas::novo(arquivo, fonte);
+    let programa = crate::parser::ArquivoParser::new()
+        .parse(Some(&mapa), tokens.iter().cloned())
+        .map_err(|e| {
+            Box::new(error::de_lalrpop_error_unit(
+                &e,
+                PathBuf::from(arquivo),
+                fonte,
+            ))
+        })?;
+    let localizador = Localizador { tokens, mapa };
+    Ok(Linter::new(config, &localizador).analisar(&programa))
+}
+
+/// Encontra a linha das declarações (a AST só guarda posições de comandos).
+struct Localizador {
+    tokens: Vec<(usize, Token, usize)>,
+    mapa: MapaLinhas,
+}
+
+impl Localizador {
+    fn tipo(&self, nome: &str) -> Option<usize> {
+        self.tokens
+            .windows(2)
+            .position(|w| {
+                matches!(
+                    w[0].1,
+                    Token::TClasse | Token::TInterface | Token::TEnumeracao
+                ) && w[1].1 == Token::TIdentificador(nome.to_string())
+            })
+            .map(|i| i + 1)
+    }
+
+    // Uma declaração de membro é `<tipo|modificador> Nome (` — chamadas vêm depois de `.`, `=`, `;` etc.
+    fn membro(&self, a_partir: usize, nome: &str) -> Option<usize> {
+        (a_partir.max(1)..self.tokens.len().saturating_sub(1)).find(|&i| {
+            self.tokens[i].1 == Token::TIdentificador(nome.to_string())
+                && matches!(
+                    self.tokens[i + 1].1,
+                    Token::TParenEsq | Token::TMenor | Token::TChaveEsq
+                )
+                && matches!(
+                    self.tokens[i - 1].1,
+                    Token::TIdentificador(_)
+                        | Token::TMaiorQue
+                        | Token::TColcheteDir
+                        | Token::TFuncao
+                        | Token::TPublico
+                        | Token::TPrivado
+                        | Token::TProtegido
+                        | Token::TTipoInteiro
+                        | Token::TTipoTexto
+                        | Token::TTipoBooleano
+                        | Token::TTipoFlutuante
+                        | Token::TTipoDuplo
+                        | Token::TTipoDecimal
+                        | Token::TTipoVazio
+                        | Token::TTipoObjeto
+                )
+        })
+    }
+
+    fn posicao(&self, indice: usize) -> Posicao {
+        self.mapa.posicao(self.tokens[indice].0)
+    }
+}
+
+struct Local {
+    nome: String,
+    posicao: Option<Posicao>,
+    usado: bool,
+    eh_parametro: bool,
+}
+
+struct MetodoPrivado {
+    classe: String,
+    nome: String,
+    posicao: Option<Posicao>,
+    silenciado: bool,
+}
+
+struct Linter<'a> {
+    config: &'a ConfiguracaoLint,
+    localizador: &'a Localizador,
+    diagnosticos: Vec<Diagnostico>,
+    posicao: Option<Posicao>,
+    /// Regras silenciadas por `[Permitir]` na função/método atual.
+    permitidas: Vec<String>,
+    /// Escopos da função atual; vazio fora de funções (código global não é verificado).
+    escopos: Vec<Vec<Local>>,
+    classes: HashMap<String, &'a ast::DeclaracaoClasse>,
+    interfaces: HashMap<String, &'a ast::DeclaracaoInterface>,
+    chamados: HashSet<String>,
+    privados: Vec<MetodoPrivado>,
+}
+
+impl<'a> Linter<'a> {
+    fn new(config: &'a ConfiguracaoLint, localizador: &'a Localizador) -> Self {
+        Self {
+            config,
+            localizador,
+            diagnosticos: Vec::new(),
+            posicao: None,
+            permitidas: Vec::new(),
+            escopos: Vec::new(),
+            classes: HashMap::new(),
+            interfaces: HashMap::new(),
+            chamados: HashSet::new(),
+            privados: Vec::new(),
+        }
+    }
+
+    fn analisar(mut self, programa: &'a ast::Programa) -> Vec<Diagnostico> {
+        let declaracoes: Vec<&'a Declaracao> = programa
+            .declaracoes
+            .iter()
+            .chain(
+                programa
+                    .namespaces
+                    .iter()
+                    .flat_map(|ns| ns.declaracoes.iter()),
+            )
+            .collect();
+        for d in &declaracoes {
+            self.registrar_tipos(d);
+        }
+        for d in declaracoes {
+            self.declaracao(d);
+        }
+
+        for m in std::mem::take(&mut self.privados) {
+            if !m.silenciado && !self.chamados.contains(&m.nome) {
+                self.posicao = m.posicao;
+                self.emitir(
+                    "metodo-privado-nao-utilizado",
+                    format!("Método privado '{}.{}' nunca é chamado", m.classe, m.nome),
+                );
+            }
+        }
+        self.diagnosticos
+            .sort_by_key(|d| d.posicao.as_ref().map(|p| p.linha));
+        self.diagnosticos
+    }
+
+    fn registrar_tipos(&mut self, declaracao: &'a Declaracao) {
+        match declaracao {
+            Declaracao::DeclaracaoClasse(c) => self.registrar_classe(c),
+            Declaracao::DeclaracaoInterface(i) => {
+                self.interfaces.insert(i.nome.clone(), i);
+            }
+            Declaracao::DeclaracaoNamespace(ns) => {
+                ns.declaracoes.iter().for_each(|d| self.registrar_tipos(d))
+            }
+            _ => {}
+        }
+    }
+
+    fn registrar_classe(&mut self, classe: &'a ast::DeclaracaoClasse) {
+        self.classes.insert(classe.nome.clone(), classe);
+        for aninhada in &classe.nested_classes {
+            self.registrar_classe(aninhada);
+        }
+    }
+
+    fn emitir(&mut self, regra: &'static str, mensagem: String) {
+        let nivel = self.config.nivel(regra);
+        if nivel == Nivel::Desligada || self.permitidas.iter().any(|r| r == regra) {
+            return;
+        }
+        self.diagnosticos.push(Diagnostico {
+            regra,
+            nivel,
+            mensagem,
+            posicao: self.posicao.clone(),
+        });
+    }
+
+    fn posicionar_tipo(&mut self, nome: &str) -> usize {
+        match self.localizador.tipo(nome) {
+            Some(i) => {
+                self.posicao = Some(self.localizador.posicao(i));
+                i
+            }
+            None => 0,
+        }
+    }
+
+    fn posicionar_membro(&mut self, a_partir: usize, nome: &str) {
+        if let Some(i) = self.localizador.membro(a_partir, nome) {
+            self.posicao = Some(self.localizador.posicao(i));
+        }
+    }
+
+    fn declaracao(&mut self, declaracao: &Declaracao) {
+        match declaracao {
+            Declaracao::Comando(cmd) => self.comando(cmd),
+            Declaracao::DeclaracaoFuncao(f) => {
+                self.posicionar_membro(0, &f.nome);
+                self.permitidas = atributos_permitir(&f.attributes);
+                self.verificar_pascal("Função", &f.nome);
+                self.corpo(&f.attributes, &f.parametros, &[], &f.corpo, true, true);
+            }
+            Declaracao::DeclaracaoClasse(c) => self.classe(c),
+            Declaracao::DeclaracaoInterface(i) => {
+                self.posicionar_tipo(&i.nome);
+                self.verificar_pascal("Interface", &i.nome);
+            }
+            Declaracao::DeclaracaoEnum(e) => {
+                self.posicionar_tipo(&e.nome);
+                self.verificar_pascal("Enumeração", &e.nome);
+            }
+            Declaracao::DeclaracaoNamespace(ns) => {
+                ns.declaracoes.iter().for_each(|d| self.declaracao(d))
+            }
+            Declaracao::DeclaracaoModulo(m) => m.conteudo.iter().for_each(|d| self.declaracao(d)),
+            _ => {}
+        }
+    }
+
+    fn classe(&mut self, classe: &ast::DeclaracaoClasse) {
+        let inicio = self.posicionar_tipo(&classe.nome);
+        self.verificar_pascal("Classe", &classe.nome);
+
+        for p in &classe.propriedades {
+            self.posicionar_membro(inicio, &p.nome);
+            self.verificar_pascal("Propriedade", &p.nome);
+            for corpo in [&p.obter, &p.definir]
+                .into_iter()
+                .flatten()
+                .filter_map(|a| a.corpo.as_ref())
+            {
+                self.corpo(&[], &[], &[], corpo, false, false);
+            }
+        }
+        for c in &classe.construtores {
+            let chamada_pai = c.chamada_pai.as_deref().unwrap_or_default();
+            self.corpo(&[], &c.parametros, chamada_pai, &c.corpo, false, true);
+        }
+        for m in &classe.metodos {
+            self.posicionar_membro(inicio, &m.nome);
+            self.permitidas = atributos_permitir(&m.attributes);
+            self.verificar_pascal("Método", &m.nome);
+
+            if !m.eh_override {
+                if let Some(base) = self.base_redefinivel(classe, &m.nome) {
+                    self.emitir(
+                        "sobrescreve-ausente",
+                        format!(
+                            "Método '{}.{}' esconde um método redefinível/abstrato de '{}'; declare-o com 'sobrescreve'",
+                            classe.nome, m.nome, base
+                        ),
+                    );
+                }
+            }
+            if m.modificador == ast::ModificadorAcesso::Privado {
+                self.privados.push(MetodoPrivado {
+                    classe: classe.nome.clone(),
+                    nome: m.nome.clone(),
+                    posicao: self.posicao.clone(),
+                    silenciado: self
+                        .permitidas
+                        .iter()
+                        .any(|r| r == "metodo-privado-nao-utilizado"),
+                });
+            }
+            if m.eh_abstrato {
+                self.permitidas.clear();
+                continue;
+            }
+            // A assinatura de métodos que redefinem ou implementam outro não é escolha do autor
+            let assinatura_herdada =
+                m.eh_override || m.eh_virtual || self.metodo_de_interface(classe, &m.nome);
+            self.corpo(
+                &m.attributes,
+                &m.parametros,
+                &[],
+                &m.corpo,
+                true,
+                !assinatura_herdada,
+            );
+        }
+        for aninhada in &classe.nested_classes {
+            self.classe(aninhada);
+        }
+    }
+
+    fn base_redefinivel(&self, classe: &ast::DeclaracaoClasse, metodo: &str) -> Option<String> {
+        let mut atual = classe;
+        let mut visitadas = HashSet::new();
+        while let Some(pai) = atual.classe_pai.as_ref().and_then(nome_tipo) {
+            if !visitadas.insert(pai.clone()) {
+                break;
+            }
+            atual = self.classes.get(&pai)?;
+            if atual
+                .metodos
+                .iter()
+                .any(|m| m.nome == metodo && (m.eh_virtual || m.eh_abstrato))
+            {
+                return Some(atual.nome.clone());
+            }
+        }
+        None
+    }
+
+    fn metodo_de_interface(&self, classe: &ast::DeclaracaoClasse, metodo: &str) -> bool {
+        classe.interfaces.iter().filter_map(nome_tipo).any(|i| {
+            self.interfaces
+                .get(&i)
+                .is_some_and(|decl| decl.metodos.iter().any(|m| m.nome == metodo))
+        })
+    }
+
+    // Corpo de função, método, construtor ou acessor: abre o escopo dos parâmetros.
+    fn corpo(
+        &mut self,
+        atributos: &[ast::Attribute],
+        parametros: &[ast::Parametro],
+        chamada_pai: &[Expressao],
+        corpo: &[Comando],
+        eh_funcao: bool,
+        verificar_parametros: bool,
+    ) {
+        self.permitidas = atributos_permitir(atributos);
+        let nativo = atributos
+            .iter()
+            .any(|a| a.name == "Nativo" || a.name == "Externo");
+        // Construtores vazios (ex.: `: base(...) {}`) e acessores não são reportados
+        if eh_funcao && !nativo && comandos_efetivos(corpo) == 0 {
+            self.emitir("bloco-vazio", "Corpo vazio".to_string());
+        }
+
+        let posicao = self.posicao.clone();
+        self.escopos.push(Vec::new());
+        for p in parametros {
+            self.verificar_camel("Parâmetro", &p.nome);
+            if let Some(padrao) = &p.valor_padrao {
+                self.expressao(padrao);
+            }
+            self.escopos.last_mut().unwrap().push(Local {
+                nome: p.nome.clone(),
+                posicao: posicao.clone(),
+                usado: nativo || !verificar_parametros || comandos_efetivos(corpo) == 0,
+                eh_parametro: true,
+            });
+        }
+        chamada_pai.iter().for_each(|e| self.expressao(e));
+        for cmd in corpo {
+            self.comando(cmd);
+        }
+        self.sair_escopo();
+        self.permitidas.clear();
+    }
+
+    fn sair_escopo(&mut self) {
+        let Some(locais) = self.escopos.pop() else {
+            return;
+        };
+        let posicao_atual = self.posicao.clone();
+        for local in locais
+            .into_iter()
+            .filter(|l| !l.usado && !l.nome.starts_with('_'))
+        {
+            self.posicao = local.posicao;
+            if local.eh_parametro {
+                self.emitir(
+                    "parametro-nao-utilizado",
+                    format!("Parâmetro '{}' nunca é utilizado", local.nome),
+                );
+            } else {
+                self.emitir(
+                    "variavel-nao-utilizada",
+                    format!("Variável '{}' declarada mas nunca utilizada", local.nome),
+                );
+            }
+        }
+        self.posicao = posicao_atual;
+    }
+
+    fn declarar_local(&mut self, nome: &str) {
+        if self.escopos.is_empty() {
+            return;
+        }
+        self.verificar_camel("Variável", nome);
+        if self.escopos.iter().flatten().any(|l| l.nome == nome) {
+            self.emitir(
+                "sombreamento",
+                format!(
+                    "Variável '{}' esconde outra declarada em um escopo externo",
+                    nome
+                ),
+            );
+        }
+        let posicao = self.posicao.clone();
+        self.escopos.last_mut().unwrap().push(Local {
+            nome: nome.to_string(),
+            posicao,
+            usado: false,
+            eh_parametro: false,
+        });
+    }
+
+    fn usar(&mut self, nome: &str) {
+        if let Some(local) = self
+            .escopos
+            .iter_mut()
+            .rev()
+            .flatten()
+            .find(|l| l.nome == nome)
+        {
+            local.usado = true;
+        }
+    }
+
+    fn bloco(&mut self, cmd: &Comando, contexto: &str) {
+        if let Comando::Bloco(cmds) = cmd {
+            if comandos_efetivos(cmds) == 0 {
+                self.emitir("bloco-vazio", format!("Bloco vazio em '{}'", contexto));
+            }
+        }
+        self.comando(cmd);
+    }
+
+    fn comando(&mut self, comando: &Comando) {
+        match comando {
+            Comando::Posicao(p) => self.posicao = Some(p.clone()),
+            Comando::DeclaracaoVariavel(_, nome, valor) => {
+                if let Some(e) = valor {
+                    self.expressao(e);
+                }
+                self.declarar_local(nome);
+            }
+            Comando::DeclaracaoVar(nome, e) => {
+                self.expressao(e);
+                self.declarar_local(nome);
+            }
+            Comando::Atribuicao(nome, e) => {
+                if *e == Expressao::Identificador(nome.clone()) {
+                    self.emitir(
+                        "autoatribuicao",
+                        format!("'{}' é atribuído a si mesmo", nome),
+                    );
+                }
+                self.expressao(e);
+            }
+            Comando::AtribuirPropriedade(obj, prop, e) | Comando::AtribuirCampo(obj, prop, e) => {
+                if *e == Expressao::AcessoMembro(obj.clone(), prop.clone()) {
+                    self.emitir(
+                        "autoatribuicao",
+                        format!("'{}' é atribuído a si mesmo", prop),
+                    );
+                }
+                self.expressao(obj);
+                self.expressao(e);
+            }
+            Comando::AtribuirIndice(obj, idx, e) => {
+                self.expressao(obj);
+                self.expressao(idx);
+                self.expressao(e);
+            }
+            Comando::Imprima(e) | Comando::Expressao(e) => self.expressao(e),
+            Comando::Retorne(e) => {
+                if let Some(e) = e {
+                    self.expressao(e);
+                }
+            }
+            Comando::Se(cond, entao, senao) => {
+                if let Some(valor) = constante(cond) {
+                    self.emitir(
+                        "condicao-constante",
+                        format!(
+                            "Condição do 'se' é sempre {}",
+                            if valor { "verdadeira" } else { "falsa" }
+                        ),
+                    );
+                }
+                self.expressao(cond);
+                self.bloco(entao, "se");
+                if let Some(senao) = senao {
+                    self.bloco(senao, "senão");
+                }
+            }
+            Comando::Enquanto(cond, corpo) => {
+                // `enquanto (verdadeiro)` é um laço infinito intencional; só o falso é suspeito
+                if constante(cond) == Some(false) {
+                    self.emitir(
+                        "condicao-constante",
+                        "Laço 'enquanto' nunca executa".to_string(),
+                    );
+                }
+                self.expressao(cond);
+                self.bloco(corpo, "enquanto");
+            }
+            Comando::Para(init, cond, incr, corpo) => {
+                self.escopos.push(Vec::new());
+                if let Some(init) = init {
+                    self.comando(init);
+                }
+                if let Some(cond) = cond {
+                    if constante(cond) == Some(false) {
+                        self.emitir(
+                            "condicao-constante",
+                            "Laço 'para' nunca executa".to_string(),
+                        );
+                    }
+                    self.expressao(cond);
+                }
+                if let Some(incr) = incr {
+                    self.comando(incr);
+                }
+                self.bloco(corpo, "para");
+                // A variável de controle é usada pela condição/incremento; não a reporte
+                if let Some(locais) = self.escopos.last_mut() {
+                    locais.iter_mut().for_each(|l| l.usado |= cond.is_some());
+                }
+                self.sair_escopo();
+            }
+            Comando::Bloco(cmds) => {
+                self.escopos.push(Vec::new());
+                for c in cmds {
+                    self.comando(c);
+                }
+                self.sair_escopo();
+            }
+            Comando::CriarObjeto(_, _, args) => args.iter().for_each(|a| self.expressao(a)),
+            Comando::ChamarMetodo(obj, metodo, args) => {
+                self.chamados.insert(metodo.clone());
+                self.expressao(obj);
+                args.iter().for_each(|a| self.expressao(a));
+            }
+            Comando::AcessarCampo(obj, _) => self.usar(obj),
+        }
+    }
+
+    fn expressao(&mut self, expr: &Expressao) {
+        match expr {
+            Expressao::Identificador(nome) => self.usar(nome),
+            Expressao::ListaLiteral(itens) => itens.iter().for_each(|e| self.expressao(e)),
+            Expressao::Aritmetica(_, a, b)
+            | Expressao::Comparacao(_, a, b)
+            | Expressao::Logica(_, a, b)
+            | Expressao::AcessoIndice(a, b) => {
+                self.expressao(a);
+                self.expressao(b);
+            }
+            Expressao::NovoObjeto(_, args) => args.iter().for_each(|e| self.expressao(e)),
+            Expressao::NovoArray(_, tamanho) => self.expressao(tamanho),
+            Expressao::AcessoMembro(obj, _) => self.expressao(obj),
+            Expressao::ChamadaMetodo(obj, metodo, args) => {
+                self.chamados.insert(metodo.clone());
+                self.expressao(obj);
+                args.iter().for_each(|e| self.expressao(e));
+            }
+            Expressao::Chamada(nome, args) => {
+                self.chamados.insert(nome.clone());
+                args.iter().for_each(|e| self.expressao(e));
+            }
+            Expressao::StringInterpolada(partes) => {
+                for parte in partes {
+                    if let ast::PartStringInterpolada::Expressao(e) = parte {
+                        self.expressao(e);
+                    }
+                }
+            }
+            Expressao::Unario(_, e) | Expressao::Aguarde(e) => self.expressao(e),
+            _ => {}
+        }
+    }
+
+    fn verificar_pascal(&mut self, oque: &str, nome: &str) {
+        let sugestao = para_pascal(nome);
+        if nome != sugestao {
+            self.emitir(
+                "nomenclatura",
+                format!(
+                    "{} '{}' deveria usar PascalCase: '{}'",
+                    oque, nome, sugestao
+                ),
+            );
+        }
+    }
+
+    fn verificar_camel(&mut self, oque: &str, nome: &str) {
+        if nome.starts_with('_') {
+            return;
+        }
+        let sugestao = para_camel(nome);
+        if nome != sugestao {
+            self.emitir(
+                "nomenclatura",
+                format!("{} '{}' deveria usar camelCase: '{}'", oque, nome, sugestao),
+            );
+        }
+    }
+}
+
+fn atributos_permitir(atributos: &[ast::Attribute]) -> Vec<String> {
+    atributos
+        .iter()
+        .filter(|a| a.name == ATRIBUTO_PERMITIR)
+        .flat_map(|a| a.arguments.iter())
+        .filter_map(|arg| match arg {
+            Expressao::Texto(regra) => Some(regra.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn nome_tipo(tipo: &ast::Tipo) -> Option<String> {
+    match tipo {
+        ast::Tipo::Classe(nome) | ast::Tipo::Aplicado { nome, .. } => {
+            nome.rsplit('.').next().map(String::from)
+        }
+        _ => None,
+    }
+}
+
+fn comandos_efetivos(cmds: &[Comando]) -> usize {
+    cmds.iter()
+        .filter(|c| !matches!(c, Comando::Posicao(_)))
+        .count()
+}
+
+/// Valor de uma condição formada só por literais (`verdadeiro`, `1 > 2`, `!falso && ...`).
+fn constante(expr: &Expressao) -> Option<bool> {
+    use ast::OperadorComparacao as Op;
+    match expr {
+        Expressao::Booleano(b) => Some(*b),
+        Expressao::Unario(ast::OperadorUnario::NegacaoLogica, e) => constante(e).map(|b| !b),
+        Expressao::Logica(op, a, b) => {
+            let (a, b) = (constante(a)?, constante(b)?);
+            Some(match op {
+                ast::OperadorLogico::E => a && b,
+                ast::OperadorLogico::Ou => a || b,
+            })
+        }
+        Expressao::Comparacao(op, a, b) => match (a.as_ref(), b.as_ref()) {
+            (Expressao::Inteiro(a), Expressao::Inteiro(b)) => Some(match op {
+                Op::Igual => a == b,
+                Op::Diferente => a != b,
+                Op::Menor => a < b,
+                Op::MaiorQue => a > b,
+                Op::MenorIgual => a <= b,
+                Op::MaiorIgual => a >= b,
+            }),
+            (Expressao::Texto(a), Expressao::Texto(b)) => match op {
+                Op::Igual => Some(a == b),
+                Op::Diferente => Some(a != b),
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn palavras(nome: &str) -> Vec<String> {
+    nome.split('_')
+        .filter(|p| !p.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn para_pascal(nome: &str) -> String {
+    palavras(nome)
+        .iter()
+        .map(|p| {
+            let mut c = p.chars();
+            c.next()
+                .map(|primeira| primeira.to_uppercase().chain(c).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn para_camel(nome: &str) -> String {
+    let pascal = para_pascal(nome);
+    let mut c = pascal.chars();
+    c.next()
+        .map(|primeira| primeira.to_lowercase().chain(c).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regras(fonte: &str) -> Vec<(&'static str, usize)> {
+        analisar_fonte(fonte, "t.pr", &ConfiguracaoLint::default())
+            .unwrap()
+            .into_iter()
+            .map(|d| (d.regra, d.posicao.map(|p| p.linha).unwrap_or(0)))
+            .collect()
+    }
+
+    #[test]
+    fn variaveis_parametros_e_sombreamento() {
+        let fonte = "\
+função vazio Somar(inteiro a, inteiro b) {
+    inteiro total = a;
+    se (a > 0) {
+        inteiro a = 2;
+        imprima(a);
+    }
+}
+";
+        assert_eq!(
+            regras(fonte),
+            vec![
+                ("parametro-nao-utilizado", 1),
+                ("variavel-nao-utilizada", 2),
+                ("sombreamento", 4)
+            ]
+        );
+    }
+
+    #[test]
+    fn permitir_silencia_regra() {
+        let fonte = "\
+[Permitir(\"nomenclatura\", \"condicao-constante\")]
+função vazio minha_funcao() {
+    se (1 > 2) { imprima(1); }
+}
+função vazio outra_funcao() {
+    inteiro x = 1;
+    x = x;
+    imprima(x);
+}
+";
+        assert_eq!(
+            regras(fonte),
+            vec![("nomenclatura", 5), ("autoatribuicao", 7)]
+        );
+    }
+
+    #[test]
+    fn sobrescreve_ausente_e_metodo_privado() {
+        let fonte = "\
+classe Animal {
+    publico redefinível vazio Falar() { imprima(1); }
+    privado vazio Auxiliar() { imprima(2); }
+}
+classe Gato : Animal {
+    publico vazio Falar() { imprima(3); }
+}
+";
+        assert_eq!(
+            regras(fonte),
+            vec![
+                ("metodo-privado-nao-utilizado", 3),
+                ("sobrescreve-ausente", 6)
+            ]
+        );
+    }
+
+    #[test]
+    fn configuracao_altera_niveis() {
+        let config = ConfiguracaoLint::de_json(
+            r#"{"regras": {"nomenclatura": "desligada", "bloco-vazio": "erro"}}"#,
+        )
+        .unwrap();
+        assert_eq!(config.nivel("nomenclatura"), Nivel::Desligada);
+        assert_eq!(config.nivel("bloco-vazio"), Nivel::Erro);
+        assert!(ConfiguracaoLint::de_json(r#"{"regras": {"inexistente": "aviso"}}"#).is_err());
+    }
+}
@@ -4,19 +4,30 @@ use std::collections::HashSet;
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use walkdir::WalkDir;
 
 // Declaração dos módulos do projeto
+mod artefato;
 mod ast;
 mod codegen;
+mod compatibilidade;
 mod error;
+mod formatador;
+mod incremental;
 mod inferencia_tipos;
 mod interpolacao;
 mod lexer;
 mod library_loader; // Novo módulo para carregar bibliotecas
+mod linter;
 mod module_system;
 mod ownership;
+mod pacotes;
+mod paralelo;
+mod projeto;
+mod runtime;
 mod stdlib;
+mod testes;
 mod type_checker;
 
 // Parser LALRPOP
@@ -61,6 +72,10 @@ use logos::Logos;
 enum TargetCompilacao {
     Universal,
     LlvmIr,
+    /// Gera código C99 legível (.c) e o compila com o runtime nativo
+    C,
+    /// Gera WebAssembly (.wat e .wasm) que usa funções importadas do hospedeiro para o console
+    Wasm,
     CilBytecode,
     Console,
     Bytecode,
@@ -80,11 +95,46 @@ OPÇÕES:
   --target=<alvo>               Define o formato de saída da compilação.
   --output-dir=<path>           Define o diretório de saída para os arquivos compilados.
   --stdlib-src-path=<path>      Especifica o caminho para o código-fonte da biblioteca padrão.
+  --modulos=<path>              Acrescenta um diretório de busca para `importar` (pode repetir).
+  --nome=<nome>                 Nome base dos arquivos gerados (padrão: o do último arquivo .pr).
+  --biblioteca=<arquivo.pbl>    Usa uma biblioteca já compilada (pode repetir; só com --target=bytecode).
   --compilar-biblioteca=<path>  Compila uma biblioteca a partir do diretório especificado.
+  --incremental=<dir>           Reaproveita o trabalho das compilações anteriores guardado em <dir>.
+  --sem-incremental             Compila do zero, sem o cache incremental (também em construir/publicar).
+  --executar                    Executa o programa diretamente (interpretador de AST), sem gerar arquivos.
+  --debug-info                  Inclui informações de depuração DWARF no LLVM IR (gdb/lldb).
+  --teste-de-mesa               Marca as linhas do fonte no bytecode para o teste de mesa do interpretador.
+  -O0 | -O1 | -O2 | -O3 | -Os   Nível de otimização do executável LLVM ou C (padrão: -O0).
+  --sem-verificacoes            Omite dos executáveis LLVM e C as verificações de índice, nulo e divisão por zero.
   --help                        Exibe esta mensagem de ajuda.
 
+COMANDOS:
+  construir [diretório]         Constrói o projeto descrito em projeto.toml; os artefatos vão para dist/.
+         [--alvo=<alvo>]        Gera só este alvo em vez dos listados no manifesto (pode repetir).
+         [--atualizar]          Ignora o projeto.lock e escolhe de novo as versões das dependências.
+  publicar [diretório]          Constrói a biblioteca e a copia para o registro local de pacotes.
+         [--registro=<dir>]     Registro de destino (padrão: [pacotes] registro ou PORDOSOL_REGISTRO).
+         [--chave=<arquivo>]    Assina a biblioteca publicada com a chave dada.
+  compat <antiga> <nova>        Compara a API de duas versões de uma biblioteca (.pbl) e indica o
+                                incremento de versão necessário (código 1 se a nova não bastar).
+  chave <arquivo>               Gera um par de chaves para assinar bibliotecas e mostra a chave pública.
+  assinar <arquivo>...          Assina .pbl/.pbc, gravando <arquivo>.sig ao lado.
+         --chave=<arquivo>      Chave gerada por 'compilador chave'.
+  verificar <arquivo>...        Confere formato, hash e assinatura de .pbl/.pbc.
+         [--chaves=<arquivo>]   Exige assinatura de uma das chaves públicas listadas.
+  analisar <arquivo.pr|dir>...  Aponta problemas de estilo e possíveis erros (linter).
+         [--config=<arquivo>]   Níveis das regras (padrão: ./lint.json, se existir).
+         [--regras]             Lista as regras disponíveis e seus níveis.
+  formatar <arquivo.pr|dir>...  Reescreve os arquivos no estilo padrão (indentação, espaços).
+         [--verificar]          Não grava nada; termina com código 1 se algum arquivo estiver fora do padrão.
+  testar <arquivo.pr|dir>...    Executa as funções/métodos marcados com [Teste].
+         [--filtro=<nome>]      Executa apenas os testes cujo nome contém <nome>.
+         [--junit=<arquivo>]    Grava também um relatório JUnit XML (para CI).
+
 ALVOS DISPONÍVEIS:
   llvm-ir            Gera código intermediário LLVM (.ll), otimizado para compilação nativa com Clang.
+  c                  Gera código C99 legível (.c) e o compila com o compilador C do sistema (CC, cc, gcc ou clang).
+  wasm               Gera um módulo WebAssembly (.wat e .wasm); execute com 'node src/runtime/pds_wasm.js <arquivo.wasm>'.
   cil-bytecode       Gera código CIL (.il) para a plataforma .NET.
   console            Cria um projeto de console .NET completo, pronto para ser executado com 'dotnet run'.
   bytecode           Gera um arquivo de bytecode customizado (.pbc) para ser executado pelo interpretador.
@@ -95,6 +145,17 @@ EXEMPLOS DE USO:
   # Compilar um programa (a biblioteca padrão é encontrada automaticamente)
   cargo run --bin compilador -- exemplos/meu_programa.pr --target=bytecode
 
+  # Executar um programa sem gerar .pbc
+  cargo run --bin compilador -- exemplos/meu_programa.pr --executar
+  cargo run --bin compilador -- exemplos/meu_programa.pr --target=llvm-ir --debug-info
+  cargo run --bin compilador -- exemplos/meu_programa.pr --target=llvm-ir -O2
+  cargo run --bin compilador -- exemplos/meu_programa.pr --target=llvm-ir -O2 --sem-verificacoes
+  cargo run --bin compilador -- exemplos/meu_programa.pr --target=c -O2
+  cargo run --bin compilador -- exemplos/meu_programa.pr --target=wasm
+
+  # Construir um projeto com projeto.toml (fontes, alvos e opções vêm do manifesto)
+  cargo run --bin compilador -- construir exemplos/projeto
+
   # Compilar a biblioteca padrão (sempre gera .pbl + .ll)
   cargo run --bin compilador -- --compilar-biblioteca=../sistema-padrao
   # O parâmetro --target é opcional e não afeta a geração da biblioteca
@@ -172,20 +233,36 @@ fn find_stdlib_source_path(args: &[String]) -> Option<PathBuf> {
     None
 }
 
+/// Compila a biblioteca de `caminho_lib` para `dist/`. Com `incremental`, reaproveita o
+/// trabalho da compilação anterior guardado em `dist/incremental/`.
 fn compilar_biblioteca(
     caminho_lib: &Path,
+    bibliotecas: &[PathBuf],
+    incremental: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     println!(
         "=== Compilando Biblioteca: {} → .pbl + .ll ===",
         caminho_lib.display()
     );
 
-    let caminho_src = caminho_lib.join("src");
+    // Com `projeto.toml`, as fontes são as do manifesto; sem ele, todos os `.pr` de `src/`
+    let manifesto = caminho_lib.join(projeto::ARQUIVO_MANIFESTO);
     let mut caminhos_arquivos = Vec::new();
-    for entrada in WalkDir::new(caminho_src) {
-        let entrada = entrada?;
-        if entrada.path().extension().and_then(|s| s.to_str()) == Some("pr") {
-            caminhos_arquivos.push(entrada.path().to_path_buf());
+    if manifesto.is_file() {
+        caminhos_arquivos = projeto::Manifesto::ler(&manifesto)
+            .and_then(|m| m.descobrir_fontes(caminho_lib))
+            .map_err(|msg| {
+                Box::new(
+                    error::ErroCompilador::novo(error::TipoErro::Semântico, msg)
+                        .com_arquivo(manifesto.clone()),
+                )
+            })?;
+    } else {
+        for entrada in WalkDir::new(caminho_lib.join("src")) {
+            let entrada = entrada?;
+            if entrada.path().extension().and_then(|s| s.to_str()) == Some("pr") {
+                caminhos_arquivos.push(entrada.path().to_path_buf());
+            }
         }
     }
 
@@ -201,37 +278,34 @@ fn compilar_biblioteca(
         .map(|p| fs::read_to_string(p))
         .collect::<Result<_, _>>()?;
 
-    let mut asts = Vec::new();
-    for (_caminho, codigo) in caminhos_arquivos.iter().zip(codigos.iter()) {
-        let lx = lexer::Token::lexer(codigo);
-        let tokens: Vec<_> = lx
-            .spanned()
-            .map(|(tok, span)| {
-                tok.map(|t| (span.start, t, span.end)).map_err(|_| {
-                    Box::new(error::ErroCompilador::novo(
-                        error::TipoErro::Léxico,
-                        format!("Erro léxico na biblioteca (arquivo {}): posição {}:{}", _caminho.display(), span.start, span.end)
-                    )) as Box<dyn std::error::Error>
-                })
-            })
-            .collect::<Result<Vec<_>, _>>()?;
-        let mut ast = parser::ArquivoParser::new()
-            .parse(tokens.iter().cloned())
-            .map_err(|e| {
-                Box::new(error::de_lalrpop_error_unit(&e, _caminho.clone(), codigo))
-            })?;
-        crate::interpolacao::walk_programa(&mut ast, |e| {
-            *e = crate::interpolacao::planificar_interpolada(e.clone());
-        });
-        asts.push(ast);
-    }
+    let caminho_dist = caminho_lib.join("dist");
+    let cache = if incremental {
+        Some(incremental::CacheIncremental::abrir(
+            &caminho_dist.join(incremental::DIRETORIO),
+        )?)
+    } else {
+        None
+    };
+    // Cada arquivo é lexado e parseado em paralelo; vale o erro do primeiro, na ordem dos arquivos
+    let fontes: Vec<(&PathBuf, &String)> = caminhos_arquivos.iter().zip(codigos.iter()).collect();
+    let asts = paralelo::mapear(&fontes, |&(caminho, codigo)| match &cache {
+        Some(cache) => cache.analisar(caminho, codigo, "biblioteca", || {
+            parsear_arquivo_biblioteca(caminho, codigo)
+        }),
+        None => parsear_arquivo_biblioteca(caminho, codigo),
+    })
+    .into_iter()
+    .collect::<Result<Vec<_>, _>>()?;
+    let mut plano = cache
+        .as_ref()
+        .map(|c| c.planejar(&caminhos_arquivos, &incremental::contexto("biblioteca", bibliotecas)));
 
     let mut programa_final = ast::Programa {
         usings: vec![],
         namespaces: vec![],
         declaracoes: vec![],
     };
-    for mut ast in asts {
+    for mut ast in asts.iter().cloned() {
         programa_final.declaracoes.extend(ast.declaracoes);
         programa_final.usings.extend(ast.usings);
         for ns in ast.namespaces.drain(..) {
@@ -252,6 +326,32 @@ fn compilar_biblioteca(
     for ns in &programa_final.namespaces {
         tc.registrar_namespace_stdlib(&ns.nome);
     }
+    // Bibliotecas das quais esta depende, já compiladas
+    let mut externa: Option<library_loader::Biblioteca> = None;
+    for caminho in bibliotecas {
+        let bib = library_loader::carregar_biblioteca(caminho).map_err(|e| {
+            Box::new(
+                error::ErroCompilador::novo(
+                    error::TipoErro::Semântico,
+                    format!("Falha ao carregar a biblioteca: {}", e),
+                )
+                .com_arquivo(caminho.clone()),
+            )
+        })?;
+        for ns in bib.namespaces() {
+            tc.registrar_namespace_stdlib(&ns);
+        }
+        match &mut externa {
+            Some(externa) => externa.mesclar(bib),
+            None => externa = Some(bib),
+        }
+    }
+    if let Some(bib) = externa {
+        tc.definir_biblioteca_externa(bib);
+    }
+    if let Some(plano) = &plano {
+        plano.preparar_verificador(&asts, &mut tc);
+    }
     if let Err(erros) = tc.verificar_programa(&programa_final) {
         for erro in &erros {
             eprintln!("Erro Semântico na biblioteca: {}", erro);
@@ -262,17 +362,30 @@ fn compilar_biblioteca(
         )));
     }
 
-    let caminho_dist = caminho_lib.join("dist");
     fs::create_dir_all(&caminho_dist)?;
 
     let mut gerador = codegen::GeradorCodigo::new()?;
 
     // Gera .pbl (formato moderno)
-    let (nome_lib, versao_lib) = ler_metadados_biblioteca(caminho_lib);
-    let conteudo_pbl = gerador.gerar_pbl(&programa_final, &mut tc, &nome_lib, &versao_lib)?;
+    let (nome_lib, versao_lib) = projeto::metadados_biblioteca(caminho_lib).map_err(|msg| {
+        Box::new(error::ErroCompilador::novo(error::TipoErro::Semântico, msg))
+    })?;
+    let bytecode = plano.as_mut().map(|p| p.gerar_bytecode(&asts, &tc));
+    let conteudo_pbl =
+        gerador.gerar_pbl(&programa_final, &mut tc, &nome_lib, &versao_lib, bytecode)?;
     let caminho_saida_pbl = caminho_dist.join(format!("{}.pbl", nome_lib.to_lowercase()));
-    fs::write(&caminho_saida_pbl, conteudo_pbl)?;
+    artefato::gravar(&caminho_saida_pbl, &conteudo_pbl)?;
     println!("✅ Biblioteca .pbl gerada em: {}", caminho_saida_pbl.display());
+    if let (Some(cache), Some(plano)) = (&cache, &plano) {
+        cache.concluir(plano);
+        println!("{}", cache.resumo(plano));
+    }
+
+    // O backend LLVM ainda não enxerga classes de outras bibliotecas
+    if !bibliotecas.is_empty() {
+        println!("ℹ️  LLVM IR da biblioteca omitido: ela depende de outras bibliotecas");
+        return Ok(());
+    }
 
     // Gera LLVM IR da biblioteca
     let nome_arquivo_ll = nome_lib.to_lowercase();
@@ -284,24 +397,33 @@ fn compilar_biblioteca(
     Ok(())
 }
 
-/// Lê nome e versão do arquivo `Sistema.toml` (ou equivalente) da biblioteca.
-fn ler_metadados_biblioteca(caminho_lib: &Path) -> (String, String) {
-    let toml_path = caminho_lib.join("Sistema.toml");
-    if let Ok(conteudo) = fs::read_to_string(&toml_path) {
-        let mut nome = "Sistema".to_string();
-        let mut versao = "1.0.0".to_string();
-        for linha in conteudo.lines() {
-            if let Some(v) = linha.strip_prefix("nome = ") {
-                nome = v.trim_matches('"').to_string();
-            }
-            if let Some(v) = linha.strip_prefix("versao = ") {
-                versao = v.trim_matches('"').to_string();
-            }
-        }
-        (nome, versao)
-    } else {
-        ("Sistema".to_string(), "1.0.0".to_string())
-    }
+/// Analisa um arquivo de biblioteca. Sem tabela de linhas: o bytecode das bibliotecas não leva
+/// as marcas de linha do teste de mesa.
+fn parsear_arquivo_biblioteca(
+    caminho: &Path,
+    codigo: &str,
+) -> Result<ast::Programa, Box<error::ErroCompilador>> {
+    let lx = lexer::Token::lexer(codigo);
+    let tokens: Vec<_> = lx
+        .spanned()
+        .map(|(tok, span)| {
+            tok.map(|t| (span.start, t, span.end)).map_err(|_| {
+                Box::new(error::ErroCompilador::novo(
+                    error::TipoErro::Léxico,
+                    format!("Erro léxico na biblioteca (arquivo {}): posição {}:{}", caminho.display(), span.start, span.end)
+                ))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut ast = parser::ArquivoParser::new()
+        .parse(None, tokens.iter().cloned())
+        .map_err(|e| {
+            Box::new(error::de_lalrpop_error_unit(&e, caminho.to_path_buf(), codigo))
+        })?;
+    crate::interpolacao::walk_programa(&mut ast, |e| {
+        *e = crate::interpolacao::planificar_interpolada(e.clone());
+    });
+    Ok(ast)
 }
 
 /// Carrega todos os arquivos .pr do sistema-padrão, parseia e retorna um AST combinado.
@@ -358,7 +480,7 @@ fn carregar_fontes_stdlib(
             }
         };
 
-        let mut ast_arquivo = match parser::ArquivoParser::new().parse(tokens.iter().cloned()) {
+        let mut ast_arquivo = match parser::ArquivoParser::new().parse(None, tokens.iter().cloned()) {
             Ok(a) => a,
             Err(e) => {
                 eprintln!(
@@ -414,16 +536,649 @@ fn carregar_fontes_stdlib(
     Ok((programa_stdlib, namespaces_stdlib))
 }
 
+/// Caminhos de busca dos módulos: os padrões (`.`, `./modulos`, `./lib`), os diretórios de
+/// `PORDOSOL_MODULOS` e os de cada `--modulos=<path>`.
+fn resolvedor_modulos(args: &[String]) -> module_system::ResolvedorCaminhos {
+    let mut resolvedor = module_system::ResolvedorCaminhos::new();
+    if let Some(caminhos) = env::var_os("PORDOSOL_MODULOS") {
+        for caminho in env::split_paths(&caminhos) {
+            resolvedor.adicionar_caminho(caminho);
+        }
+    }
+    for arg in args {
+        if let Some(caminho) = arg.strip_prefix("--modulos=") {
+            resolvedor.adicionar_caminho(PathBuf::from(caminho));
+        }
+    }
+    resolvedor
+}
+
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = env::args().collect();
 
+    match args.get(1).map(String::as_str) {
+        Some("testar") => return comando_testar(&args),
+        Some("formatar") => return comando_formatar(&args),
+        Some("analisar") => return comando_analisar(&args),
+        Some("construir") => return comando_construir(&args),
+        Some("publicar") => return comando_publicar(&args),
+        Some("compat") => return comando_compat(&args),
+        Some("chave") => return comando_chave(&args),
+        Some("assinar") => return comando_assinar(&args),
+        Some("verificar") => return comando_verificar(&args),
+        _ => {}
+    }
+
+    compilar(&args, false)
+}
+
+/// Um argumento de linha de comando vira a lista de `.pr`: o próprio arquivo ou, para
+/// diretórios, todos os `.pr` encontrados recursivamente (em ordem alfabética).
+fn expandir_arquivos_pr(arg: &str) -> Vec<PathBuf> {
+    if !Path::new(arg).is_dir() {
+        return vec![PathBuf::from(arg)];
+    }
+    let mut encontrados: Vec<PathBuf> = WalkDir::new(arg)
+        .into_iter()
+        .filter_map(Result::ok)
+        .map(|e| e.into_path())
+        .filter(|p| p.extension().is_some_and(|e| e == "pr"))
+        .collect();
+    encontrados.sort();
+    encontrados
+}
+
+/// `compilador analisar <arquivo.pr|diretório>... [--config=<lint.json>] [--regras]`
+///
+/// Executa o linter e imprime um diagnóstico por linha. Termina com código 1 se alguma
+/// regra configurada como `erro` for violada.
+fn comando_analisar(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let caminho_config = args
+        .iter()
+        .find_map(|a| a.strip_prefix("--config=").map(PathBuf::from))
+        .or_else(|| {
+            let padrao = PathBuf::from(linter::ARQUIVO_CONFIGURACAO);
+            padrao.is_file().then_some(padrao)
+        });
+    let config = match caminho_config {
+        Some(caminho) => linter::ConfiguracaoLint::de_json(&fs::read_to_string(&caminho)?)
+            .map_err(|msg| {
+                Box::new(
+                    error::ErroCompilador::novo(error::TipoErro::Semântico, msg)
+                        .com_arquivo(caminho),
+                )
+            })?,
+        None => linter::ConfiguracaoLint::default(),
+    };
+
+    if args.iter().any(|a| a == "--regras") {
+        for regra in linter::REGRAS {
+            println!("{:<30} {:<9} {}", regra.nome, config.nivel(regra.nome).to_string(), regra.descricao);
+        }
+        return Ok(());
+    }
+
+    let arquivos: Vec<PathBuf> = args[2..]
+        .iter()
+        .filter(|a| !a.starts_with("--"))
+        .flat_map(|a| expandir_arquivos_pr(a))
+        .collect();
+    if arquivos.is_empty() {
+        return Err(Box::new(error::ErroCompilador::novo(
+            error::TipoErro::Sintático,
+            "Uso: compilador analisar <arquivo.pr|diretório>... [--config=<lint.json>] [--regras]"
+                .to_string(),
+        )));
+    }
+
+    let (mut avisos, mut erros) = (0, 0);
+    for arquivo in &arquivos {
+        let fonte = fs::read_to_string(arquivo)?;
+        for diagnostico in linter::analisar_fonte(&fonte, &arquivo.display().to_string(), &config)? {
+            match diagnostico.nivel {
+                linter::Nivel::Erro => erros += 1,
+                _ => avisos += 1,
+            }
+            println!("{}", diagnostico);
+        }
+    }
+    println!(
+        "{} arquivo(s) analisado(s): {} erro(s), {} aviso(s)",
+        arquivos.len(),
+        erros,
+        avisos
+    );
+    if erros > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// `compilador formatar <arquivo.pr|diretório>... [--verificar]`
+///
+/// Reescreve os arquivos no estilo canônico. Com `--verificar` nada é gravado: apenas
+/// lista os arquivos fora do padrão e termina com código 1 se houver algum (uso em CI).
+fn comando_formatar(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let verificar = args.iter().any(|a| a == "--verificar");
+    let arquivos: Vec<PathBuf> = args[2..]
+        .iter()
+        .filter(|a| !a.starts_with("--"))
+        .flat_map(|a| expandir_arquivos_pr(a))
+        .collect();
+    if arquivos.is_empty() {
+        return Err(Box::new(error::ErroCompilador::novo(
+            error::TipoErro::Sintático,
+            "Uso: compilador formatar <arquivo.pr|diretório>... [--verificar]".to_string(),
+        )));
+    }
+
+    let mut fora_do_padrao = 0;
+    for arquivo in &arquivos {
+        let fonte = fs::read_to_string(arquivo)?;
+        let formatado = formatador::formatar(&fonte)
+            .map_err(|e| Box::new((*e).com_arquivo(arquivo.clone())))?;
+        if formatado == fonte {
+            continue;
+        }
+        fora_do_padrao += 1;
+        if verificar {
+            println!("não formatado: {}", arquivo.display());
+        } else {
+            fs::write(arquivo, &formatado)?;
+            println!("formatado: {}", arquivo.display());
+        }
+    }
+
+    if verificar && fora_do_padrao > 0 {
+        eprintln!(
+            "{} de {} arquivo(s) fora do padrão. Execute 'compilador formatar' para corrigir.",
+            fora_do_padrao,
+            arquivos.len()
+        );
+        std::process::exit(1);
+    }
+    println!(
+        "{} arquivo(s) verificado(s), {} {}",
+        arquivos.len(),
+        fora_do_padrao,
+        if verificar { "fora do padrão" } else { "reformatado(s)" }
+    );
+    Ok(())
+}
+
+/// `compilador testar <arquivo.pr|diretório>... [--filtro=<nome>] [--junit=<arquivo.xml>]`
+///
+/// Compila os arquivos com o prelúdio de testes (classe `Afirmar`) para
+/// `build/testes/<nome>.pbc` e executa as funções/métodos `[Teste]` no interpretador.
+fn comando_testar(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let uso = |msg: String| {
+        Box::new(error::ErroCompilador::novo(
+            error::TipoErro::Sintático,
+            format!(
+                "{}Uso: compilador testar <arquivo.pr|diretório>... [--filtro=<nome>] [--junit=<arquivo.xml>] [--stdlib-src-path=<path>]",
+                msg
+            ),
+        ))
+    };
+    let mut arquivos = Vec::new();
+    let mut repassar = Vec::new();
+    for arg in &args[2..] {
+        if arg.starts_with("--filtro=") || arg.starts_with("--junit=") {
+            repassar.push(arg.clone());
+        } else if arg.starts_with("--stdlib-src-path=") {
+            // Repassada à compilação abaixo
+        } else if arg.starts_with("--") {
+            return Err(uso(format!("opção desconhecida '{}'. ", arg)));
+        } else {
+            arquivos.extend(expandir_arquivos_pr(arg));
+        }
+    }
+    if arquivos.is_empty() {
+        return Err(uso(String::new()));
+    }
+
+    let dir_saida = PathBuf::from("build").join("testes");
+    let mut args_compilacao = vec![args[0].clone()];
+    args_compilacao.extend(arquivos.iter().map(|p| p.display().to_string()));
+    args_compilacao.push("--target=bytecode".to_string());
+    args_compilacao.push(format!("--output-dir={}", dir_saida.display()));
+    args_compilacao.extend(
+        args.iter()
+            .filter(|a| a.starts_with("--stdlib-src-path="))
+            .cloned(),
+    );
+    compilar(&args_compilacao, true)?;
+
+    let nome_base = arquivos
+        .last()
+        .and_then(|p| p.file_stem())
+        .and_then(|s| s.to_str())
+        .unwrap_or("saida");
+    let pbc = dir_saida.join(format!("{}.pbc", nome_base));
+    let interpretador = env::current_exe()?
+        .with_file_name(format!("interpretador{}", env::consts::EXE_SUFFIX));
+
+    println!();
+    let status = std::process::Command::new(&interpretador)
+        .arg(&pbc)
+        .arg("--testar")
+        .args(&repassar)
+        .status()
+        .map_err(|e| {
+            format!(
+                "Não foi possível executar o interpretador ({}): {}",
+                interpretador.display(),
+                e
+            )
+        })?;
+    if !status.success() {
+        std::process::exit(status.code().unwrap_or(1));
+    }
+    Ok(())
+}
+
+/// `compilador construir [diretório] [--alvo=<alvo>]...`
+///
+/// Lê o `projeto.toml` do diretório (padrão: o atual), descobre os `.pr` das fontes e gera
+/// cada alvo do manifesto (ou só os de `--alvo=`) em `<diretório>/dist/<alvo>/`.
+fn comando_construir(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let erro = |msg: String| {
+        Box::new(error::ErroCompilador::novo(error::TipoErro::Semântico, msg))
+    };
+    let raiz = args[2..]
+        .iter()
+        .find(|a| !a.starts_with("--"))
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."));
+    let caminho_manifesto = raiz.join(projeto::ARQUIVO_MANIFESTO);
+    if !caminho_manifesto.is_file() {
+        return Err(erro(format!(
+            "'{}' não encontrado. Uso: compilador construir [diretório] [--alvo=<alvo>]",
+            caminho_manifesto.display()
+        )));
+    }
+    let manifesto = projeto::Manifesto::ler(&caminho_manifesto).map_err(erro)?;
+    let fontes = manifesto.descobrir_fontes(&raiz).map_err(erro)?;
+
+    let mut alvos: Vec<String> = args
+        .iter()
+        .filter_map(|a| a.strip_prefix("--alvo="))
+        .map(str::to_string)
+        .collect();
+    if alvos.is_empty() {
+        alvos = manifesto.alvos();
+    }
+    if let Some(alvo) = alvos.iter().find(|a| !projeto::ALVOS.contains(&a.as_str())) {
+        return Err(erro(format!(
+            "alvo desconhecido '{}' (disponíveis: {})",
+            alvo,
+            projeto::ALVOS.join(", ")
+        )));
+    }
+
+    // Os demais alvos ainda não ligam o código das bibliotecas ao programa
+    if !manifesto.dependencias.is_empty() {
+        if let Some(alvo) = alvos.iter().find(|a| !["bytecode", "biblioteca"].contains(&a.as_str())) {
+            return Err(erro(format!(
+                "as dependências só podem ser usadas pelos alvos bytecode e biblioteca (pedido: '{}')",
+                alvo
+            )));
+        }
+    }
+
+    let dist = raiz.join("dist");
+    let mut opcoes = vec![format!("--nome={}", manifesto.projeto.nome)];
+    let compilacao = &manifesto.compilacao;
+    if let Some(nivel) = &compilacao.otimizacao {
+        opcoes.push(format!("-{}", nivel.trim_start_matches('-')));
+    }
+    if !compilacao.verificacoes {
+        opcoes.push("--sem-verificacoes".to_string());
+    }
+    if compilacao.debug {
+        opcoes.push("--debug-info".to_string());
+    }
+    if let Some(stdlib) = &compilacao.stdlib {
+        opcoes.push(format!("--stdlib-src-path={}", raiz.join(stdlib).display()));
+    }
+    for dir in &compilacao.modulos {
+        opcoes.push(format!("--modulos={}", raiz.join(dir).display()));
+    }
+    let incremental = !args.iter().any(|a| a == "--sem-incremental");
+    if incremental {
+        opcoes.push(format!(
+            "--incremental={}",
+            dist.join(incremental::DIRETORIO).display()
+        ));
+    }
+    let bibliotecas = preparar_dependencias(
+        &raiz,
+        &manifesto,
+        args.iter().any(|a| a == "--atualizar"),
+        incremental,
+    )?;
+    for pbl in &bibliotecas {
+        opcoes.push(format!("--biblioteca={}", pbl.display()));
+    }
+
+    println!(
+        "=== Construindo {} {} ({} arquivo(s), alvos: {}) ===",
+        manifesto.projeto.nome,
+        manifesto.projeto.versao,
+        fontes.len(),
+        alvos.join(", ")
+    );
+    for alvo in &alvos {
+        println!("\n--- {} ---", alvo);
+        if alvo == "biblioteca" {
+            // Nome e versão do .pbl vêm do manifesto; vai direto para dist/, como na stdlib
+            compilar_biblioteca(&raiz, &bibliotecas, incremental)?;
+            continue;
+        }
+        // Um subdiretório por alvo: os executáveis de `llvm-ir` e `c` (e o projeto de
+        // `console`) teriam o mesmo nome
+        let mut args_compilacao = vec![args[0].clone()];
+        args_compilacao.extend(fontes.iter().map(|p| p.display().to_string()));
+        args_compilacao.push(format!("--target={}", alvo));
+        args_compilacao.push(format!("--output-dir={}", dist.join(alvo).display()));
+        args_compilacao.extend(opcoes.iter().cloned());
+        compilar(&args_compilacao, false)?;
+    }
+    println!("\n✅ Projeto construído em {}", dist.display());
+    Ok(())
+}
+
+/// Resolve as dependências do manifesto (respeitando o `projeto.lock`, a menos que
+/// `atualizar`), compila as que vêm por caminho e devolve os `.pbl` na ordem de carga.
+fn preparar_dependencias(
+    raiz: &Path,
+    manifesto: &projeto::Manifesto,
+    atualizar: bool,
+    incremental: bool,
+) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let erro = |msg: String| {
+        Box::new(
+            error::ErroCompilador::novo(error::TipoErro::Semântico, msg)
+                .com_arquivo(raiz.join(projeto::ARQUIVO_MANIFESTO)),
+        )
+    };
+    if manifesto.dependencias.is_empty() {
+        return Ok(Vec::new());
+    }
+    let fontes = pacotes::Fontes::do_manifesto(raiz, manifesto);
+    let lock = if atualizar {
+        None
+    } else {
+        pacotes::Lock::ler(raiz).map_err(erro)?
+    };
+    let resolvidos = pacotes::resolver(raiz, manifesto, &fontes, lock.as_ref()).map_err(erro)?;
+    if pacotes::Lock::de_pacotes(&resolvidos, raiz)
+        .gravar(raiz)
+        .map_err(erro)?
+    {
+        println!("🔒 {} atualizado", pacotes::ARQUIVO_LOCK);
+    }
+
+    let mut bibliotecas = Vec::new();
+    for pacote in &resolvidos {
+        match &pacote.origem {
+            pacotes::Origem::Registro => {
+                println!("📦 {} {} (registro)", pacote.nome, pacote.versao);
+            }
+            pacotes::Origem::Caminho(dir) => {
+                println!("📦 {} {} ({})", pacote.nome, pacote.versao, dir.display());
+                // As dependências do pacote vêm antes dele e já estão em `bibliotecas`
+                compilar_biblioteca(dir, &bibliotecas, incremental)?;
+            }
+        }
+        bibliotecas.push(pacote.pbl(&fontes.cache));
+    }
+    Ok(bibliotecas)
+}
+
+/// `compilador publicar [diretório] [--registro=<dir>] [--chave=<arquivo>]`
+///
+/// Constrói a biblioteca do diretório e a copia para o registro local (o de `--registro=`,
+/// o de `[pacotes] registro` ou `PORDOSOL_REGISTRO`).
+fn comando_publicar(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let erro = |msg: String| {
+        Box::new(error::ErroCompilador::novo(error::TipoErro::Semântico, msg))
+    };
+    let raiz = args[2..]
+        .iter()
+        .find(|a| !a.starts_with("--"))
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."));
+    let caminho_manifesto = raiz.join(projeto::ARQUIVO_MANIFESTO);
+    if !caminho_manifesto.is_file() {
+        return Err(erro(format!(
+            "'{}' não encontrado. Uso: compilador publicar [diretório] [--registro=<dir>] [--chave=<arquivo>]",
+            caminho_manifesto.display()
+        )));
+    }
+    let manifesto = projeto::Manifesto::ler(&caminho_manifesto).map_err(erro)?;
+    let registro = args
+        .iter()
+        .find_map(|a| a.strip_prefix("--registro="))
+        .map(PathBuf::from)
+        .or_else(|| pacotes::Fontes::do_manifesto(&raiz, &manifesto).registro)
+        .ok_or_else(|| {
+            erro("nenhum registro: use --registro=<dir>, [pacotes] registro ou PORDOSOL_REGISTRO".to_string())
+        })?;
+
+    let destino = pacotes::destino_publicacao(&manifesto, &registro).map_err(erro)?;
+
+    let chave = args
+        .iter()
+        .find_map(|a| a.strip_prefix("--chave="))
+        .map(|c| artefato::Chave::ler(Path::new(c)))
+        .transpose()
+        .map_err(erro)?;
+
+    let incremental = !args.iter().any(|a| a == "--sem-incremental");
+    let bibliotecas = preparar_dependencias(&raiz, &manifesto, false, incremental)?;
+    compilar_biblioteca(&raiz, &bibliotecas, incremental)?;
+    if let Some(chave) = chave {
+        let pbl = raiz.join("dist").join(pacotes::arquivo_pbl(&manifesto.projeto.nome));
+        chave.assinar(&pbl).map_err(erro)?;
+    }
+    pacotes::publicar(&raiz, &manifesto, &destino).map_err(erro)?;
+    println!(
+        "✅ {} {} publicado em {}",
+        manifesto.projeto.nome,
+        manifesto.projeto.versao,
+        destino.display()
+    );
+    Ok(())
+}
+
+/// `compilador compat <antiga.pbl> <nova.pbl>`
+///
+/// Compara a API pública das duas versões, lista as mudanças que quebram e as compatíveis e
+/// sugere o incremento de versão. Termina com código 1 se o `versao=` da nova não bastar.
+fn comando_compat(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let erro = |msg: String| {
+        Box::new(error::ErroCompilador::novo(error::TipoErro::Semântico, msg))
+    };
+    let caminhos: Vec<&String> = args[2..].iter().filter(|a| !a.starts_with("--")).collect();
+    let [antiga, nova] = caminhos[..] else {
+        return Err(erro("Uso: compilador compat <antiga.pbl> <nova.pbl>".to_string()));
+    };
+    let carregar = |caminho: &String| {
+        library_loader::carregar_biblioteca(Path::new(caminho))
+            .map_err(|e| erro(format!("não foi possível ler '{}': {}", caminho, e)))
+    };
+    let (antiga, nova) = (carregar(antiga)?, carregar(nova)?);
+    let relatorio = compatibilidade::comparar(&antiga, &nova);
+
+    let descrever = |bib: &library_loader::Biblioteca| {
+        format!(
+            "{} {}",
+            bib.nome.as_deref().unwrap_or("?"),
+            bib.versao.as_deref().unwrap_or("(sem versão)")
+        )
+    };
+    println!("Compatibilidade: {} → {}", descrever(&antiga), descrever(&nova));
+    let quebras: Vec<_> = relatorio.quebras().collect();
+    let compativeis: Vec<_> = relatorio.compativeis().collect();
+    if relatorio.mudancas.is_empty() {
+        println!("\nNenhuma mudança na API pública.");
+    }
+    if !quebras.is_empty() {
+        println!("\nMudanças que quebram ({}):", quebras.len());
+        for mudanca in &quebras {
+            println!("  ✗ {}", mudanca);
+        }
+    }
+    if !compativeis.is_empty() {
+        println!("\nMudanças compatíveis ({}):", compativeis.len());
+        for mudanca in &compativeis {
+            println!("  ✓ {}", mudanca);
+        }
+    }
+
+    let versao = |bib: &library_loader::Biblioteca| {
+        bib.versao.as_deref().and_then(|v| semver::Version::parse(v).ok())
+    };
+    let incremento = relatorio.incremento();
+    let Some(versao_antiga) = versao(&antiga) else {
+        println!("\nIncremento necessário: {}", incremento);
+        return Ok(());
+    };
+    println!(
+        "\nIncremento necessário: {} ({} → {})",
+        incremento,
+        versao_antiga,
+        incremento.aplicar(&versao_antiga)
+    );
+    if let Some(versao_nova) = versao(&nova) {
+        match relatorio.verificar_versao(&versao_antiga, &versao_nova) {
+            Ok(()) => println!("✅ A versão {} é adequada.", versao_nova),
+            Err(msg) => {
+                eprintln!("❌ {}", msg);
+                std::process::exit(1);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `compilador chave <arquivo>`
+///
+/// Gera um par de chaves Ed25519 para assinar bibliotecas. A chave pública impressa é a que
+/// os consumidores listam no arquivo de `PORDOSOL_CHAVES`.
+fn comando_chave(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let erro = |msg: String| {
+        Box::new(error::ErroCompilador::novo(error::TipoErro::Semântico, msg))
+    };
+    let Some(caminho) = args.get(2).filter(|a| !a.starts_with("--")).map(PathBuf::from) else {
+        return Err(erro("Uso: compilador chave <arquivo>".to_string()));
+    };
+    if caminho.exists() {
+        return Err(erro(format!("'{}' já existe; escolha outro arquivo", caminho.display())));
+    }
+    let chave = artefato::Chave::gerar().map_err(erro)?;
+    fs::write(&caminho, chave.para_texto())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&caminho, fs::Permissions::from_mode(0o600))?;
+    }
+    println!("🔑 Chave gravada em {} (mantenha-a em segredo)", caminho.display());
+    println!("Chave pública: {}", chave.publica());
+    Ok(())
+}
+
+/// `compilador assinar <arquivo.pbl|arquivo.pbc>... --chave=<arquivo>`
+fn comando_assinar(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let erro = |msg: String| {
+        Box::new(error::ErroCompilador::novo(error::TipoErro::Semântico, msg))
+    };
+    let uso = || erro("Uso: compilador assinar <arquivo>... --chave=<arquivo>".to_string());
+    let chave = args
+        .iter()
+        .find_map(|a| a.strip_prefix("--chave="))
+        .ok_or_else(uso)?;
+    let chave = artefato::Chave::ler(Path::new(chave)).map_err(erro)?;
+    let arquivos: Vec<&String> = args[2..].iter().filter(|a| !a.starts_with("--")).collect();
+    if arquivos.is_empty() {
+        return Err(uso());
+    }
+    for arquivo in arquivos {
+        let sig = chave.assinar(Path::new(arquivo)).map_err(erro)?;
+        println!("✅ {} assinado ({})", arquivo, sig.display());
+    }
+    Ok(())
+}
+
+/// `compilador verificar <arquivo.pbl|arquivo.pbc>... [--chaves=<arquivo>]`
+///
+/// Confere formato, hash e assinatura. Com `--chaves` (ou `PORDOSOL_CHAVES`), exige que cada
+/// arquivo esteja assinado por uma das chaves listadas. Termina com código 1 se algum falhar.
+fn comando_verificar(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let erro = |msg: String| {
+        Box::new(error::ErroCompilador::novo(error::TipoErro::Semântico, msg))
+    };
+    let confiaveis = match args.iter().find_map(|a| a.strip_prefix("--chaves=")) {
+        Some(caminho) => Some(artefato::ler_chaves(Path::new(caminho)).map_err(erro)?),
+        None => artefato::chaves_confiaveis().map_err(erro)?,
+    };
+    let arquivos: Vec<&String> = args[2..].iter().filter(|a| !a.starts_with("--")).collect();
+    if arquivos.is_empty() {
+        return Err(erro("Uso: compilador verificar <arquivo>... [--chaves=<arquivo>]".to_string()));
+    }
+
+    let mut falhas = 0;
+    for arquivo in arquivos {
+        match artefato::verificar(Path::new(arquivo), confiaveis.as_deref()) {
+            Ok(lido) if lido.cabecalho.formato == 0 => {
+                println!("⚠️  {}: formato antigo, sem cabeçalho nem hash para conferir", arquivo)
+            }
+            Ok(lido) => {
+                let cabecalho = &lido.cabecalho;
+                let mut descricao = format!(
+                    "formato {}, compilador {}",
+                    cabecalho.formato,
+                    cabecalho.compilador.as_deref().unwrap_or("?")
+                );
+                if let (Some(nome), Some(versao)) = (cabecalho.valor("nome"), cabecalho.valor("versao")) {
+                    descricao.push_str(&format!(", {} {}", nome, versao));
+                }
+                match &lido.assinado_por {
+                    Some(chave) => descricao.push_str(&format!(", assinado por {}", chave)),
+                    None => descricao.push_str(", sem assinatura"),
+                }
+                println!("✅ {}: {}", arquivo, descricao);
+            }
+            Err(e) => {
+                falhas += 1;
+                eprintln!("❌ {}", e);
+            }
+        }
+    }
+    if falhas > 0 {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn compilar(args: &[String], preludio_testes: bool) -> Result<(), Box<dyn std::error::Error>> {
     if let Some(lib_path) = args
         .iter()
         .find(|arg| arg.starts_with("--compilar-biblioteca="))
     {
         let path = lib_path.split('=').nth(1).unwrap_or(".");
+        let bibliotecas: Vec<PathBuf> = args
+            .iter()
+            .filter_map(|a| a.strip_prefix("--biblioteca="))
+            .map(PathBuf::from)
+            .collect();
         // Sempre gera .pbl + .ll, independente de --target
-        return compilar_biblioteca(Path::new(path));
+        return compilar_biblioteca(
+            Path::new(path),
+            &bibliotecas,
+            !args.iter().any(|a| a == "--sem-incremental"),
+        );
     }
 
     if args.len() <= 1 || args.contains(&"--help".to_string()) {
@@ -455,6 +1210,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .map(|arg| arg.split('=').nth(1).unwrap_or("universal"))
         .map(|t| match t {
             "llvm-ir" => TargetCompilacao::LlvmIr,
+            "c" => TargetCompilacao::C,
+            "wasm" => TargetCompilacao::Wasm,
             "cil-bytecode" => TargetCompilacao::CilBytecode,
             "console" => TargetCompilacao::Console,
             "bytecode" => TargetCompilacao::Bytecode,
@@ -470,43 +1227,66 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .map(|arg| arg.split('=').nth(1).unwrap_or("build"))
         .map(|s| PathBuf::from(s));
 
+    // `--biblioteca=<arquivo.pbl>`: dependências pré-compiladas (pode repetir)
+    let caminhos_bibliotecas: Vec<PathBuf> = args
+        .iter()
+        .filter_map(|arg| arg.strip_prefix("--biblioteca="))
+        .map(PathBuf::from)
+        .collect();
+
+    // `--nome=<nome>` substitui o nome base dos artefatos (padrão: o último arquivo)
+    let nome_explicito = args.iter().find_map(|arg| arg.strip_prefix("--nome="));
+
+    // `--executar` interpreta o programa verificado em vez de gerar código
+    let executar = args.iter().any(|arg| arg == "--executar");
+    let debug_info = args.iter().any(|arg| arg == "--debug-info");
+    let teste_de_mesa = args.iter().any(|arg| arg == "--teste-de-mesa");
+    // Só a VM de bytecode carrega o código das bibliotecas (`LOAD_LIBRARY`)
+    if !caminhos_bibliotecas.is_empty() && (executar || !matches!(target, TargetCompilacao::Bytecode)) {
+        return Err(Box::new(error::ErroCompilador::novo(
+            error::TipoErro::Semântico,
+            "--biblioteca só pode ser usada com --target=bytecode".to_string(),
+        )));
+    }
+    // Verificações de execução do executável LLVM (ligadas por padrão)
+    let verificacoes = !args.iter().any(|arg| arg == "--sem-verificacoes");
+    // `-O0`..`-O3`/`-Os`: nível de otimização do clang (o último informado vale)
+    let mut otimizacao = codegen::NivelOtimizacao::default();
+    for arg in args.iter().skip(1) {
+        if let Some(nivel) = codegen::NivelOtimizacao::de_opcao(arg) {
+            otimizacao = nivel.map_err(|e| {
+                Box::new(error::ErroCompilador::novo(error::TipoErro::Sintático, e))
+            })?;
+        }
+    }
+
     // Carrega a biblioteca padrão — strategy:
     //   1. Para LLVM IR: sempre parseia fontes .pr (precisa da AST completa)
     //   2. Para outros alvos: tenta .pbl pré-compilado, senão cai de volta para fontes
     // Equivalente ao mecanismo de Reference Assemblies do .NET.
+    let mut stdlib_pbl = None;
     let stdlib_info: Option<(
         ast::Programa,
         HashSet<String>,
         Option<library_loader::Biblioteca>,
-    )> = if let Some(stdlib_path) = find_stdlib_source_path(&args) {
+    )> = if let Some(stdlib_path) = find_stdlib_source_path(args) {
         // Tenta .pbl pré-compilado para todos os alvos.
         // O gerador LLVM IR emitirá `declare` para as funções da stdlib carregadas via .pbl.
         let pbl_path = stdlib_path.join("dist").join("sistema.pbl");
+        stdlib_pbl = Some(pbl_path.clone());
 
         if pbl_path.exists() {
-            println!(
-                "📦 Carregando biblioteca padrão pré-compilada (.pbl): {}",
-                pbl_path.display()
-            );
+            // Com --executar, a saída padrão pertence só ao programa
+            if !executar {
+                println!(
+                    "📦 Carregando biblioteca padrão pré-compilada (.pbl): {}",
+                    pbl_path.display()
+                );
+            }
             match library_loader::carregar_biblioteca(&pbl_path) {
                 Ok(bib) => {
                     // Extrai namespaces da biblioteca
-                    let mut ns_set: HashSet<String> = HashSet::new();
-                    for fqn in bib.simbolos.keys() {
-                        // Ex: "Sistema.IO.Arquivo" → "Sistema.IO", "Sistema"
-                        let partes: Vec<&str> = fqn.split('.').collect();
-                        let mut acum = String::new();
-                        for (i, p) in partes.iter().enumerate() {
-                            if i == partes.len() - 1 {
-                                break;
-                            } // ignora o nome da classe
-                            if !acum.is_empty() {
-                                acum.push('.');
-                            }
-                            acum.push_str(p);
-                            ns_set.insert(acum.clone());
-                        }
-                    }
+                    let ns_set = bib.namespaces();
                     // Não carrega no tc_temp para evitar stack overflow
                     // Apenas retorna os namespaces para o type_checker principal
                     let prog_vazio = ast::Programa {
@@ -569,40 +1349,58 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .map(|p| fs::read_to_string(p).map(sanitizar_codigo))
         .collect::<Result<_, _>>()?;
 
-    // Fase 2: Parsear todos os arquivos para ASTs.
-    let mut asts = Vec::new();
-    for (caminho, codigo) in caminhos_arquivos.iter().zip(codigos.iter()) {
-        let lexer = lexer::Token::lexer(codigo);
-        let tokens_result: Result<Vec<_>, _> = lexer
-            .spanned()
-            .map(|(token, span)| token.map(|t| (span.start, t, span.end)))
-            .collect();
-
-        let tokens = match tokens_result {
-            Ok(tokens) => tokens,
-            Err(_) => {
-                let erro = error::ErroCompilador::novo(
-                    error::TipoErro::Léxico,
-                    format!("Token inválido encontrado em '{}'", caminho.display()),
-                )
-                .com_arquivo(caminho.clone());
-                eprintln!("{}", erro.formatar());
-                return Err(Box::new(erro));
-            }
-        };
+    // `--incremental=<dir>`: reaproveita o trabalho das compilações anteriores guardado em
+    // `<dir>`. Os testes (`compilador testar`) compilam sempre do zero.
+    let cache = match args.iter().find_map(|arg| arg.strip_prefix("--incremental=")) {
+        Some(dir) if !preludio_testes && !args.iter().any(|arg| arg == "--sem-incremental") => {
+            Some(Arc::new(incremental::CacheIncremental::abrir(Path::new(dir))?))
+        }
+        _ => None,
+    };
 
-        let parser = parser::ArquivoParser::new();
-        let mut ast = parser.parse(tokens.iter().cloned()).map_err(|e| {
-            let erro = error::de_lalrpop_error_unit(&e, caminho.clone(), codigo);
+    // Fase 2: Parsear todos os arquivos para ASTs, carregando os módulos importados.
+    let mut sistema_modulos = module_system::SistemaModulos::new(resolvedor_modulos(args));
+    if let Some(cache) = &cache {
+        sistema_modulos.usar_cache(cache.clone());
+    }
+    // As posições dos comandos só são registradas quando alguém as usa: o teste de mesa
+    // (`LINE` no bytecode), a depuração e as mensagens das verificações de execução
+    let verificacoes_com_posicao = verificacoes
+        && matches!(
+            target,
+            TargetCompilacao::Universal
+                | TargetCompilacao::LlvmIr
+                | TargetCompilacao::C
+                | TargetCompilacao::Wasm
+                | TargetCompilacao::CilBytecode
+        );
+    if teste_de_mesa || debug_info || executar || verificacoes_com_posicao {
+        sistema_modulos.registrar_posicoes();
+    }
+    // Os arquivos da linha de comando são lexados e parseados em paralelo; os módulos são
+    // registrados (e os erros informados) na ordem dos arquivos
+    let fontes: Vec<(&PathBuf, &String)> = caminhos_arquivos.iter().zip(codigos.iter()).collect();
+    let analises =
+        paralelo::mapear(&fontes, |&(caminho, codigo)| sistema_modulos.parsear(caminho, codigo));
+    for (caminho, analise) in caminhos_arquivos.iter().zip(analises) {
+        analise
+            .and_then(|ast| sistema_modulos.adicionar_principal(caminho, ast))
+            .inspect_err(|erro| eprintln!("{}", erro.formatar()))?;
+    }
+    if let Err(erros) = sistema_modulos.verificar() {
+        for erro in erros {
             eprintln!("{}", erro.formatar());
-            Box::new(erro)
-        })?;
-
-        crate::interpolacao::walk_programa(&mut ast, |e| {
-            *e = interpolacao::planificar_interpolada(e.clone());
-        });
-        asts.push(ast);
+        }
+        return Err(Box::new(error::ErroCompilador::novo(
+            error::TipoErro::Semântico,
+            "Houve erros nas importações entre módulos.".to_string(),
+        )));
+    }
+    let mut asts = Vec::new();
+    if preludio_testes {
+        asts.push(testes::preludio());
     }
+    asts.push(sistema_modulos.montar_programa());
 
     // Fase 3: Juntar ASTs para uma análise semântica unificada.
     let mut programa_final = ast::Programa {
@@ -633,14 +1431,18 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Fase 3.5: Injetar stdlib no contexto de tipo — dois modos:
     //   a) Via .pbl/.pbc pré-compilado: stdlib_tc já tem os tipos carregados; apenas regista namespaces
     //   b) Via fontes .pr: mescla namespaces/declarações no AST para análise semântica unificada
-    let stdlib_namespaces: HashSet<String>;
+    let mut stdlib_namespaces: HashSet<String>;
+    let mut biblioteca_externa: Option<library_loader::Biblioteca> = None;
+    // O que foi carregado de fora entra na chave do cache incremental
+    let mut bibliotecas_carregadas = Vec::new();
 
     if let Some((programa_stdlib, ns_stdlib, bib_opt)) = stdlib_info {
         stdlib_namespaces = ns_stdlib;
 
         // Passa biblioteca externa para o type_checker se disponível
         if let Some(ref bib) = bib_opt {
-            type_checker.definir_biblioteca_externa(bib.clone());
+            biblioteca_externa = Some(bib.clone());
+            bibliotecas_carregadas.extend(stdlib_pbl);
         }
 
         // Se não conseguiu carregar o .pbl, precisa mesclar a AST fonte para funcionar.
@@ -665,11 +1467,47 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         stdlib_namespaces = HashSet::new();
     }
 
+    // Dependências pré-compiladas (`--biblioteca=`) são tratadas como a stdlib via .pbl
+    bibliotecas_carregadas.extend(caminhos_bibliotecas.iter().cloned());
+    for caminho in &caminhos_bibliotecas {
+        let bib = library_loader::carregar_biblioteca(caminho).map_err(|e| {
+            Box::new(
+                error::ErroCompilador::novo(
+                    error::TipoErro::Semântico,
+                    format!("Falha ao carregar a biblioteca: {}", e),
+                )
+                .com_arquivo(caminho.clone()),
+            )
+        })?;
+        stdlib_namespaces.extend(bib.namespaces());
+        match &mut biblioteca_externa {
+            Some(externa) => externa.mesclar(bib),
+            None => biblioteca_externa = Some(bib),
+        }
+    }
+    if let Some(bib) = biblioteca_externa {
+        type_checker.definir_biblioteca_externa(bib);
+    }
+
     // Informa ao verificador de tipos quais namespaces pertencem à stdlib
     for ns in &stdlib_namespaces {
         type_checker.registrar_namespace_stdlib(ns);
     }
 
+    // Arquivos sem mudança (nem neles, nem nas assinaturas de que dependem) não têm os corpos
+    // verificados de novo
+    let (caminhos_partes, partes): (Vec<PathBuf>, Vec<ast::Programa>) =
+        sistema_modulos.partes().into_iter().unzip();
+    let mut plano = cache.as_ref().map(|cache| {
+        cache.planejar(
+            &caminhos_partes,
+            &incremental::contexto("programa", &bibliotecas_carregadas),
+        )
+    });
+    if let Some(plano) = &plano {
+        plano.preparar_verificador(&partes, &mut type_checker);
+    }
+
     if let Err(erros) = type_checker.verificar_programa(&programa_final) {
         for erro in erros {
             eprintln!("{}", erro.formatar());
@@ -680,27 +1518,80 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         )));
     }
 
+    if executar {
+        if let Err(erro) = runtime::executar_programa_otimizado(&programa_final, &type_checker) {
+            eprintln!("Erro em tempo de execução: {}", erro);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     // Fase 5: Geração de código.
-    let nome_base = caminhos_arquivos
-        .last() // Usa o último arquivo (provavelmente o principal do usuário) para o nome base
-        .and_then(|p| p.file_stem())
-        .and_then(|s| s.to_str())
-        .unwrap_or("saida");
+    let nome_base = nome_explicito.unwrap_or_else(|| {
+        caminhos_arquivos
+            .last() // Usa o último arquivo (provavelmente o principal do usuário) para o nome base
+            .and_then(|p| p.file_stem())
+            .and_then(|s| s.to_str())
+            .unwrap_or("saida")
+    });
+    // Com --output-dir, todos os artefatos vão para o diretório; sem ele, só o bytecode
+    // (em `build/`) não fica no diretório atual
+    let saida = match &output_dir {
+        Some(dir) => {
+            fs::create_dir_all(dir)?;
+            dir.join(nome_base).display().to_string()
+        }
+        None => nome_base.to_string(),
+    };
+    let executavel = if output_dir.is_some() {
+        saida.clone()
+    } else {
+        format!("./{}", nome_base)
+    };
+    // Os metadados de depuração usam o caminho como o parser registrou nas posições
+    let arquivo_debug = caminhos_arquivos
+        .last()
+        .filter(|_| debug_info)
+        .map(|p| p.display().to_string());
+
+    // Só o bytecode é montado por arquivo; os demais alvos são gerados do programa inteiro
+    let bytecode = match (&target, &mut plano) {
+        (TargetCompilacao::Bytecode | TargetCompilacao::Biblioteca, Some(plano)) => {
+            Some(plano.gerar_bytecode(&partes, &type_checker))
+        }
+        _ => None,
+    };
 
-    match target {
+    let resultado = match target {
         TargetCompilacao::Universal => {
-            compilar_universal(&programa_final, &mut type_checker, nome_base, output_dir.as_ref())
+            compilar_universal(
+                &programa_final,
+                &mut type_checker,
+                nome_base,
+                &saida,
+                output_dir.as_ref(),
+                arquivo_debug.as_deref(),
+                verificacoes,
+            )
         }
         TargetCompilacao::LlvmIr => {
-            compilar_para_llvm_ir(&programa_final, &mut type_checker, nome_base)?;
+            let bibliotecas = compilar_para_llvm_ir(
+                &programa_final,
+                &mut type_checker,
+                &saida,
+                arquivo_debug.as_deref(),
+                verificacoes,
+            )?;
             println!("Compilando com clang...");
-            let ll_path = format!("{}.ll", nome_base);
-            let stdlib_path = find_stdlib_source_path(&args);
+            let ll_path = format!("{}.ll", saida);
+            let stdlib_path = find_stdlib_source_path(args);
             if let Err(error) =
                 codegen::compilar_llvm_ir_com_runtime(
                     Path::new(&ll_path),
-                    nome_base,
-                    stdlib_path.as_deref()
+                    &saida,
+                    stdlib_path.as_deref(),
+                    otimizacao,
+                    &bibliotecas,
                 )
             {
                 return Err(Box::new(error::ErroCompilador::novo(
@@ -708,84 +1599,201 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     error,
                 )));
             }
-            println!("Executável gerado: ./{}", nome_base);
+            println!("Executável gerado: {}", executavel);
             Ok(())
         }
-        TargetCompilacao::CilBytecode => compilar_para_cil_bytecode(&programa_final, nome_base),
-        TargetCompilacao::Console => compilar_para_console(&programa_final, nome_base),
+        TargetCompilacao::C => {
+            let bibliotecas = compilar_para_c(&programa_final, &type_checker, &saida, verificacoes)?;
+            println!("Compilando com o compilador C...");
+            let c_path = format!("{}.c", saida);
+            if let Err(error) = codegen::compilar_c_com_runtime(
+                Path::new(&c_path),
+                &saida,
+                otimizacao,
+                &bibliotecas,
+            ) {
+                return Err(Box::new(error::ErroCompilador::novo(
+                    error::TipoErro::Sintático,
+                    error,
+                )));
+            }
+            println!("Executável gerado: {}", executavel);
+            Ok(())
+        }
+        TargetCompilacao::Wasm => {
+            compilar_para_wasm(&programa_final, &type_checker, &saida, verificacoes)
+        }
+        TargetCompilacao::CilBytecode => {
+            compilar_para_cil_bytecode(&programa_final, &type_checker, &saida, verificacoes)
+        }
+        TargetCompilacao::Console => {
+            compilar_para_console(&programa_final, &type_checker, &saida)
+        }
         TargetCompilacao::Bytecode => {
-            compilar_para_bytecode(&programa_final, &mut type_checker, nome_base, output_dir.as_ref())
+            compilar_para_bytecode(
+                &programa_final,
+                &mut type_checker,
+                nome_base,
+                output_dir.as_ref(),
+                &caminhos_bibliotecas,
+                bytecode,
+            )
         }
         TargetCompilacao::Biblioteca => {
             // Produz .pbl a partir dos arquivos de entrada (usa a própria lógica de biblioteca)
             let mut gerador = codegen::GeradorCodigo::new()?;
-            let conteudo =
-                gerador.gerar_pbl(&programa_final, &mut type_checker, nome_base, "1.0.0")?;
-            let caminho_saida = format!("{}.pbl", nome_base);
-            fs::write(&caminho_saida, conteudo)?;
+            let conteudo = gerador.gerar_pbl(
+                &programa_final,
+                &mut type_checker,
+                nome_base,
+                "1.0.0",
+                bytecode,
+            )?;
+            let caminho_saida = format!("{}.pbl", saida);
+            artefato::gravar(Path::new(&caminho_saida), &conteudo)?;
             println!("✅ Biblioteca .pbl gerada em: {}", caminho_saida);
             Ok(())
         }
+    };
+    resultado?;
+    if let (Some(cache), Some(plano)) = (&cache, &plano) {
+        cache.concluir(plano);
+        println!("{}", cache.resumo(plano));
     }
+    Ok(())
 }
 
 fn compilar_universal<'a>(
     ast: &'a ast::Programa,
     type_checker: &'a mut type_checker::VerificadorTipos<'a>,
     nome_base: &str,
+    saida: &str,
     output_dir: Option<&PathBuf>,
+    arquivo_debug: Option<&str>,
+    verificacoes: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     println!("\n🌍 Iniciando Compilação Universal...");
-    compilar_para_llvm_ir(ast, &mut type_checker.clone(), nome_base)?;
-    compilar_para_cil_bytecode(ast, nome_base)?;
-    compilar_para_console(ast, nome_base)?;
-    compilar_para_bytecode(ast, type_checker, nome_base, output_dir)?;
+    compilar_para_llvm_ir(
+        ast,
+        &mut type_checker.clone(),
+        saida,
+        arquivo_debug,
+        verificacoes,
+    )?;
+    compilar_para_cil_bytecode(ast, type_checker, saida, verificacoes)?;
+    compilar_para_console(ast, type_checker, saida)?;
+    compilar_para_bytecode(ast, type_checker, nome_base, output_dir, &[], None)?;
     println!("\n🎉 Compilação Universal Concluída!");
     Ok(())
 }
 
+/// Gera `<nome_base>.ll` e devolve as bibliotecas C das funções `[Externo(...)]`.
 fn compilar_para_llvm_ir<'a>(
     programa: &'a ast::Programa,
     type_checker: &'a mut type_checker::VerificadorTipos<'a>,
     nome_base: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
+    arquivo_debug: Option<&str>,
+    verificacoes: bool,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
     println!("🔧 Gerando LLVM IR...");
     let mut gerador = codegen::llvm_ir::LlvmGenerator::new(
         programa,
         type_checker,
         &type_checker.resolved_classes,
     );
-    let llvm_ir = gerador.generate();
+    if let Some(arquivo) = arquivo_debug {
+        gerador.enable_debug_info(arquivo);
+    }
+    if !verificacoes {
+        gerador.disable_runtime_checks();
+    }
+    let llvm_ir = gerador.generate().map_err(|e| {
+        Box::new(error::ErroCompilador::novo(error::TipoErro::Semântico, e))
+            as Box<dyn std::error::Error>
+    })?;
     fs::write(format!("{}.ll", nome_base), llvm_ir)?;
     println!("  ✓ {}.ll gerado.", nome_base);
+    let flags_bibliotecas: String = gerador
+        .external_libraries()
+        .iter()
+        .filter_map(|b| codegen::flag_biblioteca(b))
+        .map(|flag| format!(" {}", flag))
+        .collect();
     #[cfg(windows)]
     println!(
-        "  Para compilar: clang {0}.ll <runtime>/async_runtime.c -o {0}.exe",
-        nome_base
+        "  Para compilar: clang {0}.ll <runtime>/async_runtime.c <runtime>/gc_runtime.c{1} -o {0}.exe",
+        nome_base, flags_bibliotecas
     );
     #[cfg(not(windows))]
     println!(
-        "  Para compilar: clang {0}.ll <runtime>/async_runtime.c -o {0} -pthread",
-        nome_base
+        "  Para compilar: clang {0}.ll <runtime>/async_runtime.c <runtime>/gc_runtime.c{1} -o {0} -pthread",
+        nome_base, flags_bibliotecas
     );
     println!("🎯 Pipeline LLVM: AST → LLVM IR → Código de Máquina");
     #[cfg(windows)]
     println!("Para executar: .\\{}.exe", nome_base);
     #[cfg(not(windows))]
     println!("Para executar: ./{}", nome_base);
+    Ok(gerador.external_libraries().to_vec())
+}
+
+fn compilar_para_c<'a>(
+    programa: &'a ast::Programa,
+    type_checker: &'a type_checker::VerificadorTipos<'a>,
+    nome_base: &str,
+    verificacoes: bool,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    println!("🔧 Gerando C...");
+    let mut gerador = codegen::c::CGenerator::new(programa, type_checker);
+    if !verificacoes {
+        gerador.disable_runtime_checks();
+    }
+    let codigo = gerador.generate().map_err(|e| {
+        Box::new(error::ErroCompilador::novo(error::TipoErro::Semântico, e))
+            as Box<dyn std::error::Error>
+    })?;
+    fs::write(format!("{}.c", nome_base), codigo)?;
+    println!("  ✓ {}.c gerado.", nome_base);
+    Ok(gerador.external_libraries().to_vec())
+}
+
+fn compilar_para_wasm<'a>(
+    programa: &'a ast::Programa,
+    type_checker: &'a type_checker::VerificadorTipos<'a>,
+    nome_base: &str,
+    verificacoes: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    println!("🔧 Gerando WebAssembly...");
+    let mut gerador = codegen::wasm::WasmGenerator::new(programa, type_checker);
+    if !verificacoes {
+        gerador.disable_runtime_checks();
+    }
+    let erro = |e: String| {
+        Box::new(error::ErroCompilador::novo(error::TipoErro::Semântico, e))
+            as Box<dyn std::error::Error>
+    };
+    let wat = gerador.generate().map_err(erro)?;
+    let binario = codegen::wasm::montar(&wat).map_err(erro)?;
+    fs::write(format!("{}.wat", nome_base), &wat)?;
+    println!("  ✓ {}.wat gerado.", nome_base);
+    fs::write(format!("{}.wasm", nome_base), binario)?;
+    println!("  ✓ {}.wasm gerado.", nome_base);
+    println!("Para executar: node src/runtime/pds_wasm.js {}.wasm", nome_base);
     Ok(())
 }
 
-fn compilar_para_cil_bytecode<'a>(
-    ast: &'a ast::Programa,
+fn compilar_para_cil_bytecode(
+    ast: &ast::Programa,
+    type_checker: &type_checker::VerificadorTipos,
     nome_base: &str,
+    verificacoes: bool,
 ) -> Result<(), Box<dyn std::error::Error>> {
     println!("🔧 Gerando CIL Bytecode...");
     let gerador = codegen::GeradorCodigo::new()?;
     gerador
-        .gerar_cil(ast, nome_base)
+        .gerar_cil(ast, type_checker, nome_base, verificacoes)
         .map_err(|e| Box::new(error::ErroCompilador::novo(
-            error::TipoErro::Sintático,
+            error::TipoErro::Semântico,
             e,
         )))?;
     println!("  ✓ {}.il gerado.", nome_base);
@@ -796,16 +1804,17 @@ fn compilar_para_cil_bytecode<'a>(
     Ok(())
 }
 
-fn compilar_para_console<'a>(
-    ast: &'a ast::Programa,
+fn compilar_para_console(
+    ast: &ast::Programa,
+    type_checker: &type_checker::VerificadorTipos,
     nome_base: &str,
 ) -> Result<(), Box<dyn std::error::Error>> {
     println!("🔧 Gerando Projeto de Console .NET...");
     let gerador = codegen::GeradorCodigo::new()?;
     gerador
-        .gerar_console(ast, nome_base)
+        .gerar_console(ast, type_checker, nome_base)
         .map_err(|e| Box::new(error::ErroCompilador::novo(
-            error::TipoErro::Sintático,
+            error::TipoErro::Semântico,
             e,
         )))?;
     println!("  ✓ Projeto '{}' gerado.", nome_base);
@@ -818,6 +1827,8 @@ fn compilar_para_bytecode<'a>(
     type_checker: &'a mut type_checker::VerificadorTipos,
     nome_base: &str,
     output_dir: Option<&PathBuf>,
+    bibliotecas: &[PathBuf],
+    bytecode: Option<Vec<String>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     println!("🔧 Gerando Bytecode Customizado...");
     
@@ -829,10 +1840,24 @@ fn compilar_para_bytecode<'a>(
     fs::create_dir_all(build_dir)?;
     
     let output_path = build_dir.join(format!("{}.pbc", nome_base));
+
+    // As bibliotecas vão junto do .pbc, que as referencia pelo nome do arquivo
+    let mut nomes_bibliotecas = Vec::new();
+    for caminho in bibliotecas {
+        let nome = caminho
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let destino = build_dir.join(&nome);
+        if caminho.canonicalize().ok() != destino.canonicalize().ok() {
+            artefato::copiar(caminho, &destino)?;
+        }
+        nomes_bibliotecas.push(nome);
+    }
     
     let mut gerador = codegen::GeradorCodigo::new()?;
     gerador
-        .gerar_bytecode_para_arquivo(ast, type_checker, &output_path)
+        .gerar_bytecode_para_arquivo(ast, type_checker, &output_path, &nomes_bibliotecas, bytecode)
         .map_err(|e| Box::new(error::ErroCompilador::novo(
             error::TipoErro::Sintático,
             e,
@@ -1,229 +1,669 @@
+//! Módulos por arquivo: `importar { A, B } de "utilidades";` carrega `utilidades.pr` pelos caminhos
+//! de busca, e só o que o módulo declara com `exportar` pode ser importado. Os módulos são
+//! analisados separadamente e juntados em um único programa (dependências primeiro) para o
+//! verificador de tipos e os geradores de código.
+
 use crate::ast::*;
+use crate::error::{ErroCompilador, TipoErro};
+use crate::incremental::CacheIncremental;
 use std::collections::{HashMap, HashSet};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Tokeniza e analisa um arquivo fonte, com as strings interpoladas já planificadas. Com
+/// `posicoes`, cada comando é precedido de `Comando::Posicao`, que usa `caminho` como nome do
+/// arquivo.
+pub fn parsear_fonte(
+    caminho: &Path,
+    codigo: &str,
+    posicoes: bool,
+) -> Result<Programa, Box<ErroCompilador>> {
+    use logos::Logos;
+    let tokens: Vec<_> = crate::lexer::Token::lexer(codigo)
+        .spanned()
+        .map(|(token, span)| token.map(|t| (span.start, t, span.end)))
+        .collect::<Result<_, _>>()
+        .map_err(|_| {
+            ErroCompilador::novo(
+                TipoErro::Léxico,
+                format!("Token inválido encontrado em '{}'", caminho.display()),
+            )
+            .com_arquivo(caminho.to_path_buf())
+        })?;
+
+    let mapa_linhas = MapaLinhas::novo(caminho.display().to_string(), codigo);
+    let mut programa = crate::parser::ArquivoParser::new()
+        .parse(posicoes.then_some(&mapa_linhas), tokens.iter().cloned())
+        .map_err(|e| crate::error::de_lalrpop_error_unit(&e, caminho.to_path_buf(), codigo))?;
+
+    crate::interpolacao::walk_programa(&mut programa, |e| {
+        *e = crate::interpolacao::planificar_interpolada(e.clone());
+    });
+    Ok(programa)
+}
 
 pub struct SistemaModulos {
-    modulos: HashMap<String, Modulo>,
-    dependencias: HashMap<String, Vec<String>>,
-    namespaces: HashMap<String, String>,
+    resolvedor: ResolvedorCaminhos,
+    // Com compilação incremental, os arquivos que não mudaram não são analisados de novo
+    cache: Option<Arc<CacheIncremental>>,
+    // Registra as posições dos comandos (teste de mesa, depuração e erros de execução)
+    posicoes: bool,
+    modulos: HashMap<PathBuf, Modulo>,
+    // Ordem de junção: cada módulo aparece depois das suas dependências
+    ordem: Vec<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
 pub struct Modulo {
+    /// Nome usado na primeira importação (`"utilidades"`); arquivos principais usam o caminho
     pub nome: String,
     pub caminho: PathBuf,
-    pub declaracoes: Vec<Declaracao>,
+    pub programa: Programa,
     pub importacoes: Vec<Importacao>,
     pub exportacoes: Vec<Exportacao>,
-    pub dependencias: Vec<String>,
+    /// Caminhos dos módulos importados, na ordem das importações
+    pub dependencias: Vec<PathBuf>,
+    /// Arquivo da linha de comando: enxerga os outros arquivos principais sem importá-los
+    pub principal: bool,
 }
 
-impl SistemaModulos {
-    pub fn new() -> Self {
+/// Tipo de item declarado no nível superior de um módulo.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum TipoItem {
+    Funcao,
+    Tipo,
+}
+
+impl Modulo {
+    fn novo(nome: String, caminho: PathBuf, programa: Programa, principal: bool) -> Self {
+        let mut importacoes = Vec::new();
+        let mut exportacoes = Vec::new();
+        for decl in &programa.declaracoes {
+            match decl {
+                Declaracao::Importacao(imp) => importacoes.push(imp.clone()),
+                Declaracao::Exportacao(exp) => exportacoes.push(exp.clone()),
+                _ => {}
+            }
+        }
         Self {
-            modulos: HashMap::new(),
-            dependencias: HashMap::new(),
-            namespaces: HashMap::new(),
+            nome,
+            caminho,
+            programa,
+            importacoes,
+            exportacoes,
+            dependencias: Vec::new(),
+            principal,
         }
     }
 
-    pub fn carregar_modulo(&mut self, caminho: &str) -> Result<String, String> {
-        let caminho_path = PathBuf::from(caminho);
-        let nome_modulo = caminho_path
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("anonimo")
-            .to_string();
-
-        if self.modulos.contains_key(&nome_modulo) {
-            return Ok(nome_modulo); // Já carregado
-        }
+    fn itens(&self) -> impl Iterator<Item = (&str, TipoItem)> {
+        self.programa
+            .declaracoes
+            .iter()
+            .filter_map(|decl| match decl {
+                Declaracao::DeclaracaoFuncao(f) => Some((f.nome.as_str(), TipoItem::Funcao)),
+                Declaracao::DeclaracaoClasse(c) => Some((c.nome.as_str(), TipoItem::Tipo)),
+                Declaracao::DeclaracaoInterface(i) => Some((i.nome.as_str(), TipoItem::Tipo)),
+                Declaracao::DeclaracaoEnum(e) => Some((e.nome.as_str(), TipoItem::Tipo)),
+                _ => None,
+            })
+    }
 
-        let conteudo = std::fs::read_to_string(&caminho_path)
-            .map_err(|e| format!("Erro ao ler módulo {}: {}", caminho, e))?;
+    fn declara(&self, nome: &str) -> bool {
+        self.itens().any(|(item, _)| item == nome)
+    }
 
-        let modulo = self.parsear_modulo(&nome_modulo, caminho_path, &conteudo)?;
+    fn exporta(&self, nome: &str) -> bool {
+        self.exportacoes.iter().any(|exp| exp.nome == nome)
+    }
 
-        // Verificar dependências circulares
-        self.verificar_dependencias_circulares(&modulo)?;
+    fn erro(&self, mensagem: String) -> ErroCompilador {
+        ErroCompilador::novo(TipoErro::Semântico, mensagem).com_arquivo(self.caminho.clone())
+    }
+}
 
-        // Carregar dependências
-        for dep in &modulo.dependencias {
-            self.carregar_modulo(dep)?;
+impl SistemaModulos {
+    pub fn new(resolvedor: ResolvedorCaminhos) -> Self {
+        Self {
+            resolvedor,
+            cache: None,
+            posicoes: false,
+            modulos: HashMap::new(),
+            ordem: Vec::new(),
         }
-
-        self.modulos.insert(nome_modulo.clone(), modulo);
-        Ok(nome_modulo)
     }
 
-    fn parsear_modulo(
-        &self,
-        nome: &str,
-        caminho: PathBuf,
-        _conteudo: &str,
-    ) -> Result<Modulo, String> {
-        // Por enquanto, criar um módulo vazio
-        // Em implementação real, usaria o parser aqui
-        Ok(Modulo {
-            nome: nome.to_string(),
-            caminho,
-            declaracoes: Vec::new(),
-            importacoes: Vec::new(),
-            exportacoes: Vec::new(),
-            dependencias: Vec::new(),
-        })
+    pub fn usar_cache(&mut self, cache: Arc<CacheIncremental>) {
+        self.cache = Some(cache);
     }
 
-    fn verificar_dependencias_circulares(&self, modulo: &Modulo) -> Result<(), String> {
-        let mut visitados = HashSet::new();
-        let mut pilha = HashSet::new();
+    pub fn registrar_posicoes(&mut self) {
+        self.posicoes = true;
+    }
 
-        self.dfs_dependencias(&modulo.nome, &mut visitados, &mut pilha)
+    /// Como [`parsear_fonte`], pelo cache incremental quando há um.
+    pub fn parsear(&self, caminho: &Path, codigo: &str) -> Result<Programa, Box<ErroCompilador>> {
+        match &self.cache {
+            Some(cache) => {
+                let modo = if self.posicoes { "fonte+posicoes" } else { "fonte" };
+                cache.analisar(caminho, codigo, modo, || {
+                    parsear_fonte(caminho, codigo, self.posicoes)
+                })
+            }
+            None => parsear_fonte(caminho, codigo, self.posicoes),
+        }
     }
 
-    fn dfs_dependencias(
-        &self,
-        modulo: &str,
-        visitados: &mut HashSet<String>,
-        pilha: &mut HashSet<String>,
-    ) -> Result<(), String> {
-        if pilha.contains(modulo) {
-            return Err(format!(
-                "Dependência circular detectada envolvendo módulo '{}'",
-                modulo
-            ));
+    /// Registra um arquivo da linha de comando e carrega os módulos que ele importa.
+    pub fn adicionar_principal(
+        &mut self,
+        caminho: &Path,
+        programa: Programa,
+    ) -> Result<(), Box<ErroCompilador>> {
+        let chave = canonico(caminho);
+        if self.modulos.contains_key(&chave) {
+            return Ok(()); // Já importado por outro arquivo principal
         }
+        let modulo = Modulo::novo(
+            caminho.display().to_string(),
+            caminho.to_path_buf(),
+            programa,
+            true,
+        );
+        self.carregar_dependencias(chave, modulo, &mut Vec::new())
+    }
 
-        if visitados.contains(modulo) {
-            return Ok(());
+    /// Carrega (uma única vez) o módulo de `importacao`, feita em `origem`. `pilha` guarda os
+    /// módulos em carga, para detectar ciclos.
+    pub fn carregar_modulo(
+        &mut self,
+        origem: &Path,
+        importacao: &Importacao,
+        pilha: &mut Vec<PathBuf>,
+    ) -> Result<PathBuf, Box<ErroCompilador>> {
+        let diretorio = origem.parent().unwrap_or(Path::new("."));
+        let caminho = self
+            .resolvedor
+            .resolver(diretorio, &importacao.caminho)
+            .ok_or_else(|| {
+                ErroCompilador::novo(
+                    TipoErro::Semântico,
+                    format!("Módulo '{}' não encontrado", importacao.caminho),
+                )
+                .com_arquivo(origem.to_path_buf())
+                .com_sugestao(format!(
+                    "Procurado em: {}",
+                    self.resolvedor.diretorios(diretorio).join(", ")
+                ))
+            })?;
+        let chave = canonico(&caminho);
+
+        if let Some(inicio) = pilha.iter().position(|p| *p == chave) {
+            let ciclo: Vec<String> = pilha[inicio..]
+                .iter()
+                .chain(std::iter::once(&chave))
+                .map(|p| nome_arquivo(p))
+                .collect();
+            return Err(ErroCompilador::novo(
+                TipoErro::Semântico,
+                format!("Dependência circular entre módulos: {}", ciclo.join(" → ")),
+            )
+            .com_arquivo(origem.to_path_buf())
+            .into());
+        }
+        if self.modulos.contains_key(&chave) {
+            return Ok(chave);
         }
 
-        pilha.insert(modulo.to_string());
-        visitados.insert(modulo.to_string());
+        let codigo = std::fs::read_to_string(&caminho).map_err(|e| {
+            ErroCompilador::novo(
+                TipoErro::Semântico,
+                format!("Erro ao ler módulo '{}': {}", caminho.display(), e),
+            )
+            .com_arquivo(origem.to_path_buf())
+        })?;
+        let programa = self.parsear(&caminho, &codigo)?;
+        let modulo = Modulo::novo(importacao.caminho.clone(), caminho, programa, false);
+        self.carregar_dependencias(chave.clone(), modulo, pilha)?;
+        Ok(chave)
+    }
 
-        if let Some(deps) = self.dependencias.get(modulo) {
-            for dep in deps {
-                self.dfs_dependencias(dep, visitados, pilha)?;
-            }
+    fn carregar_dependencias(
+        &mut self,
+        chave: PathBuf,
+        mut modulo: Modulo,
+        pilha: &mut Vec<PathBuf>,
+    ) -> Result<(), Box<ErroCompilador>> {
+        pilha.push(chave.clone());
+        for importacao in modulo.importacoes.clone() {
+            let dependencia = self.carregar_modulo(&modulo.caminho, &importacao, pilha)?;
+            modulo.dependencias.push(dependencia);
         }
-
-        pilha.remove(modulo);
+        pilha.pop();
+        self.modulos.insert(chave.clone(), modulo);
+        self.ordem.push(chave);
         Ok(())
     }
 
-    pub fn resolver_importacao(&self, importacao: &Importacao) -> Result<Vec<Declaracao>, String> {
-        if let Some(modulo) = self.modulos.get(&importacao.caminho) {
-            let mut declaracoes = Vec::new();
+    /// Itens que `modulo` importa de `dependencia` (tudo o que ela exporta quando a lista é vazia).
+    pub fn resolver_importacao(
+        &self,
+        modulo: &Modulo,
+        importacao: &Importacao,
+        dependencia: &Modulo,
+    ) -> Result<Vec<String>, Box<ErroCompilador>> {
+        if importacao.itens.is_empty() {
+            return Ok(dependencia
+                .exportacoes
+                .iter()
+                .map(|exp| exp.nome.clone())
+                .collect());
+        }
+        for item in &importacao.itens {
+            if !dependencia.declara(item) {
+                return Err(modulo
+                    .erro(format!(
+                        "Item '{}' não encontrado no módulo '{}'",
+                        item, importacao.caminho
+                    ))
+                    .into());
+            }
+            if !dependencia.exporta(item) {
+                return Err(modulo
+                    .erro(format!(
+                        "Item '{}' é privado no módulo '{}'",
+                        item, importacao.caminho
+                    ))
+                    .com_sugestao(format!(
+                        "Declare-o com `exportar` em {}",
+                        dependencia.caminho.display()
+                    ))
+                    .into());
+            }
+        }
+        Ok(importacao.itens.clone())
+    }
 
-            if importacao.itens.is_empty() {
-                // Importar tudo que é público
-                for decl in &modulo.declaracoes {
-                    if self.is_declaracao_publica(decl) {
-                        declaracoes.push(decl.clone());
+    /// Confere exportações, importações, nomes repetidos entre módulos e o uso de itens que o
+    /// módulo não declara nem importa.
+    pub fn verificar(&self) -> Result<(), Vec<ErroCompilador>> {
+        let mut erros = Vec::new();
+
+        // Dono de cada item do nível superior
+        let mut donos: HashMap<&str, (&Modulo, TipoItem)> = HashMap::new();
+        for modulo in self.modulos_em_ordem() {
+            for (nome, tipo) in modulo.itens() {
+                match donos.get(nome) {
+                    Some((outro, _)) if !(outro.principal && modulo.principal) => {
+                        erros.push(modulo.erro(format!(
+                            "'{}' é declarado nos módulos '{}' e '{}'; os módulos compartilham um único programa",
+                            nome, outro.nome, modulo.nome
+                        )));
+                    }
+                    Some(_) => {}
+                    None => {
+                        donos.insert(nome, (modulo, tipo));
                     }
                 }
+            }
+        }
+
+        for modulo in self.modulos_em_ordem() {
+            if let Err(mut erros_exportacao) = self.validar_exportacoes(modulo) {
+                erros.append(&mut erros_exportacao);
+            }
+
+            let mut visiveis: HashSet<String> = HashSet::new();
+            if modulo.principal {
+                for principal in self.modulos_em_ordem().filter(|m| m.principal) {
+                    visiveis.extend(principal.itens().map(|(nome, _)| nome.to_string()));
+                }
             } else {
-                // Importar itens específicos
-                for item in &importacao.itens {
-                    if let Some(decl) =
-                        self.encontrar_declaracao_por_nome(&modulo.declaracoes, item)
-                    {
-                        if self.is_declaracao_publica(decl) {
-                            declaracoes.push(decl.clone());
-                        } else {
-                            return Err(format!(
-                                "Item '{}' não é público no módulo '{}'",
-                                item, importacao.caminho
-                            ));
-                        }
-                    } else {
-                        return Err(format!(
-                            "Item '{}' não encontrado no módulo '{}'",
-                            item, importacao.caminho
-                        ));
+                visiveis.extend(modulo.itens().map(|(nome, _)| nome.to_string()));
+            }
+            for (importacao, dependencia) in modulo.importacoes.iter().zip(&modulo.dependencias) {
+                match self.resolver_importacao(modulo, importacao, &self.modulos[dependencia]) {
+                    Ok(itens) => visiveis.extend(itens),
+                    Err(erro) => {
+                        // O erro da importação basta; os usos dos itens não são informados
+                        visiveis.extend(importacao.itens.iter().cloned());
+                        erros.push(*erro);
                     }
                 }
             }
 
-            Ok(declaracoes)
+            let mut referencias = Referencias::default();
+            referencias.programa(&modulo.programa);
+            let mut informados = HashSet::new();
+            for (nome, chamada, posicao) in referencias.itens {
+                if visiveis.contains(&nome) {
+                    continue;
+                }
+                let Some((dono, tipo)) = donos.get(nome.as_str()) else {
+                    continue;
+                };
+                // Identificadores soltos podem ser variáveis locais: só contam os nomes de tipos
+                if (!chamada && *tipo == TipoItem::Funcao) || !informados.insert(nome.clone()) {
+                    continue;
+                }
+                let mut erro = if dono.principal {
+                    modulo.erro(format!(
+                        "'{}' é declarado em '{}', que o módulo '{}' não pode importar",
+                        nome, dono.nome, modulo.nome
+                    ))
+                } else if dono.exporta(&nome) {
+                    modulo
+                        .erro(format!(
+                            "'{}' pertence ao módulo '{}' e não foi importado",
+                            nome, dono.nome
+                        ))
+                        .com_sugestao(format!("importar {{ {} }} de \"{}\";", nome, dono.nome))
+                } else {
+                    modulo.erro(format!("'{}' é privado no módulo '{}'", nome, dono.nome))
+                };
+                if let Some(posicao) = posicao {
+                    erro = erro.com_localizacao(posicao.linha, posicao.coluna);
+                }
+                erros.push(erro);
+            }
+        }
+
+        if erros.is_empty() {
+            Ok(())
         } else {
-            Err(format!("Módulo '{}' não encontrado", importacao.caminho))
+            Err(erros)
         }
     }
 
-    fn is_declaracao_publica(&self, declaracao: &Declaracao) -> bool {
-        match declaracao {
-            Declaracao::DeclaracaoClasse(classe) => {
-                matches!(classe.modificador, ModificadorAcesso::Publico)
-            }
-            Declaracao::DeclaracaoFuncao(funcao) => {
-                matches!(funcao.modificador, ModificadorAcesso::Publico)
+    pub fn validar_exportacoes(&self, modulo: &Modulo) -> Result<(), Vec<ErroCompilador>> {
+        let erros: Vec<ErroCompilador> = modulo
+            .exportacoes
+            .iter()
+            .filter(|exp| !modulo.declara(&exp.nome))
+            .map(|exp| {
+                modulo.erro(format!(
+                    "Exportação '{}' não corresponde a nenhuma declaração no módulo '{}'",
+                    exp.nome, modulo.nome
+                ))
+            })
+            .collect();
+        if erros.is_empty() {
+            Ok(())
+        } else {
+            Err(erros)
+        }
+    }
+
+    /// Junta os módulos em um programa, dependências antes de quem as importa. Os comandos de
+    /// nível superior de cada módulo rodam uma vez, nessa ordem.
+    pub fn montar_programa(&self) -> Programa {
+        let mut programa = Programa {
+            usings: vec![],
+            namespaces: vec![],
+            declaracoes: vec![],
+        };
+        for (_, parte) in self.partes() {
+            programa.usings.extend(parte.usings);
+            programa.declaracoes.extend(parte.declaracoes);
+            for ns in parte.namespaces {
+                if let Some(existente) = programa.namespaces.iter_mut().find(|n| n.nome == ns.nome)
+                {
+                    existente.declaracoes.extend(ns.declaracoes);
+                } else {
+                    programa.namespaces.push(ns);
+                }
             }
-            Declaracao::Exportacao(exp) => exp.publico,
-            _ => false,
         }
+        programa
     }
 
-    fn encontrar_declaracao_por_nome<'a>(
-        &self,
-        declaracoes: &'a [Declaracao],
-        nome: &str,
-    ) -> Option<&'a Declaracao> {
-        declaracoes.iter().find(|decl| match decl {
-            Declaracao::DeclaracaoClasse(classe) => classe.nome == nome,
-            Declaracao::DeclaracaoFuncao(funcao) => funcao.nome == nome,
-            _ => false,
-        })
+    /// O programa de cada módulo, sem importações e exportações, na ordem de
+    /// [`montar_programa`](Self::montar_programa).
+    pub fn partes(&self) -> Vec<(PathBuf, Programa)> {
+        self.modulos_em_ordem()
+            .map(|modulo| {
+                let mut parte = modulo.programa.clone();
+                parte.declaracoes.retain(|decl| {
+                    !matches!(decl, Declaracao::Importacao(_) | Declaracao::Exportacao(_))
+                });
+                (modulo.caminho.clone(), parte)
+            })
+            .collect()
     }
 
-    pub fn resolver_namespace(&self, caminho: &str) -> String {
-        if let Some(namespace) = self.namespaces.get(caminho) {
-            namespace.clone()
-        } else {
-            caminho.to_string()
+    fn modulos_em_ordem(&self) -> impl Iterator<Item = &Modulo> {
+        self.ordem.iter().map(|chave| &self.modulos[chave])
+    }
+}
+
+fn canonico(caminho: &Path) -> PathBuf {
+    caminho
+        .canonicalize()
+        .unwrap_or_else(|_| caminho.to_path_buf())
+}
+
+fn nome_arquivo(caminho: &Path) -> String {
+    caminho
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| caminho.display().to_string())
+}
+
+/// Nomes usados por um módulo: chamadas de função (`chamada`) e nomes de tipos, com a última
+/// posição vista antes do uso.
+#[derive(Default)]
+struct Referencias {
+    itens: Vec<(String, bool, Option<Posicao>)>,
+    posicao: Option<Posicao>,
+}
+
+impl Referencias {
+    fn programa(&mut self, programa: &Programa) {
+        for decl in &programa.declaracoes {
+            self.declaracao(decl);
+        }
+        for ns in &programa.namespaces {
+            ns.declaracoes.iter().for_each(|d| self.declaracao(d));
         }
     }
 
-    pub fn adicionar_namespace(&mut self, alias: String, caminho: String) {
-        self.namespaces.insert(alias, caminho);
+    fn usar(&mut self, nome: &str, chamada: bool) {
+        self.itens
+            .push((nome.to_string(), chamada, self.posicao.clone()));
     }
 
-    pub fn obter_modulo(&self, nome: &str) -> Option<&Modulo> {
-        self.modulos.get(nome)
+    fn declaracao(&mut self, decl: &Declaracao) {
+        match decl {
+            Declaracao::DeclaracaoFuncao(f) => {
+                self.parametros(&f.parametros);
+                if let Some(t) = &f.tipo_retorno {
+                    self.tipo(t);
+                }
+                self.comandos(&f.corpo);
+            }
+            Declaracao::DeclaracaoClasse(c) => self.classe(c),
+            Declaracao::DeclaracaoInterface(i) => {
+                for m in &i.metodos {
+                    self.parametros(&m.parametros);
+                    if let Some(t) = &m.tipo_retorno {
+                        self.tipo(t);
+                    }
+                }
+            }
+            Declaracao::DeclaracaoModulo(m) => m.conteudo.iter().for_each(|d| self.declaracao(d)),
+            Declaracao::DeclaracaoNamespace(ns) => {
+                ns.declaracoes.iter().for_each(|d| self.declaracao(d))
+            }
+            Declaracao::Comando(cmd) => self.comando(cmd),
+            _ => {}
+        }
     }
 
-    pub fn listar_modulos(&self) -> Vec<String> {
-        self.modulos.keys().cloned().collect()
+    fn classe(&mut self, c: &DeclaracaoClasse) {
+        c.classe_pai
+            .iter()
+            .chain(&c.interfaces)
+            .for_each(|t| self.tipo(t));
+        for campo in &c.campos {
+            self.tipo(&campo.tipo);
+            if let Some(e) = &campo.valor_inicial {
+                self.expressao(e);
+            }
+        }
+        for prop in &c.propriedades {
+            self.tipo(&prop.tipo);
+            if let Some(e) = &prop.valor_inicial {
+                self.expressao(e);
+            }
+            for acessor in prop.obter.iter().chain(&prop.definir) {
+                if let Some(corpo) = &acessor.corpo {
+                    self.comandos(corpo);
+                }
+            }
+        }
+        for m in &c.metodos {
+            self.parametros(&m.parametros);
+            if let Some(t) = &m.tipo_retorno {
+                self.tipo(t);
+            }
+            self.comandos(&m.corpo);
+        }
+        for ctor in &c.construtores {
+            self.parametros(&ctor.parametros);
+            ctor.chamada_pai
+                .iter()
+                .flatten()
+                .for_each(|e| self.expressao(e));
+            self.comandos(&ctor.corpo);
+        }
+        c.nested_classes.iter().for_each(|n| self.classe(n));
     }
 
-    pub fn validar_exportacoes(&self, modulo: &str) -> Result<(), Vec<String>> {
-        if let Some(mod_info) = self.modulos.get(modulo) {
-            let mut erros = Vec::new();
+    fn parametros(&mut self, parametros: &[Parametro]) {
+        for p in parametros {
+            self.tipo(&p.tipo);
+            if let Some(e) = &p.valor_padrao {
+                self.expressao(e);
+            }
+        }
+    }
 
-            for exportacao in &mod_info.exportacoes {
-                if !self
-                    .encontrar_declaracao_por_nome(&mod_info.declaracoes, &exportacao.nome)
-                    .is_some()
-                {
-                    erros.push(format!(
-                        "Exportação '{}' não corresponde a nenhuma declaração no módulo '{}'",
-                        exportacao.nome, modulo
-                    ));
+    fn tipo(&mut self, tipo: &Tipo) {
+        match tipo {
+            // Nomes qualificados pertencem a um `espaco`, não a um módulo
+            Tipo::Classe(nome) | Tipo::Enum(nome) if !nome.contains('.') => self.usar(nome, false),
+            Tipo::Aplicado { nome, args } => {
+                if !nome.contains('.') {
+                    self.usar(nome, false);
                 }
+                args.iter().for_each(|t| self.tipo(t));
             }
+            Tipo::Lista(t) | Tipo::Opcional(t) => self.tipo(t),
+            Tipo::Funcao(params, ret) => {
+                params.iter().for_each(|t| self.tipo(t));
+                self.tipo(ret);
+            }
+            _ => {}
+        }
+    }
 
-            if erros.is_empty() {
-                Ok(())
-            } else {
-                Err(erros)
+    fn comandos(&mut self, comandos: &[Comando]) {
+        comandos.iter().for_each(|c| self.comando(c));
+    }
+
+    fn comando(&mut self, cmd: &Comando) {
+        match cmd {
+            Comando::Posicao(p) => self.posicao = Some(p.clone()),
+            Comando::DeclaracaoVariavel(tipo, _, valor) => {
+                self.tipo(tipo);
+                valor.iter().for_each(|e| self.expressao(e));
             }
-        } else {
-            Err(vec![format!("Módulo '{}' não encontrado", modulo)])
+            Comando::DeclaracaoVar(_, e)
+            | Comando::Atribuicao(_, e)
+            | Comando::Imprima(e)
+            | Comando::Expressao(e) => self.expressao(e),
+            Comando::AtribuirPropriedade(alvo, _, e) | Comando::AtribuirCampo(alvo, _, e) => {
+                self.expressao(alvo);
+                self.expressao(e);
+            }
+            Comando::AtribuirIndice(alvo, indice, e) => {
+                self.expressao(alvo);
+                self.expressao(indice);
+                self.expressao(e);
+            }
+            Comando::Se(cond, entao, senao) => {
+                self.expressao(cond);
+                self.comando(entao);
+                if let Some(s) = senao {
+                    self.comando(s);
+                }
+            }
+            Comando::Enquanto(cond, corpo) => {
+                self.expressao(cond);
+                self.comando(corpo);
+            }
+            Comando::Para(inicio, cond, passo, corpo) => {
+                if let Some(c) = inicio {
+                    self.comando(c);
+                }
+                cond.iter().for_each(|e| self.expressao(e));
+                if let Some(c) = passo {
+                    self.comando(c);
+                }
+                self.comando(corpo);
+            }
+            Comando::Bloco(cmds) => self.comandos(cmds),
+            Comando::Retorne(e) => e.iter().for_each(|e| self.expressao(e)),
+            Comando::CriarObjeto(classe, _, args) => {
+                self.usar(classe, false);
+                args.iter().for_each(|e| self.expressao(e));
+            }
+            Comando::ChamarMetodo(alvo, _, args) => {
+                self.expressao(alvo);
+                args.iter().for_each(|e| self.expressao(e));
+            }
+            Comando::AcessarCampo(..) => {}
         }
     }
 
-    pub fn gerar_grafo_dependencias(&self) -> HashMap<String, Vec<String>> {
-        self.dependencias.clone()
+    fn expressao(&mut self, expr: &Expressao) {
+        match expr {
+            Expressao::Identificador(nome) => self.usar(nome, false),
+            Expressao::Chamada(nome, args) => {
+                self.usar(nome, true);
+                args.iter().for_each(|e| self.expressao(e));
+            }
+            Expressao::NovoObjeto(tipo, args) => {
+                self.tipo(tipo);
+                args.iter().for_each(|e| self.expressao(e));
+            }
+            Expressao::NovoArray(tipo, tamanho) => {
+                self.tipo(tipo);
+                self.expressao(tamanho);
+            }
+            Expressao::ListaLiteral(itens) => itens.iter().for_each(|e| self.expressao(e)),
+            Expressao::Aritmetica(_, a, b)
+            | Expressao::Comparacao(_, a, b)
+            | Expressao::Logica(_, a, b)
+            | Expressao::AcessoIndice(a, b) => {
+                self.expressao(a);
+                self.expressao(b);
+            }
+            Expressao::AcessoMembro(alvo, _) | Expressao::Unario(_, alvo) => self.expressao(alvo),
+            Expressao::Aguarde(alvo) => self.expressao(alvo),
+            Expressao::ChamadaMetodo(alvo, _, args) => {
+                self.expressao(alvo);
+                args.iter().for_each(|e| self.expressao(e));
+            }
+            Expressao::StringInterpolada(partes) => {
+                for parte in partes {
+                    if let PartStringInterpolada::Expressao(e) = parte {
+                        self.expressao(e);
+                    }
+                }
+            }
+            _ => {}
+        }
     }
 }
 
@@ -249,13 +689,35 @@ impl ResolvedorCaminhos {
         }
     }
 
-    pub fn resolver(&self, nome_modulo: &str) -> Option<PathBuf> {
-        for caminho_base in &self.caminhos_busca {
-            let caminho_completo = caminho_base.join(format!("{}.pr", nome_modulo));
-            if caminho_completo.exists() {
-                return Some(caminho_completo);
+    /// Diretórios consultados para uma importação feita em `diretorio_origem`, em ordem.
+    pub fn diretorios(&self, diretorio_origem: &Path) -> Vec<String> {
+        let mut diretorios: Vec<String> = Vec::new();
+        for d in std::iter::once(diretorio_origem)
+            .chain(self.caminhos_busca.iter().map(PathBuf::as_path))
+        {
+            let nome = if d.as_os_str().is_empty() {
+                ".".to_string()
+            } else {
+                d.display().to_string()
+            };
+            if !diretorios.contains(&nome) {
+                diretorios.push(nome);
             }
         }
-        None
+        diretorios
+    }
+
+    /// Procura `nome_modulo` (com ou sem `.pr`, podendo ter subdiretórios) primeiro ao lado do
+    /// arquivo que importa e depois nos caminhos de busca.
+    pub fn resolver(&self, diretorio_origem: &Path, nome_modulo: &str) -> Option<PathBuf> {
+        let arquivo = if nome_modulo.ends_with(".pr") {
+            nome_modulo.to_string()
+        } else {
+            format!("{}.pr", nome_modulo)
+        };
+        std::iter::once(diretorio_origem)
+            .chain(self.caminhos_busca.iter().map(PathBuf::as_path))
+            .map(|base| base.join(&arquivo))
+            .find(|caminho| caminho.is_file())
     }
 }
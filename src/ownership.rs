@@ -272,6 +272,8 @@ impl AnalisadorOwnership {
                 }
             }
 
+            Comando::Posicao(_) => {}
+
             Comando::AtribuirCampo(objeto_expr, _campo, valor_expr) => {
                 self.analisar_expressao(objeto_expr);
                 self.analisar_expressao(valor_expr);
@@ -0,0 +1,647 @@
+//! Gerenciador de pacotes local para as `[dependencias]` do `projeto.toml`.
+//!
+//! Um pacote é uma biblioteca compilada (`.pbl`) acompanhada do seu `projeto.toml`, e vem de:
+//! - um **registro**: diretório `<registro>/<nome>/<versao>/`, preenchido por `compilador publicar`;
+//! - um **caminho** no disco (outro projeto ou um checkout git), compilado a cada construção.
+//!
+//! A resolução é transitiva e escolhe, para cada pacote, a maior versão que satisfaz todos os
+//! requisitos (há uma única versão de cada pacote no programa). O resultado fica em
+//! `projeto.lock`, cujas versões têm preferência nas construções seguintes. Os pacotes do
+//! registro são copiados para o cache do usuário, de onde são lidos a partir de então.
+
+use crate::artefato;
+use crate::projeto::{Dependencia, Manifesto, TipoProjeto, ARQUIVO_MANIFESTO};
+use semver::{Version, VersionReq};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::env;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+pub const ARQUIVO_LOCK: &str = "projeto.lock";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origem {
+    /// Registro local; o `.pbl` é lido do cache do usuário
+    Registro,
+    /// Projeto de biblioteca no disco
+    Caminho(PathBuf),
+}
+
+/// Pacote escolhido pela resolução.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pacote {
+    pub nome: String,
+    pub versao: Version,
+    pub origem: Origem,
+    /// Nomes das dependências diretas do pacote
+    pub dependencias: Vec<String>,
+}
+
+impl Pacote {
+    /// Arquivo `.pbl` do pacote; nos pacotes de caminho, só existe depois de compilado.
+    pub fn pbl(&self, cache: &Path) -> PathBuf {
+        match &self.origem {
+            Origem::Registro => diretorio_versao(cache, &self.nome, &self.versao)
+                .join(arquivo_pbl(&self.nome)),
+            Origem::Caminho(dir) => dir.join("dist").join(arquivo_pbl(&self.nome)),
+        }
+    }
+}
+
+/// Nome do `.pbl` de uma biblioteca, como gerado por `construir`.
+pub fn arquivo_pbl(nome: &str) -> String {
+    format!("{}.pbl", nome.to_lowercase())
+}
+
+fn diretorio_versao(base: &Path, nome: &str, versao: &Version) -> PathBuf {
+    base.join(nome).join(versao.to_string())
+}
+
+/// Onde os pacotes com versão são procurados.
+#[derive(Debug, Clone)]
+pub struct Fontes {
+    pub registro: Option<PathBuf>,
+    pub cache: PathBuf,
+}
+
+impl Fontes {
+    /// Registro de `[pacotes] registro` (ou `PORDOSOL_REGISTRO`) e o cache do usuário.
+    pub fn do_manifesto(raiz: &Path, manifesto: &Manifesto) -> Self {
+        let registro = manifesto
+            .pacotes
+            .registro
+            .as_ref()
+            .map(|r| raiz.join(r))
+            .or_else(|| env::var_os("PORDOSOL_REGISTRO").map(PathBuf::from));
+        Self {
+            registro,
+            cache: diretorio_cache(),
+        }
+    }
+
+    /// Versões de `nome` no registro e no cache, da maior para a menor.
+    fn versoes(&self, nome: &str) -> Vec<Version> {
+        let mut versoes = BTreeSet::new();
+        for base in self.registro.iter().chain(std::iter::once(&self.cache)) {
+            let Ok(entradas) = fs::read_dir(base.join(nome)) else {
+                continue;
+            };
+            for entrada in entradas.filter_map(Result::ok) {
+                let Some(versao) = entrada
+                    .file_name()
+                    .to_str()
+                    .and_then(|v| Version::parse(v).ok())
+                else {
+                    continue;
+                };
+                if entrada.path().join(arquivo_pbl(nome)).is_file() {
+                    versoes.insert(versao);
+                }
+            }
+        }
+        versoes.into_iter().rev().collect()
+    }
+
+    /// Garante o pacote no cache (copiando do registro) e devolve o diretório dele.
+    fn obter(&self, nome: &str, versao: &Version) -> Result<PathBuf, String> {
+        let destino = diretorio_versao(&self.cache, nome, versao);
+        if destino.join(arquivo_pbl(nome)).is_file() {
+            return Ok(destino);
+        }
+        let registro = self
+            .registro
+            .as_ref()
+            .ok_or_else(|| format!("pacote '{}' {} fora do cache e sem registro", nome, versao))?;
+        let origem = diretorio_versao(registro, nome, versao);
+        fs::create_dir_all(&destino)
+            .map_err(|e| format!("Erro ao criar '{}': {}", destino.display(), e))?;
+        for arquivo in [ARQUIVO_MANIFESTO.to_string(), arquivo_pbl(nome)] {
+            artefato::copiar(&origem.join(&arquivo), &destino.join(&arquivo)).map_err(|e| {
+                format!(
+                    "Erro ao copiar '{}' para o cache: {}",
+                    origem.join(&arquivo).display(),
+                    e
+                )
+            })?;
+        }
+        Ok(destino)
+    }
+}
+
+/// Cache do usuário: `PORDOSOL_CACHE` ou `~/.pordosol/pacotes`.
+pub fn diretorio_cache() -> PathBuf {
+    if let Some(dir) = env::var_os("PORDOSOL_CACHE") {
+        return PathBuf::from(dir);
+    }
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".pordosol")
+        .join("pacotes")
+}
+
+/// Quem pediu o pacote e com qual requisito, para as mensagens de conflito.
+#[derive(Debug, Clone)]
+struct Requisito {
+    versao: VersionReq,
+    de: String,
+}
+
+enum Falha {
+    /// A versão escolhida não atende a um requisito visto depois: tentar outra
+    Trocar(String, Version),
+    Erro(String),
+}
+
+impl From<String> for Falha {
+    fn from(msg: String) -> Self {
+        Falha::Erro(msg)
+    }
+}
+
+/// Resolve as dependências do manifesto (e as delas) e devolve os pacotes na ordem de carga:
+/// cada pacote vem depois das suas dependências. `lock` dá preferência às versões já travadas.
+pub fn resolver(
+    raiz: &Path,
+    manifesto: &Manifesto,
+    fontes: &Fontes,
+    lock: Option<&Lock>,
+) -> Result<Vec<Pacote>, String> {
+    let mut excluidas: BTreeMap<String, BTreeSet<Version>> = BTreeMap::new();
+    // Requisitos vistos em todas as tentativas, para explicar um conflito sem solução
+    let mut vistos: BTreeMap<String, Vec<Requisito>> = BTreeMap::new();
+    loop {
+        match tentar_resolver(raiz, manifesto, fontes, lock, &excluidas, &mut vistos) {
+            Ok(pacotes) => return ordenar(pacotes),
+            Err(Falha::Trocar(nome, versao)) => {
+                excluidas.entry(nome).or_default().insert(versao);
+            }
+            Err(Falha::Erro(msg)) => return Err(msg),
+        }
+    }
+}
+
+fn tentar_resolver(
+    raiz: &Path,
+    manifesto: &Manifesto,
+    fontes: &Fontes,
+    lock: Option<&Lock>,
+    excluidas: &BTreeMap<String, BTreeSet<Version>>,
+    vistos: &mut BTreeMap<String, Vec<Requisito>>,
+) -> Result<Vec<Pacote>, Falha> {
+    let mut escolhidos: BTreeMap<String, Pacote> = BTreeMap::new();
+    let mut requisitos: BTreeMap<String, Vec<Requisito>> = BTreeMap::new();
+    let mut fila: VecDeque<(String, PathBuf, String, Dependencia)> = manifesto
+        .dependencias
+        .iter()
+        .map(|(nome, dep)| {
+            let de = format!("{} {}", manifesto.projeto.nome, manifesto.projeto.versao);
+            (de, raiz.to_path_buf(), nome.clone(), dep.clone())
+        })
+        .collect();
+
+    while let Some((de, base, nome, dep)) = fila.pop_front() {
+        let versao = match &dep.versao {
+            Some(v) => VersionReq::parse(v)
+                .map_err(|e| format!("versão inválida '{}' para '{}' (de {}): {}", v, nome, de, e))?,
+            None => VersionReq::STAR,
+        };
+        let requisito = Requisito { versao, de };
+        requisitos.entry(nome.clone()).or_default().push(requisito.clone());
+        vistos.entry(nome.clone()).or_default().push(requisito.clone());
+        let caminho = dep.caminho.as_ref().map(|c| base.join(c));
+
+        if let Some(atual) = escolhidos.get(&nome) {
+            match (&caminho, &atual.origem) {
+                (None, Origem::Registro) => {}
+                (Some(dir), Origem::Caminho(atual_dir)) if mesmo_diretorio(dir, atual_dir) => {}
+                _ => {
+                    return Err(Falha::Erro(format!(
+                        "o pacote '{}' é pedido de origens diferentes (registro e caminho, ou dois caminhos); a última vez por {}",
+                        nome, requisito.de
+                    )))
+                }
+            }
+            if requisito.versao.matches(&atual.versao) {
+                continue;
+            }
+            if caminho.is_some() {
+                return Err(Falha::Erro(format!(
+                    "'{}' {} (por caminho) não satisfaz '{}', pedido por {}",
+                    nome, atual.versao, requisito.versao, requisito.de
+                )));
+            }
+            return Err(Falha::Trocar(nome, atual.versao.clone()));
+        }
+
+        let (pacote, diretorio, manifesto_pacote) = match caminho {
+            Some(dir) => {
+                let manifesto_pacote = ler_manifesto_pacote(&dir, &nome)?;
+                let versao = Version::parse(&manifesto_pacote.projeto.versao)
+                    .map_err(|e| format!("versão inválida em '{}': {}", dir.display(), e))?;
+                if !requisito.versao.matches(&versao) {
+                    return Err(Falha::Erro(format!(
+                        "'{}' em '{}' está na versão {}, mas {} pede '{}'",
+                        nome,
+                        dir.display(),
+                        versao,
+                        requisito.de,
+                        requisito.versao
+                    )));
+                }
+                let pacote = Pacote {
+                    nome: nome.clone(),
+                    versao,
+                    origem: Origem::Caminho(dir.clone()),
+                    dependencias: Vec::new(),
+                };
+                (pacote, dir, manifesto_pacote)
+            }
+            None => {
+                let versao = escolher_versao(
+                    &fontes.versoes(&nome),
+                    &requisitos[&nome],
+                    excluidas.get(&nome),
+                    lock.and_then(|l| l.versao_travada(&nome)).as_ref(),
+                )
+                .ok_or_else(|| sem_versao_compativel(&nome, fontes, &vistos[&nome]))?;
+                let dir = fontes.obter(&nome, &versao)?;
+                let manifesto_pacote = ler_manifesto_pacote(&dir, &nome)?;
+                if let Some((dep_nome, _)) = manifesto_pacote
+                    .dependencias
+                    .iter()
+                    .find(|(_, d)| d.caminho.is_some())
+                {
+                    return Err(Falha::Erro(format!(
+                        "o pacote publicado '{}' {} depende de '{}' por caminho",
+                        nome, versao, dep_nome
+                    )));
+                }
+                let pacote = Pacote {
+                    nome: nome.clone(),
+                    versao,
+                    origem: Origem::Registro,
+                    dependencias: Vec::new(),
+                };
+                (pacote, dir, manifesto_pacote)
+            }
+        };
+
+        let de = format!("{} {}", pacote.nome, pacote.versao);
+        let mut pacote = pacote;
+        for (dep_nome, dep) in &manifesto_pacote.dependencias {
+            pacote.dependencias.push(dep_nome.clone());
+            fila.push_back((de.clone(), diretorio.clone(), dep_nome.clone(), dep.clone()));
+        }
+        escolhidos.insert(nome, pacote);
+    }
+    Ok(escolhidos.into_values().collect())
+}
+
+/// A maior versão que atende a todos os requisitos e não foi descartada; a travada no
+/// `projeto.lock` tem preferência se também atender.
+fn escolher_versao(
+    disponiveis: &[Version],
+    requisitos: &[Requisito],
+    excluidas: Option<&BTreeSet<Version>>,
+    travada: Option<&Version>,
+) -> Option<Version> {
+    let aceita = |v: &Version| {
+        !excluidas.is_some_and(|e| e.contains(v)) && requisitos.iter().all(|r| r.versao.matches(v))
+    };
+    if let Some(travada) = travada.filter(|v| disponiveis.contains(v) && aceita(v)) {
+        return Some(travada.clone());
+    }
+    disponiveis.iter().find(|v| aceita(v)).cloned()
+}
+
+fn sem_versao_compativel(nome: &str, fontes: &Fontes, requisitos: &[Requisito]) -> String {
+    let disponiveis = fontes.versoes(nome);
+    if disponiveis.is_empty() {
+        return match &fontes.registro {
+            Some(registro) => format!(
+                "pacote '{}' não encontrado no registro '{}' nem no cache '{}'",
+                nome,
+                registro.display(),
+                fontes.cache.display()
+            ),
+            None => format!(
+                "pacote '{}' não está no cache '{}' e não há registro configurado ([pacotes] registro ou PORDOSOL_REGISTRO)",
+                nome,
+                fontes.cache.display()
+            ),
+        };
+    }
+    let mut pedidos: Vec<String> = requisitos
+        .iter()
+        .map(|r| format!("'{}' (de {})", r.versao, r.de))
+        .collect();
+    pedidos.dedup();
+    format!(
+        "nenhuma versão de '{}' satisfaz {}; disponíveis: {}",
+        nome,
+        pedidos.join(", "),
+        disponiveis
+            .iter()
+            .map(Version::to_string)
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
+fn ler_manifesto_pacote(dir: &Path, nome: &str) -> Result<Manifesto, String> {
+    let caminho = dir.join(ARQUIVO_MANIFESTO);
+    if !caminho.is_file() {
+        return Err(format!(
+            "pacote '{}': '{}' não encontrado",
+            nome,
+            caminho.display()
+        ));
+    }
+    let manifesto = Manifesto::ler(&caminho)?;
+    if manifesto.projeto.nome != nome {
+        return Err(format!(
+            "'{}' declara o projeto '{}', mas foi pedido como '{}'",
+            caminho.display(),
+            manifesto.projeto.nome,
+            nome
+        ));
+    }
+    if manifesto.projeto.tipo != TipoProjeto::Biblioteca {
+        return Err(format!(
+            "a dependência '{}' não é uma biblioteca (tipo = \"biblioteca\" em '{}')",
+            nome,
+            caminho.display()
+        ));
+    }
+    Ok(manifesto)
+}
+
+fn mesmo_diretorio(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+/// Ordena os pacotes de forma que cada um venha depois das suas dependências.
+fn ordenar(pacotes: Vec<Pacote>) -> Result<Vec<Pacote>, String> {
+    let mut por_nome: BTreeMap<String, Pacote> =
+        pacotes.into_iter().map(|p| (p.nome.clone(), p)).collect();
+    let nomes: Vec<String> = por_nome.keys().cloned().collect();
+    let mut ordem = Vec::new();
+    let mut pilha = Vec::new();
+    for nome in &nomes {
+        visitar(nome, &por_nome, &mut pilha, &mut ordem)?;
+    }
+    Ok(ordem
+        .into_iter()
+        .filter_map(|nome| por_nome.remove(&nome))
+        .collect())
+}
+
+fn visitar(
+    nome: &str,
+    pacotes: &BTreeMap<String, Pacote>,
+    pilha: &mut Vec<String>,
+    ordem: &mut Vec<String>,
+) -> Result<(), String> {
+    if ordem.iter().any(|n| n == nome) {
+        return Ok(());
+    }
+    if let Some(inicio) = pilha.iter().position(|n| n == nome) {
+        let mut ciclo = pilha[inicio..].to_vec();
+        ciclo.push(nome.to_string());
+        return Err(format!(
+            "dependência circular entre pacotes: {}",
+            ciclo.join(" → ")
+        ));
+    }
+    pilha.push(nome.to_string());
+    for dep in &pacotes[nome].dependencias {
+        visitar(dep, pacotes, pilha, ordem)?;
+    }
+    pilha.pop();
+    ordem.push(nome.to_string());
+    Ok(())
+}
+
+/// Conteúdo do `projeto.lock`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Lock {
+    pub versao: u32,
+    #[serde(default, rename = "pacote")]
+    pub pacotes: Vec<PacoteTravado>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PacoteTravado {
+    pub nome: String,
+    pub versao: String,
+    /// `registro` ou `caminho+<diretório relativo ao projeto>`
+    pub origem: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dependencias: Vec<String>,
+}
+
+const CABECALHO_LOCK: &str = "# Gerado por `compilador construir`. Não edite à mão.\n\n";
+
+impl Lock {
+    /// Lê o `projeto.lock` de `raiz`, se existir.
+    pub fn ler(raiz: &Path) -> Result<Option<Self>, String> {
+        let caminho = raiz.join(ARQUIVO_LOCK);
+        let Ok(conteudo) = fs::read_to_string(&caminho) else {
+            return Ok(None);
+        };
+        toml::from_str(&conteudo)
+            .map(Some)
+            .map_err(|e| format!("{}: {}", caminho.display(), e.message()))
+    }
+
+    pub fn de_pacotes(pacotes: &[Pacote], raiz: &Path) -> Self {
+        Self {
+            versao: 1,
+            pacotes: pacotes
+                .iter()
+                .map(|p| PacoteTravado {
+                    nome: p.nome.clone(),
+                    versao: p.versao.to_string(),
+                    origem: match &p.origem {
+                        Origem::Registro => "registro".to_string(),
+                        Origem::Caminho(dir) => {
+                            format!("caminho+{}", relativo(dir, raiz).display())
+                        }
+                    },
+                    dependencias: p.dependencias.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Grava o lock em `raiz`; devolve `false` se o arquivo já tinha este conteúdo.
+    pub fn gravar(&self, raiz: &Path) -> Result<bool, String> {
+        let caminho = raiz.join(ARQUIVO_LOCK);
+        let texto = toml::to_string(self).map_err(|e| e.to_string())?;
+        let conteudo = format!("{}{}", CABECALHO_LOCK, texto);
+        if fs::read_to_string(&caminho).is_ok_and(|atual| atual == conteudo) {
+            return Ok(false);
+        }
+        fs::write(&caminho, conteudo)
+            .map_err(|e| format!("Erro ao gravar '{}': {}", caminho.display(), e))?;
+        Ok(true)
+    }
+
+    /// Versão travada de um pacote do registro.
+    fn versao_travada(&self, nome: &str) -> Option<Version> {
+        self.pacotes
+            .iter()
+            .find(|p| p.nome == nome && p.origem == "registro")
+            .and_then(|p| Version::parse(&p.versao).ok())
+    }
+}
+
+/// `dir` relativo a `base` (`../util`), para o lock não depender de onde o projeto está.
+fn relativo(dir: &Path, base: &Path) -> PathBuf {
+    let (Ok(dir), Ok(base)) = (dir.canonicalize(), base.canonicalize()) else {
+        return dir.to_path_buf();
+    };
+    let dir: Vec<Component> = dir.components().collect();
+    let base: Vec<Component> = base.components().collect();
+    let comum = dir.iter().zip(&base).take_while(|(a, b)| a == b).count();
+    let mut resultado = PathBuf::new();
+    for _ in comum..base.len() {
+        resultado.push("..");
+    }
+    for parte in &dir[comum..] {
+        resultado.push(parte);
+    }
+    if resultado.as_os_str().is_empty() {
+        resultado.push(".");
+    }
+    resultado
+}
+
+/// Diretório `<registro>/<nome>/<versao>/` onde a biblioteca será publicada, depois de
+/// conferir que ela pode ser: é uma biblioteca, não depende de nada por caminho e essa
+/// versão ainda não foi publicada.
+pub fn destino_publicacao(manifesto: &Manifesto, registro: &Path) -> Result<PathBuf, String> {
+    let nome = &manifesto.projeto.nome;
+    if manifesto.projeto.tipo != TipoProjeto::Biblioteca {
+        return Err(format!(
+            "'{}' não é uma biblioteca: só bibliotecas podem ser publicadas",
+            nome
+        ));
+    }
+    if let Some(dep) = manifesto.dependencias.iter().find(|(_, d)| d.caminho.is_some()) {
+        return Err(format!(
+            "a dependência '{}' usa `caminho`; publique-a e use `versao` antes de publicar '{}'",
+            dep.0, nome
+        ));
+    }
+    let versao = Version::parse(&manifesto.projeto.versao).map_err(|e| e.to_string())?;
+    let destino = diretorio_versao(registro, nome, &versao);
+    if destino.exists() {
+        return Err(format!(
+            "'{}' {} já está publicado em '{}'; aumente a versão em {}",
+            nome,
+            versao,
+            destino.display(),
+            ARQUIVO_MANIFESTO
+        ));
+    }
+    Ok(destino)
+}
+
+/// Copia o `projeto.toml` e o `.pbl` (já construído em `dist/`, com a assinatura, se houver)
+/// para `destino`.
+pub fn publicar(raiz: &Path, manifesto: &Manifesto, destino: &Path) -> Result<(), String> {
+    let pbl = arquivo_pbl(&manifesto.projeto.nome);
+    fs::create_dir_all(destino)
+        .map_err(|e| format!("Erro ao criar '{}': {}", destino.display(), e))?;
+    for (de, para) in [
+        (raiz.join(ARQUIVO_MANIFESTO), destino.join(ARQUIVO_MANIFESTO)),
+        (raiz.join("dist").join(&pbl), destino.join(&pbl)),
+    ] {
+        artefato::copiar(&de, &para)
+            .map_err(|e| format!("Erro ao copiar '{}': {}", de.display(), e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn requisitos(lista: &[&str]) -> Vec<Requisito> {
+        lista
+            .iter()
+            .map(|r| Requisito {
+                versao: VersionReq::parse(r).unwrap(),
+                de: "app 1.0.0".to_string(),
+            })
+            .collect()
+    }
+
+    fn versoes(lista: &[&str]) -> Vec<Version> {
+        lista.iter().map(|v| Version::parse(v).unwrap()).collect()
+    }
+
+    #[test]
+    fn escolhe_a_maior_versao_compativel_com_todos() {
+        let disponiveis = versoes(&["2.0.0", "1.4.1", "1.3.0", "1.0.0"]);
+        let escolher = |r: &[&str]| escolher_versao(&disponiveis, &requisitos(r), None, None);
+        assert_eq!(escolher(&["^1.2"]), Some(Version::new(1, 4, 1)));
+        assert_eq!(escolher(&["^1.2", "<1.4"]), Some(Version::new(1, 3, 0)));
+        assert_eq!(escolher(&["*"]), Some(Version::new(2, 0, 0)));
+        assert_eq!(escolher(&["^1", "^2"]), None);
+    }
+
+    #[test]
+    fn versao_travada_e_descartadas() {
+        let disponiveis = versoes(&["1.4.1", "1.3.0"]);
+        let reqs = requisitos(&["^1.2"]);
+        let travada = Version::new(1, 3, 0);
+        assert_eq!(
+            escolher_versao(&disponiveis, &reqs, None, Some(&travada)),
+            Some(travada.clone())
+        );
+        // A travada que não atende mais ao manifesto é ignorada
+        let reqs_novos = requisitos(&["^1.4"]);
+        assert_eq!(
+            escolher_versao(&disponiveis, &reqs_novos, None, Some(&travada)),
+            Some(Version::new(1, 4, 1))
+        );
+        let excluidas: BTreeSet<Version> = [Version::new(1, 4, 1)].into_iter().collect();
+        assert_eq!(
+            escolher_versao(&disponiveis, &reqs, Some(&excluidas), None),
+            Some(Version::new(1, 3, 0))
+        );
+    }
+
+    #[test]
+    fn ordem_de_carga_e_ciclos() {
+        let pacote = |nome: &str, deps: &[&str]| Pacote {
+            nome: nome.to_string(),
+            versao: Version::new(1, 0, 0),
+            origem: Origem::Registro,
+            dependencias: deps.iter().map(|d| d.to_string()).collect(),
+        };
+        let ordem: Vec<String> = ordenar(vec![
+            pacote("a", &["c"]),
+            pacote("b", &[]),
+            pacote("c", &["b"]),
+        ])
+        .unwrap()
+        .into_iter()
+        .map(|p| p.nome)
+        .collect();
+        assert_eq!(ordem, vec!["b", "c", "a"]);
+
+        let erro = ordenar(vec![pacote("a", &["b"]), pacote("b", &["a"])]).unwrap_err();
+        assert_eq!(erro, "dependência circular entre pacotes: a → b → a");
+    }
+}
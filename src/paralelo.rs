@@ -0,0 +1,78 @@
+//! Execução paralela com resultado determinístico: o trabalho é dividido em itens fixos (um
+//! arquivo, uma declaração) e os resultados voltam na ordem dos itens. O número de threads só
+//! muda quanto tempo leva, nunca o que é gerado.
+//!
+//! `PORDOSOL_THREADS` limita as threads (`1` deixa tudo sequencial); sem ela, usa os núcleos
+//! disponíveis.
+
+use std::env;
+use std::num::NonZeroUsize;
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+pub const VARIAVEL_THREADS: &str = "PORDOSOL_THREADS";
+
+/// Pilha de cada thread: a análise e a geração de código descem recursivamente pela AST.
+const TAMANHO_PILHA: usize = 64 * 1024 * 1024;
+
+/// Threads a usar: `PORDOSOL_THREADS`, se for um número positivo, ou os núcleos disponíveis.
+pub fn threads() -> usize {
+    env::var(VARIAVEL_THREADS)
+        .ok()
+        .and_then(|valor| valor.trim().parse::<usize>().ok())
+        .filter(|&n| n > 0)
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, NonZeroUsize::get))
+}
+
+/// `f` aplicada a cada item, em até [`threads`] threads, com os resultados na ordem dos itens.
+/// Um pânico em `f` é repassado a quem chamou, como na versão sequencial.
+pub fn mapear<T: Sync, R: Send>(itens: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let threads = threads().min(itens.len());
+    if threads <= 1 {
+        return itens.iter().map(f).collect();
+    }
+    let proximo = AtomicUsize::new(0);
+    let mut feitos: Vec<(usize, R)> = thread::scope(|escopo| {
+        let trabalhadores: Vec<_> = (0..threads)
+            .map(|_| {
+                thread::Builder::new()
+                    .stack_size(TAMANHO_PILHA)
+                    .spawn_scoped(escopo, || {
+                        let mut feitos = Vec::new();
+                        loop {
+                            let indice = proximo.fetch_add(1, Ordering::Relaxed);
+                            let Some(item) = itens.get(indice) else {
+                                break feitos;
+                            };
+                            feitos.push((indice, f(item)));
+                        }
+                    })
+                    .expect("falha ao criar thread")
+            })
+            .collect();
+        trabalhadores
+            .into_iter()
+            .flat_map(|t| t.join().unwrap_or_else(|erro| panic::resume_unwind(erro)))
+            .collect()
+    });
+    feitos.sort_by_key(|(indice, _)| *indice);
+    feitos.into_iter().map(|(_, resultado)| resultado).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resultados_seguem_a_ordem_dos_itens() {
+        let itens: Vec<u64> = (0..200).collect();
+        let quadrados = mapear(&itens, |&n| {
+            // Itens com custos diferentes terminam fora de ordem
+            thread::sleep(std::time::Duration::from_micros((200 - n) * 10));
+            n * n
+        });
+        assert_eq!(quadrados, itens.iter().map(|n| n * n).collect::<Vec<_>>());
+        assert!(mapear(&[] as &[u64], |&n| n).is_empty());
+    }
+}
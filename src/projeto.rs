@@ -0,0 +1,396 @@
+//! Manifesto de projeto (`projeto.toml`), lido por `compilador construir`:
+//!
+//! ```toml
+//! [projeto]
+//! nome = "calculadora"
+//! versao = "1.2.0"
+//! tipo = "aplicacao"            # ou "biblioteca"
+//! entrada = "src/principal.pr"  # padrão para aplicações
+//! fontes = ["src"]
+//! alvos = ["bytecode", "llvm-ir"]
+//!
+//! [compilacao]
+//! otimizacao = "O2"
+//! verificacoes = true
+//! debug = false
+//! modulos = ["lib"]
+//!
+//! [dependencias]
+//! matematica = "^1.2"
+//! util = { caminho = "../util" }
+//!
+//! [pacotes]
+//! registro = "../registro"
+//! ```
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+pub const ARQUIVO_MANIFESTO: &str = "projeto.toml";
+
+/// Alvos aceitos em `alvos` (os mesmos nomes de `--target=`).
+pub const ALVOS: &[&str] = &[
+    "bytecode",
+    "llvm-ir",
+    "c",
+    "wasm",
+    "cil-bytecode",
+    "console",
+    "biblioteca",
+];
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifesto {
+    pub projeto: InfoProjeto,
+    #[serde(default)]
+    pub compilacao: OpcoesCompilacao,
+    #[serde(default)]
+    pub dependencias: BTreeMap<String, Dependencia>,
+    #[serde(default)]
+    pub pacotes: OpcoesPacotes,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct InfoProjeto {
+    pub nome: String,
+    pub versao: String,
+    #[serde(default)]
+    pub tipo: TipoProjeto,
+    // Informativos: aceitos no manifesto, mas ainda não usados pelo compilador
+    #[allow(dead_code)]
+    #[serde(default)]
+    pub descricao: Option<String>,
+    #[allow(dead_code)]
+    #[serde(default)]
+    pub autores: Vec<String>,
+    /// Arquivo principal de uma aplicação (padrão: `src/principal.pr`)
+    #[serde(default)]
+    pub entrada: Option<PathBuf>,
+    /// Diretórios com os `.pr` do projeto, relativos ao manifesto
+    #[serde(default = "fontes_padrao")]
+    pub fontes: Vec<PathBuf>,
+    /// Alvos gerados por `construir` (padrão: `bytecode`, ou `biblioteca` para bibliotecas)
+    #[serde(default)]
+    pub alvos: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize)]
+pub enum TipoProjeto {
+    #[default]
+    #[serde(rename = "aplicacao", alias = "aplicação")]
+    Aplicacao,
+    #[serde(rename = "biblioteca")]
+    Biblioteca,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OpcoesCompilacao {
+    /// Nível do clang para os alvos nativos: `O0`..`O3` ou `Os`
+    #[serde(default)]
+    pub otimizacao: Option<String>,
+    #[serde(default = "verdadeiro")]
+    pub verificacoes: bool,
+    #[serde(default)]
+    pub debug: bool,
+    /// Caminhos de busca extras para `importar`, relativos ao manifesto
+    #[serde(default)]
+    pub modulos: Vec<PathBuf>,
+    /// Código-fonte da biblioteca padrão (o mesmo que `--stdlib-src-path`)
+    #[serde(default)]
+    pub stdlib: Option<PathBuf>,
+}
+
+impl Default for OpcoesCompilacao {
+    fn default() -> Self {
+        Self {
+            otimizacao: None,
+            verificacoes: true,
+            debug: false,
+            modulos: Vec::new(),
+            stdlib: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct OpcoesPacotes {
+    /// Registro local de pacotes, relativo ao manifesto (padrão: `PORDOSOL_REGISTRO`)
+    #[serde(default)]
+    pub registro: Option<PathBuf>,
+}
+
+/// Dependência declarada: `nome = "^1.2"` ou `nome = { versao = "^1.2", caminho = "../nome" }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Dependencia {
+    pub versao: Option<String>,
+    pub caminho: Option<PathBuf>,
+}
+
+impl<'de> Deserialize<'de> for Dependencia {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Detalhada {
+            #[serde(default)]
+            versao: Option<String>,
+            #[serde(default)]
+            caminho: Option<PathBuf>,
+        }
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Forma {
+            Versao(String),
+            Detalhada(Detalhada),
+        }
+        Ok(match Forma::deserialize(deserializer)? {
+            Forma::Versao(versao) => Dependencia {
+                versao: Some(versao),
+                caminho: None,
+            },
+            Forma::Detalhada(d) => Dependencia {
+                versao: d.versao,
+                caminho: d.caminho,
+            },
+        })
+    }
+}
+
+fn fontes_padrao() -> Vec<PathBuf> {
+    vec![PathBuf::from("src")]
+}
+
+fn verdadeiro() -> bool {
+    true
+}
+
+impl Manifesto {
+    /// Lê e valida um `projeto.toml`.
+    pub fn ler(caminho: &Path) -> Result<Self, String> {
+        let conteudo = fs::read_to_string(caminho)
+            .map_err(|e| format!("Erro ao ler '{}': {}", caminho.display(), e))?;
+        Self::de_texto(&conteudo).map_err(|e| format!("{}: {}", caminho.display(), e))
+    }
+
+    pub fn de_texto(conteudo: &str) -> Result<Self, String> {
+        let manifesto: Manifesto = toml::from_str(conteudo)
+            .map_err(|e| format!("manifesto inválido: {}", e.message()))?;
+        manifesto.validar()?;
+        Ok(manifesto)
+    }
+
+    fn validar(&self) -> Result<(), String> {
+        let nome = &self.projeto.nome;
+        if nome.is_empty()
+            || !nome
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(format!(
+                "nome de projeto inválido '{}': use letras, números, '_' ou '-'",
+                nome
+            ));
+        }
+        if !versao_valida(&self.projeto.versao) {
+            return Err(format!(
+                "versão inválida '{}': use o formato MAIOR.MENOR.CORREÇÃO (ex.: 1.0.0)",
+                self.projeto.versao
+            ));
+        }
+        for alvo in &self.projeto.alvos {
+            if !ALVOS.contains(&alvo.as_str()) {
+                return Err(format!(
+                    "alvo desconhecido '{}' (disponíveis: {})",
+                    alvo,
+                    ALVOS.join(", ")
+                ));
+            }
+        }
+        if let Some(nivel) = &self.compilacao.otimizacao {
+            if !["O0", "O1", "O2", "O3", "Os"].contains(&nivel.trim_start_matches('-')) {
+                return Err(format!(
+                    "otimização inválida '{}' (use O0, O1, O2, O3 ou Os)",
+                    nivel
+                ));
+            }
+        }
+        for (nome, dep) in &self.dependencias {
+            if dep.versao.is_none() && dep.caminho.is_none() {
+                return Err(format!(
+                    "a dependência '{}' precisa de `versao` ou `caminho`",
+                    nome
+                ));
+            }
+            if let Some(versao) = &dep.versao {
+                semver::VersionReq::parse(versao).map_err(|e| {
+                    format!(
+                        "versão inválida '{}' na dependência '{}': {}",
+                        versao, nome, e
+                    )
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Alvos a construir: os do manifesto ou o padrão do tipo de projeto.
+    pub fn alvos(&self) -> Vec<String> {
+        if !self.projeto.alvos.is_empty() {
+            return self.projeto.alvos.clone();
+        }
+        match self.projeto.tipo {
+            TipoProjeto::Aplicacao => vec!["bytecode".to_string()],
+            TipoProjeto::Biblioteca => vec!["biblioteca".to_string()],
+        }
+    }
+
+    /// Arquivo principal de uma aplicação, relativo ao manifesto.
+    pub fn entrada(&self) -> Option<PathBuf> {
+        match self.projeto.tipo {
+            TipoProjeto::Aplicacao => Some(
+                self.projeto
+                    .entrada
+                    .clone()
+                    .unwrap_or_else(|| PathBuf::from("src").join("principal.pr")),
+            ),
+            TipoProjeto::Biblioteca => self.projeto.entrada.clone(),
+        }
+    }
+
+    /// Todos os `.pr` dos diretórios de fontes (em ordem alfabética), com a entrada por último.
+    pub fn descobrir_fontes(&self, raiz: &Path) -> Result<Vec<PathBuf>, String> {
+        let entrada = self.entrada().map(|e| raiz.join(e));
+        if let Some(entrada) = &entrada {
+            if !entrada.is_file() {
+                return Err(format!(
+                    "arquivo de entrada '{}' não encontrado",
+                    entrada.display()
+                ));
+            }
+        }
+
+        let mut fontes = Vec::new();
+        for dir in &self.projeto.fontes {
+            let dir = raiz.join(dir);
+            if !dir.is_dir() {
+                return Err(format!(
+                    "diretório de fontes '{}' não encontrado",
+                    dir.display()
+                ));
+            }
+            fontes.extend(
+                WalkDir::new(&dir)
+                    .into_iter()
+                    .filter_map(Result::ok)
+                    .map(|e| e.into_path())
+                    .filter(|p| p.extension().is_some_and(|e| e == "pr")),
+            );
+        }
+        fontes.sort();
+        fontes.dedup();
+        if let Some(entrada) = entrada {
+            let canonica = entrada.canonicalize().ok();
+            fontes.retain(|p| p.canonicalize().ok() != canonica);
+            fontes.push(entrada);
+        }
+        if fontes.is_empty() {
+            return Err("nenhum arquivo .pr encontrado nas fontes do projeto".to_string());
+        }
+        Ok(fontes)
+    }
+}
+
+/// `MAIOR.MENOR.CORREÇÃO`, com sufixo opcional de pré-lançamento (`1.0.0-beta.1`).
+pub fn versao_valida(versao: &str) -> bool {
+    let nucleo = versao.split(['-', '+']).next().unwrap_or("");
+    let partes: Vec<&str> = nucleo.split('.').collect();
+    partes.len() == 3
+        && partes
+            .iter()
+            .all(|p| !p.is_empty() && p.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Nome e versão de uma biblioteca: do `projeto.toml` ou, em bibliotecas antigas, das chaves
+/// `nome`/`versao` do `Sistema.toml`. Sem manifesto, usa `Sistema` 1.0.0.
+pub fn metadados_biblioteca(caminho_lib: &Path) -> Result<(String, String), String> {
+    let manifesto = caminho_lib.join(ARQUIVO_MANIFESTO);
+    if manifesto.is_file() {
+        let m = Manifesto::ler(&manifesto)?;
+        return Ok((m.projeto.nome, m.projeto.versao));
+    }
+
+    #[derive(Deserialize)]
+    struct Legado {
+        nome: Option<String>,
+        versao: Option<String>,
+    }
+    let legado = caminho_lib.join("Sistema.toml");
+    let (nome, versao) = match fs::read_to_string(&legado) {
+        Ok(conteudo) => {
+            let l: Legado = toml::from_str(&conteudo)
+                .map_err(|e| format!("{}: {}", legado.display(), e.message()))?;
+            (l.nome, l.versao)
+        }
+        Err(_) => (None, None),
+    };
+    Ok((
+        nome.unwrap_or_else(|| "Sistema".to_string()),
+        versao.unwrap_or_else(|| "1.0.0".to_string()),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifesto_completo() {
+        let m = Manifesto::de_texto(
+            r#"
+[projeto]
+nome = "calc"
+versao = "1.2.0"
+tipo = "aplicação"
+alvos = ["bytecode", "c"]
+
+[compilacao]
+otimizacao = "O2"
+verificacoes = false
+
+[dependencias]
+matematica = "^1.2"
+util = { caminho = "../util" }
+"#,
+        )
+        .unwrap();
+        assert_eq!(m.projeto.tipo, TipoProjeto::Aplicacao);
+        assert_eq!(m.alvos(), vec!["bytecode", "c"]);
+        assert_eq!(m.entrada(), Some(PathBuf::from("src").join("principal.pr")));
+        assert!(!m.compilacao.verificacoes);
+        assert_eq!(m.dependencias["matematica"].versao.as_deref(), Some("^1.2"));
+        assert_eq!(
+            m.dependencias["util"].caminho,
+            Some(PathBuf::from("../util"))
+        );
+    }
+
+    #[test]
+    fn manifesto_invalido_explica_o_erro() {
+        let base = "[projeto]\nnome = \"calc\"\nversao = \"1.0.0\"\n";
+        let erro = |extra: &str| Manifesto::de_texto(&format!("{}{}", base, extra)).unwrap_err();
+        assert!(erro("alvos = [\"jvm\"]").contains("alvo desconhecido 'jvm'"));
+        assert!(erro("tipo = \"plugin\"").contains("plugin"));
+        assert!(erro("fonte = [\"src\"]").contains("fonte"));
+        assert!(erro("[dependencias]\nutil = \"~>1\"")
+            .contains("versão inválida '~>1' na dependência 'util'"));
+        assert!(Manifesto::de_texto("[projeto]\nnome = \"calc\"\nversao = \"1.0\"\n")
+            .unwrap_err()
+            .contains("versão inválida '1.0'"));
+    }
+}
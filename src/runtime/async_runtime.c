@@ -3,6 +3,7 @@
 #endif
 
 #include "async_runtime.h"
+#include "gc_runtime.h"
 
 #include <stdio.h>
 #include <stdlib.h>
@@ -135,6 +136,8 @@ static void *worker_thread(void *argument)
         } else {
             task_complete(task, task->function(task->argument), TASK_COMPLETED);
         }
+        /* A partir daqui esta thread não usa mais objetos do coletor */
+        pds_gc_tarefa_concluida();
     }
 
 #ifdef _WIN32
@@ -222,8 +225,17 @@ void thread_pool_shutdown(void) {
     free(pool);
 }
 
+/* Chamado pelo coletor quando a tarefa deixa de ser alcançável. */
+static void task_finalize(void *memory) {
+    Task *task = (Task *)memory;
+    condition_destroy(&task->completed);
+    mutex_destroy(&task->mutex);
+}
+
+/* A tarefa vive no heap do coletor: o resultado e o argumento que ela guarda continuam
+ * alcançáveis enquanto o código gerado tiver o Task*. */
 Task *task_create(int id) {
-    Task *task = (Task *)calloc(1, sizeof(*task));
+    Task *task = (Task *)pds_gc_alocar_com_finalizador(sizeof(*task), task_finalize);
     if (task == NULL) {
         return NULL;
     }
@@ -234,13 +246,9 @@ Task *task_create(int id) {
     return task;
 }
 
+/* Mantida por compatibilidade: a tarefa é liberada pelo coletor (task_finalize). */
 void task_destroy(Task *task) {
-    if (task == NULL) {
-        return;
-    }
-    condition_destroy(&task->completed);
-    mutex_destroy(&task->mutex);
-    free(task);
+    (void)task;
 }
 
 void task_submit_to_pool(Task *task, TaskFunction function, void *argument) {
@@ -270,6 +278,7 @@ void task_submit_to_pool(Task *task, TaskFunction function, void *argument) {
         task_complete(task, NULL, TASK_FAILED);
         return;
     }
+    pds_gc_tarefa_iniciada();
     pool->queue[pool->tail] = task;
     pool->tail = (pool->tail + 1) % MAX_QUEUE_SIZE;
     ++pool->count;
@@ -363,7 +372,7 @@ static void *async_read_file(void *argument) {
         fclose(file);
         return NULL;
     }
-    contents = (char *)malloc((size_t)size + 1);
+    contents = (char *)pds_gc_alocar_atomico((size_t)size + 1);
     if (contents == NULL) {
         fclose(file);
         return NULL;
@@ -445,6 +454,7 @@ Task *task_create_file_exists(const char *path) {
     return task;
 }
 
+/* Mantida por compatibilidade: os resultados ficam no heap do coletor. */
 void free_async_result(void *result) {
-    free(result);
+    (void)result;
 }
@@ -0,0 +1,627 @@
+/* Runtime do alvo C: implementação das funções usadas pelo código gerado por --target=c. */
+#include "c_runtime.h"
+
+#include <errno.h>
+#include <math.h>
+#include <stdarg.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+/* ------------------------------------------------------------------------ */
+/* Arrays e verificações                                                     */
+/* ------------------------------------------------------------------------ */
+
+static const char *texto_array(const PdsObjeto *objeto);
+
+static const PdsClasse classe_array = {"array", NULL, texto_array};
+
+static size_t tamanho_elemento(PdsElemento elemento) {
+    switch (elemento) {
+    case PDS_ELEMENTO_INTEIRO:
+        return sizeof(int64_t);
+    case PDS_ELEMENTO_FLUTUANTE:
+        return sizeof(float);
+    case PDS_ELEMENTO_DUPLO:
+        return sizeof(double);
+    case PDS_ELEMENTO_BOOLEANO:
+        return sizeof(bool);
+    default:
+        return sizeof(void *);
+    }
+}
+
+PdsArray *pds_array_novo(int64_t tamanho, PdsElemento elemento, const char *arquivo, int linha, int coluna) {
+    PdsArray *array;
+    size_t cabecalho = (sizeof(PdsArray) + sizeof(double) - 1) / sizeof(double) * sizeof(double);
+    if (tamanho < 0) {
+        pds_panico("Tamanho de array negativo", arquivo, linha, coluna);
+    }
+    array = (PdsArray *)pds_gc_alocar(cabecalho + (size_t)tamanho * tamanho_elemento(elemento));
+    array->base.classe = &classe_array;
+    array->tamanho = tamanho;
+    array->elemento = elemento;
+    array->itens = (char *)array + cabecalho;
+    if (elemento == PDS_ELEMENTO_TEXTO) {
+        /* Como no interpretador, itens de texto começam vazios (e não nulos) */
+        int64_t i;
+        for (i = 0; i < tamanho; i++) {
+            PDS_ITENS(array, const char *)[i] = "";
+        }
+    }
+    return array;
+}
+
+PdsArray *pds_array_de(int quantidade, PdsElemento elemento, ...) {
+    PdsArray *array = pds_array_novo(quantidade, elemento, NULL, 0, 0);
+    va_list itens;
+    int i;
+    va_start(itens, elemento);
+    for (i = 0; i < quantidade; i++) {
+        switch (elemento) {
+        case PDS_ELEMENTO_INTEIRO:
+            PDS_ITENS(array, int64_t)[i] = va_arg(itens, int64_t);
+            break;
+        case PDS_ELEMENTO_FLUTUANTE:
+            PDS_ITENS(array, float)[i] = (float)va_arg(itens, double);
+            break;
+        case PDS_ELEMENTO_DUPLO:
+            PDS_ITENS(array, double)[i] = va_arg(itens, double);
+            break;
+        case PDS_ELEMENTO_BOOLEANO:
+            PDS_ITENS(array, bool)[i] = va_arg(itens, int) != 0;
+            break;
+        case PDS_ELEMENTO_TEXTO:
+            PDS_ITENS(array, const char *)[i] = va_arg(itens, const char *);
+            break;
+        case PDS_ELEMENTO_OBJETO:
+            PDS_ITENS(array, void *)[i] = va_arg(itens, void *);
+            break;
+        }
+    }
+    va_end(itens);
+    return array;
+}
+
+int64_t pds_indice(const PdsArray *array, int64_t indice, const char *arquivo, int linha, int coluna) {
+    if (array == NULL) {
+        pds_panico("Acesso por índice requer array ou texto e um inteiro", arquivo, linha, coluna);
+    }
+    if (indice < 0) {
+        pds_panico("Índice negativo", arquivo, linha, coluna);
+    }
+    if (indice >= array->tamanho) {
+        pds_panico("Índice fora do intervalo", arquivo, linha, coluna);
+    }
+    return indice;
+}
+
+void pds_verificar_nulo(const void *referencia, const char *mensagem, const char *arquivo, int linha, int coluna) {
+    if (referencia == NULL) {
+        pds_panico(mensagem, arquivo, linha, coluna);
+    }
+}
+
+int64_t pds_dividir(int64_t a, int64_t b, const char *arquivo, int linha, int coluna) {
+    if (b == 0) {
+        pds_panico("Divisão por zero", arquivo, linha, coluna);
+    }
+    /* INT64_MIN / -1 transborda: o resultado modular é o próprio INT64_MIN */
+    if (b == -1) {
+        return (int64_t)(0 - (uint64_t)a);
+    }
+    return a / b;
+}
+
+int64_t pds_resto(int64_t a, int64_t b, const char *arquivo, int linha, int coluna) {
+    if (b == 0) {
+        pds_panico("Módulo por zero", arquivo, linha, coluna);
+    }
+    if (b == -1) {
+        return 0;
+    }
+    return a % b;
+}
+
+double pds_dividir_duplo(double a, double b, const char *arquivo, int linha, int coluna) {
+    if (b == 0.0) {
+        pds_panico("Divisão por zero", arquivo, linha, coluna);
+    }
+    return a / b;
+}
+
+double pds_resto_duplo(double a, double b, const char *arquivo, int linha, int coluna) {
+    if (b == 0.0) {
+        pds_panico("Módulo por zero", arquivo, linha, coluna);
+    }
+    return fmod(a, b);
+}
+
+double pds_resto_real(double a, double b) {
+    return fmod(a, b);
+}
+
+/* ------------------------------------------------------------------------ */
+/* Conversão para texto                                                      */
+/* ------------------------------------------------------------------------ */
+
+static char *novo_texto(size_t tamanho) {
+    char *texto = (char *)pds_gc_alocar_atomico(tamanho + 1);
+    texto[tamanho] = '\0';
+    return texto;
+}
+
+static const char *copiar_texto(const char *origem, size_t tamanho) {
+    char *texto = novo_texto(tamanho);
+    memcpy(texto, origem, tamanho);
+    return texto;
+}
+
+const char *pds_texto(const char *texto) {
+    return texto == NULL ? "nulo" : texto;
+}
+
+const char *pds_texto_inteiro(int64_t valor) {
+    char buffer[32];
+    int tamanho = snprintf(buffer, sizeof buffer, "%lld", (long long)valor);
+    return copiar_texto(buffer, (size_t)tamanho);
+}
+
+/* Como o `{:.6}` do interpretador: seis casas decimais, "NaN" e "inf". */
+const char *pds_texto_duplo(double valor) {
+    char buffer[512];
+    int tamanho;
+    if (isnan(valor)) {
+        return "NaN";
+    }
+    if (isinf(valor)) {
+        return valor > 0 ? "inf" : "-inf";
+    }
+    tamanho = snprintf(buffer, sizeof buffer, "%.6f", valor);
+    return copiar_texto(buffer, (size_t)tamanho);
+}
+
+const char *pds_texto_booleano(bool valor) {
+    return valor ? "verdadeiro" : "falso";
+}
+
+const char *pds_texto_objeto(const void *objeto) {
+    const PdsClasse *classe;
+    if (objeto == NULL) {
+        return "nulo";
+    }
+    classe = ((const PdsObjeto *)objeto)->classe;
+    if (classe->texto != NULL) {
+        return classe->texto((const PdsObjeto *)objeto);
+    }
+    return pds_concatenar(3, "Objeto<", classe->nome, ">");
+}
+
+static const char *texto_elemento(const PdsArray *array, int64_t i) {
+    switch (array->elemento) {
+    case PDS_ELEMENTO_INTEIRO:
+        return pds_texto_inteiro(PDS_ITENS(array, int64_t)[i]);
+    case PDS_ELEMENTO_FLUTUANTE:
+        return pds_texto_duplo((double)PDS_ITENS(array, float)[i]);
+    case PDS_ELEMENTO_DUPLO:
+        return pds_texto_duplo(PDS_ITENS(array, double)[i]);
+    case PDS_ELEMENTO_BOOLEANO:
+        return pds_texto_booleano(PDS_ITENS(array, bool)[i]);
+    case PDS_ELEMENTO_TEXTO:
+        return pds_texto(PDS_ITENS(array, const char *)[i]);
+    default:
+        return pds_texto_objeto(PDS_ITENS(array, PdsObjeto *)[i]);
+    }
+}
+
+static const char *texto_array(const PdsObjeto *objeto) {
+    const PdsArray *array = (const PdsArray *)objeto;
+    const char *texto = "[";
+    int64_t i;
+    for (i = 0; i < array->tamanho; i++) {
+        texto = pds_concatenar(3, texto, i == 0 ? "" : ", ", texto_elemento(array, i));
+    }
+    return pds_concatenar(2, texto, "]");
+}
+
+/* ------------------------------------------------------------------------ */
+/* Textos                                                                    */
+/* ------------------------------------------------------------------------ */
+
+const char *pds_concatenar(int quantidade, ...) {
+    va_list partes;
+    size_t tamanho = 0;
+    char *texto;
+    char *destino;
+    int i;
+
+    va_start(partes, quantidade);
+    for (i = 0; i < quantidade; i++) {
+        tamanho += strlen(pds_texto(va_arg(partes, const char *)));
+    }
+    va_end(partes);
+
+    texto = novo_texto(tamanho);
+    destino = texto;
+    va_start(partes, quantidade);
+    for (i = 0; i < quantidade; i++) {
+        const char *parte = pds_texto(va_arg(partes, const char *));
+        size_t n = strlen(parte);
+        memcpy(destino, parte, n);
+        destino += n;
+    }
+    va_end(partes);
+    return texto;
+}
+
+bool pds_textos_iguais(const char *a, const char *b) {
+    if (a == NULL || b == NULL) {
+        return a == b;
+    }
+    return strcmp(a, b) == 0;
+}
+
+int pds_comparar_textos(const char *a, const char *b) {
+    return strcmp(a == NULL ? "" : a, b == NULL ? "" : b);
+}
+
+/* Bytes de continuação UTF-8 (10xxxxxx) não iniciam um caractere. */
+static bool inicia_caractere(char byte) {
+    return ((unsigned char)byte & 0xC0) != 0x80;
+}
+
+int64_t pds_texto_tamanho(const char *texto) {
+    int64_t tamanho = 0;
+    for (; texto != NULL && *texto != '\0'; texto++) {
+        if (inicia_caractere(*texto)) {
+            tamanho++;
+        }
+    }
+    return tamanho;
+}
+
+const char *pds_texto_caractere(const char *texto, int64_t indice, const char *arquivo, int linha, int coluna) {
+    const char *inicio;
+    const char *fim;
+    int64_t atual = 0;
+    if (texto == NULL) {
+        pds_panico("Acesso por índice requer array ou texto e um inteiro", arquivo, linha, coluna);
+    }
+    if (indice < 0) {
+        pds_panico("Índice negativo", arquivo, linha, coluna);
+    }
+    for (inicio = texto; *inicio != '\0'; inicio++) {
+        if (inicia_caractere(*inicio) && atual++ == indice) {
+            break;
+        }
+    }
+    if (*inicio == '\0') {
+        pds_panico("Índice fora do intervalo", arquivo, linha, coluna);
+    }
+    for (fim = inicio + 1; *fim != '\0' && !inicia_caractere(*fim); fim++) {
+    }
+    return copiar_texto(inicio, (size_t)(fim - inicio));
+}
+
+/* Maiúsculas e minúsculas: ASCII e o bloco Latin-1 (à..þ, 2 bytes em UTF-8). */
+static const char *trocar_caixa(const char *texto, bool maiuscula) {
+    size_t tamanho = strlen(pds_texto(texto));
+    char *resultado = (char *)copiar_texto(pds_texto(texto), tamanho);
+    size_t i;
+    for (i = 0; i < tamanho; i++) {
+        unsigned char c = (unsigned char)resultado[i];
+        if (maiuscula && c >= 'a' && c <= 'z') {
+            resultado[i] = (char)(c - 'a' + 'A');
+        } else if (!maiuscula && c >= 'A' && c <= 'Z') {
+            resultado[i] = (char)(c - 'A' + 'a');
+        } else if (c == 0xC3 && i + 1 < tamanho) {
+            unsigned char segundo = (unsigned char)resultado[i + 1];
+            /* U+00E0..U+00FE ↔ U+00C0..U+00DE, exceto ÷ (U+00F7) e × (U+00D7) */
+            if (maiuscula && segundo >= 0xA0 && segundo <= 0xBE && segundo != 0xB7) {
+                resultado[i + 1] = (char)(segundo - 0x20);
+            } else if (!maiuscula && segundo >= 0x80 && segundo <= 0x9E && segundo != 0x97) {
+                resultado[i + 1] = (char)(segundo + 0x20);
+            }
+            i++;
+        }
+    }
+    return resultado;
+}
+
+const char *pds_texto_maiusculo(const char *texto) {
+    return trocar_caixa(texto, true);
+}
+
+const char *pds_texto_minusculo(const char *texto) {
+    return trocar_caixa(texto, false);
+}
+
+static bool espaco(char c) {
+    return c == ' ' || c == '\t' || c == '\n' || c == '\r' || c == '\v' || c == '\f';
+}
+
+const char *pds_texto_aparar(const char *texto) {
+    const char *inicio = pds_texto(texto);
+    const char *fim = inicio + strlen(inicio);
+    while (inicio < fim && espaco(*inicio)) {
+        inicio++;
+    }
+    while (fim > inicio && espaco(fim[-1])) {
+        fim--;
+    }
+    return copiar_texto(inicio, (size_t)(fim - inicio));
+}
+
+bool pds_texto_contem(const char *texto, const char *parte) {
+    return strstr(pds_texto(texto), pds_texto(parte)) != NULL;
+}
+
+const char *pds_texto_substituir(const char *texto, const char *de, const char *para) {
+    const char *resultado = "";
+    const char *atual = pds_texto(texto);
+    const char *encontrado;
+    size_t tamanho_de;
+    de = pds_texto(de);
+    para = pds_texto(para);
+    tamanho_de = strlen(de);
+    /* Como str::replace com padrão vazio: insere `para` entre cada caractere */
+    if (tamanho_de == 0) {
+        resultado = para;
+        while (*atual != '\0') {
+            const char *fim = atual + 1;
+            while (*fim != '\0' && !inicia_caractere(*fim)) {
+                fim++;
+            }
+            resultado = pds_concatenar(3, resultado, copiar_texto(atual, (size_t)(fim - atual)), para);
+            atual = fim;
+        }
+        return resultado;
+    }
+    while ((encontrado = strstr(atual, de)) != NULL) {
+        resultado = pds_concatenar(3, resultado, copiar_texto(atual, (size_t)(encontrado - atual)), para);
+        atual = encontrado + tamanho_de;
+    }
+    return pds_concatenar(2, resultado, atual);
+}
+
+/* ------------------------------------------------------------------------ */
+/* Caixas                                                                    */
+/* ------------------------------------------------------------------------ */
+
+typedef struct Caixa {
+    PdsObjeto base;
+    union {
+        int64_t inteiro;
+        float flutuante;
+        double duplo;
+        bool booleano;
+        const char *texto;
+    } valor;
+} Caixa;
+
+static const char *texto_caixa_inteiro(const PdsObjeto *objeto) {
+    return pds_texto_inteiro(((const Caixa *)objeto)->valor.inteiro);
+}
+static const char *texto_caixa_flutuante(const PdsObjeto *objeto) {
+    return pds_texto_duplo((double)((const Caixa *)objeto)->valor.flutuante);
+}
+static const char *texto_caixa_duplo(const PdsObjeto *objeto) {
+    return pds_texto_duplo(((const Caixa *)objeto)->valor.duplo);
+}
+static const char *texto_caixa_booleano(const PdsObjeto *objeto) {
+    return pds_texto_booleano(((const Caixa *)objeto)->valor.booleano);
+}
+static const char *texto_caixa_texto(const PdsObjeto *objeto) {
+    return pds_texto(((const Caixa *)objeto)->valor.texto);
+}
+
+static const PdsClasse classe_inteiro = {"inteiro", NULL, texto_caixa_inteiro};
+static const PdsClasse classe_flutuante = {"flutuante", NULL, texto_caixa_flutuante};
+static const PdsClasse classe_duplo = {"duplo", NULL, texto_caixa_duplo};
+static const PdsClasse classe_booleano = {"booleano", NULL, texto_caixa_booleano};
+static const PdsClasse classe_texto = {"texto", NULL, texto_caixa_texto};
+
+static Caixa *nova_caixa(const PdsClasse *classe) {
+    /* Só a caixa de texto guarda um ponteiro para o heap */
+    Caixa *caixa = (Caixa *)(classe == &classe_texto ? pds_gc_alocar(sizeof(Caixa))
+                                                     : pds_gc_alocar_atomico(sizeof(Caixa)));
+    caixa->base.classe = classe;
+    return caixa;
+}
+
+PdsObjeto *pds_caixa_inteiro(int64_t valor) {
+    Caixa *caixa = nova_caixa(&classe_inteiro);
+    caixa->valor.inteiro = valor;
+    return &caixa->base;
+}
+
+PdsObjeto *pds_caixa_flutuante(float valor) {
+    Caixa *caixa = nova_caixa(&classe_flutuante);
+    caixa->valor.flutuante = valor;
+    return &caixa->base;
+}
+
+PdsObjeto *pds_caixa_duplo(double valor) {
+    Caixa *caixa = nova_caixa(&classe_duplo);
+    caixa->valor.duplo = valor;
+    return &caixa->base;
+}
+
+PdsObjeto *pds_caixa_booleano(bool valor) {
+    Caixa *caixa = nova_caixa(&classe_booleano);
+    caixa->valor.booleano = valor;
+    return &caixa->base;
+}
+
+PdsObjeto *pds_caixa_texto(const char *valor) {
+    Caixa *caixa;
+    if (valor == NULL) {
+        return NULL;
+    }
+    caixa = nova_caixa(&classe_texto);
+    caixa->valor.texto = valor;
+    return &caixa->base;
+}
+
+static const Caixa *abrir_caixa(const PdsObjeto *objeto, const PdsClasse *classe) {
+    if (objeto == NULL || objeto->classe != classe) {
+        pds_panico(pds_concatenar(3, "Conversão inválida para ", classe->nome,
+                                  objeto == NULL ? ": valor nulo" : pds_concatenar(2, ": ", objeto->classe->nome)),
+                   NULL, 0, 0);
+    }
+    return (const Caixa *)objeto;
+}
+
+int64_t pds_inteiro_de(const PdsObjeto *objeto) {
+    return abrir_caixa(objeto, &classe_inteiro)->valor.inteiro;
+}
+
+float pds_flutuante_de(const PdsObjeto *objeto) {
+    return abrir_caixa(objeto, &classe_flutuante)->valor.flutuante;
+}
+
+double pds_duplo_de(const PdsObjeto *objeto) {
+    return abrir_caixa(objeto, &classe_duplo)->valor.duplo;
+}
+
+bool pds_booleano_de(const PdsObjeto *objeto) {
+    return abrir_caixa(objeto, &classe_booleano)->valor.booleano;
+}
+
+const char *pds_texto_de(const PdsObjeto *objeto) {
+    if (objeto == NULL) {
+        return NULL;
+    }
+    return abrir_caixa(objeto, &classe_texto)->valor.texto;
+}
+
+bool pds_objetos_iguais(const void *a, const void *b) {
+    const PdsObjeto *x = (const PdsObjeto *)a;
+    const PdsObjeto *y = (const PdsObjeto *)b;
+    const Caixa *cx;
+    const Caixa *cy;
+    if (x == y) {
+        return true;
+    }
+    if (x == NULL || y == NULL || x->classe != y->classe) {
+        return false;
+    }
+    cx = (const Caixa *)x;
+    cy = (const Caixa *)y;
+    if (x->classe == &classe_inteiro) {
+        return cx->valor.inteiro == cy->valor.inteiro;
+    }
+    if (x->classe == &classe_flutuante) {
+        return cx->valor.flutuante == cy->valor.flutuante;
+    }
+    if (x->classe == &classe_duplo) {
+        return cx->valor.duplo == cy->valor.duplo;
+    }
+    if (x->classe == &classe_booleano) {
+        return cx->valor.booleano == cy->valor.booleano;
+    }
+    if (x->classe == &classe_texto) {
+        return strcmp(cx->valor.texto, cy->valor.texto) == 0;
+    }
+    return false;
+}
+
+/* ------------------------------------------------------------------------ */
+/* Console                                                                   */
+/* ------------------------------------------------------------------------ */
+
+void pds_imprimir(const char *texto) {
+    fputs(pds_texto(texto), stdout);
+    fputc('\n', stdout);
+}
+
+void pds_escrever(const char *texto) {
+    fputs(pds_texto(texto), stdout);
+    fflush(stdout);
+}
+
+const char *pds_ler_linha(void) {
+    const char *linha = "";
+    char buffer[256];
+    fflush(stdout);
+    while (fgets(buffer, sizeof buffer, stdin) != NULL) {
+        size_t tamanho = strlen(buffer);
+        bool fim = tamanho > 0 && buffer[tamanho - 1] == '\n';
+        while (tamanho > 0 && (buffer[tamanho - 1] == '\n' || buffer[tamanho - 1] == '\r')) {
+            tamanho--;
+        }
+        linha = pds_concatenar(2, linha, copiar_texto(buffer, tamanho));
+        if (fim) {
+            break;
+        }
+    }
+    return linha;
+}
+
+/* ------------------------------------------------------------------------ */
+/* Arquivos                                                                  */
+/* ------------------------------------------------------------------------ */
+
+static void falha_arquivo(const char *operacao, const char *caminho, const char *arquivo, int linha, int coluna) {
+    pds_panico(pds_concatenar(5, operacao, ": ", strerror(errno), ": ", caminho), arquivo, linha, coluna);
+}
+
+const char *pds_arquivo_ler(const char *caminho, const char *arquivo, int linha, int coluna) {
+    FILE *entrada = fopen(pds_texto(caminho), "rb");
+    const char *conteudo = "";
+    char buffer[4096];
+    size_t lidos;
+    if (entrada == NULL) {
+        falha_arquivo("Arquivo::LerTexto", pds_texto(caminho), arquivo, linha, coluna);
+    }
+    while ((lidos = fread(buffer, 1, sizeof buffer, entrada)) > 0) {
+        conteudo = pds_concatenar(2, conteudo, copiar_texto(buffer, lidos));
+    }
+    fclose(entrada);
+    return conteudo;
+}
+
+void pds_arquivo_escrever(const char *caminho, const char *conteudo, bool adicionar, const char *arquivo, int linha, int coluna) {
+    FILE *saida = fopen(pds_texto(caminho), adicionar ? "ab" : "wb");
+    const char *operacao = adicionar ? "Arquivo::AdicionarTexto" : "Arquivo::EscreverTexto";
+    if (saida == NULL) {
+        falha_arquivo(operacao, pds_texto(caminho), arquivo, linha, coluna);
+    }
+    fputs(pds_texto(conteudo), saida);
+    fclose(saida);
+}
+
+bool pds_arquivo_existe(const char *caminho) {
+    FILE *entrada = fopen(pds_texto(caminho), "rb");
+    if (entrada == NULL) {
+        return false;
+    }
+    fclose(entrada);
+    return true;
+}
+
+void pds_arquivo_excluir(const char *caminho, const char *arquivo, int linha, int coluna) {
+    if (remove(pds_texto(caminho)) != 0) {
+        falha_arquivo("Arquivo::Excluir", pds_texto(caminho), arquivo, linha, coluna);
+    }
+}
+
+const char *pds_aguardar_leitura(Task *tarefa, const char *arquivo, int linha, int coluna) {
+    const char *conteudo = (const char *)task_await(tarefa);
+    if (conteudo == NULL) {
+        pds_panico("LerArquivoAssíncrono: não foi possível ler o arquivo", arquivo, linha, coluna);
+    }
+    return conteudo;
+}
+
+/* Como no interpretador, a escrita concluída resulta em nulo. */
+PdsObjeto *pds_aguardar_escrita(Task *tarefa, const char *arquivo, int linha, int coluna) {
+    if (task_await(tarefa) == NULL) {
+        pds_panico("EscreverArquivoAssíncrono: não foi possível gravar o arquivo", arquivo, linha, coluna);
+    }
+    return NULL;
+}
+
+bool pds_aguardar_existencia(Task *tarefa) {
+    return task_await(tarefa) != NULL;
+}
@@ -0,0 +1,125 @@
+/* Runtime do alvo C (--target=c): objetos, arrays, textos, console e verificações do código gerado.
+ * A memória vem do coletor de gc_runtime.c e as operações assíncronas de async_runtime.c. */
+#ifndef POR_DO_SOL_C_RUNTIME_H
+#define POR_DO_SOL_C_RUNTIME_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#include "async_runtime.h"
+#include "gc_runtime.h"
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct PdsObjeto PdsObjeto;
+
+/* Descritor de classe. A vtable gerada para cada classe começa com um PdsClasse (diretamente ou
+ * dentro da vtable da classe pai), então o descritor de qualquer objeto pode ser lido como
+ * PdsClasse e como a vtable de qualquer um de seus ancestrais. */
+typedef struct PdsClasse {
+    /* Nome qualificado (Espaco.Classe). */
+    const char *nome;
+    const struct PdsClasse *pai;
+    /* Texto exibido por imprima; NULL exibe "Objeto<nome>". */
+    const char *(*texto)(const PdsObjeto *objeto);
+} PdsClasse;
+
+/* Cabeçalho de todo objeto: as structs das classes começam com ele (ou com a struct da classe pai). */
+struct PdsObjeto {
+    const PdsClasse *classe;
+};
+
+typedef enum PdsElemento {
+    PDS_ELEMENTO_INTEIRO,
+    PDS_ELEMENTO_FLUTUANTE,
+    PDS_ELEMENTO_DUPLO,
+    PDS_ELEMENTO_BOOLEANO,
+    PDS_ELEMENTO_TEXTO,
+    PDS_ELEMENTO_OBJETO
+} PdsElemento;
+
+/* Arrays também são objetos (podem ser guardados em variáveis do tipo objeto). Os itens ficam no
+ * mesmo bloco, logo após o cabeçalho. */
+typedef struct PdsArray {
+    PdsObjeto base;
+    int64_t tamanho;
+    PdsElemento elemento;
+    void *itens;
+} PdsArray;
+
+#define PDS_ITENS(array, tipo) ((tipo *)(array)->itens)
+
+/* Arrays. `arquivo`, `linha` e `coluna` são a posição informada a pds_panico quando a
+ * verificação falha (arquivo NULL quando desconhecida). */
+PdsArray *pds_array_novo(int64_t tamanho, PdsElemento elemento, const char *arquivo, int linha, int coluna);
+/* Literal `[a, b, ...]`: os itens seguem como argumentos variádicos do tipo do elemento
+ * (int64_t, double, bool, const char * ou ponteiro de objeto). */
+PdsArray *pds_array_de(int quantidade, PdsElemento elemento, ...);
+int64_t pds_indice(const PdsArray *array, int64_t indice, const char *arquivo, int linha, int coluna);
+void pds_verificar_nulo(const void *referencia, const char *mensagem, const char *arquivo, int linha, int coluna);
+
+/* Divisão e resto com a semântica da linguagem: inteiros com aritmética modular, erro em
+ * divisão por zero (também para reais). As variantes _real não verificam o divisor. */
+int64_t pds_dividir(int64_t a, int64_t b, const char *arquivo, int linha, int coluna);
+int64_t pds_resto(int64_t a, int64_t b, const char *arquivo, int linha, int coluna);
+double pds_dividir_duplo(double a, double b, const char *arquivo, int linha, int coluna);
+double pds_resto_duplo(double a, double b, const char *arquivo, int linha, int coluna);
+double pds_resto_real(double a, double b);
+
+/* Conversão para texto, como imprima exibe cada valor. Textos NULL são "nulo". */
+const char *pds_texto(const char *texto);
+const char *pds_texto_inteiro(int64_t valor);
+const char *pds_texto_duplo(double valor);
+const char *pds_texto_booleano(bool valor);
+const char *pds_texto_objeto(const void *objeto);
+
+/* Textos (UTF-8, imutáveis; os resultados são alocados no coletor). */
+const char *pds_concatenar(int quantidade, ...);
+bool pds_textos_iguais(const char *a, const char *b);
+int pds_comparar_textos(const char *a, const char *b);
+int64_t pds_texto_tamanho(const char *texto);
+const char *pds_texto_caractere(const char *texto, int64_t indice, const char *arquivo, int linha, int coluna);
+const char *pds_texto_maiusculo(const char *texto);
+const char *pds_texto_minusculo(const char *texto);
+const char *pds_texto_aparar(const char *texto);
+bool pds_texto_contem(const char *texto, const char *parte);
+const char *pds_texto_substituir(const char *texto, const char *de, const char *para);
+
+/* Valores primitivos guardados em variáveis do tipo objeto (ou em parâmetros genéricos). */
+PdsObjeto *pds_caixa_inteiro(int64_t valor);
+PdsObjeto *pds_caixa_flutuante(float valor);
+PdsObjeto *pds_caixa_duplo(double valor);
+PdsObjeto *pds_caixa_booleano(bool valor);
+PdsObjeto *pds_caixa_texto(const char *valor);
+int64_t pds_inteiro_de(const PdsObjeto *objeto);
+float pds_flutuante_de(const PdsObjeto *objeto);
+double pds_duplo_de(const PdsObjeto *objeto);
+bool pds_booleano_de(const PdsObjeto *objeto);
+const char *pds_texto_de(const PdsObjeto *objeto);
+/* Igualdade de `==` entre objetos: caixas comparam o valor; objetos e arrays, a referência. */
+bool pds_objetos_iguais(const void *a, const void *b);
+
+/* Console. */
+void pds_imprimir(const char *texto);
+void pds_escrever(const char *texto);
+const char *pds_ler_linha(void);
+
+/* Arquivos (classe nativa Arquivo). Falhas terminam o programa como no interpretador. */
+const char *pds_arquivo_ler(const char *caminho, const char *arquivo, int linha, int coluna);
+void pds_arquivo_escrever(const char *caminho, const char *conteudo, bool adicionar, const char *arquivo, int linha, int coluna);
+bool pds_arquivo_existe(const char *caminho);
+void pds_arquivo_excluir(const char *caminho, const char *arquivo, int linha, int coluna);
+
+/* Resultado das tarefas nativas de async_runtime.c (LerArquivoAssíncrono e afins). */
+const char *pds_aguardar_leitura(Task *tarefa, const char *arquivo, int linha, int coluna);
+PdsObjeto *pds_aguardar_escrita(Task *tarefa, const char *arquivo, int linha, int coluna);
+bool pds_aguardar_existencia(Task *tarefa);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,182 @@
+// Runtime do alvo Console (.NET): exibição dos valores e erros em tempo de execução como no
+// interpretador de AST. O compilador copia este arquivo para o projeto gerado como PorDoSol.cs.
+using System;
+using System.Collections;
+using System.Globalization;
+using System.Text;
+using System.Threading;
+
+namespace PorDoSol
+{
+    /// <summary>Erro em tempo de execução da linguagem.</summary>
+    public sealed class ErroExecucao : Exception
+    {
+        public ErroExecucao(string mensagem) : base(mensagem)
+        {
+        }
+    }
+
+    public static class Runtime
+    {
+        /// <summary>
+        /// Executa o programa: erros não tratados são exibidos como no interpretador
+        /// ("Erro em tempo de execução: ...") e terminam com código 1.
+        /// </summary>
+        public static int Executar(Action programa)
+        {
+            CultureInfo.DefaultThreadCurrentCulture = CultureInfo.InvariantCulture;
+            Thread.CurrentThread.CurrentCulture = CultureInfo.InvariantCulture;
+            Console.OutputEncoding = new UTF8Encoding(false);
+            try
+            {
+                programa();
+                Console.Out.Flush();
+                return 0;
+            }
+            catch (Exception erro)
+            {
+                Console.Out.Flush();
+                Console.Error.WriteLine("Erro em tempo de execução: " + Mensagem(erro));
+                return 1;
+            }
+        }
+
+        public static string Mensagem(Exception erro)
+        {
+            switch (erro)
+            {
+                case AggregateException agregada when agregada.InnerException != null:
+                    return Mensagem(agregada.InnerException);
+                case ErroExecucao:
+                    return erro.Message;
+                case DivideByZeroException:
+                    return "Divisão por zero";
+                case IndexOutOfRangeException:
+                case ArgumentOutOfRangeException:
+                    return "Índice fora do intervalo";
+                case NullReferenceException:
+                    return "Referência nula";
+                case InvalidCastException:
+                    return "Conversão inválida";
+                default:
+                    return erro.Message;
+            }
+        }
+
+        /// <summary>Texto exibido por imprima e pela concatenação com textos.</summary>
+        public static string Texto(object valor)
+        {
+            switch (valor)
+            {
+                case null:
+                    return "nulo";
+                case string texto:
+                    return texto;
+                case bool booleano:
+                    return booleano ? "verdadeiro" : "falso";
+                case float real:
+                    return Real(real);
+                case double real:
+                    return Real(real);
+                case decimal numero:
+                    return numero.ToString(CultureInfo.InvariantCulture);
+                case Enum enumeracao:
+                    return Convert.ToInt64(enumeracao).ToString(CultureInfo.InvariantCulture);
+                case IFormattable numero:
+                    return numero.ToString(null, CultureInfo.InvariantCulture);
+                case IEnumerable itens:
+                    var partes = new StringBuilder("[");
+                    var primeiro = true;
+                    foreach (var item in itens)
+                    {
+                        if (!primeiro)
+                        {
+                            partes.Append(", ");
+                        }
+                        partes.Append(Texto(item));
+                        primeiro = false;
+                    }
+                    return partes.Append(']').ToString();
+                default:
+                    // Objetos sem campo Nome (que redefine ToString) exibem a classe
+                    var metodo = valor.GetType().GetMethod("ToString", Type.EmptyTypes);
+                    if (metodo == null || metodo.DeclaringType == typeof(object))
+                    {
+                        return "Objeto<" + valor.GetType().FullName + ">";
+                    }
+                    return valor.ToString();
+            }
+        }
+
+        /// <summary>Reais com 6 casas decimais, como o printf("%f").</summary>
+        public static string Real(double valor)
+        {
+            if (double.IsNaN(valor))
+            {
+                return "NaN";
+            }
+            if (double.IsInfinity(valor))
+            {
+                return valor > 0 ? "inf" : "-inf";
+            }
+            return valor.ToString("F6", CultureInfo.InvariantCulture);
+        }
+
+        /// <summary>Valores de tipos diferentes nunca são iguais; objetos comparam a referência.</summary>
+        public static bool Iguais(object a, object b)
+        {
+            return Equals(a, b);
+        }
+
+        /// <summary>Resto da divisão inteira; o divisor zero tem a mensagem do interpretador.</summary>
+        public static long Resto(long a, long b)
+        {
+            if (b == 0)
+            {
+                throw new ErroExecucao("Módulo por zero");
+            }
+            return a % b;
+        }
+
+        /// <summary>Substitui todas as ocorrências; o texto antigo vazio insere o novo entre os caracteres.</summary>
+        public static string Substituir(string texto, string antigo, string novo)
+        {
+            if (antigo.Length > 0)
+            {
+                return texto.Replace(antigo, novo, StringComparison.Ordinal);
+            }
+            var saida = new StringBuilder(novo);
+            foreach (var caractere in texto)
+            {
+                saida.Append(caractere).Append(novo);
+            }
+            return saida.ToString();
+        }
+
+        /// <summary>Tamanho do texto em bytes UTF-8, como no interpretador.</summary>
+        public static long Comprimento(string texto)
+        {
+            return Encoding.UTF8.GetByteCount(texto);
+        }
+
+        /// <summary>Linha lida da entrada padrão, sem a quebra (texto vazio no fim da entrada).</summary>
+        public static string LerLinha()
+        {
+            return Console.ReadLine() ?? "";
+        }
+
+        /// <summary>Arrays de texto começam com textos vazios, como no interpretador.</summary>
+        public static string[] NovoArrayTexto(long tamanho)
+        {
+            var itens = new string[tamanho];
+            Array.Fill(itens, "");
+            return itens;
+        }
+
+        /// <summary>Termina o programa com um erro em tempo de execução.</summary>
+        public static T Falhar<T>(string mensagem)
+        {
+            throw new ErroExecucao(mensagem);
+        }
+    }
+}
@@ -1,585 +1,1327 @@
-// src/runtime/execution_context.rs
+// Interpretador de AST: executa um `Programa` já verificado sem gerar bytecode.
+// A semântica dos valores (exibição, igualdade, promoções numéricas e mensagens de erro)
+// acompanha a VM de bytecode, para que a saída das duas execuções possa ser comparada.
+
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+
+use super::nativos;
 use crate::ast::*;
+use crate::codegen::tabelas::{
+    aceita_argumentos, chave_nativa, erro_metodo_texto, nome_do_tipo, AlvoEstatico, Classe, Escopo,
+    Funcao, Tabelas, Variavel,
+};
+
+/// Limite de chamadas aninhadas antes de abortar com estouro de pilha.
+pub const LIMITE_CHAMADAS: usize = 10_000;
+
+/// Espaço deixado livre no fim da pilha da thread: a recursão para antes de estourá-la.
+const MARGEM_PILHA: usize = 4 * 1024 * 1024;
 
-#[derive(Clone, Debug)]
+/// Endereço aproximado do topo da pilha da thread atual (a pilha cresce para baixo).
+fn endereco_pilha() -> usize {
+    let marcador = 0u8;
+    std::hint::black_box(&marcador) as *const u8 as usize
+}
+
+#[derive(Debug, Clone)]
 pub enum ValorRuntime {
     Inteiro(i64),
+    Flutuante(f32),
+    Duplo(f64),
+    Decimal(Decimal),
     Texto(String),
     Booleano(bool),
-    Objeto {
-        classe: String,
-        propriedades: HashMap<String, ValorRuntime>,
-        metodos: HashMap<String, MetodoInfo>,
-    },
+    /// Arrays e objetos são referências: atribuir não copia o conteúdo.
+    Array(Rc<RefCell<Vec<ValorRuntime>>>),
+    Objeto(Rc<Objeto>),
     Nulo,
 }
 
-#[derive(Clone, Debug)]
-pub struct MetodoInfo {
-    pub parametros: Vec<Parametro>,
-    pub corpo: Vec<Comando>,
-    pub eh_virtual: bool,
-    pub eh_override: bool,
+#[derive(Debug)]
+pub struct Objeto {
+    pub classe: String,
+    pub campos: RefCell<HashMap<String, ValorRuntime>>,
 }
 
-#[derive(Clone, Debug)]
-pub struct ContextoExecucao {
-    // Stack de escopos para variáveis locais
-    pub escopos: Vec<HashMap<String, ValorRuntime>>,
-    // Registro de classes compiladas (compile-time)
-    pub classes_compiladas: HashMap<String, ClasseCompilada>,
-    // Cache de objetos instanciados (runtime)
-    pub cache_objetos: HashMap<String, ValorRuntime>,
-    // Pilha de chamadas para debug
-    pub pilha_chamadas: Vec<ChamadaInfo>,
-    // Sistema de captura de argumentos dinâmicos
-    pub argumentos_capturados: Vec<Vec<ValorRuntime>>,
-    // Contexto de método atual
-    pub contexto_metodo_atual: Option<String>,
-}
+impl ValorRuntime {
+    pub fn nome_tipo(&self) -> String {
+        match self {
+            ValorRuntime::Inteiro(_) => "inteiro".to_string(),
+            ValorRuntime::Flutuante(_) => "flutuante".to_string(),
+            ValorRuntime::Duplo(_) => "duplo".to_string(),
+            ValorRuntime::Decimal(_) => "decimal".to_string(),
+            ValorRuntime::Texto(_) => "texto".to_string(),
+            ValorRuntime::Booleano(_) => "booleano".to_string(),
+            ValorRuntime::Array(_) => "array".to_string(),
+            ValorRuntime::Objeto(o) => o.classe.clone(),
+            ValorRuntime::Nulo => "nulo".to_string(),
+        }
+    }
 
-#[derive(Clone, Debug)]
-pub struct ClasseCompilada {
-    pub nome: String,
-    pub classe_pai: Option<String>,
-    pub propriedades: HashMap<String, TipoPropriedade>,
-    pub metodos: HashMap<String, MetodoInfo>,
-    pub construtores: Vec<ConstrutorInfo>,
+    fn padrao(tipo: &Tipo) -> ValorRuntime {
+        match tipo {
+            Tipo::Inteiro => ValorRuntime::Inteiro(0),
+            Tipo::Flutuante => ValorRuntime::Flutuante(0.0),
+            Tipo::Duplo => ValorRuntime::Duplo(0.0),
+            Tipo::Decimal => ValorRuntime::Decimal(Decimal::ZERO),
+            Tipo::Texto => ValorRuntime::Texto(String::new()),
+            Tipo::Booleano => ValorRuntime::Booleano(false),
+            _ => ValorRuntime::Nulo,
+        }
+    }
 }
 
-#[derive(Clone, Debug)]
-pub struct TipoPropriedade {
-    pub tipo: Tipo,
-    pub valor_inicial: Option<ValorRuntime>,
-    pub eh_readonly: bool,
+impl fmt::Display for ValorRuntime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValorRuntime::Inteiro(n) => write!(f, "{}", n),
+            ValorRuntime::Flutuante(x) => write!(f, "{:.6}", *x as f64),
+            ValorRuntime::Duplo(x) => write!(f, "{:.6}", x),
+            ValorRuntime::Decimal(d) => write!(f, "{}", d),
+            ValorRuntime::Texto(s) => write!(f, "{}", s),
+            ValorRuntime::Booleano(b) => write!(f, "{}", if *b { "verdadeiro" } else { "falso" }),
+            ValorRuntime::Array(itens) => {
+                let itens = itens.borrow();
+                let s = itens
+                    .iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                write!(f, "[{}]", s)
+            }
+            ValorRuntime::Objeto(o) => match o.campos.borrow().get("Nome") {
+                Some(nome) => write!(f, "{}", nome),
+                None => write!(f, "Objeto<{}>", o.classe),
+            },
+            ValorRuntime::Nulo => write!(f, "nulo"),
+        }
+    }
 }
 
-#[derive(Clone, Debug)]
-pub struct ConstrutorInfo {
-    pub parametros: Vec<Parametro>,
-    pub corpo: Vec<Comando>,
-    pub chamada_pai: Option<Vec<Expressao>>,
+// Valores de tipos diferentes nunca são iguais; arrays e objetos comparam a referência
+impl PartialEq for ValorRuntime {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (ValorRuntime::Inteiro(a), ValorRuntime::Inteiro(b)) => a == b,
+            (ValorRuntime::Flutuante(a), ValorRuntime::Flutuante(b)) => a == b,
+            (ValorRuntime::Duplo(a), ValorRuntime::Duplo(b)) => a == b,
+            (ValorRuntime::Decimal(a), ValorRuntime::Decimal(b)) => a == b,
+            (ValorRuntime::Texto(a), ValorRuntime::Texto(b)) => a == b,
+            (ValorRuntime::Booleano(a), ValorRuntime::Booleano(b)) => a == b,
+            (ValorRuntime::Array(a), ValorRuntime::Array(b)) => Rc::ptr_eq(a, b),
+            (ValorRuntime::Objeto(a), ValorRuntime::Objeto(b)) => Rc::ptr_eq(a, b),
+            (ValorRuntime::Nulo, ValorRuntime::Nulo) => true,
+            _ => false,
+        }
+    }
 }
 
-#[derive(Clone, Debug)]
-pub struct ChamadaInfo {
-    pub funcao: String,
-    pub linha: usize,
-    pub parametros: Vec<ValorRuntime>,
+/// Registro de ativação de uma função, método, construtor ou acessor.
+struct Quadro<'a> {
+    variaveis: HashMap<String, ValorRuntime>,
+    este: Option<Rc<Objeto>>,
+    /// Classe que declara o código em execução (resolve membros estáticos e chamadas sem alvo).
+    classe: Option<&'a Classe<'a>>,
+    namespace: String,
 }
 
-impl ContextoExecucao {
-    pub fn new() -> Self {
+impl<'a> Quadro<'a> {
+    fn novo(namespace: &str) -> Self {
         Self {
-            escopos: vec![HashMap::new()], // Escopo global
-            classes_compiladas: HashMap::new(),
-            cache_objetos: HashMap::new(),
-            pilha_chamadas: Vec::new(),
-            argumentos_capturados: Vec::new(),
-            contexto_metodo_atual: None,
-        }
-    }
-
-    // COMPILE-TIME: Registrar classe após verificação de tipos
-    pub fn registrar_classe_compilada(&mut self, classe: &DeclaracaoClasse) -> Result<(), String> {
-        let mut propriedades = HashMap::new();
-        let mut metodos = HashMap::new();
-        let mut construtores = Vec::new();
-
-        // Processar propriedades
-        for prop in &classe.propriedades {
-            propriedades.insert(
-                prop.nome.clone(),
-                TipoPropriedade {
-                    tipo: prop.tipo.clone(),
-                    valor_inicial: prop.valor_inicial.as_ref()
-                        .map(|expr| self.avaliar_expressao_estatica(expr))
-                        .transpose()?,
-                    eh_readonly: false,
-                }
-            );
-        }
-
-        // Processar métodos
-        for metodo in &classe.metodos {
-            metodos.insert(
-                metodo.nome.clone(),
-                MetodoInfo {
-                    parametros: metodo.parametros.clone(),
-                    corpo: metodo.corpo.clone(),
-                    eh_virtual: metodo.eh_virtual,
-                    eh_override: metodo.eh_override,
-                }
-            );
-        }
-
-        // Processar construtores
-        for construtor in &classe.construtores {
-            construtores.push(ConstrutorInfo {
-                parametros: construtor.parametros.clone(),
-                corpo: construtor.corpo.clone(),
-                chamada_pai: construtor.chamada_pai.clone(),
-            });
-        }
-
-        let classe_compilada = ClasseCompilada {
-            nome: classe.nome.clone(),
-            classe_pai: classe
-                .classe_pai
-                .as_ref()
-                .and_then(|t| match t {
-                    Tipo::Classe(n) => Some(n.clone()),
-                    Tipo::Aplicado { nome, .. } => Some(nome.clone()),
-                    _ => None,
-                }),
-            propriedades,
-            metodos,
-            construtores,
-        };
+            variaveis: HashMap::new(),
+            este: None,
+            classe: None,
+            namespace: namespace.to_string(),
+        }
+    }
 
-        self.classes_compiladas.insert(classe.nome.clone(), classe_compilada);
-        Ok(())
+    fn de_classe(classe: &'a Classe<'a>, este: Option<Rc<Objeto>>) -> Self {
+        Self {
+            variaveis: HashMap::new(),
+            este,
+            classe: Some(classe),
+            namespace: classe.namespace.clone(),
+        }
     }
+}
 
-    // RUNTIME: Criar instância com argumentos reais dinâmicos
-    pub fn criar_instancia(&mut self, nome_classe: &str, argumentos: Vec<ValorRuntime>) -> Result<ValorRuntime, String> {
-        let classe = self.classes_compiladas.get(nome_classe)
-            .ok_or_else(|| format!("Classe '{}' não encontrada", nome_classe))?
-            .clone();
-
-        let construtor = classe.construtores.iter()
-            .find(|c| c.parametros.len() == argumentos.len())
-            .ok_or_else(|| format!("Nenhum construtor compatível para {} argumentos em {}", argumentos.len(), nome_classe))?;
-
-        let mut propriedades_runtime = HashMap::new();
-
-        // 1. Inicializar propriedades da classe pai (se houver) e mesclá-las
-        if let Some(ref classe_pai_nome) = classe.classe_pai {
-            if let Some(ref chamada_pai_args_expr) = construtor.chamada_pai {
-                let mut args_pai_avaliados = Vec::new();
-                self.entrar_escopo(); // Escopo temporário para resolver os argumentos da chamada pai
-                
-                // Disponibilizar os parâmetros do construtor filho para resolver os argumentos do pai
-                for (i, param) in construtor.parametros.iter().enumerate() {
-                    if let Some(arg) = argumentos.get(i) {
-                        self.definir_variavel(&param.nome, arg.clone());
-                    }
-                }
+enum Fluxo {
+    Normal,
+    Retorno(ValorRuntime),
+}
 
-                for expr in chamada_pai_args_expr {
-                    args_pai_avaliados.push(self.avaliar_expressao(expr)?);
-                }
-                self.sair_escopo();
+pub struct ContextoExecucao<'a> {
+    tabelas: &'a Tabelas<'a>,
+    /// Membros estáticos de cada classe, pelo nome qualificado, depois de inicializados.
+    estaticos: HashMap<String, RefCell<HashMap<String, ValorRuntime>>>,
+    quadros: Vec<Quadro<'a>>,
+    posicao: Option<Posicao>,
+    /// Endereço da pilha no início da execução e a parte dela que a recursão pode usar.
+    pilha: Option<(usize, usize)>,
+}
 
-                // Criar recursivamente a instância da classe pai e obter suas propriedades inicializadas
-                match self.criar_instancia(classe_pai_nome, args_pai_avaliados)? {
-                    ValorRuntime::Objeto { propriedades: props_pai, .. } => {
-                        propriedades_runtime.extend(props_pai);
-                    }
-                    _ => return Err(format!("A classe pai '{}' não retornou um objeto.", classe_pai_nome)),
-                }
-            }
+impl<'a> Escopo<'a, ValorRuntime> for ContextoExecucao<'a> {
+    fn tabelas(&self) -> &Tabelas<'a> {
+        self.tabelas
+    }
+
+    fn namespace(&self) -> &str {
+        &self.quadro().namespace
+    }
+
+    fn classe_atual(&self) -> Option<&str> {
+        self.quadro().classe.map(|c| c.fqn.as_str())
+    }
+
+    fn tem_este(&self) -> bool {
+        self.quadro().este.is_some()
+    }
+
+    fn local(&self, nome: &str) -> Option<ValorRuntime> {
+        self.quadro().variaveis.get(nome).cloned()
+    }
+}
+
+impl<'a> ContextoExecucao<'a> {
+    pub fn new(tabelas: &'a Tabelas<'a>) -> Self {
+        Self {
+            tabelas,
+            estaticos: tabelas
+                .classes
+                .keys()
+                .map(|fqn| (fqn.clone(), RefCell::default()))
+                .collect(),
+            quadros: Vec::new(),
+            posicao: None,
+            pilha: None,
         }
+    }
 
-        // 2. Inicializar propriedades da classe atual com valores padrão ou definidos
-        for (nome_prop, tipo_prop) in &classe.propriedades {
-            let valor = tipo_prop.valor_inicial.clone().unwrap_or_else(|| self.valor_padrao_para_tipo(&tipo_prop.tipo));
-            propriedades_runtime.insert(nome_prop.clone(), valor);
+    /// Limita a recursão do programa à pilha da thread atual, de `tamanho` bytes: chamadas que
+    /// passariam dela viram erro de execução em vez de derrubar o processo.
+    pub fn limitar_pilha(&mut self, tamanho: usize) {
+        self.pilha = Some((endereco_pilha(), tamanho.saturating_sub(MARGEM_PILHA)));
+    }
+
+    fn verificar_profundidade(&self) -> Result<(), String> {
+        if self.quadros.len() >= LIMITE_CHAMADAS {
+            return Err(format!(
+                "Estouro de pilha: mais de {} chamadas aninhadas",
+                LIMITE_CHAMADAS
+            ));
         }
-        
-        // 3. Mapear argumentos do construtor atual para as propriedades (sobrescrevendo se necessário)
-        for (i, param) in construtor.parametros.iter().enumerate() {
-            if let Some(arg) = argumentos.get(i) {
-                // Apenas mapeia se a propriedade existe na classe (ou hierarquia)
-                if propriedades_runtime.contains_key(&param.nome) {
-                    propriedades_runtime.insert(param.nome.clone(), arg.clone());
-                }
+        if let Some((base, disponivel)) = self.pilha {
+            if base.saturating_sub(endereco_pilha()) > disponivel {
+                return Err(format!(
+                    "Estouro de pilha: {} chamadas aninhadas esgotaram a pilha do interpretador",
+                    self.quadros.len()
+                ));
             }
         }
+        Ok(())
+    }
 
-        // 4. Coletar todos os métodos da hierarquia
-        let mut metodos_completos = HashMap::new();
-        self.coletar_metodos_hierarquia(nome_classe, &mut metodos_completos)?;
+    fn classe(&self, fqn: &str) -> &'a Classe<'a> {
+        let tabelas = self.tabelas;
+        &tabelas.classes[fqn]
+    }
 
-        let mut objeto = ValorRuntime::Objeto {
-            classe: nome_classe.to_string(),
-            propriedades: propriedades_runtime,
-            metodos: metodos_completos,
-        };
+    fn resolver_classe(&self, nome: &str) -> Option<&'a Classe<'a>> {
+        self.tabelas
+            .resolver_classe(&self.quadro().namespace, nome)
+            .map(|fqn| self.classe(&fqn))
+    }
 
-        // 5. Executar o corpo do construtor
-        self.entrar_escopo();
-        self.definir_variavel("este", objeto);
-        for (i, param) in construtor.parametros.iter().enumerate() {
-            if let Some(arg) = argumentos.get(i) {
-                self.definir_variavel(&param.nome, arg.clone());
+    fn pai(&self, classe: &Classe) -> Option<&'a Classe<'a>> {
+        classe.pai.as_ref().map(|p| self.classe(p))
+    }
+
+    /// A classe e seus ancestrais, da base para a derivada.
+    fn hierarquia(&self, classe: &Classe) -> Vec<&'a Classe<'a>> {
+        let tabelas = self.tabelas;
+        let mut cadeia = tabelas.hierarquia(&classe.fqn);
+        cadeia.reverse();
+        cadeia
+    }
+
+    pub fn posicao(&self) -> Option<&Posicao> {
+        self.posicao.as_ref()
+    }
+
+    fn quadro(&self) -> &Quadro<'a> {
+        self.quadros.last().expect("nenhum quadro ativo")
+    }
+
+    fn quadro_mut(&mut self) -> &mut Quadro<'a> {
+        self.quadros.last_mut().expect("nenhum quadro ativo")
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* EXECUÇÃO DO PROGRAMA                                                */
+    /* ------------------------------------------------------------------ */
+
+    /// Inicializa os membros estáticos, executa o código de nível superior e depois `Principal`.
+    pub fn executar(&mut self) -> Result<(), String> {
+        let tabelas = self.tabelas;
+        for nome in &tabelas.ordem_classes {
+            let classe = self.classe(nome);
+            self.quadros.push(Quadro::de_classe(classe, None));
+            let membros = classe
+                .decl
+                .campos
+                .iter()
+                .filter(|c| c.eh_estatica)
+                .map(|c| (&c.nome, &c.valor_inicial))
+                .chain(
+                    classe
+                        .decl
+                        .propriedades
+                        .iter()
+                        .filter(|p| p.eh_estatica)
+                        .map(|p| (&p.nome, &p.valor_inicial)),
+                );
+            for (nome, inicial) in membros {
+                let valor = match inicial {
+                    Some(expr) => self.avaliar(expr)?,
+                    None => ValorRuntime::Nulo,
+                };
+                self.estaticos[&classe.fqn]
+                    .borrow_mut()
+                    .insert(nome.clone(), valor);
             }
+            self.quadros.pop();
         }
 
-        for comando in &construtor.corpo {
-            self.executar_comando(comando)?;
+        self.quadros.push(Quadro::novo(""));
+        for (namespace, cmd) in &tabelas.globais {
+            self.quadro_mut().namespace = namespace.clone();
+            if let Fluxo::Retorno(_) = self.executar_comando(cmd)? {
+                break;
+            }
         }
+        self.quadros.pop();
+
+        let principal = tabelas
+            .ordem_funcoes
+            .iter()
+            .find(|n| *n == "Principal" || *n == "principal" || n.ends_with(".Principal"))
+            .cloned();
+        if let Some(nome) = principal {
+            self.chamar(&nome, Vec::new())?;
+        }
+        Ok(())
+    }
 
-        // Obter o estado final do objeto 'este'
-        let objeto_final = self.obter_variavel("este").unwrap();
-        self.sair_escopo();
+    /// Chama uma função pelo nome qualificado.
+    pub fn chamar(&mut self, nome: &str, args: Vec<ValorRuntime>) -> Result<ValorRuntime, String> {
+        let funcao = self
+            .tabelas
+            .funcoes
+            .get(nome)
+            .ok_or_else(|| format!("Função '{}' não encontrada", nome))?;
+        self.chamar_funcao(funcao, args)
+    }
 
-        Ok(objeto_final)
+    fn chamar_funcao(
+        &mut self,
+        funcao: &Funcao<'a>,
+        args: Vec<ValorRuntime>,
+    ) -> Result<ValorRuntime, String> {
+        if let Some(chave) = chave_nativa(&funcao.decl.attributes) {
+            return nativos::chamar(chave, args);
+        }
+        if funcao.decl.attributes.iter().any(|a| a.name == "Externo") {
+            return Err(format!(
+                "Função externa '{}' só pode ser chamada no executável LLVM (--target=llvm-ir)",
+                funcao.decl.nome
+            ));
+        }
+        self.invocar(
+            Quadro::novo(&funcao.namespace),
+            &funcao.decl.parametros,
+            args,
+            &funcao.decl.corpo,
+        )
     }
 
-    // NOVO: Coleta e inicializa propriedades de toda a hierarquia com valores padrão
-    fn coletar_e_inicializar_propriedades_hierarquia(&self, nome_classe: &str, propriedades: &mut HashMap<String, ValorRuntime>) -> Result<(), String> {
-        if let Some(classe) = self.classes_compiladas.get(nome_classe) {
-            // Primeiro, coletar propriedades da classe pai (recursivamente)
-            if let Some(classe_pai) = &classe.classe_pai {
-                self.coletar_e_inicializar_propriedades_hierarquia(classe_pai, propriedades)?;
+    fn invocar(
+        &mut self,
+        quadro: Quadro<'a>,
+        parametros: &[Parametro],
+        args: Vec<ValorRuntime>,
+        corpo: &[Comando],
+    ) -> Result<ValorRuntime, String> {
+        self.verificar_profundidade()?;
+        let posicao_chamada = self.posicao.clone();
+        self.quadros.push(quadro);
+        let resultado = self.executar_corpo(parametros, args, corpo);
+        self.quadros.pop();
+        // Em caso de erro a posição fica no ponto da falha
+        if resultado.is_ok() {
+            self.posicao = posicao_chamada;
+        }
+        resultado
+    }
+
+    fn executar_corpo(
+        &mut self,
+        parametros: &[Parametro],
+        args: Vec<ValorRuntime>,
+        corpo: &[Comando],
+    ) -> Result<ValorRuntime, String> {
+        let mut args = args.into_iter();
+        for param in parametros {
+            let valor = match (args.next(), &param.valor_padrao) {
+                (Some(valor), _) => valor,
+                (None, Some(padrao)) => self.avaliar(padrao)?,
+                (None, None) => ValorRuntime::Nulo,
+            };
+            self.quadro_mut()
+                .variaveis
+                .insert(param.nome.clone(), valor);
+        }
+        match self.executar_bloco(corpo)? {
+            Fluxo::Retorno(valor) => Ok(valor),
+            Fluxo::Normal => Ok(ValorRuntime::Nulo),
+        }
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* CLASSES E OBJETOS                                                   */
+    /* ------------------------------------------------------------------ */
+
+    fn instanciar(
+        &mut self,
+        classe: &'a Classe<'a>,
+        args: Vec<ValorRuntime>,
+    ) -> Result<ValorRuntime, String> {
+        if classe.decl.eh_abstrata {
+            return Err(format!(
+                "Não é possível instanciar a classe abstrata '{}'",
+                classe.fqn
+            ));
+        }
+        if classe.decl.eh_estatica {
+            return Err(format!(
+                "Não é possível instanciar a classe estática '{}'",
+                classe.fqn
+            ));
+        }
+        let objeto = Rc::new(Objeto {
+            classe: classe.fqn.clone(),
+            campos: RefCell::new(HashMap::new()),
+        });
+        let hierarquia = self.hierarquia(classe);
+        for c in &hierarquia {
+            let mut campos = objeto.campos.borrow_mut();
+            for campo in c.decl.campos.iter().filter(|c| !c.eh_estatica) {
+                campos.insert(campo.nome.clone(), ValorRuntime::Nulo);
+            }
+            for prop in c.decl.propriedades.iter().filter(|p| !p.eh_estatica) {
+                campos.insert(prop.nome.clone(), ValorRuntime::Nulo);
             }
+        }
+        // Inicializadores rodam antes dos construtores, da base para a derivada
+        for c in &hierarquia {
+            self.quadros
+                .push(Quadro::de_classe(c, Some(objeto.clone())));
+            let iniciais = c
+                .decl
+                .campos
+                .iter()
+                .filter(|c| !c.eh_estatica)
+                .filter_map(|c| c.valor_inicial.as_ref().map(|e| (&c.nome, e)))
+                .chain(
+                    c.decl
+                        .propriedades
+                        .iter()
+                        .filter(|p| !p.eh_estatica)
+                        .filter_map(|p| p.valor_inicial.as_ref().map(|e| (&p.nome, e))),
+                );
+            for (nome, expr) in iniciais {
+                let valor = match self.avaliar(expr) {
+                    Ok(valor) => valor,
+                    Err(e) => {
+                        self.quadros.pop();
+                        return Err(e);
+                    }
+                };
+                objeto.campos.borrow_mut().insert(nome.clone(), valor);
+            }
+            self.quadros.pop();
+        }
+        self.construir(classe, &objeto, args)?;
+        Ok(ValorRuntime::Objeto(objeto))
+    }
 
-            // Em seguida, adicionar/sobrescrever propriedades da classe atual
-            for (nome_prop, tipo_prop) in &classe.propriedades {
-                let valor = tipo_prop.valor_inicial.clone().unwrap_or_else(|| self.valor_padrao_para_tipo(&tipo_prop.tipo));
-                propriedades.insert(nome_prop.clone(), valor);
+    fn construir(
+        &mut self,
+        classe: &'a Classe<'a>,
+        objeto: &Rc<Objeto>,
+        args: Vec<ValorRuntime>,
+    ) -> Result<(), String> {
+        let construtores = &classe.decl.construtores;
+        let escolhido = construtores
+            .iter()
+            .find(|c| aceita_argumentos(&c.parametros, args.len()))
+            .or(construtores.first());
+        let Some(construtor) = escolhido else {
+            // Sem construtor declarado: apenas o construtor padrão da classe pai
+            if let Some(pai) = self.pai(classe) {
+                self.construir(pai, objeto, Vec::new())?;
             }
+            return Ok(());
+        };
+
+        self.verificar_profundidade()?;
+        let posicao_chamada = self.posicao.clone();
+        self.quadros
+            .push(Quadro::de_classe(classe, Some(objeto.clone())));
+        let resultado = self.executar_construtor(classe, construtor, objeto, args);
+        self.quadros.pop();
+        if resultado.is_ok() {
+            self.posicao = posicao_chamada;
+        }
+        resultado
+    }
+
+    fn executar_construtor(
+        &mut self,
+        classe: &'a Classe<'a>,
+        construtor: &ConstrutorClasse,
+        objeto: &Rc<Objeto>,
+        args: Vec<ValorRuntime>,
+    ) -> Result<(), String> {
+        // Os parâmetros ficam visíveis para os argumentos de `: base(...)`
+        self.executar_corpo(&construtor.parametros, args, &[])?;
+        if let Some(pai) = self.pai(classe) {
+            let args_pai = match &construtor.chamada_pai {
+                Some(exprs) => self.avaliar_lista(exprs)?,
+                None => Vec::new(),
+            };
+            self.construir(pai, objeto, args_pai)?;
+        } else if construtor.chamada_pai.is_some() {
+            return Err(format!(
+                "'{}' chama base(...) mas não tem classe pai",
+                classe.fqn
+            ));
         }
+        self.executar_bloco(&construtor.corpo)?;
         Ok(())
     }
 
-    // RUNTIME: Avaliação dinâmica de expressões
-    pub fn avaliar_expressao(&mut self, expr: &Expressao) -> Result<ValorRuntime, String> {
-        match expr {
-            Expressao::Inteiro(n) => Ok(ValorRuntime::Inteiro(*n)),
-            Expressao::Texto(s) => Ok(ValorRuntime::Texto(s.clone())),
-            Expressao::Booleano(b) => Ok(ValorRuntime::Booleano(*b)),
-            Expressao::Nulo => Ok(ValorRuntime::Nulo),
+    /// Procura o método na classe e nos ancestrais (a classe mais derivada vence).
+    fn buscar_metodo(
+        &self,
+        classe: &Classe,
+        nome: &str,
+        n_args: usize,
+    ) -> Option<(&'a Classe<'a>, &'a MetodoClasse)> {
+        let tabelas = self.tabelas;
+        let (dono, metodo) = tabelas.buscar_metodo(&classe.fqn, nome, n_args)?;
+        Some((self.classe(&dono), metodo))
+    }
 
-            Expressao::Identificador(nome) => {
-                self.obter_variavel(nome)
-                    .ok_or_else(|| format!("Variável '{}' não encontrada", nome))
-            }
+    fn buscar_propriedade(
+        &self,
+        classe: &Classe,
+        nome: &str,
+    ) -> Option<(&'a Classe<'a>, &'a PropriedadeClasse)> {
+        let tabelas = self.tabelas;
+        let (dono, propriedade) = tabelas.buscar_propriedade(&classe.fqn, nome)?;
+        Some((self.classe(&dono), propriedade))
+    }
 
-            Expressao::Este => {
-                self.obter_variavel("este")
-                    .ok_or_else(|| "Referência 'este' não disponível neste contexto".to_string())
+    fn chamar_metodo(
+        &mut self,
+        objeto: &Rc<Objeto>,
+        nome: &str,
+        args: Vec<ValorRuntime>,
+    ) -> Result<ValorRuntime, String> {
+        let classe = self.classe(&objeto.classe);
+        let (dono, metodo) = self
+            .buscar_metodo(classe, nome, args.len())
+            .ok_or_else(|| {
+                format!(
+                    "Método '{}' não encontrado na classe '{}'",
+                    nome, objeto.classe
+                )
+            })?;
+        if let Some(chave) = chave_nativa(&metodo.attributes) {
+            let mut com_este = vec![ValorRuntime::Objeto(objeto.clone())];
+            if metodo.eh_estatica {
+                com_este.clear();
             }
+            com_este.extend(args);
+            return nativos::chamar(chave, com_este);
+        }
+        if metodo.eh_abstrato {
+            return Err(format!(
+                "Método abstrato '{}' não implementado em '{}'",
+                nome, objeto.classe
+            ));
+        }
+        let este = (!metodo.eh_estatica).then(|| objeto.clone());
+        self.invocar(
+            Quadro::de_classe(dono, este),
+            &metodo.parametros,
+            args,
+            &metodo.corpo,
+        )
+    }
 
-            Expressao::AcessoMembro(obj_expr, membro) => {
-                let objeto = self.avaliar_expressao(obj_expr)?;
-                match objeto {
-                    ValorRuntime::Objeto { propriedades, .. } => {
-                        propriedades.get(membro)
-                            .cloned()
-                            .ok_or_else(|| format!("Propriedade '{}' não encontrada", membro))
-                    }
-                    _ => Err(format!("Tentativa de acessar membro '{}' em não-objeto", membro))
-                }
-            }
+    fn chamar_estatico(
+        &mut self,
+        classe: &Classe,
+        nome: &str,
+        args: Vec<ValorRuntime>,
+    ) -> Result<ValorRuntime, String> {
+        let (dono, metodo) = self
+            .buscar_metodo(classe, nome, args.len())
+            .ok_or_else(|| {
+                format!(
+                    "Método '{}' não encontrado na classe '{}'",
+                    nome, classe.fqn
+                )
+            })?;
+        if let Some(chave) = chave_nativa(&metodo.attributes) {
+            return nativos::chamar(chave, args);
+        }
+        self.invocar(
+            Quadro::de_classe(dono, None),
+            &metodo.parametros,
+            args,
+            &metodo.corpo,
+        )
+    }
 
-            Expressao::NovoObjeto(tipo, args) => {
-                let classe = match tipo {
-                    Tipo::Classe(n) => n.clone(),
-                    Tipo::Aplicado { nome, .. } => nome.clone(),
-                    _ => return Err(format!("Instanciação de tipo não suportado na VM: {:?}", tipo)),
-                };
-                let mut argumentos_avaliados = Vec::new();
-                for arg in args {
-                    argumentos_avaliados.push(self.avaliar_expressao(arg)?);
-                }
-                self.criar_instancia(&classe, argumentos_avaliados)
-            }
-            Expressao::NovoArray(tipo, _tamanho) => {
-                Err(format!("Criação de array não suportada na VM para o tipo: {:?}", tipo))
+    fn ler_membro(&mut self, objeto: &Rc<Objeto>, membro: &str) -> Result<ValorRuntime, String> {
+        let classe = self.classe(&objeto.classe);
+        if let Some((dono, propriedade)) = self.buscar_propriedade(classe, membro) {
+            if let Some(corpo) = propriedade.obter.as_ref().and_then(|a| a.corpo.as_ref()) {
+                return self.invocar(
+                    Quadro::de_classe(dono, Some(objeto.clone())),
+                    &[],
+                    Vec::new(),
+                    corpo,
+                );
             }
+        }
+        objeto.campos.borrow().get(membro).cloned().ok_or_else(|| {
+            format!(
+                "Membro '{}' não encontrado na classe '{}'",
+                membro, objeto.classe
+            )
+        })
+    }
 
-            Expressao::Aritmetica(op, esq, dir) => {
-                let val_esq = self.avaliar_expressao(esq)?;
-                let val_dir = self.avaliar_expressao(dir)?;
-                self.executar_operacao_aritmetica(op, val_esq, val_dir)
+    fn escrever_membro(
+        &mut self,
+        objeto: &Rc<Objeto>,
+        membro: &str,
+        valor: ValorRuntime,
+    ) -> Result<(), String> {
+        let classe = self.classe(&objeto.classe);
+        if let Some((dono, propriedade)) = self.buscar_propriedade(classe, membro) {
+            if let Some(corpo) = propriedade.definir.as_ref().and_then(|a| a.corpo.as_ref()) {
+                // O acessor `definir` recebe o novo valor na variável implícita `valor`
+                let mut quadro = Quadro::de_classe(dono, Some(objeto.clone()));
+                quadro.variaveis.insert("valor".to_string(), valor);
+                self.invocar(quadro, &[], Vec::new(), corpo)?;
+                return Ok(());
             }
+        }
+        objeto.campos.borrow_mut().insert(membro.to_string(), valor);
+        Ok(())
+    }
 
-            Expressao::ChamadaMetodo(obj_expr, metodo, args) => {
-                let objeto = self.avaliar_expressao(obj_expr)?;
-                let mut argumentos = Vec::new();
-                for arg in args {
-                    argumentos.push(self.avaliar_expressao(arg)?);
-                }
-                self.chamar_metodo(objeto, metodo, argumentos)
+    /// Classe (ou ancestral) que declara o membro estático `nome`, se ele já foi inicializado.
+    fn dono_estatico(&self, classe: &Classe, nome: &str) -> Option<&'a Classe<'a>> {
+        let tabelas = self.tabelas;
+        let dono = tabelas.buscar_membro(&classe.fqn, nome, true)?.classe;
+        self.estaticos[&dono.fqn]
+            .borrow()
+            .contains_key(nome)
+            .then_some(dono)
+    }
+
+    /* ------------------------------------------------------------------ */
+    /* VARIÁVEIS                                                           */
+    /* ------------------------------------------------------------------ */
+
+    fn ler_variavel(&self, nome: &str) -> Option<ValorRuntime> {
+        match self.variavel(nome)? {
+            Variavel::Local(valor) => Some(valor),
+            Variavel::Campo(_) => {
+                let este = self.quadro().este.as_ref()?;
+                let valor = este.campos.borrow().get(nome).cloned();
+                valor
+            }
+            Variavel::Estatico(membro) => {
+                let valor = self.estaticos[&membro.classe.fqn]
+                    .borrow()
+                    .get(nome)
+                    .cloned();
+                valor
             }
+        }
+    }
 
-            Expressao::StringInterpolada(partes) => {
-                let mut resultado = String::new();
-                for parte in partes {
-                    match parte {
-                        PartStringInterpolada::Texto(texto) => {
-                            resultado.push_str(texto);
-                        }
-                        PartStringInterpolada::Expressao(expr) => {
-                            let valor = self.avaliar_expressao(expr)?;
-                            resultado.push_str(&self.valor_para_string(&valor));
-                        }
-                    }
+    fn atribuir_variavel(&mut self, nome: &str, valor: ValorRuntime) {
+        let valor = match self.variavel(nome) {
+            Some(Variavel::Campo(_)) => {
+                if let Some(este) = &self.quadro().este {
+                    este.campos.borrow_mut().insert(nome.to_string(), valor);
                 }
-                Ok(ValorRuntime::Texto(resultado))
+                return;
             }
-
-            _ => Err("Expressão não implementada".to_string())
-        }
+            Some(Variavel::Estatico(membro)) => {
+                self.estaticos[&membro.classe.fqn]
+                    .borrow_mut()
+                    .insert(nome.to_string(), valor);
+                return;
+            }
+            // Atribuição a um nome desconhecido cria a variável
+            _ => valor,
+        };
+        self.quadro_mut().variaveis.insert(nome.to_string(), valor);
     }
 
-    // COMPILE-TIME: Avaliação estática para valores iniciais
-    fn avaliar_expressao_estatica(&self, expr: &Expressao) -> Result<ValorRuntime, String> {
-        match expr {
-            Expressao::Inteiro(n) => Ok(ValorRuntime::Inteiro(*n)),
-            Expressao::Texto(s) => Ok(ValorRuntime::Texto(s.clone())),
-            Expressao::Booleano(b) => Ok(ValorRuntime::Booleano(*b)),
-            _ => Err("Apenas valores literais são permitidos como valores iniciais".to_string())
+    /* ------------------------------------------------------------------ */
+    /* COMANDOS                                                            */
+    /* ------------------------------------------------------------------ */
+
+    fn executar_bloco(&mut self, comandos: &[Comando]) -> Result<Fluxo, String> {
+        for cmd in comandos {
+            if let Fluxo::Retorno(valor) = self.executar_comando(cmd)? {
+                return Ok(Fluxo::Retorno(valor));
+            }
         }
+        Ok(Fluxo::Normal)
     }
 
-    // **CORRIGIDO**: Métodos auxiliares agora públicos
-    fn executar_operacao_aritmetica(&self, op: &OperadorAritmetico, esq: ValorRuntime, dir: ValorRuntime) -> Result<ValorRuntime, String> {
-        match op {
-            OperadorAritmetico::Soma => {
-                match (&esq, &dir) {
-                    (ValorRuntime::Inteiro(a), ValorRuntime::Inteiro(b)) => Ok(ValorRuntime::Inteiro(a + b)),
-                    _ => {
-                        let str_esq = self.valor_para_string(&esq);
-                        let str_dir = self.valor_para_string(&dir);
-                        Ok(ValorRuntime::Texto(format!("{}{}", str_esq, str_dir)))
+    fn executar_comando(&mut self, comando: &Comando) -> Result<Fluxo, String> {
+        match comando {
+            Comando::Posicao(posicao) => self.posicao = Some(posicao.clone()),
+            Comando::DeclaracaoVariavel(tipo, nome, inicial) => {
+                let valor = match inicial {
+                    Some(expr) => self.avaliar(expr)?,
+                    None => ValorRuntime::padrao(tipo),
+                };
+                self.quadro_mut().variaveis.insert(nome.clone(), valor);
+            }
+            Comando::DeclaracaoVar(nome, expr) => {
+                let valor = self.avaliar(expr)?;
+                self.quadro_mut().variaveis.insert(nome.clone(), valor);
+            }
+            Comando::Atribuicao(nome, expr) => {
+                let valor = self.avaliar(expr)?;
+                self.atribuir_variavel(nome, valor);
+            }
+            Comando::AtribuirPropriedade(alvo, membro, expr)
+            | Comando::AtribuirCampo(alvo, membro, expr) => {
+                if let Some(estatico) = self.alvo_estatico(alvo) {
+                    let valor = self.avaliar(expr)?;
+                    let AlvoEstatico::Classe(classe) = estatico else {
+                        return Err(format!("Não é possível atribuir a '{}'", membro));
+                    };
+                    let classe = self.classe(&classe);
+                    let dono = self.dono_estatico(classe, membro).unwrap_or(classe);
+                    self.estaticos[&dono.fqn]
+                        .borrow_mut()
+                        .insert(membro.clone(), valor);
+                } else {
+                    let objeto = self.avaliar(alvo)?;
+                    let valor = self.avaliar(expr)?;
+                    match objeto {
+                        ValorRuntime::Objeto(objeto) => {
+                            self.escrever_membro(&objeto, membro, valor)?
+                        }
+                        ValorRuntime::Nulo => {
+                            return Err(format!("Atribuição a '{}' em referência nula", membro))
+                        }
+                        outro => {
+                            return Err(format!(
+                                "Não é possível atribuir '{}' em um valor do tipo {}",
+                                membro,
+                                outro.nome_tipo()
+                            ))
+                        }
                     }
                 }
             }
-            OperadorAritmetico::Subtracao => {
-                match (&esq, &dir) {
-                    (ValorRuntime::Inteiro(a), ValorRuntime::Inteiro(b)) => Ok(ValorRuntime::Inteiro(a - b)),
-                    _ => Err("Subtração só é válida entre inteiros".to_string())
+            Comando::AtribuirIndice(alvo, indice, expr) => {
+                let alvo = self.avaliar(alvo)?;
+                let indice = self.avaliar(indice)?;
+                let valor = self.avaliar(expr)?;
+                match (alvo, indice) {
+                    (ValorRuntime::Array(itens), ValorRuntime::Inteiro(i)) => {
+                        let mut itens = itens.borrow_mut();
+                        let i = Self::verificar_indice(i, itens.len())?;
+                        itens[i] = valor;
+                    }
+                    _ => return Err("Atribuição por índice requer array e inteiro".to_string()),
                 }
             }
-            OperadorAritmetico::Multiplicacao => {
-                match (&esq, &dir) {
-                    (ValorRuntime::Inteiro(a), ValorRuntime::Inteiro(b)) => Ok(ValorRuntime::Inteiro(a * b)),
-                    _ => Err("Multiplicação só é válida entre inteiros".to_string())
-                }
+            Comando::Imprima(expr) => {
+                let valor = self.avaliar(expr)?;
+                println!("{}", valor);
             }
-            OperadorAritmetico::Divisao => {
-                match (&esq, &dir) {
-                    (ValorRuntime::Inteiro(a), ValorRuntime::Inteiro(b)) if *b != 0 => Ok(ValorRuntime::Inteiro(a / b)),
-                    _ => Err("Divisão inválida ou por zero".to_string())
+            Comando::Se(condicao, entao, senao) => {
+                if self.condicao(condicao)? {
+                    return self.executar_comando(entao);
+                } else if let Some(senao) = senao {
+                    return self.executar_comando(senao);
                 }
             }
-            OperadorAritmetico::Modulo => {
-                match (&esq, &dir) {
-                    (ValorRuntime::Inteiro(a), ValorRuntime::Inteiro(b)) if *b != 0 => Ok(ValorRuntime::Inteiro(a % b)),
-                    _ => Err("Módulo inválido ou por zero".to_string())
+            Comando::Enquanto(condicao, corpo) => {
+                while self.condicao(condicao)? {
+                    if let Fluxo::Retorno(valor) = self.executar_comando(corpo)? {
+                        return Ok(Fluxo::Retorno(valor));
+                    }
                 }
             }
-        }
-    }
-
-    fn chamar_metodo(&mut self, mut objeto: ValorRuntime, nome_metodo: &str, argumentos: Vec<ValorRuntime>) -> Result<ValorRuntime, String> {
-        if let ValorRuntime::Objeto { ref metodos, .. } = objeto {
-            if let Some(metodo_info) = metodos.get(nome_metodo).cloned() {
-                // Entrar em novo escopo para o método
-                self.entrar_escopo();
-                self.contexto_metodo_atual = Some(nome_metodo.to_string());
-                self.definir_variavel("este", objeto.clone());
-                
-                // Definir parâmetros
-                for (i, param) in metodo_info.parametros.iter().enumerate() {
-                    if let Some(arg) = argumentos.get(i) {
-                        self.definir_variavel(&param.nome, arg.clone());
-                    }
+            Comando::Para(inicio, condicao, incremento, corpo) => {
+                if let Some(inicio) = inicio {
+                    self.executar_comando(inicio)?;
                 }
-
-                // Executar corpo do método
-                let mut resultado = ValorRuntime::Nulo;
-                for comando in &metodo_info.corpo {
-                    if let Ok(val) = self.executar_comando(comando) {
-                        if let Some(ret) = val {
-                            resultado = ret;
+                loop {
+                    if let Some(condicao) = condicao {
+                        if !self.condicao(condicao)? {
                             break;
                         }
                     }
+                    if let Fluxo::Retorno(valor) = self.executar_comando(corpo)? {
+                        return Ok(Fluxo::Retorno(valor));
+                    }
+                    if let Some(incremento) = incremento {
+                        self.executar_comando(incremento)?;
+                    }
                 }
-
-                // Atualizar objeto se foi modificado
-                if let Some(objeto_modificado) = self.obter_variavel("este") {
-                   objeto = objeto_modificado.clone();
-                   return Ok(objeto);
-                }
-
-                self.contexto_metodo_atual = None;
-                self.sair_escopo();
-                Ok(resultado)
-            } else {
-                Err(format!("Método '{}' não encontrado", nome_metodo))
             }
-        } else {
-            Err("Tentativa de chamar método em não-objeto".to_string())
+            Comando::Bloco(comandos) => return self.executar_bloco(comandos),
+            Comando::Retorne(expr) => {
+                let valor = match expr {
+                    Some(expr) => self.avaliar(expr)?,
+                    None => ValorRuntime::Nulo,
+                };
+                return Ok(Fluxo::Retorno(valor));
+            }
+            Comando::Expressao(expr) => {
+                self.avaliar(expr)?;
+            }
+            Comando::CriarObjeto(variavel, classe, args) => {
+                let valor = self.avaliar(&Expressao::NovoObjeto(
+                    Tipo::Classe(classe.clone()),
+                    args.clone(),
+                ))?;
+                self.quadro_mut().variaveis.insert(variavel.clone(), valor);
+            }
+            Comando::ChamarMetodo(alvo, metodo, args) => {
+                self.chamar_membro(alvo, metodo, args)?;
+            }
+            Comando::AcessarCampo(variavel, campo) => {
+                let alvo = Expressao::Identificador(variavel.clone());
+                self.avaliar(&Expressao::AcessoMembro(Box::new(alvo), campo.clone()))?;
+            }
         }
+        Ok(Fluxo::Normal)
     }
 
-    fn coletar_metodos_hierarquia(&self, nome_classe: &str, metodos: &mut HashMap<String, MetodoInfo>) -> Result<(), String> {
-        if let Some(classe) = self.classes_compiladas.get(nome_classe) {
-            // Coletar métodos da classe pai primeiro
-            if let Some(classe_pai) = &classe.classe_pai {
-                self.coletar_metodos_hierarquia(classe_pai, metodos)?;
-            }
+    fn condicao(&mut self, expr: &Expressao) -> Result<bool, String> {
+        match self.avaliar(expr)? {
+            ValorRuntime::Booleano(b) => Ok(b),
+            outro => Err(format!(
+                "Condição deve ser booleana, obtido {}",
+                outro.nome_tipo()
+            )),
+        }
+    }
 
-            // Adicionar/sobrescrever métodos da classe atual
-            for (nome, metodo) in &classe.metodos {
-                metodos.insert(nome.clone(), metodo.clone());
-            }
+    fn verificar_indice(i: i64, tamanho: usize) -> Result<usize, String> {
+        if i < 0 {
+            return Err("Índice negativo".to_string());
         }
-        Ok(())
+        let i = i as usize;
+        if i >= tamanho {
+            return Err("Índice fora do intervalo".to_string());
+        }
+        Ok(i)
     }
 
-    // **NOVO**: Coleta propriedades de toda a hierarquia
-    fn coletar_propriedades_hierarquia(&self, nome_classe: &str, propriedades: &mut HashMap<String, TipoPropriedade>) -> Result<(), String> {
-        if let Some(classe) = self.classes_compiladas.get(nome_classe) {
-            // Coletar propriedades da classe pai primeiro (base)
-            if let Some(classe_pai) = &classe.classe_pai {
-                self.coletar_propriedades_hierarquia(classe_pai, propriedades)?;
-            }
+    /* ------------------------------------------------------------------ */
+    /* EXPRESSÕES                                                          */
+    /* ------------------------------------------------------------------ */
 
-            // Adicionar/sobrescrever propriedades da classe atual
-            for (nome, prop) in &classe.propriedades {
-                propriedades.insert(nome.clone(), prop.clone());
-            }
-        }
-        Ok(())
+    fn avaliar_lista(&mut self, exprs: &[Expressao]) -> Result<Vec<ValorRuntime>, String> {
+        exprs.iter().map(|e| self.avaliar(e)).collect()
     }
 
-    // **CORRIGIDO**: Método público para execução de comandos
-    pub fn executar_comando(&mut self, comando: &Comando) -> Result<Option<ValorRuntime>, String> {
-        match comando {
-            Comando::Imprima(expr) => {
-                let valor = self.avaliar_expressao(expr)?;
-                println!("{}", self.valor_para_string(&valor));
-                Ok(None)
+    pub fn avaliar(&mut self, expr: &Expressao) -> Result<ValorRuntime, String> {
+        match expr {
+            Expressao::Inteiro(n) => Ok(ValorRuntime::Inteiro(*n)),
+            Expressao::Texto(s) => Ok(ValorRuntime::Texto(s.clone())),
+            Expressao::Booleano(b) => Ok(ValorRuntime::Booleano(*b)),
+            Expressao::Decimal(lit) => Decimal::from_str(lit.trim_end_matches(['m', 'M']))
+                .map(ValorRuntime::Decimal)
+                .map_err(|e| format!("Decimal inválido '{}': {}", lit, e)),
+            Expressao::FlutuanteLiteral(lit) => lit
+                .trim_end_matches(['f', 'F'])
+                .parse()
+                .map(ValorRuntime::Flutuante)
+                .map_err(|e| format!("Flutuante inválido '{}': {}", lit, e)),
+            Expressao::DuploLiteral(lit) => lit
+                .parse()
+                .map(ValorRuntime::Duplo)
+                .map_err(|e| format!("Duplo inválido '{}': {}", lit, e)),
+            Expressao::Nulo => Ok(ValorRuntime::Nulo),
+            Expressao::Identificador(nome) => self
+                .ler_variavel(nome)
+                .ok_or_else(|| format!("Variável '{}' não definida", nome)),
+            Expressao::Este => self
+                .quadro()
+                .este
+                .clone()
+                .map(ValorRuntime::Objeto)
+                .ok_or_else(|| "'este' usado fora de um método de instância".to_string()),
+            Expressao::ListaLiteral(itens) => {
+                let itens = self.avaliar_lista(itens)?;
+                Ok(ValorRuntime::Array(Rc::new(RefCell::new(itens))))
             }
-
-            Comando::AtribuirPropriedade(obj_nome, prop, expr) => {
-                let valor = self.avaliar_expressao(expr)?;
-                
-                if let Some(objeto) = self.obter_variavel_mut(obj_nome) {
-                    if let ValorRuntime::Objeto { ref mut propriedades, .. } = objeto {
-                        propriedades.insert(prop.clone(), valor);
-                    }
-                }
-                Ok(None)
+            Expressao::NovoArray(tipo, tamanho) => match self.avaliar(tamanho)? {
+                ValorRuntime::Inteiro(n) if n >= 0 => Ok(ValorRuntime::Array(Rc::new(
+                    RefCell::new(vec![ValorRuntime::padrao(tipo); n as usize]),
+                ))),
+                ValorRuntime::Inteiro(_) => Err("Tamanho de array negativo".to_string()),
+                outro => Err(format!(
+                    "Tamanho de array deve ser inteiro, obtido {}",
+                    outro.nome_tipo()
+                )),
+            },
+            Expressao::NovoObjeto(tipo, args) => {
+                let nome = nome_do_tipo(tipo)
+                    .ok_or_else(|| format!("Não é possível instanciar o tipo '{}'", tipo))?;
+                let classe = self
+                    .resolver_classe(nome)
+                    .ok_or_else(|| format!("Classe '{}' não encontrada", nome))?;
+                let args = self.avaliar_lista(args)?;
+                self.instanciar(classe, args)
             }
-
-            Comando::DeclaracaoVar(nome, expr) => {
-                let valor = self.avaliar_expressao(expr)?;
-                self.definir_variavel(nome, valor);
-                Ok(None)
+            Expressao::Aritmetica(op, esq, dir) => {
+                let esq = self.avaliar(esq)?;
+                let dir = self.avaliar(dir)?;
+                aritmetica(op, esq, dir)
             }
-
-            Comando::Atribuicao(nome, expr) => {
-                let valor = self.avaliar_expressao(expr)?;
-                self.definir_variavel(nome, valor);
-                Ok(None)
+            Expressao::Comparacao(op, esq, dir) => {
+                let esq = self.avaliar(esq)?;
+                let dir = self.avaliar(dir)?;
+                comparacao(op, &esq, &dir).map(ValorRuntime::Booleano)
             }
-
-            Comando::Retorne(expr_opt) => {
-                if let Some(expr) = expr_opt {
-                    Ok(Some(self.avaliar_expressao(expr)?))
-                } else {
-                    Ok(Some(ValorRuntime::Nulo))
+            Expressao::Logica(op, esq, dir) => {
+                let esq = self.condicao(esq)?;
+                let resultado = match op {
+                    OperadorLogico::E => esq && self.condicao(dir)?,
+                    OperadorLogico::Ou => esq || self.condicao(dir)?,
+                };
+                Ok(ValorRuntime::Booleano(resultado))
+            }
+            Expressao::Unario(op, expr) => match (op, self.avaliar(expr)?) {
+                (OperadorUnario::NegacaoLogica, ValorRuntime::Booleano(b)) => {
+                    Ok(ValorRuntime::Booleano(!b))
+                }
+                (OperadorUnario::NegacaoNumerica, ValorRuntime::Inteiro(n)) => {
+                    Ok(ValorRuntime::Inteiro(n.wrapping_neg()))
+                }
+                (OperadorUnario::NegacaoNumerica, ValorRuntime::Flutuante(x)) => {
+                    Ok(ValorRuntime::Flutuante(-x))
+                }
+                (OperadorUnario::NegacaoNumerica, ValorRuntime::Duplo(x)) => {
+                    Ok(ValorRuntime::Duplo(-x))
                 }
+                (OperadorUnario::NegacaoNumerica, ValorRuntime::Decimal(d)) => {
+                    Ok(ValorRuntime::Decimal(-d))
+                }
+                (_, outro) => Err(format!(
+                    "Operador unário incompatível com {}",
+                    outro.nome_tipo()
+                )),
+            },
+            Expressao::StringInterpolada(partes) => {
+                let mut texto = String::new();
+                for parte in partes {
+                    match parte {
+                        PartStringInterpolada::Texto(s) => texto.push_str(s),
+                        PartStringInterpolada::Expressao(e) => {
+                            texto.push_str(&self.avaliar(e)?.to_string())
+                        }
+                    }
+                }
+                Ok(ValorRuntime::Texto(texto))
             }
-
-            Comando::Bloco(comandos) => {
-                self.entrar_escopo();
-                let mut resultado = None;
-                for cmd in comandos {
-                    if let Some(ret) = self.executar_comando(cmd)? {
-                        resultado = Some(ret);
-                        break;
+            Expressao::AcessoMembro(alvo, membro) => {
+                if let Some(estatico) = self.alvo_estatico(alvo) {
+                    return match estatico {
+                        AlvoEstatico::Classe(classe) => {
+                            let dono = self
+                                .dono_estatico(self.classe(&classe), membro)
+                                .ok_or_else(|| {
+                                    format!(
+                                        "Membro estático '{}' não encontrado em '{}'",
+                                        membro, classe
+                                    )
+                                })?;
+                            let valor = self.estaticos[&dono.fqn].borrow()[membro].clone();
+                            Ok(valor)
+                        }
+                        AlvoEstatico::Enum(en) => self.tabelas.enums[&en]
+                            .valores
+                            .iter()
+                            .position(|v| v == membro)
+                            .map(|i| ValorRuntime::Inteiro(i as i64))
+                            .ok_or_else(|| format!("Valor '{}' não existe na enumeração", membro)),
+                        AlvoEstatico::Nativo(nome) => {
+                            Err(format!("Classe '{}' não encontrada", nome))
+                        }
+                    };
+                }
+                match self.avaliar(alvo)? {
+                    ValorRuntime::Objeto(objeto) => self.ler_membro(&objeto, membro),
+                    ValorRuntime::Array(itens) if membro == "tamanho" => {
+                        Ok(ValorRuntime::Inteiro(itens.borrow().len() as i64))
+                    }
+                    ValorRuntime::Texto(s) if membro == "tamanho" => {
+                        Ok(ValorRuntime::Inteiro(s.chars().count() as i64))
                     }
+                    ValorRuntime::Nulo => Err(format!("Acesso a '{}' em referência nula", membro)),
+                    outro => Err(format!(
+                        "Membro '{}' não existe para valores do tipo {}",
+                        membro,
+                        outro.nome_tipo()
+                    )),
                 }
-                self.sair_escopo();
-                Ok(resultado)
             }
-
-            _ => Ok(None)
+            Expressao::AcessoIndice(alvo, indice) => {
+                let alvo = self.avaliar(alvo)?;
+                let indice = self.avaliar(indice)?;
+                match (alvo, indice) {
+                    (ValorRuntime::Array(itens), ValorRuntime::Inteiro(i)) => {
+                        let itens = itens.borrow();
+                        let i = Self::verificar_indice(i, itens.len())?;
+                        Ok(itens[i].clone())
+                    }
+                    (ValorRuntime::Texto(s), ValorRuntime::Inteiro(i)) => {
+                        let i = Self::verificar_indice(i, s.chars().count())?;
+                        Ok(ValorRuntime::Texto(s.chars().nth(i).unwrap().to_string()))
+                    }
+                    _ => Err("Acesso por índice requer array ou texto e um inteiro".to_string()),
+                }
+            }
+            Expressao::ChamadaMetodo(alvo, metodo, args) => self.chamar_membro(alvo, metodo, args),
+            Expressao::Chamada(nome, args) => {
+                let args = self.avaliar_lista(args)?;
+                self.chamar_por_nome(nome, args)
+            }
+            // Não há tarefas concorrentes: funções assíncronas terminam antes de retornar
+            Expressao::Aguarde(expr) => self.avaliar(expr),
         }
     }
 
-    fn valor_padrao_para_tipo(&self, tipo: &Tipo) -> ValorRuntime {
-        match tipo {
-            Tipo::Inteiro => ValorRuntime::Inteiro(0),
-            Tipo::Texto => ValorRuntime::Texto("".to_string()),
-            Tipo::Booleano => ValorRuntime::Booleano(false),
-            _ => ValorRuntime::Nulo,
+    fn chamar_membro(
+        &mut self,
+        alvo: &Expressao,
+        metodo: &str,
+        args: &[Expressao],
+    ) -> Result<ValorRuntime, String> {
+        if let Some(estatico) = self.alvo_estatico(alvo) {
+            let args = self.avaliar_lista(args)?;
+            return match estatico {
+                AlvoEstatico::Classe(classe) => {
+                    self.chamar_estatico(self.classe(&classe), metodo, args)
+                }
+                AlvoEstatico::Nativo(classe) => {
+                    let chave = format!("{}::{}", classe, metodo);
+                    if nativos::existe(&chave) {
+                        nativos::chamar(&chave, args)
+                    } else {
+                        Err(format!("Classe '{}' não encontrada", classe))
+                    }
+                }
+                AlvoEstatico::Enum(_) => Err(format!("Enumerações não têm o método '{}'", metodo)),
+            };
+        }
+        let objeto = self.avaliar(alvo)?;
+        let args = self.avaliar_lista(args)?;
+        match objeto {
+            ValorRuntime::Objeto(objeto) => self.chamar_metodo(&objeto, metodo, args),
+            ValorRuntime::Texto(s) => metodo_texto(&s, metodo, &args),
+            ValorRuntime::Nulo => Err(format!("Chamada de '{}' em referência nula", metodo)),
+            outro => Err(format!(
+                "Método '{}' não existe para valores do tipo {}",
+                metodo,
+                outro.nome_tipo()
+            )),
         }
     }
 
-    fn valor_para_string(&self, valor: &ValorRuntime) -> String {
-        match valor {
-            ValorRuntime::Inteiro(n) => n.to_string(),
-            ValorRuntime::Texto(s) => s.clone(),
-            ValorRuntime::Booleano(b) => if *b { "verdadeiro" } else { "falso" }.to_string(),
-            ValorRuntime::Objeto { classe, propriedades, .. } => {
-                if let Some(nome) = propriedades.get("Nome") {
-                    self.valor_para_string(nome)
-                } else {
-                    format!("Objeto<{}>", classe)
-                }
+    // Chamada sem alvo: método da classe atual, função do programa ou intrínseca de console
+    fn chamar_por_nome(
+        &mut self,
+        nome: &str,
+        args: Vec<ValorRuntime>,
+    ) -> Result<ValorRuntime, String> {
+        let (este, classe) = {
+            let quadro = self.quadro();
+            (quadro.este.clone(), quadro.classe)
+        };
+        if let Some(este) = este {
+            let classe = self.classe(&este.classe);
+            if self.buscar_metodo(classe, nome, args.len()).is_some() {
+                return self.chamar_metodo(&este, nome, args);
             }
-            ValorRuntime::Nulo => "nulo".to_string(),
+        } else if let Some(classe) = classe {
+            if self.buscar_metodo(classe, nome, args.len()).is_some() {
+                return self.chamar_estatico(classe, nome, args);
+            }
+        }
+        let tabelas = self.tabelas;
+        if let Some(qualificado) = tabelas.resolver_funcao(&self.quadro().namespace, nome) {
+            return self.chamar_funcao(&tabelas.funcoes[&qualificado], args);
+        }
+        match nome {
+            "EscreverLinha" | "Escrever" | "LerLinha" => {
+                nativos::chamar(&format!("Console::{}", nome), args)
+            }
+            _ if nativos::existe(nome) => nativos::chamar(nome, args),
+            _ => Err(format!("Função '{}' não encontrada", nome)),
         }
     }
+}
 
-    // **CORRIGIDO**: Métodos de gerenciamento de escopo agora públicos
-    pub fn entrar_escopo(&mut self) {
-        self.escopos.push(HashMap::new());
+// Métodos embutidos de `texto` (os mesmos que a VM reconhece)
+fn metodo_texto(s: &str, metodo: &str, args: &[ValorRuntime]) -> Result<ValorRuntime, String> {
+    let arg = |i: usize| args.get(i).map(|v| v.to_string()).unwrap_or_default();
+    match metodo {
+        "comprimento" => Ok(ValorRuntime::Inteiro(s.chars().count() as i64)),
+        "ParaMaiusculo" => Ok(ValorRuntime::Texto(s.to_uppercase())),
+        "ParaMinusculo" => Ok(ValorRuntime::Texto(s.to_lowercase())),
+        "Aparar" => Ok(ValorRuntime::Texto(s.trim().to_string())),
+        "Contem" => Ok(ValorRuntime::Booleano(s.contains(arg(0).as_str()))),
+        "Substituir" => Ok(ValorRuntime::Texto(
+            s.replace(arg(0).as_str(), arg(1).as_str()),
+        )),
+        _ => Err(erro_metodo_texto(metodo)),
     }
+}
 
-    pub fn sair_escopo(&mut self) {
-        self.escopos.pop();
+/* ---------------------------------------------------------------------- */
+/* OPERADORES                                                              */
+/* ---------------------------------------------------------------------- */
+
+fn nome_operador(op: &OperadorAritmetico) -> &'static str {
+    match op {
+        OperadorAritmetico::Soma => "soma",
+        OperadorAritmetico::Subtracao => "subtração",
+        OperadorAritmetico::Multiplicacao => "multiplicação",
+        OperadorAritmetico::Divisao => "divisão",
+        OperadorAritmetico::Modulo => "módulo",
     }
+}
 
-    pub fn definir_variavel(&mut self, nome: &str, valor: ValorRuntime) {
-        if let Some(escopo_atual) = self.escopos.last_mut() {
-            escopo_atual.insert(nome.to_string(), valor);
-        }
+fn erro_zero(op: &OperadorAritmetico) -> String {
+    match op {
+        OperadorAritmetico::Modulo => "Módulo por zero".to_string(),
+        _ => "Divisão por zero".to_string(),
     }
+}
 
-    pub fn obter_variavel(&self, nome: &str) -> Option<ValorRuntime> {
-        // Buscar do escopo mais interno para o mais externo
-        for escopo in self.escopos.iter().rev() {
-            if let Some(valor) = escopo.get(nome) {
-                return Some(valor.clone());
+fn aritmetica(
+    op: &OperadorAritmetico,
+    esq: ValorRuntime,
+    dir: ValorRuntime,
+) -> Result<ValorRuntime, String> {
+    use ValorRuntime::*;
+    let divide = matches!(op, OperadorAritmetico::Divisao | OperadorAritmetico::Modulo);
+    match (esq, dir) {
+        // Texto em qualquer lado de `+` concatena
+        (Texto(a), b) if *op == OperadorAritmetico::Soma => Ok(Texto(format!("{}{}", a, b))),
+        (a, Texto(b)) if *op == OperadorAritmetico::Soma => Ok(Texto(format!("{}{}", a, b))),
+        (Inteiro(a), Inteiro(b)) => {
+            if divide && b == 0 {
+                return Err(erro_zero(op));
+            }
+            Ok(Inteiro(match op {
+                OperadorAritmetico::Soma => a.wrapping_add(b),
+                OperadorAritmetico::Subtracao => a.wrapping_sub(b),
+                OperadorAritmetico::Multiplicacao => a.wrapping_mul(b),
+                OperadorAritmetico::Divisao => a.wrapping_div(b),
+                OperadorAritmetico::Modulo => a.wrapping_rem(b),
+            }))
+        }
+        (Decimal(a), Decimal(b)) => {
+            if divide && b.is_zero() {
+                return Err(erro_zero(op));
+            }
+            Ok(Decimal(match op {
+                OperadorAritmetico::Soma => a + b,
+                OperadorAritmetico::Subtracao => a - b,
+                OperadorAritmetico::Multiplicacao => a * b,
+                OperadorAritmetico::Divisao => a / b,
+                OperadorAritmetico::Modulo => a % b,
+            }))
+        }
+        // Inteiro com flutuante resulta em flutuante; qualquer duplo promove para duplo
+        (a @ (Inteiro(_) | Flutuante(_)), b @ (Inteiro(_) | Flutuante(_))) => {
+            let (a, b) = (como_f64(&a) as f32, como_f64(&b) as f32);
+            if divide && b == 0.0 {
+                return Err(erro_zero(op));
+            }
+            Ok(Flutuante(operar_reais(op, a as f64, b as f64) as f32))
+        }
+        (
+            a @ (Inteiro(_) | Flutuante(_) | Duplo(_)),
+            b @ (Inteiro(_) | Flutuante(_) | Duplo(_)),
+        ) => {
+            let (a, b) = (como_f64(&a), como_f64(&b));
+            if divide && b == 0.0 {
+                return Err(erro_zero(op));
             }
+            Ok(Duplo(operar_reais(op, a, b)))
         }
-        None
+        (a, b) => Err(format!(
+            "Tipos incompatíveis para {}: {} e {}",
+            nome_operador(op),
+            a.nome_tipo(),
+            b.nome_tipo()
+        )),
+    }
+}
+
+fn como_f64(valor: &ValorRuntime) -> f64 {
+    match valor {
+        ValorRuntime::Inteiro(n) => *n as f64,
+        ValorRuntime::Flutuante(x) => *x as f64,
+        ValorRuntime::Duplo(x) => *x,
+        _ => f64::NAN,
     }
+}
 
-    pub fn obter_variavel_mut(&mut self, nome: &str) -> Option<&mut ValorRuntime> {
-        // Buscar do escopo mais interno para o mais externo
-        for escopo in self.escopos.iter_mut().rev() {
-            if escopo.contains_key(nome) {
-                return escopo.get_mut(nome);
+fn operar_reais(op: &OperadorAritmetico, a: f64, b: f64) -> f64 {
+    match op {
+        OperadorAritmetico::Soma => a + b,
+        OperadorAritmetico::Subtracao => a - b,
+        OperadorAritmetico::Multiplicacao => a * b,
+        OperadorAritmetico::Divisao => a / b,
+        OperadorAritmetico::Modulo => a % b,
+    }
+}
+
+fn comparacao(
+    op: &OperadorComparacao,
+    esq: &ValorRuntime,
+    dir: &ValorRuntime,
+) -> Result<bool, String> {
+    use std::cmp::Ordering;
+    use ValorRuntime::*;
+    let ordem = match op {
+        OperadorComparacao::Igual => return Ok(esq == dir),
+        OperadorComparacao::Diferente => return Ok(esq != dir),
+        _ => match (esq, dir) {
+            (Inteiro(a), Inteiro(b)) => Some(a.cmp(b)),
+            (Decimal(a), Decimal(b)) => Some(a.cmp(b)),
+            (Texto(a), Texto(b)) => Some(a.cmp(b)),
+            (Inteiro(_) | Flutuante(_) | Duplo(_), Inteiro(_) | Flutuante(_) | Duplo(_)) => {
+                como_f64(esq).partial_cmp(&como_f64(dir))
             }
-        }
-        None
+            _ => {
+                return Err(format!(
+                    "Tipos incompatíveis para comparação: {} e {}",
+                    esq.nome_tipo(),
+                    dir.nome_tipo()
+                ))
+            }
+        },
+    };
+    // NaN não é menor, maior nem igual a nada
+    let Some(ordem) = ordem else {
+        return Ok(false);
+    };
+    Ok(match op {
+        OperadorComparacao::Menor => ordem == Ordering::Less,
+        OperadorComparacao::MaiorQue => ordem == Ordering::Greater,
+        OperadorComparacao::MenorIgual => ordem != Ordering::Greater,
+        OperadorComparacao::MaiorIgual => ordem != Ordering::Less,
+        OperadorComparacao::Igual | OperadorComparacao::Diferente => unreachable!(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::Token;
+    use logos::Logos;
+
+    /// O contexto vive até o fim do teste: o programa, o verificador e as tabelas vazam.
+    fn contexto(fonte: &str) -> ContextoExecucao<'static> {
+        let tokens = Token::lexer(fonte)
+            .spanned()
+            .map(|(tok, span)| (span.start, tok.unwrap(), span.end));
+        let mapa = MapaLinhas::novo("t.pr", fonte);
+        let programa = crate::parser::ArquivoParser::new()
+            .parse(Some(&mapa), tokens)
+            .unwrap();
+        let programa = Box::leak(Box::new(programa));
+        let verificador = Box::leak(Box::new(crate::type_checker::VerificadorTipos::new()));
+        verificador.verificar_programa(programa).unwrap();
+        let nome = |fqn: &str| fqn.to_string();
+        let tabelas = Tabelas::new(programa, verificador, nome, nome);
+        ContextoExecucao::new(Box::leak(Box::new(tabelas)))
     }
 
-    // **NOVO**: Método para capturar argumentos dinâmicos
-    pub fn capturar_argumentos_dinamicos(&mut self, expressoes: &[Expressao]) -> Result<Vec<ValorRuntime>, String> {
-        let mut argumentos = Vec::new();
-        for expr in expressoes {
-            argumentos.push(self.avaliar_expressao(expr)?);
-        }
-        Ok(argumentos)
+    #[test]
+    fn despacho_virtual_e_construtor_base() {
+        let mut ctx = contexto(
+            "\
+classe Forma {
+    publico texto Nome { obter; definir; }
+    publico Forma(texto nome) { Nome = nome; }
+    publico redefinível inteiro Lados() { retorne 0; }
+    publico texto Descrever() { retorne Nome + \":\" + Lados(); }
+}
+classe Quadrado : Forma {
+    publico Quadrado() : base(\"quadrado\") { }
+    publico sobrescreve inteiro Lados() { retorne 4; }
+}
+função texto Teste() {
+    Forma f = novo Quadrado();
+    retorne f.Descrever();
+}
+",
+        );
+        assert_eq!(
+            ctx.chamar("Teste", Vec::new()),
+            Ok(ValorRuntime::Texto("quadrado:4".to_string()))
+        );
     }
 
-    // **NOVO**: Método para resetar contexto
-    pub fn resetar_contexto(&mut self) {
-        self.argumentos_capturados.clear();
-        self.contexto_metodo_atual = None;
-        self.pilha_chamadas.clear();
+    #[test]
+    fn erro_guarda_a_posicao_da_falha() {
+        let mut ctx = contexto(
+            "\
+função inteiro Dividir(inteiro a, inteiro b) {
+    retorne a / b;
+}
+função vazio Principal() {
+    imprima(Dividir(1, 0));
+}
+",
+        );
+        assert_eq!(ctx.executar(), Err("Divisão por zero".to_string()));
+        assert_eq!(ctx.posicao().map(|p| p.linha), Some(2));
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,310 @@
+/*
+ * Coletor de lixo do alvo LLVM: marcação e varredura conservadora, sem mover objetos.
+ *
+ * Toda alocação do código gerado (objetos, arrays, textos) passa por pds_gc_alocar*. Cada bloco
+ * tem um cabeçalho e fica registrado em `blocos`. A coleta marca os blocos alcançáveis a partir
+ * da pilha da thread atual (com os registradores salvos nela), das raízes registradas (campos
+ * estáticos) e, transitivamente, do conteúdo dos blocos não atômicos. Qualquer palavra que
+ * aponte para dentro de um bloco o mantém vivo, inclusive ponteiros internos. Os blocos não
+ * marcados são liberados.
+ */
+#if !defined(_WIN32) && !defined(_GNU_SOURCE)
+#define _GNU_SOURCE /* pthread_getattr_np */
+#endif
+
+#include "gc_runtime.h"
+
+#include <setjmp.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#ifdef _WIN32
+#include <windows.h>
+static SRWLOCK trava = SRWLOCK_INIT;
+static void travar(void) { AcquireSRWLockExclusive(&trava); }
+static void destravar(void) { ReleaseSRWLockExclusive(&trava); }
+#else
+#include <pthread.h>
+static pthread_mutex_t trava = PTHREAD_MUTEX_INITIALIZER;
+static void travar(void) { pthread_mutex_lock(&trava); }
+static void destravar(void) { pthread_mutex_unlock(&trava); }
+#endif
+
+#if defined(__GNUC__) || defined(__clang__)
+#define PDS_NOINLINE __attribute__((noinline))
+#elif defined(_MSC_VER)
+#define PDS_NOINLINE __declspec(noinline)
+#else
+#define PDS_NOINLINE
+#endif
+
+/* Coleta quando o alocado desde a última coleta passa de max(LIMITE_MINIMO, bytes vivos).
+ * Pode ser trocado na compilação do runtime (-DPDS_GC_LIMITE_MINIMO=0 coleta a cada alocação). */
+#ifndef PDS_GC_LIMITE_MINIMO
+#define PDS_GC_LIMITE_MINIMO ((size_t)8 * 1024 * 1024)
+#endif
+#define LIMITE_MINIMO ((size_t)(PDS_GC_LIMITE_MINIMO))
+
+typedef struct Bloco {
+    size_t tamanho;
+    void (*finalizador)(void *);
+    unsigned char marcado;
+    unsigned char atomico;
+} Bloco;
+
+/* Cabeçalho arredondado para manter a área útil alinhada a 16 bytes. */
+#define CABECALHO ((sizeof(Bloco) + 15) & ~(size_t)15)
+#define AREA(bloco) ((char *)(bloco) + CABECALHO)
+
+typedef struct Raiz {
+    char *inicio;
+    size_t tamanho;
+} Raiz;
+
+static Bloco **blocos = NULL;
+static size_t total_blocos = 0;
+static size_t capacidade_blocos = 0;
+
+static Raiz *raizes = NULL;
+static size_t total_raizes = 0;
+static size_t capacidade_raizes = 0;
+
+/* Blocos marcados cujo conteúdo ainda não foi percorrido. */
+static Bloco **pendentes = NULL;
+static size_t total_pendentes = 0;
+static size_t capacidade_pendentes = 0;
+
+/* Faixa de endereços do heap durante a marcação, para descartar palavras rapidamente. */
+static uintptr_t heap_inicio = 0;
+static uintptr_t heap_fim = 0;
+
+static size_t alocados_desde_coleta = 0;
+static size_t bytes_vivos = 0;
+static int tarefas_ativas = 0;
+
+static void sem_memoria(void) {
+    fputs("Erro fatal: memória insuficiente\n", stderr);
+    abort();
+}
+
+/* Mesmo formato do `compilador --executar`. */
+void pds_panico(const char *mensagem, const char *arquivo, int linha, int coluna) {
+    fflush(stdout);
+    if (arquivo != NULL) {
+        fprintf(stderr, "Erro em tempo de execução: %s (em %s:%d:%d)\n", mensagem, arquivo, linha, coluna);
+    } else {
+        fprintf(stderr, "Erro em tempo de execução: %s\n", mensagem);
+    }
+    exit(1);
+}
+
+static void *crescer(void *vetor, size_t *capacidade, size_t elemento) {
+    size_t nova = *capacidade == 0 ? 256 : *capacidade * 2;
+    void *novo = realloc(vetor, nova * elemento);
+    if (novo == NULL) {
+        sem_memoria();
+    }
+    *capacidade = nova;
+    return novo;
+}
+
+static int comparar_blocos(const void *a, const void *b) {
+    uintptr_t x = (uintptr_t)*(Bloco *const *)a;
+    uintptr_t y = (uintptr_t)*(Bloco *const *)b;
+    return (x > y) - (x < y);
+}
+
+/* Bloco cuja área útil contém `endereco`; `blocos` precisa estar ordenado por endereço. */
+static Bloco *bloco_de(uintptr_t endereco) {
+    size_t inicio = 0;
+    size_t fim = total_blocos;
+    Bloco *bloco;
+    if (endereco < heap_inicio || endereco > heap_fim) {
+        return NULL;
+    }
+    while (inicio < fim) {
+        size_t meio = inicio + (fim - inicio) / 2;
+        if ((uintptr_t)AREA(blocos[meio]) <= endereco) {
+            inicio = meio + 1;
+        } else {
+            fim = meio;
+        }
+    }
+    if (inicio == 0) {
+        return NULL;
+    }
+    bloco = blocos[inicio - 1];
+    /* O byte extra de cada bloco faz o ponteiro "um após o fim" ainda contar como interno. */
+    return endereco <= (uintptr_t)AREA(bloco) + bloco->tamanho ? bloco : NULL;
+}
+
+static void marcar_intervalo(const char *inicio, const char *fim) {
+    uintptr_t mascara = (uintptr_t)sizeof(void *) - 1;
+    const char *p = (const char *)(((uintptr_t)inicio + mascara) & ~mascara);
+    for (; p + sizeof(void *) <= fim; p += sizeof(void *)) {
+        uintptr_t palavra;
+        Bloco *bloco;
+        memcpy(&palavra, p, sizeof(palavra));
+        bloco = bloco_de(palavra);
+        if (bloco == NULL || bloco->marcado) {
+            continue;
+        }
+        bloco->marcado = 1;
+        if (!bloco->atomico) {
+            if (total_pendentes == capacidade_pendentes) {
+                pendentes = (Bloco **)crescer(pendentes, &capacidade_pendentes, sizeof(Bloco *));
+            }
+            pendentes[total_pendentes++] = bloco;
+        }
+    }
+}
+
+/* Endereço mais alto da pilha da thread atual (a pilha cresce para baixo). */
+static char *base_da_pilha(void) {
+#ifdef _WIN32
+    return (char *)((NT_TIB *)NtCurrentTeb())->StackBase;
+#elif defined(__APPLE__)
+    return (char *)pthread_get_stackaddr_np(pthread_self());
+#else
+    pthread_attr_t atributos;
+    void *endereco = NULL;
+    size_t tamanho = 0;
+    if (pthread_getattr_np(pthread_self(), &atributos) != 0) {
+        return NULL;
+    }
+    pthread_attr_getstack(&atributos, &endereco, &tamanho);
+    pthread_attr_destroy(&atributos);
+    return endereco == NULL ? NULL : (char *)endereco + tamanho;
+#endif
+}
+
+/* Percorre a pilha do quadro atual até a base, passando pelos quadros de quem chamou. */
+static PDS_NOINLINE void marcar_pilha(char *base) {
+    volatile char topo = 0;
+    marcar_intervalo((const char *)&topo, base);
+}
+
+static void varrer(void) {
+    size_t i;
+    size_t vivos = 0;
+    bytes_vivos = 0;
+    for (i = 0; i < total_blocos; ++i) {
+        Bloco *bloco = blocos[i];
+        if (bloco->marcado) {
+            bloco->marcado = 0;
+            bytes_vivos += bloco->tamanho;
+            blocos[vivos++] = bloco;
+        } else {
+            if (bloco->finalizador != NULL) {
+                bloco->finalizador(AREA(bloco));
+            }
+            free(bloco);
+        }
+    }
+    total_blocos = vivos;
+}
+
+/* Chamada com a trava. Retorna 0 se a coleta foi adiada. */
+static PDS_NOINLINE int coletar(void) {
+    jmp_buf registradores;
+    char *base;
+    size_t i;
+    if (tarefas_ativas > 0 || total_blocos == 0) {
+        return 0;
+    }
+    base = base_da_pilha();
+    if (base == NULL) {
+        return 0;
+    }
+    /* Registradores com ponteiros vivos vão para a pilha, onde marcar_pilha os encontra. */
+#if defined(__GNUC__) || defined(__clang__)
+    __builtin_unwind_init();
+#endif
+    setjmp(registradores);
+
+    qsort(blocos, total_blocos, sizeof(Bloco *), comparar_blocos);
+    heap_inicio = (uintptr_t)AREA(blocos[0]);
+    heap_fim = (uintptr_t)AREA(blocos[total_blocos - 1]) + blocos[total_blocos - 1]->tamanho;
+
+    marcar_pilha(base);
+    for (i = 0; i < total_raizes; ++i) {
+        marcar_intervalo(raizes[i].inicio, raizes[i].inicio + raizes[i].tamanho);
+    }
+    while (total_pendentes > 0) {
+        Bloco *bloco = pendentes[--total_pendentes];
+        marcar_intervalo(AREA(bloco), AREA(bloco) + bloco->tamanho);
+    }
+    varrer();
+    alocados_desde_coleta = 0;
+    return 1;
+}
+
+static void *alocar(size_t tamanho, int atomico, void (*finalizador)(void *)) {
+    size_t bytes = CABECALHO + tamanho + 1;
+    Bloco *bloco;
+    travar();
+    if (alocados_desde_coleta >= (bytes_vivos > LIMITE_MINIMO ? bytes_vivos : LIMITE_MINIMO)) {
+        coletar();
+    }
+    bloco = (Bloco *)(atomico ? malloc(bytes) : calloc(1, bytes));
+    if (bloco == NULL && coletar()) {
+        bloco = (Bloco *)(atomico ? malloc(bytes) : calloc(1, bytes));
+    }
+    if (bloco == NULL) {
+        sem_memoria();
+    }
+    bloco->tamanho = tamanho;
+    bloco->finalizador = finalizador;
+    bloco->marcado = 0;
+    bloco->atomico = (unsigned char)atomico;
+    if (total_blocos == capacidade_blocos) {
+        blocos = (Bloco **)crescer(blocos, &capacidade_blocos, sizeof(Bloco *));
+    }
+    blocos[total_blocos++] = bloco;
+    alocados_desde_coleta += tamanho;
+    destravar();
+    return AREA(bloco);
+}
+
+void *pds_gc_alocar(size_t tamanho) {
+    return alocar(tamanho, 0, NULL);
+}
+
+void *pds_gc_alocar_atomico(size_t tamanho) {
+    return alocar(tamanho, 1, NULL);
+}
+
+void *pds_gc_alocar_com_finalizador(size_t tamanho, void (*finalizador)(void *)) {
+    return alocar(tamanho, 0, finalizador);
+}
+
+void pds_gc_adicionar_raiz(void *inicio, size_t tamanho) {
+    travar();
+    if (total_raizes == capacidade_raizes) {
+        raizes = (Raiz *)crescer(raizes, &capacidade_raizes, sizeof(Raiz));
+    }
+    raizes[total_raizes].inicio = (char *)inicio;
+    raizes[total_raizes].tamanho = tamanho;
+    ++total_raizes;
+    destravar();
+}
+
+void pds_gc_coletar(void) {
+    travar();
+    coletar();
+    destravar();
+}
+
+void pds_gc_tarefa_iniciada(void) {
+    travar();
+    ++tarefas_ativas;
+    destravar();
+}
+
+void pds_gc_tarefa_concluida(void) {
+    travar();
+    --tarefas_ativas;
+    destravar();
+}
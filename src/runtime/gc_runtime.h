@@ -0,0 +1,37 @@
+/* Coletor de lixo do alvo LLVM (marcação e varredura conservadora) e erros fatais do código gerado. */
+#ifndef POR_DO_SOL_GC_RUNTIME_H
+#define POR_DO_SOL_GC_RUNTIME_H
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Memória zerada e percorrida pelo coletor: objetos, arrays e estruturas com ponteiros. */
+void *pds_gc_alocar(size_t tamanho);
+/* Memória sem ponteiros (textos): não é percorrida na marcação. */
+void *pds_gc_alocar_atomico(size_t tamanho);
+/* Como pds_gc_alocar; `finalizador` é chamado antes de o bloco ser liberado. */
+void *pds_gc_alocar_com_finalizador(size_t tamanho, void (*finalizador)(void *));
+
+/* Registra uma região fora do heap (ex.: campos estáticos) que pode apontar para o heap. */
+void pds_gc_adicionar_raiz(void *inicio, size_t tamanho);
+/* Força uma coleta (adiada se houver tarefas assíncronas em execução). */
+void pds_gc_coletar(void);
+
+/* Enquanto houver tarefas em execução nas threads do pool, não há coleta: só a pilha da
+ * thread que coleta é percorrida. */
+void pds_gc_tarefa_iniciada(void);
+void pds_gc_tarefa_concluida(void);
+
+/* Erro de execução detectado pelas verificações do código gerado (índice, nulo, divisão por
+ * zero): imprime a mensagem e a posição no fonte em stderr e termina com código 1.
+ * `arquivo` é NULL quando a posição não é conhecida. */
+void pds_panico(const char *mensagem, const char *arquivo, int linha, int coluna);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,137 @@
+// Funções nativas de E/S disponíveis ao interpretador de AST.
+// As chaves são as mesmas de `[Nativo("Classe::Metodo")]` e da biblioteca padrão, e o
+// comportamento acompanha o `despachar_nativo_estatico` da VM de bytecode.
+
+use std::fs;
+use std::io::Write;
+
+use super::execution_context::ValorRuntime;
+
+/// Indica se existe implementação nativa para a chave.
+pub fn existe(chave: &str) -> bool {
+    matches!(
+        chave,
+        "Console::EscreverLinha"
+            | "Console::Escrever"
+            | "Console::LerLinha"
+            | "Arquivo::LerTexto"
+            | "Arquivo::EscreverTexto"
+            | "Arquivo::AdicionarTexto"
+            | "Arquivo::Existe"
+            | "Arquivo::Excluir"
+            | "Arquivo::Copiar"
+            | "Arquivo::Mover"
+            | "LerArquivoAssíncrono"
+            | "Arquivo::LerTextoAssíncrono"
+            | "EscreverArquivoAssíncrono"
+            | "Arquivo::EscreverTextoAssíncrono"
+            | "Diretorio::Existe"
+            | "Diretorio::Criar"
+            | "Diretorio::Excluir"
+            | "Diretorio::ObterAtual"
+            | "Diretorio::DefinirAtual"
+    )
+}
+
+pub fn chamar(chave: &str, args: Vec<ValorRuntime>) -> Result<ValorRuntime, String> {
+    let mut textos = args.iter().map(|v| v.to_string());
+    let mut proximo = || textos.next().unwrap_or_default();
+    match chave {
+        "Console::EscreverLinha" => {
+            let msg: String = args.iter().map(|v| v.to_string()).collect();
+            println!("{}", msg);
+            Ok(ValorRuntime::Nulo)
+        }
+        "Console::Escrever" => {
+            let msg: String = args.iter().map(|v| v.to_string()).collect();
+            print!("{}", msg);
+            let _ = std::io::stdout().flush();
+            Ok(ValorRuntime::Nulo)
+        }
+        "Console::LerLinha" => {
+            let mut entrada = String::new();
+            std::io::stdin()
+                .read_line(&mut entrada)
+                .map_err(|e| format!("Erro ao ler entrada: {}", e))?;
+            Ok(ValorRuntime::Texto(
+                entrada.trim_end_matches(['\r', '\n']).to_string(),
+            ))
+        }
+
+        "Arquivo::LerTexto" => fs::read_to_string(proximo())
+            .map(ValorRuntime::Texto)
+            .map_err(|e| format!("Arquivo::LerTexto: {}", e)),
+        // Sem tarefas concorrentes, as variantes assíncronas completam antes de retornar
+        "LerArquivoAssíncrono" | "Arquivo::LerTextoAssíncrono" => fs::read_to_string(proximo())
+            .map(ValorRuntime::Texto)
+            .map_err(|e| format!("LerArquivoAssíncrono: {}", e)),
+        "EscreverArquivoAssíncrono" | "Arquivo::EscreverTextoAssíncrono" => {
+            let (caminho, conteudo) = (proximo(), proximo());
+            fs::write(caminho, conteudo)
+                .map_err(|e| format!("EscreverArquivoAssíncrono: {}", e))?;
+            Ok(ValorRuntime::Nulo)
+        }
+        "Arquivo::EscreverTexto" => {
+            let (caminho, conteudo) = (proximo(), proximo());
+            fs::write(caminho, conteudo).map_err(|e| format!("Arquivo::EscreverTexto: {}", e))?;
+            Ok(ValorRuntime::Nulo)
+        }
+        "Arquivo::AdicionarTexto" => {
+            let (caminho, conteudo) = (proximo(), proximo());
+            fs::OpenOptions::new()
+                .append(true)
+                .create(true)
+                .open(caminho)
+                .and_then(|mut f| f.write_all(conteudo.as_bytes()))
+                .map_err(|e| format!("Arquivo::AdicionarTexto: {}", e))?;
+            Ok(ValorRuntime::Nulo)
+        }
+        "Arquivo::Existe" => Ok(ValorRuntime::Booleano(
+            std::path::Path::new(&proximo()).is_file(),
+        )),
+        "Arquivo::Excluir" => {
+            fs::remove_file(proximo()).map_err(|e| format!("Arquivo::Excluir: {}", e))?;
+            Ok(ValorRuntime::Nulo)
+        }
+        "Arquivo::Copiar" => {
+            let (origem, destino) = (proximo(), proximo());
+            fs::copy(origem, destino).map_err(|e| format!("Arquivo::Copiar: {}", e))?;
+            Ok(ValorRuntime::Nulo)
+        }
+        "Arquivo::Mover" => {
+            let (origem, destino) = (proximo(), proximo());
+            fs::rename(origem, destino).map_err(|e| format!("Arquivo::Mover: {}", e))?;
+            Ok(ValorRuntime::Nulo)
+        }
+
+        "Diretorio::Existe" => Ok(ValorRuntime::Booleano(
+            std::path::Path::new(&proximo()).is_dir(),
+        )),
+        "Diretorio::Criar" => {
+            fs::create_dir_all(proximo()).map_err(|e| format!("Diretorio::Criar: {}", e))?;
+            Ok(ValorRuntime::Nulo)
+        }
+        "Diretorio::Excluir" => {
+            let caminho = proximo();
+            let resultado = if matches!(args.get(1), Some(ValorRuntime::Booleano(true))) {
+                fs::remove_dir_all(caminho)
+            } else {
+                fs::remove_dir(caminho)
+            };
+            resultado.map_err(|e| format!("Diretorio::Excluir: {}", e))?;
+            Ok(ValorRuntime::Nulo)
+        }
+        "Diretorio::ObterAtual" => Ok(ValorRuntime::Texto(
+            std::env::current_dir()
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+        )),
+        "Diretorio::DefinirAtual" => {
+            std::env::set_current_dir(proximo())
+                .map_err(|e| format!("Diretorio::DefinirAtual: {}", e))?;
+            Ok(ValorRuntime::Nulo)
+        }
+
+        _ => Err(format!("Função nativa '{}' não implementada", chave)),
+    }
+}
@@ -0,0 +1,122 @@
+#!/usr/bin/env node
+// Hospedeiro Node.js dos módulos gerados por `--target=wasm` do compilador Por do Sol.
+//
+// Uso: node pds_wasm.js programa.wasm
+//
+// Implementa as funções importadas do módulo "pds" (console, formatação de números e erros
+// em tempo de execução) e chama a exportação `_start`.
+'use strict';
+
+const fs = require('fs');
+
+function formatarDuplo(v) {
+    if (Number.isNaN(v)) return 'NaN';
+    if (v === Infinity) return 'inf';
+    if (v === -Infinity) return '-inf';
+    if (Object.is(v, -0)) return '-0.000000';
+    if (Math.abs(v) >= 1e21) return BigInt(v).toString() + '.000000';
+    // toFixed arredonda empates para longe do zero; printf("%f") arredonda para o par
+    const escalado = v * 1e6;
+    if (Number.isInteger(escalado * 2) && !Number.isInteger(escalado)) {
+        const baixo = Math.floor(escalado);
+        const par = baixo % 2 === 0 ? baixo : baixo + 1;
+        return (par / 1e6).toFixed(6);
+    }
+    return v.toFixed(6);
+}
+
+function principal() {
+    const caminho = process.argv[2];
+    if (!caminho) {
+        process.stderr.write('Uso: node pds_wasm.js <programa.wasm>\n');
+        process.exit(2);
+    }
+
+    let memoria = null;
+    const bytes = () => new Uint8Array(memoria.buffer);
+    const decodificador = new TextDecoder('utf-8');
+    const lerTexto = (ptr, len) => decodificador.decode(bytes().subarray(ptr, ptr + len));
+
+    let entrada = Buffer.alloc(0);
+    let fimEntrada = false;
+    let linhaPendente = Buffer.alloc(0);
+
+    function proximaLinha() {
+        for (;;) {
+            const nl = entrada.indexOf(10);
+            if (nl >= 0) {
+                let linha = entrada.subarray(0, nl);
+                entrada = entrada.subarray(nl + 1);
+                if (linha.length > 0 && linha[linha.length - 1] === 13) {
+                    linha = linha.subarray(0, linha.length - 1);
+                }
+                return linha;
+            }
+            if (fimEntrada) {
+                if (entrada.length === 0) return null;
+                const linha = entrada;
+                entrada = Buffer.alloc(0);
+                return linha;
+            }
+            const pedaco = Buffer.alloc(4096);
+            let lidos = 0;
+            try {
+                lidos = fs.readSync(0, pedaco, 0, pedaco.length, null);
+            } catch (e) {
+                if (e.code === 'EAGAIN') continue;
+                if (e.code !== 'EOF') throw e;
+            }
+            if (lidos === 0) fimEntrada = true;
+            entrada = Buffer.concat([entrada, pedaco.subarray(0, lidos)]);
+        }
+    }
+
+    const saida = [];
+    const descarregar = () => {
+        if (saida.length > 0) {
+            fs.writeSync(1, Buffer.concat(saida));
+            saida.length = 0;
+        }
+    };
+
+    const pds = {
+        escrever(ptr, len) {
+            saida.push(Buffer.from(bytes().slice(ptr, ptr + len)));
+        },
+        ler_linha() {
+            descarregar();
+            const linha = proximaLinha();
+            if (linha === null) return -1;
+            linhaPendente = linha;
+            return linha.length;
+        },
+        copiar_linha(ptr) {
+            bytes().set(linhaPendente, ptr);
+        },
+        formatar_duplo(valor, ptr) {
+            const texto = Buffer.from(formatarDuplo(valor), 'utf-8');
+            bytes().set(texto, ptr);
+            return texto.length;
+        },
+        resto(a, b) {
+            return a % b;
+        },
+        panico(msgPtr, msgLen, arqPtr, arqLen, linha, coluna) {
+            descarregar();
+            let mensagem = 'Erro em tempo de execução: ' + lerTexto(msgPtr, msgLen);
+            if (arqLen > 0) {
+                mensagem += ` (em ${lerTexto(arqPtr, arqLen)}:${linha}:${coluna})`;
+            }
+            process.stderr.write(mensagem + '\n');
+            process.exit(1);
+        },
+    };
+
+    const modulo = new WebAssembly.Module(fs.readFileSync(caminho));
+    const instancia = new WebAssembly.Instance(modulo, { pds });
+    memoria = instancia.exports.memory;
+    instancia.exports._start();
+    descarregar();
+}
+
+principal();
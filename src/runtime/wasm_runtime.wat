@@ -0,0 +1,671 @@
+  ;; Runtime do alvo WebAssembly (--target=wasm) do compilador Por do Sol.
+  ;;
+  ;; Incluído no início de todo módulo gerado por `src/codegen/wasm.rs`, que define a memória,
+  ;; a tabela `$pds_funcoes`, os dados estáticos e as globais usadas aqui: os textos
+  ;; `$pds_txt_*`/`$pds_msg_*`, os descritores `$pds_classe_*`, `$pds_buffer` e `$pds_heap`.
+  ;;
+  ;; Representação dos valores na memória linear (endereço 0 é `nulo`):
+  ;;   texto   [tamanho em bytes: i32][bytes UTF-8]
+  ;;   objeto  [descritor da classe: i32][campos...]
+  ;;   array   [descritor][tamanho: i32][elemento: i32][-][itens a partir do byte 16]
+  ;;   caixa   [descritor][-][valor a partir do byte 8]
+  ;;   classe  [nome: texto][pai][função de texto na tabela, ou -1][entradas da vtable...]
+  ;;
+  ;; A memória vem de um alocador linear que só cresce: não há coletor de lixo neste alvo.
+
+  ;; Funções do hospedeiro (módulo "pds"), implementadas por quem executa o módulo
+  (import "pds" "escrever" (func $pds_host_escrever (param i32 i32)))
+  (import "pds" "ler_linha" (func $pds_host_ler_linha (result i32)))
+  (import "pds" "copiar_linha" (func $pds_host_copiar_linha (param i32)))
+  (import "pds" "formatar_duplo" (func $pds_host_formatar_duplo (param f64 i32) (result i32)))
+  (import "pds" "resto" (func $pds_host_resto (param f64 f64) (result f64)))
+  (import "pds" "panico" (func $pds_host_panico (param i32 i32 i32 i32 i32 i32)))
+
+  ;; Função de texto de uma classe (exibição por imprima)
+  (type $pds_texto_fn (func (param i32) (result i32)))
+
+  ;; ------------------------------------------------------------------------
+  ;; Memória e erros
+  ;; ------------------------------------------------------------------------
+
+  ;; Bloco zerado de `tamanho` bytes, alinhado em 8.
+  (func $pds_alocar (param $tamanho i32) (result i32)
+    (local $inicio i32)
+    (local $fim i32)
+    (local $disponivel i32)
+    (local.set $inicio (i32.and (i32.add (global.get $pds_heap) (i32.const 7)) (i32.const -8)))
+    (local.set $fim (i32.add (local.get $inicio) (local.get $tamanho)))
+    (if (i32.lt_u (local.get $fim) (local.get $inicio))
+      (then (call $pds_panico (global.get $pds_msg_memoria) (i32.const 0) (i32.const 0) (i32.const 0))))
+    (local.set $disponivel (i32.shl (memory.size) (i32.const 16)))
+    (if (i32.gt_u (local.get $fim) (local.get $disponivel))
+      (then
+        (if (i32.eq
+              (memory.grow
+                (i32.shr_u
+                  (i32.add (i32.sub (local.get $fim) (local.get $disponivel)) (i32.const 65535))
+                  (i32.const 16)))
+              (i32.const -1))
+          (then (call $pds_panico (global.get $pds_msg_memoria) (i32.const 0) (i32.const 0) (i32.const 0))))))
+    (global.set $pds_heap (local.get $fim))
+    (local.get $inicio))
+
+  ;; Termina o programa com "Erro em tempo de execução" (o hospedeiro exibe e encerra).
+  (func $pds_panico (param $mensagem i32) (param $arquivo i32) (param $linha i32) (param $coluna i32)
+    (call $pds_host_panico
+      (i32.add (local.get $mensagem) (i32.const 4))
+      (i32.load (local.get $mensagem))
+      (if (result i32) (local.get $arquivo)
+        (then (i32.add (local.get $arquivo) (i32.const 4)))
+        (else (i32.const 0)))
+      (if (result i32) (local.get $arquivo)
+        (then (i32.load (local.get $arquivo)))
+        (else (i32.const 0)))
+      (local.get $linha)
+      (local.get $coluna))
+    unreachable)
+
+  ;; Devolve a referência, ou termina com `mensagem` se ela for nula.
+  (func $pds_nao_nulo (param $referencia i32) (param $mensagem i32) (param $arquivo i32) (param $linha i32) (param $coluna i32) (result i32)
+    (if (i32.eqz (local.get $referencia))
+      (then (call $pds_panico (local.get $mensagem) (local.get $arquivo) (local.get $linha) (local.get $coluna))))
+    (local.get $referencia))
+
+  ;; Como `$pds_nao_nulo`, para referências já usadas (verificadas depois dos argumentos).
+  (func $pds_verificar_nulo (param $referencia i32) (param $mensagem i32) (param $arquivo i32) (param $linha i32) (param $coluna i32)
+    (if (i32.eqz (local.get $referencia))
+      (then (call $pds_panico (local.get $mensagem) (local.get $arquivo) (local.get $linha) (local.get $coluna)))))
+
+  ;; Chamada em interface ou `objeto` de uma classe sem o método.
+  (func $pds_metodo_nao_encontrado (param $metodo i32) (param $objeto i32)
+    (call $pds_panico
+      (call $pds_concatenar
+        (call $pds_concatenar
+          (call $pds_concatenar
+            (call $pds_concatenar (global.get $pds_txt_metodo) (local.get $metodo))
+            (global.get $pds_txt_nao_encontrado))
+          (i32.load (i32.load (local.get $objeto))))
+        (global.get $pds_txt_aspa))
+      (i32.const 0) (i32.const 0) (i32.const 0)))
+
+  ;; ------------------------------------------------------------------------
+  ;; Textos
+  ;; ------------------------------------------------------------------------
+
+  (func $pds_texto_novo (param $tamanho i32) (result i32)
+    (local $texto i32)
+    (local.set $texto (call $pds_alocar (i32.add (local.get $tamanho) (i32.const 4))))
+    (i32.store (local.get $texto) (local.get $tamanho))
+    (local.get $texto))
+
+  (func $pds_texto_de_bytes (param $bytes i32) (param $tamanho i32) (result i32)
+    (local $texto i32)
+    (local.set $texto (call $pds_texto_novo (local.get $tamanho)))
+    (memory.copy (i32.add (local.get $texto) (i32.const 4)) (local.get $bytes) (local.get $tamanho))
+    (local.get $texto))
+
+  ;; `nulo` é exibido como "nulo".
+  (func $pds_texto (param $texto i32) (result i32)
+    (if (result i32) (local.get $texto)
+      (then (local.get $texto))
+      (else (global.get $pds_txt_nulo))))
+
+  (func $pds_concatenar (param $a i32) (param $b i32) (result i32)
+    (local $ta i32)
+    (local $tb i32)
+    (local $texto i32)
+    (local.set $a (call $pds_texto (local.get $a)))
+    (local.set $b (call $pds_texto (local.get $b)))
+    (local.set $ta (i32.load (local.get $a)))
+    (local.set $tb (i32.load (local.get $b)))
+    (local.set $texto (call $pds_texto_novo (i32.add (local.get $ta) (local.get $tb))))
+    (memory.copy
+      (i32.add (local.get $texto) (i32.const 4))
+      (i32.add (local.get $a) (i32.const 4))
+      (local.get $ta))
+    (memory.copy
+      (i32.add (i32.add (local.get $texto) (i32.const 4)) (local.get $ta))
+      (i32.add (local.get $b) (i32.const 4))
+      (local.get $tb))
+    (local.get $texto))
+
+  (func $pds_texto_inteiro (param $valor i64) (result i32)
+    (local $fim i32)
+    (local $p i32)
+    (local $negativo i32)
+    (local $resto i64)
+    ;; Dígitos escritos de trás para frente no buffer
+    (local.set $fim (i32.add (global.get $pds_buffer) (i32.const 32)))
+    (local.set $p (local.get $fim))
+    (local.set $negativo (i64.lt_s (local.get $valor) (i64.const 0)))
+    (local.set $resto
+      (if (result i64) (local.get $negativo)
+        (then (i64.sub (i64.const 0) (local.get $valor)))
+        (else (local.get $valor))))
+    (loop $digitos
+      (local.set $p (i32.sub (local.get $p) (i32.const 1)))
+      (i32.store8 (local.get $p)
+        (i32.wrap_i64 (i64.add (i64.rem_u (local.get $resto) (i64.const 10)) (i64.const 48))))
+      (local.set $resto (i64.div_u (local.get $resto) (i64.const 10)))
+      (br_if $digitos (i64.ne (local.get $resto) (i64.const 0))))
+    (if (local.get $negativo)
+      (then
+        (local.set $p (i32.sub (local.get $p) (i32.const 1)))
+        (i32.store8 (local.get $p) (i32.const 45))))
+    (call $pds_texto_de_bytes (local.get $p) (i32.sub (local.get $fim) (local.get $p))))
+
+  ;; Seis casas decimais, como `{:.6}` no interpretador (formatado pelo hospedeiro).
+  (func $pds_texto_duplo (param $valor f64) (result i32)
+    (call $pds_texto_de_bytes
+      (global.get $pds_buffer)
+      (call $pds_host_formatar_duplo (local.get $valor) (global.get $pds_buffer))))
+
+  (func $pds_texto_booleano (param $valor i32) (result i32)
+    (select (global.get $pds_txt_verdadeiro) (global.get $pds_txt_falso) (local.get $valor)))
+
+  (func $pds_bytes_iguais (param $a i32) (param $b i32) (param $tamanho i32) (result i32)
+    (local $i i32)
+    (block $iguais
+      (loop $bytes
+        (br_if $iguais (i32.ge_u (local.get $i) (local.get $tamanho)))
+        (if (i32.ne
+              (i32.load8_u (i32.add (local.get $a) (local.get $i)))
+              (i32.load8_u (i32.add (local.get $b) (local.get $i))))
+          (then (return (i32.const 0))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $bytes)))
+    (i32.const 1))
+
+  (func $pds_textos_iguais (param $a i32) (param $b i32) (result i32)
+    (if (i32.eq (local.get $a) (local.get $b))
+      (then (return (i32.const 1))))
+    (if (i32.or (i32.eqz (local.get $a)) (i32.eqz (local.get $b)))
+      (then (return (i32.const 0))))
+    (if (i32.ne (i32.load (local.get $a)) (i32.load (local.get $b)))
+      (then (return (i32.const 0))))
+    (call $pds_bytes_iguais
+      (i32.add (local.get $a) (i32.const 4))
+      (i32.add (local.get $b) (i32.const 4))
+      (i32.load (local.get $a))))
+
+  ;; Ordem dos bytes UTF-8 (a mesma de `str::cmp`): -1, 0 ou 1.
+  (func $pds_comparar_textos (param $a i32) (param $b i32) (result i32)
+    (local $ta i32)
+    (local $tb i32)
+    (local $i i32)
+    (local $x i32)
+    (local $y i32)
+    (local.set $a (call $pds_texto (local.get $a)))
+    (local.set $b (call $pds_texto (local.get $b)))
+    (local.set $ta (i32.load (local.get $a)))
+    (local.set $tb (i32.load (local.get $b)))
+    (block $fim
+      (loop $bytes
+        (br_if $fim (i32.or
+          (i32.ge_u (local.get $i) (local.get $ta))
+          (i32.ge_u (local.get $i) (local.get $tb))))
+        (local.set $x (i32.load8_u offset=4 (i32.add (local.get $a) (local.get $i))))
+        (local.set $y (i32.load8_u offset=4 (i32.add (local.get $b) (local.get $i))))
+        (if (i32.ne (local.get $x) (local.get $y))
+          (then (return (select (i32.const -1) (i32.const 1) (i32.lt_u (local.get $x) (local.get $y))))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $bytes)))
+    (i32.sub (i32.gt_u (local.get $ta) (local.get $tb)) (i32.lt_u (local.get $ta) (local.get $tb))))
+
+  ;; Número de caracteres (bytes que não continuam uma sequência UTF-8).
+  (func $pds_texto_tamanho (param $texto i32) (result i64)
+    (local $i i32)
+    (local $n i32)
+    (local $tamanho i64)
+    (if (i32.eqz (local.get $texto))
+      (then (return (i64.const 0))))
+    (local.set $n (i32.load (local.get $texto)))
+    (block $fim
+      (loop $bytes
+        (br_if $fim (i32.ge_u (local.get $i) (local.get $n)))
+        (if (i32.ne
+              (i32.and (i32.load8_u offset=4 (i32.add (local.get $texto) (local.get $i))) (i32.const 0xC0))
+              (i32.const 0x80))
+          (then (local.set $tamanho (i64.add (local.get $tamanho) (i64.const 1)))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $bytes)))
+    (local.get $tamanho))
+
+  ;; Maiúsculas e minúsculas: ASCII e o bloco Latin-1 (à..þ, 2 bytes em UTF-8).
+  (func $pds_trocar_caixa (param $texto i32) (param $maiuscula i32) (result i32)
+    (local $resultado i32)
+    (local $n i32)
+    (local $i i32)
+    (local $p i32)
+    (local $c i32)
+    (local $segundo i32)
+    (local.set $texto (call $pds_texto (local.get $texto)))
+    (local.set $n (i32.load (local.get $texto)))
+    (local.set $resultado
+      (call $pds_texto_de_bytes (i32.add (local.get $texto) (i32.const 4)) (local.get $n)))
+    (block $fim
+      (loop $bytes
+        (br_if $fim (i32.ge_u (local.get $i) (local.get $n)))
+        (local.set $p (i32.add (i32.add (local.get $resultado) (i32.const 4)) (local.get $i)))
+        (local.set $c (i32.load8_u (local.get $p)))
+        (if (i32.and (local.get $maiuscula)
+              (i32.and (i32.ge_u (local.get $c) (i32.const 97)) (i32.le_u (local.get $c) (i32.const 122))))
+          (then (i32.store8 (local.get $p) (i32.sub (local.get $c) (i32.const 32))))
+          (else
+            (if (i32.and (i32.eqz (local.get $maiuscula))
+                  (i32.and (i32.ge_u (local.get $c) (i32.const 65)) (i32.le_u (local.get $c) (i32.const 90))))
+              (then (i32.store8 (local.get $p) (i32.add (local.get $c) (i32.const 32))))
+              (else
+                (if (i32.and (i32.eq (local.get $c) (i32.const 0xC3))
+                      (i32.lt_u (i32.add (local.get $i) (i32.const 1)) (local.get $n)))
+                  (then
+                    ;; U+00E0..U+00FE <-> U+00C0..U+00DE, exceto ÷ (U+00F7) e × (U+00D7)
+                    (local.set $segundo (i32.load8_u offset=1 (local.get $p)))
+                    (if (i32.and (local.get $maiuscula)
+                          (i32.and
+                            (i32.and (i32.ge_u (local.get $segundo) (i32.const 0xA0))
+                                     (i32.le_u (local.get $segundo) (i32.const 0xBE)))
+                            (i32.ne (local.get $segundo) (i32.const 0xB7))))
+                      (then (i32.store8 offset=1 (local.get $p) (i32.sub (local.get $segundo) (i32.const 0x20)))))
+                    (if (i32.and (i32.eqz (local.get $maiuscula))
+                          (i32.and
+                            (i32.and (i32.ge_u (local.get $segundo) (i32.const 0x80))
+                                     (i32.le_u (local.get $segundo) (i32.const 0x9E)))
+                            (i32.ne (local.get $segundo) (i32.const 0x97))))
+                      (then (i32.store8 offset=1 (local.get $p) (i32.add (local.get $segundo) (i32.const 0x20)))))
+                    (local.set $i (i32.add (local.get $i) (i32.const 1)))))))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $bytes)))
+    (local.get $resultado))
+
+  (func $pds_texto_maiusculo (param $texto i32) (result i32)
+    (call $pds_trocar_caixa (local.get $texto) (i32.const 1)))
+
+  (func $pds_texto_minusculo (param $texto i32) (result i32)
+    (call $pds_trocar_caixa (local.get $texto) (i32.const 0)))
+
+  (func $pds_espaco (param $c i32) (result i32)
+    (i32.or
+      (i32.eq (local.get $c) (i32.const 32))
+      (i32.and (i32.ge_u (local.get $c) (i32.const 9)) (i32.le_u (local.get $c) (i32.const 13)))))
+
+  (func $pds_texto_aparar (param $texto i32) (result i32)
+    (local $inicio i32)
+    (local $fim i32)
+    (local.set $texto (call $pds_texto (local.get $texto)))
+    (local.set $inicio (i32.add (local.get $texto) (i32.const 4)))
+    (local.set $fim (i32.add (local.get $inicio) (i32.load (local.get $texto))))
+    (block $pronto
+      (loop $inicio_laco
+        (br_if $pronto (i32.ge_u (local.get $inicio) (local.get $fim)))
+        (br_if $pronto (i32.eqz (call $pds_espaco (i32.load8_u (local.get $inicio)))))
+        (local.set $inicio (i32.add (local.get $inicio) (i32.const 1)))
+        (br $inicio_laco)))
+    (block $pronto
+      (loop $fim_laco
+        (br_if $pronto (i32.le_u (local.get $fim) (local.get $inicio)))
+        (br_if $pronto (i32.eqz (call $pds_espaco (i32.load8_u (i32.sub (local.get $fim) (i32.const 1))))))
+        (local.set $fim (i32.sub (local.get $fim) (i32.const 1)))
+        (br $fim_laco)))
+    (call $pds_texto_de_bytes (local.get $inicio) (i32.sub (local.get $fim) (local.get $inicio))))
+
+  ;; Posição (em bytes) da primeira ocorrência de `parte` a partir de `inicio`, ou -1.
+  (func $pds_buscar (param $texto i32) (param $parte i32) (param $inicio i32) (result i32)
+    (local $n i32)
+    (local $m i32)
+    (local.set $n (i32.load (local.get $texto)))
+    (local.set $m (i32.load (local.get $parte)))
+    (block $fim
+      (loop $posicoes
+        (br_if $fim (i32.gt_u (i32.add (local.get $inicio) (local.get $m)) (local.get $n)))
+        (if (call $pds_bytes_iguais
+              (i32.add (i32.add (local.get $texto) (i32.const 4)) (local.get $inicio))
+              (i32.add (local.get $parte) (i32.const 4))
+              (local.get $m))
+          (then (return (local.get $inicio))))
+        (local.set $inicio (i32.add (local.get $inicio) (i32.const 1)))
+        (br $posicoes)))
+    (i32.const -1))
+
+  (func $pds_texto_contem (param $texto i32) (param $parte i32) (result i32)
+    (i32.ge_s
+      (call $pds_buscar (call $pds_texto (local.get $texto)) (call $pds_texto (local.get $parte)) (i32.const 0))
+      (i32.const 0)))
+
+  (func $pds_texto_substituir (param $texto i32) (param $de i32) (param $para i32) (result i32)
+    (local $resultado i32)
+    (local $atual i32)
+    (local $encontrado i32)
+    (local $fim i32)
+    (local $n i32)
+    (local.set $texto (call $pds_texto (local.get $texto)))
+    (local.set $de (call $pds_texto (local.get $de)))
+    (local.set $para (call $pds_texto (local.get $para)))
+    (local.set $n (i32.load (local.get $texto)))
+    ;; Como str::replace com padrão vazio: insere `para` entre cada caractere
+    (if (i32.eqz (i32.load (local.get $de)))
+      (then
+        (local.set $resultado (local.get $para))
+        (block $pronto
+          (loop $caracteres
+            (br_if $pronto (i32.ge_u (local.get $atual) (local.get $n)))
+            (local.set $fim (i32.add (local.get $atual) (i32.const 1)))
+            (block $inicio_encontrado
+              (loop $continuacao
+                (br_if $inicio_encontrado (i32.ge_u (local.get $fim) (local.get $n)))
+                (br_if $inicio_encontrado
+                  (i32.ne
+                    (i32.and (i32.load8_u offset=4 (i32.add (local.get $texto) (local.get $fim))) (i32.const 0xC0))
+                    (i32.const 0x80)))
+                (local.set $fim (i32.add (local.get $fim) (i32.const 1)))
+                (br $continuacao)))
+            (local.set $resultado
+              (call $pds_concatenar
+                (call $pds_concatenar
+                  (local.get $resultado)
+                  (call $pds_texto_de_bytes
+                    (i32.add (i32.add (local.get $texto) (i32.const 4)) (local.get $atual))
+                    (i32.sub (local.get $fim) (local.get $atual))))
+                (local.get $para)))
+            (local.set $atual (local.get $fim))
+            (br $caracteres)))
+        (return (local.get $resultado))))
+    (local.set $resultado (global.get $pds_txt_vazio))
+    (block $pronto
+      (loop $ocorrencias
+        (local.set $encontrado (call $pds_buscar (local.get $texto) (local.get $de) (local.get $atual)))
+        (br_if $pronto (i32.lt_s (local.get $encontrado) (i32.const 0)))
+        (local.set $resultado
+          (call $pds_concatenar
+            (call $pds_concatenar
+              (local.get $resultado)
+              (call $pds_texto_de_bytes
+                (i32.add (i32.add (local.get $texto) (i32.const 4)) (local.get $atual))
+                (i32.sub (local.get $encontrado) (local.get $atual))))
+            (local.get $para)))
+        (local.set $atual (i32.add (local.get $encontrado) (i32.load (local.get $de))))
+        (br $ocorrencias)))
+    (call $pds_concatenar
+      (local.get $resultado)
+      (call $pds_texto_de_bytes
+        (i32.add (i32.add (local.get $texto) (i32.const 4)) (local.get $atual))
+        (i32.sub (local.get $n) (local.get $atual)))))
+
+  ;; ------------------------------------------------------------------------
+  ;; Aritmética verificada
+  ;; ------------------------------------------------------------------------
+
+  (func $pds_dividir (param $a i64) (param $b i64) (param $arquivo i32) (param $linha i32) (param $coluna i32) (result i64)
+    (if (i64.eqz (local.get $b))
+      (then (call $pds_panico (global.get $pds_msg_divisao) (local.get $arquivo) (local.get $linha) (local.get $coluna))))
+    ;; i64::MIN / -1 dá a volta, como `wrapping_div` no interpretador
+    (if (i64.eq (local.get $b) (i64.const -1))
+      (then (return (i64.sub (i64.const 0) (local.get $a)))))
+    (i64.div_s (local.get $a) (local.get $b)))
+
+  (func $pds_resto (param $a i64) (param $b i64) (param $arquivo i32) (param $linha i32) (param $coluna i32) (result i64)
+    (if (i64.eqz (local.get $b))
+      (then (call $pds_panico (global.get $pds_msg_modulo) (local.get $arquivo) (local.get $linha) (local.get $coluna))))
+    (if (i64.eq (local.get $b) (i64.const -1))
+      (then (return (i64.const 0))))
+    (i64.rem_s (local.get $a) (local.get $b)))
+
+  (func $pds_dividir_flutuante (param $a f32) (param $b f32) (param $arquivo i32) (param $linha i32) (param $coluna i32) (result f32)
+    (if (f32.eq (local.get $b) (f32.const 0))
+      (then (call $pds_panico (global.get $pds_msg_divisao) (local.get $arquivo) (local.get $linha) (local.get $coluna))))
+    (f32.div (local.get $a) (local.get $b)))
+
+  (func $pds_dividir_duplo (param $a f64) (param $b f64) (param $arquivo i32) (param $linha i32) (param $coluna i32) (result f64)
+    (if (f64.eq (local.get $b) (f64.const 0))
+      (then (call $pds_panico (global.get $pds_msg_divisao) (local.get $arquivo) (local.get $linha) (local.get $coluna))))
+    (f64.div (local.get $a) (local.get $b)))
+
+  ;; O WebAssembly não tem resto de reais: o hospedeiro calcula (fmod, `%` do JavaScript).
+  (func $pds_resto_duplo (param $a f64) (param $b f64) (param $arquivo i32) (param $linha i32) (param $coluna i32) (result f64)
+    (if (f64.eq (local.get $b) (f64.const 0))
+      (then (call $pds_panico (global.get $pds_msg_modulo) (local.get $arquivo) (local.get $linha) (local.get $coluna))))
+    (call $pds_host_resto (local.get $a) (local.get $b)))
+
+  ;; ------------------------------------------------------------------------
+  ;; Arrays
+  ;; ------------------------------------------------------------------------
+
+  ;; Elementos: 0 inteiro, 1 flutuante, 2 duplo, 3 booleano, 4 texto, 5 referência.
+  (func $pds_tamanho_item (param $elemento i32) (result i32)
+    (select (i32.const 8) (i32.const 4)
+      (i32.or (i32.eqz (local.get $elemento)) (i32.eq (local.get $elemento) (i32.const 2)))))
+
+  ;; Array com os valores padrão (textos vazios, zeros e nulos).
+  (func $pds_array_novo (param $tamanho i64) (param $elemento i32) (param $arquivo i32) (param $linha i32) (param $coluna i32) (result i32)
+    (local $array i32)
+    (local $n i32)
+    (local $i i32)
+    (if (i64.lt_s (local.get $tamanho) (i64.const 0))
+      (then (call $pds_panico (global.get $pds_msg_tamanho) (local.get $arquivo) (local.get $linha) (local.get $coluna))))
+    (if (i64.gt_s (local.get $tamanho) (i64.const 0x10000000))
+      (then (call $pds_panico (global.get $pds_msg_memoria) (local.get $arquivo) (local.get $linha) (local.get $coluna))))
+    (local.set $n (i32.wrap_i64 (local.get $tamanho)))
+    (local.set $array
+      (call $pds_alocar
+        (i32.add (i32.const 16) (i32.mul (local.get $n) (call $pds_tamanho_item (local.get $elemento))))))
+    (i32.store (local.get $array) (global.get $pds_classe_array))
+    (i32.store offset=4 (local.get $array) (local.get $n))
+    (i32.store offset=8 (local.get $array) (local.get $elemento))
+    (if (i32.eq (local.get $elemento) (i32.const 4))
+      (then
+        (block $fim
+          (loop $itens
+            (br_if $fim (i32.ge_u (local.get $i) (local.get $n)))
+            (i32.store offset=16
+              (i32.add (local.get $array) (i32.shl (local.get $i) (i32.const 2)))
+              (global.get $pds_txt_vazio))
+            (local.set $i (i32.add (local.get $i) (i32.const 1)))
+            (br $itens)))))
+    (local.get $array))
+
+  ;; Deslocamento do item `indice` (somado a 16 no acesso), com as verificações do interpretador.
+  (func $pds_endereco (param $array i32) (param $indice i64) (param $tamanho_item i32) (param $arquivo i32) (param $linha i32) (param $coluna i32) (result i32)
+    (if (i32.eqz (local.get $array))
+      (then (call $pds_panico (global.get $pds_msg_indice_invalido) (local.get $arquivo) (local.get $linha) (local.get $coluna))))
+    (if (i64.lt_s (local.get $indice) (i64.const 0))
+      (then (call $pds_panico (global.get $pds_msg_indice_negativo) (local.get $arquivo) (local.get $linha) (local.get $coluna))))
+    (if (i64.ge_s (local.get $indice) (i64.extend_i32_u (i32.load offset=4 (local.get $array))))
+      (then (call $pds_panico (global.get $pds_msg_indice_fora) (local.get $arquivo) (local.get $linha) (local.get $coluna))))
+    (i32.add (local.get $array) (i32.mul (i32.wrap_i64 (local.get $indice)) (local.get $tamanho_item))))
+
+  ;; Sem verificações (--sem-verificacoes).
+  (func $pds_endereco_direto (param $array i32) (param $indice i64) (param $tamanho_item i32) (result i32)
+    (i32.add (local.get $array) (i32.mul (i32.wrap_i64 (local.get $indice)) (local.get $tamanho_item))))
+
+  (func $pds_texto_item (param $array i32) (param $i i32) (result i32)
+    (local $elemento i32)
+    (local $endereco i32)
+    (local.set $elemento (i32.load offset=8 (local.get $array)))
+    (local.set $endereco
+      (i32.add (i32.add (local.get $array) (i32.const 16))
+        (i32.mul (local.get $i) (call $pds_tamanho_item (local.get $elemento)))))
+    (block $referencia
+      (block $texto
+        (block $booleano
+          (block $duplo
+            (block $flutuante
+              (block $inteiro
+                (br_table $inteiro $flutuante $duplo $booleano $texto $referencia (local.get $elemento)))
+              (return (call $pds_texto_inteiro (i64.load (local.get $endereco)))))
+            (return (call $pds_texto_duplo (f64.promote_f32 (f32.load (local.get $endereco))))))
+          (return (call $pds_texto_duplo (f64.load (local.get $endereco)))))
+        (return (call $pds_texto_booleano (i32.load (local.get $endereco)))))
+      (return (call $pds_texto (i32.load (local.get $endereco)))))
+    (call $pds_texto_objeto (i32.load (local.get $endereco))))
+
+  (func $pds_texto_array (param $array i32) (result i32)
+    (local $texto i32)
+    (local $i i32)
+    (local.set $texto (global.get $pds_txt_abre))
+    (block $fim
+      (loop $itens
+        (br_if $fim (i32.ge_u (local.get $i) (i32.load offset=4 (local.get $array))))
+        (if (local.get $i)
+          (then (local.set $texto (call $pds_concatenar (local.get $texto) (global.get $pds_txt_virgula)))))
+        (local.set $texto
+          (call $pds_concatenar (local.get $texto) (call $pds_texto_item (local.get $array) (local.get $i))))
+        (local.set $i (i32.add (local.get $i) (i32.const 1)))
+        (br $itens)))
+    (call $pds_concatenar (local.get $texto) (global.get $pds_txt_fecha)))
+
+  ;; ------------------------------------------------------------------------
+  ;; Caixas (valores primitivos guardados em `objeto` e interfaces)
+  ;; ------------------------------------------------------------------------
+
+  (func $pds_caixa (param $classe i32) (result i32)
+    (local $caixa i32)
+    (local.set $caixa (call $pds_alocar (i32.const 16)))
+    (i32.store (local.get $caixa) (local.get $classe))
+    (local.get $caixa))
+
+  (func $pds_caixa_inteiro (param $valor i64) (result i32)
+    (local $caixa i32)
+    (local.set $caixa (call $pds_caixa (global.get $pds_classe_inteiro)))
+    (i64.store offset=8 (local.get $caixa) (local.get $valor))
+    (local.get $caixa))
+
+  (func $pds_caixa_flutuante (param $valor f32) (result i32)
+    (local $caixa i32)
+    (local.set $caixa (call $pds_caixa (global.get $pds_classe_flutuante)))
+    (f32.store offset=8 (local.get $caixa) (local.get $valor))
+    (local.get $caixa))
+
+  (func $pds_caixa_duplo (param $valor f64) (result i32)
+    (local $caixa i32)
+    (local.set $caixa (call $pds_caixa (global.get $pds_classe_duplo)))
+    (f64.store offset=8 (local.get $caixa) (local.get $valor))
+    (local.get $caixa))
+
+  (func $pds_caixa_booleano (param $valor i32) (result i32)
+    (local $caixa i32)
+    (local.set $caixa (call $pds_caixa (global.get $pds_classe_booleano)))
+    (i32.store offset=8 (local.get $caixa) (local.get $valor))
+    (local.get $caixa))
+
+  (func $pds_caixa_texto (param $valor i32) (result i32)
+    (local $caixa i32)
+    (if (i32.eqz (local.get $valor))
+      (then (return (i32.const 0))))
+    (local.set $caixa (call $pds_caixa (global.get $pds_classe_texto)))
+    (i32.store offset=8 (local.get $caixa) (local.get $valor))
+    (local.get $caixa))
+
+  (func $pds_abrir_caixa (param $objeto i32) (param $classe i32)
+    (if (i32.eqz (local.get $objeto))
+      (then
+        (call $pds_panico
+          (call $pds_concatenar
+            (call $pds_concatenar (global.get $pds_txt_conversao) (i32.load (local.get $classe)))
+            (global.get $pds_txt_valor_nulo))
+          (i32.const 0) (i32.const 0) (i32.const 0))))
+    (if (i32.ne (i32.load (local.get $objeto)) (local.get $classe))
+      (then
+        (call $pds_panico
+          (call $pds_concatenar
+            (call $pds_concatenar
+              (call $pds_concatenar (global.get $pds_txt_conversao) (i32.load (local.get $classe)))
+              (global.get $pds_txt_dois_pontos))
+            (i32.load (i32.load (local.get $objeto))))
+          (i32.const 0) (i32.const 0) (i32.const 0)))))
+
+  (func $pds_inteiro_de (param $objeto i32) (result i64)
+    (call $pds_abrir_caixa (local.get $objeto) (global.get $pds_classe_inteiro))
+    (i64.load offset=8 (local.get $objeto)))
+
+  (func $pds_flutuante_de (param $objeto i32) (result f32)
+    (call $pds_abrir_caixa (local.get $objeto) (global.get $pds_classe_flutuante))
+    (f32.load offset=8 (local.get $objeto)))
+
+  (func $pds_duplo_de (param $objeto i32) (result f64)
+    (call $pds_abrir_caixa (local.get $objeto) (global.get $pds_classe_duplo))
+    (f64.load offset=8 (local.get $objeto)))
+
+  (func $pds_booleano_de (param $objeto i32) (result i32)
+    (call $pds_abrir_caixa (local.get $objeto) (global.get $pds_classe_booleano))
+    (i32.load offset=8 (local.get $objeto)))
+
+  (func $pds_texto_de (param $objeto i32) (result i32)
+    (if (i32.eqz (local.get $objeto))
+      (then (return (i32.const 0))))
+    (call $pds_abrir_caixa (local.get $objeto) (global.get $pds_classe_texto))
+    (i32.load offset=8 (local.get $objeto)))
+
+  (func $pds_texto_caixa_inteiro (param $caixa i32) (result i32)
+    (call $pds_texto_inteiro (i64.load offset=8 (local.get $caixa))))
+
+  (func $pds_texto_caixa_flutuante (param $caixa i32) (result i32)
+    (call $pds_texto_duplo (f64.promote_f32 (f32.load offset=8 (local.get $caixa)))))
+
+  (func $pds_texto_caixa_duplo (param $caixa i32) (result i32)
+    (call $pds_texto_duplo (f64.load offset=8 (local.get $caixa))))
+
+  (func $pds_texto_caixa_booleano (param $caixa i32) (result i32)
+    (call $pds_texto_booleano (i32.load offset=8 (local.get $caixa))))
+
+  (func $pds_texto_caixa_texto (param $caixa i32) (result i32)
+    (call $pds_texto (i32.load offset=8 (local.get $caixa))))
+
+  ;; Igualdade de `objeto`: mesma referência, ou caixas com valores iguais.
+  (func $pds_objetos_iguais (param $a i32) (param $b i32) (result i32)
+    (local $classe i32)
+    (if (i32.eq (local.get $a) (local.get $b))
+      (then (return (i32.const 1))))
+    (if (i32.or (i32.eqz (local.get $a)) (i32.eqz (local.get $b)))
+      (then (return (i32.const 0))))
+    (local.set $classe (i32.load (local.get $a)))
+    (if (i32.ne (local.get $classe) (i32.load (local.get $b)))
+      (then (return (i32.const 0))))
+    (if (i32.eq (local.get $classe) (global.get $pds_classe_inteiro))
+      (then (return (i64.eq (i64.load offset=8 (local.get $a)) (i64.load offset=8 (local.get $b))))))
+    (if (i32.eq (local.get $classe) (global.get $pds_classe_flutuante))
+      (then (return (f32.eq (f32.load offset=8 (local.get $a)) (f32.load offset=8 (local.get $b))))))
+    (if (i32.eq (local.get $classe) (global.get $pds_classe_duplo))
+      (then (return (f64.eq (f64.load offset=8 (local.get $a)) (f64.load offset=8 (local.get $b))))))
+    (if (i32.eq (local.get $classe) (global.get $pds_classe_booleano))
+      (then (return (i32.eq (i32.load offset=8 (local.get $a)) (i32.load offset=8 (local.get $b))))))
+    (if (i32.eq (local.get $classe) (global.get $pds_classe_texto))
+      (then (return (call $pds_textos_iguais (i32.load offset=8 (local.get $a)) (i32.load offset=8 (local.get $b))))))
+    (i32.const 0))
+
+  ;; Texto exibido por imprima: a função de texto da classe, ou "Objeto<Classe>".
+  (func $pds_texto_objeto (param $objeto i32) (result i32)
+    (local $classe i32)
+    (if (i32.eqz (local.get $objeto))
+      (then (return (global.get $pds_txt_nulo))))
+    (local.set $classe (i32.load (local.get $objeto)))
+    (if (i32.ge_s (i32.load offset=8 (local.get $classe)) (i32.const 0))
+      (then
+        (return
+          (call_indirect $pds_funcoes (type $pds_texto_fn)
+            (local.get $objeto)
+            (i32.load offset=8 (local.get $classe))))))
+    (call $pds_concatenar
+      (call $pds_concatenar (global.get $pds_txt_objeto) (i32.load (local.get $classe)))
+      (global.get $pds_txt_maior)))
+
+  ;; ------------------------------------------------------------------------
+  ;; Console
+  ;; ------------------------------------------------------------------------
+
+  (func $pds_escrever (param $texto i32)
+    (call $pds_host_escrever (i32.add (local.get $texto) (i32.const 4)) (i32.load (local.get $texto))))
+
+  (func $pds_imprimir (param $texto i32)
+    (call $pds_escrever (local.get $texto))
+    (call $pds_escrever (global.get $pds_txt_quebra)))
+
+  ;; Próxima linha da entrada sem o fim de linha; "" no fim da entrada.
+  (func $pds_ler_linha (result i32)
+    (local $tamanho i32)
+    (local $texto i32)
+    (local.set $tamanho (call $pds_host_ler_linha))
+    (if (i32.lt_s (local.get $tamanho) (i32.const 0))
+      (then (return (global.get $pds_txt_vazio))))
+    (local.set $texto (call $pds_texto_novo (local.get $tamanho)))
+    (call $pds_host_copiar_linha (i32.add (local.get $texto) (i32.const 4)))
+    (local.get $texto))
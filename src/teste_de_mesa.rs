@@ -0,0 +1,326 @@
+// src/teste_de_mesa.rs
+
+//! Teste de mesa: rastreamento da execução linha a linha.
+//!
+//! O interpretador chama [`TesteDeMesa::linha`] a cada instrução `LINE` executada.
+//! A linha anterior só é registrada quando a próxima começa (ou quando o frame
+//! termina), de modo que cada passo mostra os valores *após* a execução da linha,
+//! como na tabela feita à mão em sala de aula.
+
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatoTabela {
+    Texto,
+    Csv,
+    Markdown,
+}
+
+impl FormatoTabela {
+    pub fn de_nome(nome: &str) -> Option<Self> {
+        match nome.to_ascii_lowercase().as_str() {
+            "texto" | "txt" => Some(Self::Texto),
+            "csv" => Some(Self::Csv),
+            "md" | "markdown" => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+
+    /// Deduz o formato pela extensão do arquivo de saída (padrão: texto).
+    pub fn de_caminho(caminho: &Path) -> Self {
+        caminho
+            .extension()
+            .and_then(|e| e.to_str())
+            .and_then(Self::de_nome)
+            .unwrap_or(Self::Texto)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Passo {
+    pub funcao: String,
+    pub arquivo: String,
+    pub linha: usize,
+    pub valores: HashMap<String, String>,
+}
+
+#[derive(Debug, Default)]
+pub struct TesteDeMesa {
+    funcao: Option<String>,
+    limite: Option<usize>,
+    colunas: Vec<String>,
+    passos: Vec<Passo>,
+    // Uma entrada por frame ativo: (funcao, arquivo, linha) ainda não registrada
+    pendentes: Vec<Option<(String, String, usize)>>,
+    truncado: bool,
+}
+
+impl TesteDeMesa {
+    pub fn new(funcao: Option<String>, limite: Option<usize>) -> Self {
+        Self {
+            funcao,
+            limite,
+            ..Default::default()
+        }
+    }
+
+    /// Converte um code_id da VM (ex.: `method:Conta::Depositar`) em nome legível.
+    pub fn nome_funcao(code_id: &str) -> String {
+        if code_id.starts_with("global") {
+            return "global".to_string();
+        }
+        let nome = code_id.split_once(':').map(|(_, n)| n).unwrap_or(code_id);
+        nome.replace("::", ".")
+    }
+
+    /// Indica se o frame identificado por `code_id` deve aparecer na tabela.
+    pub fn aceita(&self, code_id: &str) -> bool {
+        match &self.funcao {
+            None => true,
+            Some(filtro) => {
+                let nome = Self::nome_funcao(code_id);
+                nome == *filtro || nome.rsplit('.').next() == Some(filtro.as_str())
+            }
+        }
+    }
+
+    pub fn passos(&self) -> &[Passo] {
+        &self.passos
+    }
+
+    pub fn truncado(&self) -> bool {
+        self.truncado
+    }
+
+    pub fn entrar_frame(&mut self) {
+        self.pendentes.push(None);
+    }
+
+    /// Encerra o frame atual registrando a última linha executada nele.
+    pub fn sair_frame(&mut self, valores: Vec<(String, String)>) {
+        if let Some(Some((funcao, arquivo, linha))) = self.pendentes.pop() {
+            self.registrar(funcao, arquivo, linha, valores);
+        }
+    }
+
+    /// Início de uma nova linha: registra a anterior com os valores atuais.
+    pub fn linha(
+        &mut self,
+        code_id: &str,
+        arquivo: &str,
+        linha: usize,
+        valores: Vec<(String, String)>,
+    ) {
+        if self.pendentes.is_empty() {
+            self.pendentes.push(None);
+        }
+        let nova = Some((Self::nome_funcao(code_id), arquivo.to_string(), linha));
+        if let Some(Some((funcao, arquivo, anterior))) =
+            self.pendentes.last_mut().map(|p| std::mem::replace(p, nova))
+        {
+            self.registrar(funcao, arquivo, anterior, valores);
+        }
+    }
+
+    fn registrar(
+        &mut self,
+        funcao: String,
+        arquivo: String,
+        linha: usize,
+        valores: Vec<(String, String)>,
+    ) {
+        if self.limite.is_some_and(|max| self.passos.len() >= max) {
+            self.truncado = true;
+            return;
+        }
+        for (nome, _) in &valores {
+            if !self.colunas.contains(nome) {
+                self.colunas.push(nome.clone());
+            }
+        }
+        self.passos.push(Passo {
+            funcao,
+            arquivo,
+            linha,
+            valores: valores.into_iter().collect(),
+        });
+    }
+
+    pub fn renderizar(&self, formato: FormatoTabela) -> String {
+        // Com mais de um arquivo, o número da linha sozinho é ambíguo
+        let varios_arquivos = self
+            .passos
+            .iter()
+            .any(|p| p.arquivo != self.passos[0].arquivo);
+        let mut cabecalho = vec!["passo".to_string(), "função".to_string()];
+        if varios_arquivos {
+            cabecalho.push("arquivo".to_string());
+        }
+        cabecalho.push("linha".to_string());
+        cabecalho.extend(self.colunas.iter().cloned());
+
+        let linhas: Vec<Vec<String>> = self
+            .passos
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let mut l = vec![(i + 1).to_string(), p.funcao.clone()];
+                if varios_arquivos {
+                    l.push(p.arquivo.clone());
+                }
+                l.push(p.linha.to_string());
+                l.extend(
+                    self.colunas
+                        .iter()
+                        .map(|c| p.valores.get(c).cloned().unwrap_or_else(|| "-".to_string())),
+                );
+                l
+            })
+            .collect();
+
+        let mut saida = match formato {
+            FormatoTabela::Texto => renderizar_texto(&cabecalho, &linhas),
+            FormatoTabela::Csv => renderizar_csv(&cabecalho, &linhas),
+            FormatoTabela::Markdown => renderizar_markdown(&cabecalho, &linhas),
+        };
+        if self.truncado && formato != FormatoTabela::Csv {
+            let _ = writeln!(
+                saida,
+                "(tabela truncada em {} passos)",
+                self.limite.unwrap_or(self.passos.len())
+            );
+        }
+        saida
+    }
+}
+
+fn renderizar_texto(cabecalho: &[String], linhas: &[Vec<String>]) -> String {
+    let mut larguras: Vec<usize> = cabecalho.iter().map(|c| c.chars().count()).collect();
+    for l in linhas {
+        for (i, celula) in l.iter().enumerate() {
+            larguras[i] = larguras[i].max(celula.chars().count());
+        }
+    }
+    let formatar = |celulas: &[String]| {
+        celulas
+            .iter()
+            .zip(&larguras)
+            .map(|(c, w)| format!("{:<w$}", c, w = *w))
+            .collect::<Vec<_>>()
+            .join(" | ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut saida = String::new();
+    let _ = writeln!(saida, "{}", formatar(cabecalho));
+    let _ = writeln!(
+        saida,
+        "{}",
+        larguras
+            .iter()
+            .map(|w| "-".repeat(*w))
+            .collect::<Vec<_>>()
+            .join("-+-")
+    );
+    for l in linhas {
+        let _ = writeln!(saida, "{}", formatar(l));
+    }
+    saida
+}
+
+fn renderizar_csv(cabecalho: &[String], linhas: &[Vec<String>]) -> String {
+    let escapar = |c: &String| {
+        if c.contains([',', '"', '\n']) {
+            format!("\"{}\"", c.replace('"', "\"\""))
+        } else {
+            c.clone()
+        }
+    };
+    let mut saida = String::new();
+    for l in std::iter::once(cabecalho).chain(linhas.iter().map(|l| l.as_slice())) {
+        let _ = writeln!(saida, "{}", l.iter().map(escapar).collect::<Vec<_>>().join(","));
+    }
+    saida
+}
+
+fn renderizar_markdown(cabecalho: &[String], linhas: &[Vec<String>]) -> String {
+    let escapar = |c: &String| c.replace('|', "\\|");
+    let mut saida = String::new();
+    let _ = writeln!(
+        saida,
+        "| {} |",
+        cabecalho.iter().map(escapar).collect::<Vec<_>>().join(" | ")
+    );
+    let _ = writeln!(saida, "|{}", "---|".repeat(cabecalho.len()));
+    for l in linhas {
+        let _ = writeln!(
+            saida,
+            "| {} |",
+            l.iter().map(escapar).collect::<Vec<_>>().join(" | ")
+        );
+    }
+    saida
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vals(pares: &[(&str, &str)]) -> Vec<(String, String)> {
+        pares
+            .iter()
+            .map(|(n, v)| (n.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn registra_linha_anterior_com_valores_posteriores() {
+        let mut t = TesteDeMesa::new(None, None);
+        t.entrar_frame();
+        t.linha("main:Principal", "a.pr", 3, vals(&[]));
+        t.linha("main:Principal", "a.pr", 4, vals(&[("x", "1")]));
+        t.sair_frame(vals(&[("x", "1"), ("y", "2")]));
+
+        let passos = t.passos();
+        assert_eq!(passos.len(), 2);
+        assert_eq!(passos[0].linha, 3);
+        assert_eq!(passos[0].valores.get("x").map(String::as_str), Some("1"));
+        assert_eq!(passos[1].linha, 4);
+        assert_eq!(passos[1].valores.get("y").map(String::as_str), Some("2"));
+
+        let csv = t.renderizar(FormatoTabela::Csv);
+        assert_eq!(
+            csv,
+            "passo,função,linha,x,y\n1,Principal,3,1,-\n2,Principal,4,1,2\n"
+        );
+    }
+
+    #[test]
+    fn filtro_de_funcao_e_limite() {
+        let t = TesteDeMesa::new(Some("Depositar".into()), Some(1));
+        assert!(t.aceita("method:Banco.Conta::Depositar"));
+        assert!(!t.aceita("main:Principal"));
+
+        let mut t = TesteDeMesa::new(None, Some(1));
+        t.linha("global", "a.pr", 1, vals(&[]));
+        t.linha("global", "a.pr", 2, vals(&[]));
+        t.linha("global", "a.pr", 3, vals(&[]));
+        assert_eq!(t.passos().len(), 1);
+        assert!(t.truncado());
+    }
+
+    #[test]
+    fn coluna_de_arquivo_com_varios_arquivos() {
+        let mut t = TesteDeMesa::new(None, None);
+        t.linha("global", "a.pr", 1, vals(&[]));
+        t.linha("global", "b.pr", 1, vals(&[]));
+        t.linha("global", "b.pr", 2, vals(&[]));
+        assert_eq!(
+            t.renderizar(FormatoTabela::Csv),
+            "passo,função,arquivo,linha\n1,global,a.pr,1\n2,global,b.pr,1\n"
+        );
+    }
+}
@@ -0,0 +1,224 @@
+// src/testes.rs
+
+//! Suporte ao framework de testes embutido (`[Teste]` + `compilador testar`).
+//!
+//! - O prelúdio declara a classe estática `Afirmar`, cujos métodos são nativos do interpretador.
+//! - O gerador de bytecode marca cada função/método `[Teste]` com `DEFINE_TEST`.
+//! - O interpretador (`--testar`) executa cada teste isolado e monta um [`RelatorioTestes`].
+//!
+//! O binário `compilador` também declara este módulo, mas usa só o prelúdio e [`eh_teste`]: os
+//! itens do relatório, usados apenas pelo interpretador, levam `#[allow(dead_code)]`.
+
+use std::fmt::Write as _;
+use std::time::Duration;
+
+use logos::Logos;
+
+use crate::ast;
+
+pub const ATRIBUTO_TESTE: &str = "Teste";
+
+/// Declarações injetadas pelo `compilador testar` antes dos arquivos do usuário.
+pub const PRELUDIO_TESTES: &str = r#"
+publico estática classe Afirmar {
+    [Nativo("Afirmar::Igual")]
+    publico estática vazio Igual(objeto esperado, objeto obtido) { }
+
+    [Nativo("Afirmar::Verdadeiro")]
+    publico estática vazio Verdadeiro(booleano condicao) { }
+
+    [Nativo("Afirmar::Lanca")]
+    publico estática vazio Lanca(texto alvo) { }
+}
+"#;
+
+pub fn eh_teste(attributes: &[ast::Attribute]) -> bool {
+    attributes.iter().any(|a| a.name == ATRIBUTO_TESTE)
+}
+
+/// Analisa o prelúdio de testes (não falha: o texto é fixo e coberto por testes).
+pub fn preludio() -> ast::Programa {
+    let tokens: Vec<_> = crate::lexer::Token::lexer(PRELUDIO_TESTES)
+        .spanned()
+        .filter_map(|(tok, span)| tok.ok().map(|t| (span.start, t, span.end)))
+        .collect();
+    crate::parser::ArquivoParser::new()
+        .parse(None, tokens)
+        .expect("prelúdio de testes inválido")
+}
+
+/// Diferença linha a linha entre o valor esperado e o obtido, no estilo `-`/`+`.
+#[allow(dead_code)]
+pub fn formatar_diferenca(esperado: &str, obtido: &str) -> String {
+    let mut saida = String::new();
+    let _ = writeln!(saida, "  esperado: {}", esperado.lines().next().unwrap_or(""));
+    let _ = writeln!(saida, "  obtido:   {}", obtido.lines().next().unwrap_or(""));
+    let (esp, obt): (Vec<&str>, Vec<&str>) = (esperado.lines().collect(), obtido.lines().collect());
+    if esp.len() > 1 || obt.len() > 1 {
+        let _ = writeln!(saida, "  diferença:");
+        for i in 0..esp.len().max(obt.len()) {
+            match (esp.get(i), obt.get(i)) {
+                (Some(e), Some(o)) if e == o => {
+                    let _ = writeln!(saida, "      {}", e);
+                }
+                (e, o) => {
+                    if let Some(e) = e {
+                        let _ = writeln!(saida, "    - {}", e);
+                    }
+                    if let Some(o) = o {
+                        let _ = writeln!(saida, "    + {}", o);
+                    }
+                }
+            }
+        }
+    }
+    saida
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResultadoTeste {
+    pub nome: String,
+    pub duracao: Duration,
+    /// `None` quando o teste passou; caso contrário, a mensagem de falha.
+    pub falha: Option<String>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, Default)]
+pub struct RelatorioTestes {
+    pub suite: String,
+    pub resultados: Vec<ResultadoTeste>,
+}
+
+#[allow(dead_code)]
+impl RelatorioTestes {
+    pub fn new(suite: impl Into<String>) -> Self {
+        Self {
+            suite: suite.into(),
+            resultados: Vec::new(),
+        }
+    }
+
+    pub fn aprovados(&self) -> usize {
+        self.resultados.iter().filter(|r| r.falha.is_none()).count()
+    }
+
+    pub fn falhas(&self) -> usize {
+        self.resultados.len() - self.aprovados()
+    }
+
+    pub fn duracao_total(&self) -> Duration {
+        self.resultados.iter().map(|r| r.duracao).sum()
+    }
+
+    /// Linha de resultado de um teste, impressa assim que ele termina.
+    pub fn formatar_resultado(r: &ResultadoTeste) -> String {
+        let status = if r.falha.is_some() { "FALHOU" } else { "ok" };
+        format!("teste {} ... {} ({:.2?})", r.nome, status, r.duracao)
+    }
+
+    pub fn resumo(&self) -> String {
+        let mut saida = String::new();
+        let falhas: Vec<_> = self.resultados.iter().filter(|r| r.falha.is_some()).collect();
+        if !falhas.is_empty() {
+            let _ = writeln!(saida, "\nfalhas:");
+            for r in &falhas {
+                let _ = writeln!(saida, "\n---- {} ----", r.nome);
+                let _ = writeln!(saida, "{}", r.falha.as_deref().unwrap_or("").trim_end());
+            }
+        }
+        let _ = writeln!(
+            saida,
+            "\nresultado: {}. {} aprovado(s); {} falha(s); {} teste(s) em {:.2?}",
+            if falhas.is_empty() { "ok" } else { "FALHOU" },
+            self.aprovados(),
+            self.falhas(),
+            self.resultados.len(),
+            self.duracao_total()
+        );
+        saida
+    }
+
+    /// Relatório no formato JUnit XML (consumido por servidores de CI).
+    pub fn junit_xml(&self) -> String {
+        let mut xml = String::new();
+        let _ = writeln!(xml, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+        let _ = writeln!(
+            xml,
+            r#"<testsuite name="{}" tests="{}" failures="{}" errors="0" time="{:.3}">"#,
+            escapar_xml(&self.suite),
+            self.resultados.len(),
+            self.falhas(),
+            self.duracao_total().as_secs_f64()
+        );
+        for r in &self.resultados {
+            let _ = write!(
+                xml,
+                r#"  <testcase name="{}" classname="{}" time="{:.3}""#,
+                escapar_xml(&r.nome),
+                escapar_xml(&self.suite),
+                r.duracao.as_secs_f64()
+            );
+            match &r.falha {
+                None => {
+                    let _ = writeln!(xml, "/>");
+                }
+                Some(msg) => {
+                    let primeira = msg.lines().next().unwrap_or("");
+                    let _ = writeln!(xml, ">");
+                    let _ = writeln!(
+                        xml,
+                        r#"    <failure message="{}">{}</failure>"#,
+                        escapar_xml(primeira),
+                        escapar_xml(msg)
+                    );
+                    let _ = writeln!(xml, "  </testcase>");
+                }
+            }
+        }
+        let _ = writeln!(xml, "</testsuite>");
+        xml
+    }
+}
+
+#[allow(dead_code)]
+fn escapar_xml(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn preludio_declara_afirmar() {
+        let programa = preludio();
+        assert!(programa.declaracoes.iter().any(|d| matches!(
+            d,
+            ast::Declaracao::DeclaracaoClasse(c) if c.nome == "Afirmar" && c.metodos.len() == 3
+        )));
+    }
+
+    #[test]
+    fn junit_inclui_falhas() {
+        let mut rel = RelatorioTestes::new("calc");
+        rel.resultados.push(ResultadoTeste {
+            nome: "TesteSoma".into(),
+            duracao: Duration::from_millis(2),
+            falha: None,
+        });
+        rel.resultados.push(ResultadoTeste {
+            nome: "TesteDivisao".into(),
+            duracao: Duration::from_millis(1),
+            falha: Some("Afirmar.Igual falhou\n  esperado: 1 < 2".into()),
+        });
+        let xml = rel.junit_xml();
+        assert!(xml.contains(r#"tests="2" failures="1""#));
+        assert!(xml.contains(r#"<testcase name="TesteSoma" classname="calc" time="0.002"/>"#));
+        assert!(xml.contains("esperado: 1 &lt; 2</failure>"));
+    }
+}
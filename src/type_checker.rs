@@ -1,21 +1,32 @@
 use crate::ast;
 use crate::ast::*;
 use crate::error::{ErroCompilador, TipoErro};
-use crate::library_loader::{self, LibSimbolo};
+use crate::library_loader::{
+    self, LibAcesso, LibClasse, LibEnum, LibFuncao, LibInterface, LibMetodo, LibParametro,
+    LibSimbolo,
+};
 use std::collections::HashMap;
 
-// Helper function to convert string to ast::Tipo
-pub fn string_para_tipo(s: &str) -> ast::Tipo {
-    match s {
-        "inteiro" => ast::Tipo::Inteiro,
-        "texto" => ast::Tipo::Texto,
-        "booleano" => ast::Tipo::Booleano,
-        "flutuante" => ast::Tipo::Flutuante,
-        "duplo" => ast::Tipo::Duplo,
-        "decimal" => ast::Tipo::Decimal,
-        "vazio" => ast::Tipo::Vazio,
-        "objeto" => ast::Tipo::Objeto,
-        _ => ast::Tipo::Classe(s.to_string()),
+/// Nome de um tipo do manifesto sem os argumentos (`Util.Caixa<texto>` → `Util.Caixa`).
+fn nome_base_externa(tipo: &str) -> String {
+    tipo.split('<').next().unwrap_or(tipo).to_string()
+}
+
+/// Liga os parâmetros de tipo que aparecem em `param` aos tipos correspondentes em `arg`.
+fn deduzir_genericos(param: &Tipo, arg: &Tipo, subst: &mut HashMap<String, Tipo>) {
+    match (param, arg) {
+        (Tipo::Generico(g), _) if *arg != Tipo::Inferido => {
+            subst.entry(g.clone()).or_insert_with(|| arg.clone());
+        }
+        (Tipo::Lista(p), Tipo::Lista(a)) | (Tipo::Opcional(p), Tipo::Opcional(a)) => {
+            deduzir_genericos(p, a, subst)
+        }
+        (Tipo::Aplicado { args: ps, .. }, Tipo::Aplicado { args: as_, .. }) => {
+            for (p, a) in ps.iter().zip(as_) {
+                deduzir_genericos(p, a, subst);
+            }
+        }
+        _ => {}
     }
 }
 
@@ -34,6 +45,18 @@ pub struct VerificadorTipos<'a> {
     loaded_lib_declarations: Vec<Declaracao>,
     pub generic_scope: Vec<std::collections::HashSet<String>>,
     stdlib_namespaces: std::collections::HashSet<String>,
+    // Classes e funções (FQN) cujos corpos já foram verificados numa compilação anterior
+    corpos_verificados: std::collections::HashSet<String>,
+}
+
+/// Membro encontrado na hierarquia de uma classe externa.
+struct MembroExterno<T> {
+    membro: T,
+    /// Classe que declara o membro
+    dono: String,
+    /// Parâmetros de tipo do dono e os argumentos ligados a eles
+    genericos: Vec<String>,
+    subst: HashMap<String, Tipo>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -62,6 +85,7 @@ impl<'a> VerificadorTipos<'a> {
             loaded_lib_declarations: Vec::new(),
             generic_scope: Vec::new(),
             stdlib_namespaces: std::collections::HashSet::new(),
+            corpos_verificados: std::collections::HashSet::new(),
         };
         vt.inicializar_tipos_integrados();
         vt
@@ -111,6 +135,13 @@ impl<'a> VerificadorTipos<'a> {
         self.stdlib_namespaces.insert(ns.to_string());
     }
 
+    /// Registra uma classe ou função (FQN) cujo arquivo e dependências não mudaram desde a
+    /// última compilação bem-sucedida: a declaração entra no programa, mas os corpos dos seus
+    /// métodos não são verificados de novo (ver `incremental`).
+    pub fn registrar_corpo_verificado(&mut self, fqn: &str) {
+        self.corpos_verificados.insert(fqn.to_string());
+    }
+
     pub fn eh_classe_stdlib(&self, fqn: &str) -> bool {
         let ns = self.get_namespace_from_full_name(fqn);
         self.stdlib_namespaces.contains(&ns)
@@ -299,10 +330,11 @@ impl<'a> VerificadorTipos<'a> {
                         return (Generico(n.clone()), vec![]);
                     }
                 }
-                (
-                    Classe(self.resolver_nome_classe(n, namespace_atual)),
-                    vec![],
-                )
+                let fqn = self.resolver_nome_classe(n, namespace_atual);
+                if self.enum_externo(&fqn).is_some() {
+                    return (Enum(fqn), vec![]);
+                }
+                (Classe(fqn), vec![])
             }
             Enum(n) => (Enum(self.resolver_nome_enum(n, namespace_atual)), vec![]),
             Aplicado { nome, args } => {
@@ -314,6 +346,14 @@ impl<'a> VerificadorTipos<'a> {
                 }
                 let fqn_cls = self.resolver_nome_classe(nome, namespace_atual);
                 let fqn_iface = self.resolver_nome_interface(nome, namespace_atual);
+                // Classes e interfaces genéricas de bibliotecas: (nome completo, parâmetros)
+                let externo = self
+                    .classe_externa(&fqn_cls)
+                    .map(|c| (c.fqn.clone(), c.genericos.len()))
+                    .or_else(|| {
+                        self.interface_externa(&fqn_iface)
+                            .map(|i| (i.fqn.clone(), i.genericos.len()))
+                    });
                 let (is_class, is_iface, resolved_name) = (
                     self.classes.contains_key(&fqn_cls),
                     self.interfaces.contains_key(&fqn_iface),
@@ -321,11 +361,34 @@ impl<'a> VerificadorTipos<'a> {
                         fqn_cls.clone()
                     } else if self.interfaces.contains_key(&fqn_iface) {
                         fqn_iface.clone()
+                    } else if let Some((fqn, _)) = &externo {
+                        fqn.clone()
                     } else {
                         nome.clone()
                     },
                 );
                 let mut erros: Vec<ErroCompilador> = Vec::new();
+                if let (false, false, Some((fqn, expected))) = (is_class, is_iface, &externo) {
+                    if *expected == 0 {
+                        erros.push(ErroCompilador::novo(
+                            TipoErro::Semântico,
+                            format!(
+                                "Tipo '{}' não é genérico, mas foi usado como '{}' com argumentos.",
+                                fqn, nome
+                            ),
+                        ));
+                    } else if *expected != args.len() {
+                        erros.push(ErroCompilador::novo(
+                            TipoErro::Semântico,
+                            format!(
+                                "Aridade genérica incorreta para '{}': esperados {}, recebidos {}.",
+                                fqn,
+                                expected,
+                                args.len()
+                            ),
+                        ));
+                    }
+                }
                 if is_class {
                     if let Some(decl) = self.classes.get(&fqn_cls) {
                         let expected = decl.generic_params.len();
@@ -469,7 +532,7 @@ impl<'a> VerificadorTipos<'a> {
     }
 
     fn is_interface_type(&self, nome: &str) -> bool {
-        self.interfaces.contains_key(nome)
+        self.interfaces.contains_key(nome) || self.interface_externa(nome).is_some()
     }
 
     fn class_implements_interface(&self, class_fqn: &str, iface_fqn: &str) -> bool {
@@ -515,6 +578,17 @@ impl<'a> VerificadorTipos<'a> {
                     Tipo::Aplicado { nome, .. } => self.resolver_nome_classe(nome, &ns),
                     _ => String::new(),
                 });
+            } else if let Some(lib_classe) = self.classe_externa(&cls) {
+                let implementa = lib_classe.interfaces.iter().any(|i| {
+                    matches!(
+                        self.tipo_externo(i, &lib_classe.genericos),
+                        Tipo::Aplicado { nome, args } if nome == iface_fqn && args == iface_args
+                    )
+                });
+                if implementa {
+                    return true;
+                }
+                current = lib_classe.nome_pai.as_ref().map(|p| nome_base_externa(p));
             } else {
                 break;
             }
@@ -550,6 +624,9 @@ impl<'a> VerificadorTipos<'a> {
                     Tipo::Aplicado { nome, .. } => self.resolver_nome_classe(nome, &ns),
                     _ => String::new(),
                 });
+            } else if let Some(lib_classe) = self.classe_externa(&cls) {
+                set.extend(lib_classe.interfaces.iter().map(|i| nome_base_externa(i)));
+                current = lib_classe.nome_pai.as_ref().map(|p| nome_base_externa(p));
             } else {
                 break;
             }
@@ -588,6 +665,15 @@ impl<'a> VerificadorTipos<'a> {
                     current = Some(parent_fqn);
                     continue;
                 }
+            } else if let Some(lib_classe) = self.classe_externa(&cls_fqn) {
+                if let Some(pai) = &lib_classe.nome_pai {
+                    let pai = nome_base_externa(pai);
+                    if pai == base {
+                        return true;
+                    }
+                    current = Some(pai);
+                    continue;
+                }
             }
             break;
         }
@@ -779,6 +865,10 @@ impl<'a> VerificadorTipos<'a> {
                             )));
                         }
                     }
+                } else if let Some(iface) = self.interface_externa(&iface_fqn) {
+                    let mut erros =
+                        self.validar_interface_externa(fqn, classe, &resolved_methods, iface);
+                    self.erros.append(&mut erros);
                 } else {
                     self.erros.push(ErroCompilador::novo(
                         TipoErro::Semântico,
@@ -813,7 +903,10 @@ impl<'a> VerificadorTipos<'a> {
                 return fqn;
             }
         }
-        nome_iface.to_string()
+        self.resolver_nome_externo(nome_iface, namespace_atual, |s| {
+            matches!(s, LibSimbolo::Interface(_))
+        })
+        .unwrap_or_else(|| nome_iface.to_string())
     }
 
     fn assinatura_metodo(&self, m: &'a ast::MetodoClasse) -> (Option<Tipo>, Vec<Tipo>) {
@@ -947,13 +1040,18 @@ impl<'a> VerificadorTipos<'a> {
                     }
                 }
                 parent_effective = Some(parent_name.clone());
+            } else if self.classe_externa(&parent_name).is_some() {
+                // Base de uma biblioteca: os membros dela são consultados no manifesto
+                parent_effective = Some(parent_name.clone());
             } else {
                 // Não é classe — pode ser uma interface listada após ':' (estilo C#)
                 let iface_fqn = self.resolver_nome_interface(
                     &parent_name_simple,
                     &self.get_namespace_from_full_name(class_name),
                 );
-                if self.interfaces.contains_key(&iface_fqn) {
+                if self.interfaces.contains_key(&iface_fqn)
+                    || self.interface_externa(&iface_fqn).is_some()
+                {
                     interfaces_final.push(parent_name_simple.clone());
                 // Sem classe pai efetiva
                 } else {
@@ -1024,10 +1122,69 @@ impl<'a> VerificadorTipos<'a> {
         } else if let Some(class_decl) = self.classes.get(class_name) {
             class_decl.eh_estatica
         } else {
-            false
+            self.classe_externa(class_name).is_some_and(|c| c.eh_estatica)
         }
     }
 
+    /// Escreve um tipo como no manifesto de um `.pbl`: sem espaços e com os nomes de classes,
+    /// interfaces e enums completos, para que valham fora do namespace que os declara.
+    pub fn tipo_no_manifesto(&self, tipo: &Tipo, namespace_atual: &str, genericos: &[String]) -> String {
+        library_loader::tipo_para_manifesto(&self.qualificar_tipo(tipo, namespace_atual, genericos))
+    }
+
+    fn qualificar_tipo(&self, tipo: &Tipo, ns: &str, genericos: &[String]) -> Tipo {
+        let qualificar_nome = |nome: &String| {
+            let classe = self.resolver_nome_classe(nome, ns);
+            if self.classes.contains_key(&classe) || self.classe_externa(&classe).is_some() {
+                return classe;
+            }
+            let iface = self.resolver_nome_interface(nome, ns);
+            if self.interfaces.contains_key(&iface) || self.interface_externa(&iface).is_some() {
+                return iface;
+            }
+            let en = self.resolver_nome_enum(nome, ns);
+            if self.enums.contains_key(&en) || self.enum_externo(&en).is_some() {
+                return en;
+            }
+            nome.clone()
+        };
+        match tipo {
+            Tipo::Classe(n) | Tipo::Generico(n) if genericos.contains(n) => {
+                Tipo::Generico(n.clone())
+            }
+            Tipo::Classe(n) | Tipo::Enum(n) => Tipo::Classe(qualificar_nome(n)),
+            Tipo::Aplicado { nome, args } => Tipo::Aplicado {
+                nome: qualificar_nome(nome),
+                args: args
+                    .iter()
+                    .map(|a| self.qualificar_tipo(a, ns, genericos))
+                    .collect(),
+            },
+            Tipo::Lista(t) => Tipo::Lista(Box::new(self.qualificar_tipo(t, ns, genericos))),
+            Tipo::Opcional(t) => Tipo::Opcional(Box::new(self.qualificar_tipo(t, ns, genericos))),
+            Tipo::Funcao(params, ret) => Tipo::Funcao(
+                params
+                    .iter()
+                    .map(|p| self.qualificar_tipo(p, ns, genericos))
+                    .collect(),
+                Box::new(self.qualificar_tipo(ret, ns, genericos)),
+            ),
+            outro => outro.clone(),
+        }
+    }
+
+    /// Propriedade ou campo `estática` de uma classe externa (ou de uma base dela).
+    pub fn eh_membro_estatico_externo(&self, class_name: &str, membro: &str) -> bool {
+        self.membro_externo(class_name, &[], |c| {
+            c.propriedades
+                .iter()
+                .find(|p| p.nome == membro)
+                .map(|p| p.eh_estatica)
+                .or_else(|| c.campos.iter().find(|f| f.nome == membro).map(|f| f.eh_estatica))
+        })
+        .is_some_and(|m| m.membro)
+    }
+
     fn get_namespace_from_full_name(&self, full_name: &str) -> String {
         if let Some(pos) = full_name.rfind('.') {
             full_name[..pos].to_string()
@@ -1055,6 +1212,10 @@ impl<'a> VerificadorTipos<'a> {
         }
         // NOVO: Consultar biblioteca externa primeiro
         if let Some(bib) = &self.biblioteca_externa {
+            let fqn = format!("{}.{}", namespace_atual, nome_classe);
+            if !namespace_atual.is_empty() && bib.simbolos.contains_key(&fqn) {
+                return fqn;
+            }
             for using_path in &self.usings {
                 let fqn = format!("{}.{}", using_path, nome_classe);
                 if bib.simbolos.contains_key(&fqn) {
@@ -1103,7 +1264,10 @@ impl<'a> VerificadorTipos<'a> {
                 return nome_funcao.to_string();
             }
         }
-        nome_funcao.to_string()
+        self.resolver_nome_externo(nome_funcao, namespace_atual, |s| {
+            matches!(s, LibSimbolo::Funcao(_))
+        })
+        .unwrap_or_else(|| nome_funcao.to_string())
     }
 
     pub fn is_member_of_class(&self, class_name: &str, member_name: &str) -> bool {
@@ -1223,7 +1387,607 @@ impl<'a> VerificadorTipos<'a> {
         if self.enums.contains_key(nome) {
             return nome.to_string();
         }
-        nome.to_string()
+        self.resolver_nome_externo(nome, namespace_atual, |s| matches!(s, LibSimbolo::Enum(_)))
+            .unwrap_or_else(|| nome.to_string())
+    }
+
+    // ---------------------------------------------------------------------
+    // Biblioteca externa (.pbl): o manifesto descreve classes, interfaces,
+    // enums e funções com tipos completos, verificados como os locais.
+    // ---------------------------------------------------------------------
+
+    fn classe_externa(&self, fqn: &str) -> Option<&LibClasse> {
+        match self.biblioteca_externa.as_ref()?.simbolos.get(fqn)? {
+            LibSimbolo::Classe(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    fn interface_externa(&self, fqn: &str) -> Option<&LibInterface> {
+        match self.biblioteca_externa.as_ref()?.simbolos.get(fqn)? {
+            LibSimbolo::Interface(i) => Some(i),
+            _ => None,
+        }
+    }
+
+    fn enum_externo(&self, fqn: &str) -> Option<&LibEnum> {
+        match self.biblioteca_externa.as_ref()?.simbolos.get(fqn)? {
+            LibSimbolo::Enum(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    fn funcao_externa(&self, fqn: &str) -> Option<&LibFuncao> {
+        match self.biblioteca_externa.as_ref()?.simbolos.get(fqn)? {
+            LibSimbolo::Funcao(f) => Some(f),
+            _ => None,
+        }
+    }
+
+    /// Nome completo de um símbolo externo visto do namespace atual e dos `usando`.
+    fn resolver_nome_externo(
+        &self,
+        nome: &str,
+        namespace_atual: &str,
+        aceita: fn(&LibSimbolo) -> bool,
+    ) -> Option<String> {
+        let bib = self.biblioteca_externa.as_ref()?;
+        let mut candidatos = Vec::new();
+        if !nome.contains('.') {
+            if !namespace_atual.is_empty() {
+                candidatos.push(format!("{}.{}", namespace_atual, nome));
+            }
+            candidatos.extend(self.usings.iter().map(|u| format!("{}.{}", u, nome)));
+        }
+        candidatos.push(nome.to_string());
+        candidatos
+            .into_iter()
+            .find(|fqn| bib.simbolos.get(fqn).is_some_and(aceita))
+    }
+
+    /// Tipo escrito no manifesto; nomes de enumerações viram `Tipo::Enum`.
+    fn tipo_externo(&self, texto: &str, genericos: &[String]) -> Tipo {
+        self.marcar_enums(library_loader::tipo_do_manifesto(texto, genericos))
+    }
+
+    fn marcar_enums(&self, tipo: Tipo) -> Tipo {
+        match tipo {
+            Tipo::Classe(n) if self.enums.contains_key(&n) || self.enum_externo(&n).is_some() => {
+                Tipo::Enum(n)
+            }
+            Tipo::Lista(t) => Tipo::Lista(Box::new(self.marcar_enums(*t))),
+            Tipo::Opcional(t) => Tipo::Opcional(Box::new(self.marcar_enums(*t))),
+            Tipo::Aplicado { nome, args } => Tipo::Aplicado {
+                nome,
+                args: args.into_iter().map(|a| self.marcar_enums(a)).collect(),
+            },
+            Tipo::Funcao(params, ret) => Tipo::Funcao(
+                params.into_iter().map(|p| self.marcar_enums(p)).collect(),
+                Box::new(self.marcar_enums(*ret)),
+            ),
+            outro => outro,
+        }
+    }
+
+    /// Procura um membro numa classe externa e nas suas bases externas. O resultado traz os
+    /// parâmetros de tipo da classe que declara o membro, já ligados aos argumentos.
+    fn membro_externo<T>(
+        &self,
+        fqn: &str,
+        args: &[Tipo],
+        escolher: impl Fn(&LibClasse) -> Option<T>,
+    ) -> Option<MembroExterno<T>> {
+        let mut atual = Some((fqn.to_string(), args.to_vec()));
+        for _ in 0..32 {
+            let (nome, args) = atual.take()?;
+            let classe = self.classe_externa(&nome)?;
+            let subst: HashMap<String, Tipo> =
+                classe.genericos.iter().cloned().zip(args).collect();
+            if let Some(membro) = escolher(classe) {
+                return Some(MembroExterno {
+                    membro,
+                    dono: classe.fqn.clone(),
+                    genericos: classe.genericos.clone(),
+                    subst,
+                });
+            }
+            atual = classe.nome_pai.as_ref().map(|pai| {
+                match self.tipo_externo(pai, &classe.genericos) {
+                    Tipo::Aplicado { nome, args } => (
+                        nome,
+                        args.iter()
+                            .map(|a| self.substitute_generics_in_tipo(a, &subst))
+                            .collect(),
+                    ),
+                    outro => (outro.to_string(), Vec::new()),
+                }
+            });
+        }
+        None
+    }
+
+    /// Primeira classe externa na cadeia de bases de uma classe local, com os argumentos de
+    /// tipo com que ela foi herdada (`classe Pilha : Util.Colecao<inteiro>`).
+    fn base_externa(&self, fqn: &str) -> Option<(String, Vec<Tipo>)> {
+        let mut atual = fqn.to_string();
+        for _ in 0..32 {
+            let decl = self.classes.get(&atual)?;
+            let ns = self.get_namespace_from_full_name(&atual);
+            let (nome, args) = match decl.classe_pai.as_ref()? {
+                Tipo::Classe(n) => (n, Vec::new()),
+                Tipo::Aplicado { nome, args } => (nome, args.clone()),
+                _ => return None,
+            };
+            let pai = self.resolver_nome_classe(nome, &ns);
+            if self.classe_externa(&pai).is_some() {
+                let args = args
+                    .iter()
+                    .map(|a| self.normalize_tipo_ro(a, &ns).0)
+                    .collect();
+                return Some((pai, args));
+            }
+            atual = pai;
+        }
+        None
+    }
+
+    /// `Classe.Membro` com `Classe` sendo o nome de um tipo (e não uma variável ou campo).
+    fn eh_acesso_estatico(
+        &self,
+        obj_expr: &Expressao,
+        classe_atual: Option<&String>,
+        escopo_vars: &HashMap<String, Tipo>,
+    ) -> bool {
+        let Expressao::Identificador(nome_id) = obj_expr else {
+            return false;
+        };
+        if escopo_vars.contains_key(nome_id) {
+            return false;
+        }
+        !classe_atual
+            .and_then(|c| self.resolved_classes.get(c))
+            .is_some_and(|info| {
+                info.properties.iter().any(|p| p.nome == *nome_id)
+                    || info.fields.iter().any(|f| f.nome == *nome_id)
+            })
+    }
+
+    /// Confere a quantidade e os tipos dos argumentos de uma chamada a um membro externo e
+    /// devolve o tipo de retorno. Os genéricos do próprio método são deduzidos dos argumentos.
+    #[allow(clippy::too_many_arguments)]
+    fn verificar_argumentos_externos(
+        &mut self,
+        descricao: &str,
+        parametros: &[LibParametro],
+        genericos: &[String],
+        tipo_retorno: &str,
+        subst: &HashMap<String, Tipo>,
+        args: &[Expressao],
+        namespace_atual: &str,
+        classe_atual: Option<&String>,
+        escopo_vars: &HashMap<String, Tipo>,
+    ) -> Tipo {
+        let tipos_args: Vec<Tipo> = args
+            .iter()
+            .map(|a| self.inferir_tipo_expressao(a, namespace_atual, classe_atual, escopo_vars))
+            .collect();
+        let mut subst = subst.clone();
+        let total = parametros.len();
+        let obrigatorios = library_loader::obrigatorios(parametros);
+        if args.len() < obrigatorios || args.len() > total {
+            let esperado = if obrigatorios == total {
+                total.to_string()
+            } else {
+                format!("de {} a {}", obrigatorios, total)
+            };
+            self.erros.push(ErroCompilador::novo(
+                TipoErro::Semântico,
+                format!(
+                    "'{}' espera {} argumento(s), mas recebeu {}.",
+                    descricao,
+                    esperado,
+                    args.len()
+                ),
+            ));
+        } else {
+            for (i, (param, t_arg)) in parametros.iter().zip(&tipos_args).enumerate() {
+                let t_param = self.tipo_externo(&param.tipo, genericos);
+                deduzir_genericos(&t_param, t_arg, &mut subst);
+                let t_param = self.substitute_generics_in_tipo(&t_param, &subst);
+                if *t_arg != Tipo::Inferido && !self.tipos_compativeis_atribuicao(&t_param, t_arg) {
+                    self.erros.push(ErroCompilador::novo(
+                        TipoErro::Semântico,
+                        format!(
+                            "Argumento {} ('{}') de '{}': esperado {}, recebido {}.",
+                            i + 1,
+                            param.nome,
+                            descricao,
+                            t_param,
+                            t_arg
+                        ),
+                    ));
+                }
+            }
+        }
+        let ret = self.tipo_externo(tipo_retorno, genericos);
+        self.substitute_generics_in_tipo(&ret, &subst)
+    }
+
+    /// `obj.Metodo(args)` sobre uma classe ou interface externa, ou sobre uma classe local que
+    /// herda o método de uma base externa. `None` quando o alvo não envolve a biblioteca.
+    #[allow(clippy::too_many_arguments)]
+    fn verificar_chamada_externa(
+        &mut self,
+        obj_expr: &Expressao,
+        obj_tipo: &Tipo,
+        metodo_nome: &str,
+        args: &[Expressao],
+        namespace_atual: &str,
+        classe_atual: Option<&String>,
+        escopo_vars: &HashMap<String, Tipo>,
+    ) -> Option<Tipo> {
+        let (nome, tipo_args) = match obj_tipo {
+            Tipo::Classe(n) => (self.resolver_nome_classe(n, namespace_atual), Vec::new()),
+            Tipo::Aplicado { nome, args } => {
+                (self.resolver_nome_classe(nome, namespace_atual), args.clone())
+            }
+            _ => return None,
+        };
+
+        if let Some(iface) = self.interface_externa(&nome).cloned() {
+            let descricao = format!("{}.{}", nome, metodo_nome);
+            let Some(metodo) = iface.metodos.get(metodo_nome) else {
+                self.erros.push(ErroCompilador::novo(
+                    TipoErro::Semântico,
+                    format!(
+                        "Método '{}' não existe na interface '{}'.",
+                        metodo_nome, nome
+                    ),
+                ));
+                return Some(Tipo::Inferido);
+            };
+            let subst = iface.genericos.iter().cloned().zip(tipo_args).collect();
+            let genericos = [iface.genericos.clone(), metodo.genericos.clone()].concat();
+            return Some(self.verificar_argumentos_externos(
+                &descricao,
+                &metodo.parametros,
+                &genericos,
+                &metodo.tipo_retorno,
+                &subst,
+                args,
+                namespace_atual,
+                classe_atual,
+                escopo_vars,
+            ));
+        }
+
+        let (classe, tipo_args) = if self.classe_externa(&nome).is_some() {
+            (nome.clone(), tipo_args)
+        } else {
+            self.base_externa(&nome)?
+        };
+        let Some(encontrado) =
+            self.membro_externo(&classe, &tipo_args, |c| c.metodos.get(metodo_nome).cloned())
+        else {
+            self.erros.push(ErroCompilador::novo(
+                TipoErro::Semântico,
+                format!("Método '{}' não existe na classe '{}'.", metodo_nome, nome),
+            ));
+            return Some(Tipo::Inferido);
+        };
+        let metodo = &encontrado.membro;
+
+        let is_static_access = self.eh_acesso_estatico(obj_expr, classe_atual, escopo_vars);
+        if metodo.eh_estatica && !is_static_access {
+            self.erros.push(ErroCompilador::novo(
+                TipoErro::Semântico,
+                format!(
+                    "O método '{}' de '{}' é estático e não pode ser chamado a partir de uma instância.",
+                    metodo_nome, nome
+                ),
+            ));
+        } else if !metodo.eh_estatica && is_static_access {
+            self.erros.push(ErroCompilador::novo(
+                TipoErro::Semântico,
+                format!(
+                    "O método '{}' de '{}' não é estático e não pode ser chamado diretamente pela classe.",
+                    metodo_nome, nome
+                ),
+            ));
+        }
+        if metodo.acesso == LibAcesso::Protegido
+            && !classe_atual.is_some_and(|c| self.is_subclass_of(c, &encontrado.dono))
+        {
+            self.erros.push(ErroCompilador::novo(
+                TipoErro::Semântico,
+                format!(
+                    "O método '{}' de '{}' é inacessível: é protegido e só pode ser chamado dentro da classe ou de subclasses.",
+                    metodo_nome, nome
+                ),
+            ));
+        }
+
+        let genericos = [encontrado.genericos.clone(), metodo.genericos.clone()].concat();
+        Some(self.verificar_argumentos_externos(
+            &format!("{}.{}", nome, metodo_nome),
+            &metodo.parametros,
+            &genericos,
+            &metodo.tipo_retorno,
+            &encontrado.subst,
+            args,
+            namespace_atual,
+            classe_atual,
+            escopo_vars,
+        ))
+    }
+
+    /// `obj.Membro` (propriedade ou campo) sobre uma classe externa.
+    fn acessar_membro_externo(
+        &mut self,
+        fqn: &str,
+        tipo_args: &[Tipo],
+        membro_nome: &str,
+        classe_atual: Option<&String>,
+    ) -> Tipo {
+        let encontrado = self.membro_externo(fqn, tipo_args, |c| {
+            c.propriedades
+                .iter()
+                .find(|p| p.nome == membro_nome)
+                .map(|p| ("A propriedade", "protegida e só pode ser acessada", p.tipo.clone(), p.acesso))
+                .or_else(|| {
+                    c.campos
+                        .iter()
+                        .find(|f| f.nome == membro_nome)
+                        .map(|f| ("O campo", "protegido e só pode ser acessado", f.tipo.clone(), f.acesso))
+                })
+                .or_else(|| {
+                    // Métodos sem parâmetros também podem ser lidos como valor
+                    c.metodos
+                        .get(membro_nome)
+                        .map(|m| ("O método", "protegido e só pode ser acessado", m.tipo_retorno.clone(), m.acesso))
+                })
+        });
+        let Some(encontrado) = encontrado else {
+            self.erros.push(ErroCompilador::novo(
+                TipoErro::Semântico,
+                format!(
+                    "Membro \"{}\" não encontrado na classe \"{}\".",
+                    membro_nome, fqn
+                ),
+            ));
+            return Tipo::Inferido;
+        };
+        let (artigo, restricao, tipo, acesso) = encontrado.membro;
+        if acesso == LibAcesso::Protegido
+            && !classe_atual.is_some_and(|c| self.is_subclass_of(c, &encontrado.dono))
+        {
+            self.erros.push(ErroCompilador::novo(
+                TipoErro::Semântico,
+                format!(
+                    "{} '{}' de '{}' é inacessível: é {} dentro da classe ou de subclasses.",
+                    artigo, membro_nome, fqn, restricao
+                ),
+            ));
+        }
+        let tipo = self.tipo_externo(&tipo, &encontrado.genericos);
+        self.substitute_generics_in_tipo(&tipo, &encontrado.subst)
+    }
+
+    /// `novo Tipo(args)` com `Tipo` vindo de uma biblioteca externa.
+    fn verificar_construcao_externa(
+        &mut self,
+        tipo: &Tipo,
+        args: &[Expressao],
+        namespace_atual: &str,
+        classe_atual: Option<&String>,
+        escopo_vars: &HashMap<String, Tipo>,
+    ) {
+        let (nome, tipo_args) = match tipo {
+            Tipo::Classe(n) => (n.clone(), Vec::new()),
+            Tipo::Aplicado { nome, args } => (nome.clone(), args.clone()),
+            _ => return,
+        };
+        let erro = if self.interface_externa(&nome).is_some() {
+            format!("A interface '{}' não pode ser instanciada.", nome)
+        } else if let Some(classe) = self.classe_externa(&nome).cloned() {
+            if classe.eh_abstrata {
+                format!("A classe abstrata '{}' não pode ser instanciada.", nome)
+            } else if classe.eh_estatica {
+                format!("A classe estática '{}' não pode ser instanciada.", nome)
+            } else if classe.construtores.is_empty() {
+                if args.is_empty() {
+                    return;
+                }
+                format!(
+                    "A classe '{}' não contém um construtor que receba {} argumentos.",
+                    nome,
+                    args.len()
+                )
+            } else if let Some(construtor) = classe.construtores.iter().find(|c| {
+                args.len() >= library_loader::obrigatorios(&c.parametros)
+                    && args.len() <= c.parametros.len()
+            }) {
+                let subst = classe.genericos.iter().cloned().zip(tipo_args).collect();
+                self.verificar_argumentos_externos(
+                    &format!("novo {}", nome),
+                    &construtor.parametros,
+                    &classe.genericos,
+                    "vazio",
+                    &subst,
+                    args,
+                    namespace_atual,
+                    classe_atual,
+                    escopo_vars,
+                );
+                if construtor.acesso != LibAcesso::Protegido
+                    || classe_atual.is_some_and(|c| self.is_subclass_of(c, &nome))
+                {
+                    return;
+                }
+                format!(
+                    "O construtor da classe '{}' é inacessível devido ao seu nível de proteção.",
+                    nome
+                )
+            } else {
+                format!(
+                    "A classe '{}' não contém um construtor que receba {} argumentos.",
+                    nome,
+                    args.len()
+                )
+            }
+        } else {
+            return;
+        };
+        self.erros
+            .push(ErroCompilador::novo(TipoErro::Semântico, erro));
+    }
+
+    /// Métodos abstratos de uma classe externa e das suas bases ainda sem implementação,
+    /// com a classe que declara cada um.
+    fn metodos_abstratos_externos(&self, fqn: &str) -> Vec<(String, String)> {
+        let mut implementados = std::collections::HashSet::new();
+        let mut abstratos = Vec::new();
+        let mut atual = Some(fqn.to_string());
+        for _ in 0..32 {
+            let Some(classe) = atual.take().and_then(|c| self.classe_externa(&c)) else {
+                break;
+            };
+            let mut nomes: Vec<&String> = classe.metodos.keys().collect();
+            nomes.sort();
+            for nome in nomes {
+                if classe.metodos[nome].eh_abstrato {
+                    if !implementados.contains(nome) {
+                        abstratos.push((nome.clone(), classe.fqn.clone()));
+                    }
+                } else {
+                    implementados.insert(nome.clone());
+                }
+            }
+            atual = classe.nome_pai.as_ref().map(|p| nome_base_externa(p));
+        }
+        abstratos
+    }
+
+    /// Assinatura de um membro externo com os genéricos da classe já aplicados.
+    fn assinatura_externa(
+        &self,
+        metodo: &LibMetodo,
+        genericos: &[String],
+        subst: &HashMap<String, Tipo>,
+    ) -> (Tipo, Vec<Tipo>) {
+        let genericos = [genericos, &metodo.genericos[..]].concat();
+        let converter = |texto: &str| {
+            self.substitute_generics_in_tipo(&self.tipo_externo(texto, &genericos), subst)
+        };
+        (
+            converter(&metodo.tipo_retorno),
+            metodo.parametros.iter().map(|p| converter(&p.tipo)).collect(),
+        )
+    }
+
+    /// Assinatura de um método local com os tipos normalizados, para comparar com a externa.
+    fn assinatura_local(&self, metodo: &ast::MetodoClasse, ns: &str) -> (Tipo, Vec<Tipo>) {
+        let normalizar = |t: &Tipo| self.normalize_tipo_ro(t, ns).0;
+        (
+            normalizar(metodo.tipo_retorno.as_ref().unwrap_or(&Tipo::Vazio)),
+            metodo.parametros.iter().map(|p| normalizar(&p.tipo)).collect(),
+        )
+    }
+
+    /// `sobrescreve` num método cuja base está numa biblioteca.
+    fn validar_redefinicao_externa(
+        &self,
+        fqn: &str,
+        metodo: &ast::MetodoClasse,
+        base: &str,
+        args: &[Tipo],
+    ) -> Vec<ErroCompilador> {
+        let Some(base_m) = self.membro_externo(base, args, |c| c.metodos.get(&metodo.nome).cloned())
+        else {
+            return vec![ErroCompilador::novo(
+                TipoErro::Semântico,
+                format!(
+                    "Método '{}' marcado como 'sobrescreve' mas não existe método correspondente na classe base de '{}'. Dica: verifique nome, parâmetros e se o método da base está visível.",
+                    metodo.nome, fqn
+                ),
+            )];
+        };
+        if !(base_m.membro.eh_redefinivel || base_m.membro.eh_abstrato) {
+            return vec![ErroCompilador::novo(
+                TipoErro::Semântico,
+                format!(
+                    "Método '{}' em '{}' usa 'sobrescreve' mas o método da classe base não é 'redefinível'. Dica: marque o método da base como 'redefinível'.",
+                    metodo.nome, fqn
+                ),
+            )];
+        }
+        let esperada = self.assinatura_externa(&base_m.membro, &base_m.genericos, &base_m.subst);
+        if self.assinatura_local(metodo, &self.get_namespace_from_full_name(fqn)) != esperada {
+            return vec![ErroCompilador::novo(
+                TipoErro::Semântico,
+                format!(
+                    "Assinatura incompatível no override de '{}.{}'. Dica: a assinatura deve ser exatamente a mesma da base (retorno e parâmetros).",
+                    fqn, metodo.nome
+                ),
+            )];
+        }
+        Vec::new()
+    }
+
+    /// Uma classe local que implementa uma interface de biblioteca.
+    fn validar_interface_externa(
+        &self,
+        fqn: &str,
+        classe: &DeclaracaoClasse,
+        metodos: &HashMap<String, &'a ast::MetodoClasse>,
+        iface: &LibInterface,
+    ) -> Vec<ErroCompilador> {
+        let ns = self.get_namespace_from_full_name(fqn);
+        // Argumentos com que a interface foi aplicada (`classe Caixa : IValor<texto>`)
+        let aplicada: Vec<Tipo> = classe
+            .interfaces
+            .iter()
+            .chain(classe.classe_pai.iter())
+            .find_map(|t| match t {
+                Tipo::Aplicado { nome, args }
+                    if self.resolver_nome_interface(nome, &ns) == iface.fqn =>
+                {
+                    Some(args.iter().map(|a| self.normalize_tipo_ro(a, &ns).0).collect())
+                }
+                _ => None,
+            })
+            .unwrap_or_default();
+        let subst: HashMap<String, Tipo> =
+            iface.genericos.iter().cloned().zip(aplicada).collect();
+
+        let mut nomes: Vec<&String> = iface.metodos.keys().collect();
+        nomes.sort();
+        let mut erros = Vec::new();
+        for nome in nomes {
+            let (ret_i, params_i) =
+                self.assinatura_externa(&iface.metodos[nome], &iface.genericos, &subst);
+            match metodos.get(nome) {
+                Some(m) if self.assinatura_local(m, &ns) != (ret_i.clone(), params_i.clone()) => {
+                    erros.push(ErroCompilador::novo(
+                        TipoErro::Semântico,
+                        format!(
+                            "Classe '{}' não implementa corretamente método '{}' da interface '{}'. Assinatura esperada: ({:?}) -> {:?}",
+                            fqn, nome, iface.fqn, params_i, ret_i
+                        ),
+                    ))
+                }
+                Some(_) => {}
+                None if !classe.eh_abstrata => erros.push(ErroCompilador::novo(
+                    TipoErro::Semântico,
+                    format!(
+                        "Classe '{}' não implementa método obrigatório '{}' da interface '{}'",
+                        fqn, nome, iface.fqn
+                    ),
+                )),
+                None => {}
+            }
+        }
+        erros
     }
 
     fn verificar_declaracao(
@@ -1282,6 +2046,26 @@ impl<'a> VerificadorTipos<'a> {
                         fqn
                     )));
                 }
+                // 5) Classe concreta implementa os métodos abstratos de uma base externa
+                if !classe.eh_abstrata {
+                    if let Some((base, _)) = self.base_externa(&fqn) {
+                        for (metodo, dono) in self.metodos_abstratos_externos(&base) {
+                            let implementado = self
+                                .resolved_classes
+                                .get(&fqn)
+                                .is_some_and(|ci| ci.methods.contains_key(&metodo));
+                            if !implementado {
+                                self.erros.push(ErroCompilador::novo(
+                                    TipoErro::Semântico,
+                                    format!(
+                                        "Classe '{}' não implementa o método abstrato '{}' de '{}'",
+                                        fqn, metodo, dono
+                                    ),
+                                ));
+                            }
+                        }
+                    }
+                }
                 for metodo in &classe.metodos {
                     let is_nativo = metodo.attributes.iter().any(|a| a.name == "Nativo");
                     // Um método é "externo" quando tem corpo vazio, não é abstrato, e tem o atributo [Nativo]
@@ -1302,6 +2086,17 @@ impl<'a> VerificadorTipos<'a> {
                         )));
                     }
 
+                    if crate::testes::eh_teste(&metodo.attributes) && !metodo.parametros.is_empty()
+                    {
+                        self.erros.push(ErroCompilador::novo(
+                            TipoErro::Semântico,
+                            format!(
+                                "Método de teste '{}' não pode ter parâmetros em '{}'",
+                                metodo.nome, fqn
+                            ),
+                        ));
+                    }
+
                     let mut metodo_vars = escopo_vars.clone();
                     // Validação de override/virtual
                     if let Some(parent_simple) = &classe.classe_pai {
@@ -1335,6 +2130,10 @@ impl<'a> VerificadorTipos<'a> {
                                         )));
                                     }
                                 }
+                            } else if let Some((base, args)) = self.base_externa(&fqn) {
+                                let mut erros =
+                                    self.validar_redefinicao_externa(&fqn, metodo, &base, &args);
+                                self.erros.append(&mut erros);
                             } else {
                                 self.erros.push(ErroCompilador::novo(
                                     TipoErro::Semântico,
@@ -1359,12 +2158,9 @@ impl<'a> VerificadorTipos<'a> {
                         self.validar_tipo_conhecido(&resolved_param_type, namespace_atual, format!("o parâmetro '{}' do método '{}'", param.nome, metodo.nome));
                         metodo_vars.insert(param.nome.clone(), resolved_param_type);
                     }
-                    println!(
-                        "DEBUG: Verificando método \"{}\". Parâmetros no escopo: {:?}",
-                        metodo.nome, metodo_vars
-                    );
 
-                    let eh_stdlib = self.eh_classe_stdlib(&fqn);
+                    let eh_stdlib = self.eh_classe_stdlib(&fqn)
+                        || self.corpos_verificados.contains(&fqn);
 
                     if !metodo.eh_abstrato && !is_nativo && !eh_stdlib {
                         for comando in &metodo.corpo {
@@ -1391,12 +2187,29 @@ impl<'a> VerificadorTipos<'a> {
                     )));
                 }
 
+                if let Some(externo) = funcao.attributes.iter().find(|a| a.name == "Externo") {
+                    self.verificar_funcao_externa(funcao, externo);
+                }
+
+                if crate::testes::eh_teste(&funcao.attributes) && !funcao.parametros.is_empty() {
+                    self.erros.push(ErroCompilador::novo(
+                        TipoErro::Semântico,
+                        format!("Função de teste '{}' não pode ter parâmetros.", funcao.nome),
+                    ));
+                }
+
                 let mut func_vars = escopo_vars.clone();
                 for param in &funcao.parametros {
                     func_vars.insert(param.nome.clone(), param.tipo.clone());
                 }
 
-                let eh_stdlib = self.stdlib_namespaces.contains(namespace_atual);
+                let fqn = if namespace_atual.is_empty() {
+                    funcao.nome.clone()
+                } else {
+                    format!("{}.{}", namespace_atual, funcao.nome)
+                };
+                let eh_stdlib = self.stdlib_namespaces.contains(namespace_atual)
+                    || self.corpos_verificados.contains(&fqn);
 
                 if !is_nativo && !eh_stdlib {
                     for comando in &funcao.corpo {
@@ -1410,6 +2223,60 @@ impl<'a> VerificadorTipos<'a> {
             _ => {}
         }
     }
+    /// `[Externo("biblioteca", "símbolo")]`: função sem corpo chamada pela ABI do C no alvo LLVM.
+    /// Só aceita tipos com equivalente direto em C.
+    fn verificar_funcao_externa(&mut self, funcao: &DeclaracaoFuncao, atributo: &Attribute) {
+        let argumentos_validos = atributo.arguments.len() == 2
+            && atributo
+                .arguments
+                .iter()
+                .all(|a| matches!(a, Expressao::Texto(t) if !t.is_empty()));
+        if !argumentos_validos {
+            self.erros.push(ErroCompilador::novo(
+                TipoErro::Semântico,
+                format!(
+                    "Função externa '{}': use [Externo(\"biblioteca\", \"símbolo\")], ex.: [Externo(\"libm\", \"cos\")].",
+                    funcao.nome
+                ),
+            ));
+        }
+        if !funcao.corpo.is_empty() {
+            self.erros.push(ErroCompilador::novo(
+                TipoErro::Semântico,
+                format!("Função externa '{}' não pode ter corpo.", funcao.nome),
+            ));
+        }
+        let suportado = |tipo: &Tipo| {
+            matches!(
+                tipo,
+                Tipo::Inteiro | Tipo::Duplo | Tipo::Flutuante | Tipo::Texto | Tipo::Booleano
+            )
+        };
+        for param in &funcao.parametros {
+            if !suportado(&param.tipo) {
+                self.erros.push(ErroCompilador::novo(
+                    TipoErro::Semântico,
+                    format!(
+                        "Parâmetro '{}' da função externa '{}' tem tipo '{}', sem equivalente em C (use inteiro, duplo, flutuante, texto ou booleano).",
+                        param.nome, funcao.nome, param.tipo
+                    ),
+                ));
+            }
+        }
+        match &funcao.tipo_retorno {
+            Some(tipo) if *tipo != Tipo::Vazio && !suportado(tipo) => {
+                self.erros.push(ErroCompilador::novo(
+                    TipoErro::Semântico,
+                    format!(
+                        "Função externa '{}' retorna '{}', sem equivalente em C (use inteiro, duplo, flutuante, texto, booleano ou vazio).",
+                        funcao.nome, tipo
+                    ),
+                ));
+            }
+            _ => {}
+        }
+    }
+
     fn verificar_comando(
         &mut self,
         comando: &Comando,
@@ -1422,7 +2289,14 @@ impl<'a> VerificadorTipos<'a> {
                 let tipo_resolvido = match tipo {
                     Tipo::Classe(nome_classe) => {
                         let fqn_cls = self.resolver_nome_classe(nome_classe, namespace_atual);
-                        if self.classes.contains_key(&fqn_cls) {
+                        // Classes de bibliotecas .pbl também dão nome a tipos de variáveis
+                        let eh_classe_externa = self
+                            .biblioteca_externa
+                            .as_ref()
+                            .is_some_and(|bib| bib.simbolos.contains_key(&fqn_cls));
+                        if self.enum_externo(&fqn_cls).is_some() {
+                            Tipo::Enum(fqn_cls)
+                        } else if self.classes.contains_key(&fqn_cls) || eh_classe_externa {
                             Tipo::Classe(fqn_cls)
                         } else {
                             let fqn_en = self.resolver_nome_enum(nome_classe, namespace_atual);
@@ -1433,6 +2307,8 @@ impl<'a> VerificadorTipos<'a> {
                             }
                         }
                     }
+                    // `Caixa<inteiro>` de uma biblioteca: guarda o nome completo para as chamadas
+                    Tipo::Aplicado { .. } => self.normalize_tipo_ro(tipo, namespace_atual).0,
                     _ => tipo.clone(),
                 };
                 if let Some(e) = expr {
@@ -1852,6 +2728,9 @@ impl<'a> VerificadorTipos<'a> {
                 }
                 // NOVO: Verificar se a classe está na biblioteca externa
                 if let Some(bib) = &self.biblioteca_externa {
+                    if let Some(LibSimbolo::Enum(_)) = bib.simbolos.get(&fqn_class) {
+                        return Tipo::Enum(fqn_class);
+                    }
                     if bib.simbolos.contains_key(&fqn_class) {
                         return Tipo::Classe(fqn_class);
                     }
@@ -1891,13 +2770,18 @@ impl<'a> VerificadorTipos<'a> {
                 if let Some(nome_classe) = lookup_class_name {
                     let fqn = self.resolver_nome_classe(&nome_classe, namespace_atual);
 
-                    // NOVO: Consultar biblioteca externa para métodos
-                    if let Some(bib) = &self.biblioteca_externa {
-                        if let Some(LibSimbolo::Classe(lib_classe)) = bib.simbolos.get(&fqn) {
-                            if let Some(metodo) = lib_classe.metodos.get(membro_nome) {
-                                return string_para_tipo(&metodo.tipo_retorno);
-                            }
-                        }
+                    // NOVO: Consultar biblioteca externa para propriedades, campos e métodos
+                    if self.classe_externa(&fqn).is_some() {
+                        let tipo_args = match &obj_tipo {
+                            Tipo::Aplicado { args, .. } => args.clone(),
+                            _ => Vec::new(),
+                        };
+                        return self.acessar_membro_externo(
+                            &fqn,
+                            &tipo_args,
+                            membro_nome,
+                            classe_atual,
+                        );
                     }
 
                     if let Some(class_info) = self.resolved_classes.get(&fqn) {
@@ -1966,8 +2850,13 @@ impl<'a> VerificadorTipos<'a> {
                             }
                             // Enum membro? O membro possui o tipo do próprio enum
                             if let Tipo::Enum(ref fqn_enum) = obj_tipo {
-                            if let Some(en) = self.enums.get(fqn_enum) {
-                            if en.valores.iter().any(|v| v == membro_nome) {
+                            let valores = self
+                                .enums
+                                .get(fqn_enum)
+                                .map(|en| en.valores.clone())
+                                .or_else(|| self.enum_externo(fqn_enum).map(|en| en.valores.clone()));
+                            if let Some(valores) = valores {
+                            if valores.iter().any(|v| v == membro_nome) {
                             return Tipo::Enum(fqn_enum.clone());
                             } else {
                             self.erros.push(ErroCompilador::novo(
@@ -2125,6 +3014,13 @@ impl<'a> VerificadorTipos<'a> {
 
                     }
                 }
+                self.verificar_construcao_externa(
+                    &t_norm,
+                    args,
+                    namespace_atual,
+                    classe_atual,
+                    escopo_vars,
+                );
 
                 t_norm
             }
@@ -2150,23 +3046,55 @@ impl<'a> VerificadorTipos<'a> {
             }
             Expressao::Comparacao(_, _, _) => Tipo::Booleano,
             Expressao::Logica(_, _, _) => Tipo::Booleano,
-            Expressao::ChamadaMetodo(obj_expr, metodo_nome, _args) => {
+            Expressao::Chamada(nome, args) => {
+                // Só as funções de bibliotecas têm os argumentos conferidos aqui
+                let fqn = self.resolver_nome_funcao(nome, namespace_atual);
+                let metodo_da_classe = classe_atual
+                    .and_then(|c| self.resolved_classes.get(c))
+                    .is_some_and(|ci| ci.methods.contains_key(nome));
+                match self.funcao_externa(&fqn).cloned() {
+                    Some(funcao)
+                        if !metodo_da_classe && !self.simbolos_namespaces.contains_key(&fqn) =>
+                    {
+                        self.verificar_argumentos_externos(
+                            &fqn,
+                            &funcao.parametros,
+                            &funcao.genericos,
+                            &funcao.tipo_retorno,
+                            &HashMap::new(),
+                            args,
+                            namespace_atual,
+                            classe_atual,
+                            escopo_vars,
+                        )
+                    }
+                    _ => Tipo::Inferido,
+                }
+            }
+            Expressao::ChamadaMetodo(obj_expr, metodo_nome, args) => {
                 let obj_tipo = self.inferir_tipo_expressao(
                     obj_expr,
                     namespace_atual,
                     classe_atual,
                     escopo_vars,
                 );
-                if let Tipo::Classe(nome_classe) = obj_tipo {
-                    // NOVO: Consultar biblioteca externa primeiro
-                    if let Some(bib) = &self.biblioteca_externa {
-                        if let Some(LibSimbolo::Classe(lib_classe)) = bib.simbolos.get(&nome_classe)
-                        {
-                            if let Some(metodo) = lib_classe.metodos.get(metodo_nome) {
-                                return string_para_tipo(&metodo.tipo_retorno);
-                            }
-                        }
+                // Métodos de bibliotecas, inclusive os herdados por classes locais
+                let metodo_local = matches!(&obj_tipo, Tipo::Classe(n)
+                    if self.resolved_classes.get(n).is_some_and(|ci| ci.methods.contains_key(metodo_nome)));
+                if !metodo_local {
+                    if let Some(tipo) = self.verificar_chamada_externa(
+                        obj_expr,
+                        &obj_tipo,
+                        metodo_nome,
+                        args,
+                        namespace_atual,
+                        classe_atual,
+                        escopo_vars,
+                    ) {
+                        return tipo;
                     }
+                }
+                if let Tipo::Classe(nome_classe) = obj_tipo {
                     if let Some(class_info) = self.resolved_classes.get(&nome_classe) {
                         if let Some(metodo) = class_info.methods.get(metodo_nome) {
                             let mut is_static_access = false;
@@ -2272,6 +3200,9 @@ impl<'a> VerificadorTipos<'a> {
                 }
                 // NOVO: Verificar se a classe está na biblioteca externa
                 if let Some(bib) = &self.biblioteca_externa {
+                    if let Some(LibSimbolo::Enum(_)) = bib.simbolos.get(&fqn_class) {
+                        return Tipo::Enum(fqn_class);
+                    }
                     if bib.simbolos.contains_key(&fqn_class) {
                         return Tipo::Classe(fqn_class);
                     }
@@ -2287,13 +3218,21 @@ impl<'a> VerificadorTipos<'a> {
                     self.get_expr_type(obj_expr, namespace_atual, classe_atual, escopo_vars);
                 if let Tipo::Classe(ref nome_classe) = obj_tipo {
                     // NOVO: Consultar biblioteca externa primeiro
-                    if let Some(bib) = &self.biblioteca_externa {
-                        if let Some(LibSimbolo::Classe(lib_classe)) = bib.simbolos.get(nome_classe)
-                        {
-                            if let Some(metodo) = lib_classe.metodos.get(membro_nome) {
-                                return string_para_tipo(&metodo.tipo_retorno);
-                            }
-                        }
+                    let externo = self.membro_externo(nome_classe, &[], |c| {
+                        c.propriedades
+                            .iter()
+                            .find(|p| p.nome == *membro_nome)
+                            .map(|p| p.tipo.clone())
+                            .or_else(|| {
+                                c.campos
+                                    .iter()
+                                    .find(|f| f.nome == *membro_nome)
+                                    .map(|f| f.tipo.clone())
+                            })
+                            .or_else(|| c.metodos.get(membro_nome).map(|m| m.tipo_retorno.clone()))
+                    });
+                    if let Some(encontrado) = externo {
+                        return self.tipo_externo(&encontrado.membro, &encontrado.genericos);
                     }
                     if let Some(class_info) = self.resolved_classes.get(nome_classe) {
                         if let Some(prop) = class_info
@@ -2321,6 +3260,12 @@ impl<'a> VerificadorTipos<'a> {
                             return Tipo::Enum(fqn_enum.clone());
                         }
                     }
+                    if self
+                        .enum_externo(fqn_enum)
+                        .is_some_and(|en| en.valores.iter().any(|v| v == membro_nome))
+                    {
+                        return Tipo::Enum(fqn_enum.clone());
+                    }
                 }
                 Tipo::Inferido
             }
@@ -15,11 +15,9 @@ if (!(Test-Path $interpretador)) {
     exit 1
 }
 
-$multi_file_script = ".\compile_fast_Multiplos_arquivos.sh"
-
 # Teste para programa_principal.pr e biblioteca.pr
 Write-Host "Testando programa_principal.pr e biblioteca.pr..."
-& $multi_file_script "exemplos/biblioteca.pr" "exemplos/programa_principal.pr"
+& $compilador "exemplos/biblioteca.pr" "exemplos/programa_principal.pr" --target=bytecode --output-dir=. --nome=biblioteca
 $compilou_multi = $LASTEXITCODE -eq 0
 
 if ($compilou_multi) {
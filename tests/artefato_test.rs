@@ -0,0 +1,201 @@
+use compilador_portugues::artefato::{self, Tipo};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Área de um teste: `build/artefato/<nome>/`, com a biblioteca `util` e um `app` que a usa.
+struct Area {
+    raiz: PathBuf,
+}
+
+impl Area {
+    fn nova(nome: &str) -> Self {
+        let raiz = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("build")
+            .join("artefato")
+            .join(nome);
+        let _ = fs::remove_dir_all(&raiz);
+        for (caminho, conteudo) in [
+            (
+                "util/projeto.toml",
+                "[projeto]\nnome = \"util\"\nversao = \"1.0.0\"\ntipo = \"biblioteca\"\n",
+            ),
+            (
+                "util/src/util.pr",
+                "espaco Util {\n    publico função inteiro Dobro(inteiro x) { retorne x * 2; }\n}\n",
+            ),
+            (
+                "app/projeto.toml",
+                "[projeto]\nnome = \"app\"\nversao = \"0.1.0\"\nalvos = [\"bytecode\"]\n\n[dependencias]\nutil = { caminho = \"../util\" }\n",
+            ),
+            (
+                "app/src/principal.pr",
+                "usando Util;\n\nfunção Principal() {\n    imprima(Dobro(21));\n}\n",
+            ),
+        ] {
+            let caminho = raiz.join(caminho);
+            fs::create_dir_all(caminho.parent().unwrap()).unwrap();
+            fs::write(caminho, conteudo).unwrap();
+        }
+        let area = Self { raiz };
+        assert_sucesso(&area.compilador(&["construir", "app"], &[]));
+        area
+    }
+
+    fn caminho(&self, relativo: &str) -> PathBuf {
+        self.raiz.join(relativo)
+    }
+
+    fn compilador(&self, args: &[&str], env: &[(&str, &Path)]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_compilador"))
+            .current_dir(&self.raiz)
+            .args(args)
+            .envs(env.iter().copied())
+            .output()
+            .expect("falha ao executar compilador")
+    }
+
+    fn executar_app(&self, env: &[(&str, &Path)]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_interpretador"))
+            .arg(self.caminho("app/dist/bytecode/app.pbc"))
+            .envs(env.iter().copied())
+            .output()
+            .expect("falha ao executar interpretador")
+    }
+
+    fn editar(&self, relativo: &str, de: &str, para: &str) {
+        let caminho = self.caminho(relativo);
+        let conteudo = fs::read_to_string(&caminho).unwrap();
+        assert!(conteudo.contains(de), "'{}' não está em {}", de, relativo);
+        fs::write(caminho, conteudo.replacen(de, para, 1)).unwrap();
+    }
+}
+
+fn assert_sucesso(output: &Output) {
+    assert!(
+        output.status.success(),
+        "stdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+fn assert_erro(output: &Output, trecho: &str) {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "deveria falhar");
+    assert!(stderr.contains(trecho), "esperado '{}' em:\n{}", trecho, stderr);
+}
+
+#[test]
+fn artefatos_tem_cabecalho_com_formato_versao_e_hash() {
+    let area = Area::nova("cabecalho");
+    let pbc = fs::read_to_string(area.caminho("app/dist/bytecode/app.pbc")).unwrap();
+    let pbl = fs::read_to_string(area.caminho("util/dist/util.pbl")).unwrap();
+    let versao = format!("compilador={}\n", env!("CARGO_PKG_VERSION"));
+    assert!(pbc.starts_with(&format!("[PBC]\nformato=1\n{}sha256=", versao)), "{}", pbc);
+    assert!(
+        pbl.starts_with(&format!("[PBL]\nformato=1\n{}nome=util\nversao=1.0.0\nsha256=", versao)),
+        "{}",
+        pbl
+    );
+
+    let output = area.executar_app(&[]);
+    assert_sucesso(&output);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "42\n");
+    let output = area.compilador(&["verificar", "app/dist/bytecode/app.pbc", "util/dist/util.pbl"], &[]);
+    assert_sucesso(&output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("util 1.0.0, sem assinatura"));
+}
+
+#[test]
+fn conteudo_alterado_ou_formato_novo_sao_recusados() {
+    let area = Area::nova("integridade");
+    // A cópia da biblioteca ao lado do programa é a que a VM carrega
+    area.editar("app/dist/bytecode/util.pbl", "MUL", "ADD");
+    assert_erro(&area.executar_app(&[]), "não confere com o hash do cabeçalho");
+    assert_erro(
+        &area.compilador(&["verificar", "app/dist/bytecode/util.pbl"], &[]),
+        "arquivo corrompido ou alterado",
+    );
+
+    // O compilador também confere as bibliotecas que lê
+    area.editar("util/dist/util.pbl", "inteiro:x", "texto:x");
+    assert_erro(
+        &area.compilador(
+            &["app/src/principal.pr", "--target=bytecode", "--biblioteca=util/dist/util.pbl"],
+            &[],
+        ),
+        "não confere com o hash do cabeçalho",
+    );
+
+    // O hash cobre também os metadados do cabeçalho
+    area.editar("util/dist/util.pbl", "versao=1.0.0", "versao=9.9.9");
+    assert_erro(
+        &area.compilador(&["verificar", "util/dist/util.pbl"], &[]),
+        "não confere com o hash do cabeçalho",
+    );
+
+    area.editar("app/dist/bytecode/app.pbc", "formato=1", "formato=7");
+    assert_erro(&area.executar_app(&[]), "formato 7 (gerado pelo compilador");
+
+    // Apagar formato= não transforma o artefato em um legado sem verificação
+    area.editar("app/dist/bytecode/app.pbc", "formato=7\n", "");
+    assert_erro(&area.executar_app(&[]), "cabeçalho [PBC] sem formato=");
+}
+
+#[test]
+fn bytecode_sem_cabecalho_continua_aceito() {
+    let area = Area::nova("legado");
+    let caminho = area.caminho("app/dist/bytecode/app.pbc");
+    let pbc = fs::read_to_string(&caminho).unwrap();
+    let (_, corpo) = pbc.split_once("\n\n").unwrap();
+    fs::write(&caminho, corpo).unwrap();
+    let output = area.executar_app(&[]);
+    assert_sucesso(&output);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "42\n");
+}
+
+#[test]
+fn assinatura_destacada_e_chaves_confiaveis() {
+    let area = Area::nova("assinatura");
+    assert_sucesso(&area.compilador(&["chave", "autora.chave"], &[]));
+    assert_sucesso(&area.compilador(&["chave", "outra.chave"], &[]));
+    assert_erro(&area.compilador(&["chave", "autora.chave"], &[]), "já existe");
+    let publica = |arquivo: &str| {
+        let texto = fs::read_to_string(area.caminho(arquivo)).unwrap();
+        let linha = texto.lines().find(|l| l.starts_with("publica=")).unwrap();
+        fs::write(area.caminho(&format!("{}.pub", arquivo)), linha).unwrap();
+        area.caminho(&format!("{}.pub", arquivo))
+    };
+    let (confiavel, outra) = (publica("autora.chave"), publica("outra.chave"));
+
+    let pbl = "app/dist/bytecode/util.pbl";
+    // Com chaves confiáveis, bibliotecas sem assinatura são recusadas
+    assert_erro(
+        &area.executar_app(&[("PORDOSOL_CHAVES", &confiavel)]),
+        "sem assinatura",
+    );
+
+    assert_sucesso(&area.compilador(&["assinar", pbl, "--chave=autora.chave"], &[]));
+    assert!(area.caminho(&format!("{}.sig", pbl)).is_file());
+    assert_sucesso(&area.executar_app(&[("PORDOSOL_CHAVES", &confiavel)]));
+    assert_erro(
+        &area.executar_app(&[("PORDOSOL_CHAVES", &outra)]),
+        "que não está entre as confiáveis",
+    );
+    let output = area.compilador(&["verificar", pbl, "--chaves=autora.chave.pub"], &[]);
+    assert_sucesso(&output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("assinado por"));
+
+    // Mesmo sem chaves configuradas, uma assinatura presente precisa conferir: um artefato
+    // selado de novo tem hash válido, mas não é o que foi assinado
+    let conteudo = fs::read_to_string(area.caminho(pbl)).unwrap();
+    let (_, corpo) = conteudo.split_once("\n\n").unwrap();
+    let selado = artefato::selar(
+        Tipo::Biblioteca,
+        &[("nome", "util"), ("versao", "1.0.1")],
+        corpo,
+    );
+    fs::write(area.caminho(pbl), selado).unwrap();
+    assert_erro(&area.executar_app(&[]), "não confere com o arquivo");
+}
@@ -0,0 +1,80 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+#[test]
+fn avaliar_pontua_casos_e_grava_json() {
+    let root = repo_root();
+    let fixtures = root.join("tests").join("fixtures").join("avaliacao");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(&root)
+        .args([
+            fixtures.join("saudacao.pr").to_string_lossy().as_ref(),
+            "--target=bytecode",
+        ])
+        .output()
+        .expect("falha ao executar compilador");
+    assert!(
+        output.status.success(),
+        "compilador falhou: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let pbc = root.join("build").join("saudacao.pbc");
+    let json = root.join("build").join("saudacao.avaliacao.json");
+    let _ = fs::remove_file(&json);
+    let out = Command::new(env!("CARGO_BIN_EXE_interpretador"))
+        .current_dir(&root)
+        .arg(&pbc)
+        .args([
+            "--avaliar",
+            fixtures.join("casos").to_string_lossy().as_ref(),
+        ])
+        .args([
+            "--tempo-limite=1000",
+            &format!("--relatorio={}", json.display()),
+        ])
+        .output()
+        .expect("falha ao executar interpretador");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+
+    assert_eq!(out.status.code(), Some(1), "stdout:\n{}", stdout);
+    assert!(stdout.contains("caso 01_ana ... ok"));
+    // Espaços no fim das linhas e linhas vazias finais são tolerados por padrão
+    assert!(stdout.contains("caso 02_espacos ... ok"));
+    assert!(stdout.contains("caso 03_errado ... SAÍDA INCORRETA"));
+    assert!(stdout.contains(r#"linha 1: esperado "Olá, Caio.", obtido "Olá, Caio!""#));
+    assert!(stdout.contains("caso 04_laco ... TEMPO ESGOTADO"));
+    assert!(stdout.contains("nota: 2/4 caso(s) aprovado(s) (50.0%)"));
+
+    let relatorio: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&json).expect("relatório JSON não gerado"))
+            .expect("relatório JSON inválido");
+    assert_eq!(relatorio["aprovados"], 2);
+    assert_eq!(relatorio["total"], 4);
+    assert_eq!(relatorio["casos"][3]["status"], "tempo_esgotado");
+
+    // Na comparação exata, os espaços extras passam a contar
+    let out = Command::new(env!("CARGO_BIN_EXE_interpretador"))
+        .current_dir(&root)
+        .arg(&pbc)
+        .args([
+            "--avaliar",
+            fixtures.join("casos").to_string_lossy().as_ref(),
+        ])
+        .args(["--tempo-limite=1000", "--comparacao=exata"])
+        .output()
+        .expect("falha ao executar interpretador");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(
+        stdout.contains("caso 02_espacos ... SAÍDA INCORRETA"),
+        "stdout:\n{}",
+        stdout
+    );
+    assert!(stdout.contains("nota: 1/4 caso(s) aprovado(s) (25.0%)"));
+}
@@ -0,0 +1,166 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Compila `fonte` para `build/bytecode_test/<nome>/<nome>.pbc` e devolve o bytecode e a saída
+/// do interpretador.
+fn compilar_e_executar(nome: &str, fonte: &str) -> (String, String) {
+    let dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("build")
+        .join("bytecode_test")
+        .join(nome);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let fonte_path = dir.join(format!("{}.pr", nome));
+    fs::write(&fonte_path, fonte).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .arg(&fonte_path)
+        .arg("--target=bytecode")
+        .arg(format!("--output-dir={}", dir.display()))
+        .output()
+        .expect("falha ao executar compilador");
+    assert_sucesso(&output);
+    let pbc = dir.join(format!("{}.pbc", nome));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_interpretador"))
+        .arg(&pbc)
+        .output()
+        .expect("falha ao executar interpretador");
+    assert_sucesso(&output);
+    (
+        fs::read_to_string(&pbc).unwrap(),
+        String::from_utf8_lossy(&output.stdout).replace("\r\n", "\n"),
+    )
+}
+
+fn assert_sucesso(output: &Output) {
+    assert!(
+        output.status.success(),
+        "stdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// Corpo do `DEFINE_METHOD`/`DEFINE_STATIC_METHOD` de `Classe.metodo`.
+fn corpo_do_metodo<'a>(bytecode: &'a str, classe: &str, metodo: &str) -> Vec<&'a str> {
+    let mut linhas = bytecode.lines();
+    for linha in linhas.by_ref() {
+        let partes: Vec<&str> = linha.split_whitespace().collect();
+        if partes.len() > 4
+            && partes[0].starts_with("DEFINE_")
+            && partes[1] == classe
+            && partes[2] == metodo
+        {
+            let tamanho: usize = partes[4].parse().unwrap();
+            return linhas.take(tamanho).collect();
+        }
+    }
+    panic!("método {}.{} não encontrado:\n{}", classe, metodo, bytecode);
+}
+
+#[test]
+fn parametros_padrao_deslocam_os_saltos_do_corpo() {
+    let (bytecode, saida) = compilar_e_executar(
+        "padrao_com_saltos",
+        r#"publico classe Contador {
+    publico Contador() { }
+
+    publico inteiro Contar(inteiro ate, inteiro passo = 1) {
+        inteiro total = 0;
+        inteiro i = 0;
+        enquanto (i < ate) {
+            se (i == 2) { total = total + 10; } senão { total = total + passo; }
+            i = i + 1;
+        }
+        retorne total;
+    }
+}
+
+função vazio Principal() {
+    var c = novo Contador();
+    imprima(c.Contar(4));
+    imprima(c.Contar(4, 3));
+}
+"#,
+    );
+    assert_eq!(saida, "13\n19\n");
+
+    // O SET_DEFAULT vem antes do corpo e todos os saltos continuam dentro do método
+    let corpo = corpo_do_metodo(&bytecode, "Contador", "Contar");
+    assert!(corpo[0].starts_with("SET_DEFAULT passo "), "{:?}", corpo);
+    for instrucao in &corpo {
+        if let Some(destino) = instrucao
+            .strip_prefix("JUMP ")
+            .or_else(|| instrucao.strip_prefix("JUMP_IF_FALSE "))
+        {
+            let destino: usize = destino.parse().unwrap();
+            assert!(destino > 0 && destino <= corpo.len(), "{:?}", corpo);
+        }
+    }
+}
+
+#[test]
+fn construtor_base_sobe_um_nivel_por_vez() {
+    let (bytecode, saida) = compilar_e_executar(
+        "cadeia_de_construtores",
+        r#"publico classe A {
+    publico inteiro Valor { obter; definir; }
+    publico A(inteiro v) { Valor = v; imprima("A " + v); }
+}
+
+publico classe B : A {
+    publico B(inteiro v) : base(v + 1) { imprima("B " + v); }
+}
+
+publico classe C : B {
+    publico C(inteiro v) : base(v * 10) { imprima("C " + v); }
+}
+
+função vazio Principal() {
+    var c = novo C(2);
+    imprima(c.Valor);
+}
+"#,
+    );
+    // Em `C`, `este` é sempre um `C`: a instrução diz de qual construtor a chamada parte
+    assert!(bytecode.contains("CALL_BASE_CONSTRUCTOR 1 C\n"), "{}", bytecode);
+    assert!(bytecode.contains("CALL_BASE_CONSTRUCTOR 1 B\n"), "{}", bytecode);
+    assert_eq!(saida, "A 21\nB 20\nC 2\n21\n");
+}
+
+#[test]
+fn chamada_sem_alvo_em_metodo_estatico_nao_usa_este() {
+    let (bytecode, saida) = compilar_e_executar(
+        "chamada_sem_alvo",
+        r#"função texto Rotulo(inteiro n) { retorne "global " + n; }
+
+publico classe Caixa {
+    publico inteiro Valor { obter; definir; }
+    publico Caixa(inteiro v) { Valor = v; }
+
+    publico texto Rotulo(inteiro n) { retorne "caixa " + (Valor + n); }
+    publico texto Descrever() { retorne Rotulo(1); }
+
+    publico estática inteiro Dobro(inteiro x) { retorne x * 2; }
+    publico estática texto Fabricar(inteiro x) {
+        retorne Rotulo(Dobro(x));
+    }
+}
+
+função vazio Principal() {
+    var c = novo Caixa(10);
+    imprima(c.Descrever());
+    imprima(Caixa.Fabricar(4));
+}
+"#,
+    );
+    assert_eq!(saida, "caixa 11\nglobal 8\n");
+
+    let fabricar = corpo_do_metodo(&bytecode, "Caixa", "Fabricar");
+    assert!(!fabricar.contains(&"LOAD_VAR este"), "{:?}", fabricar);
+    assert!(fabricar.contains(&"CALL_STATIC_METHOD Caixa Dobro 1"), "{:?}", fabricar);
+    let descrever = corpo_do_metodo(&bytecode, "Caixa", "Descrever");
+    assert!(descrever.contains(&"LOAD_VAR este"), "{:?}", descrever);
+}
@@ -0,0 +1,145 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// O alvo C usa `CC` ou o primeiro entre cc, gcc e clang.
+fn have_c_compiler() -> bool {
+    std::env::var_os("CC").is_some()
+        || ["cc", "gcc", "clang"]
+            .iter()
+            .any(|c| Command::new(c).arg("--version").output().is_ok())
+}
+
+fn fixture(nome: &str) -> PathBuf {
+    repo_root()
+        .join("tests")
+        .join("fixtures")
+        .join("c")
+        .join(format!("{}.pr", nome))
+}
+
+/// Compila o fixture para C em `build/c/<subdir>` (o alvo grava o .c e o executável no
+/// diretório atual) e devolve o diretório e a saída do compilador.
+fn compilar(nome: &str, subdir: &str, extras: &[&str]) -> (PathBuf, Output) {
+    let dir = repo_root().join("build").join("c").join(subdir);
+    fs::create_dir_all(&dir).unwrap();
+    let _ = fs::remove_file(dir.join(format!("{}.c", nome)));
+    let output = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(&dir)
+        .arg(fixture(nome))
+        .arg("--target=c")
+        .args(extras)
+        .output()
+        .expect("falha ao executar compilador");
+    (dir, output)
+}
+
+fn ler_c(dir: &Path, nome: &str) -> String {
+    fs::read_to_string(dir.join(format!("{}.c", nome)))
+        .unwrap_or_else(|_| panic!("{}.c não gerado", nome))
+}
+
+fn executar(dir: &Path, nome: &str) -> Output {
+    let exe = if cfg!(windows) {
+        dir.join(format!("{}.exe", nome))
+    } else {
+        dir.join(nome)
+    };
+    Command::new(&exe)
+        .output()
+        .expect("falha ao executar programa")
+}
+
+#[test]
+fn c_gerado_tem_structs_e_vtables_por_classe() {
+    let (dir, _) = compilar("recursos", "codigo", &[]);
+    let c = ler_c(&dir, "recursos");
+
+    // A struct da classe derivada começa com a da classe pai
+    assert!(
+        c.contains("struct FormaBase {\n    PdsObjeto base;\n"),
+        "C:\n{}",
+        c
+    );
+    assert!(c.contains("struct Quadrado {\n    FormaBase base;\n    double Lado;\n"));
+    // Vtables encadeadas e instanciadas com inicializadores designados
+    assert!(c.contains("typedef struct Quadrado_vtable {\n    FormaBase_vtable base;\n"));
+    assert!(c.contains("    double (*Area)(FormaBase *);\n"));
+    assert!(c.contains("    .base.Area = Quadrado_Area,\n"));
+    // Método abstrato sem implementação na classe base
+    assert!(c.contains("    .Area = NULL,\n"));
+    // Estáticos são globais registrados como raízes do coletor
+    assert!(c.contains("static const char *Contador_Rotulo;"));
+    assert!(c.contains("pds_gc_adicionar_raiz(&Contador_Rotulo, sizeof(Contador_Rotulo));"));
+    // Chamadas pela classe base usam a vtable; em interfaces, a função de despacho pelo nome
+    assert!(c.contains("FormaBase_Area_virtual(pds_t"));
+    assert!(c.contains("static double pds_despachar_Area_0(PdsObjeto *objeto)"));
+    // Propriedade com corpo vira par de funções
+    assert!(c.contains("int64_t Conta_obter_Saldo(Conta *este)"));
+    assert!(c.contains("void Conta_definir_Saldo(Conta *este, int64_t valor)"));
+}
+
+#[test]
+fn executavel_c_reproduz_o_interpretador() {
+    if !have_c_compiler() {
+        eprintln!("compilador C não encontrado; ignorando teste do alvo C.");
+        return;
+    }
+    let (dir, output) = compilar("recursos", "executavel", &["-O2"]);
+    assert!(
+        output.status.success(),
+        "compilador falhou:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let execucao = executar(&dir, "recursos");
+    assert!(execucao.status.success());
+
+    let referencia = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .arg(fixture("recursos"))
+        .arg("--executar")
+        .output()
+        .expect("falha ao executar compilador");
+    assert!(referencia.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&execucao.stdout).replace("\r\n", "\n"),
+        String::from_utf8_lossy(&referencia.stdout).replace("\r\n", "\n")
+    );
+}
+
+#[test]
+fn verificacoes_de_execucao_podem_ser_omitidas() {
+    let (dir, _) = compilar("indice", "sem", &["--sem-verificacoes"]);
+    let c = ler_c(&dir, "indice");
+    assert!(!c.contains("pds_indice("), "C:\n{}", c);
+
+    let (dir, output) = compilar("indice", "com", &[]);
+    let c = ler_c(&dir, "indice");
+    assert!(c.contains("pds_indice(numeros, 3, "), "C:\n{}", c);
+
+    if !have_c_compiler() {
+        eprintln!("compilador C não encontrado; ignorando teste do alvo C.");
+        return;
+    }
+    assert!(
+        output.status.success(),
+        "compilador falhou:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let execucao = executar(&dir, "indice");
+    assert_eq!(execucao.status.code(), Some(1));
+    assert_eq!(
+        String::from_utf8_lossy(&execucao.stdout).replace("\r\n", "\n"),
+        "20\n"
+    );
+    let stderr = String::from_utf8_lossy(&execucao.stderr);
+    assert!(
+        stderr.contains("Erro em tempo de execução: Índice fora do intervalo (em ")
+            && stderr.contains("indice.pr:4:"),
+        "stderr:\n{}",
+        stderr
+    );
+}
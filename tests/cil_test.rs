@@ -0,0 +1,279 @@
+use compilador_portugues::codegen::cil;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn fixture(nome: &str) -> PathBuf {
+    repo_root()
+        .join("tests")
+        .join("fixtures")
+        .join("cil")
+        .join(format!("{}.pr", nome))
+}
+
+/// Compila o arquivo para CIL em `build/cil/<subdir>` (o alvo grava o .il no diretório atual) e
+/// devolve o diretório e a saída do compilador.
+fn compilar_arquivo(arquivo: &Path, subdir: &str, extras: &[&str]) -> (PathBuf, Output) {
+    let dir = repo_root().join("build").join("cil").join(subdir);
+    fs::create_dir_all(&dir).unwrap();
+    let nome = arquivo.file_stem().unwrap().to_string_lossy().to_string();
+    let _ = fs::remove_file(dir.join(format!("{}.il", nome)));
+    let output = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(&dir)
+        .arg(arquivo)
+        .arg("--target=cil-bytecode")
+        .args(extras)
+        .output()
+        .expect("falha ao executar compilador");
+    (dir, output)
+}
+
+fn compilar(nome: &str, subdir: &str, extras: &[&str]) -> String {
+    let (dir, output) = compilar_arquivo(&fixture(nome), subdir, extras);
+    assert!(
+        output.status.success(),
+        "compilador falhou:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    fs::read_to_string(dir.join(format!("{}.il", nome)))
+        .unwrap_or_else(|_| panic!("{}.il não gerado", nome))
+}
+
+fn interpretar(nome: &str, entrada: &str) -> Output {
+    use std::io::Write;
+    use std::process::Stdio;
+    let mut filho = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .arg(fixture(nome))
+        .arg("--executar")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("falha ao executar compilador");
+    filho
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(entrada.as_bytes())
+        .unwrap();
+    filho.wait_with_output().unwrap()
+}
+
+fn disponivel(programa: &str) -> bool {
+    Command::new(programa).arg("--version").output().is_ok()
+}
+
+#[test]
+fn il_gerado_tem_classes_interfaces_e_callvirt() {
+    let il = compilar("recursos", "codigo", &[]);
+    cil::verificar(&il).unwrap_or_else(|e| panic!("IL inválido: {}\n{}", e, il));
+
+    // Uma classe .NET por classe da linguagem, com herança e interfaces
+    assert!(il.contains(".class interface public abstract auto ansi 'IForma'"));
+    assert!(il.contains(
+        ".class public abstract auto ansi beforefieldinit 'FormaBase'\n       extends PorDoSol.Objeto\n       implements 'IForma'"
+    ));
+    assert!(il.contains(
+        ".class public auto ansi beforefieldinit 'Quadrado'\n       extends 'FormaBase'"
+    ));
+    assert!(il.contains(".override method instance float64 'IForma'::'Area'()"));
+    // Métodos redefiníveis e abstratos são virtuais e chamados com callvirt
+    assert!(il.contains(
+        ".method public hidebysig newslot abstract virtual instance float64 'Area'() cil managed"
+    ));
+    assert!(il
+        .contains(".method public hidebysig newslot virtual instance string 'Nome'() cil managed"));
+    assert!(il.contains(".method public hidebysig virtual instance float64 'Area'() cil managed"));
+    assert!(il.contains("callvirt instance float64 'FormaBase'::'Area'()"));
+    assert!(il.contains("callvirt instance string 'IForma'::'Nome'()"));
+    // Construtores chamam o construtor da classe pai
+    assert!(il.contains("instance void .ctor(float64 'lado') cil managed"));
+    assert!(il.contains("call instance void 'FormaBase'::.ctor(string)"));
+    assert!(il.contains("newobj instance void 'Pessoa'::.ctor(string, int64)"));
+    // Campos, propriedades e estáticos
+    assert!(il.contains(".field public int64 'Idade'"));
+    assert!(il.contains(".field public static int64 'Total'"));
+    assert!(il.contains(".property instance float64 'Saldo'()"));
+    assert!(il.contains(".set instance void 'Conta'::'set_Saldo'(float64)"));
+    assert!(il.contains(
+        ".locals init (class 'FormaBase'[] 'pds_t0', class 'FormaBase'[] 'formas', int64 'i'"
+    ));
+    assert!(il.contains(".entrypoint"));
+}
+
+#[test]
+fn il_cobre_controle_de_fluxo_e_operadores() {
+    let il = compilar("recursos", "operadores", &[]);
+    let principal = &il[il.find("'Principal'() cil managed").unwrap()..];
+    let principal = &principal[..principal.find("\n  }\n").unwrap()];
+    let instrucoes: Vec<&str> = principal.lines().map(str::trim).collect();
+    for opcode in ["add", "sub", "mul", "ceq", "clt", "cgt", "neg"] {
+        assert!(
+            instrucoes.contains(&opcode),
+            "sem {}:\n{}",
+            opcode,
+            principal
+        );
+    }
+    assert!(principal
+        .contains("call int64 PorDoSol.Runtime::Dividir(int64, int64, string, int32, int32)"));
+    assert!(principal
+        .contains("call int64 PorDoSol.Runtime::Resto(int64, int64, string, int32, int32)"));
+    assert!(principal.contains("call float64 PorDoSol.Runtime::DividirDuplo("));
+    // `para`/`enquanto` voltam ao rótulo do laço; `se`/`senão` e `&&`/`||` desviam
+    assert!(principal.contains("  L1_laco:\n"));
+    assert!(principal.contains("br L1_laco"));
+    assert!(principal.contains("brfalse L2_fim"));
+    assert!(principal.contains("_senao"));
+    assert!(principal.contains("brfalse L") && principal.contains("brtrue L"));
+
+    let sem = compilar("recursos", "operadores_sem", &["--sem-verificacoes"]);
+    let principal = &sem[sem.find("'Principal'() cil managed").unwrap()..];
+    let principal = &principal[..principal.find("\n  }\n").unwrap()];
+    let instrucoes: Vec<&str> = principal.lines().map(str::trim).collect();
+    assert!(instrucoes.contains(&"div") && instrucoes.contains(&"rem"));
+}
+
+#[test]
+fn verificacoes_de_execucao_podem_ser_omitidas() {
+    let il = compilar("indice", "sem", &["--sem-verificacoes"]);
+    cil::verificar(&il).unwrap();
+    let programa = &il[il
+        .find(".class public abstract sealed auto ansi beforefieldinit PorDoSol.Programa")
+        .unwrap()..];
+    assert!(
+        !programa.contains("PorDoSol.Runtime::Indice"),
+        "IL:\n{}",
+        programa
+    );
+
+    let il = compilar("indice", "com", &[]);
+    let programa = &il[il
+        .find(".class public abstract sealed auto ansi beforefieldinit PorDoSol.Programa")
+        .unwrap()..];
+    assert!(programa.contains("call int32 PorDoSol.Runtime::Indice("));
+}
+
+#[test]
+fn exemplos_geram_il_estruturalmente_valido() {
+    let mut verificados = 0;
+    for entrada in fs::read_dir(repo_root().join("exemplos")).unwrap() {
+        let caminho = entrada.unwrap().path();
+        if caminho.extension().and_then(|e| e.to_str()) != Some("pr") {
+            continue;
+        }
+        let (dir, output) = compilar_arquivo(&caminho, "exemplos", &[]);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.status.success() {
+            // Exemplos negativos são rejeitados antes da geração de código
+            assert!(
+                stderr.contains("Houve erros semânticos"),
+                "{} falhou:\n{}",
+                caminho.display(),
+                stderr
+            );
+            continue;
+        }
+        let nome = caminho.file_stem().unwrap().to_string_lossy().to_string();
+        let il = fs::read_to_string(dir.join(format!("{}.il", nome))).unwrap();
+        cil::verificar(&il).unwrap_or_else(|e| panic!("{}: {}", caminho.display(), e));
+        verificados += 1;
+    }
+    assert!(
+        verificados > 20,
+        "apenas {} exemplos compilados",
+        verificados
+    );
+}
+
+#[test]
+fn verificar_rejeita_il_malformado() {
+    let il = compilar("recursos", "malformado", &[]);
+    assert!(cil::verificar(&il).is_ok());
+
+    let sem_rotulo = il.replacen("  L1_laco:\n", "", 1);
+    assert!(cil::verificar(&sem_rotulo).unwrap_err().contains("L1_laco"));
+
+    let sem_chave = il.replacen("  }\n", "", 1);
+    assert!(cil::verificar(&sem_chave).is_err());
+
+    let pilha_pequena = il.replacen(".maxstack 7", ".maxstack 1", 1);
+    assert!(cil::verificar(&pilha_pequena)
+        .unwrap_err()
+        .contains(".maxstack"));
+
+    let membro = il.replacen(
+        "call int64 PorDoSol.Programa::'Fatorial'(int64)",
+        "call int64 PorDoSol.Programa::'Fatoria'(int64)",
+        1,
+    );
+    assert!(cil::verificar(&membro).unwrap_err().contains("Fatoria"));
+}
+
+/// Monta o IL com o `ilasm` e executa o assembly (com o `mono` fora do Windows), quando as
+/// ferramentas estão instaladas.
+#[test]
+fn assembly_montado_reproduz_o_interpretador() {
+    if !disponivel("ilasm") {
+        eprintln!("ilasm não encontrado; teste de montagem ignorado");
+        return;
+    }
+    let entrada = "Bia\n";
+    compilar("recursos", "executavel", &[]);
+    let dir = repo_root().join("build").join("cil").join("executavel");
+    let montagem = Command::new("ilasm")
+        .current_dir(&dir)
+        .arg("recursos.il")
+        .arg("/exe")
+        .arg("/output:recursos.exe")
+        .output()
+        .expect("falha ao executar ilasm");
+    assert!(
+        montagem.status.success(),
+        "ilasm falhou:\n{}{}",
+        String::from_utf8_lossy(&montagem.stdout),
+        String::from_utf8_lossy(&montagem.stderr)
+    );
+
+    let mut comando = if cfg!(windows) {
+        Command::new(dir.join("recursos.exe"))
+    } else if disponivel("mono") {
+        let mut mono = Command::new("mono");
+        mono.arg(dir.join("recursos.exe"));
+        mono
+    } else {
+        eprintln!("mono não encontrado; execução do assembly ignorada");
+        return;
+    };
+    use std::io::Write;
+    use std::process::Stdio;
+    let mut filho = comando
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    filho
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(entrada.as_bytes())
+        .unwrap();
+    let execucao = filho.wait_with_output().unwrap();
+    assert!(
+        execucao.status.success(),
+        "stderr:\n{}",
+        String::from_utf8_lossy(&execucao.stderr)
+    );
+
+    let referencia = interpretar("recursos", entrada);
+    assert!(referencia.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&execucao.stdout).replace("\r\n", "\n"),
+        String::from_utf8_lossy(&referencia.stdout).replace("\r\n", "\n")
+    );
+}
@@ -0,0 +1,158 @@
+use compilador_portugues::artefato::{self, Tipo};
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+const ANTIGA: &str = r#"[PBL]
+nome=geo
+versao=1.2.0
+
+[MANIFESTO]
+DEFINE_ENUM Geo.Cor Vermelho Verde
+DEFINE_INTERFACE Geo.IForma
+INTERFACE_METHOD Geo.IForma Area duplo 0
+DEFINE_CLASS Geo.Forma NULO abstrata interfaces=Geo.IForma
+DEFINE_CONSTRUCTOR Geo.Forma 1 texto:rotulo
+DEFINE_METHOD Geo.Forma Area duplo 0 abstrato
+DEFINE_METHOD Geo.Forma Nome texto 0 redefinivel
+DEFINE_CLASS Geo.Quadrado Geo.Forma
+DEFINE_CONSTRUCTOR Geo.Quadrado 1 duplo:lado
+PROPERTY Geo.Quadrado lado duplo
+DEFINE_METHOD Geo.Quadrado Area duplo 0 sobrescreve
+DEFINE_FUNCTION Geo.Dobro inteiro 1 inteiro:x
+
+[BYTECODE]
+"#;
+
+/// `.pbl`s escritos à mão em `build/compat/<nome>/`, selados com [`selar`].
+struct Area {
+    raiz: PathBuf,
+}
+
+impl Area {
+    fn nova(nome: &str) -> Self {
+        let raiz = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("build")
+            .join("compat")
+            .join(nome);
+        let _ = fs::remove_dir_all(&raiz);
+        fs::create_dir_all(&raiz).unwrap();
+        fs::write(raiz.join("antiga.pbl"), selar(ANTIGA)).unwrap();
+        Self { raiz }
+    }
+
+    /// Compara a versão antiga com ela mesma após as substituições dadas.
+    fn compat(&self, substituicoes: &[(&str, &str)]) -> Output {
+        let mut nova = ANTIGA.to_string();
+        for (de, para) in substituicoes {
+            assert!(nova.contains(de), "'{}' não está no manifesto", de);
+            nova = nova.replace(de, para);
+        }
+        fs::write(self.raiz.join("nova.pbl"), selar(&nova)).unwrap();
+        Command::new(env!("CARGO_BIN_EXE_compilador"))
+            .current_dir(&self.raiz)
+            .args(["compat", "antiga.pbl", "nova.pbl"])
+            .output()
+            .expect("falha ao executar compilador")
+    }
+}
+
+/// Sela o manifesto escrito à mão, levando `nome=`/`versao=` para o cabeçalho com formato e hash.
+fn selar(texto: &str) -> String {
+    let (cabecalho, corpo) = texto.split_once("\n\n").unwrap();
+    let metadados: Vec<(&str, &str)> = cabecalho
+        .lines()
+        .filter_map(|linha| linha.split_once('='))
+        .collect();
+    artefato::selar(Tipo::Biblioteca, &metadados, corpo)
+}
+
+fn saida(output: &Output) -> String {
+    format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+}
+
+#[test]
+fn acrescimos_pedem_versao_minor() {
+    let area = Area::nova("acrescimos");
+    let output = area.compat(&[
+        ("versao=1.2.0", "versao=1.3.0"),
+        ("Vermelho Verde", "Vermelho Verde Azul"),
+        (
+            "DEFINE_FUNCTION Geo.Dobro inteiro 1 inteiro:x",
+            "DEFINE_FUNCTION Geo.Dobro inteiro 2 inteiro:x inteiro:vezes=2\nDEFINE_FUNCTION Geo.Triplo inteiro 1 inteiro:x",
+        ),
+        ("PROPERTY Geo.Quadrado lado duplo", "PROPERTY Geo.Quadrado lado duplo\nDEFINE_METHOD Geo.Quadrado Perimetro duplo 0"),
+    ]);
+    let texto = saida(&output);
+    assert!(output.status.success(), "{}", texto);
+    for trecho in [
+        "Compatibilidade: geo 1.2.0 → geo 1.3.0",
+        "Mudanças compatíveis (4):",
+        "✓ enumeração Geo.Cor: valor 'Azul' adicionado",
+        "✓ função Geo.Dobro: novo parâmetro opcional 'vezes'",
+        "✓ função Geo.Triplo: adicionada",
+        "✓ classe Geo.Quadrado: método 'Perimetro' adicionado",
+        "Incremento necessário: minor (1.2.0 → 1.3.0)",
+        "✅ A versão 1.3.0 é adequada.",
+    ] {
+        assert!(texto.contains(trecho), "esperado '{}' em:\n{}", trecho, texto);
+    }
+    assert!(!texto.contains("quebram"), "{}", texto);
+}
+
+#[test]
+fn quebras_exigem_versao_major() {
+    let area = Area::nova("quebras");
+    let output = area.compat(&[
+        ("versao=1.2.0", "versao=1.3.0"),
+        ("Vermelho Verde", "Verde Vermelho"),
+        ("INTERFACE_METHOD Geo.IForma Area duplo 0", "INTERFACE_METHOD Geo.IForma Area duplo 0\nINTERFACE_METHOD Geo.IForma Perimetro duplo 0"),
+        ("DEFINE_METHOD Geo.Forma Nome texto 0 redefinivel", "DEFINE_METHOD Geo.Forma Nome texto 0\nDEFINE_METHOD Geo.Forma Cor texto 0 abstrato"),
+        ("DEFINE_CLASS Geo.Quadrado Geo.Forma", "DEFINE_CLASS Geo.Quadrado NULO"),
+        ("DEFINE_CONSTRUCTOR Geo.Quadrado 1 duplo:lado", "DEFINE_CONSTRUCTOR Geo.Quadrado 2 duplo:lado duplo:altura"),
+        ("PROPERTY Geo.Quadrado lado duplo", "PROPERTY Geo.Quadrado lado inteiro"),
+        ("DEFINE_FUNCTION Geo.Dobro inteiro 1 inteiro:x", "DEFINE_FUNCTION Geo.Dobro texto 1 texto:x"),
+    ]);
+    let texto = saida(&output);
+    assert_eq!(output.status.code(), Some(1), "{}", texto);
+    for trecho in [
+        "✗ enumeração Geo.Cor: valor 'Vermelho' mudou de posição (0 → 1)",
+        "✗ interface Geo.IForma: novo método exigido 'Perimetro'",
+        "✗ classe Geo.Forma: novo método abstrato 'Cor'",
+        "✗ classe Geo.Forma: método 'Nome': deixou de ser redefinível",
+        "✗ classe Geo.Quadrado: classe base mudou de Geo.Forma para nenhuma",
+        "✗ classe Geo.Quadrado: construtor (duplo lado) removido ou alterado",
+        "✗ classe Geo.Quadrado: tipo de 'lado' mudou de duplo para inteiro",
+        "✗ função Geo.Dobro: retorno mudou de inteiro para texto",
+        "✗ função Geo.Dobro: parâmetro 1 ('x') mudou de inteiro para texto",
+        "Incremento necessário: major (1.2.0 → 2.0.0)",
+        "❌ a versão 1.3.0 não basta para mudanças major: use 2.0.0 ou maior",
+    ] {
+        assert!(texto.contains(trecho), "esperado '{}' em:\n{}", trecho, texto);
+    }
+
+    // Remoções também quebram
+    let texto = saida(&area.compat(&[
+        ("versao=1.2.0", "versao=2.0.0"),
+        ("DEFINE_FUNCTION Geo.Dobro inteiro 1 inteiro:x\n", ""),
+    ]));
+    assert!(texto.contains("✗ função Geo.Dobro: removida da biblioteca"), "{}", texto);
+    assert!(texto.contains("✅ A versão 2.0.0 é adequada."), "{}", texto);
+}
+
+#[test]
+fn sem_mudancas_basta_um_patch() {
+    let area = Area::nova("patch");
+    let output = area.compat(&[("versao=1.2.0", "versao=1.2.0")]);
+    let texto = saida(&output);
+    assert_eq!(output.status.code(), Some(1), "{}", texto);
+    assert!(texto.contains("Nenhuma mudança na API pública."), "{}", texto);
+    assert!(texto.contains("Incremento necessário: patch (1.2.0 → 1.2.1)"), "{}", texto);
+
+    let output = area.compat(&[("versao=1.2.0", "versao=1.2.1")]);
+    assert!(output.status.success(), "{}", saida(&output));
+}
@@ -0,0 +1,198 @@
+use compilador_portugues::codegen::console;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn fixture(nome: &str) -> PathBuf {
+    repo_root()
+        .join("tests")
+        .join("fixtures")
+        .join("console")
+        .join(format!("{}.pr", nome))
+}
+
+/// Gera o projeto de console em `build/console/<subdir>/<nome>` (o alvo grava o projeto no
+/// diretório atual) e devolve o diretório do projeto e a saída do compilador.
+fn compilar_arquivo(arquivo: &Path, subdir: &str) -> (PathBuf, Output) {
+    let dir = repo_root().join("build").join("console").join(subdir);
+    fs::create_dir_all(&dir).unwrap();
+    let nome = arquivo.file_stem().unwrap().to_string_lossy().to_string();
+    let _ = fs::remove_dir_all(dir.join(&nome));
+    let output = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(&dir)
+        .arg(arquivo)
+        .arg("--target=console")
+        .output()
+        .expect("falha ao executar compilador");
+    (dir.join(nome), output)
+}
+
+fn compilar(arquivo: &Path, subdir: &str) -> (PathBuf, String) {
+    let (projeto, output) = compilar_arquivo(arquivo, subdir);
+    assert!(
+        output.status.success(),
+        "compilador falhou:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let codigo = fs::read_to_string(projeto.join("Program.cs"))
+        .unwrap_or_else(|_| panic!("Program.cs não gerado em {}", projeto.display()));
+    (projeto, codigo)
+}
+
+/// Compara o `Program.cs` gerado com `tests/fixtures/console/<nome>.cs`. Com
+/// `PDS_ATUALIZAR_SNAPSHOTS=1` o snapshot é regravado.
+fn conferir_snapshot(nome: &str) {
+    let (_, codigo) = compilar(&fixture(nome), "snapshots");
+    let snapshot = fixture(nome).with_extension("cs");
+    if std::env::var_os("PDS_ATUALIZAR_SNAPSHOTS").is_some() {
+        fs::write(&snapshot, &codigo).unwrap();
+        return;
+    }
+    let esperado = fs::read_to_string(&snapshot)
+        .unwrap_or_else(|_| panic!("snapshot {} não encontrado", snapshot.display()));
+    assert_eq!(
+        codigo.replace("\r\n", "\n"),
+        esperado.replace("\r\n", "\n"),
+        "Program.cs de {} difere do snapshot (PDS_ATUALIZAR_SNAPSHOTS=1 regrava)",
+        nome
+    );
+}
+
+fn executar(comando: &mut Command, entrada: &str) -> Output {
+    use std::io::Write;
+    use std::process::Stdio;
+    let mut filho = comando
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("falha ao executar processo");
+    filho
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(entrada.as_bytes())
+        .unwrap();
+    filho.wait_with_output().unwrap()
+}
+
+fn disponivel(programa: &str) -> bool {
+    Command::new(programa).arg("--version").output().is_ok()
+}
+
+#[test]
+fn classes_heranca_propriedades_e_operadores_conferem_com_snapshot() {
+    conferir_snapshot("recursos");
+}
+
+#[test]
+fn genericos_async_namespaces_e_listas_conferem_com_snapshot() {
+    conferir_snapshot("avancado");
+}
+
+#[test]
+fn projeto_tem_csproj_e_runtime() {
+    let (projeto, codigo) = compilar(&fixture("recursos"), "projeto");
+    let csproj = fs::read_to_string(projeto.join("recursos.csproj")).unwrap();
+    assert!(csproj.contains("<OutputType>Exe</OutputType>"));
+    assert!(csproj.contains("<TargetFramework>net8.0</TargetFramework>"));
+    assert!(csproj.contains("<AssemblyName>recursos</AssemblyName>"));
+    assert_eq!(
+        fs::read_to_string(projeto.join("PorDoSol.cs")).unwrap(),
+        console::runtime()
+    );
+    // O ponto de entrada fica no namespace do runtime e chama `Principal`
+    assert!(codigo.contains("namespace PorDoSol\n{\n    internal static class Inicio"));
+    assert!(codigo.contains("global::Programa.Principal();"));
+}
+
+#[test]
+fn funcoes_externas_viram_dllimport() {
+    let arquivo = repo_root()
+        .join("tests")
+        .join("fixtures")
+        .join("externo")
+        .join("matematica.pr");
+    let (_, codigo) = compilar(&arquivo, "externo");
+    assert!(codigo.contains("using System.Runtime.InteropServices;"));
+    assert!(codigo.contains(
+        "[DllImport(\"libm\", EntryPoint = \"cos\")]\n    private static extern double Cosseno_nativo(double x);"
+    ));
+    assert!(codigo.contains("return EhDigito_nativo((int)caractere) != 0;"));
+    assert!(codigo.contains("[MarshalAs(UnmanagedType.LPUTF8Str)] string t"));
+}
+
+#[test]
+fn exemplos_geram_projetos() {
+    let mut gerados = 0;
+    for entrada in fs::read_dir(repo_root().join("exemplos")).unwrap() {
+        let caminho = entrada.unwrap().path();
+        if caminho.extension().and_then(|e| e.to_str()) != Some("pr") {
+            continue;
+        }
+        let (projeto, output) = compilar_arquivo(&caminho, "exemplos");
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !output.status.success() {
+            // Exemplos negativos são rejeitados antes da geração de código
+            assert!(
+                stderr.contains("Houve erros semânticos"),
+                "{} falhou:\n{}",
+                caminho.display(),
+                stderr
+            );
+            continue;
+        }
+        let codigo = fs::read_to_string(projeto.join("Program.cs")).unwrap();
+        let abertas = codigo.matches('{').count() - codigo.matches("{{").count() * 2;
+        let fechadas = codigo.matches('}').count() - codigo.matches("}}").count() * 2;
+        assert_eq!(abertas, fechadas, "{}:\n{}", caminho.display(), codigo);
+        assert!(
+            !codigo.contains("Runtime.Falhar"),
+            "{} usa construções não traduzidas:\n{}",
+            caminho.display(),
+            codigo
+        );
+        gerados += 1;
+    }
+    assert!(gerados > 20, "apenas {} exemplos gerados", gerados);
+}
+
+/// Compila e executa o projeto com o `dotnet`, quando instalado, e compara com o interpretador.
+#[test]
+fn projeto_executado_reproduz_o_interpretador() {
+    if !disponivel("dotnet") {
+        eprintln!("dotnet não encontrado; teste de execução ignorado");
+        return;
+    }
+    let entrada = "Bia\n";
+    let (projeto, _) = compilar(&fixture("recursos"), "executavel");
+    let execucao = executar(
+        Command::new("dotnet")
+            .current_dir(&projeto)
+            .arg("run")
+            .arg("--nologo"),
+        entrada,
+    );
+    assert!(
+        execucao.status.success(),
+        "dotnet run falhou:\n{}{}",
+        String::from_utf8_lossy(&execucao.stdout),
+        String::from_utf8_lossy(&execucao.stderr)
+    );
+
+    let referencia = executar(
+        Command::new(env!("CARGO_BIN_EXE_compilador"))
+            .arg(fixture("recursos"))
+            .arg("--executar"),
+        entrada,
+    );
+    assert!(referencia.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&execucao.stdout).replace("\r\n", "\n"),
+        String::from_utf8_lossy(&referencia.stdout).replace("\r\n", "\n")
+    );
+}
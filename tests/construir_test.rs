@@ -0,0 +1,182 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Recria `build/construir/<subdir>` com os arquivos `(caminho, conteúdo)`.
+fn projeto(subdir: &str, arquivos: &[(&str, &str)]) -> PathBuf {
+    let dir = repo_root().join("build").join("construir").join(subdir);
+    let _ = fs::remove_dir_all(&dir);
+    for (nome, conteudo) in arquivos {
+        let caminho = dir.join(nome);
+        fs::create_dir_all(caminho.parent().unwrap()).unwrap();
+        fs::write(caminho, conteudo).unwrap();
+    }
+    dir
+}
+
+fn construir(dir: &Path, extras: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(dir)
+        .arg("construir")
+        .args(extras)
+        .output()
+        .expect("falha ao executar compilador")
+}
+
+fn assert_sucesso(output: &Output) {
+    assert!(
+        output.status.success(),
+        "stdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+fn assert_erro(output: &Output, trecho: &str) {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "deveria falhar");
+    assert!(
+        stderr.contains(trecho),
+        "esperado '{}' em:\n{}",
+        trecho,
+        stderr
+    );
+}
+
+const APLICACAO: &[(&str, &str)] = &[
+    (
+        "app/projeto.toml",
+        r#"
+[projeto]
+nome = "calc"
+versao = "1.0.0"
+alvos = ["bytecode"]
+
+[dependencias]
+util = { caminho = "../util" }
+"#,
+    ),
+    (
+        "app/src/principal.pr",
+        r#"usando Calc.Formatos;
+usando Util;
+
+função Principal() {
+    imprima(Rotulo("dobro", Matematica.Dobro(21)));
+}
+"#,
+    ),
+    (
+        "app/src/formatos.pr",
+        r#"espaco Calc.Formatos {
+    publico função texto Rotulo(texto nome, inteiro valor) {
+        retorne nome + ": " + valor;
+    }
+}
+"#,
+    ),
+    (
+        "util/projeto.toml",
+        "[projeto]\nnome = \"util\"\nversao = \"0.2.0\"\ntipo = \"biblioteca\"\nfontes = [\"fontes\"]\n",
+    ),
+    (
+        "util/fontes/dobro.pr",
+        r#"espaco Util {
+    publico estática classe Matematica {
+        publico estática inteiro Dobro(inteiro x) {
+            retorne x * 2;
+        }
+    }
+}
+"#,
+    ),
+];
+
+#[test]
+fn construir_gera_os_alvos_do_manifesto_em_dist() {
+    let dir = projeto("aplicacao", APLICACAO).join("app");
+    assert_sucesso(&construir(&dir, &[]));
+
+    let dist = dir.join("dist");
+        assert!(dir.join("projeto.lock").is_file());
+    let execucao = Command::new(env!("CARGO_BIN_EXE_interpretador"))
+        .arg(dist.join("bytecode").join("calc.pbc"))
+        .output()
+        .expect("falha ao executar interpretador");
+    assert_eq!(String::from_utf8_lossy(&execucao.stdout), "dobro: 42\n");
+}
+
+#[test]
+fn construir_aceita_diretorio_e_alvo_na_linha_de_comando() {
+    let raiz = projeto(
+        "alvo",
+        &[
+            ("app/projeto.toml", "[projeto]\nnome = \"calc\"\nversao = \"1.0.0\"\n"),
+            ("app/src/principal.pr", "imprima(1);\n"),
+        ],
+    );
+    let output = construir(&raiz, &["app", "--alvo=cil-bytecode"]);
+    assert_sucesso(&output);
+    let dist = raiz.join("app").join("dist");
+    assert!(dist.join("cil-bytecode").join("calc.il").is_file());
+    assert!(!dist.join("bytecode").exists());
+
+    // Só o bytecode carrega as dependências (.pbl) na execução
+    let output = construir(&projeto("alvo_deps", APLICACAO).join("app"), &["--alvo=wasm"]);
+    assert_erro(&output, "dependências só podem ser usadas pelos alvos");
+}
+
+#[test]
+fn biblioteca_usa_nome_e_versao_do_manifesto() {
+    let dir = projeto(
+        "biblioteca",
+        &[
+            (
+                "projeto.toml",
+                "[projeto]\nnome = \"Geometria\"\nversao = \"2.3.4\"\ntipo = \"biblioteca\"\n",
+            ),
+            (
+                "src/formas.pr",
+                r#"espaco Geometria {
+    publico classe Quadrado {
+        publico inteiro Lado { obter; definir; }
+        publico Quadrado(inteiro lado) { Lado = lado; }
+        publico inteiro Area() { retorne Lado * Lado; }
+    }
+}
+"#,
+            ),
+        ],
+    );
+    assert_sucesso(&construir(&dir, &[]));
+    let pbl = fs::read(dir.join("dist").join("geometria.pbl")).expect("geometria.pbl não gerado");
+    assert!(String::from_utf8_lossy(&pbl).contains("2.3.4"));
+}
+
+#[test]
+fn erros_do_manifesto_sao_explicados() {
+    let sem_manifesto = projeto("sem_manifesto", &[("src/principal.pr", "imprima(1);\n")]);
+    assert_erro(&construir(&sem_manifesto, &[]), "projeto.toml' não encontrado");
+
+    let alvo_invalido = projeto(
+        "alvo_invalido",
+        &[(
+            "projeto.toml",
+            "[projeto]\nnome = \"x\"\nversao = \"1.0.0\"\nalvos = [\"jvm\"]\n",
+        )],
+    );
+    assert_erro(&construir(&alvo_invalido, &[]), "alvo desconhecido 'jvm'");
+
+    let sem_entrada = projeto(
+        "sem_entrada",
+        &[
+            ("projeto.toml", "[projeto]\nnome = \"x\"\nversao = \"1.0.0\"\n"),
+            ("src/outro.pr", "imprima(1);\n"),
+        ],
+    );
+    assert_erro(&construir(&sem_entrada, &[]), "principal.pr' não encontrado");
+}
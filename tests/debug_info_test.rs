@@ -0,0 +1,109 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn have_opt() -> bool {
+    Command::new("opt").arg("--version").output().is_ok()
+}
+
+/// Gera `conta.ll` em um diretório próprio (o backend LLVM grava no diretório atual).
+/// O status do compilador é ignorado: sem clang, o .ll é gerado mas o executável não.
+fn gerar_ll(subdir: &str, extras: &[&str]) -> String {
+    let root = repo_root();
+    let dir = root.join("build").join("debug_info").join(subdir);
+    fs::create_dir_all(&dir).unwrap();
+    let ll = dir.join("conta.ll");
+    let _ = fs::remove_file(&ll);
+
+    let fonte = root
+        .join("tests")
+        .join("fixtures")
+        .join("debug_info")
+        .join("conta.pr");
+    let output = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(&dir)
+        .arg(&fonte)
+        .arg("--target=llvm-ir")
+        .args(extras)
+        .output()
+        .expect("falha ao executar compilador");
+    fs::read_to_string(&ll).unwrap_or_else(|_| {
+        panic!(
+            "conta.ll não gerado: stderr=\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        )
+    })
+}
+
+#[test]
+fn debug_info_emite_metadados_dwarf() {
+    let ir = gerar_ll("com", &["--debug-info"]);
+
+    assert!(ir.contains("!llvm.dbg.cu = !{"));
+    assert!(ir.contains("!DIFile(filename: \"conta.pr\", directory: \""));
+    assert!(ir.contains("!DICompileUnit("));
+    assert!(ir.contains("declare void @llvm.dbg.declare(metadata, metadata, metadata)"));
+
+    // Funções, parâmetros e locais com linha do fonte
+    assert!(ir.contains("!DISubprogram(name: \"Somar\""));
+    assert!(ir.contains("!DISubprogram(name: \"Principal\""));
+    assert!(ir.contains("!DILocalVariable(name: \"a\", arg: 1"));
+    assert!(ir.contains("!DILocalVariable(name: \"total\", scope:"));
+    assert!(ir.contains("!DILocalVariable(name: \"y\", scope: !"));
+    assert!(ir.contains("line: 23, type:"));
+    assert!(ir.contains("!DILocation(line: 23, column:"));
+
+    // Layout da classe: vtable seguida das propriedades
+    assert!(ir.contains("DW_TAG_structure_type, name: \"Conta\""));
+    assert!(ir.contains("DW_TAG_member, name: \"_vtable\""));
+    assert!(ir.contains("DW_TAG_member, name: \"Titular\""));
+    assert!(ir.contains("DW_TAG_member, name: \"Saldo\""));
+    assert!(ir.contains("!DILocalVariable(name: \"este\", arg: 1"));
+
+    // Toda instrução dentro de uma função com depuração tem localização
+    let define_somar = ir
+        .lines()
+        .find(|l| l.starts_with("define") && l.contains("@\"Somar\"("))
+        .expect("definição de Somar não encontrada");
+    assert!(define_somar.contains("!dbg !"));
+    assert!(ir
+        .lines()
+        .any(|l| l.trim_start().starts_with("ret i32") && l.contains(", !dbg !")));
+}
+
+#[test]
+fn debug_info_ir_valido_no_verificador_llvm() {
+    if !have_opt() {
+        eprintln!("opt não encontrado; ignorando verificação do IR.");
+        return;
+    }
+    gerar_ll("verificar", &["--debug-info"]);
+    let ll = repo_root()
+        .join("build")
+        .join("debug_info")
+        .join("verificar")
+        .join("conta.ll");
+    let output = Command::new("opt")
+        .args(["-passes=verify", "-S", "-o"])
+        .arg(if cfg!(windows) { "NUL" } else { "/dev/null" })
+        .arg(&ll)
+        .output()
+        .expect("falha ao executar opt");
+    assert!(
+        output.status.success(),
+        "IR com depuração rejeitado pelo opt:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn sem_flag_nao_emite_metadados() {
+    let ir = gerar_ll("sem", &[]);
+    assert!(!ir.contains("!dbg"));
+    assert!(!ir.contains("llvm.dbg"));
+    assert!(!ir.contains("!DICompileUnit"));
+}
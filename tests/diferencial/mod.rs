@@ -0,0 +1,391 @@
+// Teste diferencial: o mesmo programa, com a mesma entrada, em vários backends executáveis.
+// Para cada `.pr` de `exemplos/` e de `tests/casos/`, compara stdout e status de saída de
+// `--executar` (interpretador de AST), do bytecode, do executável LLVM, do executável do alvo C
+// e do módulo WebAssembly (no wasmi). `examples_test` compara os backends sem LLVM e
+// `llvm_run_examples_test` o LLVM (quando há clang).
+// A referência é o arquivo esperado do caso (`tests/casos/<nome>.saida` ou `<stem>.out.txt`
+// na raiz); sem ele, a saída do interpretador de AST.
+use super::wasm_host;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+/// Divergências já conhecidas: (caso, backend, motivo). O backend não é comparado nesse caso
+/// (nem precisa compilar), e o teste falha se a divergência deixar de existir, para a lista não
+/// ficar desatualizada.
+const DIVERGENCIAS_CONHECIDAS: &[(&str, &str, &str)] = &[
+    (
+        "exemplos/teste_avancado.pr",
+        "bytecode",
+        "a VM guarda um único construtor por classe (o de mais parâmetros)",
+    ),
+    (
+        "exemplos/async_io_exemplo.pr",
+        "wasm",
+        "o alvo wasm não tem acesso a arquivos",
+    ),
+    (
+        "exemplos/async_llvm_test.pr",
+        "wasm",
+        "o alvo wasm não tem acesso a arquivos",
+    ),
+    (
+        "exemplos/async_llvm_test.pr",
+        "llvm",
+        "EscreverArquivoAssíncrono devolve verdadeiro no alvo LLVM e nulo no interpretador",
+    ),
+    (
+        "exemplos/interfaces_avancado.pr",
+        "llvm",
+        "o alvo LLVM não despacha por interface: a lista mistura classes sem ancestral comum e \
+         a chamada usa o método do primeiro elemento",
+    ),
+    (
+        "exemplos/teste_lista_generico.pr",
+        "llvm",
+        "Lista vem do sistema-padrão, que o alvo LLVM só encontra compilado",
+    ),
+    (
+        "exemplos/teste_llvm_stdlib.pr",
+        "llvm",
+        "Lista vem do sistema-padrão, que o alvo LLVM só encontra compilado",
+    ),
+    (
+        "tests/casos/texto_e_interpolacao.pr",
+        "llvm",
+        "o alvo LLVM não tem os métodos de texto (ParaMaiusculo, Aparar, Contem)",
+    ),
+];
+
+/// Entrada usada nos exemplos que leem do console (a mesma de `llvm_run_examples_test`).
+const ENTRADA_EXEMPLOS: &str = "adriano\n30\n";
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// O alvo C usa `CC` ou o primeiro entre cc, gcc e clang.
+#[allow(dead_code)]
+pub fn have_c_compiler() -> bool {
+    std::env::var_os("CC").is_some()
+        || ["cc", "gcc", "clang"]
+            .iter()
+            .any(|c| Command::new(c).arg("--version").output().is_ok())
+}
+
+// Cada arquivo de teste compara só alguns dos backends
+#[allow(dead_code)]
+#[derive(Clone, Copy, PartialEq)]
+pub enum Backend {
+    Ast,
+    Bytecode,
+    Llvm,
+    C,
+    Wasm,
+}
+
+impl Backend {
+    fn nome(self) -> &'static str {
+        match self {
+            Backend::Ast => "ast",
+            Backend::Bytecode => "bytecode",
+            Backend::Llvm => "llvm",
+            Backend::C => "c",
+            Backend::Wasm => "wasm",
+        }
+    }
+}
+
+pub struct Caso {
+    /// Caminho relativo à raiz, usado no relatório.
+    nome: String,
+    /// Arquivos-fonte na ordem passada ao compilador (o último dá nome à saída).
+    fontes: Vec<PathBuf>,
+    entrada: String,
+    esperado: Option<String>,
+    /// Casos de `tests/casos/` precisam compilar; exemplos rejeitados pelo compilador são ignorados.
+    deve_compilar: bool,
+}
+
+#[derive(Clone, PartialEq)]
+struct Execucao {
+    stdout: String,
+    sucesso: bool,
+}
+
+fn normalizar(s: &str) -> String {
+    let s = s
+        .strip_prefix('\u{feff}')
+        .unwrap_or(s)
+        .replace("\r\n", "\n");
+    s.trim_end().to_string()
+}
+
+pub fn casos_exemplos() -> Vec<Caso> {
+    let root = repo_root();
+    let mut caminhos: Vec<PathBuf> = fs::read_dir(root.join("exemplos"))
+        .expect("exemplos/ não encontrado")
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|s| s.to_str()) == Some("pr"))
+        .filter(|p| !p.to_string_lossy().ends_with("_neg.pr"))
+        .collect();
+    caminhos.sort();
+    caminhos
+        .into_iter()
+        .map(|caminho| {
+            let stem = caminho.file_stem().unwrap().to_string_lossy().to_string();
+            // programa_principal depende de biblioteca.pr
+            let fontes = if stem == "programa_principal" {
+                vec![root.join("exemplos").join("biblioteca.pr"), caminho.clone()]
+            } else {
+                vec![caminho.clone()]
+            };
+            Caso {
+                nome: format!("exemplos/{}.pr", stem),
+                fontes,
+                entrada: ENTRADA_EXEMPLOS.to_string(),
+                esperado: fs::read_to_string(root.join(format!("{}.out.txt", stem))).ok(),
+                deve_compilar: false,
+            }
+        })
+        .collect()
+}
+
+pub fn casos_corpus() -> Vec<Caso> {
+    let dir = repo_root().join("tests").join("casos");
+    let mut caminhos: Vec<PathBuf> = fs::read_dir(&dir)
+        .expect("tests/casos/ não encontrado")
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().and_then(|s| s.to_str()) == Some("pr"))
+        .collect();
+    caminhos.sort();
+    caminhos
+        .into_iter()
+        .map(|caminho| {
+            let stem = caminho.file_stem().unwrap().to_string_lossy().to_string();
+            let esperado = fs::read_to_string(caminho.with_extension("saida"))
+                .unwrap_or_else(|_| panic!("tests/casos/{}.saida não encontrado", stem));
+            Caso {
+                nome: format!("tests/casos/{}.pr", stem),
+                entrada: fs::read_to_string(caminho.with_extension("entrada")).unwrap_or_default(),
+                fontes: vec![caminho],
+                esperado: Some(esperado),
+                deve_compilar: true,
+            }
+        })
+        .collect()
+}
+
+fn com_entrada(mut comando: Command, entrada: &str) -> Output {
+    let mut filho = comando
+        .current_dir(repo_root())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("falha ao iniciar processo");
+    // O programa pode terminar sem ler toda a entrada
+    let _ = filho.stdin.take().unwrap().write_all(entrada.as_bytes());
+    filho
+        .wait_with_output()
+        .expect("falha ao aguardar processo")
+}
+
+fn execucao(output: &Output) -> Execucao {
+    Execucao {
+        stdout: normalizar(&String::from_utf8_lossy(&output.stdout)),
+        sucesso: output.status.success(),
+    }
+}
+
+/// Compila (quando o backend gera artefato) e executa o caso. `Err` indica falha de compilação.
+fn executar(backend: Backend, caso: &Caso) -> Result<Execucao, String> {
+    let root = repo_root();
+    let dir = root.join("build").join("diferencial").join(backend.nome());
+    fs::create_dir_all(&dir).unwrap();
+    let stem = caso.fontes.last().unwrap().file_stem().unwrap();
+
+    let compilar = |args: &[String], cwd: &Path| -> Result<(), String> {
+        let saida = Command::new(env!("CARGO_BIN_EXE_compilador"))
+            .current_dir(cwd)
+            .args(&caso.fontes)
+            .args(args)
+            .output()
+            .expect("falha ao executar compilador");
+        if saida.status.success() {
+            Ok(())
+        } else {
+            Err(String::from_utf8_lossy(&saida.stderr).into_owned())
+        }
+    };
+
+    match backend {
+        Backend::Ast => {
+            let mut comando = Command::new(env!("CARGO_BIN_EXE_compilador"));
+            comando.args(&caso.fontes).arg("--executar");
+            Ok(execucao(&com_entrada(comando, &caso.entrada)))
+        }
+        Backend::Bytecode => {
+            compilar(
+                &[
+                    "--target=bytecode".into(),
+                    format!("--output-dir={}", dir.display()),
+                ],
+                &root,
+            )?;
+            let mut comando = Command::new(env!("CARGO_BIN_EXE_interpretador"));
+            comando.arg(dir.join(stem).with_extension("pbc"));
+            Ok(execucao(&com_entrada(comando, &caso.entrada)))
+        }
+        Backend::Llvm | Backend::C => {
+            // Os backends nativos gravam o .ll/.c e o executável no diretório atual
+            let alvo = if backend == Backend::C { "c" } else { "llvm-ir" };
+            compilar(&[format!("--target={}", alvo)], &dir)?;
+            let exe = if cfg!(windows) {
+                dir.join(stem).with_extension("exe")
+            } else {
+                dir.join(stem)
+            };
+            Ok(execucao(&com_entrada(Command::new(exe), &caso.entrada)))
+        }
+        Backend::Wasm => {
+            compilar(&["--target=wasm".into()], &dir)?;
+            let wasm =
+                fs::read(dir.join(stem).with_extension("wasm")).map_err(|e| e.to_string())?;
+            let resultado = wasm_host::executar(&wasm, &caso.entrada)?;
+            Ok(Execucao {
+                stdout: normalizar(&resultado.stdout),
+                sucesso: resultado.codigo == 0,
+            })
+        }
+    }
+}
+
+/// Descreve a primeira linha diferente entre a referência e a saída obtida.
+fn descrever_diferenca(referencia: &Execucao, obtida: &Execucao) -> String {
+    let mut texto = String::new();
+    if referencia.sucesso != obtida.sucesso {
+        let status = |ok: bool| if ok { "sucesso" } else { "erro" };
+        let _ = writeln!(
+            texto,
+            "    status: esperado {}, obtido {}",
+            status(referencia.sucesso),
+            status(obtida.sucesso)
+        );
+    }
+    let esperadas: Vec<&str> = referencia.stdout.lines().collect();
+    let obtidas: Vec<&str> = obtida.stdout.lines().collect();
+    let total = esperadas.len().max(obtidas.len());
+    if let Some(i) = (0..total).find(|&i| esperadas.get(i) != obtidas.get(i)) {
+        let linha = |v: &[&str]| {
+            v.get(i)
+                .map(|l| format!("{:?}", l))
+                .unwrap_or_else(|| "<fim da saída>".to_string())
+        };
+        let _ = writeln!(
+            texto,
+            "    linha {}: esperado {}, obtido {}",
+            i + 1,
+            linha(&esperadas),
+            linha(&obtidas)
+        );
+    }
+    texto
+}
+
+/// Compara cada um dos `backends` com a referência de cada caso e falha com o relatório das
+/// divergências.
+pub fn verificar(casos: Vec<Caso>, backends: &[Backend]) {
+    let mut relatorio = String::new();
+    for caso in &casos {
+        // O bytecode compila primeiro: separa erros de compilação de erros de execução
+        let bytecode = match executar(Backend::Bytecode, caso) {
+            Ok(execucao) => execucao,
+            Err(erro) if caso.deve_compilar => {
+                let _ = writeln!(relatorio, "{}: não compila\n{}", caso.nome, erro);
+                continue;
+            }
+            Err(_) => continue,
+        };
+        let resultados: Vec<(Backend, Result<Execucao, String>)> = backends
+            .iter()
+            .map(|&b| {
+                let r = if b == Backend::Bytecode {
+                    Ok(bytecode.clone())
+                } else {
+                    executar(b, caso)
+                };
+                (b, r)
+            })
+            .collect();
+
+        let (referencia, nome_referencia) = match &caso.esperado {
+            Some(esperado) => (
+                Execucao {
+                    stdout: normalizar(esperado),
+                    sucesso: true,
+                },
+                "saída esperada".to_string(),
+            ),
+            None => match resultados
+                .iter()
+                .find(|(b, _)| *b == Backend::Ast)
+                .map(|(_, r)| r.clone())
+                .unwrap_or_else(|| executar(Backend::Ast, caso))
+            {
+                Ok(ast) => (ast, "interpretador de AST".to_string()),
+                Err(erro) => {
+                    let _ = writeln!(relatorio, "{}: ast: {}", caso.nome, erro);
+                    continue;
+                }
+            },
+        };
+
+        for (backend, resultado) in &resultados {
+            let conhecida = DIVERGENCIAS_CONHECIDAS
+                .iter()
+                .find(|(c, b, _)| *c == caso.nome && *b == backend.nome());
+            match (resultado, conhecida) {
+                (Err(_), Some(_)) => {}
+                (Err(erro), None) => {
+                    let _ = writeln!(
+                        relatorio,
+                        "{}: {} não compila\n{}",
+                        caso.nome,
+                        backend.nome(),
+                        erro
+                    );
+                }
+                (Ok(obtida), None) if *obtida != referencia => {
+                    let _ = write!(
+                        relatorio,
+                        "{}: {} diverge de {}\n{}",
+                        caso.nome,
+                        backend.nome(),
+                        nome_referencia,
+                        descrever_diferenca(&referencia, obtida)
+                    );
+                }
+                (Ok(obtida), Some((_, _, motivo))) if *obtida == referencia => {
+                    let _ = writeln!(
+                        relatorio,
+                        "{}: {} não diverge mais ({}); remova de DIVERGENCIAS_CONHECIDAS",
+                        caso.nome,
+                        backend.nome(),
+                        motivo
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+    assert!(
+        relatorio.is_empty(),
+        "divergências entre backends:\n{}",
+        relatorio
+    );
+}
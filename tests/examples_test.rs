@@ -1,3 +1,7 @@
+mod diferencial;
+mod wasm_host;
+
+use diferencial::Backend;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Command;
@@ -336,3 +340,26 @@ publico função vazio Principal() {
         "saida incorreta do programa minimo"
     );
 }
+
+/// Interpretador de AST, bytecode, alvo C (quando há um compilador C) e wasm: o LLVM é comparado
+/// em `llvm_run_examples_test`.
+fn backends_diferencial() -> Vec<Backend> {
+    let mut backends = vec![Backend::Ast, Backend::Bytecode];
+    if diferencial::have_c_compiler() {
+        backends.push(Backend::C);
+    } else {
+        eprintln!("compilador C não encontrado; alvo C fora da comparação.");
+    }
+    backends.push(Backend::Wasm);
+    backends
+}
+
+#[test]
+fn backends_concordam_nos_exemplos() {
+    diferencial::verificar(diferencial::casos_exemplos(), &backends_diferencial());
+}
+
+#[test]
+fn backends_concordam_no_corpus_de_casos() {
+    diferencial::verificar(diferencial::casos_corpus(), &backends_diferencial());
+}
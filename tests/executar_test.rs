@@ -0,0 +1,178 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn fixture(nome: &str) -> PathBuf {
+    repo_root()
+        .join("tests")
+        .join("fixtures")
+        .join("executar")
+        .join(format!("{}.pr", nome))
+}
+
+fn com_entrada(mut comando: Command, entrada: &str) -> Output {
+    let mut filho = comando
+        .current_dir(repo_root())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("falha ao iniciar processo");
+    filho
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(entrada.as_bytes())
+        .unwrap();
+    filho
+        .wait_with_output()
+        .expect("falha ao aguardar processo")
+}
+
+fn executar(fonte: &Path, entrada: &str) -> Output {
+    let mut comando = Command::new(env!("CARGO_BIN_EXE_compilador"));
+    comando.arg(fonte).arg("--executar");
+    com_entrada(comando, entrada)
+}
+
+// Compila para bytecode em um diretório próprio e roda no interpretador
+fn interpretar(fonte: &Path, entrada: &str) -> Output {
+    let saida = repo_root().join("build").join("executar_test");
+    let compilado = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(repo_root())
+        .arg(fonte)
+        .arg("--target=bytecode")
+        .arg(format!("--output-dir={}", saida.display()))
+        .output()
+        .expect("falha ao executar compilador");
+    assert!(
+        compilado.status.success(),
+        "compilador falhou para {}: {}",
+        fonte.display(),
+        String::from_utf8_lossy(&compilado.stderr)
+    );
+    let stem = fonte.file_stem().unwrap().to_str().unwrap();
+    let mut comando = Command::new(env!("CARGO_BIN_EXE_interpretador"));
+    comando.arg(saida.join(format!("{}.pbc", stem)));
+    com_entrada(comando, entrada)
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).replace("\r\n", "\n")
+}
+
+#[test]
+fn executa_classes_heranca_propriedades_e_interfaces() {
+    let output = executar(&fixture("classes"), "");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let esperado = std::fs::read_to_string(
+        repo_root()
+            .join("tests")
+            .join("fixtures")
+            .join("executar")
+            .join("classes.saida"),
+    )
+    .unwrap();
+    assert_eq!(stdout(&output), esperado.replace("\r\n", "\n"));
+}
+
+#[test]
+fn nao_gera_arquivos_de_saida() {
+    let pbc = repo_root().join("build").join("classes.pbc");
+    let _ = std::fs::remove_file(&pbc);
+    let output = executar(&fixture("classes"), "");
+    assert!(output.status.success());
+    assert!(!pbc.exists());
+}
+
+#[test]
+fn mesma_saida_que_o_interpretador_de_bytecode() {
+    let exemplos = [
+        "aritmetica",
+        "condicionais",
+        "loops",
+        "heranca",
+        "heranca_simples",
+        "interfaces_basico",
+        "interfaces_avancado",
+        "teste_abstrata",
+        "teste_enum",
+        "virtual_override",
+        "exemplo_nova",
+    ];
+    let mut fontes: Vec<PathBuf> = exemplos
+        .iter()
+        .map(|n| repo_root().join("exemplos").join(format!("{}.pr", n)))
+        .collect();
+    fontes.push(fixture("nativos"));
+    fontes.push(fixture("erro_divisao"));
+
+    for fonte in &fontes {
+        let referencia = executar(fonte, "Ana\n");
+        let bytecode = interpretar(fonte, "Ana\n");
+        assert_eq!(
+            stdout(&referencia),
+            stdout(&bytecode),
+            "stdout diferente em {}",
+            fonte.display()
+        );
+        assert_eq!(
+            referencia.status.success(),
+            bytecode.status.success(),
+            "status diferente em {}",
+            fonte.display()
+        );
+    }
+}
+
+#[test]
+fn erro_em_tempo_de_execucao_informa_a_posicao() {
+    let output = executar(&fixture("erro_divisao"), "");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "20\n25\n33\n50\n100\n");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Erro em tempo de execução: Divisão por zero")
+            && stderr.contains("erro_divisao.pr:2:5"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn erros_semanticos_impedem_a_execucao() {
+    let dir = repo_root().join("build").join("executar_test");
+    std::fs::create_dir_all(&dir).unwrap();
+    let fonte = dir.join("invalido.pr");
+    std::fs::write(
+        &fonte,
+        "função vazio Principal() {\n    inteiro x = \"texto\";\n    imprima(x);\n}\n",
+    )
+    .unwrap();
+    let output = executar(&fonte, "");
+    assert!(!output.status.success());
+    assert_eq!(stdout(&output), "");
+}
+
+#[test]
+fn recursao_sem_fim_e_erro_de_execucao() {
+    // Limitada pelas chamadas aninhadas ou pela pilha da thread, o que vier antes
+    let output = executar(&fixture("recursao_infinita"), "");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "antes\n");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Erro em tempo de execução: Estouro de pilha")
+            && stderr.contains("recursao_infinita.pr:2:5"),
+        "{}",
+        stderr
+    );
+}
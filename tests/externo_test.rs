@@ -0,0 +1,147 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn have_clang() -> bool {
+    Command::new("clang").arg("--version").output().is_ok()
+}
+
+fn fixture(nome: &str) -> PathBuf {
+    repo_root()
+        .join("tests")
+        .join("fixtures")
+        .join("externo")
+        .join(format!("{}.pr", nome))
+}
+
+/// Compila `fonte` em `build/externo/<subdir>` (o backend LLVM grava no diretório atual).
+fn compilar(fonte: &Path, subdir: &str, extras: &[&str]) -> (PathBuf, Output) {
+    let dir = repo_root().join("build").join("externo").join(subdir);
+    fs::create_dir_all(&dir).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(&dir)
+        .arg(fonte)
+        .args(extras)
+        .output()
+        .expect("falha ao executar compilador");
+    (dir, output)
+}
+
+/// Grava um programa em `build/externo/<subdir>` e tenta compilá-lo para LLVM.
+fn compilar_codigo(codigo: &str, subdir: &str) -> Output {
+    let dir = repo_root().join("build").join("externo").join(subdir);
+    fs::create_dir_all(&dir).unwrap();
+    let fonte = dir.join("programa.pr");
+    fs::write(&fonte, codigo).unwrap();
+    compilar(&fonte, subdir, &["--target=llvm-ir"]).1
+}
+
+#[test]
+fn ir_declara_simbolos_c_e_converte_argumentos() {
+    let (dir, output) = compilar(&fixture("matematica"), "ir", &["--target=llvm-ir"]);
+    let ir = fs::read_to_string(dir.join("matematica.ll")).expect("matematica.ll não gerado");
+
+    assert!(
+        ir.contains("declare double @\"cos\"(double)"),
+        "IR:\n{}",
+        ir
+    );
+    assert!(ir.contains("declare double @\"pow\"(double, double)"));
+    assert!(ir.contains("declare i32 @\"abs\"(i32)"));
+    assert!(ir.contains("declare i32 @\"isdigit\"(i32)"));
+    assert!(ir.contains("declare i32 @\"atoi\"(i8*)"));
+    // As funções declaradas no programa não têm corpo próprio
+    assert!(!ir.contains("@\"Cosseno\""));
+    // `inteiro` passado a `duplo` é convertido; `booleano` vai e volta como int
+    assert!(ir.contains("sitofp i32 2 to double"));
+    assert!(ir.contains("= call i32 @\"isdigit\"(i32 55)"));
+    assert!(ir.contains("= icmp ne i32 "));
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if cfg!(windows) {
+        assert!(!stdout.contains(" -lm"), "{}", stdout);
+    } else {
+        assert!(stdout.contains(" -lm"), "{}", stdout);
+    }
+    assert!(!stdout.contains(" -lc"), "{}", stdout);
+}
+
+#[test]
+fn verificador_rejeita_declaracoes_externas_invalidas() {
+    let casos = [
+        (
+            "[Externo(\"libm\")]\nexterno função duplo Cosseno(duplo x);\n",
+            "use [Externo(\"biblioteca\", \"símbolo\")]",
+        ),
+        (
+            "[Externo(\"libc\", \"soma\")]\nexterno função inteiro Soma(inteiro[] xs);\n",
+            "Parâmetro 'xs' da função externa 'Soma'",
+        ),
+        (
+            "externo função duplo Cosseno(duplo x);\n",
+            "[Externo(\"biblioteca\", \"símbolo\")]",
+        ),
+    ];
+    for (i, (declaracao, mensagem)) in casos.iter().enumerate() {
+        let codigo = format!(
+            "{}\nfunção vazio Principal() {{\n    imprima(1);\n}}\n",
+            declaracao
+        );
+        let output = compilar_codigo(&codigo, &format!("invalido{}", i));
+        assert!(!output.status.success(), "caso {} compilou", i);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains(mensagem), "caso {}: stderr:\n{}", i, stderr);
+    }
+}
+
+#[test]
+fn outros_alvos_recusam_funcoes_externas() {
+    let (_, output) = compilar(&fixture("matematica"), "executar", &["--executar"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Função externa 'Cosseno' só pode ser chamada no executável LLVM"),
+        "stderr:\n{}",
+        stderr
+    );
+
+    let (_, output) = compilar(&fixture("matematica"), "bytecode", &["--target=bytecode"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("não é suportada no alvo bytecode"),
+        "stderr:\n{}",
+        stderr
+    );
+}
+
+#[test]
+fn executavel_chama_libm_e_libc() {
+    if !have_clang() {
+        eprintln!("clang não encontrado; ignorando teste LLVM.");
+        return;
+    }
+    let (dir, output) = compilar(&fixture("matematica"), "executavel", &["--target=llvm-ir"]);
+    assert!(
+        output.status.success(),
+        "compilador falhou:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let exe = if cfg!(windows) {
+        dir.join("matematica.exe")
+    } else {
+        dir.join("matematica")
+    };
+    let execucao = Command::new(&exe)
+        .output()
+        .expect("falha ao executar programa");
+    assert!(execucao.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&execucao.stdout).replace("\r\n", "\n"),
+        "1.000000\n1024.000000\n42\nverdadeiro\nfalso\n124\n"
+    );
+}
@@ -0,0 +1,128 @@
+// Gerado pelo compilador Por do Sol (alvo console).
+using System;
+using System.Collections.Generic;
+using System.IO;
+using System.Threading.Tasks;
+using PorDoSol;
+
+public static partial class Programa
+{
+    public static async Task<long> Dobrar(long valor)
+    {
+        return valor * 2;
+    }
+
+    public static async Task<long> Somar(long a, long b)
+    {
+        long x = await Dobrar(a);
+        return x + b;
+    }
+
+    public static long Contar(global::Loja.Modelos.IRepositorio<global::Loja.Modelos.Produto> repo)
+    {
+        return repo.Quantidade();
+    }
+
+    public static void Principal()
+    {
+        var numero = new global::Loja.Modelos.Caixa<long>(41);
+        Console.WriteLine(Runtime.Texto(numero.Obter() + 1));
+        var nome = new global::Loja.Modelos.Caixa<string>("caixa");
+        Console.WriteLine(nome.Obter().ToUpperInvariant());
+        global::Loja.Modelos.Repositorio repo = new global::Loja.Modelos.Repositorio();
+        repo.Adicionar(new global::Loja.Modelos.Produto("Café", 12.5m));
+        repo.Adicionar(new global::Loja.Modelos.Produto("Pão", 0.75m));
+        Console.WriteLine($"{Contar(repo)} produtos, primeiro: {Runtime.Texto(repo.Primeiro())}");
+        Console.WriteLine(Runtime.Texto(repo.Primeiro().Preco * 2));
+        var palavras = new List<string>();
+        palavras.Add("sol");
+        palavras.Add("mar");
+        Console.WriteLine(Runtime.Texto(palavras.Count));
+        Console.WriteLine(palavras[1] + "!");
+        Console.WriteLine(Runtime.Texto(Somar(10, 1).GetAwaiter().GetResult()));
+        float f = 1.5f;
+        double d = 2.25;
+        Console.WriteLine(Runtime.Texto(f * 2));
+        Console.WriteLine(Runtime.Texto(d / 3));
+        Console.WriteLine(Runtime.Texto(10L / 4));
+        Console.WriteLine(Runtime.Texto(Runtime.Iguais(1L, 1.0)));
+        object o = null;
+        Console.WriteLine(Runtime.Texto(o));
+        Console.WriteLine(Runtime.Texto(o == null));
+        Console.WriteLine($"duplo: {Runtime.Texto(d)}, soma: {Runtime.Texto(d + 1)}");
+        _ = 42;
+        return;
+    }
+}
+
+namespace Loja.Modelos
+{
+    public interface IRepositorio<T>
+    {
+        void Adicionar(T item);
+        long Quantidade();
+    }
+
+    public class Caixa<T>
+    {
+        public T Valor;
+
+        public Caixa(T valor)
+        {
+            Valor = valor;
+        }
+
+        public T Obter()
+        {
+            return Valor;
+        }
+    }
+
+    public class Produto
+    {
+        public string Nome;
+        public decimal Preco;
+
+        public Produto(string nome, decimal preco)
+        {
+            Nome = nome;
+            Preco = preco;
+        }
+
+        public override string ToString() => Runtime.Texto(Nome);
+    }
+
+    public class Repositorio : IRepositorio<Produto>
+    {
+        private List<Produto> itens = new List<Produto>();
+
+        public void Adicionar(Produto item)
+        {
+            itens.Add(item);
+        }
+
+        public long Quantidade()
+        {
+            return itens.Count;
+        }
+
+        public Produto Primeiro()
+        {
+            return itens[0];
+        }
+    }
+}
+
+namespace PorDoSol
+{
+    internal static class Inicio
+    {
+        private static int Main()
+        {
+            return Runtime.Executar(() =>
+            {
+                global::Programa.Principal();
+            });
+        }
+    }
+}
@@ -0,0 +1,204 @@
+// Gerado pelo compilador Por do Sol (alvo console).
+using System;
+using System.Collections.Generic;
+using System.IO;
+using System.Threading.Tasks;
+using PorDoSol;
+
+public enum Cor
+{
+    Vermelho,
+    Verde,
+    Azul,
+}
+
+public interface IForma
+{
+    double Area();
+    string Nome();
+}
+
+public class Contador
+{
+    public static long Total = 0;
+
+    public static long Proximo()
+    {
+        Total = Total + 1;
+        return Total;
+    }
+}
+
+public class Pessoa
+{
+    public long Idade;
+    public string Nome { get; set; }
+
+    public Pessoa(string nome, long idade)
+    {
+        Nome = nome;
+        Idade = idade;
+    }
+
+    public override string ToString() => Runtime.Texto(Nome);
+}
+
+public class Conta
+{
+    private double saldo = 10.0;
+
+    public double Saldo
+    {
+        get
+        {
+            return saldo;
+        }
+        set
+        {
+            if (value >= 0.0)
+            {
+                saldo = value;
+            }
+        }
+    }
+}
+
+public abstract class FormaBase : IForma
+{
+    public string Rotulo = "forma";
+
+    public FormaBase(string rotulo)
+    {
+        Rotulo = rotulo;
+    }
+
+    public abstract double Area();
+
+    public virtual string Nome()
+    {
+        return Rotulo;
+    }
+}
+
+public class Quadrado : FormaBase
+{
+    public double Lado;
+
+    public Quadrado(double lado) : base("quadrado")
+    {
+        Lado = lado;
+    }
+
+    public override double Area()
+    {
+        return Lado * Lado;
+    }
+}
+
+public class Circulo : FormaBase
+{
+    public double Raio;
+
+    public Circulo(double r) : base("círculo")
+    {
+        Raio = r;
+    }
+
+    public override double Area()
+    {
+        return 3.0 * Raio * Raio;
+    }
+
+    public override string Nome()
+    {
+        return "redondo";
+    }
+}
+
+public static partial class Programa
+{
+    public static long Fatorial(long n)
+    {
+        if (n <= 1)
+        {
+            return 1;
+        }
+        return n * Fatorial(n - 1);
+    }
+
+    public static void Principal()
+    {
+        Console.WriteLine(Runtime.Texto(Fatorial(10)));
+        Console.WriteLine(Runtime.Texto(Contador.Proximo() + Contador.Proximo()));
+        var formas = new FormaBase[] { new Quadrado(2.0), new Circulo(1.5) };
+        for (long i = 0; i < formas.Length;)
+        {
+            Console.WriteLine(formas[i].Nome() + ": " + Runtime.Texto(formas[i].Area()));
+            i = i + 1;
+        }
+        IForma forma = formas[1];
+        Console.WriteLine(forma.Nome());
+        Pessoa p = new Pessoa("Ana", 30);
+        Console.WriteLine(Runtime.Texto(p));
+        Console.WriteLine($"{p.Nome} tem {p.Idade} anos");
+        Conta c = new Conta();
+        c.Saldo = -5.0;
+        Console.WriteLine(Runtime.Texto(c.Saldo));
+        c.Saldo = 2.5;
+        Console.WriteLine(Runtime.Texto(c.Saldo));
+        long a = 17;
+        long b = 5;
+        Console.WriteLine(Runtime.Texto(a + b));
+        Console.WriteLine(Runtime.Texto(a - b));
+        Console.WriteLine(Runtime.Texto(a * b));
+        Console.WriteLine(Runtime.Texto(a / b));
+        Console.WriteLine(Runtime.Texto(Runtime.Resto(a, b)));
+        Console.WriteLine(Runtime.Texto(Runtime.Resto(-7L, 3)));
+        Console.WriteLine(Runtime.Texto(7 / 2.0));
+        Console.WriteLine(Runtime.Texto(a == b));
+        Console.WriteLine(Runtime.Texto(a != b));
+        Console.WriteLine(Runtime.Texto(a < b));
+        Console.WriteLine(Runtime.Texto(a > b));
+        Console.WriteLine(Runtime.Texto(a <= b));
+        Console.WriteLine(Runtime.Texto(a >= b));
+        Console.WriteLine(Runtime.Texto(a > b && !(a == 0) || false));
+        long n = 0;
+        while (n < 3)
+        {
+            if (Runtime.Resto(n, 2) == 0)
+            {
+                Console.WriteLine("par");
+            }
+            else
+            {
+                Console.WriteLine("ímpar");
+            }
+            n = n + 1;
+        }
+        var nomes = Runtime.NovoArrayTexto(2);
+        nomes[1] = "fim";
+        Console.WriteLine(Runtime.Texto(nomes));
+        Console.WriteLine(Runtime.Texto(new double[] { 1.5, 2.25 }));
+        Console.WriteLine(Runtime.Texto(Cor.Verde));
+        string t = "Olá Mundo";
+        Console.WriteLine(t.ToUpperInvariant() + " " + Runtime.Comprimento(t));
+        Console.WriteLine(t.Replace("Mundo", "Sol"));
+        Console.WriteLine("Nome:");
+        string nome = Runtime.LerLinha();
+        Console.WriteLine("Olá, " + nome);
+    }
+}
+
+namespace PorDoSol
+{
+    internal static class Inicio
+    {
+        private static int Main()
+        {
+            return Runtime.Executar(() =>
+            {
+                global::Programa.Principal();
+            });
+        }
+    }
+}
@@ -0,0 +1,128 @@
+/*
+ * Teste do coletor de src/runtime/gc_runtime.c, compilado junto com ele por tests/memoria_test.rs.
+ * Cada bloco de teste tem um número; o finalizador marca em `liberados` os blocos que a coleta
+ * liberou. Termina com 0 e imprime "ok" se todas as verificações passarem.
+ */
+#include "gc_runtime.h"
+
+#include <stdio.h>
+#include <string.h>
+
+#if defined(__GNUC__) || defined(__clang__)
+#define NOINLINE __attribute__((noinline))
+#else
+#define NOINLINE
+#endif
+
+#define TOTAL 64
+/* A marcação é conservadora: uma cópia de ponteiro que sobrou num registrador pode manter um bloco
+ * inalcançável; aceita essa folga nos blocos de lixo. */
+#define FOLGA 2
+
+typedef struct No {
+    struct No *proximo;
+    int id;
+} No;
+
+static int liberados[TOTAL];
+static int falhas = 0;
+
+/* Raízes registradas: o único caminho até os blocos vivos. */
+static No *cadeia = NULL;
+static char *interno = NULL;
+static void **atomico = NULL;
+
+static void finalizar(void *area) {
+    liberados[((No *)area)->id] = 1;
+}
+
+static NOINLINE No *novo(int id) {
+    No *no = (No *)pds_gc_alocar_com_finalizador(sizeof(No), finalizar);
+    no->id = id;
+    return no;
+}
+
+/* Sobrescreve a pilha abaixo do chamador para não deixar cópias de ponteiros antigos. */
+static NOINLINE void limpar_pilha(void) {
+    volatile char lixo[16 * 1024];
+    memset((char *)lixo, 0, sizeof(lixo));
+}
+
+static void verificar(int condicao, const char *descricao) {
+    if (!condicao) {
+        printf("falhou: %s\n", descricao);
+        ++falhas;
+    }
+}
+
+static NOINLINE void montar(void) {
+    int i;
+    /* 0 -> 1 -> 2, alcançáveis só pela raiz `cadeia` */
+    cadeia = novo(0);
+    cadeia->proximo = novo(1);
+    cadeia->proximo->proximo = novo(2);
+    /* 3: só um ponteiro para o meio do bloco */
+    interno = (char *)novo(3) + sizeof(void *);
+    /* 4: só referenciado de um bloco atômico, que não é percorrido */
+    atomico = (void **)pds_gc_alocar_atomico(sizeof(void *));
+    *atomico = novo(4);
+    /* 10..TOTAL-1: inalcançáveis */
+    for (i = 10; i < TOTAL; ++i) {
+        novo(i);
+    }
+}
+
+static NOINLINE void montar_lixo(int inicio, int fim) {
+    int i;
+    for (i = inicio; i < fim; ++i) {
+        novo(i);
+    }
+}
+
+static int contar_liberados(int inicio, int fim) {
+    int i;
+    int total = 0;
+    for (i = inicio; i < fim; ++i) {
+        total += liberados[i];
+    }
+    return total;
+}
+
+int main(void) {
+    pds_gc_adicionar_raiz(&cadeia, sizeof(cadeia));
+    pds_gc_adicionar_raiz(&interno, sizeof(interno));
+    pds_gc_adicionar_raiz(&atomico, sizeof(atomico));
+
+    montar();
+    limpar_pilha();
+    pds_gc_coletar();
+
+    verificar(!liberados[0] && !liberados[1] && !liberados[2], "cadeia a partir de uma raiz liberada");
+    verificar(cadeia->proximo->proximo->id == 2, "conteúdo da cadeia alterado");
+    verificar(!liberados[3], "bloco com ponteiro interno liberado");
+    verificar(liberados[4], "bloco referenciado só de um bloco atômico mantido");
+    verificar(contar_liberados(10, TOTAL) >= TOTAL - 10 - FOLGA, "blocos inalcançáveis mantidos");
+
+    /* Sem a raiz, a cadeia inteira é liberada */
+    cadeia = NULL;
+    limpar_pilha();
+    pds_gc_coletar();
+    verificar(liberados[0] && liberados[1] && liberados[2], "cadeia sem raiz mantida");
+    verificar(!liberados[3], "bloco com ponteiro interno liberado na segunda coleta");
+
+    /* Com uma tarefa em execução, a coleta é adiada */
+    memset(liberados, 0, sizeof(liberados));
+    pds_gc_tarefa_iniciada();
+    montar_lixo(10, TOTAL);
+    limpar_pilha();
+    pds_gc_coletar();
+    verificar(contar_liberados(10, TOTAL) == 0, "coleta com tarefa em execução");
+    pds_gc_tarefa_concluida();
+    pds_gc_coletar();
+    verificar(contar_liberados(10, TOTAL) >= TOTAL - 10 - FOLGA, "coleta depois da tarefa");
+
+    if (falhas == 0) {
+        printf("ok\n");
+    }
+    return falhas == 0 ? 0 : 1;
+}
@@ -0,0 +1,83 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use compilador_portugues::formatador::formatar;
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+#[test]
+fn formatar_exemplos_e_idempotente() {
+    let mut arquivos: Vec<PathBuf> = fs::read_dir(repo_root().join("exemplos"))
+        .expect("diretório exemplos/ não encontrado")
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == "pr"))
+        .collect();
+    arquivos.sort();
+    assert!(!arquivos.is_empty());
+
+    for arquivo in arquivos {
+        let fonte = fs::read_to_string(&arquivo).unwrap();
+        let uma_vez = formatar(&fonte)
+            .unwrap_or_else(|e| panic!("falha ao formatar {}: {}", arquivo.display(), e));
+        let duas_vezes = formatar(&uma_vez).unwrap();
+        assert_eq!(
+            uma_vez,
+            duas_vezes,
+            "formatação não é idempotente em {}",
+            arquivo.display()
+        );
+        // Comentários são preservados
+        assert_eq!(
+            fonte.matches("//").count(),
+            uma_vez.matches("//").count(),
+            "comentários perdidos em {}",
+            arquivo.display()
+        );
+    }
+}
+
+#[test]
+fn formatar_verificar_sinaliza_arquivo_fora_do_padrao() {
+    let root = repo_root();
+    let dir = root.join("build").join("formatar_teste");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let arquivo = dir.join("baguncado.pr");
+    fs::write(
+        &arquivo,
+        "função vazio Principal()\n{\nimprima( 1+2 );\n}\n",
+    )
+    .unwrap();
+
+    let verificar = || {
+        Command::new(env!("CARGO_BIN_EXE_compilador"))
+            .current_dir(&root)
+            .args(["formatar", dir.to_string_lossy().as_ref(), "--verificar"])
+            .output()
+            .expect("falha ao executar compilador formatar")
+    };
+
+    let out = verificar();
+    assert_eq!(out.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&out.stdout).contains("não formatado:"));
+    // --verificar não altera o arquivo
+    assert!(fs::read_to_string(&arquivo)
+        .unwrap()
+        .contains("imprima( 1+2 );"));
+
+    let out = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(&root)
+        .args(["formatar", arquivo.to_string_lossy().as_ref()])
+        .output()
+        .expect("falha ao executar compilador formatar");
+    assert!(out.status.success());
+    assert_eq!(
+        fs::read_to_string(&arquivo).unwrap(),
+        "função vazio Principal() {\n    imprima(1 + 2);\n}\n"
+    );
+    assert!(verificar().status.success());
+}
@@ -0,0 +1,197 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Área de um teste: `build/incremental/<nome>/`, com um projeto `app` de três arquivos
+/// (`forma.pr` ← `area.pr` ← `principal.pr`) e uma biblioteca `util`.
+struct Area {
+    raiz: PathBuf,
+}
+
+impl Area {
+    fn nova(nome: &str) -> Self {
+        let raiz = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("build")
+            .join("incremental")
+            .join(nome);
+        let _ = fs::remove_dir_all(&raiz);
+        for (caminho, conteudo) in [
+            (
+                "app/projeto.toml",
+                "[projeto]\nnome = \"app\"\nversao = \"0.1.0\"\nalvos = [\"bytecode\"]\n",
+            ),
+            (
+                "app/src/forma.pr",
+                "espaco Geo {\n    publico classe Forma {\n        publico inteiro Lado { obter; definir; }\n        publico Forma(inteiro lado) { este.Lado = lado; }\n        publico inteiro Escala() { retorne 1; }\n    }\n}\n",
+            ),
+            (
+                "app/src/area.pr",
+                "usando Geo;\n\nespaco Geo {\n    publico classe Quadrado : Forma {\n        publico Quadrado(inteiro lado) : base(lado) { }\n        publico inteiro Area() {\n            inteiro escala = este.Escala();\n            retorne este.Lado * este.Lado * escala;\n        }\n    }\n}\n",
+            ),
+            (
+                "app/src/principal.pr",
+                "usando Geo;\n\nfunção Principal() {\n    var q = novo Quadrado(3);\n    imprima(q.Area());\n}\n",
+            ),
+            (
+                "util/projeto.toml",
+                "[projeto]\nnome = \"util\"\nversao = \"1.0.0\"\ntipo = \"biblioteca\"\n",
+            ),
+            (
+                "util/src/a.pr",
+                "espaco Util {\n    publico função inteiro Dobro(inteiro x) { retorne x * 2; }\n}\n",
+            ),
+            (
+                "util/src/b.pr",
+                "espaco Util {\n    publico função inteiro Quadruplo(inteiro x) { retorne Dobro(Dobro(x)); }\n}\n",
+            ),
+        ] {
+            let caminho = raiz.join(caminho);
+            fs::create_dir_all(caminho.parent().unwrap()).unwrap();
+            fs::write(caminho, conteudo).unwrap();
+        }
+        Self { raiz }
+    }
+
+    fn construir(&self, projeto: &str, extra: &[&str]) -> String {
+        let output = Command::new(env!("CARGO_BIN_EXE_compilador"))
+            .current_dir(&self.raiz)
+            .arg("construir")
+            .arg(projeto)
+            .args(extra)
+            .output()
+            .expect("falha ao executar compilador");
+        assert_sucesso(&output);
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    }
+
+    fn construir_com_erro(&self, projeto: &str) -> String {
+        let output = Command::new(env!("CARGO_BIN_EXE_compilador"))
+            .current_dir(&self.raiz)
+            .args(["construir", projeto])
+            .output()
+            .expect("falha ao executar compilador");
+        assert!(!output.status.success(), "deveria falhar");
+        String::from_utf8_lossy(&output.stderr).into_owned()
+    }
+
+    fn ler(&self, relativo: &str) -> String {
+        fs::read_to_string(self.raiz.join(relativo)).unwrap()
+    }
+
+    fn executar_app(&self) -> String {
+        let output = Command::new(env!("CARGO_BIN_EXE_interpretador"))
+            .arg(self.raiz.join("app/dist/bytecode/app.pbc"))
+            .output()
+            .expect("falha ao executar interpretador");
+        assert_sucesso(&output);
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    }
+
+    fn escrever(&self, relativo: &str, conteudo: &str) {
+        fs::write(self.raiz.join(relativo), conteudo).unwrap();
+    }
+
+    fn editar(&self, relativo: &str, de: &str, para: &str) {
+        let caminho = self.raiz.join(relativo);
+        let conteudo = fs::read_to_string(&caminho).unwrap();
+        assert!(conteudo.contains(de), "'{}' não está em {}", de, relativo);
+        fs::write(caminho, conteudo.replacen(de, para, 1)).unwrap();
+    }
+}
+
+fn assert_sucesso(output: &Output) {
+    assert!(
+        output.status.success(),
+        "stdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+fn assert_resumo(stdout: &str, trecho: &str) {
+    assert!(stdout.contains(trecho), "esperado '{}' em:\n{}", trecho, stdout);
+}
+
+#[test]
+fn segunda_compilacao_reaproveita_tudo_e_gera_o_mesmo_bytecode() {
+    let area = Area::nova("reaproveita");
+    let stdout = area.construir("app", &[]);
+    assert_resumo(&stdout, "análise reaproveitada em 0/3 arquivo(s), verificação e bytecode em 0/3");
+    let primeiro = area.ler("app/dist/bytecode/app.pbc");
+    assert_eq!(area.executar_app(), "9\n");
+
+    let stdout = area.construir("app", &[]);
+    assert_resumo(&stdout, "análise reaproveitada em 3/3 arquivo(s), verificação e bytecode em 3/3");
+    assert_eq!(area.ler("app/dist/bytecode/app.pbc"), primeiro);
+
+    let stdout = area.construir("app", &["--sem-incremental"]);
+    assert!(!stdout.contains("Compilação incremental"), "{}", stdout);
+    assert_eq!(area.ler("app/dist/bytecode/app.pbc"), primeiro);
+}
+
+#[test]
+fn mudanca_so_no_corpo_refaz_apenas_o_arquivo() {
+    let area = Area::nova("corpo");
+    area.construir("app", &[]);
+    area.editar("app/src/forma.pr", "retorne 1;", "retorne 2;");
+    let stdout = area.construir("app", &[]);
+    assert_resumo(&stdout, "análise reaproveitada em 2/3 arquivo(s), verificação e bytecode em 2/3");
+    assert_eq!(area.executar_app(), "18\n");
+
+    let incremental = area.ler("app/dist/bytecode/app.pbc");
+    area.construir("app", &["--sem-incremental"]);
+    assert_eq!(area.ler("app/dist/bytecode/app.pbc"), incremental);
+}
+
+#[test]
+fn mudanca_de_assinatura_refaz_os_dependentes() {
+    let area = Area::nova("assinatura");
+    area.construir("app", &[]);
+    // `Escala` passa a devolver texto: `area.pr` depende dela e precisa ser verificado de novo
+    area.editar("app/src/forma.pr", "publico inteiro Escala() { retorne 1; }", "publico texto Escala() { retorne \"1\"; }");
+    let stderr = area.construir_com_erro("app");
+    assert!(stderr.contains("Houve erros semânticos"), "{}", stderr);
+
+    area.editar("app/src/forma.pr", "publico texto Escala() { retorne \"1\"; }", "publico inteiro Escala(inteiro k) { retorne k; }");
+    area.editar("app/src/area.pr", "este.Escala()", "este.Escala(3)");
+    let stdout = area.construir("app", &[]);
+    // `principal.pr` depende de `forma.pr` indiretamente, pela herança de `Quadrado`
+    assert_resumo(&stdout, "análise reaproveitada em 1/3 arquivo(s), verificação e bytecode em 0/3");
+    assert_eq!(area.executar_app(), "27\n");
+}
+
+#[test]
+fn biblioteca_incremental_gera_o_mesmo_pbl() {
+    let area = Area::nova("biblioteca");
+    area.construir("util", &[]);
+    let stdout = area.construir("util", &[]);
+    assert_resumo(&stdout, "análise reaproveitada em 2/2 arquivo(s), verificação e bytecode em 2/2");
+    let incremental = area.ler("util/dist/util.pbl");
+    area.construir("util", &["--sem-incremental"]);
+    assert_eq!(area.ler("util/dist/util.pbl"), incremental);
+}
+
+#[test]
+fn codigo_global_de_varios_arquivos_executa_igual_com_e_sem_cache() {
+    let area = Area::nova("codigo_global");
+    // Cada arquivo tem definições antes de código global com saltos: os destinos precisam
+    // contar só o código global de todos os arquivos juntos
+    area.escrever(
+        "app/src/global_a.pr",
+        "função inteiro Um() { retorne 1; }\n\ninteiro a = Um();\nse (a == 1) { imprima(\"a1\"); } senão { imprima(\"a2\"); }\n",
+    );
+    area.escrever(
+        "app/src/global_b.pr",
+        "espaco Geo {\n    publico função inteiro Dois() { retorne 2; }\n}\n\ninteiro b = 0;\nenquanto (b < 2) { b = b + 1; }\nse (b == 2) { imprima(\"b2\"); } senão { imprima(\"b?\"); }\n",
+    );
+    area.construir("app", &["--sem-incremental"]);
+    let sem_cache = area.executar_app();
+    let globais: Vec<&str> = sem_cache.lines().filter(|l| l.len() == 2).collect();
+    assert_eq!(globais, ["a1", "b2"], "{}", sem_cache);
+
+    area.construir("app", &[]);
+    assert_eq!(area.executar_app(), sem_cache);
+    let stdout = area.construir("app", &[]);
+    assert_resumo(&stdout, "verificação e bytecode em 5/5");
+    assert_eq!(area.executar_app(), sem_cache);
+}
@@ -0,0 +1,128 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+const EXEMPLOS: &[&str] = &[
+    "jit_recursao",
+    "jit_lacos",
+    "jit_duplos",
+    "jit_misto",
+    "jit_divisao_zero",
+];
+
+// Cada teste compila em seu próprio diretório, já que rodam em paralelo
+fn compilar(nome: &str, teste: &str) -> PathBuf {
+    let root = repo_root();
+    let saida = root.join("build").join("jit_test").join(teste);
+    let fonte = root
+        .join("tests")
+        .join("fixtures")
+        .join("jit")
+        .join(format!("{}.pr", nome));
+    let output = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(&root)
+        .arg(&fonte)
+        .arg("--target=bytecode")
+        .arg(format!("--output-dir={}", saida.display()))
+        .output()
+        .expect("falha ao executar compilador");
+    assert!(
+        output.status.success(),
+        "compilador falhou para {}: {}",
+        nome,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    saida.join(format!("{}.pbc", nome))
+}
+
+fn interpretar(pbc: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_interpretador"))
+        .current_dir(repo_root())
+        .arg(pbc)
+        .args(args)
+        .output()
+        .expect("falha ao executar interpretador")
+}
+
+// Stderr sem as linhas informativas do JIT
+fn erros(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr)
+        .lines()
+        .filter(|l| !l.starts_with("[JIT]"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[test]
+fn jit_produz_a_mesma_saida_que_o_interpretador() {
+    for nome in EXEMPLOS {
+        let pbc = compilar(nome, "diferencial");
+        let interpretado = interpretar(&pbc, &[]);
+        // Limite 1: toda função elegível é compilada já na primeira chamada
+        let compilado = interpretar(&pbc, &["--jit", "--jit-limite=1"]);
+        assert_eq!(
+            String::from_utf8_lossy(&interpretado.stdout),
+            String::from_utf8_lossy(&compilado.stdout),
+            "stdout diferente em {}",
+            nome
+        );
+        assert_eq!(
+            erros(&interpretado),
+            erros(&compilado),
+            "stderr diferente em {}",
+            nome
+        );
+        assert_eq!(
+            interpretado.status.code(),
+            compilado.status.code(),
+            "{}",
+            nome
+        );
+    }
+}
+
+#[test]
+fn divisao_por_zero_no_codigo_nativo_vira_erro_da_vm() {
+    let pbc = compilar("jit_divisao_zero", "divisao");
+    let output = interpretar(&pbc, &["--jit", "--jit-limite=1"]);
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout).replace("\r\n", "\n"),
+        "20\n25\n33\n50\n100\n"
+    );
+    assert!(erros(&output).contains("Divisão por zero"));
+}
+
+#[cfg(feature = "jit")]
+#[test]
+fn estatisticas_listam_funcoes_compiladas_e_interpretadas() {
+    let pbc = compilar("jit_misto", "estatisticas");
+    let output = interpretar(&pbc, &["--jit-limite=2", "--jit-estatisticas"]);
+    assert!(output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    for esperado in [
+        "[JIT] compilada: Quadrado(inteiro) -> inteiro",
+        "[JIT] compilada: Quadrado2(duplo) -> duplo",
+        "[JIT] interpretada: não suportado: Rotulo(inteiro): instrução 'LOAD_CONST_STR'",
+    ] {
+        assert!(
+            stderr.contains(esperado),
+            "faltou {:?} em:\n{}",
+            esperado,
+            stderr
+        );
+    }
+}
+
+#[cfg(not(feature = "jit"))]
+#[test]
+fn sem_feature_jit_avisa_e_interpreta() {
+    let pbc = compilar("jit_recursao", "sem_feature");
+    let output = interpretar(&pbc, &["--jit"]);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("[JIT] indisponível"));
+    assert!(String::from_utf8_lossy(&output.stdout).contains("46368"));
+}
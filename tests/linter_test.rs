@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn analisar(args: &[&str]) -> (bool, String) {
+    let fixtures = repo_root().join("tests").join("fixtures").join("linter");
+    let saida = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(&fixtures)
+        .arg("analisar")
+        .args(args)
+        .output()
+        .expect("falha ao executar o compilador");
+    (
+        saida.status.success(),
+        String::from_utf8_lossy(&saida.stdout).into_owned(),
+    )
+}
+
+#[test]
+fn analisar_reporta_diagnosticos_com_linha() {
+    // Sem --config, o lint.json do diretório atual é usado
+    let (sucesso, saida) = analisar(&["pedido.pr"]);
+    assert!(!sucesso, "sobrescreve-ausente está configurada como erro:\n{}", saida);
+    for esperado in [
+        "pedido.pr:12: aviso[metodo-privado-nao-utilizado]: Método privado 'Pedido.registrar_log' nunca é chamado",
+        "pedido.pr:20: erro[sobrescreve-ausente]:",
+        "pedido.pr:21: aviso[variavel-nao-utilizada]: Variável 'taxa'",
+        "pedido.pr:33: aviso[condicao-constante]:",
+        "pedido.pr:34: aviso[sombreamento]: Variável 'total'",
+        "pedido.pr:37: aviso[autoatribuicao]:",
+        "1 arquivo(s) analisado(s): 1 erro(s), 6 aviso(s)",
+    ] {
+        assert!(saida.contains(esperado), "faltou {:?} em:\n{}", esperado, saida);
+    }
+    // Desligada pelo lint.json; silenciada por [Permitir] em Ignorar
+    assert!(!saida.contains("[nomenclatura]"), "{}", saida);
+    assert!(!saida.contains("[parametro-nao-utilizado]"), "{}", saida);
+}
+
+#[test]
+fn config_inexistente_falha_e_regras_listam_niveis() {
+    let (sucesso, saida) = analisar(&["pedido.pr", "--config=/dev/null/inexistente.json"]);
+    assert!(!sucesso);
+    assert!(saida.is_empty());
+
+    let (_, regras) = analisar(&["--regras", "--config=lint.json"]);
+    assert!(regras.contains("nomenclatura"));
+    assert!(regras
+        .lines()
+        .any(|l| l.starts_with("sobrescreve-ausente") && l.contains("erro")));
+}
@@ -101,14 +101,15 @@ fn parse_and_typecheck(files: &[&str]) -> compilador_portugues::ast::Programa {
     programa_final
 }
 
-/// Gera LLVM IR diretamente (sem invocar clang) e valida que não panica e não é vazio.
+/// Gera LLVM IR diretamente (sem invocar clang) e valida que não falha e não é vazio.
 fn assert_llvm_ir_generates(files: &[&str]) {
     let programa = parse_and_typecheck(files);
     let mut tc = compilador_portugues::type_checker::VerificadorTipos::new();
     tc.verificar_programa(&programa)
         .expect("typecheck deveria passar");
 
-    let ir = compilador_portugues::codegen::gerar_llvm_ir_puro(&programa, &mut tc);
+    let ir = compilador_portugues::codegen::gerar_llvm_ir_puro(&programa, &mut tc)
+        .unwrap_or_else(|e| panic!("geração de LLVM IR falhou para {:?}:\n{}", files, e));
 
     assert!(
         ir.contains("define i32 @main()"),
@@ -1,3 +1,7 @@
+mod diferencial;
+mod wasm_host;
+
+use diferencial::Backend;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
@@ -128,3 +132,21 @@ fn run_exemplos_llvm_end_to_end() {
         }
     }
 }
+
+#[test]
+fn llvm_concorda_com_a_referencia_nos_exemplos() {
+    if !have_clang() {
+        eprintln!("clang não encontrado; backend LLVM fora da comparação.");
+        return;
+    }
+    diferencial::verificar(diferencial::casos_exemplos(), &[Backend::Llvm]);
+}
+
+#[test]
+fn llvm_concorda_com_a_referencia_no_corpus_de_casos() {
+    if !have_clang() {
+        eprintln!("clang não encontrado; backend LLVM fora da comparação.");
+        return;
+    }
+    diferencial::verificar(diferencial::casos_corpus(), &[Backend::Llvm]);
+}
@@ -0,0 +1,244 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Biblioteca `geo` com tudo que o manifesto descreve: interfaces, enumerações, genéricos,
+/// classes abstratas, membros protegidos e estáticos, valores padrão e funções livres.
+const GEO: &str = r#"espaco Geo {
+    enumeração Cor { Vermelho, Verde, Azul }
+
+    publico interface IForma {
+        publico duplo Area();
+    }
+
+    publico interface IBox<T> {
+        publico T Pegar();
+    }
+
+    publico abstrata classe Forma : IForma {
+        protegido texto rotulo;
+        publico Forma(texto rotulo) { este.rotulo = rotulo; }
+        publico abstrata duplo Area();
+        publico redefinível texto Nome() { retorne rotulo; }
+        publico texto Fixo() { retorne "fixo"; }
+        protegido texto Segredo() { retorne "!"; }
+        privado texto Interno() { retorne "interno"; }
+    }
+
+    publico classe Quadrado : Forma {
+        publico duplo lado { obter; definir; }
+        publico Quadrado(duplo lado) : base("quadrado") { este.lado = lado; }
+        publico sobrescreve duplo Area() { retorne lado * lado; }
+    }
+
+    publico classe Caixa<T> : IBox<T> {
+        publico T valor { obter; definir; }
+        publico Caixa(T v) { este.valor = v; }
+        publico T Pegar() { retorne valor; }
+    }
+
+    publico estática classe Util {
+        publico estática texto Saudar(texto nome, texto prefixo = "Olá", inteiro vezes = 1) {
+            retorne prefixo + ", " + nome + " x" + vezes;
+        }
+    }
+
+    publico função inteiro Dobro(inteiro x) { retorne x * 2; }
+}
+
+publico função texto Versao() { retorne "1.0.0"; }
+"#;
+
+/// Área de um teste: `build/manifesto/<nome>/`, com a biblioteca `geo` e um `app` que a usa.
+struct Area {
+    raiz: PathBuf,
+}
+
+impl Area {
+    fn nova(nome: &str) -> Self {
+        let raiz = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("build")
+            .join("manifesto")
+            .join(nome);
+        let _ = fs::remove_dir_all(&raiz);
+        for (caminho, conteudo) in [
+            (
+                "geo/projeto.toml",
+                "[projeto]\nnome = \"geo\"\nversao = \"1.0.0\"\ntipo = \"biblioteca\"\n",
+            ),
+            ("geo/src/geo.pr", GEO),
+            (
+                "app/projeto.toml",
+                "[projeto]\nnome = \"app\"\nversao = \"0.1.0\"\nalvos = [\"bytecode\"]\n\n[dependencias]\ngeo = { caminho = \"../geo\" }\n",
+            ),
+        ] {
+            let caminho = raiz.join(caminho);
+            fs::create_dir_all(caminho.parent().unwrap()).unwrap();
+            fs::write(caminho, conteudo).unwrap();
+        }
+        Self { raiz }
+    }
+
+    /// Constrói o `app` com o programa dado.
+    fn construir(&self, principal: &str) -> Output {
+        fs::create_dir_all(self.raiz.join("app/src")).unwrap();
+        fs::write(self.raiz.join("app/src/principal.pr"), principal).unwrap();
+        Command::new(env!("CARGO_BIN_EXE_compilador"))
+            .current_dir(self.raiz.join("app"))
+            .arg("construir")
+            .output()
+            .expect("falha ao executar compilador")
+    }
+
+    fn executar_app(&self) -> String {
+        let saida = Command::new(env!("CARGO_BIN_EXE_interpretador"))
+            .arg(self.raiz.join("app/dist/bytecode/app.pbc"))
+            .output()
+            .expect("falha ao executar interpretador");
+        String::from_utf8_lossy(&saida.stdout).into_owned()
+    }
+
+    fn manifesto(&self) -> String {
+        let pbl = fs::read_to_string(self.raiz.join("geo/dist/geo.pbl")).expect("geo.pbl não gerado");
+        pbl.split("[BYTECODE]").next().unwrap().to_string()
+    }
+}
+
+fn assert_sucesso(output: &Output) {
+    assert!(
+        output.status.success(),
+        "stdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+fn assert_erro(output: &Output, trecho: &str) {
+    let saida = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!output.status.success(), "deveria falhar");
+    assert!(saida.contains(trecho), "esperado '{}' em:\n{}", trecho, saida);
+}
+
+#[test]
+fn manifesto_descreve_toda_a_api_publica() {
+    let area = Area::nova("api");
+    assert_sucesso(&area.construir("usando Geo;\n\nfunção Principal() { }\n"));
+    let manifesto = area.manifesto();
+    for linha in [
+        "DEFINE_FUNCTION Versao texto 0",
+        "DEFINE_ENUM Geo.Cor Vermelho Verde Azul",
+        "DEFINE_INTERFACE Geo.IBox genericos=T",
+        "INTERFACE_METHOD Geo.IBox Pegar T 0",
+        "DEFINE_CLASS Geo.Forma NULO abstrata interfaces=Geo.IForma",
+        "FIELD Geo.Forma rotulo texto acesso=protegido",
+        "DEFINE_METHOD Geo.Forma Area duplo 0 abstrato",
+        "DEFINE_METHOD Geo.Forma Nome texto 0 redefinivel",
+        "DEFINE_METHOD Geo.Quadrado Area duplo 0 sobrescreve",
+        "DEFINE_CLASS Geo.Caixa NULO genericos=T interfaces=Geo.IBox<T>",
+        "DEFINE_STATIC_METHOD Geo.Util Saudar texto 3 texto:nome texto:prefixo=\"Olá\" inteiro:vezes=1",
+        "DEFINE_FUNCTION Geo.Dobro inteiro 1 inteiro:x",
+    ] {
+        assert!(manifesto.contains(linha), "falta '{}' em:\n{}", linha, manifesto);
+    }
+    assert!(!manifesto.contains("Interno"), "{}", manifesto);
+}
+
+#[test]
+fn programa_usa_toda_a_api_da_biblioteca() {
+    let area = Area::nova("uso");
+    assert_sucesso(&area.construir(
+        r#"usando Geo;
+
+classe Circulo : Forma {
+    publico duplo raio { obter; definir; }
+    publico Circulo(duplo r) : base("circulo") { este.raio = r; }
+    publico sobrescreve duplo Area() { retorne 3.0 * raio * raio; }
+    publico sobrescreve texto Nome() { retorne "c:" + este.rotulo + este.Segredo(); }
+}
+
+publico função Principal() {
+    var q = novo Quadrado(2.0);
+    imprima(q.Area());
+    imprima(q.Nome());
+    IForma f = novo Circulo(1.0);
+    imprima(f.Area());
+    imprima(novo Circulo(2.0).Nome());
+    var cx = novo Caixa<inteiro>(7);
+    inteiro v = cx.Pegar();
+    imprima(v + 1);
+    imprima(Util.Saudar("Ana"));
+    imprima(Util.Saudar("Ana", "Oi", 2));
+    Cor k = Cor.Azul;
+    imprima(k == Cor.Azul);
+    imprima(Dobro(21));
+    imprima(Versao());
+}
+"#,
+    ));
+    assert_eq!(
+        area.executar_app(),
+        "4.000000\nquadrado\n3.000000\nc:circulo!\n8\nOlá, Ana x1\nOi, Ana x2\nverdadeiro\n42\n1.0.0\n"
+    );
+}
+
+#[test]
+fn chamadas_invalidas_sao_recusadas_na_compilacao() {
+    let area = Area::nova("erros");
+    let casos = [
+        ("imprima(Dobro(1, 2));", "'Geo.Dobro' espera 1 argumento(s), mas recebeu 2."),
+        (
+            "imprima(Dobro(\"a\"));",
+            "Argumento 1 ('x') de 'Geo.Dobro': esperado inteiro, recebido texto.",
+        ),
+        ("Util.Saudar();", "'Geo.Util.Saudar' espera de 1 a 3 argumento(s), mas recebeu 0."),
+        ("imprima(novo Quadrado(2.0).Segredo());", "é protegido"),
+        ("imprima(novo Quadrado(2.0).rotulo);", "é protegido"),
+        ("novo Quadrado(2.0).Voar();", "Método 'Voar' não existe na classe 'Geo.Quadrado'."),
+        ("var f = novo Forma(\"x\");", "A classe abstrata 'Geo.Forma' não pode ser instanciada."),
+        ("var f = novo IForma();", "A interface 'Geo.IForma' não pode ser instanciada."),
+        ("var u = novo Util();", "A classe estática 'Geo.Util' não pode ser instanciada."),
+        ("Cor k = Cor.Roxo;", "Membro \"Roxo\" não existe no enum \"Geo.Cor\"."),
+        (
+            "var cx = novo Caixa<inteiro>(\"a\");",
+            "Argumento 1 ('v') de 'novo Geo.Caixa': esperado inteiro, recebido texto.",
+        ),
+    ];
+    for (corpo, erro) in casos {
+        let programa = format!("usando Geo;\n\nfunção Principal() {{\n    {}\n}}\n", corpo);
+        assert_erro(&area.construir(&programa), erro);
+    }
+}
+
+#[test]
+fn subclasses_respeitam_o_contrato_da_base_externa() {
+    let area = Area::nova("heranca");
+    assert_erro(
+        &area.construir(
+            r#"usando Geo;
+
+classe C : Forma {
+    publico C() : base("c") { }
+    publico sobrescreve duplo Area() { retorne 1.0; }
+    publico sobrescreve texto Fixo() { retorne "x"; }
+}
+
+função Principal() { }
+"#,
+        ),
+        "Método 'Fixo' em 'C' usa 'sobrescreve' mas o método da classe base não é 'redefinível'.",
+    );
+    assert_erro(
+        &area.construir(
+            "usando Geo;\n\nclasse C : Forma {\n    publico C() : base(\"c\") { }\n}\n\nfunção Principal() { }\n",
+        ),
+        "Classe 'C' não implementa o método abstrato 'Area' de 'Geo.Forma'",
+    );
+    assert_erro(
+        &area.construir("usando Geo;\n\nclasse C : IForma { }\n\nfunção Principal() { }\n"),
+        "Classe 'C' não implementa método obrigatório 'Area' da interface 'Geo.IForma'",
+    );
+}
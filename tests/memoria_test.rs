@@ -0,0 +1,222 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+/// Pico de memória residente aceito para o laço de um milhão de iterações.
+const LIMITE_RSS_KB: u64 = 64 * 1024;
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn have_clang() -> bool {
+    Command::new("clang").arg("--version").output().is_ok()
+}
+
+/// `CC` ou o primeiro entre cc, gcc e clang.
+fn compilador_c() -> Option<String> {
+    std::env::var("CC").ok().or_else(|| {
+        ["cc", "gcc", "clang"]
+            .iter()
+            .find(|c| Command::new(c).arg("--version").output().is_ok())
+            .map(|c| c.to_string())
+    })
+}
+
+/// Compila o fixture em `build/memoria` (o backend LLVM grava no diretório atual).
+/// O status é ignorado: sem clang, o .ll é gerado mas o executável não.
+fn compilar() -> (PathBuf, std::process::Output) {
+    let root = repo_root();
+    let dir = root.join("build").join("memoria");
+    fs::create_dir_all(&dir).unwrap();
+    let _ = fs::remove_file(dir.join("laco_textos.ll"));
+    let fonte = root
+        .join("tests")
+        .join("fixtures")
+        .join("memoria")
+        .join("laco_textos.pr");
+    let output = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(&dir)
+        .arg(&fonte)
+        .arg("--target=llvm-ir")
+        .output()
+        .expect("falha ao executar compilador");
+    (dir, output)
+}
+
+/// Pico de memória residente (VmHWM) de um processo em execução, em KB.
+fn pico_rss_kb(pid: u32) -> Option<u64> {
+    let status = fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    status
+        .lines()
+        .find(|l| l.starts_with("VmHWM:"))?
+        .split_whitespace()
+        .nth(1)?
+        .parse()
+        .ok()
+}
+
+#[test]
+fn alocacoes_passam_pelo_coletor() {
+    let (dir, _) = compilar();
+    let ir = fs::read_to_string(dir.join("laco_textos.ll")).expect("laco_textos.ll não gerado");
+
+    assert!(ir.contains("declare noalias i8* @pds_gc_alocar(i64)"));
+    assert!(
+        !ir.contains("call i8* @malloc("),
+        "alocação fora do coletor"
+    );
+    // Textos não têm ponteiros: o coletor não precisa percorrê-los
+    assert!(ir.contains("call i8* @pds_gc_alocar_atomico("));
+    // Conversões de número para texto não usam mais buffers na pilha
+    assert!(!ir.contains("alloca [21 x i8]"));
+
+    // Campos estáticos são raízes registradas antes de `main`
+    assert!(ir.contains("@llvm.global_ctors"));
+    assert!(ir.contains("call void @pds_gc_adicionar_raiz(i8* bitcast (i8** @.static.Registro.Primeiro to i8*), i64 8)"));
+}
+
+#[test]
+fn allocas_ficam_no_bloco_de_entrada() {
+    let (dir, _) = compilar();
+    let ir = fs::read_to_string(dir.join("laco_textos.ll")).expect("laco_textos.ll não gerado");
+
+    let principal = ir
+        .split("define ")
+        .find(|f| f.contains("@\"Principal\"()"))
+        .expect("Principal não encontrada");
+    let mut linhas = principal.lines().skip_while(|l| *l != "entry:").skip(1);
+    // Primeiro todos os alloca (inclusive os de `linha` e `numeros`, declarados no laço)...
+    let allocas: Vec<&str> = linhas
+        .by_ref()
+        .take_while(|l| l.contains(" = alloca "))
+        .collect();
+    assert!(allocas.iter().any(|l| l.contains("%var.linha")));
+    assert!(allocas.iter().any(|l| l.contains("%var.numeros")));
+    // ...e nenhum depois
+    assert!(linhas.all(|l| !l.contains(" = alloca ")));
+}
+
+#[test]
+fn laco_de_um_milhao_de_textos_com_memoria_limitada() {
+    if !have_clang() {
+        eprintln!("clang não encontrado; ignorando teste LLVM.");
+        return;
+    }
+    let (dir, output) = compilar();
+    assert!(
+        output.status.success(),
+        "compilador falhou:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let exe = if cfg!(windows) {
+        dir.join("laco_textos.exe")
+    } else {
+        dir.join("laco_textos")
+    };
+
+    let mut filho = Command::new(&exe)
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("falha ao executar programa");
+    // VmHWM só aumenta: a última leitura antes do fim é o pico (só há /proc no Linux)
+    let mut pico = None;
+    while filho
+        .try_wait()
+        .expect("falha ao aguardar programa")
+        .is_none()
+    {
+        pico = pico_rss_kb(filho.id()).or(pico);
+        thread::sleep(Duration::from_millis(5));
+    }
+    let saida = filho.wait_with_output().expect("falha ao ler saída");
+    assert!(saida.status.success(), "programa terminou com erro");
+    assert_eq!(
+        String::from_utf8_lossy(&saida.stdout).replace("\r\n", "\n"),
+        "linha 999999: 1999998 (1000001)\nprimeiro 0\n"
+    );
+
+    if cfg!(target_os = "linux") {
+        let pico = pico.expect("VmHWM não lido");
+        assert!(
+            pico < LIMITE_RSS_KB,
+            "pico de memória {} KB acima do limite de {} KB",
+            pico,
+            LIMITE_RSS_KB
+        );
+    }
+}
+
+#[test]
+fn coletor_libera_so_blocos_inalcancaveis() {
+    let Some(cc) = compilador_c() else {
+        eprintln!("compilador C não encontrado; ignorando teste do coletor.");
+        return;
+    };
+    let root = repo_root();
+    let runtime = root.join("src").join("runtime");
+    let dir = root.join("build").join("memoria");
+    fs::create_dir_all(&dir).unwrap();
+    let exe = dir.join(if cfg!(windows) { "coletor.exe" } else { "coletor" });
+
+    let mut compilar = Command::new(&cc);
+    compilar
+        .arg("-O0")
+        .arg("-I")
+        .arg(&runtime)
+        .arg(root.join("tests").join("fixtures").join("memoria").join("coletor.c"))
+        .arg(runtime.join("gc_runtime.c"))
+        .arg("-o")
+        .arg(&exe);
+    if !cfg!(windows) {
+        compilar.arg("-pthread");
+    }
+    let output = compilar.output().expect("falha ao executar compilador C");
+    assert!(
+        output.status.success(),
+        "{} falhou:\n{}",
+        cc,
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let saida = Command::new(&exe)
+        .output()
+        .expect("falha ao executar teste do coletor");
+    let stdout = String::from_utf8_lossy(&saida.stdout).replace("\r\n", "\n");
+    assert!(saida.status.success(), "{}", stdout);
+    assert_eq!(stdout, "ok\n");
+}
+
+#[test]
+fn stdlib_gerada_antes_do_coletor_e_rejeitada() {
+    let root = repo_root();
+    let dir = root.join("build").join("memoria").join("stdlib_antiga");
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::create_dir_all(dir.join("dist")).unwrap();
+    // Um objeto alocado fora do coletor pode guardar a única referência a um bloco do coletor
+    fs::write(
+        dir.join("dist").join("sistema.ll"),
+        "define i8* @novo_objeto() {\n  %p = call i8* @malloc(i64 16)\n  ret i8* %p\n}\n",
+    )
+    .unwrap();
+    let fonte = dir.join("programa.pr");
+    fs::write(&fonte, "função vazio Principal() {\n    imprima(1);\n}\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(&dir)
+        .arg(&fonte)
+        .arg("--target=llvm-ir")
+        .arg(format!("--stdlib-src-path={}", dir.display()))
+        .output()
+        .expect("falha ao executar compilador");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "stdlib antiga aceita");
+    assert!(
+        stderr.contains("aloca com malloc; recompile-a com --compilar-biblioteca"),
+        "{}",
+        stderr
+    );
+}
@@ -0,0 +1,293 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn fixtures() -> PathBuf {
+    repo_root().join("tests").join("fixtures").join("modulos")
+}
+
+fn have_clang() -> bool {
+    Command::new("clang").arg("--version").output().is_ok()
+}
+
+const SAIDA_ESPERADA: &str = "carregando utilidades\nOlá, Bia!\n42\n12\n14\n25\n2\nababab\n";
+
+fn diretorio(subdir: &str) -> PathBuf {
+    let dir = repo_root().join("build").join("modulos").join(subdir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Compila o programa principal dos fixtures em `build/modulos/<subdir>`, com `bib/` nos
+/// caminhos de busca.
+fn compilar_principal(subdir: &str, extras: &[&str]) -> (PathBuf, Output) {
+    let dir = diretorio(subdir);
+    let output = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(&dir)
+        .arg(fixtures().join("app").join("principal.pr"))
+        .arg(format!("--modulos={}", fixtures().join("bib").display()))
+        .args(extras)
+        .output()
+        .expect("falha ao executar compilador");
+    (dir, output)
+}
+
+/// Grava os arquivos `(nome, código)` em `build/modulos/<subdir>` e executa o primeiro.
+fn executar_arquivos(subdir: &str, arquivos: &[(&str, &str)]) -> Output {
+    let dir = diretorio(subdir);
+    for (nome, codigo) in arquivos {
+        let caminho = dir.join(nome);
+        fs::create_dir_all(caminho.parent().unwrap()).unwrap();
+        fs::write(caminho, codigo).unwrap();
+    }
+    Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(&dir)
+        .arg(arquivos[0].0)
+        .arg("--executar")
+        .output()
+        .expect("falha ao executar compilador")
+}
+
+fn assert_erro(output: &Output, trecho: &str) {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "deveria falhar");
+    assert!(
+        stderr.contains(trecho),
+        "esperado '{}' em:\n{}",
+        trecho,
+        stderr
+    );
+}
+
+#[test]
+fn modulos_importados_sao_executados() {
+    let (_, output) = compilar_principal("executar", &["--executar"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), SAIDA_ESPERADA);
+}
+
+#[test]
+fn bytecode_inclui_os_modulos() {
+    let (dir, output) = compilar_principal("bytecode", &["--target=bytecode"]);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let execucao = Command::new(env!("CARGO_BIN_EXE_interpretador"))
+        .current_dir(&dir)
+        .arg(Path::new("build").join("principal.pbc"))
+        .output()
+        .expect("falha ao executar interpretador");
+    assert!(execucao.status.success());
+    assert_eq!(String::from_utf8_lossy(&execucao.stdout), SAIDA_ESPERADA);
+}
+
+#[test]
+fn llvm_ir_inclui_os_modulos() {
+    let (dir, output) = compilar_principal("llvm", &["--target=llvm-ir"]);
+    let ir = fs::read_to_string(dir.join("principal.ll")).unwrap_or_else(|_| {
+        panic!(
+            "principal.ll não gerado:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        )
+    });
+    for simbolo in [
+        "@\"Dobro\"",
+        "@\"Somar\"",
+        "@\"Retangulo::Area\"",
+        "@\"Repetir\"",
+    ] {
+        assert!(ir.contains(simbolo), "{} ausente no IR", simbolo);
+    }
+    // Os comandos do módulo rodam antes do programa principal
+    assert!(ir.contains("carregando utilidades"));
+
+    if !have_clang() {
+        eprintln!("clang não encontrado; execução do executável ignorada");
+        return;
+    }
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let execucao = Command::new(dir.join("principal")).output().unwrap();
+    assert_eq!(String::from_utf8_lossy(&execucao.stdout), SAIDA_ESPERADA);
+}
+
+#[test]
+fn caminhos_de_busca_vem_de_pordosol_modulos() {
+    let dir = diretorio("variavel");
+    let output = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(&dir)
+        .arg(fixtures().join("app").join("principal.pr"))
+        .arg("--executar")
+        .env("PORDOSOL_MODULOS", fixtures().join("bib"))
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), SAIDA_ESPERADA);
+
+    let sem_caminho = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(&dir)
+        .arg(fixtures().join("app").join("principal.pr"))
+        .arg("--executar")
+        .env_remove("PORDOSOL_MODULOS")
+        .output()
+        .unwrap();
+    assert_erro(&sem_caminho, "Módulo 'texto' não encontrado");
+}
+
+#[test]
+fn itens_privados_nao_podem_ser_importados_nem_usados() {
+    let modulo = "exportar função inteiro Dobro(inteiro x) { retorne Somar(x, x); }\n\
+                  função inteiro Somar(inteiro a, inteiro b) { retorne a + b; }\n\
+                  exportar função inteiro Triplo(inteiro x) { retorne x * 3; }\n\
+                  classe Interna { }\n";
+
+    let importa_privado = executar_arquivos(
+        "privado",
+        &[
+            (
+                "main.pr",
+                "importar { Somar } de \"util\";\nfunção Principal() { imprima(Somar(1, 2)); }\n",
+            ),
+            ("util.pr", modulo),
+        ],
+    );
+    assert_erro(&importa_privado, "Item 'Somar' é privado no módulo 'util'");
+
+    let usa_privado = executar_arquivos(
+        "uso_privado",
+        &[
+            (
+                "main.pr",
+                "importar { Dobro } de \"util\";\nfunção Principal() {\n    Interna i = novo Interna();\n}\n",
+            ),
+            ("util.pr", modulo),
+        ],
+    );
+    assert_erro(&usa_privado, "'Interna' é privado no módulo 'util'");
+    assert_erro(&usa_privado, "main.pr:3:");
+
+    let nao_importado = executar_arquivos(
+        "nao_importado",
+        &[
+            (
+                "main.pr",
+                "importar { Dobro } de \"util\";\nfunção Principal() { imprima(Triplo(2)); }\n",
+            ),
+            ("util.pr", modulo),
+        ],
+    );
+    assert_erro(
+        &nao_importado,
+        "'Triplo' pertence ao módulo 'util' e não foi importado",
+    );
+
+    let inexistente = executar_arquivos(
+        "inexistente",
+        &[
+            ("main.pr", "importar { Quadruplo } de \"util\";\n"),
+            ("util.pr", modulo),
+        ],
+    );
+    assert_erro(
+        &inexistente,
+        "Item 'Quadruplo' não encontrado no módulo 'util'",
+    );
+}
+
+#[test]
+fn dependencias_circulares_sao_rejeitadas() {
+    let output = executar_arquivos(
+        "ciclo",
+        &[
+            (
+                "main.pr",
+                "importar \"a\";\nfunção Principal() { imprima(A()); }\n",
+            ),
+            (
+                "a.pr",
+                "importar { B } de \"lib/b\";\nexportar função inteiro A() { retorne B(); }\n",
+            ),
+            (
+                "lib/b.pr",
+                "importar { A } de \"../a\";\nexportar função inteiro B() { retorne 2; }\n",
+            ),
+        ],
+    );
+    assert_erro(
+        &output,
+        "Dependência circular entre módulos: a.pr → b.pr → a.pr",
+    );
+}
+
+#[test]
+fn exportacoes_e_nomes_repetidos_sao_verificados() {
+    let exportacao_invalida = executar_arquivos(
+        "exportacao",
+        &[
+            ("main.pr", "importar \"util\";\n"),
+            ("util.pr", "exportar { Inexistente };\n"),
+        ],
+    );
+    assert_erro(
+        &exportacao_invalida,
+        "Exportação 'Inexistente' não corresponde a nenhuma declaração no módulo 'util'",
+    );
+
+    let repetido = executar_arquivos(
+        "repetido",
+        &[
+            (
+                "main.pr",
+                "importar \"util\";\nfunção inteiro Auxiliar() { retorne 1; }\n",
+            ),
+            ("util.pr", "função inteiro Auxiliar() { retorne 2; }\n"),
+        ],
+    );
+    assert_erro(
+        &repetido,
+        "'Auxiliar' é declarado nos módulos 'util' e 'main.pr'",
+    );
+}
+
+#[test]
+fn modulo_compartilhado_e_carregado_uma_vez() {
+    let output = executar_arquivos(
+        "compartilhado",
+        &[
+            (
+                "main.pr",
+                "importar { A } de \"a\";\nimportar { B } de \"b\";\nimprima(A() + B());\n",
+            ),
+            (
+                "a.pr",
+                "importar { Base } de \"base\";\nexportar função inteiro A() { retorne Base() + 1; }\n",
+            ),
+            (
+                "b.pr",
+                "importar { Base } de \"base\";\nexportar função inteiro B() { retorne Base() + 2; }\n",
+            ),
+            (
+                "base.pr",
+                "imprima(\"base\");\nexportar função inteiro Base() { retorne 10; }\n",
+            ),
+        ],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "base\n23\n");
+}
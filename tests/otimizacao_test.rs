@@ -0,0 +1,175 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn have_clang() -> bool {
+    Command::new("clang").arg("--version").output().is_ok()
+}
+
+fn have_opt() -> bool {
+    Command::new("opt").arg("--version").output().is_ok()
+}
+
+fn fixture() -> PathBuf {
+    repo_root()
+        .join("tests")
+        .join("fixtures")
+        .join("otimizacao")
+        .join("fatorial.pr")
+}
+
+/// Compila `fatorial.pr` para LLVM em `build/otimizacao/<subdir>` (o backend grava no
+/// diretório atual) e devolve o diretório e a saída do compilador.
+fn compilar(subdir: &str, extras: &[&str]) -> (PathBuf, Output) {
+    let dir = repo_root().join("build").join("otimizacao").join(subdir);
+    fs::create_dir_all(&dir).unwrap();
+    let _ = fs::remove_file(dir.join("fatorial.ll"));
+    let output = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(&dir)
+        .arg(fixture())
+        .arg("--target=llvm-ir")
+        .args(extras)
+        .output()
+        .expect("falha ao executar compilador");
+    (dir, output)
+}
+
+fn ler_ll(dir: &Path) -> String {
+    fs::read_to_string(dir.join("fatorial.ll")).expect("fatorial.ll não gerado")
+}
+
+#[test]
+fn ir_marca_funcoes_internas_e_aritmetica_nsw() {
+    let (dir, _) = compilar("atributos", &[]);
+    let ir = ler_ll(&dir);
+
+    // Só `main` é exportada pelo programa
+    assert!(ir.contains("define i32 @main()"));
+    assert!(ir.contains("define internal i32 @\"Fatorial\"(i32 %param.n)"));
+    assert!(ir.contains("define internal void @\"Principal\"()"));
+
+    assert!(ir.contains(" = mul nsw i32 "));
+    assert!(ir.contains(" = sub nsw i32 "));
+    assert!(ir.contains(" = add nsw i32 "));
+    assert!(ir.contains(" = sub nsw i32 0, "));
+
+    assert!(ir.contains("(%class.Acumulador* nonnull %param.self, i32 %param.valor)"));
+    assert!(ir.contains("(%class.Acumulador* nonnull %param.self)"));
+    assert!(!ir.contains("noalias nonnull %param.self"));
+}
+
+#[test]
+fn biblioteca_mantem_funcoes_exportadas() {
+    let lib = repo_root()
+        .join("build")
+        .join("otimizacao")
+        .join("biblioteca");
+    let _ = fs::remove_dir_all(&lib);
+    fs::create_dir_all(lib.join("src")).unwrap();
+    fs::write(
+        lib.join("src").join("util.pr"),
+        "espaco Util {\n    publico função inteiro Dobro(inteiro x) {\n        retorne x * 2;\n    }\n}\n",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(repo_root())
+        .arg(format!("--compilar-biblioteca={}", lib.display()))
+        .output()
+        .expect("falha ao executar compilador");
+    assert!(
+        output.status.success(),
+        "compilador falhou:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let ll = fs::read_dir(lib.join("dist"))
+        .unwrap()
+        .flatten()
+        .map(|e| e.path())
+        .find(|p| p.extension().and_then(|s| s.to_str()) == Some("ll"))
+        .expect(".ll da biblioteca não gerado");
+    let ir = fs::read_to_string(ll).unwrap();
+    assert!(ir.contains("define i32 @\"Util_Dobro\"("), "IR:\n{}", ir);
+    assert!(!ir.contains("define internal"));
+}
+
+#[test]
+fn nivel_de_otimizacao_invalido_e_rejeitado() {
+    let (_, output) = compilar("invalido", &["-O5"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Nível de otimização inválido: -O5"),
+        "stderr:\n{}",
+        stderr
+    );
+}
+
+#[test]
+fn ir_otimizado_pelo_opt_em_todos_os_niveis() {
+    if !have_opt() {
+        eprintln!("opt não encontrado; ignorando teste.");
+        return;
+    }
+    let (dir, _) = compilar("opt", &[]);
+    for nivel in ["-O1", "-O2", "-O3", "-Os"] {
+        let saida = dir.join(format!("fatorial{}.ll", nivel));
+        let output = Command::new("opt")
+            .arg(nivel)
+            .arg(dir.join("fatorial.ll"))
+            .arg("-S")
+            .arg("-o")
+            .arg(&saida)
+            .output()
+            .expect("falha ao executar opt");
+        assert!(
+            output.status.success(),
+            "opt {} falhou:\n{}",
+            nivel,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        // Funções internas já incorporadas a `main` deixam de existir
+        let otimizado = fs::read_to_string(&saida).unwrap();
+        assert!(
+            !otimizado.contains("@\"Acumulador::Somar\""),
+            "{}: método não usado deveria ser removido",
+            nivel
+        );
+    }
+}
+
+#[test]
+fn executavel_otimizado_tem_a_mesma_saida() {
+    if !have_clang() {
+        eprintln!("clang não encontrado; ignorando teste LLVM.");
+        return;
+    }
+    for nivel in ["-O0", "-O2", "-Os"] {
+        let (dir, output) = compilar(&nivel[1..], &[nivel]);
+        assert!(
+            output.status.success(),
+            "compilador falhou com {}:\n{}",
+            nivel,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let exe = if cfg!(windows) {
+            dir.join("fatorial.exe")
+        } else {
+            dir.join("fatorial")
+        };
+        let execucao = Command::new(&exe)
+            .output()
+            .expect("falha ao executar programa");
+        assert_eq!(
+            String::from_utf8_lossy(&execucao.stdout).replace("\r\n", "\n"),
+            "153\n-153\n",
+            "saída com {}",
+            nivel
+        );
+    }
+}
@@ -0,0 +1,266 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Área de um teste: `build/pacotes/<nome>/`, com registro e cache próprios.
+struct Area {
+    raiz: PathBuf,
+}
+
+impl Area {
+    fn nova(nome: &str) -> Self {
+        let raiz = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("build")
+            .join("pacotes")
+            .join(nome);
+        let _ = fs::remove_dir_all(&raiz);
+        fs::create_dir_all(&raiz).unwrap();
+        Self { raiz }
+    }
+
+    fn registro(&self) -> PathBuf {
+        self.raiz.join("registro")
+    }
+
+    fn escrever(&self, arquivos: &[(&str, &str)]) {
+        for (nome, conteudo) in arquivos {
+            let caminho = self.raiz.join(nome);
+            fs::create_dir_all(caminho.parent().unwrap()).unwrap();
+            fs::write(caminho, conteudo).unwrap();
+        }
+    }
+
+    fn compilador(&self, dir: &str, args: &[&str]) -> Output {
+        Command::new(env!("CARGO_BIN_EXE_compilador"))
+            .current_dir(self.raiz.join(dir))
+            .args(args)
+            .env("PORDOSOL_REGISTRO", self.registro())
+            .env("PORDOSOL_CACHE", self.raiz.join("cache"))
+            .output()
+            .expect("falha ao executar compilador")
+    }
+
+    /// Publica a biblioteca `Texto` na versão dada; `Versao()` devolve a própria versão.
+    fn publicar_texto(&self, versao: &str) {
+        let dir = format!("texto-{}", versao);
+        self.escrever(&[
+            (
+                &format!("{}/projeto.toml", dir),
+                &format!(
+                    "[projeto]\nnome = \"texto\"\nversao = \"{}\"\ntipo = \"biblioteca\"\n",
+                    versao
+                ),
+            ),
+            (
+                &format!("{}/src/formato.pr", dir),
+                &format!(
+                    r#"espaco Texto {{
+    publico estática classe Formato {{
+        publico estática texto Versao() {{
+            retorne "{}";
+        }}
+        publico estática texto Caixa(texto s) {{
+            retorne "[" + s + "]";
+        }}
+    }}
+}}
+"#,
+                    versao
+                ),
+            ),
+        ]);
+        assert_sucesso(&self.compilador(&dir, &["publicar"]));
+    }
+
+    /// Publica `formas` 1.0.0, que depende de `texto` com o requisito dado.
+    fn publicar_formas(&self, requisito_texto: &str) {
+        self.escrever(&[
+            (
+                "formas/projeto.toml",
+                &format!(
+                    "[projeto]\nnome = \"formas\"\nversao = \"1.0.0\"\ntipo = \"biblioteca\"\n\n[dependencias]\ntexto = \"{}\"\n",
+                    requisito_texto
+                ),
+            ),
+            (
+                "formas/src/rotulos.pr",
+                r#"usando Texto;
+
+espaco Formas {
+    publico estática classe Rotulos {
+        publico estática texto Quadrado(inteiro lado) {
+            retorne Formato.Caixa("quadrado " + lado);
+        }
+    }
+}
+"#,
+            ),
+        ]);
+        assert_sucesso(&self.compilador("formas", &["publicar"]));
+    }
+
+    /// Aplicação que usa `texto` (e `formas`, se pedido) com os requisitos dados.
+    fn aplicacao(&self, dependencias: &str, principal: &str) {
+        self.escrever(&[
+            (
+                "app/projeto.toml",
+                &format!(
+                    "[projeto]\nnome = \"app\"\nversao = \"0.1.0\"\nalvos = [\"bytecode\"]\n\n[dependencias]\n{}",
+                    dependencias
+                ),
+            ),
+            ("app/src/principal.pr", principal),
+        ]);
+    }
+
+    fn executar_app(&self) -> String {
+        let saida = Command::new(env!("CARGO_BIN_EXE_interpretador"))
+            .arg(self.raiz.join("app/dist/bytecode/app.pbc"))
+            .output()
+            .expect("falha ao executar interpretador");
+        String::from_utf8_lossy(&saida.stdout).into_owned()
+    }
+
+    fn lock(&self) -> String {
+        fs::read_to_string(self.raiz.join("app/projeto.lock")).expect("projeto.lock não gerado")
+    }
+}
+
+fn assert_sucesso(output: &Output) {
+    assert!(
+        output.status.success(),
+        "stdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+fn assert_erro(output: &Output, trecho: &str) {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "deveria falhar");
+    assert!(
+        stderr.contains(trecho),
+        "esperado '{}' em:\n{}",
+        trecho,
+        stderr
+    );
+}
+
+const USA_TEXTO: &str = r#"usando Texto;
+
+função Principal() {
+    imprima(Formato.Versao());
+}
+"#;
+
+#[test]
+fn resolve_dependencias_transitivas_do_registro() {
+    let area = Area::nova("transitivas");
+    for versao in ["1.0.0", "1.2.0", "2.0.0"] {
+        area.publicar_texto(versao);
+    }
+    area.publicar_formas("^1");
+    area.aplicacao(
+        "formas = \"1\"\ntexto = \">=1.1\"\n",
+        r#"usando Texto;
+usando Formas;
+
+função Principal() {
+    imprima(Formato.Versao());
+    imprima(Rotulos.Quadrado(3));
+}
+"#,
+    );
+
+    assert_sucesso(&area.compilador("app", &["construir"]));
+    // 2.0.0 satisfaz o app, mas não `formas`: fica a maior 1.x
+    assert_eq!(area.executar_app(), "1.2.0\n[quadrado 3]\n");
+    let lock = area.lock();
+    assert!(lock.contains("nome = \"texto\"\nversao = \"1.2.0\""), "{}", lock);
+    assert!(lock.contains("dependencias = [\"texto\"]"), "{}", lock);
+    assert!(area
+        .raiz
+        .join("cache/texto/1.2.0/texto.pbl")
+        .is_file());
+}
+
+#[test]
+fn lock_fixa_versoes_ate_atualizar() {
+    let area = Area::nova("lock");
+    area.publicar_texto("1.0.0");
+    area.aplicacao("texto = \"^1\"\n", USA_TEXTO);
+    assert_sucesso(&area.compilador("app", &["construir"]));
+    assert_eq!(area.executar_app(), "1.0.0\n");
+
+    area.publicar_texto("1.1.0");
+    assert_sucesso(&area.compilador("app", &["construir"]));
+    assert_eq!(area.executar_app(), "1.0.0\n");
+
+    assert_sucesso(&area.compilador("app", &["construir", "--atualizar"]));
+    assert_eq!(area.executar_app(), "1.1.0\n");
+    assert!(area.lock().contains("versao = \"1.1.0\""));
+
+    // Sem o registro, as versões já baixadas continuam disponíveis no cache
+    fs::remove_dir_all(area.registro()).unwrap();
+    assert_sucesso(&area.compilador("app", &["construir", "--atualizar"]));
+    assert_eq!(area.executar_app(), "1.1.0\n");
+}
+
+#[test]
+fn dependencia_por_caminho_e_compilada_junto() {
+    let area = Area::nova("caminho");
+    area.escrever(&[
+        (
+            "texto/projeto.toml",
+            "[projeto]\nnome = \"texto\"\nversao = \"0.3.0\"\ntipo = \"biblioteca\"\n",
+        ),
+        (
+            "texto/src/formato.pr",
+            "espaco Texto {\n    publico estática classe Formato {\n        publico estática texto Versao() {\n            retorne \"local\";\n        }\n    }\n}\n",
+        ),
+    ]);
+    area.aplicacao("texto = { caminho = \"../texto\" }\n", USA_TEXTO);
+    assert_sucesso(&area.compilador("app", &["construir"]));
+    assert_eq!(area.executar_app(), "local\n");
+    assert!(area.lock().contains("origem = \"caminho+../texto\""));
+
+    area.aplicacao("texto = { caminho = \"../texto\", versao = \"^1\" }\n", USA_TEXTO);
+    assert_erro(
+        &area.compilador("app", &["construir"]),
+        "está na versão 0.3.0, mas app 0.1.0 pede '^1'",
+    );
+}
+
+#[test]
+fn conflitos_e_pacotes_ausentes_sao_explicados() {
+    let area = Area::nova("conflitos");
+    area.publicar_texto("1.0.0");
+    area.publicar_texto("2.0.0");
+    area.publicar_formas("^1");
+
+    area.aplicacao("formas = \"1\"\ntexto = \"^2\"\n", USA_TEXTO);
+    let output = area.compilador("app", &["construir"]);
+    assert_erro(&output, "nenhuma versão de 'texto' satisfaz");
+    assert_erro(&output, "'^2' (de app 0.1.0)");
+    assert_erro(&output, "'^1' (de formas 1.0.0)");
+    assert_erro(&output, "disponíveis: 2.0.0, 1.0.0");
+
+    area.aplicacao("graficos = \"1\"\n", USA_TEXTO);
+    assert_erro(
+        &area.compilador("app", &["construir"]),
+        "pacote 'graficos' não encontrado no registro",
+    );
+
+    // Uma versão publicada não é sobrescrita
+    assert_erro(&area.compilador("texto-2.0.0", &["publicar"]), "já está publicado");
+}
+
+#[test]
+fn publicar_exige_biblioteca_sem_dependencias_por_caminho() {
+    let area = Area::nova("publicar");
+    area.aplicacao("", USA_TEXTO);
+    assert_erro(
+        &area.compilador("app", &["publicar"]),
+        "só bibliotecas podem ser publicadas",
+    );
+}
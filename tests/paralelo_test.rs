@@ -0,0 +1,227 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Projeto com vários arquivos, namespaces e classes, código global com laço e uma função
+/// assíncrona chamada de dois namespaces (o trampolim do LLVM precisa sair uma vez só).
+const ARQUIVOS: &[(&str, &str)] = &[
+    (
+        "projeto.toml",
+        "[projeto]\nnome = \"app\"\nversao = \"0.1.0\"\nalvos = [\"bytecode\", \"llvm-ir\"]\n",
+    ),
+    (
+        "src/numeros.pr",
+        r#"espaco Numeros {
+    publico assíncrona função inteiro Dobrar(inteiro valor) {
+        retorne valor * 2;
+    }
+
+    publico função inteiro Somar(inteiro[] valores) {
+        inteiro total = 0;
+        inteiro i = 0;
+        enquanto (i < valores.tamanho) {
+            total = total + valores[i];
+            i = i + 1;
+        }
+        retorne total;
+    }
+}
+"#,
+    ),
+    (
+        "src/formas.pr",
+        r#"usando Numeros;
+
+espaco Formas {
+    publico classe Forma {
+        publico texto Nome { obter; definir; }
+        publico Forma(texto nome) { este.Nome = nome; }
+        publico redefinível inteiro Area() { retorne 0; }
+        publico texto Descrever() { retorne este.Nome + ": " + este.Area(); }
+    }
+
+    publico classe Retangulo : Forma {
+        publico inteiro Largura { obter; definir; }
+        publico inteiro Altura { obter; definir; }
+        publico Retangulo(inteiro largura, inteiro altura) : base("retângulo") {
+            este.Largura = largura;
+            este.Altura = altura;
+        }
+        publico sobrescreve inteiro Area() { retorne este.Largura * este.Altura; }
+    }
+
+    publico classe Quadrado : Retangulo {
+        publico Quadrado(inteiro lado) : base(lado, lado) { este.Nome = "quadrado"; }
+    }
+
+    publico função inteiro AreaDobrada(Forma forma) {
+        inteiro dobro = aguarde Dobrar(forma.Area());
+        retorne dobro;
+    }
+}
+"#,
+    ),
+    (
+        "src/principal.pr",
+        r#"usando Numeros;
+usando Formas;
+
+inteiro contador = 0;
+enquanto (contador < 3) {
+    contador = contador + 1;
+}
+imprima(contador);
+
+função vazio Principal() {
+    Forma retangulo = novo Retangulo(2, 3);
+    Forma quadrado = novo Quadrado(4);
+    imprima(retangulo.Descrever());
+    imprima(AreaDobrada(retangulo));
+    imprima(quadrado.Descrever());
+    imprima(AreaDobrada(quadrado));
+    imprima(Somar([1, aguarde Dobrar(5)]));
+}
+"#,
+    ),
+];
+
+/// Código global com saltos espalhado entre definições, em vários arquivos e namespaces.
+const CODIGO_GLOBAL: &[(&str, &str)] = &[
+    (
+        "projeto.toml",
+        "[projeto]\nnome = \"app\"\nversao = \"0.1.0\"\nalvos = [\"bytecode\"]\n",
+    ),
+    (
+        "src/a.pr",
+        r#"função inteiro Um() { retorne 1; }
+
+inteiro a = Um();
+se (a == 1) { imprima("a: sim"); } senão { imprima("a: não"); }
+
+espaco Contas {
+    publico função inteiro Dobro(inteiro x) { retorne x * 2; }
+}
+
+inteiro i = 0;
+enquanto (i < 3) {
+    se (i == 1) { imprima("i: 1"); }
+    i = i + 1;
+}
+"#,
+    ),
+    (
+        "src/principal.pr",
+        r#"usando Contas;
+
+classe Ponto {
+    publico inteiro X { obter; definir; }
+    publico Ponto(inteiro x) { este.X = x; }
+}
+
+inteiro b = Dobro(2);
+enquanto (b > 0) {
+    b = b - 3;
+}
+se (b < 0) { imprima("b: negativo"); }
+
+função vazio Principal() {
+    Ponto p = novo Ponto(7);
+    se (p.X > 5) { imprima("p: grande"); } senão { imprima("p: pequeno"); }
+}
+"#,
+    ),
+];
+
+fn area(nome: &str, arquivos: &[(&str, &str)]) -> PathBuf {
+    let raiz = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("build")
+        .join("paralelo")
+        .join(nome);
+    let _ = fs::remove_dir_all(&raiz);
+    for (caminho, conteudo) in arquivos {
+        let caminho = raiz.join(caminho);
+        fs::create_dir_all(caminho.parent().unwrap()).unwrap();
+        fs::write(caminho, conteudo).unwrap();
+    }
+    raiz
+}
+
+fn have_clang() -> bool {
+    Command::new("clang").arg("--version").output().is_ok()
+}
+
+/// Constrói do zero (sem o cache incremental) com `threads` threads e devolve o `.pbc` e o `.ll`.
+/// O `.ll` é gravado antes de chamar o clang, então sem ele só a construção falha.
+fn construir(raiz: &Path, threads: &str) -> (String, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(raiz)
+        .args(["construir", "--sem-incremental"])
+        .env("PORDOSOL_THREADS", threads)
+        .output()
+        .expect("falha ao executar compilador");
+    if have_clang() {
+        assert_sucesso(&output);
+    }
+    let ler = |relativo: &str| fs::read_to_string(raiz.join(relativo)).unwrap();
+    (ler("dist/bytecode/app.pbc"), ler("dist/llvm-ir/app.ll"))
+}
+
+fn executar(pbc: &Path) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_interpretador"))
+        .arg(pbc)
+        .output()
+        .expect("falha ao executar interpretador");
+    assert_sucesso(&output);
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn assert_sucesso(output: &Output) {
+    assert!(
+        output.status.success(),
+        "stdout:\n{}\nstderr:\n{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn artefatos_nao_dependem_do_numero_de_threads() {
+    let raiz = area("threads", ARQUIVOS);
+    let sequencial = construir(&raiz, "1");
+    for threads in ["2", "8", "8"] {
+        let paralelo = construir(&raiz, threads);
+        assert!(paralelo.0 == sequencial.0, "bytecode difere com {} threads", threads);
+        assert!(paralelo.1 == sequencial.1, "LLVM IR difere com {} threads", threads);
+    }
+    assert_eq!(
+        sequencial.1.matches("define internal i8* @\".async.wrapper.Numeros_Dobrar\"").count(),
+        1
+    );
+
+    assert_eq!(
+        executar(&raiz.join("dist/bytecode/app.pbc")),
+        "3\nretângulo: 6\n12\nquadrado: 16\n32\n11\n"
+    );
+}
+
+#[test]
+fn codigo_global_executa_igual_com_qualquer_numero_de_threads() {
+    let raiz = area("codigo_global", CODIGO_GLOBAL);
+    let pbc = raiz.join("dist/bytecode/app.pbc");
+    let mut saidas = Vec::new();
+    for threads in ["1", "2", "8"] {
+        let output = Command::new(env!("CARGO_BIN_EXE_compilador"))
+            .current_dir(&raiz)
+            .args(["construir", "--sem-incremental"])
+            .env("PORDOSOL_THREADS", threads)
+            .output()
+            .expect("falha ao executar compilador");
+        assert_sucesso(&output);
+        saidas.push((fs::read_to_string(&pbc).unwrap(), executar(&pbc)));
+    }
+    assert_eq!(saidas[0].1, "a: sim\ni: 1\nb: negativo\np: grande\n");
+    for (threads, saida) in ["2", "8"].iter().zip(&saidas[1..]) {
+        assert!(saida.0 == saidas[0].0, "bytecode difere com {} threads", threads);
+        assert_eq!(saida.1, saidas[0].1, "saída difere com {} threads", threads);
+    }
+}
@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+/// Compila o exemplo para `build/<nome>.pbc` com as opções extras e devolve o caminho.
+fn compilar_exemplo(nome: &str, extra: &[&str]) -> PathBuf {
+    let root = repo_root();
+    let exemplo = root.join("exemplos").join("teste_de_mesa.pr");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(&root)
+        .arg(&exemplo)
+        .args(["--target=bytecode", &format!("--nome={}", nome)])
+        .args(extra)
+        .output()
+        .expect("falha ao executar compilador");
+    assert!(
+        output.status.success(),
+        "compilador falhou: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    root.join("build").join(format!("{}.pbc", nome))
+}
+
+#[test]
+fn teste_de_mesa_exporta_csv_filtrado() {
+    let root = repo_root();
+    let pbc = compilar_exemplo("teste_de_mesa", &["--teste-de-mesa"]);
+    let out = Command::new(env!("CARGO_BIN_EXE_interpretador"))
+        .current_dir(&root)
+        .arg(&pbc)
+        .args([
+            "--teste-de-mesa-funcao=Principal",
+            "--teste-de-mesa-limite=4",
+            "--teste-de-mesa-formato=csv",
+        ])
+        .output()
+        .expect("falha ao executar interpretador");
+    assert!(out.status.success(), "execucao retornou erro");
+
+    let texto = String::from_utf8_lossy(&out.stdout).replace("\r\n", "\n");
+    assert!(texto.contains("total: 6"));
+    let tabela = texto
+        .split("=== Teste de mesa ===\n")
+        .nth(1)
+        .expect("tabela do teste de mesa ausente");
+    assert_eq!(
+        tabela,
+        "passo,função,linha,x,total\n\
+         1,Principal,9,1,-\n\
+         2,Principal,10,1,0\n\
+         3,Principal,11,1,0\n\
+         4,Principal,12,1,1\n"
+    );
+}
+
+#[test]
+fn enquanto_registra_cada_avaliacao_da_condicao() {
+    let root = repo_root();
+    let pbc = compilar_exemplo("teste_de_mesa_laco", &["--teste-de-mesa"]);
+    let codigo = std::fs::read_to_string(&pbc).unwrap();
+    assert!(
+        codigo.lines().any(|l| l.starts_with("LINE 11 ") && l.ends_with("teste_de_mesa.pr")),
+        "LINE sem o arquivo:\n{}",
+        codigo
+    );
+
+    let out = Command::new(env!("CARGO_BIN_EXE_interpretador"))
+        .current_dir(&root)
+        .arg(&pbc)
+        .args(["--teste-de-mesa-funcao=Principal", "--teste-de-mesa-formato=csv"])
+        .output()
+        .expect("falha ao executar interpretador");
+    assert!(out.status.success(), "execucao retornou erro");
+    let texto = String::from_utf8_lossy(&out.stdout).replace("\r\n", "\n");
+    let linhas: Vec<&str> = texto
+        .split("=== Teste de mesa ===\n")
+        .nth(1)
+        .expect("tabela do teste de mesa ausente")
+        .lines()
+        .skip(1)
+        .map(|l| l.split(',').nth(2).unwrap())
+        .collect();
+    // Três voltas com a condição verdadeira e a avaliação final, falsa
+    assert_eq!(
+        linhas,
+        ["9", "10", "11", "12", "13", "11", "12", "13", "11", "12", "13", "11", "15"]
+    );
+}
+
+#[test]
+fn bytecode_sem_teste_de_mesa_nao_tem_line() {
+    let pbc = compilar_exemplo("teste_de_mesa_sem_linhas", &[]);
+    let codigo = std::fs::read_to_string(&pbc).unwrap();
+    assert!(!codigo.lines().any(|l| l.starts_with("LINE")), "{}", codigo);
+}
@@ -0,0 +1,82 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+#[test]
+fn compilador_testar_reporta_falhas_e_junit() {
+    let root = repo_root();
+    let junit = root.join("build").join("testes").join("calculadora.junit.xml");
+    let _ = fs::remove_file(&junit);
+
+    let out = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(&root)
+        .args([
+            "testar",
+            "tests/fixtures/testes",
+            &format!("--junit={}", junit.display()),
+        ])
+        .output()
+        .expect("falha ao executar compilador testar");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+
+    // Um teste falha de propósito: o comando deve sinalizar erro para o CI
+    assert_eq!(out.status.code(), Some(1), "stdout:\n{}", stdout);
+    assert!(stdout.contains("teste TesteSoma ... ok"));
+    assert!(stdout.contains("teste TesteDivisaoPorZero ... ok"));
+    assert!(stdout.contains("teste TesteSomaErrada ... FALHOU"));
+    assert!(stdout.contains("esperado: 5\n  obtido:   4"));
+    assert!(stdout.contains("2 aprovado(s); 1 falha(s); 3 teste(s)"));
+
+    let xml = fs::read_to_string(&junit).expect("relatório JUnit não gerado");
+    assert!(xml.contains(r#"tests="3" failures="1""#));
+    assert!(xml.contains(r#"<testcase name="TesteSomaErrada" classname="calculadora""#));
+
+    // --filtro restringe a execução
+    let out = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(&root)
+        .args(["testar", "tests/fixtures/testes", "--filtro=TesteSoma"])
+        .output()
+        .expect("falha ao executar compilador testar");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("1 aprovado(s); 1 falha(s); 2 teste(s)"), "stdout:\n{}", stdout);
+}
+
+#[test]
+fn compilador_testar_executa_metodos_de_instancia_e_estaticos() {
+    let out = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(repo_root())
+        .args(["testar", "tests/fixtures/testes_metodos"])
+        .output()
+        .expect("falha ao executar compilador testar");
+    let stdout = String::from_utf8_lossy(&out.stdout);
+
+    assert_eq!(out.status.code(), Some(1), "stdout:\n{}", stdout);
+    // Cada teste de instância recebe um objeto novo, criado com o construtor sem argumentos
+    assert!(stdout.contains("teste Contador.TesteInstancia ... ok"), "stdout:\n{}", stdout);
+    assert!(stdout.contains("teste Contador.TesteEstatico ... ok"), "stdout:\n{}", stdout);
+    // Afirmar.Lanca aceita "Classe.Metodo", estático ou de instância
+    assert!(stdout.contains("teste Contador.TesteLancaEmMetodo ... ok"), "stdout:\n{}", stdout);
+    assert!(stdout.contains("teste Contador.TesteLancaInexistente ... FALHOU"));
+    assert!(stdout.contains(
+        r#"Afirmar.Lanca: "Contador.Inexistente" não é uma função nem um método no formato "Classe.Metodo""#
+    ));
+    assert!(stdout.contains("3 aprovado(s); 1 falha(s); 4 teste(s)"), "stdout:\n{}", stdout);
+}
+
+#[test]
+fn compilador_testar_recusa_opcoes_desconhecidas() {
+    let out = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(repo_root())
+        .args(["testar", "tests/fixtures/testes", "--fitro=TesteSoma"])
+        .output()
+        .expect("falha ao executar compilador testar");
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(!out.status.success());
+    assert!(stderr.contains("opção desconhecida '--fitro=TesteSoma'"), "stderr:\n{}", stderr);
+    // Nada é compilado nem executado
+    assert!(!String::from_utf8_lossy(&out.stdout).contains("executando"));
+}
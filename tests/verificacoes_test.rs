@@ -0,0 +1,147 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn have_clang() -> bool {
+    Command::new("clang").arg("--version").output().is_ok()
+}
+
+fn fixture(nome: &str) -> PathBuf {
+    repo_root()
+        .join("tests")
+        .join("fixtures")
+        .join("verificacoes")
+        .join(format!("{}.pr", nome))
+}
+
+/// Compila o fixture para LLVM em `build/verificacoes/<subdir>` (o backend grava no diretório
+/// atual) e devolve o diretório e a saída do compilador.
+fn compilar(nome: &str, subdir: &str, extras: &[&str]) -> (PathBuf, Output) {
+    let dir = repo_root().join("build").join("verificacoes").join(subdir);
+    fs::create_dir_all(&dir).unwrap();
+    let _ = fs::remove_file(dir.join(format!("{}.ll", nome)));
+    let output = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(&dir)
+        .arg(fixture(nome))
+        .arg("--target=llvm-ir")
+        .args(extras)
+        .output()
+        .expect("falha ao executar compilador");
+    (dir, output)
+}
+
+fn ler_ll(dir: &Path, nome: &str) -> String {
+    fs::read_to_string(dir.join(format!("{}.ll", nome)))
+        .unwrap_or_else(|_| panic!("{}.ll não gerado", nome))
+}
+
+#[test]
+fn ir_verifica_indice_nulo_e_divisao_com_posicao() {
+    let casos = [
+        ("indice", "Índice fora do intervalo", 6),
+        ("nulo", "Acesso a 'Saldo' em referência nula", 12),
+        ("divisao", "Divisão por zero", 3),
+        ("estouro", "Estouro na divisão inteira", 3),
+    ];
+    for (nome, mensagem, linha) in casos {
+        let (dir, _) = compilar(nome, "com", &[]);
+        let ir = ler_ll(&dir, nome);
+        assert!(
+            ir.contains("declare void @pds_panico(i8*, i8*, i32, i32) noreturn"),
+            "{}: pds_panico não declarado",
+            nome
+        );
+        // Mensagem em UTF-8 escapado e arquivo do fonte como constantes
+        let escapada: String = mensagem
+            .bytes()
+            .map(|b| match b {
+                b' ' | b'!'..=b'~' if b != b'"' && b != b'\\' => (b as char).to_string(),
+                _ => format!("\\{:02X}", b),
+            })
+            .collect();
+        assert!(ir.contains(&escapada), "{}: mensagem ausente\n{}", nome, ir);
+        assert!(ir.contains(&format!("{}.pr", nome)));
+        assert!(
+            ir.contains(&format!("i32 {}, i32 5)\n  unreachable", linha)),
+            "{}: posição {}:5 ausente\n{}",
+            nome,
+            linha,
+            ir
+        );
+    }
+}
+
+#[test]
+fn sem_verificacoes_nao_emite_checagens() {
+    for nome in ["indice", "nulo", "divisao", "estouro"] {
+        let (dir, _) = compilar(nome, "sem", &["--sem-verificacoes"]);
+        let ir = ler_ll(&dir, nome);
+        assert!(!ir.contains("call void @pds_panico"), "{}:\n{}", nome, ir);
+        assert!(!ir.contains("chk."), "{}:\n{}", nome, ir);
+    }
+}
+
+#[test]
+fn este_nao_e_verificado() {
+    let (dir, _) = compilar("nulo", "este", &[]);
+    let ir = ler_ll(&dir, "nulo");
+    // O construtor acessa `Saldo` em `este`; só o acesso em `Principal` é verificado
+    assert_eq!(
+        ir.matches("call void @pds_panico").count(),
+        1,
+        "IR:\n{}",
+        ir
+    );
+}
+
+#[test]
+fn executavel_termina_com_erro_e_posicao() {
+    if !have_clang() {
+        eprintln!("clang não encontrado; ignorando teste LLVM.");
+        return;
+    }
+    let casos = [
+        ("indice", "antes\n", "Índice fora do intervalo", 6),
+        ("nulo", "", "Acesso a 'Saldo' em referência nula", 12),
+        ("divisao", "5\n", "Divisão por zero", 3),
+        ("estouro", "-1073741824\n", "Estouro na divisão inteira", 3),
+    ];
+    for (nome, stdout, mensagem, linha) in casos {
+        let (dir, output) = compilar(nome, "executavel", &[]);
+        assert!(
+            output.status.success(),
+            "compilador falhou:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let exe = if cfg!(windows) {
+            dir.join(format!("{}.exe", nome))
+        } else {
+            dir.join(nome)
+        };
+        let execucao = Command::new(&exe)
+            .output()
+            .expect("falha ao executar programa");
+        assert_eq!(execucao.status.code(), Some(1), "{}", nome);
+        assert_eq!(
+            String::from_utf8_lossy(&execucao.stdout).replace("\r\n", "\n"),
+            stdout
+        );
+        let stderr = String::from_utf8_lossy(&execucao.stderr);
+        assert!(
+            stderr.contains(&format!("Erro em tempo de execução: {} (em ", mensagem)),
+            "{}: stderr:\n{}",
+            nome,
+            stderr
+        );
+        assert!(
+            stderr.contains(&format!("{}.pr:{}:5)", nome, linha)),
+            "{}: stderr:\n{}",
+            nome,
+            stderr
+        );
+    }
+}
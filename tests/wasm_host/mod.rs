@@ -0,0 +1,152 @@
+// Hospedeiro dos módulos do alvo `--target=wasm` para os testes: implementa as funções
+// importadas do módulo "pds" (como `src/runtime/pds_wasm.js`) sobre o interpretador wasmi,
+// que também valida o módulo, e executa `_start` sem depender de ferramentas externas.
+use wasmi::{Caller, Engine, Extern, Linker, Module, Store};
+
+/// Resultado de uma execução: stdout, stderr e código de saída.
+pub struct ExecucaoWasm {
+    pub stdout: String,
+    // O teste diferencial compara só stdout e o código de saída
+    #[allow(dead_code)]
+    pub stderr: String,
+    pub codigo: i32,
+}
+
+struct Estado {
+    stdout: Vec<u8>,
+    stderr: String,
+    linhas: Vec<Vec<u8>>,
+    proxima: usize,
+}
+
+fn memoria(caller: &Caller<'_, Estado>) -> wasmi::Memory {
+    caller
+        .get_export("memory")
+        .and_then(Extern::into_memory)
+        .expect("módulo sem memória exportada")
+}
+
+fn ler(caller: &Caller<'_, Estado>, ptr: i32, len: i32) -> Vec<u8> {
+    let inicio = ptr as usize;
+    memoria(caller).data(caller)[inicio..inicio + len as usize].to_vec()
+}
+
+fn gravar(caller: &mut Caller<'_, Estado>, ptr: i32, bytes: &[u8]) {
+    memoria(caller)
+        .write(caller, ptr as usize, bytes)
+        .expect("escrita fora da memória");
+}
+
+fn erro(e: impl std::fmt::Display) -> String {
+    e.to_string()
+}
+
+/// Valida o módulo (`Module::new`) e o executa com `entrada` no console. `Err` se o módulo for
+/// inválido ou terminar com uma trap que não seja um erro em tempo de execução da linguagem.
+pub fn executar(wasm: &[u8], entrada: &str) -> Result<ExecucaoWasm, String> {
+    let engine = Engine::default();
+    let module = Module::new(&engine, wasm).map_err(|e| format!("módulo inválido: {}", e))?;
+    let estado = Estado {
+        stdout: Vec::new(),
+        stderr: String::new(),
+        linhas: entrada.lines().map(|l| l.as_bytes().to_vec()).collect(),
+        proxima: 0,
+    };
+    let mut store = Store::new(&engine, estado);
+    let mut linker = Linker::<Estado>::new(&engine);
+
+    linker
+        .func_wrap(
+            "pds",
+            "escrever",
+            |mut caller: Caller<'_, Estado>, ptr: i32, len: i32| {
+                let bytes = ler(&caller, ptr, len);
+                caller.data_mut().stdout.extend_from_slice(&bytes);
+            },
+        )
+        .map_err(erro)?;
+    linker
+        .func_wrap("pds", "ler_linha", |caller: Caller<'_, Estado>| -> i32 {
+            let estado = caller.data();
+            estado
+                .linhas
+                .get(estado.proxima)
+                .map_or(-1, |l| l.len() as i32)
+        })
+        .map_err(erro)?;
+    linker
+        .func_wrap(
+            "pds",
+            "copiar_linha",
+            |mut caller: Caller<'_, Estado>, ptr: i32| {
+                let estado = caller.data_mut();
+                let linha = estado.linhas[estado.proxima].clone();
+                estado.proxima += 1;
+                gravar(&mut caller, ptr, &linha);
+            },
+        )
+        .map_err(erro)?;
+    linker
+        .func_wrap(
+            "pds",
+            "formatar_duplo",
+            |mut caller: Caller<'_, Estado>, valor: f64, ptr: i32| -> i32 {
+                let texto = format!("{:.6}", valor);
+                gravar(&mut caller, ptr, texto.as_bytes());
+                texto.len() as i32
+            },
+        )
+        .map_err(erro)?;
+    linker
+        .func_wrap("pds", "resto", |a: f64, b: f64| -> f64 { a % b })
+        .map_err(erro)?;
+    linker
+        .func_wrap(
+            "pds",
+            "panico",
+            |mut caller: Caller<'_, Estado>,
+             msg: i32,
+             msg_len: i32,
+             arquivo: i32,
+             arquivo_len: i32,
+             linha: i32,
+             coluna: i32|
+             -> Result<(), wasmi::Error> {
+                let mensagem = String::from_utf8_lossy(&ler(&caller, msg, msg_len)).into_owned();
+                let mut texto = format!("Erro em tempo de execução: {}", mensagem);
+                if arquivo_len > 0 {
+                    let arquivo = ler(&caller, arquivo, arquivo_len);
+                    texto.push_str(&format!(
+                        " (em {}:{}:{})",
+                        String::from_utf8_lossy(&arquivo),
+                        linha,
+                        coluna
+                    ));
+                }
+                caller.data_mut().stderr.push_str(&texto);
+                caller.data_mut().stderr.push('\n');
+                Err(wasmi::Error::i32_exit(1))
+            },
+        )
+        .map_err(erro)?;
+
+    let instancia = linker
+        .instantiate(&mut store, &module)
+        .and_then(|pre| pre.start(&mut store))
+        .map_err(erro)?;
+    let inicio = instancia
+        .get_typed_func::<(), ()>(&store, "_start")
+        .map_err(erro)?;
+    let codigo = match inicio.call(&mut store, ()) {
+        Ok(()) => 0,
+        Err(e) => e
+            .i32_exit_status()
+            .ok_or_else(|| format!("trap durante a execução: {}", e))?,
+    };
+    let estado = store.into_data();
+    Ok(ExecucaoWasm {
+        stdout: String::from_utf8_lossy(&estado.stdout).into_owned(),
+        stderr: estado.stderr,
+        codigo,
+    })
+}
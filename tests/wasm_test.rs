@@ -0,0 +1,145 @@
+mod wasm_host;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+fn repo_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn fixture(nome: &str) -> PathBuf {
+    repo_root()
+        .join("tests")
+        .join("fixtures")
+        .join("wasm")
+        .join(format!("{}.pr", nome))
+}
+
+/// Compila o fixture para WebAssembly em `build/wasm/<subdir>` (o alvo grava o .wat e o .wasm
+/// no diretório atual) e devolve o diretório e a saída do compilador.
+fn compilar(nome: &str, subdir: &str, extras: &[&str]) -> (PathBuf, Output) {
+    let dir = repo_root().join("build").join("wasm").join(subdir);
+    fs::create_dir_all(&dir).unwrap();
+    let _ = fs::remove_file(dir.join(format!("{}.wat", nome)));
+    let _ = fs::remove_file(dir.join(format!("{}.wasm", nome)));
+    let output = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .current_dir(&dir)
+        .arg(fixture(nome))
+        .arg("--target=wasm")
+        .args(extras)
+        .output()
+        .expect("falha ao executar compilador");
+    assert!(
+        output.status.success(),
+        "compilador falhou:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    (dir, output)
+}
+
+fn ler_wat(dir: &Path, nome: &str) -> String {
+    fs::read_to_string(dir.join(format!("{}.wat", nome)))
+        .unwrap_or_else(|_| panic!("{}.wat não gerado", nome))
+}
+
+fn ler_wasm(dir: &Path, nome: &str) -> Vec<u8> {
+    fs::read(dir.join(format!("{}.wasm", nome)))
+        .unwrap_or_else(|_| panic!("{}.wasm não gerado", nome))
+}
+
+fn interpretar(nome: &str, entrada: &str) -> Output {
+    use std::io::Write;
+    use std::process::Stdio;
+    let mut filho = Command::new(env!("CARGO_BIN_EXE_compilador"))
+        .arg(fixture(nome))
+        .arg("--executar")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("falha ao executar compilador");
+    filho
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(entrada.as_bytes())
+        .unwrap();
+    filho.wait_with_output().unwrap()
+}
+
+#[test]
+fn wat_gerado_tem_importacoes_tabela_e_despacho() {
+    let (dir, _) = compilar("recursos", "codigo", &[]);
+    let wat = ler_wat(&dir, "recursos");
+
+    // Console e erros passam por funções importadas do hospedeiro
+    assert!(wat.contains("(import \"pds\" \"escrever\" (func $pds_host_escrever (param i32 i32)))"));
+    assert!(wat.contains("(import \"pds\" \"ler_linha\""));
+    assert!(wat.contains("(memory (export \"memory\") "));
+    assert!(wat.contains("(func $pds_iniciar (export \"_start\")"));
+    // Métodos virtuais ficam na tabela de funções e são chamados pela vtable do descritor
+    assert!(
+        wat.contains("$FormaBase.Nome $Quadrado.Area $Quadrado.Nome $Circulo.Area)"),
+        "WAT:\n{}",
+        wat
+    );
+    assert!(wat.contains("call_indirect $pds_funcoes (type $pds_tipo."));
+    // Chamadas em interfaces usam a função de despacho pelo nome do método
+    assert!(wat.contains("(func $pds_despachar.Nome.0 (param $objeto i32) (result i32)"));
+    assert!(wat.contains("call $pds_despachar.Nome.0"));
+    // Estáticos são globais; construtores viram #novo e #construir
+    assert!(wat.contains("(global $Contador.Total (mut i64) (i64.const 0))"));
+    assert!(wat.contains("(func $Quadrado#novo (param $lado f64) (result i32)"));
+    assert!(wat.contains("(func $Quadrado#construir (param $este i32) (param $lado f64)"));
+}
+
+#[test]
+fn modulo_wasm_reproduz_o_interpretador() {
+    let entrada = "Bia\n";
+    let (dir, _) = compilar("recursos", "executavel", &[]);
+    let execucao = wasm_host::executar(&ler_wasm(&dir, "recursos"), entrada)
+        .unwrap_or_else(|e| panic!("{}", e));
+    assert_eq!(execucao.codigo, 0, "stderr:\n{}", execucao.stderr);
+
+    let referencia = interpretar("recursos", entrada);
+    assert!(referencia.status.success());
+    assert_eq!(
+        execucao.stdout,
+        String::from_utf8_lossy(&referencia.stdout).replace("\r\n", "\n")
+    );
+    assert!(execucao.stdout.ends_with("Nome:\nOlá, Bia\n"));
+}
+
+#[test]
+fn wasm_binario_corresponde_ao_texto() {
+    let (dir, _) = compilar("recursos", "montagem", &[]);
+    let montado = compilador_portugues::codegen::wasm::montar(&ler_wat(&dir, "recursos"))
+        .expect("WAT inválido");
+    assert_eq!(montado, ler_wasm(&dir, "recursos"));
+    assert!(montado.starts_with(b"\0asm"));
+}
+
+#[test]
+fn verificacoes_de_execucao_podem_ser_omitidas() {
+    let (dir, _) = compilar("indice", "sem", &["--sem-verificacoes"]);
+    let wat = ler_wat(&dir, "indice");
+    assert!(!wat.contains("call $pds_endereco\n"), "WAT:\n{}", wat);
+
+    let (dir, _) = compilar("indice", "com", &[]);
+    let wat = ler_wat(&dir, "indice");
+    assert!(wat.contains("call $pds_endereco\n"), "WAT:\n{}", wat);
+
+    let execucao =
+        wasm_host::executar(&ler_wasm(&dir, "indice"), "").unwrap_or_else(|e| panic!("{}", e));
+    assert_eq!(execucao.codigo, 1);
+    assert_eq!(execucao.stdout, "20\n");
+    assert!(
+        execucao
+            .stderr
+            .starts_with("Erro em tempo de execução: Índice fora do intervalo (em ")
+            && execucao.stderr.contains("indice.pr:4:"),
+        "stderr:\n{}",
+        execucao.stderr
+    );
+}